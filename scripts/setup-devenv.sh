@@ -297,6 +297,13 @@ p11tool --login --write \
   --provider="${HSM_LIBRARY_PATH}" \
   "$(p11tool --list-token-urls --provider="${HSM_LIBRARY_PATH}" | grep "SoftHSM")"
 
+p11tool --login --write \
+  --secret-key="$(openssl rand 32 | od -A n -v -t x1 | tr -d ' \n')" \
+  --set-pin "${HSM_USER_PIN}" \
+  --label="instruction_audit_log_key" \
+  --provider="${HSM_LIBRARY_PATH}" \
+  "$(p11tool --list-token-urls --provider="${HSM_LIBRARY_PATH}" | grep "SoftHSM")"
+
 
 ########################################################################
 # Configure configuration-server
@@ -14,6 +14,23 @@ typedef struct wire_uint_8_list {
   int32_t len;
 } wire_uint_8_list;
 
+typedef struct wire_SelfAssertedAttribute {
+  struct wire_uint_8_list *doc_type;
+  struct wire_uint_8_list *namespace;
+  struct wire_uint_8_list *key;
+  struct wire_uint_8_list *value;
+} wire_SelfAssertedAttribute;
+
+typedef struct wire_list_self_asserted_attribute {
+  struct wire_SelfAssertedAttribute *ptr;
+  int32_t len;
+} wire_list_self_asserted_attribute;
+
+typedef struct wire_uint_32_list {
+  uint32_t *ptr;
+  int32_t len;
+} wire_uint_32_list;
+
 typedef struct DartCObject *WireSyncReturn;
 
 void store_dart_post_cobject(DartPostCObjectFnType ptr);
@@ -70,6 +87,11 @@ void wire_has_active_pid_issuance_session(int64_t port_);
 
 void wire_start_disclosure(int64_t port_, struct wire_uint_8_list *uri, bool is_qr_code);
 
+void wire_select_disclosure_candidates(int64_t port_, struct wire_uint_32_list *selection);
+
+void wire_set_disclosure_self_asserted_attributes(int64_t port_,
+                                                  struct wire_list_self_asserted_attribute *attributes);
+
 void wire_cancel_disclosure(int64_t port_);
 
 void wire_accept_disclosure(int64_t port_, struct wire_uint_8_list *pin);
@@ -80,8 +102,20 @@ void wire_get_history(int64_t port_);
 
 void wire_get_history_for_card(int64_t port_, struct wire_uint_8_list *doc_type);
 
+void wire_start_device_link(int64_t port_, struct wire_uint_8_list *pin);
+
+void wire_link_device(int64_t port_, struct wire_uint_8_list *pin, struct wire_uint_8_list *link_code);
+
+void wire_get_linked_devices(int64_t port_, struct wire_uint_8_list *pin);
+
+void wire_remove_linked_device(int64_t port_, struct wire_uint_8_list *pin, struct wire_uint_8_list *device_id);
+
 void wire_reset_wallet(int64_t port_);
 
+struct wire_list_self_asserted_attribute *new_list_self_asserted_attribute_0(int32_t len);
+
+struct wire_uint_32_list *new_uint_32_list_0(int32_t len);
+
 struct wire_uint_8_list *new_uint_8_list_0(int32_t len);
 
 void free_WireSyncReturn(WireSyncReturn ptr);
@@ -110,12 +144,20 @@ static int64_t dummy_method_to_enforce_bundling(void) {
     dummy_var ^= ((int64_t) (void*) wire_accept_pid_issuance);
     dummy_var ^= ((int64_t) (void*) wire_has_active_pid_issuance_session);
     dummy_var ^= ((int64_t) (void*) wire_start_disclosure);
+    dummy_var ^= ((int64_t) (void*) wire_select_disclosure_candidates);
+    dummy_var ^= ((int64_t) (void*) wire_set_disclosure_self_asserted_attributes);
     dummy_var ^= ((int64_t) (void*) wire_cancel_disclosure);
     dummy_var ^= ((int64_t) (void*) wire_accept_disclosure);
     dummy_var ^= ((int64_t) (void*) wire_has_active_disclosure_session);
     dummy_var ^= ((int64_t) (void*) wire_get_history);
     dummy_var ^= ((int64_t) (void*) wire_get_history_for_card);
+    dummy_var ^= ((int64_t) (void*) wire_start_device_link);
+    dummy_var ^= ((int64_t) (void*) wire_link_device);
+    dummy_var ^= ((int64_t) (void*) wire_get_linked_devices);
+    dummy_var ^= ((int64_t) (void*) wire_remove_linked_device);
     dummy_var ^= ((int64_t) (void*) wire_reset_wallet);
+    dummy_var ^= ((int64_t) (void*) new_list_self_asserted_attribute_0);
+    dummy_var ^= ((int64_t) (void*) new_uint_32_list_0);
     dummy_var ^= ((int64_t) (void*) new_uint_8_list_0);
     dummy_var ^= ((int64_t) (void*) free_WireSyncReturn);
     dummy_var ^= ((int64_t) (void*) store_dart_post_cobject);
@@ -1,3 +1,5 @@
+import 'dart:typed_data';
+
 import 'package:equatable/equatable.dart';
 
 import 'value/gender.dart';
@@ -43,3 +45,45 @@ class GenderValue extends AttributeValue {
 
   const GenderValue(this.value);
 }
+
+class IntegerValue extends AttributeValue {
+  @override
+  final int value;
+
+  const IntegerValue(this.value);
+}
+
+class DateTimeValue extends AttributeValue {
+  @override
+  final DateTime value;
+
+  const DateTimeValue(this.value);
+}
+
+class ImageValue extends AttributeValue {
+  @override
+  final Uint8List value;
+
+  const ImageValue(this.value);
+}
+
+class BytesValue extends AttributeValue {
+  @override
+  final Uint8List value;
+
+  const BytesValue(this.value);
+}
+
+class ArrayValue extends AttributeValue {
+  @override
+  final List<AttributeValue> value;
+
+  const ArrayValue(this.value);
+}
+
+class DictionaryValue extends AttributeValue {
+  @override
+  final Map<String, AttributeValue> value;
+
+  const DictionaryValue(this.value);
+}
@@ -1,3 +1,5 @@
+import 'dart:convert';
+
 import 'package:json_annotation/json_annotation.dart';
 
 import '../attribute_value.dart';
@@ -12,6 +14,12 @@ const _kStringValue = 'string';
 const _kBooleanValue = 'bool';
 const _kDateValue = 'date';
 const _kGenderValue = 'gender';
+const _kIntegerValue = 'integer';
+const _kDateTimeValue = 'date_time';
+const _kImageValue = 'image';
+const _kBytesValue = 'bytes';
+const _kArrayValue = 'array';
+const _kDictionaryValue = 'dictionary';
 
 /// Map used to (consistently) convert the gender enum to a string
 const _kGenderTypeEnumMap = {
@@ -35,6 +43,20 @@ class AttributeValueConverter extends JsonConverter<AttributeValue, Map<String,
         return DateValue(_decodeDateTime(json[_kValueKey]!));
       case _kGenderValue:
         return GenderValue(_decodeGender(json[_kValueKey]!));
+      case _kIntegerValue:
+        return IntegerValue(json[_kValueKey]!);
+      case _kDateTimeValue:
+        return DateTimeValue(DateTime.parse(json[_kValueKey]!));
+      case _kImageValue:
+        return ImageValue(base64Decode(json[_kValueKey]!));
+      case _kBytesValue:
+        return BytesValue(base64Decode(json[_kValueKey]!));
+      case _kArrayValue:
+        return ArrayValue((json[_kValueKey]! as List<dynamic>).cast<Map<String, dynamic>>().map(fromJson).toList());
+      case _kDictionaryValue:
+        return DictionaryValue(
+          (json[_kValueKey]! as Map<String, dynamic>).map((key, value) => MapEntry(key, fromJson(value))),
+        );
     }
     throw UnsupportedError('Unknown type: ${json[_kTypeKey]}');
   }
@@ -50,6 +72,21 @@ class AttributeValueConverter extends JsonConverter<AttributeValue, Map<String,
         return {_kTypeKey: _kDateValue, _kValueKey: _encodeDateTime(object.value)};
       case GenderValue():
         return {_kTypeKey: _kGenderValue, _kValueKey: _encodeGender(object.value)};
+      case IntegerValue():
+        return {_kTypeKey: _kIntegerValue, _kValueKey: object.value};
+      case DateTimeValue():
+        return {_kTypeKey: _kDateTimeValue, _kValueKey: object.value.toIso8601String()};
+      case ImageValue():
+        return {_kTypeKey: _kImageValue, _kValueKey: base64Encode(object.value)};
+      case BytesValue():
+        return {_kTypeKey: _kBytesValue, _kValueKey: base64Encode(object.value)};
+      case ArrayValue():
+        return {_kTypeKey: _kArrayValue, _kValueKey: object.value.map(toJson).toList()};
+      case DictionaryValue():
+        return {
+          _kTypeKey: _kDictionaryValue,
+          _kValueKey: object.value.map((key, value) => MapEntry(key, toJson(value))),
+        };
     }
   }
 
@@ -7,6 +7,8 @@ import '../../../../util/formatter/attribute_value_formatter.dart';
 import '../../../../util/helper/bsn_helper.dart';
 import '../../../../util/helper/semantics_helper.dart';
 
+const _kImageHeight = 120.0;
+
 class DataAttributeRow extends StatelessWidget {
   final DataAttribute attribute;
 
@@ -24,14 +26,30 @@ class DataAttributeRow extends StatelessWidget {
             attribute.label.l10nValue(context),
             style: context.textTheme.bodySmall,
           ),
-          Text(
-            prettyValue,
-            style: context.textTheme.titleMedium,
-            semanticsLabel:
-                BsnHelper.isValidBsnFormat(prettyValue) ? SemanticsHelper.splitNumberString(prettyValue) : null,
-          ),
+          _buildValue(context, prettyValue),
         ],
       ),
     );
   }
+
+  Widget _buildValue(BuildContext context, String prettyValue) {
+    final text = Text(
+      prettyValue,
+      style: context.textTheme.titleMedium,
+      semanticsLabel: BsnHelper.isValidBsnFormat(prettyValue) ? SemanticsHelper.splitNumberString(prettyValue) : null,
+    );
+    final value = attribute.value;
+    if (value is! ImageValue) return text;
+    return Padding(
+      padding: const EdgeInsets.only(top: 4),
+      child: Image.memory(
+        value.value,
+        height: _kImageHeight,
+        alignment: Alignment.centerLeft,
+        semanticLabel: prettyValue,
+        // Not every encoding found in documents (e.g. JPEG 2000) can be decoded, fall back to the textual value.
+        errorBuilder: (context, error, stackTrace) => text,
+      ),
+    );
+  }
 }
@@ -8,22 +8,25 @@ import '../../domain/model/attribute/value/gender.dart';
 import '../extension/build_context_extension.dart';
 
 class AttributeValueFormatter {
-  static String format(BuildContext context, AttributeValue attributeValue) {
-    return switch (attributeValue) {
-      StringValue() => attributeValue.value,
-      BooleanValue() => attributeValue.value ? context.l10n.cardValueTrue : context.l10n.cardValueFalse,
-      DateValue() => _prettyPrintDateTime(context.locale, attributeValue.value),
-      GenderValue() => _prettyPrintGender(context.l10n, attributeValue.value),
-    };
-  }
+  static String format(BuildContext context, AttributeValue attributeValue) =>
+      _format(context.l10n, context.locale, attributeValue);
+
+  static String formatWithLocale(Locale locale, AttributeValue attributeValue) =>
+      _format(lookupAppLocalizations(locale), locale.languageCode, attributeValue);
 
-  static String formatWithLocale(Locale locale, AttributeValue attributeValue) {
-    final l10n = lookupAppLocalizations(locale);
+  static String _format(AppLocalizations l10n, String locale, AttributeValue attributeValue) {
     return switch (attributeValue) {
       StringValue() => attributeValue.value,
       BooleanValue() => attributeValue.value ? l10n.cardValueTrue : l10n.cardValueFalse,
-      DateValue() => _prettyPrintDateTime(locale.languageCode, attributeValue.value),
+      DateValue() => _prettyPrintDateTime(locale, attributeValue.value),
       GenderValue() => _prettyPrintGender(l10n, attributeValue.value),
+      IntegerValue() => attributeValue.value.toString(),
+      DateTimeValue() => _prettyPrintTimestamp(locale, attributeValue.value),
+      ImageValue() => l10n.cardValueImage,
+      BytesValue() => l10n.cardValueBytes(attributeValue.value.length),
+      ArrayValue() => attributeValue.value.map((value) => _format(l10n, locale, value)).join(', '),
+      DictionaryValue() =>
+        attributeValue.value.entries.map((entry) => '${entry.key}: ${_format(l10n, locale, entry.value)}').join(', '),
     };
   }
 
@@ -36,6 +39,16 @@ class AttributeValueFormatter {
     }
   }
 
+  static String _prettyPrintTimestamp(String locale, DateTime dateTime) {
+    final localDateTime = dateTime.toLocal();
+    if (DateFormat.localeExists(locale)) {
+      return DateFormat(DateFormat.YEAR_MONTH_DAY, locale).add_Hm().format(localDateTime);
+    } else {
+      Fimber.i('DateFormat does not support locale: $locale, formatting without locale.');
+      return DateFormat(DateFormat.YEAR_MONTH_DAY).add_Hm().format(localDateTime);
+    }
+  }
+
   static String _prettyPrintGender(AppLocalizations l10n, Gender gender) {
     switch (gender) {
       case Gender.unknown:
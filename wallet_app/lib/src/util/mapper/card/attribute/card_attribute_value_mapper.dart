@@ -25,6 +25,12 @@ class CardAttributeValueMapper extends Mapper<CardValue, AttributeValue> {
             return const GenderValue(Gender.notApplicable);
         }
       },
+      integer: (input) => IntegerValue(input.value),
+      dateTime: (input) => DateTimeValue(DateTime.parse(input.value)),
+      image: (input) => ImageValue(input.value),
+      bytes: (input) => BytesValue(input.value),
+      array: (input) => ArrayValue(input.value.map(map).toList()),
+      dictionary: (input) => DictionaryValue({for (final entry in input.value) entry.key: map(entry.value)}),
     );
   }
 }
@@ -21,6 +21,8 @@ class CardFrontMapper extends Mapper<Card, CardFront> {
         return _getPidCardFront(l10ns, input);
       case kAddressDocType:
         return _getAddressCardFront(l10ns, input);
+      case kMdlDocType:
+        return _getMdlCardFront(l10ns, input);
       case 'DIPLOMA_1':
         return _kMockDiplomaCardFront;
       case 'DIPLOMA_2':
@@ -58,6 +60,16 @@ class CardFrontMapper extends Mapper<Card, CardFront> {
     );
   }
 
+  CardFront _getMdlCardFront(List<AppLocalizations> l10ns, Card input) {
+    return CardFront(
+      title: l10ns.asMap().map((_, l10n) => MapEntry(l10n.localeName, l10n.mdlCardTitle)),
+      subtitle: _subtitleMapper.map(input),
+      logoImage: WalletAssets.logo_nl_driving_license,
+      backgroundImage: WalletAssets.image_bg_nl_driving_license,
+      theme: CardFrontTheme.light,
+    );
+  }
+
   /// Hacky way to figure out if the card is a renewed license, this will be removed once we properly implement
   /// a way to get the [CardFront]s through the core.
   bool isRenewedLicense(Card input) => input.attributes
@@ -15,6 +15,7 @@ class CardSubtitleMapper extends Mapper<Card, LocalizedText?> {
   LocalizedText? map(Card input) {
     switch (input.docType) {
       case kPidDocType:
+      case kMdlDocType:
         final nameAttribute =
             input.attributes.firstWhereOrNull((attribute) => attribute.key.toLowerCase().contains('name'));
         if (nameAttribute == null) return null;
@@ -113,6 +113,14 @@ class TypedWalletCore {
   Future<StartDisclosureResult> startDisclosure(String uri, {bool isQrCode = false}) =>
       call((core) => core.startDisclosure(uri: uri, isQrCode: isQrCode));
 
+  /// Selects which of the candidate cards should be disclosed, by providing an index for every requested card.
+  Future<List<DisclosureCard>> selectDisclosureCandidates(List<int> selection) =>
+      call((core) => core.selectDisclosureCandidates(selection: Uint32List.fromList(selection)));
+
+  /// Provides the values of the requested self-asserted attributes, which are disclosed without being verified.
+  Future<void> setDisclosureSelfAssertedAttributes(List<SelfAssertedAttribute> attributes) =>
+      call((core) => core.setDisclosureSelfAssertedAttributes(attributes: attributes));
+
   Future<void> cancelDisclosure() => call((core) => core.cancelDisclosure());
 
   Future<AcceptDisclosureResult> acceptDisclosure(String pin) => call((core) => core.acceptDisclosure(pin: pin));
@@ -130,6 +138,16 @@ class TypedWalletCore {
 
   Stream<List<WalletEvent>> observeRecentHistory() => _recentHistory.stream;
 
+  Future<StartDeviceLinkResult> startDeviceLink(String pin) => call((core) => core.startDeviceLink(pin: pin));
+
+  Future<WalletInstructionResult> linkDevice(String pin, String linkCode) =>
+      call((core) => core.linkDevice(pin: pin, linkCode: linkCode));
+
+  Future<LinkedDevicesResult> getLinkedDevices(String pin) => call((core) => core.getLinkedDevices(pin: pin));
+
+  Future<WalletInstructionResult> removeLinkedDevice(String pin, String deviceId) =>
+      call((core) => core.removeLinkedDevice(pin: pin, deviceId: deviceId));
+
   /// This function should be used to call through to the core, as it makes sure potential exceptions are processed
   /// before they are (re)thrown.
   Future<T> call<T>(Future<T> Function(WalletCore) runnable) async {
@@ -11,3 +11,4 @@ export 'src/wallet_core.dart';
 // based on this docType inside wallet_app (see [CardFrontMapper]). To be removed #someday
 const kPidDocType = 'com.example.pid';
 const kAddressDocType = 'com.example.address';
+const kMdlDocType = 'org.iso.18013.5.1.mDL';
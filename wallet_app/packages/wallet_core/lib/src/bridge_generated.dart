@@ -102,6 +102,14 @@ abstract class WalletCore {
 
   FlutterRustBridgeTaskConstMeta get kStartDisclosureConstMeta;
 
+  Future<List<DisclosureCard>> selectDisclosureCandidates({required Uint32List selection, dynamic hint});
+
+  FlutterRustBridgeTaskConstMeta get kSelectDisclosureCandidatesConstMeta;
+
+  Future<void> setDisclosureSelfAssertedAttributes({required List<SelfAssertedAttribute> attributes, dynamic hint});
+
+  FlutterRustBridgeTaskConstMeta get kSetDisclosureSelfAssertedAttributesConstMeta;
+
   Future<void> cancelDisclosure({dynamic hint});
 
   FlutterRustBridgeTaskConstMeta get kCancelDisclosureConstMeta;
@@ -122,6 +130,22 @@ abstract class WalletCore {
 
   FlutterRustBridgeTaskConstMeta get kGetHistoryForCardConstMeta;
 
+  Future<StartDeviceLinkResult> startDeviceLink({required String pin, dynamic hint});
+
+  FlutterRustBridgeTaskConstMeta get kStartDeviceLinkConstMeta;
+
+  Future<WalletInstructionResult> linkDevice({required String pin, required String linkCode, dynamic hint});
+
+  FlutterRustBridgeTaskConstMeta get kLinkDeviceConstMeta;
+
+  Future<LinkedDevicesResult> getLinkedDevices({required String pin, dynamic hint});
+
+  FlutterRustBridgeTaskConstMeta get kGetLinkedDevicesConstMeta;
+
+  Future<WalletInstructionResult> removeLinkedDevice({required String pin, required String deviceId, dynamic hint});
+
+  FlutterRustBridgeTaskConstMeta get kRemoveLinkedDeviceConstMeta;
+
   Future<void> resetWallet({dynamic hint});
 
   FlutterRustBridgeTaskConstMeta get kResetWalletConstMeta;
@@ -185,6 +209,34 @@ class CardValue with _$CardValue {
   const factory CardValue.gender({
     required GenderCardValue value,
   }) = CardValue_Gender;
+  const factory CardValue.integer({
+    required int value,
+  }) = CardValue_Integer;
+  const factory CardValue.dateTime({
+    required String value,
+  }) = CardValue_DateTime;
+  const factory CardValue.image({
+    required Uint8List value,
+  }) = CardValue_Image;
+  const factory CardValue.bytes({
+    required Uint8List value,
+  }) = CardValue_Bytes;
+  const factory CardValue.array({
+    required List<CardValue> value,
+  }) = CardValue_Array;
+  const factory CardValue.dictionary({
+    required List<CardValueDictionaryEntry> value,
+  }) = CardValue_Dictionary;
+}
+
+class CardValueDictionaryEntry {
+  final String key;
+  final CardValue value;
+
+  const CardValueDictionaryEntry({
+    required this.key,
+    required this.value,
+  });
 }
 
 class DisclosureCard {
@@ -199,6 +251,14 @@ class DisclosureCard {
   });
 }
 
+class DisclosureCardCandidates {
+  final List<DisclosureCard> cards;
+
+  const DisclosureCardCandidates({
+    required this.cards,
+  });
+}
+
 enum DisclosureSessionType {
   SameDevice,
   CrossDevice,
@@ -255,6 +315,28 @@ class Image with _$Image {
   }) = Image_Asset;
 }
 
+class LinkedDevice {
+  final String id;
+  final String linkedDateTime;
+  final bool isCurrent;
+
+  const LinkedDevice({
+    required this.id,
+    required this.linkedDateTime,
+    required this.isCurrent,
+  });
+}
+
+@freezed
+class LinkedDevicesResult with _$LinkedDevicesResult {
+  const factory LinkedDevicesResult.ok({
+    required List<LinkedDevice> devices,
+  }) = LinkedDevicesResult_Ok;
+  const factory LinkedDevicesResult.instructionError({
+    required WalletInstructionError error,
+  }) = LinkedDevicesResult_InstructionError;
+}
+
 class LocalizedString {
   final String language;
   final String value;
@@ -322,12 +404,50 @@ class RequestPolicy {
   });
 }
 
+class RequestedSelfAssertedAttribute {
+  final String docType;
+  final String namespace;
+  final String key;
+
+  const RequestedSelfAssertedAttribute({
+    required this.docType,
+    required this.namespace,
+    required this.key,
+  });
+}
+
+class SelfAssertedAttribute {
+  final String docType;
+  final String namespace;
+  final String key;
+  final String value;
+
+  const SelfAssertedAttribute({
+    required this.docType,
+    required this.namespace,
+    required this.key,
+    required this.value,
+  });
+}
+
+@freezed
+class StartDeviceLinkResult with _$StartDeviceLinkResult {
+  const factory StartDeviceLinkResult.ok({
+    required String linkCode,
+  }) = StartDeviceLinkResult_Ok;
+  const factory StartDeviceLinkResult.instructionError({
+    required WalletInstructionError error,
+  }) = StartDeviceLinkResult_InstructionError;
+}
+
 @freezed
 class StartDisclosureResult with _$StartDisclosureResult {
   const factory StartDisclosureResult.request({
     required Organization relyingParty,
     required RequestPolicy policy,
     required List<DisclosureCard> requestedCards,
+    required List<DisclosureCardCandidates> candidateCards,
+    required List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
     required bool sharedDataWithRelyingPartyBefore,
     required DisclosureSessionType sessionType,
     required List<LocalizedString> requestPurpose,
@@ -748,6 +868,41 @@ class WalletCoreImpl implements WalletCore {
         argNames: ["uri", "isQrCode"],
       );
 
+  Future<List<DisclosureCard>> selectDisclosureCandidates({required Uint32List selection, dynamic hint}) {
+    var arg0 = _platform.api2wire_uint_32_list(selection);
+    return _platform.executeNormal(FlutterRustBridgeTask(
+      callFfi: (port_) => _platform.inner.wire_select_disclosure_candidates(port_, arg0),
+      parseSuccessData: _wire2api_list_disclosure_card,
+      parseErrorData: _wire2api_FrbAnyhowException,
+      constMeta: kSelectDisclosureCandidatesConstMeta,
+      argValues: [selection],
+      hint: hint,
+    ));
+  }
+
+  FlutterRustBridgeTaskConstMeta get kSelectDisclosureCandidatesConstMeta => const FlutterRustBridgeTaskConstMeta(
+        debugName: "select_disclosure_candidates",
+        argNames: ["selection"],
+      );
+
+  Future<void> setDisclosureSelfAssertedAttributes({required List<SelfAssertedAttribute> attributes, dynamic hint}) {
+    var arg0 = _platform.api2wire_list_self_asserted_attribute(attributes);
+    return _platform.executeNormal(FlutterRustBridgeTask(
+      callFfi: (port_) => _platform.inner.wire_set_disclosure_self_asserted_attributes(port_, arg0),
+      parseSuccessData: _wire2api_unit,
+      parseErrorData: _wire2api_FrbAnyhowException,
+      constMeta: kSetDisclosureSelfAssertedAttributesConstMeta,
+      argValues: [attributes],
+      hint: hint,
+    ));
+  }
+
+  FlutterRustBridgeTaskConstMeta get kSetDisclosureSelfAssertedAttributesConstMeta =>
+      const FlutterRustBridgeTaskConstMeta(
+        debugName: "set_disclosure_self_asserted_attributes",
+        argNames: ["attributes"],
+      );
+
   Future<void> cancelDisclosure({dynamic hint}) {
     return _platform.executeNormal(FlutterRustBridgeTask(
       callFfi: (port_) => _platform.inner.wire_cancel_disclosure(port_),
@@ -830,6 +985,76 @@ class WalletCoreImpl implements WalletCore {
         argNames: ["docType"],
       );
 
+  Future<StartDeviceLinkResult> startDeviceLink({required String pin, dynamic hint}) {
+    var arg0 = _platform.api2wire_String(pin);
+    return _platform.executeNormal(FlutterRustBridgeTask(
+      callFfi: (port_) => _platform.inner.wire_start_device_link(port_, arg0),
+      parseSuccessData: _wire2api_start_device_link_result,
+      parseErrorData: _wire2api_FrbAnyhowException,
+      constMeta: kStartDeviceLinkConstMeta,
+      argValues: [pin],
+      hint: hint,
+    ));
+  }
+
+  FlutterRustBridgeTaskConstMeta get kStartDeviceLinkConstMeta => const FlutterRustBridgeTaskConstMeta(
+        debugName: "start_device_link",
+        argNames: ["pin"],
+      );
+
+  Future<WalletInstructionResult> linkDevice({required String pin, required String linkCode, dynamic hint}) {
+    var arg0 = _platform.api2wire_String(pin);
+    var arg1 = _platform.api2wire_String(linkCode);
+    return _platform.executeNormal(FlutterRustBridgeTask(
+      callFfi: (port_) => _platform.inner.wire_link_device(port_, arg0, arg1),
+      parseSuccessData: _wire2api_wallet_instruction_result,
+      parseErrorData: _wire2api_FrbAnyhowException,
+      constMeta: kLinkDeviceConstMeta,
+      argValues: [pin, linkCode],
+      hint: hint,
+    ));
+  }
+
+  FlutterRustBridgeTaskConstMeta get kLinkDeviceConstMeta => const FlutterRustBridgeTaskConstMeta(
+        debugName: "link_device",
+        argNames: ["pin", "linkCode"],
+      );
+
+  Future<LinkedDevicesResult> getLinkedDevices({required String pin, dynamic hint}) {
+    var arg0 = _platform.api2wire_String(pin);
+    return _platform.executeNormal(FlutterRustBridgeTask(
+      callFfi: (port_) => _platform.inner.wire_get_linked_devices(port_, arg0),
+      parseSuccessData: _wire2api_linked_devices_result,
+      parseErrorData: _wire2api_FrbAnyhowException,
+      constMeta: kGetLinkedDevicesConstMeta,
+      argValues: [pin],
+      hint: hint,
+    ));
+  }
+
+  FlutterRustBridgeTaskConstMeta get kGetLinkedDevicesConstMeta => const FlutterRustBridgeTaskConstMeta(
+        debugName: "get_linked_devices",
+        argNames: ["pin"],
+      );
+
+  Future<WalletInstructionResult> removeLinkedDevice({required String pin, required String deviceId, dynamic hint}) {
+    var arg0 = _platform.api2wire_String(pin);
+    var arg1 = _platform.api2wire_String(deviceId);
+    return _platform.executeNormal(FlutterRustBridgeTask(
+      callFfi: (port_) => _platform.inner.wire_remove_linked_device(port_, arg0, arg1),
+      parseSuccessData: _wire2api_wallet_instruction_result,
+      parseErrorData: _wire2api_FrbAnyhowException,
+      constMeta: kRemoveLinkedDeviceConstMeta,
+      argValues: [pin, deviceId],
+      hint: hint,
+    ));
+  }
+
+  FlutterRustBridgeTaskConstMeta get kRemoveLinkedDeviceConstMeta => const FlutterRustBridgeTaskConstMeta(
+        debugName: "remove_linked_device",
+        argNames: ["pin", "deviceId"],
+      );
+
   Future<void> resetWallet({dynamic hint}) {
     return _platform.executeNormal(FlutterRustBridgeTask(
       callFfi: (port_) => _platform.inner.wire_reset_wallet(port_),
@@ -954,11 +1179,44 @@ class WalletCoreImpl implements WalletCore {
         return CardValue_Gender(
           value: _wire2api_gender_card_value(raw[1]),
         );
+      case 4:
+        return CardValue_Integer(
+          value: _wire2api_i64(raw[1]),
+        );
+      case 5:
+        return CardValue_DateTime(
+          value: _wire2api_String(raw[1]),
+        );
+      case 6:
+        return CardValue_Image(
+          value: _wire2api_uint_8_list(raw[1]),
+        );
+      case 7:
+        return CardValue_Bytes(
+          value: _wire2api_uint_8_list(raw[1]),
+        );
+      case 8:
+        return CardValue_Array(
+          value: _wire2api_list_card_value(raw[1]),
+        );
+      case 9:
+        return CardValue_Dictionary(
+          value: _wire2api_list_card_value_dictionary_entry(raw[1]),
+        );
       default:
         throw Exception("unreachable");
     }
   }
 
+  CardValueDictionaryEntry _wire2api_card_value_dictionary_entry(dynamic raw) {
+    final arr = raw as List<dynamic>;
+    if (arr.length != 2) throw Exception('unexpected arr length: expect 2 but see ${arr.length}');
+    return CardValueDictionaryEntry(
+      key: _wire2api_String(arr[0]),
+      value: _wire2api_card_value(arr[1]),
+    );
+  }
+
   DisclosureCard _wire2api_disclosure_card(dynamic raw) {
     final arr = raw as List<dynamic>;
     if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
@@ -969,6 +1227,14 @@ class WalletCoreImpl implements WalletCore {
     );
   }
 
+  DisclosureCardCandidates _wire2api_disclosure_card_candidates(dynamic raw) {
+    final arr = raw as List<dynamic>;
+    if (arr.length != 1) throw Exception('unexpected arr length: expect 1 but see ${arr.length}');
+    return DisclosureCardCandidates(
+      cards: _wire2api_list_disclosure_card(arr[0]),
+    );
+  }
+
   DisclosureSessionType _wire2api_disclosure_session_type(dynamic raw) {
     return DisclosureSessionType.values[raw as int];
   }
@@ -999,6 +1265,10 @@ class WalletCoreImpl implements WalletCore {
     return raw as int;
   }
 
+  int _wire2api_i64(dynamic raw) {
+    return castInt(raw);
+  }
+
   IdentifyUriResult _wire2api_identify_uri_result(dynamic raw) {
     return IdentifyUriResult.values[raw as int];
   }
@@ -1026,6 +1296,31 @@ class WalletCoreImpl implements WalletCore {
     }
   }
 
+  LinkedDevice _wire2api_linked_device(dynamic raw) {
+    final arr = raw as List<dynamic>;
+    if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+    return LinkedDevice(
+      id: _wire2api_String(arr[0]),
+      linkedDateTime: _wire2api_String(arr[1]),
+      isCurrent: _wire2api_bool(arr[2]),
+    );
+  }
+
+  LinkedDevicesResult _wire2api_linked_devices_result(dynamic raw) {
+    switch (raw[0]) {
+      case 0:
+        return LinkedDevicesResult_Ok(
+          devices: _wire2api_list_linked_device(raw[1]),
+        );
+      case 1:
+        return LinkedDevicesResult_InstructionError(
+          error: _wire2api_box_autoadd_wallet_instruction_error(raw[1]),
+        );
+      default:
+        throw Exception("unreachable");
+    }
+  }
+
   List<Card> _wire2api_list_card(dynamic raw) {
     return (raw as List<dynamic>).map(_wire2api_card).toList();
   }
@@ -1034,10 +1329,26 @@ class WalletCoreImpl implements WalletCore {
     return (raw as List<dynamic>).map(_wire2api_card_attribute).toList();
   }
 
+  List<CardValue> _wire2api_list_card_value(dynamic raw) {
+    return (raw as List<dynamic>).map(_wire2api_card_value).toList();
+  }
+
+  List<CardValueDictionaryEntry> _wire2api_list_card_value_dictionary_entry(dynamic raw) {
+    return (raw as List<dynamic>).map(_wire2api_card_value_dictionary_entry).toList();
+  }
+
   List<DisclosureCard> _wire2api_list_disclosure_card(dynamic raw) {
     return (raw as List<dynamic>).map(_wire2api_disclosure_card).toList();
   }
 
+  List<DisclosureCardCandidates> _wire2api_list_disclosure_card_candidates(dynamic raw) {
+    return (raw as List<dynamic>).map(_wire2api_disclosure_card_candidates).toList();
+  }
+
+  List<LinkedDevice> _wire2api_list_linked_device(dynamic raw) {
+    return (raw as List<dynamic>).map(_wire2api_linked_device).toList();
+  }
+
   List<LocalizedString> _wire2api_list_localized_string(dynamic raw) {
     return (raw as List<dynamic>).map(_wire2api_localized_string).toList();
   }
@@ -1046,6 +1357,10 @@ class WalletCoreImpl implements WalletCore {
     return (raw as List<dynamic>).map(_wire2api_missing_attribute).toList();
   }
 
+  List<RequestedSelfAssertedAttribute> _wire2api_list_requested_self_asserted_attribute(dynamic raw) {
+    return (raw as List<dynamic>).map(_wire2api_requested_self_asserted_attribute).toList();
+  }
+
   List<WalletEvent> _wire2api_list_wallet_event(dynamic raw) {
     return (raw as List<dynamic>).map(_wire2api_wallet_event).toList();
   }
@@ -1120,6 +1435,31 @@ class WalletCoreImpl implements WalletCore {
     );
   }
 
+  RequestedSelfAssertedAttribute _wire2api_requested_self_asserted_attribute(dynamic raw) {
+    final arr = raw as List<dynamic>;
+    if (arr.length != 3) throw Exception('unexpected arr length: expect 3 but see ${arr.length}');
+    return RequestedSelfAssertedAttribute(
+      docType: _wire2api_String(arr[0]),
+      namespace: _wire2api_String(arr[1]),
+      key: _wire2api_String(arr[2]),
+    );
+  }
+
+  StartDeviceLinkResult _wire2api_start_device_link_result(dynamic raw) {
+    switch (raw[0]) {
+      case 0:
+        return StartDeviceLinkResult_Ok(
+          linkCode: _wire2api_String(raw[1]),
+        );
+      case 1:
+        return StartDeviceLinkResult_InstructionError(
+          error: _wire2api_box_autoadd_wallet_instruction_error(raw[1]),
+        );
+      default:
+        throw Exception("unreachable");
+    }
+  }
+
   StartDisclosureResult _wire2api_start_disclosure_result(dynamic raw) {
     switch (raw[0]) {
       case 0:
@@ -1127,11 +1467,13 @@ class WalletCoreImpl implements WalletCore {
           relyingParty: _wire2api_box_autoadd_organization(raw[1]),
           policy: _wire2api_box_autoadd_request_policy(raw[2]),
           requestedCards: _wire2api_list_disclosure_card(raw[3]),
-          sharedDataWithRelyingPartyBefore: _wire2api_bool(raw[4]),
-          sessionType: _wire2api_disclosure_session_type(raw[5]),
-          requestPurpose: _wire2api_list_localized_string(raw[6]),
-          requestOriginBaseUrl: _wire2api_String(raw[7]),
-          requestType: _wire2api_disclosure_type(raw[8]),
+          candidateCards: _wire2api_list_disclosure_card_candidates(raw[4]),
+          requestedSelfAssertedAttributes: _wire2api_list_requested_self_asserted_attribute(raw[5]),
+          sharedDataWithRelyingPartyBefore: _wire2api_bool(raw[6]),
+          sessionType: _wire2api_disclosure_session_type(raw[7]),
+          requestPurpose: _wire2api_list_localized_string(raw[8]),
+          requestOriginBaseUrl: _wire2api_String(raw[9]),
+          requestType: _wire2api_disclosure_type(raw[10]),
         );
       case 1:
         return StartDisclosureResult_RequestAttributesMissing(
@@ -1228,6 +1570,11 @@ bool api2wire_bool(bool raw) {
   return raw;
 }
 
+@protected
+int api2wire_u32(int raw) {
+  return raw;
+}
+
 @protected
 int api2wire_u8(int raw) {
   return raw;
@@ -1245,6 +1592,23 @@ class WalletCorePlatform extends FlutterRustBridgeBase<WalletCoreWire> {
     return api2wire_uint_8_list(utf8.encoder.convert(raw));
   }
 
+  @protected
+  ffi.Pointer<wire_list_self_asserted_attribute> api2wire_list_self_asserted_attribute(
+      List<SelfAssertedAttribute> raw) {
+    final ans = inner.new_list_self_asserted_attribute_0(raw.length);
+    for (var i = 0; i < raw.length; ++i) {
+      _api_fill_to_wire_self_asserted_attribute(raw[i], ans.ref.ptr[i]);
+    }
+    return ans;
+  }
+
+  @protected
+  ffi.Pointer<wire_uint_32_list> api2wire_uint_32_list(Uint32List raw) {
+    final ans = inner.new_uint_32_list_0(raw.length);
+    ans.ref.ptr.asTypedList(raw.length).setAll(0, raw);
+    return ans;
+  }
+
   @protected
   ffi.Pointer<wire_uint_8_list> api2wire_uint_8_list(Uint8List raw) {
     final ans = inner.new_uint_8_list_0(raw.length);
@@ -1254,6 +1618,13 @@ class WalletCorePlatform extends FlutterRustBridgeBase<WalletCoreWire> {
 // Section: finalizer
 
 // Section: api_fill_to_wire
+
+  void _api_fill_to_wire_self_asserted_attribute(SelfAssertedAttribute apiObj, wire_SelfAssertedAttribute wireObj) {
+    wireObj.doc_type = api2wire_String(apiObj.docType);
+    wireObj.namespace = api2wire_String(apiObj.namespace);
+    wireObj.key = api2wire_String(apiObj.key);
+    wireObj.value = api2wire_String(apiObj.value);
+  }
 }
 
 // ignore_for_file: camel_case_types, non_constant_identifier_names, avoid_positional_boolean_parameters, annotate_overrides, constant_identifier_names
@@ -1621,6 +1992,38 @@ class WalletCoreWire implements FlutterRustBridgeWireBase {
   late final _wire_start_disclosure =
       _wire_start_disclosurePtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>, bool)>();
 
+  void wire_select_disclosure_candidates(
+    int port_,
+    ffi.Pointer<wire_uint_32_list> selection,
+  ) {
+    return _wire_select_disclosure_candidates(
+      port_,
+      selection,
+    );
+  }
+
+  late final _wire_select_disclosure_candidatesPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_32_list>)>>(
+          'wire_select_disclosure_candidates');
+  late final _wire_select_disclosure_candidates =
+      _wire_select_disclosure_candidatesPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_32_list>)>();
+
+  void wire_set_disclosure_self_asserted_attributes(
+    int port_,
+    ffi.Pointer<wire_list_self_asserted_attribute> attributes,
+  ) {
+    return _wire_set_disclosure_self_asserted_attributes(
+      port_,
+      attributes,
+    );
+  }
+
+  late final _wire_set_disclosure_self_asserted_attributesPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_list_self_asserted_attribute>)>>(
+          'wire_set_disclosure_self_asserted_attributes');
+  late final _wire_set_disclosure_self_asserted_attributes = _wire_set_disclosure_self_asserted_attributesPtr
+      .asFunction<void Function(int, ffi.Pointer<wire_list_self_asserted_attribute>)>();
+
   void wire_cancel_disclosure(
     int port_,
   ) {
@@ -1689,6 +2092,76 @@ class WalletCoreWire implements FlutterRustBridgeWireBase {
   late final _wire_get_history_for_card =
       _wire_get_history_for_cardPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
 
+  void wire_start_device_link(
+    int port_,
+    ffi.Pointer<wire_uint_8_list> pin,
+  ) {
+    return _wire_start_device_link(
+      port_,
+      pin,
+    );
+  }
+
+  late final _wire_start_device_linkPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>(
+          'wire_start_device_link');
+  late final _wire_start_device_link =
+      _wire_start_device_linkPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_link_device(
+    int port_,
+    ffi.Pointer<wire_uint_8_list> pin,
+    ffi.Pointer<wire_uint_8_list> link_code,
+  ) {
+    return _wire_link_device(
+      port_,
+      pin,
+      link_code,
+    );
+  }
+
+  late final _wire_link_devicePtr = _lookup<
+      ffi.NativeFunction<
+          ffi.Void Function(
+              ffi.Int64, ffi.Pointer<wire_uint_8_list>, ffi.Pointer<wire_uint_8_list>)>>('wire_link_device');
+  late final _wire_link_device = _wire_link_devicePtr
+      .asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_get_linked_devices(
+    int port_,
+    ffi.Pointer<wire_uint_8_list> pin,
+  ) {
+    return _wire_get_linked_devices(
+      port_,
+      pin,
+    );
+  }
+
+  late final _wire_get_linked_devicesPtr =
+      _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64, ffi.Pointer<wire_uint_8_list>)>>(
+          'wire_get_linked_devices');
+  late final _wire_get_linked_devices =
+      _wire_get_linked_devicesPtr.asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>)>();
+
+  void wire_remove_linked_device(
+    int port_,
+    ffi.Pointer<wire_uint_8_list> pin,
+    ffi.Pointer<wire_uint_8_list> device_id,
+  ) {
+    return _wire_remove_linked_device(
+      port_,
+      pin,
+      device_id,
+    );
+  }
+
+  late final _wire_remove_linked_devicePtr = _lookup<
+      ffi.NativeFunction<
+          ffi.Void Function(
+              ffi.Int64, ffi.Pointer<wire_uint_8_list>, ffi.Pointer<wire_uint_8_list>)>>('wire_remove_linked_device');
+  late final _wire_remove_linked_device = _wire_remove_linked_devicePtr
+      .asFunction<void Function(int, ffi.Pointer<wire_uint_8_list>, ffi.Pointer<wire_uint_8_list>)>();
+
   void wire_reset_wallet(
     int port_,
   ) {
@@ -1700,6 +2173,32 @@ class WalletCoreWire implements FlutterRustBridgeWireBase {
   late final _wire_reset_walletPtr = _lookup<ffi.NativeFunction<ffi.Void Function(ffi.Int64)>>('wire_reset_wallet');
   late final _wire_reset_wallet = _wire_reset_walletPtr.asFunction<void Function(int)>();
 
+  ffi.Pointer<wire_list_self_asserted_attribute> new_list_self_asserted_attribute_0(
+    int len,
+  ) {
+    return _new_list_self_asserted_attribute_0(
+      len,
+    );
+  }
+
+  late final _new_list_self_asserted_attribute_0Ptr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_list_self_asserted_attribute> Function(ffi.Int32)>>(
+          'new_list_self_asserted_attribute_0');
+  late final _new_list_self_asserted_attribute_0 = _new_list_self_asserted_attribute_0Ptr
+      .asFunction<ffi.Pointer<wire_list_self_asserted_attribute> Function(int)>();
+
+  ffi.Pointer<wire_uint_32_list> new_uint_32_list_0(
+    int len,
+  ) {
+    return _new_uint_32_list_0(
+      len,
+    );
+  }
+
+  late final _new_uint_32_list_0Ptr =
+      _lookup<ffi.NativeFunction<ffi.Pointer<wire_uint_32_list> Function(ffi.Int32)>>('new_uint_32_list_0');
+  late final _new_uint_32_list_0 = _new_uint_32_list_0Ptr.asFunction<ffi.Pointer<wire_uint_32_list> Function(int)>();
+
   ffi.Pointer<wire_uint_8_list> new_uint_8_list_0(
     int len,
   ) {
@@ -1734,6 +2233,30 @@ final class wire_uint_8_list extends ffi.Struct {
   external int len;
 }
 
+final class wire_SelfAssertedAttribute extends ffi.Struct {
+  external ffi.Pointer<wire_uint_8_list> doc_type;
+
+  external ffi.Pointer<wire_uint_8_list> namespace;
+
+  external ffi.Pointer<wire_uint_8_list> key;
+
+  external ffi.Pointer<wire_uint_8_list> value;
+}
+
+final class wire_list_self_asserted_attribute extends ffi.Struct {
+  external ffi.Pointer<wire_SelfAssertedAttribute> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
+final class wire_uint_32_list extends ffi.Struct {
+  external ffi.Pointer<ffi.Uint32> ptr;
+
+  @ffi.Int32()
+  external int len;
+}
+
 typedef DartPostCObjectFnType
     = ffi.Pointer<ffi.NativeFunction<ffi.Bool Function(DartPort port_id, ffi.Pointer<ffi.Void> message)>>;
 typedef DartPort = ffi.Int64;
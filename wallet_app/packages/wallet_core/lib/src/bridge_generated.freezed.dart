@@ -652,6 +652,12 @@ mixin _$CardValue {
     required TResult Function(bool value) boolean,
     required TResult Function(String value) date,
     required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
@@ -660,6 +666,12 @@ mixin _$CardValue {
     TResult? Function(bool value)? boolean,
     TResult? Function(String value)? date,
     TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
@@ -668,6 +680,12 @@ mixin _$CardValue {
     TResult Function(bool value)? boolean,
     TResult Function(String value)? date,
     TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
     required TResult orElse(),
   }) =>
       throw _privateConstructorUsedError;
@@ -677,6 +695,12 @@ mixin _$CardValue {
     required TResult Function(CardValue_Boolean value) boolean,
     required TResult Function(CardValue_Date value) date,
     required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
@@ -685,6 +709,12 @@ mixin _$CardValue {
     TResult? Function(CardValue_Boolean value)? boolean,
     TResult? Function(CardValue_Date value)? date,
     TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
   }) =>
       throw _privateConstructorUsedError;
   @optionalTypeArgs
@@ -693,6 +723,12 @@ mixin _$CardValue {
     TResult Function(CardValue_Boolean value)? boolean,
     TResult Function(CardValue_Date value)? date,
     TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
     required TResult orElse(),
   }) =>
       throw _privateConstructorUsedError;
@@ -778,6 +814,12 @@ class _$CardValue_StringImpl implements CardValue_String {
     required TResult Function(bool value) boolean,
     required TResult Function(String value) date,
     required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
   }) {
     return string(value);
   }
@@ -789,6 +831,12 @@ class _$CardValue_StringImpl implements CardValue_String {
     TResult? Function(bool value)? boolean,
     TResult? Function(String value)? date,
     TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
   }) {
     return string?.call(value);
   }
@@ -800,6 +848,12 @@ class _$CardValue_StringImpl implements CardValue_String {
     TResult Function(bool value)? boolean,
     TResult Function(String value)? date,
     TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
     required TResult orElse(),
   }) {
     if (string != null) {
@@ -815,6 +869,12 @@ class _$CardValue_StringImpl implements CardValue_String {
     required TResult Function(CardValue_Boolean value) boolean,
     required TResult Function(CardValue_Date value) date,
     required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
   }) {
     return string(this);
   }
@@ -826,6 +886,12 @@ class _$CardValue_StringImpl implements CardValue_String {
     TResult? Function(CardValue_Boolean value)? boolean,
     TResult? Function(CardValue_Date value)? date,
     TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
   }) {
     return string?.call(this);
   }
@@ -837,6 +903,12 @@ class _$CardValue_StringImpl implements CardValue_String {
     TResult Function(CardValue_Boolean value)? boolean,
     TResult Function(CardValue_Date value)? date,
     TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
     required TResult orElse(),
   }) {
     if (string != null) {
@@ -920,6 +992,12 @@ class _$CardValue_BooleanImpl implements CardValue_Boolean {
     required TResult Function(bool value) boolean,
     required TResult Function(String value) date,
     required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
   }) {
     return boolean(value);
   }
@@ -931,6 +1009,12 @@ class _$CardValue_BooleanImpl implements CardValue_Boolean {
     TResult? Function(bool value)? boolean,
     TResult? Function(String value)? date,
     TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
   }) {
     return boolean?.call(value);
   }
@@ -942,6 +1026,12 @@ class _$CardValue_BooleanImpl implements CardValue_Boolean {
     TResult Function(bool value)? boolean,
     TResult Function(String value)? date,
     TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
     required TResult orElse(),
   }) {
     if (boolean != null) {
@@ -957,6 +1047,12 @@ class _$CardValue_BooleanImpl implements CardValue_Boolean {
     required TResult Function(CardValue_Boolean value) boolean,
     required TResult Function(CardValue_Date value) date,
     required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
   }) {
     return boolean(this);
   }
@@ -968,6 +1064,12 @@ class _$CardValue_BooleanImpl implements CardValue_Boolean {
     TResult? Function(CardValue_Boolean value)? boolean,
     TResult? Function(CardValue_Date value)? date,
     TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
   }) {
     return boolean?.call(this);
   }
@@ -979,6 +1081,12 @@ class _$CardValue_BooleanImpl implements CardValue_Boolean {
     TResult Function(CardValue_Boolean value)? boolean,
     TResult Function(CardValue_Date value)? date,
     TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
     required TResult orElse(),
   }) {
     if (boolean != null) {
@@ -1062,6 +1170,12 @@ class _$CardValue_DateImpl implements CardValue_Date {
     required TResult Function(bool value) boolean,
     required TResult Function(String value) date,
     required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
   }) {
     return date(value);
   }
@@ -1073,6 +1187,12 @@ class _$CardValue_DateImpl implements CardValue_Date {
     TResult? Function(bool value)? boolean,
     TResult? Function(String value)? date,
     TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
   }) {
     return date?.call(value);
   }
@@ -1084,6 +1204,12 @@ class _$CardValue_DateImpl implements CardValue_Date {
     TResult Function(bool value)? boolean,
     TResult Function(String value)? date,
     TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
     required TResult orElse(),
   }) {
     if (date != null) {
@@ -1099,6 +1225,12 @@ class _$CardValue_DateImpl implements CardValue_Date {
     required TResult Function(CardValue_Boolean value) boolean,
     required TResult Function(CardValue_Date value) date,
     required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
   }) {
     return date(this);
   }
@@ -1110,6 +1242,12 @@ class _$CardValue_DateImpl implements CardValue_Date {
     TResult? Function(CardValue_Boolean value)? boolean,
     TResult? Function(CardValue_Date value)? date,
     TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
   }) {
     return date?.call(this);
   }
@@ -1121,6 +1259,12 @@ class _$CardValue_DateImpl implements CardValue_Date {
     TResult Function(CardValue_Boolean value)? boolean,
     TResult Function(CardValue_Date value)? date,
     TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
     required TResult orElse(),
   }) {
     if (date != null) {
@@ -1204,6 +1348,12 @@ class _$CardValue_GenderImpl implements CardValue_Gender {
     required TResult Function(bool value) boolean,
     required TResult Function(String value) date,
     required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
   }) {
     return gender(value);
   }
@@ -1215,6 +1365,12 @@ class _$CardValue_GenderImpl implements CardValue_Gender {
     TResult? Function(bool value)? boolean,
     TResult? Function(String value)? date,
     TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
   }) {
     return gender?.call(value);
   }
@@ -1226,6 +1382,12 @@ class _$CardValue_GenderImpl implements CardValue_Gender {
     TResult Function(bool value)? boolean,
     TResult Function(String value)? date,
     TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
     required TResult orElse(),
   }) {
     if (gender != null) {
@@ -1241,6 +1403,12 @@ class _$CardValue_GenderImpl implements CardValue_Gender {
     required TResult Function(CardValue_Boolean value) boolean,
     required TResult Function(CardValue_Date value) date,
     required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
   }) {
     return gender(this);
   }
@@ -1252,6 +1420,12 @@ class _$CardValue_GenderImpl implements CardValue_Gender {
     TResult? Function(CardValue_Boolean value)? boolean,
     TResult? Function(CardValue_Date value)? date,
     TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
   }) {
     return gender?.call(this);
   }
@@ -1263,6 +1437,12 @@ class _$CardValue_GenderImpl implements CardValue_Gender {
     TResult Function(CardValue_Boolean value)? boolean,
     TResult Function(CardValue_Date value)? date,
     TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
     required TResult orElse(),
   }) {
     if (gender != null) {
@@ -1281,6 +1461,1088 @@ abstract class CardValue_Gender implements CardValue {
   _$$CardValue_GenderImplCopyWith<_$CardValue_GenderImpl> get copyWith => throw _privateConstructorUsedError;
 }
 
+/// @nodoc
+abstract class _$$CardValue_IntegerImplCopyWith<$Res> {
+  factory _$$CardValue_IntegerImplCopyWith(_$CardValue_IntegerImpl value, $Res Function(_$CardValue_IntegerImpl) then) =
+      __$$CardValue_IntegerImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({int value});
+}
+
+/// @nodoc
+class __$$CardValue_IntegerImplCopyWithImpl<$Res> extends _$CardValueCopyWithImpl<$Res, _$CardValue_IntegerImpl>
+    implements _$$CardValue_IntegerImplCopyWith<$Res> {
+  __$$CardValue_IntegerImplCopyWithImpl(_$CardValue_IntegerImpl _value, $Res Function(_$CardValue_IntegerImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? value = null,
+  }) {
+    return _then(_$CardValue_IntegerImpl(
+      value: null == value
+          ? _value.value
+          : value // ignore: cast_nullable_to_non_nullable
+              as int,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$CardValue_IntegerImpl implements CardValue_Integer {
+  const _$CardValue_IntegerImpl({required this.value});
+
+  @override
+  final int value;
+
+  @override
+  String toString() {
+    return 'CardValue.integer(value: $value)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$CardValue_IntegerImpl &&
+            (identical(other.value, value) || other.value == value));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, value);
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$CardValue_IntegerImplCopyWith<_$CardValue_IntegerImpl> get copyWith =>
+      __$$CardValue_IntegerImplCopyWithImpl<_$CardValue_IntegerImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String value) string,
+    required TResult Function(bool value) boolean,
+    required TResult Function(String value) date,
+    required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
+  }) {
+    return integer(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String value)? string,
+    TResult? Function(bool value)? boolean,
+    TResult? Function(String value)? date,
+    TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
+  }) {
+    return integer?.call(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String value)? string,
+    TResult Function(bool value)? boolean,
+    TResult Function(String value)? date,
+    TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (integer != null) {
+      return integer(value);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(CardValue_String value) string,
+    required TResult Function(CardValue_Boolean value) boolean,
+    required TResult Function(CardValue_Date value) date,
+    required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
+  }) {
+    return integer(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(CardValue_String value)? string,
+    TResult? Function(CardValue_Boolean value)? boolean,
+    TResult? Function(CardValue_Date value)? date,
+    TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
+  }) {
+    return integer?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(CardValue_String value)? string,
+    TResult Function(CardValue_Boolean value)? boolean,
+    TResult Function(CardValue_Date value)? date,
+    TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (integer != null) {
+      return integer(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class CardValue_Integer implements CardValue {
+  const factory CardValue_Integer({required final int value}) = _$CardValue_IntegerImpl;
+
+  @override
+  int get value;
+  @JsonKey(ignore: true)
+  _$$CardValue_IntegerImplCopyWith<_$CardValue_IntegerImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$CardValue_DateTimeImplCopyWith<$Res> {
+  factory _$$CardValue_DateTimeImplCopyWith(
+          _$CardValue_DateTimeImpl value, $Res Function(_$CardValue_DateTimeImpl) then) =
+      __$$CardValue_DateTimeImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({String value});
+}
+
+/// @nodoc
+class __$$CardValue_DateTimeImplCopyWithImpl<$Res> extends _$CardValueCopyWithImpl<$Res, _$CardValue_DateTimeImpl>
+    implements _$$CardValue_DateTimeImplCopyWith<$Res> {
+  __$$CardValue_DateTimeImplCopyWithImpl(_$CardValue_DateTimeImpl _value, $Res Function(_$CardValue_DateTimeImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? value = null,
+  }) {
+    return _then(_$CardValue_DateTimeImpl(
+      value: null == value
+          ? _value.value
+          : value // ignore: cast_nullable_to_non_nullable
+              as String,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$CardValue_DateTimeImpl implements CardValue_DateTime {
+  const _$CardValue_DateTimeImpl({required this.value});
+
+  @override
+  final String value;
+
+  @override
+  String toString() {
+    return 'CardValue.dateTime(value: $value)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$CardValue_DateTimeImpl &&
+            (identical(other.value, value) || other.value == value));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, value);
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$CardValue_DateTimeImplCopyWith<_$CardValue_DateTimeImpl> get copyWith =>
+      __$$CardValue_DateTimeImplCopyWithImpl<_$CardValue_DateTimeImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String value) string,
+    required TResult Function(bool value) boolean,
+    required TResult Function(String value) date,
+    required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
+  }) {
+    return dateTime(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String value)? string,
+    TResult? Function(bool value)? boolean,
+    TResult? Function(String value)? date,
+    TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
+  }) {
+    return dateTime?.call(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String value)? string,
+    TResult Function(bool value)? boolean,
+    TResult Function(String value)? date,
+    TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (dateTime != null) {
+      return dateTime(value);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(CardValue_String value) string,
+    required TResult Function(CardValue_Boolean value) boolean,
+    required TResult Function(CardValue_Date value) date,
+    required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
+  }) {
+    return dateTime(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(CardValue_String value)? string,
+    TResult? Function(CardValue_Boolean value)? boolean,
+    TResult? Function(CardValue_Date value)? date,
+    TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
+  }) {
+    return dateTime?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(CardValue_String value)? string,
+    TResult Function(CardValue_Boolean value)? boolean,
+    TResult Function(CardValue_Date value)? date,
+    TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (dateTime != null) {
+      return dateTime(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class CardValue_DateTime implements CardValue {
+  const factory CardValue_DateTime({required final String value}) = _$CardValue_DateTimeImpl;
+
+  @override
+  String get value;
+  @JsonKey(ignore: true)
+  _$$CardValue_DateTimeImplCopyWith<_$CardValue_DateTimeImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$CardValue_ImageImplCopyWith<$Res> {
+  factory _$$CardValue_ImageImplCopyWith(_$CardValue_ImageImpl value, $Res Function(_$CardValue_ImageImpl) then) =
+      __$$CardValue_ImageImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({Uint8List value});
+}
+
+/// @nodoc
+class __$$CardValue_ImageImplCopyWithImpl<$Res> extends _$CardValueCopyWithImpl<$Res, _$CardValue_ImageImpl>
+    implements _$$CardValue_ImageImplCopyWith<$Res> {
+  __$$CardValue_ImageImplCopyWithImpl(_$CardValue_ImageImpl _value, $Res Function(_$CardValue_ImageImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? value = null,
+  }) {
+    return _then(_$CardValue_ImageImpl(
+      value: null == value
+          ? _value.value
+          : value // ignore: cast_nullable_to_non_nullable
+              as Uint8List,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$CardValue_ImageImpl implements CardValue_Image {
+  const _$CardValue_ImageImpl({required this.value});
+
+  @override
+  final Uint8List value;
+
+  @override
+  String toString() {
+    return 'CardValue.image(value: $value)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$CardValue_ImageImpl &&
+            const DeepCollectionEquality().equals(other.value, value));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, const DeepCollectionEquality().hash(value));
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$CardValue_ImageImplCopyWith<_$CardValue_ImageImpl> get copyWith =>
+      __$$CardValue_ImageImplCopyWithImpl<_$CardValue_ImageImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String value) string,
+    required TResult Function(bool value) boolean,
+    required TResult Function(String value) date,
+    required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
+  }) {
+    return image(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String value)? string,
+    TResult? Function(bool value)? boolean,
+    TResult? Function(String value)? date,
+    TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
+  }) {
+    return image?.call(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String value)? string,
+    TResult Function(bool value)? boolean,
+    TResult Function(String value)? date,
+    TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (image != null) {
+      return image(value);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(CardValue_String value) string,
+    required TResult Function(CardValue_Boolean value) boolean,
+    required TResult Function(CardValue_Date value) date,
+    required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
+  }) {
+    return image(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(CardValue_String value)? string,
+    TResult? Function(CardValue_Boolean value)? boolean,
+    TResult? Function(CardValue_Date value)? date,
+    TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
+  }) {
+    return image?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(CardValue_String value)? string,
+    TResult Function(CardValue_Boolean value)? boolean,
+    TResult Function(CardValue_Date value)? date,
+    TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (image != null) {
+      return image(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class CardValue_Image implements CardValue {
+  const factory CardValue_Image({required final Uint8List value}) = _$CardValue_ImageImpl;
+
+  @override
+  Uint8List get value;
+  @JsonKey(ignore: true)
+  _$$CardValue_ImageImplCopyWith<_$CardValue_ImageImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$CardValue_BytesImplCopyWith<$Res> {
+  factory _$$CardValue_BytesImplCopyWith(_$CardValue_BytesImpl value, $Res Function(_$CardValue_BytesImpl) then) =
+      __$$CardValue_BytesImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({Uint8List value});
+}
+
+/// @nodoc
+class __$$CardValue_BytesImplCopyWithImpl<$Res> extends _$CardValueCopyWithImpl<$Res, _$CardValue_BytesImpl>
+    implements _$$CardValue_BytesImplCopyWith<$Res> {
+  __$$CardValue_BytesImplCopyWithImpl(_$CardValue_BytesImpl _value, $Res Function(_$CardValue_BytesImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? value = null,
+  }) {
+    return _then(_$CardValue_BytesImpl(
+      value: null == value
+          ? _value.value
+          : value // ignore: cast_nullable_to_non_nullable
+              as Uint8List,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$CardValue_BytesImpl implements CardValue_Bytes {
+  const _$CardValue_BytesImpl({required this.value});
+
+  @override
+  final Uint8List value;
+
+  @override
+  String toString() {
+    return 'CardValue.bytes(value: $value)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$CardValue_BytesImpl &&
+            const DeepCollectionEquality().equals(other.value, value));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, const DeepCollectionEquality().hash(value));
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$CardValue_BytesImplCopyWith<_$CardValue_BytesImpl> get copyWith =>
+      __$$CardValue_BytesImplCopyWithImpl<_$CardValue_BytesImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String value) string,
+    required TResult Function(bool value) boolean,
+    required TResult Function(String value) date,
+    required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
+  }) {
+    return bytes(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String value)? string,
+    TResult? Function(bool value)? boolean,
+    TResult? Function(String value)? date,
+    TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
+  }) {
+    return bytes?.call(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String value)? string,
+    TResult Function(bool value)? boolean,
+    TResult Function(String value)? date,
+    TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (bytes != null) {
+      return bytes(value);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(CardValue_String value) string,
+    required TResult Function(CardValue_Boolean value) boolean,
+    required TResult Function(CardValue_Date value) date,
+    required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
+  }) {
+    return bytes(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(CardValue_String value)? string,
+    TResult? Function(CardValue_Boolean value)? boolean,
+    TResult? Function(CardValue_Date value)? date,
+    TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
+  }) {
+    return bytes?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(CardValue_String value)? string,
+    TResult Function(CardValue_Boolean value)? boolean,
+    TResult Function(CardValue_Date value)? date,
+    TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (bytes != null) {
+      return bytes(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class CardValue_Bytes implements CardValue {
+  const factory CardValue_Bytes({required final Uint8List value}) = _$CardValue_BytesImpl;
+
+  @override
+  Uint8List get value;
+  @JsonKey(ignore: true)
+  _$$CardValue_BytesImplCopyWith<_$CardValue_BytesImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$CardValue_ArrayImplCopyWith<$Res> {
+  factory _$$CardValue_ArrayImplCopyWith(_$CardValue_ArrayImpl value, $Res Function(_$CardValue_ArrayImpl) then) =
+      __$$CardValue_ArrayImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({List<CardValue> value});
+}
+
+/// @nodoc
+class __$$CardValue_ArrayImplCopyWithImpl<$Res> extends _$CardValueCopyWithImpl<$Res, _$CardValue_ArrayImpl>
+    implements _$$CardValue_ArrayImplCopyWith<$Res> {
+  __$$CardValue_ArrayImplCopyWithImpl(_$CardValue_ArrayImpl _value, $Res Function(_$CardValue_ArrayImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? value = null,
+  }) {
+    return _then(_$CardValue_ArrayImpl(
+      value: null == value
+          ? _value._value
+          : value // ignore: cast_nullable_to_non_nullable
+              as List<CardValue>,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$CardValue_ArrayImpl implements CardValue_Array {
+  const _$CardValue_ArrayImpl({required final List<CardValue> value}) : _value = value;
+
+  final List<CardValue> _value;
+  @override
+  List<CardValue> get value {
+    if (_value is EqualUnmodifiableListView) return _value;
+    // ignore: implicit_dynamic_type
+    return EqualUnmodifiableListView(_value);
+  }
+
+  @override
+  String toString() {
+    return 'CardValue.array(value: $value)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$CardValue_ArrayImpl &&
+            const DeepCollectionEquality().equals(other._value, _value));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, const DeepCollectionEquality().hash(_value));
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$CardValue_ArrayImplCopyWith<_$CardValue_ArrayImpl> get copyWith =>
+      __$$CardValue_ArrayImplCopyWithImpl<_$CardValue_ArrayImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String value) string,
+    required TResult Function(bool value) boolean,
+    required TResult Function(String value) date,
+    required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
+  }) {
+    return array(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String value)? string,
+    TResult? Function(bool value)? boolean,
+    TResult? Function(String value)? date,
+    TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
+  }) {
+    return array?.call(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String value)? string,
+    TResult Function(bool value)? boolean,
+    TResult Function(String value)? date,
+    TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (array != null) {
+      return array(value);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(CardValue_String value) string,
+    required TResult Function(CardValue_Boolean value) boolean,
+    required TResult Function(CardValue_Date value) date,
+    required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
+  }) {
+    return array(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(CardValue_String value)? string,
+    TResult? Function(CardValue_Boolean value)? boolean,
+    TResult? Function(CardValue_Date value)? date,
+    TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
+  }) {
+    return array?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(CardValue_String value)? string,
+    TResult Function(CardValue_Boolean value)? boolean,
+    TResult Function(CardValue_Date value)? date,
+    TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (array != null) {
+      return array(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class CardValue_Array implements CardValue {
+  const factory CardValue_Array({required final List<CardValue> value}) = _$CardValue_ArrayImpl;
+
+  @override
+  List<CardValue> get value;
+  @JsonKey(ignore: true)
+  _$$CardValue_ArrayImplCopyWith<_$CardValue_ArrayImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$CardValue_DictionaryImplCopyWith<$Res> {
+  factory _$$CardValue_DictionaryImplCopyWith(
+          _$CardValue_DictionaryImpl value, $Res Function(_$CardValue_DictionaryImpl) then) =
+      __$$CardValue_DictionaryImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({List<CardValueDictionaryEntry> value});
+}
+
+/// @nodoc
+class __$$CardValue_DictionaryImplCopyWithImpl<$Res> extends _$CardValueCopyWithImpl<$Res, _$CardValue_DictionaryImpl>
+    implements _$$CardValue_DictionaryImplCopyWith<$Res> {
+  __$$CardValue_DictionaryImplCopyWithImpl(
+      _$CardValue_DictionaryImpl _value, $Res Function(_$CardValue_DictionaryImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? value = null,
+  }) {
+    return _then(_$CardValue_DictionaryImpl(
+      value: null == value
+          ? _value._value
+          : value // ignore: cast_nullable_to_non_nullable
+              as List<CardValueDictionaryEntry>,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$CardValue_DictionaryImpl implements CardValue_Dictionary {
+  const _$CardValue_DictionaryImpl({required final List<CardValueDictionaryEntry> value}) : _value = value;
+
+  final List<CardValueDictionaryEntry> _value;
+  @override
+  List<CardValueDictionaryEntry> get value {
+    if (_value is EqualUnmodifiableListView) return _value;
+    // ignore: implicit_dynamic_type
+    return EqualUnmodifiableListView(_value);
+  }
+
+  @override
+  String toString() {
+    return 'CardValue.dictionary(value: $value)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$CardValue_DictionaryImpl &&
+            const DeepCollectionEquality().equals(other._value, _value));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, const DeepCollectionEquality().hash(_value));
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$CardValue_DictionaryImplCopyWith<_$CardValue_DictionaryImpl> get copyWith =>
+      __$$CardValue_DictionaryImplCopyWithImpl<_$CardValue_DictionaryImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String value) string,
+    required TResult Function(bool value) boolean,
+    required TResult Function(String value) date,
+    required TResult Function(GenderCardValue value) gender,
+    required TResult Function(int value) integer,
+    required TResult Function(String value) dateTime,
+    required TResult Function(Uint8List value) image,
+    required TResult Function(Uint8List value) bytes,
+    required TResult Function(List<CardValue> value) array,
+    required TResult Function(List<CardValueDictionaryEntry> value) dictionary,
+  }) {
+    return dictionary(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String value)? string,
+    TResult? Function(bool value)? boolean,
+    TResult? Function(String value)? date,
+    TResult? Function(GenderCardValue value)? gender,
+    TResult? Function(int value)? integer,
+    TResult? Function(String value)? dateTime,
+    TResult? Function(Uint8List value)? image,
+    TResult? Function(Uint8List value)? bytes,
+    TResult? Function(List<CardValue> value)? array,
+    TResult? Function(List<CardValueDictionaryEntry> value)? dictionary,
+  }) {
+    return dictionary?.call(value);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String value)? string,
+    TResult Function(bool value)? boolean,
+    TResult Function(String value)? date,
+    TResult Function(GenderCardValue value)? gender,
+    TResult Function(int value)? integer,
+    TResult Function(String value)? dateTime,
+    TResult Function(Uint8List value)? image,
+    TResult Function(Uint8List value)? bytes,
+    TResult Function(List<CardValue> value)? array,
+    TResult Function(List<CardValueDictionaryEntry> value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (dictionary != null) {
+      return dictionary(value);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(CardValue_String value) string,
+    required TResult Function(CardValue_Boolean value) boolean,
+    required TResult Function(CardValue_Date value) date,
+    required TResult Function(CardValue_Gender value) gender,
+    required TResult Function(CardValue_Integer value) integer,
+    required TResult Function(CardValue_DateTime value) dateTime,
+    required TResult Function(CardValue_Image value) image,
+    required TResult Function(CardValue_Bytes value) bytes,
+    required TResult Function(CardValue_Array value) array,
+    required TResult Function(CardValue_Dictionary value) dictionary,
+  }) {
+    return dictionary(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(CardValue_String value)? string,
+    TResult? Function(CardValue_Boolean value)? boolean,
+    TResult? Function(CardValue_Date value)? date,
+    TResult? Function(CardValue_Gender value)? gender,
+    TResult? Function(CardValue_Integer value)? integer,
+    TResult? Function(CardValue_DateTime value)? dateTime,
+    TResult? Function(CardValue_Image value)? image,
+    TResult? Function(CardValue_Bytes value)? bytes,
+    TResult? Function(CardValue_Array value)? array,
+    TResult? Function(CardValue_Dictionary value)? dictionary,
+  }) {
+    return dictionary?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(CardValue_String value)? string,
+    TResult Function(CardValue_Boolean value)? boolean,
+    TResult Function(CardValue_Date value)? date,
+    TResult Function(CardValue_Gender value)? gender,
+    TResult Function(CardValue_Integer value)? integer,
+    TResult Function(CardValue_DateTime value)? dateTime,
+    TResult Function(CardValue_Image value)? image,
+    TResult Function(CardValue_Bytes value)? bytes,
+    TResult Function(CardValue_Array value)? array,
+    TResult Function(CardValue_Dictionary value)? dictionary,
+    required TResult orElse(),
+  }) {
+    if (dictionary != null) {
+      return dictionary(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class CardValue_Dictionary implements CardValue {
+  const factory CardValue_Dictionary({required final List<CardValueDictionaryEntry> value}) =
+      _$CardValue_DictionaryImpl;
+
+  @override
+  List<CardValueDictionaryEntry> get value;
+  @JsonKey(ignore: true)
+  _$$CardValue_DictionaryImplCopyWith<_$CardValue_DictionaryImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
 /// @nodoc
 mixin _$Image {
   @optionalTypeArgs
@@ -1289,16 +2551,568 @@ mixin _$Image {
     required TResult Function(String base64) png,
     required TResult Function(String base64) jpg,
     required TResult Function(String path) asset,
-  }) =>
-      throw _privateConstructorUsedError;
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String xml)? svg,
+    TResult? Function(String base64)? png,
+    TResult? Function(String base64)? jpg,
+    TResult? Function(String path)? asset,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String xml)? svg,
+    TResult Function(String base64)? png,
+    TResult Function(String base64)? jpg,
+    TResult Function(String path)? asset,
+    required TResult orElse(),
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(Image_Svg value) svg,
+    required TResult Function(Image_Png value) png,
+    required TResult Function(Image_Jpg value) jpg,
+    required TResult Function(Image_Asset value) asset,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(Image_Svg value)? svg,
+    TResult? Function(Image_Png value)? png,
+    TResult? Function(Image_Jpg value)? jpg,
+    TResult? Function(Image_Asset value)? asset,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(Image_Svg value)? svg,
+    TResult Function(Image_Png value)? png,
+    TResult Function(Image_Jpg value)? jpg,
+    TResult Function(Image_Asset value)? asset,
+    required TResult orElse(),
+  }) =>
+      throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class $ImageCopyWith<$Res> {
+  factory $ImageCopyWith(Image value, $Res Function(Image) then) = _$ImageCopyWithImpl<$Res, Image>;
+}
+
+/// @nodoc
+class _$ImageCopyWithImpl<$Res, $Val extends Image> implements $ImageCopyWith<$Res> {
+  _$ImageCopyWithImpl(this._value, this._then);
+
+  // ignore: unused_field
+  final $Val _value;
+  // ignore: unused_field
+  final $Res Function($Val) _then;
+}
+
+/// @nodoc
+abstract class _$$Image_SvgImplCopyWith<$Res> {
+  factory _$$Image_SvgImplCopyWith(_$Image_SvgImpl value, $Res Function(_$Image_SvgImpl) then) =
+      __$$Image_SvgImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({String xml});
+}
+
+/// @nodoc
+class __$$Image_SvgImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_SvgImpl>
+    implements _$$Image_SvgImplCopyWith<$Res> {
+  __$$Image_SvgImplCopyWithImpl(_$Image_SvgImpl _value, $Res Function(_$Image_SvgImpl) _then) : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? xml = null,
+  }) {
+    return _then(_$Image_SvgImpl(
+      xml: null == xml
+          ? _value.xml
+          : xml // ignore: cast_nullable_to_non_nullable
+              as String,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$Image_SvgImpl implements Image_Svg {
+  const _$Image_SvgImpl({required this.xml});
+
+  @override
+  final String xml;
+
+  @override
+  String toString() {
+    return 'Image.svg(xml: $xml)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$Image_SvgImpl &&
+            (identical(other.xml, xml) || other.xml == xml));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, xml);
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$Image_SvgImplCopyWith<_$Image_SvgImpl> get copyWith =>
+      __$$Image_SvgImplCopyWithImpl<_$Image_SvgImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String xml) svg,
+    required TResult Function(String base64) png,
+    required TResult Function(String base64) jpg,
+    required TResult Function(String path) asset,
+  }) {
+    return svg(xml);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String xml)? svg,
+    TResult? Function(String base64)? png,
+    TResult? Function(String base64)? jpg,
+    TResult? Function(String path)? asset,
+  }) {
+    return svg?.call(xml);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String xml)? svg,
+    TResult Function(String base64)? png,
+    TResult Function(String base64)? jpg,
+    TResult Function(String path)? asset,
+    required TResult orElse(),
+  }) {
+    if (svg != null) {
+      return svg(xml);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(Image_Svg value) svg,
+    required TResult Function(Image_Png value) png,
+    required TResult Function(Image_Jpg value) jpg,
+    required TResult Function(Image_Asset value) asset,
+  }) {
+    return svg(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(Image_Svg value)? svg,
+    TResult? Function(Image_Png value)? png,
+    TResult? Function(Image_Jpg value)? jpg,
+    TResult? Function(Image_Asset value)? asset,
+  }) {
+    return svg?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(Image_Svg value)? svg,
+    TResult Function(Image_Png value)? png,
+    TResult Function(Image_Jpg value)? jpg,
+    TResult Function(Image_Asset value)? asset,
+    required TResult orElse(),
+  }) {
+    if (svg != null) {
+      return svg(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class Image_Svg implements Image {
+  const factory Image_Svg({required final String xml}) = _$Image_SvgImpl;
+
+  String get xml;
+  @JsonKey(ignore: true)
+  _$$Image_SvgImplCopyWith<_$Image_SvgImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$Image_PngImplCopyWith<$Res> {
+  factory _$$Image_PngImplCopyWith(_$Image_PngImpl value, $Res Function(_$Image_PngImpl) then) =
+      __$$Image_PngImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({String base64});
+}
+
+/// @nodoc
+class __$$Image_PngImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_PngImpl>
+    implements _$$Image_PngImplCopyWith<$Res> {
+  __$$Image_PngImplCopyWithImpl(_$Image_PngImpl _value, $Res Function(_$Image_PngImpl) _then) : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? base64 = null,
+  }) {
+    return _then(_$Image_PngImpl(
+      base64: null == base64
+          ? _value.base64
+          : base64 // ignore: cast_nullable_to_non_nullable
+              as String,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$Image_PngImpl implements Image_Png {
+  const _$Image_PngImpl({required this.base64});
+
+  @override
+  final String base64;
+
+  @override
+  String toString() {
+    return 'Image.png(base64: $base64)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$Image_PngImpl &&
+            (identical(other.base64, base64) || other.base64 == base64));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, base64);
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$Image_PngImplCopyWith<_$Image_PngImpl> get copyWith =>
+      __$$Image_PngImplCopyWithImpl<_$Image_PngImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String xml) svg,
+    required TResult Function(String base64) png,
+    required TResult Function(String base64) jpg,
+    required TResult Function(String path) asset,
+  }) {
+    return png(base64);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String xml)? svg,
+    TResult? Function(String base64)? png,
+    TResult? Function(String base64)? jpg,
+    TResult? Function(String path)? asset,
+  }) {
+    return png?.call(base64);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String xml)? svg,
+    TResult Function(String base64)? png,
+    TResult Function(String base64)? jpg,
+    TResult Function(String path)? asset,
+    required TResult orElse(),
+  }) {
+    if (png != null) {
+      return png(base64);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(Image_Svg value) svg,
+    required TResult Function(Image_Png value) png,
+    required TResult Function(Image_Jpg value) jpg,
+    required TResult Function(Image_Asset value) asset,
+  }) {
+    return png(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(Image_Svg value)? svg,
+    TResult? Function(Image_Png value)? png,
+    TResult? Function(Image_Jpg value)? jpg,
+    TResult? Function(Image_Asset value)? asset,
+  }) {
+    return png?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(Image_Svg value)? svg,
+    TResult Function(Image_Png value)? png,
+    TResult Function(Image_Jpg value)? jpg,
+    TResult Function(Image_Asset value)? asset,
+    required TResult orElse(),
+  }) {
+    if (png != null) {
+      return png(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class Image_Png implements Image {
+  const factory Image_Png({required final String base64}) = _$Image_PngImpl;
+
+  String get base64;
+  @JsonKey(ignore: true)
+  _$$Image_PngImplCopyWith<_$Image_PngImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$Image_JpgImplCopyWith<$Res> {
+  factory _$$Image_JpgImplCopyWith(_$Image_JpgImpl value, $Res Function(_$Image_JpgImpl) then) =
+      __$$Image_JpgImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({String base64});
+}
+
+/// @nodoc
+class __$$Image_JpgImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_JpgImpl>
+    implements _$$Image_JpgImplCopyWith<$Res> {
+  __$$Image_JpgImplCopyWithImpl(_$Image_JpgImpl _value, $Res Function(_$Image_JpgImpl) _then) : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? base64 = null,
+  }) {
+    return _then(_$Image_JpgImpl(
+      base64: null == base64
+          ? _value.base64
+          : base64 // ignore: cast_nullable_to_non_nullable
+              as String,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$Image_JpgImpl implements Image_Jpg {
+  const _$Image_JpgImpl({required this.base64});
+
+  @override
+  final String base64;
+
+  @override
+  String toString() {
+    return 'Image.jpg(base64: $base64)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$Image_JpgImpl &&
+            (identical(other.base64, base64) || other.base64 == base64));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, base64);
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$Image_JpgImplCopyWith<_$Image_JpgImpl> get copyWith =>
+      __$$Image_JpgImplCopyWithImpl<_$Image_JpgImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String xml) svg,
+    required TResult Function(String base64) png,
+    required TResult Function(String base64) jpg,
+    required TResult Function(String path) asset,
+  }) {
+    return jpg(base64);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String xml)? svg,
+    TResult? Function(String base64)? png,
+    TResult? Function(String base64)? jpg,
+    TResult? Function(String path)? asset,
+  }) {
+    return jpg?.call(base64);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String xml)? svg,
+    TResult Function(String base64)? png,
+    TResult Function(String base64)? jpg,
+    TResult Function(String path)? asset,
+    required TResult orElse(),
+  }) {
+    if (jpg != null) {
+      return jpg(base64);
+    }
+    return orElse();
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(Image_Svg value) svg,
+    required TResult Function(Image_Png value) png,
+    required TResult Function(Image_Jpg value) jpg,
+    required TResult Function(Image_Asset value) asset,
+  }) {
+    return jpg(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(Image_Svg value)? svg,
+    TResult? Function(Image_Png value)? png,
+    TResult? Function(Image_Jpg value)? jpg,
+    TResult? Function(Image_Asset value)? asset,
+  }) {
+    return jpg?.call(this);
+  }
+
+  @override
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(Image_Svg value)? svg,
+    TResult Function(Image_Png value)? png,
+    TResult Function(Image_Jpg value)? jpg,
+    TResult Function(Image_Asset value)? asset,
+    required TResult orElse(),
+  }) {
+    if (jpg != null) {
+      return jpg(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class Image_Jpg implements Image {
+  const factory Image_Jpg({required final String base64}) = _$Image_JpgImpl;
+
+  String get base64;
+  @JsonKey(ignore: true)
+  _$$Image_JpgImplCopyWith<_$Image_JpgImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class _$$Image_AssetImplCopyWith<$Res> {
+  factory _$$Image_AssetImplCopyWith(_$Image_AssetImpl value, $Res Function(_$Image_AssetImpl) then) =
+      __$$Image_AssetImplCopyWithImpl<$Res>;
+  @useResult
+  $Res call({String path});
+}
+
+/// @nodoc
+class __$$Image_AssetImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_AssetImpl>
+    implements _$$Image_AssetImplCopyWith<$Res> {
+  __$$Image_AssetImplCopyWithImpl(_$Image_AssetImpl _value, $Res Function(_$Image_AssetImpl) _then)
+      : super(_value, _then);
+
+  @pragma('vm:prefer-inline')
+  @override
+  $Res call({
+    Object? path = null,
+  }) {
+    return _then(_$Image_AssetImpl(
+      path: null == path
+          ? _value.path
+          : path // ignore: cast_nullable_to_non_nullable
+              as String,
+    ));
+  }
+}
+
+/// @nodoc
+
+class _$Image_AssetImpl implements Image_Asset {
+  const _$Image_AssetImpl({required this.path});
+
+  @override
+  final String path;
+
+  @override
+  String toString() {
+    return 'Image.asset(path: $path)';
+  }
+
+  @override
+  bool operator ==(Object other) {
+    return identical(this, other) ||
+        (other.runtimeType == runtimeType &&
+            other is _$Image_AssetImpl &&
+            (identical(other.path, path) || other.path == path));
+  }
+
+  @override
+  int get hashCode => Object.hash(runtimeType, path);
+
+  @JsonKey(ignore: true)
+  @override
+  @pragma('vm:prefer-inline')
+  _$$Image_AssetImplCopyWith<_$Image_AssetImpl> get copyWith =>
+      __$$Image_AssetImplCopyWithImpl<_$Image_AssetImpl>(this, _$identity);
+
+  @override
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String xml) svg,
+    required TResult Function(String base64) png,
+    required TResult Function(String base64) jpg,
+    required TResult Function(String path) asset,
+  }) {
+    return asset(path);
+  }
+
+  @override
   @optionalTypeArgs
   TResult? whenOrNull<TResult extends Object?>({
     TResult? Function(String xml)? svg,
     TResult? Function(String base64)? png,
     TResult? Function(String base64)? jpg,
     TResult? Function(String path)? asset,
-  }) =>
-      throw _privateConstructorUsedError;
+  }) {
+    return asset?.call(path);
+  }
+
+  @override
   @optionalTypeArgs
   TResult maybeWhen<TResult extends Object?>({
     TResult Function(String xml)? svg,
@@ -1306,24 +3120,36 @@ mixin _$Image {
     TResult Function(String base64)? jpg,
     TResult Function(String path)? asset,
     required TResult orElse(),
-  }) =>
-      throw _privateConstructorUsedError;
+  }) {
+    if (asset != null) {
+      return asset(path);
+    }
+    return orElse();
+  }
+
+  @override
   @optionalTypeArgs
   TResult map<TResult extends Object?>({
     required TResult Function(Image_Svg value) svg,
     required TResult Function(Image_Png value) png,
     required TResult Function(Image_Jpg value) jpg,
     required TResult Function(Image_Asset value) asset,
-  }) =>
-      throw _privateConstructorUsedError;
+  }) {
+    return asset(this);
+  }
+
+  @override
   @optionalTypeArgs
   TResult? mapOrNull<TResult extends Object?>({
     TResult? Function(Image_Svg value)? svg,
     TResult? Function(Image_Png value)? png,
     TResult? Function(Image_Jpg value)? jpg,
     TResult? Function(Image_Asset value)? asset,
-  }) =>
-      throw _privateConstructorUsedError;
+  }) {
+    return asset?.call(this);
+  }
+
+  @override
   @optionalTypeArgs
   TResult maybeMap<TResult extends Object?>({
     TResult Function(Image_Svg value)? svg,
@@ -1331,18 +3157,74 @@ mixin _$Image {
     TResult Function(Image_Jpg value)? jpg,
     TResult Function(Image_Asset value)? asset,
     required TResult orElse(),
+  }) {
+    if (asset != null) {
+      return asset(this);
+    }
+    return orElse();
+  }
+}
+
+abstract class Image_Asset implements Image {
+  const factory Image_Asset({required final String path}) = _$Image_AssetImpl;
+
+  String get path;
+  @JsonKey(ignore: true)
+  _$$Image_AssetImplCopyWith<_$Image_AssetImpl> get copyWith => throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+mixin _$LinkedDevicesResult {
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(List<LinkedDevice> devices) ok,
+    required TResult Function(WalletInstructionError error) instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(List<LinkedDevice> devices)? ok,
+    TResult? Function(WalletInstructionError error)? instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(List<LinkedDevice> devices)? ok,
+    TResult Function(WalletInstructionError error)? instructionError,
+    required TResult orElse(),
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(LinkedDevicesResult_Ok value) ok,
+    required TResult Function(LinkedDevicesResult_InstructionError value) instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(LinkedDevicesResult_Ok value)? ok,
+    TResult? Function(LinkedDevicesResult_InstructionError value)? instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(LinkedDevicesResult_Ok value)? ok,
+    TResult Function(LinkedDevicesResult_InstructionError value)? instructionError,
+    required TResult orElse(),
   }) =>
       throw _privateConstructorUsedError;
 }
 
 /// @nodoc
-abstract class $ImageCopyWith<$Res> {
-  factory $ImageCopyWith(Image value, $Res Function(Image) then) = _$ImageCopyWithImpl<$Res, Image>;
+abstract class $LinkedDevicesResultCopyWith<$Res> {
+  factory $LinkedDevicesResultCopyWith(LinkedDevicesResult value, $Res Function(LinkedDevicesResult) then) =
+      _$LinkedDevicesResultCopyWithImpl<$Res, LinkedDevicesResult>;
 }
 
 /// @nodoc
-class _$ImageCopyWithImpl<$Res, $Val extends Image> implements $ImageCopyWith<$Res> {
-  _$ImageCopyWithImpl(this._value, this._then);
+class _$LinkedDevicesResultCopyWithImpl<$Res, $Val extends LinkedDevicesResult>
+    implements $LinkedDevicesResultCopyWith<$Res> {
+  _$LinkedDevicesResultCopyWithImpl(this._value, this._then);
 
   // ignore: unused_field
   final $Val _value;
@@ -1351,95 +3233,98 @@ class _$ImageCopyWithImpl<$Res, $Val extends Image> implements $ImageCopyWith<$R
 }
 
 /// @nodoc
-abstract class _$$Image_SvgImplCopyWith<$Res> {
-  factory _$$Image_SvgImplCopyWith(_$Image_SvgImpl value, $Res Function(_$Image_SvgImpl) then) =
-      __$$Image_SvgImplCopyWithImpl<$Res>;
+abstract class _$$LinkedDevicesResult_OkImplCopyWith<$Res> {
+  factory _$$LinkedDevicesResult_OkImplCopyWith(
+          _$LinkedDevicesResult_OkImpl value, $Res Function(_$LinkedDevicesResult_OkImpl) then) =
+      __$$LinkedDevicesResult_OkImplCopyWithImpl<$Res>;
   @useResult
-  $Res call({String xml});
+  $Res call({List<LinkedDevice> devices});
 }
 
 /// @nodoc
-class __$$Image_SvgImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_SvgImpl>
-    implements _$$Image_SvgImplCopyWith<$Res> {
-  __$$Image_SvgImplCopyWithImpl(_$Image_SvgImpl _value, $Res Function(_$Image_SvgImpl) _then) : super(_value, _then);
+class __$$LinkedDevicesResult_OkImplCopyWithImpl<$Res>
+    extends _$LinkedDevicesResultCopyWithImpl<$Res, _$LinkedDevicesResult_OkImpl>
+    implements _$$LinkedDevicesResult_OkImplCopyWith<$Res> {
+  __$$LinkedDevicesResult_OkImplCopyWithImpl(
+      _$LinkedDevicesResult_OkImpl _value, $Res Function(_$LinkedDevicesResult_OkImpl) _then)
+      : super(_value, _then);
 
   @pragma('vm:prefer-inline')
   @override
   $Res call({
-    Object? xml = null,
+    Object? devices = null,
   }) {
-    return _then(_$Image_SvgImpl(
-      xml: null == xml
-          ? _value.xml
-          : xml // ignore: cast_nullable_to_non_nullable
-              as String,
+    return _then(_$LinkedDevicesResult_OkImpl(
+      devices: null == devices
+          ? _value._devices
+          : devices // ignore: cast_nullable_to_non_nullable
+              as List<LinkedDevice>,
     ));
   }
 }
 
 /// @nodoc
 
-class _$Image_SvgImpl implements Image_Svg {
-  const _$Image_SvgImpl({required this.xml});
+class _$LinkedDevicesResult_OkImpl implements LinkedDevicesResult_Ok {
+  const _$LinkedDevicesResult_OkImpl({required final List<LinkedDevice> devices}) : _devices = devices;
 
+  final List<LinkedDevice> _devices;
   @override
-  final String xml;
+  List<LinkedDevice> get devices {
+    if (_devices is EqualUnmodifiableListView) return _devices;
+    // ignore: implicit_dynamic_type
+    return EqualUnmodifiableListView(_devices);
+  }
 
   @override
   String toString() {
-    return 'Image.svg(xml: $xml)';
+    return 'LinkedDevicesResult.ok(devices: $devices)';
   }
 
   @override
   bool operator ==(Object other) {
     return identical(this, other) ||
         (other.runtimeType == runtimeType &&
-            other is _$Image_SvgImpl &&
-            (identical(other.xml, xml) || other.xml == xml));
+            other is _$LinkedDevicesResult_OkImpl &&
+            const DeepCollectionEquality().equals(other._devices, _devices));
   }
 
   @override
-  int get hashCode => Object.hash(runtimeType, xml);
+  int get hashCode => Object.hash(runtimeType, const DeepCollectionEquality().hash(_devices));
 
   @JsonKey(ignore: true)
   @override
   @pragma('vm:prefer-inline')
-  _$$Image_SvgImplCopyWith<_$Image_SvgImpl> get copyWith =>
-      __$$Image_SvgImplCopyWithImpl<_$Image_SvgImpl>(this, _$identity);
+  _$$LinkedDevicesResult_OkImplCopyWith<_$LinkedDevicesResult_OkImpl> get copyWith =>
+      __$$LinkedDevicesResult_OkImplCopyWithImpl<_$LinkedDevicesResult_OkImpl>(this, _$identity);
 
   @override
   @optionalTypeArgs
   TResult when<TResult extends Object?>({
-    required TResult Function(String xml) svg,
-    required TResult Function(String base64) png,
-    required TResult Function(String base64) jpg,
-    required TResult Function(String path) asset,
+    required TResult Function(List<LinkedDevice> devices) ok,
+    required TResult Function(WalletInstructionError error) instructionError,
   }) {
-    return svg(xml);
+    return ok(devices);
   }
 
   @override
   @optionalTypeArgs
   TResult? whenOrNull<TResult extends Object?>({
-    TResult? Function(String xml)? svg,
-    TResult? Function(String base64)? png,
-    TResult? Function(String base64)? jpg,
-    TResult? Function(String path)? asset,
+    TResult? Function(List<LinkedDevice> devices)? ok,
+    TResult? Function(WalletInstructionError error)? instructionError,
   }) {
-    return svg?.call(xml);
+    return ok?.call(devices);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeWhen<TResult extends Object?>({
-    TResult Function(String xml)? svg,
-    TResult Function(String base64)? png,
-    TResult Function(String base64)? jpg,
-    TResult Function(String path)? asset,
+    TResult Function(List<LinkedDevice> devices)? ok,
+    TResult Function(WalletInstructionError error)? instructionError,
     required TResult orElse(),
   }) {
-    if (svg != null) {
-      return svg(xml);
+    if (ok != null) {
+      return ok(devices);
     }
     return orElse();
   }
@@ -1447,139 +3332,143 @@ class _$Image_SvgImpl implements Image_Svg {
   @override
   @optionalTypeArgs
   TResult map<TResult extends Object?>({
-    required TResult Function(Image_Svg value) svg,
-    required TResult Function(Image_Png value) png,
-    required TResult Function(Image_Jpg value) jpg,
-    required TResult Function(Image_Asset value) asset,
+    required TResult Function(LinkedDevicesResult_Ok value) ok,
+    required TResult Function(LinkedDevicesResult_InstructionError value) instructionError,
   }) {
-    return svg(this);
+    return ok(this);
   }
 
   @override
   @optionalTypeArgs
   TResult? mapOrNull<TResult extends Object?>({
-    TResult? Function(Image_Svg value)? svg,
-    TResult? Function(Image_Png value)? png,
-    TResult? Function(Image_Jpg value)? jpg,
-    TResult? Function(Image_Asset value)? asset,
+    TResult? Function(LinkedDevicesResult_Ok value)? ok,
+    TResult? Function(LinkedDevicesResult_InstructionError value)? instructionError,
   }) {
-    return svg?.call(this);
+    return ok?.call(this);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeMap<TResult extends Object?>({
-    TResult Function(Image_Svg value)? svg,
-    TResult Function(Image_Png value)? png,
-    TResult Function(Image_Jpg value)? jpg,
-    TResult Function(Image_Asset value)? asset,
+    TResult Function(LinkedDevicesResult_Ok value)? ok,
+    TResult Function(LinkedDevicesResult_InstructionError value)? instructionError,
     required TResult orElse(),
   }) {
-    if (svg != null) {
-      return svg(this);
+    if (ok != null) {
+      return ok(this);
     }
     return orElse();
   }
 }
 
-abstract class Image_Svg implements Image {
-  const factory Image_Svg({required final String xml}) = _$Image_SvgImpl;
+abstract class LinkedDevicesResult_Ok implements LinkedDevicesResult {
+  const factory LinkedDevicesResult_Ok({required final List<LinkedDevice> devices}) = _$LinkedDevicesResult_OkImpl;
 
-  String get xml;
+  List<LinkedDevice> get devices;
   @JsonKey(ignore: true)
-  _$$Image_SvgImplCopyWith<_$Image_SvgImpl> get copyWith => throw _privateConstructorUsedError;
+  _$$LinkedDevicesResult_OkImplCopyWith<_$LinkedDevicesResult_OkImpl> get copyWith =>
+      throw _privateConstructorUsedError;
 }
 
 /// @nodoc
-abstract class _$$Image_PngImplCopyWith<$Res> {
-  factory _$$Image_PngImplCopyWith(_$Image_PngImpl value, $Res Function(_$Image_PngImpl) then) =
-      __$$Image_PngImplCopyWithImpl<$Res>;
+abstract class _$$LinkedDevicesResult_InstructionErrorImplCopyWith<$Res> {
+  factory _$$LinkedDevicesResult_InstructionErrorImplCopyWith(_$LinkedDevicesResult_InstructionErrorImpl value,
+          $Res Function(_$LinkedDevicesResult_InstructionErrorImpl) then) =
+      __$$LinkedDevicesResult_InstructionErrorImplCopyWithImpl<$Res>;
   @useResult
-  $Res call({String base64});
+  $Res call({WalletInstructionError error});
+
+  $WalletInstructionErrorCopyWith<$Res> get error;
 }
 
 /// @nodoc
-class __$$Image_PngImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_PngImpl>
-    implements _$$Image_PngImplCopyWith<$Res> {
-  __$$Image_PngImplCopyWithImpl(_$Image_PngImpl _value, $Res Function(_$Image_PngImpl) _then) : super(_value, _then);
+class __$$LinkedDevicesResult_InstructionErrorImplCopyWithImpl<$Res>
+    extends _$LinkedDevicesResultCopyWithImpl<$Res, _$LinkedDevicesResult_InstructionErrorImpl>
+    implements _$$LinkedDevicesResult_InstructionErrorImplCopyWith<$Res> {
+  __$$LinkedDevicesResult_InstructionErrorImplCopyWithImpl(_$LinkedDevicesResult_InstructionErrorImpl _value,
+      $Res Function(_$LinkedDevicesResult_InstructionErrorImpl) _then)
+      : super(_value, _then);
 
   @pragma('vm:prefer-inline')
   @override
   $Res call({
-    Object? base64 = null,
+    Object? error = null,
   }) {
-    return _then(_$Image_PngImpl(
-      base64: null == base64
-          ? _value.base64
-          : base64 // ignore: cast_nullable_to_non_nullable
-              as String,
+    return _then(_$LinkedDevicesResult_InstructionErrorImpl(
+      error: null == error
+          ? _value.error
+          : error // ignore: cast_nullable_to_non_nullable
+              as WalletInstructionError,
     ));
   }
+
+  @override
+  @pragma('vm:prefer-inline')
+  $WalletInstructionErrorCopyWith<$Res> get error {
+    return $WalletInstructionErrorCopyWith<$Res>(_value.error, (value) {
+      return _then(_value.copyWith(error: value));
+    });
+  }
 }
 
 /// @nodoc
 
-class _$Image_PngImpl implements Image_Png {
-  const _$Image_PngImpl({required this.base64});
+class _$LinkedDevicesResult_InstructionErrorImpl implements LinkedDevicesResult_InstructionError {
+  const _$LinkedDevicesResult_InstructionErrorImpl({required this.error});
 
   @override
-  final String base64;
+  final WalletInstructionError error;
 
   @override
   String toString() {
-    return 'Image.png(base64: $base64)';
+    return 'LinkedDevicesResult.instructionError(error: $error)';
   }
 
   @override
   bool operator ==(Object other) {
     return identical(this, other) ||
         (other.runtimeType == runtimeType &&
-            other is _$Image_PngImpl &&
-            (identical(other.base64, base64) || other.base64 == base64));
+            other is _$LinkedDevicesResult_InstructionErrorImpl &&
+            (identical(other.error, error) || other.error == error));
   }
 
   @override
-  int get hashCode => Object.hash(runtimeType, base64);
+  int get hashCode => Object.hash(runtimeType, error);
 
   @JsonKey(ignore: true)
   @override
   @pragma('vm:prefer-inline')
-  _$$Image_PngImplCopyWith<_$Image_PngImpl> get copyWith =>
-      __$$Image_PngImplCopyWithImpl<_$Image_PngImpl>(this, _$identity);
+  _$$LinkedDevicesResult_InstructionErrorImplCopyWith<_$LinkedDevicesResult_InstructionErrorImpl> get copyWith =>
+      __$$LinkedDevicesResult_InstructionErrorImplCopyWithImpl<_$LinkedDevicesResult_InstructionErrorImpl>(
+          this, _$identity);
 
   @override
   @optionalTypeArgs
   TResult when<TResult extends Object?>({
-    required TResult Function(String xml) svg,
-    required TResult Function(String base64) png,
-    required TResult Function(String base64) jpg,
-    required TResult Function(String path) asset,
+    required TResult Function(List<LinkedDevice> devices) ok,
+    required TResult Function(WalletInstructionError error) instructionError,
   }) {
-    return png(base64);
+    return instructionError(error);
   }
 
   @override
   @optionalTypeArgs
   TResult? whenOrNull<TResult extends Object?>({
-    TResult? Function(String xml)? svg,
-    TResult? Function(String base64)? png,
-    TResult? Function(String base64)? jpg,
-    TResult? Function(String path)? asset,
+    TResult? Function(List<LinkedDevice> devices)? ok,
+    TResult? Function(WalletInstructionError error)? instructionError,
   }) {
-    return png?.call(base64);
+    return instructionError?.call(error);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeWhen<TResult extends Object?>({
-    TResult Function(String xml)? svg,
-    TResult Function(String base64)? png,
-    TResult Function(String base64)? jpg,
-    TResult Function(String path)? asset,
+    TResult Function(List<LinkedDevice> devices)? ok,
+    TResult Function(WalletInstructionError error)? instructionError,
     required TResult orElse(),
   }) {
-    if (png != null) {
-      return png(base64);
+    if (instructionError != null) {
+      return instructionError(error);
     }
     return orElse();
   }
@@ -1587,71 +3476,131 @@ class _$Image_PngImpl implements Image_Png {
   @override
   @optionalTypeArgs
   TResult map<TResult extends Object?>({
-    required TResult Function(Image_Svg value) svg,
-    required TResult Function(Image_Png value) png,
-    required TResult Function(Image_Jpg value) jpg,
-    required TResult Function(Image_Asset value) asset,
+    required TResult Function(LinkedDevicesResult_Ok value) ok,
+    required TResult Function(LinkedDevicesResult_InstructionError value) instructionError,
   }) {
-    return png(this);
+    return instructionError(this);
   }
 
   @override
   @optionalTypeArgs
   TResult? mapOrNull<TResult extends Object?>({
-    TResult? Function(Image_Svg value)? svg,
-    TResult? Function(Image_Png value)? png,
-    TResult? Function(Image_Jpg value)? jpg,
-    TResult? Function(Image_Asset value)? asset,
+    TResult? Function(LinkedDevicesResult_Ok value)? ok,
+    TResult? Function(LinkedDevicesResult_InstructionError value)? instructionError,
   }) {
-    return png?.call(this);
+    return instructionError?.call(this);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeMap<TResult extends Object?>({
-    TResult Function(Image_Svg value)? svg,
-    TResult Function(Image_Png value)? png,
-    TResult Function(Image_Jpg value)? jpg,
-    TResult Function(Image_Asset value)? asset,
+    TResult Function(LinkedDevicesResult_Ok value)? ok,
+    TResult Function(LinkedDevicesResult_InstructionError value)? instructionError,
     required TResult orElse(),
   }) {
-    if (png != null) {
-      return png(this);
+    if (instructionError != null) {
+      return instructionError(this);
     }
     return orElse();
   }
 }
 
-abstract class Image_Png implements Image {
-  const factory Image_Png({required final String base64}) = _$Image_PngImpl;
+abstract class LinkedDevicesResult_InstructionError implements LinkedDevicesResult {
+  const factory LinkedDevicesResult_InstructionError({required final WalletInstructionError error}) =
+      _$LinkedDevicesResult_InstructionErrorImpl;
 
-  String get base64;
+  WalletInstructionError get error;
   @JsonKey(ignore: true)
-  _$$Image_PngImplCopyWith<_$Image_PngImpl> get copyWith => throw _privateConstructorUsedError;
+  _$$LinkedDevicesResult_InstructionErrorImplCopyWith<_$LinkedDevicesResult_InstructionErrorImpl> get copyWith =>
+      throw _privateConstructorUsedError;
 }
 
+
 /// @nodoc
-abstract class _$$Image_JpgImplCopyWith<$Res> {
-  factory _$$Image_JpgImplCopyWith(_$Image_JpgImpl value, $Res Function(_$Image_JpgImpl) then) =
-      __$$Image_JpgImplCopyWithImpl<$Res>;
+mixin _$StartDeviceLinkResult {
+  @optionalTypeArgs
+  TResult when<TResult extends Object?>({
+    required TResult Function(String linkCode) ok,
+    required TResult Function(WalletInstructionError error) instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult? whenOrNull<TResult extends Object?>({
+    TResult? Function(String linkCode)? ok,
+    TResult? Function(WalletInstructionError error)? instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult maybeWhen<TResult extends Object?>({
+    TResult Function(String linkCode)? ok,
+    TResult Function(WalletInstructionError error)? instructionError,
+    required TResult orElse(),
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult map<TResult extends Object?>({
+    required TResult Function(StartDeviceLinkResult_Ok value) ok,
+    required TResult Function(StartDeviceLinkResult_InstructionError value) instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult? mapOrNull<TResult extends Object?>({
+    TResult? Function(StartDeviceLinkResult_Ok value)? ok,
+    TResult? Function(StartDeviceLinkResult_InstructionError value)? instructionError,
+  }) =>
+      throw _privateConstructorUsedError;
+  @optionalTypeArgs
+  TResult maybeMap<TResult extends Object?>({
+    TResult Function(StartDeviceLinkResult_Ok value)? ok,
+    TResult Function(StartDeviceLinkResult_InstructionError value)? instructionError,
+    required TResult orElse(),
+  }) =>
+      throw _privateConstructorUsedError;
+}
+
+/// @nodoc
+abstract class $StartDeviceLinkResultCopyWith<$Res> {
+  factory $StartDeviceLinkResultCopyWith(StartDeviceLinkResult value, $Res Function(StartDeviceLinkResult) then) =
+      _$StartDeviceLinkResultCopyWithImpl<$Res, StartDeviceLinkResult>;
+}
+
+/// @nodoc
+class _$StartDeviceLinkResultCopyWithImpl<$Res, $Val extends StartDeviceLinkResult>
+    implements $StartDeviceLinkResultCopyWith<$Res> {
+  _$StartDeviceLinkResultCopyWithImpl(this._value, this._then);
+
+  // ignore: unused_field
+  final $Val _value;
+  // ignore: unused_field
+  final $Res Function($Val) _then;
+}
+
+/// @nodoc
+abstract class _$$StartDeviceLinkResult_OkImplCopyWith<$Res> {
+  factory _$$StartDeviceLinkResult_OkImplCopyWith(
+          _$StartDeviceLinkResult_OkImpl value, $Res Function(_$StartDeviceLinkResult_OkImpl) then) =
+      __$$StartDeviceLinkResult_OkImplCopyWithImpl<$Res>;
   @useResult
-  $Res call({String base64});
+  $Res call({String linkCode});
 }
 
 /// @nodoc
-class __$$Image_JpgImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_JpgImpl>
-    implements _$$Image_JpgImplCopyWith<$Res> {
-  __$$Image_JpgImplCopyWithImpl(_$Image_JpgImpl _value, $Res Function(_$Image_JpgImpl) _then) : super(_value, _then);
+class __$$StartDeviceLinkResult_OkImplCopyWithImpl<$Res>
+    extends _$StartDeviceLinkResultCopyWithImpl<$Res, _$StartDeviceLinkResult_OkImpl>
+    implements _$$StartDeviceLinkResult_OkImplCopyWith<$Res> {
+  __$$StartDeviceLinkResult_OkImplCopyWithImpl(
+      _$StartDeviceLinkResult_OkImpl _value, $Res Function(_$StartDeviceLinkResult_OkImpl) _then)
+      : super(_value, _then);
 
   @pragma('vm:prefer-inline')
   @override
   $Res call({
-    Object? base64 = null,
+    Object? linkCode = null,
   }) {
-    return _then(_$Image_JpgImpl(
-      base64: null == base64
-          ? _value.base64
-          : base64 // ignore: cast_nullable_to_non_nullable
+    return _then(_$StartDeviceLinkResult_OkImpl(
+      linkCode: null == linkCode
+          ? _value.linkCode
+          : linkCode // ignore: cast_nullable_to_non_nullable
               as String,
     ));
   }
@@ -1659,67 +3608,61 @@ class __$$Image_JpgImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Im
 
 /// @nodoc
 
-class _$Image_JpgImpl implements Image_Jpg {
-  const _$Image_JpgImpl({required this.base64});
+class _$StartDeviceLinkResult_OkImpl implements StartDeviceLinkResult_Ok {
+  const _$StartDeviceLinkResult_OkImpl({required this.linkCode});
 
   @override
-  final String base64;
+  final String linkCode;
 
   @override
   String toString() {
-    return 'Image.jpg(base64: $base64)';
+    return 'StartDeviceLinkResult.ok(linkCode: $linkCode)';
   }
 
   @override
   bool operator ==(Object other) {
     return identical(this, other) ||
         (other.runtimeType == runtimeType &&
-            other is _$Image_JpgImpl &&
-            (identical(other.base64, base64) || other.base64 == base64));
+            other is _$StartDeviceLinkResult_OkImpl &&
+            (identical(other.linkCode, linkCode) || other.linkCode == linkCode));
   }
 
   @override
-  int get hashCode => Object.hash(runtimeType, base64);
+  int get hashCode => Object.hash(runtimeType, linkCode);
 
   @JsonKey(ignore: true)
   @override
   @pragma('vm:prefer-inline')
-  _$$Image_JpgImplCopyWith<_$Image_JpgImpl> get copyWith =>
-      __$$Image_JpgImplCopyWithImpl<_$Image_JpgImpl>(this, _$identity);
+  _$$StartDeviceLinkResult_OkImplCopyWith<_$StartDeviceLinkResult_OkImpl> get copyWith =>
+      __$$StartDeviceLinkResult_OkImplCopyWithImpl<_$StartDeviceLinkResult_OkImpl>(this, _$identity);
 
   @override
   @optionalTypeArgs
   TResult when<TResult extends Object?>({
-    required TResult Function(String xml) svg,
-    required TResult Function(String base64) png,
-    required TResult Function(String base64) jpg,
-    required TResult Function(String path) asset,
+    required TResult Function(String linkCode) ok,
+    required TResult Function(WalletInstructionError error) instructionError,
   }) {
-    return jpg(base64);
+    return ok(linkCode);
   }
 
   @override
   @optionalTypeArgs
   TResult? whenOrNull<TResult extends Object?>({
-    TResult? Function(String xml)? svg,
-    TResult? Function(String base64)? png,
-    TResult? Function(String base64)? jpg,
-    TResult? Function(String path)? asset,
+    TResult? Function(String linkCode)? ok,
+    TResult? Function(WalletInstructionError error)? instructionError,
   }) {
-    return jpg?.call(base64);
+    return ok?.call(linkCode);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeWhen<TResult extends Object?>({
-    TResult Function(String xml)? svg,
-    TResult Function(String base64)? png,
-    TResult Function(String base64)? jpg,
-    TResult Function(String path)? asset,
+    TResult Function(String linkCode)? ok,
+    TResult Function(WalletInstructionError error)? instructionError,
     required TResult orElse(),
   }) {
-    if (jpg != null) {
-      return jpg(base64);
+    if (ok != null) {
+      return ok(linkCode);
     }
     return orElse();
   }
@@ -1727,140 +3670,143 @@ class _$Image_JpgImpl implements Image_Jpg {
   @override
   @optionalTypeArgs
   TResult map<TResult extends Object?>({
-    required TResult Function(Image_Svg value) svg,
-    required TResult Function(Image_Png value) png,
-    required TResult Function(Image_Jpg value) jpg,
-    required TResult Function(Image_Asset value) asset,
+    required TResult Function(StartDeviceLinkResult_Ok value) ok,
+    required TResult Function(StartDeviceLinkResult_InstructionError value) instructionError,
   }) {
-    return jpg(this);
+    return ok(this);
   }
 
   @override
   @optionalTypeArgs
   TResult? mapOrNull<TResult extends Object?>({
-    TResult? Function(Image_Svg value)? svg,
-    TResult? Function(Image_Png value)? png,
-    TResult? Function(Image_Jpg value)? jpg,
-    TResult? Function(Image_Asset value)? asset,
+    TResult? Function(StartDeviceLinkResult_Ok value)? ok,
+    TResult? Function(StartDeviceLinkResult_InstructionError value)? instructionError,
   }) {
-    return jpg?.call(this);
+    return ok?.call(this);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeMap<TResult extends Object?>({
-    TResult Function(Image_Svg value)? svg,
-    TResult Function(Image_Png value)? png,
-    TResult Function(Image_Jpg value)? jpg,
-    TResult Function(Image_Asset value)? asset,
+    TResult Function(StartDeviceLinkResult_Ok value)? ok,
+    TResult Function(StartDeviceLinkResult_InstructionError value)? instructionError,
     required TResult orElse(),
   }) {
-    if (jpg != null) {
-      return jpg(this);
+    if (ok != null) {
+      return ok(this);
     }
     return orElse();
   }
 }
 
-abstract class Image_Jpg implements Image {
-  const factory Image_Jpg({required final String base64}) = _$Image_JpgImpl;
+abstract class StartDeviceLinkResult_Ok implements StartDeviceLinkResult {
+  const factory StartDeviceLinkResult_Ok({required final String linkCode}) = _$StartDeviceLinkResult_OkImpl;
 
-  String get base64;
+  String get linkCode;
   @JsonKey(ignore: true)
-  _$$Image_JpgImplCopyWith<_$Image_JpgImpl> get copyWith => throw _privateConstructorUsedError;
+  _$$StartDeviceLinkResult_OkImplCopyWith<_$StartDeviceLinkResult_OkImpl> get copyWith =>
+      throw _privateConstructorUsedError;
 }
 
 /// @nodoc
-abstract class _$$Image_AssetImplCopyWith<$Res> {
-  factory _$$Image_AssetImplCopyWith(_$Image_AssetImpl value, $Res Function(_$Image_AssetImpl) then) =
-      __$$Image_AssetImplCopyWithImpl<$Res>;
+abstract class _$$StartDeviceLinkResult_InstructionErrorImplCopyWith<$Res> {
+  factory _$$StartDeviceLinkResult_InstructionErrorImplCopyWith(_$StartDeviceLinkResult_InstructionErrorImpl value,
+          $Res Function(_$StartDeviceLinkResult_InstructionErrorImpl) then) =
+      __$$StartDeviceLinkResult_InstructionErrorImplCopyWithImpl<$Res>;
   @useResult
-  $Res call({String path});
+  $Res call({WalletInstructionError error});
+
+  $WalletInstructionErrorCopyWith<$Res> get error;
 }
 
 /// @nodoc
-class __$$Image_AssetImplCopyWithImpl<$Res> extends _$ImageCopyWithImpl<$Res, _$Image_AssetImpl>
-    implements _$$Image_AssetImplCopyWith<$Res> {
-  __$$Image_AssetImplCopyWithImpl(_$Image_AssetImpl _value, $Res Function(_$Image_AssetImpl) _then)
+class __$$StartDeviceLinkResult_InstructionErrorImplCopyWithImpl<$Res>
+    extends _$StartDeviceLinkResultCopyWithImpl<$Res, _$StartDeviceLinkResult_InstructionErrorImpl>
+    implements _$$StartDeviceLinkResult_InstructionErrorImplCopyWith<$Res> {
+  __$$StartDeviceLinkResult_InstructionErrorImplCopyWithImpl(_$StartDeviceLinkResult_InstructionErrorImpl _value,
+      $Res Function(_$StartDeviceLinkResult_InstructionErrorImpl) _then)
       : super(_value, _then);
 
   @pragma('vm:prefer-inline')
   @override
   $Res call({
-    Object? path = null,
+    Object? error = null,
   }) {
-    return _then(_$Image_AssetImpl(
-      path: null == path
-          ? _value.path
-          : path // ignore: cast_nullable_to_non_nullable
-              as String,
+    return _then(_$StartDeviceLinkResult_InstructionErrorImpl(
+      error: null == error
+          ? _value.error
+          : error // ignore: cast_nullable_to_non_nullable
+              as WalletInstructionError,
     ));
   }
+
+  @override
+  @pragma('vm:prefer-inline')
+  $WalletInstructionErrorCopyWith<$Res> get error {
+    return $WalletInstructionErrorCopyWith<$Res>(_value.error, (value) {
+      return _then(_value.copyWith(error: value));
+    });
+  }
 }
 
 /// @nodoc
 
-class _$Image_AssetImpl implements Image_Asset {
-  const _$Image_AssetImpl({required this.path});
+class _$StartDeviceLinkResult_InstructionErrorImpl implements StartDeviceLinkResult_InstructionError {
+  const _$StartDeviceLinkResult_InstructionErrorImpl({required this.error});
 
   @override
-  final String path;
+  final WalletInstructionError error;
 
   @override
   String toString() {
-    return 'Image.asset(path: $path)';
+    return 'StartDeviceLinkResult.instructionError(error: $error)';
   }
 
   @override
   bool operator ==(Object other) {
     return identical(this, other) ||
         (other.runtimeType == runtimeType &&
-            other is _$Image_AssetImpl &&
-            (identical(other.path, path) || other.path == path));
+            other is _$StartDeviceLinkResult_InstructionErrorImpl &&
+            (identical(other.error, error) || other.error == error));
   }
 
   @override
-  int get hashCode => Object.hash(runtimeType, path);
+  int get hashCode => Object.hash(runtimeType, error);
 
   @JsonKey(ignore: true)
   @override
   @pragma('vm:prefer-inline')
-  _$$Image_AssetImplCopyWith<_$Image_AssetImpl> get copyWith =>
-      __$$Image_AssetImplCopyWithImpl<_$Image_AssetImpl>(this, _$identity);
+  _$$StartDeviceLinkResult_InstructionErrorImplCopyWith<_$StartDeviceLinkResult_InstructionErrorImpl> get copyWith =>
+      __$$StartDeviceLinkResult_InstructionErrorImplCopyWithImpl<_$StartDeviceLinkResult_InstructionErrorImpl>(
+          this, _$identity);
 
   @override
   @optionalTypeArgs
   TResult when<TResult extends Object?>({
-    required TResult Function(String xml) svg,
-    required TResult Function(String base64) png,
-    required TResult Function(String base64) jpg,
-    required TResult Function(String path) asset,
+    required TResult Function(String linkCode) ok,
+    required TResult Function(WalletInstructionError error) instructionError,
   }) {
-    return asset(path);
+    return instructionError(error);
   }
 
   @override
   @optionalTypeArgs
   TResult? whenOrNull<TResult extends Object?>({
-    TResult? Function(String xml)? svg,
-    TResult? Function(String base64)? png,
-    TResult? Function(String base64)? jpg,
-    TResult? Function(String path)? asset,
+    TResult? Function(String linkCode)? ok,
+    TResult? Function(WalletInstructionError error)? instructionError,
   }) {
-    return asset?.call(path);
+    return instructionError?.call(error);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeWhen<TResult extends Object?>({
-    TResult Function(String xml)? svg,
-    TResult Function(String base64)? png,
-    TResult Function(String base64)? jpg,
-    TResult Function(String path)? asset,
+    TResult Function(String linkCode)? ok,
+    TResult Function(WalletInstructionError error)? instructionError,
     required TResult orElse(),
   }) {
-    if (asset != null) {
-      return asset(path);
+    if (instructionError != null) {
+      return instructionError(error);
     }
     return orElse();
   }
@@ -1868,49 +3814,46 @@ class _$Image_AssetImpl implements Image_Asset {
   @override
   @optionalTypeArgs
   TResult map<TResult extends Object?>({
-    required TResult Function(Image_Svg value) svg,
-    required TResult Function(Image_Png value) png,
-    required TResult Function(Image_Jpg value) jpg,
-    required TResult Function(Image_Asset value) asset,
+    required TResult Function(StartDeviceLinkResult_Ok value) ok,
+    required TResult Function(StartDeviceLinkResult_InstructionError value) instructionError,
   }) {
-    return asset(this);
+    return instructionError(this);
   }
 
   @override
   @optionalTypeArgs
   TResult? mapOrNull<TResult extends Object?>({
-    TResult? Function(Image_Svg value)? svg,
-    TResult? Function(Image_Png value)? png,
-    TResult? Function(Image_Jpg value)? jpg,
-    TResult? Function(Image_Asset value)? asset,
+    TResult? Function(StartDeviceLinkResult_Ok value)? ok,
+    TResult? Function(StartDeviceLinkResult_InstructionError value)? instructionError,
   }) {
-    return asset?.call(this);
+    return instructionError?.call(this);
   }
 
   @override
   @optionalTypeArgs
   TResult maybeMap<TResult extends Object?>({
-    TResult Function(Image_Svg value)? svg,
-    TResult Function(Image_Png value)? png,
-    TResult Function(Image_Jpg value)? jpg,
-    TResult Function(Image_Asset value)? asset,
+    TResult Function(StartDeviceLinkResult_Ok value)? ok,
+    TResult Function(StartDeviceLinkResult_InstructionError value)? instructionError,
     required TResult orElse(),
   }) {
-    if (asset != null) {
-      return asset(this);
+    if (instructionError != null) {
+      return instructionError(this);
     }
     return orElse();
   }
 }
 
-abstract class Image_Asset implements Image {
-  const factory Image_Asset({required final String path}) = _$Image_AssetImpl;
+abstract class StartDeviceLinkResult_InstructionError implements StartDeviceLinkResult {
+  const factory StartDeviceLinkResult_InstructionError({required final WalletInstructionError error}) =
+      _$StartDeviceLinkResult_InstructionErrorImpl;
 
-  String get path;
+  WalletInstructionError get error;
   @JsonKey(ignore: true)
-  _$$Image_AssetImplCopyWith<_$Image_AssetImpl> get copyWith => throw _privateConstructorUsedError;
+  _$$StartDeviceLinkResult_InstructionErrorImplCopyWith<_$StartDeviceLinkResult_InstructionErrorImpl> get copyWith =>
+      throw _privateConstructorUsedError;
 }
 
+
 /// @nodoc
 mixin _$StartDisclosureResult {
   Organization get relyingParty => throw _privateConstructorUsedError;
@@ -1924,6 +3867,8 @@ mixin _$StartDisclosureResult {
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -1946,6 +3891,8 @@ mixin _$StartDisclosureResult {
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -1968,6 +3915,8 @@ mixin _$StartDisclosureResult {
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -2077,6 +4026,8 @@ abstract class _$$StartDisclosureResult_RequestImplCopyWith<$Res> implements $St
       {Organization relyingParty,
       RequestPolicy policy,
       List<DisclosureCard> requestedCards,
+      List<DisclosureCardCandidates> candidateCards,
+      List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
       bool sharedDataWithRelyingPartyBefore,
       DisclosureSessionType sessionType,
       List<LocalizedString> requestPurpose,
@@ -2098,6 +4049,8 @@ class __$$StartDisclosureResult_RequestImplCopyWithImpl<$Res>
     Object? relyingParty = null,
     Object? policy = null,
     Object? requestedCards = null,
+    Object? candidateCards = null,
+    Object? requestedSelfAssertedAttributes = null,
     Object? sharedDataWithRelyingPartyBefore = null,
     Object? sessionType = null,
     Object? requestPurpose = null,
@@ -2117,6 +4070,14 @@ class __$$StartDisclosureResult_RequestImplCopyWithImpl<$Res>
           ? _value._requestedCards
           : requestedCards // ignore: cast_nullable_to_non_nullable
               as List<DisclosureCard>,
+      candidateCards: null == candidateCards
+          ? _value._candidateCards
+          : candidateCards // ignore: cast_nullable_to_non_nullable
+              as List<DisclosureCardCandidates>,
+      requestedSelfAssertedAttributes: null == requestedSelfAssertedAttributes
+          ? _value._requestedSelfAssertedAttributes
+          : requestedSelfAssertedAttributes // ignore: cast_nullable_to_non_nullable
+              as List<RequestedSelfAssertedAttribute>,
       sharedDataWithRelyingPartyBefore: null == sharedDataWithRelyingPartyBefore
           ? _value.sharedDataWithRelyingPartyBefore
           : sharedDataWithRelyingPartyBefore // ignore: cast_nullable_to_non_nullable
@@ -2148,12 +4109,16 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
       {required this.relyingParty,
       required this.policy,
       required final List<DisclosureCard> requestedCards,
+      required final List<DisclosureCardCandidates> candidateCards,
+      required final List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
       required this.sharedDataWithRelyingPartyBefore,
       required this.sessionType,
       required final List<LocalizedString> requestPurpose,
       required this.requestOriginBaseUrl,
       required this.requestType})
       : _requestedCards = requestedCards,
+        _candidateCards = candidateCards,
+        _requestedSelfAssertedAttributes = requestedSelfAssertedAttributes,
         _requestPurpose = requestPurpose;
 
   @override
@@ -2168,6 +4133,22 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
     return EqualUnmodifiableListView(_requestedCards);
   }
 
+  final List<DisclosureCardCandidates> _candidateCards;
+  @override
+  List<DisclosureCardCandidates> get candidateCards {
+    if (_candidateCards is EqualUnmodifiableListView) return _candidateCards;
+    // ignore: implicit_dynamic_type
+    return EqualUnmodifiableListView(_candidateCards);
+  }
+
+  final List<RequestedSelfAssertedAttribute> _requestedSelfAssertedAttributes;
+  @override
+  List<RequestedSelfAssertedAttribute> get requestedSelfAssertedAttributes {
+    if (_requestedSelfAssertedAttributes is EqualUnmodifiableListView) return _requestedSelfAssertedAttributes;
+    // ignore: implicit_dynamic_type
+    return EqualUnmodifiableListView(_requestedSelfAssertedAttributes);
+  }
+
   @override
   final bool sharedDataWithRelyingPartyBefore;
   @override
@@ -2187,7 +4168,7 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
 
   @override
   String toString() {
-    return 'StartDisclosureResult.request(relyingParty: $relyingParty, policy: $policy, requestedCards: $requestedCards, sharedDataWithRelyingPartyBefore: $sharedDataWithRelyingPartyBefore, sessionType: $sessionType, requestPurpose: $requestPurpose, requestOriginBaseUrl: $requestOriginBaseUrl, requestType: $requestType)';
+    return 'StartDisclosureResult.request(relyingParty: $relyingParty, policy: $policy, requestedCards: $requestedCards, candidateCards: $candidateCards, requestedSelfAssertedAttributes: $requestedSelfAssertedAttributes, sharedDataWithRelyingPartyBefore: $sharedDataWithRelyingPartyBefore, sessionType: $sessionType, requestPurpose: $requestPurpose, requestOriginBaseUrl: $requestOriginBaseUrl, requestType: $requestType)';
   }
 
   @override
@@ -2198,6 +4179,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
             (identical(other.relyingParty, relyingParty) || other.relyingParty == relyingParty) &&
             (identical(other.policy, policy) || other.policy == policy) &&
             const DeepCollectionEquality().equals(other._requestedCards, _requestedCards) &&
+            const DeepCollectionEquality().equals(other._candidateCards, _candidateCards) &&
+            const DeepCollectionEquality().equals(other._requestedSelfAssertedAttributes, _requestedSelfAssertedAttributes) &&
             (identical(other.sharedDataWithRelyingPartyBefore, sharedDataWithRelyingPartyBefore) ||
                 other.sharedDataWithRelyingPartyBefore == sharedDataWithRelyingPartyBefore) &&
             (identical(other.sessionType, sessionType) || other.sessionType == sessionType) &&
@@ -2213,6 +4196,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
       relyingParty,
       policy,
       const DeepCollectionEquality().hash(_requestedCards),
+      const DeepCollectionEquality().hash(_candidateCards),
+      const DeepCollectionEquality().hash(_requestedSelfAssertedAttributes),
       sharedDataWithRelyingPartyBefore,
       sessionType,
       const DeepCollectionEquality().hash(_requestPurpose),
@@ -2232,6 +4217,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -2247,8 +4234,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
             String requestOriginBaseUrl)
         requestAttributesMissing,
   }) {
-    return request(relyingParty, policy, requestedCards, sharedDataWithRelyingPartyBefore, sessionType, requestPurpose,
-        requestOriginBaseUrl, requestType);
+    return request(relyingParty, policy, requestedCards, candidateCards, requestedSelfAssertedAttributes,
+        sharedDataWithRelyingPartyBefore, sessionType, requestPurpose, requestOriginBaseUrl, requestType);
   }
 
   @override
@@ -2258,6 +4245,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -2273,8 +4262,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
             String requestOriginBaseUrl)?
         requestAttributesMissing,
   }) {
-    return request?.call(relyingParty, policy, requestedCards, sharedDataWithRelyingPartyBefore, sessionType,
-        requestPurpose, requestOriginBaseUrl, requestType);
+    return request?.call(relyingParty, policy, requestedCards, candidateCards, requestedSelfAssertedAttributes,
+        sharedDataWithRelyingPartyBefore, sessionType, requestPurpose, requestOriginBaseUrl, requestType);
   }
 
   @override
@@ -2284,6 +4273,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -2301,8 +4292,8 @@ class _$StartDisclosureResult_RequestImpl implements StartDisclosureResult_Reque
     required TResult orElse(),
   }) {
     if (request != null) {
-      return request(relyingParty, policy, requestedCards, sharedDataWithRelyingPartyBefore, sessionType,
-          requestPurpose, requestOriginBaseUrl, requestType);
+      return request(relyingParty, policy, requestedCards, candidateCards, requestedSelfAssertedAttributes,
+          sharedDataWithRelyingPartyBefore, sessionType, requestPurpose, requestOriginBaseUrl, requestType);
     }
     return orElse();
   }
@@ -2344,6 +4335,8 @@ abstract class StartDisclosureResult_Request implements StartDisclosureResult {
       {required final Organization relyingParty,
       required final RequestPolicy policy,
       required final List<DisclosureCard> requestedCards,
+      required final List<DisclosureCardCandidates> candidateCards,
+      required final List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
       required final bool sharedDataWithRelyingPartyBefore,
       required final DisclosureSessionType sessionType,
       required final List<LocalizedString> requestPurpose,
@@ -2354,6 +4347,8 @@ abstract class StartDisclosureResult_Request implements StartDisclosureResult {
   Organization get relyingParty;
   RequestPolicy get policy;
   List<DisclosureCard> get requestedCards;
+  List<DisclosureCardCandidates> get candidateCards;
+  List<RequestedSelfAssertedAttribute> get requestedSelfAssertedAttributes;
   @override
   bool get sharedDataWithRelyingPartyBefore;
   @override
@@ -2517,6 +4512,8 @@ class _$StartDisclosureResult_RequestAttributesMissingImpl implements StartDiscl
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -2543,6 +4540,8 @@ class _$StartDisclosureResult_RequestAttributesMissingImpl implements StartDiscl
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
@@ -2569,6 +4568,8 @@ class _$StartDisclosureResult_RequestAttributesMissingImpl implements StartDiscl
             Organization relyingParty,
             RequestPolicy policy,
             List<DisclosureCard> requestedCards,
+            List<DisclosureCardCandidates> candidateCards,
+            List<RequestedSelfAssertedAttribute> requestedSelfAssertedAttributes,
             bool sharedDataWithRelyingPartyBefore,
             DisclosureSessionType sessionType,
             List<LocalizedString> requestPurpose,
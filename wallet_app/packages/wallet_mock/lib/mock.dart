@@ -8,3 +8,5 @@ export 'src/wallet_core_for_signing.dart';
 
 const kMockPidIssuanceRedirectUri = 'initiate_mock_digid_flow';
 const kDrivingLicenseDocType = 'com.example.drivinglicense';
+const kMockDeviceId = 'mock_device';
+const kMockDeviceLinkCode = 'mock_device_link_code';
@@ -1,4 +1,5 @@
 import 'dart:convert';
+import 'dart:typed_data';
 
 import 'package:wallet_core/core.dart';
 
@@ -34,10 +35,16 @@ class WalletCoreMock extends _FlutterRustBridgeTasksMeta implements WalletCore {
     if (containsAllRequestedAttributes) {
       final isLoginRequest =
           request.requestedAttributes.length == 1 && request.requestedAttributes.first.key == 'mock.citizenshipNumber';
+      final requestedCards =
+          _wallet.getDisclosureCards(request.requestedAttributes.map((attribute) => attribute.key));
       return _ongoingDisclosure = StartDisclosureResult.request(
         relyingParty: request.relyingParty,
         policy: request.policy,
-        requestedCards: _wallet.getDisclosureCards(request.requestedAttributes.map((attribute) => attribute.key)),
+        requestedCards: requestedCards,
+        // The mock wallet only ever holds a single card per doc type
+        candidateCards: requestedCards.map((card) => DisclosureCardCandidates(cards: [card])).toList(),
+        // The mock disclosure requests do not contain self-asserted attributes
+        requestedSelfAssertedAttributes: [],
         sharedDataWithRelyingPartyBefore: _eventLog.includesInteractionWith(request.relyingParty),
         sessionType: DisclosureSessionType.CrossDevice,
         requestOriginBaseUrl: requestOriginBaseUrl,
@@ -62,6 +69,29 @@ class WalletCoreMock extends _FlutterRustBridgeTasksMeta implements WalletCore {
     }
   }
 
+  @override
+  Future<List<DisclosureCard>> selectDisclosureCandidates({required Uint32List selection, hint}) async {
+    final disclosure = _ongoingDisclosure;
+    assert(disclosure is StartDisclosureResult_Request, 'No ongoing disclosure to select candidates for');
+    final request = disclosure! as StartDisclosureResult_Request;
+    assert(selection.length == request.candidateCards.length, 'Selection should contain an index per card');
+    return List.generate(
+      selection.length,
+      (index) => request.candidateCards[index].cards[selection[index]],
+    );
+  }
+
+  @override
+  Future<void> setDisclosureSelfAssertedAttributes({required List<SelfAssertedAttribute> attributes, hint}) async {
+    final disclosure = _ongoingDisclosure;
+    assert(disclosure is StartDisclosureResult_Request, 'No ongoing disclosure to set self-asserted attributes for');
+    final request = disclosure! as StartDisclosureResult_Request;
+    assert(
+      attributes.length == request.requestedSelfAssertedAttributes.length,
+      'Exactly the requested self-asserted attributes should be provided',
+    );
+  }
+
   @override
   Future<void> cancelDisclosure({hint}) async {
     final disclosure = _ongoingDisclosure;
@@ -244,6 +274,41 @@ class WalletCoreMock extends _FlutterRustBridgeTasksMeta implements WalletCore {
 
   @override
   Future<bool> hasActivePidIssuanceSession({hint}) async => false;
+
+  @override
+  Future<StartDeviceLinkResult> startDeviceLink({required String pin, hint}) async {
+    final result = _pinManager.checkPin(pin);
+    if (result is WalletInstructionResult_InstructionError) {
+      return StartDeviceLinkResult.instructionError(error: result.error);
+    }
+    return StartDeviceLinkResult.ok(linkCode: kMockDeviceLinkCode);
+  }
+
+  @override
+  Future<WalletInstructionResult> linkDevice({required String pin, required String linkCode, hint}) async {
+    // Linking this (mock) device to another wallet account is not supported, the pin is checked for consistency only
+    return _pinManager.checkPin(pin);
+  }
+
+  @override
+  Future<LinkedDevicesResult> getLinkedDevices({required String pin, hint}) async {
+    final result = _pinManager.checkPin(pin);
+    if (result is WalletInstructionResult_InstructionError) {
+      return LinkedDevicesResult.instructionError(error: result.error);
+    }
+    // The mock wallet only ever consists of the current device
+    return LinkedDevicesResult.ok(
+      devices: [
+        LinkedDevice(id: kMockDeviceId, linkedDateTime: DateTime(2024).toIso8601String(), isCurrent: true),
+      ],
+    );
+  }
+
+  @override
+  Future<WalletInstructionResult> removeLinkedDevice({required String pin, required String deviceId, hint}) async {
+    // The mock wallet has no other devices to remove, the pin is checked for consistency only
+    return _pinManager.checkPin(pin);
+  }
 }
 
 /// Helper class to make [WalletCoreMock] satisfy [WalletCore]
@@ -293,6 +358,10 @@ class _FlutterRustBridgeTasksMeta {
 
   FlutterRustBridgeTaskConstMeta get kStartDisclosureConstMeta => throw UnimplementedError();
 
+  FlutterRustBridgeTaskConstMeta get kSelectDisclosureCandidatesConstMeta => throw UnimplementedError();
+
+  FlutterRustBridgeTaskConstMeta get kSetDisclosureSelfAssertedAttributesConstMeta => throw UnimplementedError();
+
   FlutterRustBridgeTaskConstMeta get kUnlockWalletConstMeta => throw UnimplementedError();
 
   FlutterRustBridgeTaskConstMeta get kGetHistoryConstMeta => throw UnimplementedError();
@@ -306,4 +375,12 @@ class _FlutterRustBridgeTasksMeta {
   FlutterRustBridgeTaskConstMeta get kHasActiveDisclosureSessionConstMeta => throw UnimplementedError();
 
   FlutterRustBridgeTaskConstMeta get kHasActivePidIssuanceSessionConstMeta => throw UnimplementedError();
+
+  FlutterRustBridgeTaskConstMeta get kStartDeviceLinkConstMeta => throw UnimplementedError();
+
+  FlutterRustBridgeTaskConstMeta get kLinkDeviceConstMeta => throw UnimplementedError();
+
+  FlutterRustBridgeTaskConstMeta get kGetLinkedDevicesConstMeta => throw UnimplementedError();
+
+  FlutterRustBridgeTaskConstMeta get kRemoveLinkedDeviceConstMeta => throw UnimplementedError();
 }
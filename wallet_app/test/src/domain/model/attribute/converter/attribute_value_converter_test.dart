@@ -1,3 +1,5 @@
+import 'dart:typed_data';
+
 import 'package:test/test.dart';
 import 'package:wallet/src/domain/model/attribute/attribute_value.dart';
 import 'package:wallet/src/domain/model/attribute/converter/attribute_value_converter.dart';
@@ -34,6 +36,51 @@ void main() {
     expect(result, equals(input));
   });
 
+  test('IntegerValue', () {
+    const input = IntegerValue(42);
+    final json = converter.toJson(input);
+    final result = converter.fromJson(json);
+    expect(result, equals(input));
+  });
+
+  test('DateTimeValue', () {
+    final input = DateTimeValue(DateTime.utc(2015, 10, 21, 7, 28));
+    final json = converter.toJson(input);
+    final result = converter.fromJson(json);
+    expect(result, equals(input));
+  });
+
+  test('ImageValue', () {
+    final input = ImageValue(Uint8List.fromList([0xFF, 0xD8, 0xFF]));
+    final json = converter.toJson(input);
+    final result = converter.fromJson(json);
+    expect(result, equals(input));
+  });
+
+  test('BytesValue', () {
+    final input = BytesValue(Uint8List.fromList([1, 2, 3]));
+    final json = converter.toJson(input);
+    final result = converter.fromJson(json);
+    expect(result, equals(input));
+  });
+
+  test('ArrayValue', () {
+    const input = ArrayValue([StringValue('string'), IntegerValue(42)]);
+    final json = converter.toJson(input);
+    final result = converter.fromJson(json);
+    expect(result, equals(input));
+  });
+
+  test('DictionaryValue', () {
+    const input = DictionaryValue({
+      'code': StringValue('B'),
+      'restrictions': ArrayValue([StringValue('01'), StringValue('78')]),
+    });
+    final json = converter.toJson(input);
+    final result = converter.fromJson(json);
+    expect(result, equals(input));
+  });
+
   test('decoding an unsupported type throws', () {
     expect(
       () => converter.fromJson({'type': 'non-existent-type'}),
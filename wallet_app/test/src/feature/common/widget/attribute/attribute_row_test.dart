@@ -1,3 +1,5 @@
+import 'dart:typed_data';
+
 import 'package:flutter/material.dart';
 import 'package:flutter_test/flutter_test.dart';
 import 'package:golden_toolkit/golden_toolkit.dart';
@@ -127,5 +129,21 @@ void main() {
       expect(labelFinder, findsOneWidget);
       expect(valueFinder, findsOneWidget);
     });
+
+    testWidgets('Image value is rendered as image', (tester) async {
+      await tester.pumpWidgetWithAppWrapper(
+        AttributeRow(
+          attribute: DataAttribute.untranslated(
+            label: 'Portrait',
+            value: ImageValue(Uint8List.fromList([0xFF, 0xD8, 0xFF])),
+            sourceCardDocType: '',
+            key: 'mock.portrait',
+          ),
+        ),
+      );
+
+      expect(find.text('Portrait'), findsOneWidget);
+      expect(find.byType(Image), findsOneWidget);
+    });
   });
 }
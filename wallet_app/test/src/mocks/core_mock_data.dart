@@ -1,3 +1,5 @@
+import 'dart:typed_data';
+
 import 'package:wallet_core/core.dart';
 
 abstract class CoreMockData {
@@ -8,6 +10,106 @@ abstract class CoreMockData {
     issuer: organization,
   );
 
+  /// Mirrors the mDL as it is produced by the core's document mapping, covering every [CardValue] type it emits.
+  static final Card mdlCard = Card(
+    persistence: const CardPersistence.stored(id: 'mdl'),
+    docType: kMdlDocType,
+    attributes: [
+      const CardAttribute(
+        key: 'given_name',
+        labels: [
+          LocalizedString(language: 'en', value: 'First names'),
+          LocalizedString(language: 'nl', value: 'Voornamen'),
+        ],
+        value: CardValue_String(value: 'Willeke Liselotte'),
+      ),
+      const CardAttribute(
+        key: 'family_name',
+        labels: [
+          LocalizedString(language: 'en', value: 'Surname'),
+          LocalizedString(language: 'nl', value: 'Achternaam'),
+        ],
+        value: CardValue_String(value: 'De Bruijn'),
+      ),
+      const CardAttribute(
+        key: 'birth_date',
+        labels: [
+          LocalizedString(language: 'en', value: 'Birth date'),
+          LocalizedString(language: 'nl', value: 'Geboortedatum'),
+        ],
+        value: CardValue_Date(value: '1997-05-10'),
+      ),
+      CardAttribute(
+        key: 'portrait',
+        labels: const [
+          LocalizedString(language: 'en', value: 'Portrait'),
+          LocalizedString(language: 'nl', value: 'Pasfoto'),
+        ],
+        value: CardValue_Image(value: Uint8List.fromList([0xff, 0xd8, 0xff, 0xe0])),
+      ),
+      const CardAttribute(
+        key: 'issue_date',
+        labels: [
+          LocalizedString(language: 'en', value: 'Issue date'),
+          LocalizedString(language: 'nl', value: 'Datum van afgifte'),
+        ],
+        value: CardValue_Date(value: '2023-11-14'),
+      ),
+      const CardAttribute(
+        key: 'expiry_date',
+        labels: [
+          LocalizedString(language: 'en', value: 'Expiry date'),
+          LocalizedString(language: 'nl', value: 'Vervaldatum'),
+        ],
+        value: CardValue_Date(value: '2033-11-14'),
+      ),
+      const CardAttribute(
+        key: 'driving_privileges',
+        labels: [
+          LocalizedString(language: 'en', value: 'Driving privileges'),
+          LocalizedString(language: 'nl', value: 'Rijbevoegdheden'),
+        ],
+        value: CardValue_Array(
+          value: [
+            CardValue_Dictionary(
+              value: [
+                CardValueDictionaryEntry(key: 'vehicle_category_code', value: CardValue_String(value: 'B')),
+                CardValueDictionaryEntry(key: 'issue_date', value: CardValue_Date(value: '2018-08-09')),
+                CardValueDictionaryEntry(
+                  key: 'codes',
+                  value: CardValue_Array(
+                    value: [
+                      CardValue_Dictionary(
+                        value: [CardValueDictionaryEntry(key: 'code', value: CardValue_Integer(value: 5))],
+                      ),
+                    ],
+                  ),
+                ),
+              ],
+            ),
+          ],
+        ),
+      ),
+      const CardAttribute(
+        key: 'sex',
+        labels: [
+          LocalizedString(language: 'en', value: 'Gender'),
+          LocalizedString(language: 'nl', value: 'Geslacht'),
+        ],
+        value: CardValue_Gender(value: GenderCardValue.Female),
+      ),
+      const CardAttribute(
+        key: 'height',
+        labels: [
+          LocalizedString(language: 'en', value: 'Height (cm)'),
+          LocalizedString(language: 'nl', value: 'Lengte (cm)'),
+        ],
+        value: CardValue_Integer(value: 172),
+      ),
+    ],
+    issuer: organization,
+  );
+
   static const CardAttribute cardAttributeName = CardAttribute(
     key: 'name',
     labels: [],
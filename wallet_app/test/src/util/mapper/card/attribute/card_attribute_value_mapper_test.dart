@@ -1,8 +1,10 @@
+import 'dart:typed_data';
 import 'dart:ui';
 
 import 'package:flutter_gen/gen_l10n/app_localizations.dart';
 import 'package:flutter_test/flutter_test.dart';
 import 'package:intl/date_symbol_data_local.dart';
+import 'package:intl/intl.dart';
 import 'package:wallet/src/domain/model/attribute/attribute.dart';
 import 'package:wallet/src/util/formatter/attribute_value_formatter.dart';
 import 'package:wallet/src/util/mapper/card/attribute/card_attribute_value_mapper.dart';
@@ -75,5 +77,54 @@ void main() {
       final actual = AttributeValueFormatter.formatWithLocale(_kSampleLocale, mapper.map(input));
       expect(actual, l10n.cardValueGenderUnknown);
     });
+
+    test('`CardValue_Integer` should return the number as string', () {
+      const CardValue input = CardValue_Integer(value: 1234567890);
+      final actual = AttributeValueFormatter.formatWithLocale(_kSampleLocale, mapper.map(input));
+      expect(actual, '1234567890');
+    });
+
+    test('`CardValue_DateTime` should return formatted local date and time string', () {
+      const CardValue input = CardValue_DateTime(value: '2015-10-21T07:28:00+00:00');
+      final actual = AttributeValueFormatter.formatWithLocale(_kSampleLocale, mapper.map(input));
+      final expected =
+          DateFormat(DateFormat.YEAR_MONTH_DAY, 'nl').add_Hm().format(DateTime.utc(2015, 10, 21, 7, 28).toLocal());
+      expect(actual, expected);
+    });
+
+    test('`CardValue_Image` should map to `ImageValue` and return localized string', () {
+      final input = CardValue_Image(value: Uint8List.fromList([0xFF, 0xD8, 0xFF]));
+      final value = mapper.map(input);
+      expect(value, ImageValue(Uint8List.fromList([0xFF, 0xD8, 0xFF])));
+      expect(AttributeValueFormatter.formatWithLocale(_kSampleLocale, value), l10n.cardValueImage);
+    });
+
+    test('`CardValue_Bytes` should return localized string with the size', () {
+      final input = CardValue_Bytes(value: Uint8List.fromList([1, 2, 3]));
+      final actual = AttributeValueFormatter.formatWithLocale(_kSampleLocale, mapper.map(input));
+      expect(actual, l10n.cardValueBytes(3));
+    });
+
+    test('`CardValue_Array` should return the formatted elements', () {
+      const CardValue input = CardValue_Array(
+        value: [
+          CardValue_String(value: 'NL Wallet'),
+          CardValue_Boolean(value: true),
+        ],
+      );
+      final actual = AttributeValueFormatter.formatWithLocale(_kSampleLocale, mapper.map(input));
+      expect(actual, 'NL Wallet, ${l10n.cardValueTrue}');
+    });
+
+    test('`CardValue_Dictionary` should return the formatted entries', () {
+      const CardValue input = CardValue_Dictionary(
+        value: [
+          CardValueDictionaryEntry(key: 'vehicle_category_code', value: CardValue_String(value: 'B')),
+          CardValueDictionaryEntry(key: 'issue_date', value: CardValue_Date(value: '2015-10-21')),
+        ],
+      );
+      final actual = AttributeValueFormatter.formatWithLocale(_kSampleLocale, mapper.map(input));
+      expect(actual, 'vehicle_category_code: B, issue_date: 21 oktober 2015');
+    });
   });
 }
@@ -80,6 +80,34 @@ void main() {
       verify(mockSubtitleMapper.map(coreCard)).called(1);
     });
 
+    test('card with `org.iso.18013.5.1.mDL` docType should return light localized driving licence card front',
+        () async {
+      const coreCard = Card(
+        persistence: CardPersistence.inMemory(),
+        docType: 'org.iso.18013.5.1.mDL',
+        attributes: [],
+        issuer: _kSampleIssuer,
+      );
+
+      when(mockSubtitleMapper.map(coreCard)).thenReturn('Subtitle'.untranslated);
+
+      final nlL10n = await TestUtils.dutchLocalizations;
+      final enL10n = await TestUtils.englishLocalizations;
+
+      final expected = CardFront(
+        title: {'en': enL10n.mdlCardTitle, 'nl': nlL10n.mdlCardTitle},
+        subtitle: 'Subtitle'.untranslated,
+        logoImage: WalletAssets.logo_nl_driving_license,
+        backgroundImage: WalletAssets.image_bg_nl_driving_license,
+        theme: CardFrontTheme.light,
+      );
+
+      expect(mapper.map(coreCard), expected);
+      expect(kMdlDocType, 'org.iso.18013.5.1.mDL');
+
+      verify(mockSubtitleMapper.map(coreCard)).called(1);
+    });
+
     test('card with unknown docType should throw exception', () {
       const input =
           Card(persistence: CardPersistence.inMemory(), docType: 'unknown', attributes: [], issuer: _kSampleIssuer);
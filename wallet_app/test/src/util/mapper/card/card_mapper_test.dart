@@ -1,13 +1,26 @@
+import 'dart:ui';
+
 import 'package:flutter_test/flutter_test.dart';
 import 'package:mockito/mockito.dart';
+import 'package:wallet/src/domain/model/attribute/attribute.dart';
 import 'package:wallet/src/domain/model/attribute/data_attribute.dart';
+import 'package:wallet/src/domain/model/attribute/value/gender.dart';
 import 'package:wallet/src/domain/model/card_config.dart';
 import 'package:wallet/src/domain/model/card_front.dart';
 import 'package:wallet/src/domain/model/organization.dart';
 import 'package:wallet/src/domain/model/wallet_card.dart';
+import 'package:wallet/src/util/formatter/attribute_value_formatter.dart';
 import 'package:wallet/src/util/mapper/card/attribute/card_attribute_mapper.dart';
+import 'package:wallet/src/util/mapper/card/attribute/card_attribute_value_mapper.dart';
+import 'package:wallet/src/util/mapper/card/attribute/localized_labels_mapper.dart';
+import 'package:wallet/src/util/mapper/card/card_config_mapper.dart';
+import 'package:wallet/src/util/mapper/card/card_front_mapper.dart';
 import 'package:wallet/src/util/mapper/card/card_mapper.dart';
+import 'package:wallet/src/util/mapper/card/card_subtitle_mapper.dart';
+import 'package:wallet/src/util/mapper/image/image_mapper.dart';
 import 'package:wallet/src/util/mapper/mapper.dart';
+import 'package:wallet/src/util/mapper/organization/organization_mapper.dart';
+import 'package:wallet/src/wallet_assets.dart';
 import 'package:wallet_core/core.dart' as core;
 
 import '../../../mocks/core_mock_data.dart';
@@ -81,4 +94,63 @@ void main() {
       verify(mockCardFrontMapper.map(_kSampleCard)).called(1);
     });
   });
+
+  group('mDL', () {
+    late Mapper<core.Card, WalletCard> cardMapper;
+
+    setUp(() {
+      cardMapper = CardMapper(
+        CardFrontMapper(CardSubtitleMapper(CardAttributeValueMapper())),
+        CardConfigMapper(),
+        CardAttributeMapper(CardAttributeValueMapper(), LocalizedLabelsMapper()),
+        OrganizationMapper(LocalizedLabelsMapper(), ImageMapper()),
+      );
+    });
+
+    test('issued mDL card is mapped with driving licence front and typed attribute values', () {
+      final WalletCard actual = cardMapper.map(CoreMockData.mdlCard);
+
+      expect(actual.id, 'mdl');
+      expect(actual.docType, core.kMdlDocType);
+      expect(actual.front.logoImage, WalletAssets.logo_nl_driving_license);
+      expect(actual.front.subtitle, {'en': 'Willeke Liselotte', 'nl': 'Willeke Liselotte'});
+
+      final values = {for (final attribute in actual.attributes) attribute.key: attribute.value};
+      expect(values.keys, CoreMockData.mdlCard.attributes.map((attribute) => attribute.key));
+      expect(values['given_name'], const StringValue('Willeke Liselotte'));
+      expect(values['birth_date'], DateValue(DateTime(1997, 5, 10)));
+      expect(values['portrait'], isA<ImageValue>());
+      expect(values['expiry_date'], DateValue(DateTime(2033, 11, 14)));
+      expect(values['sex'], const GenderValue(Gender.female));
+      expect(values['height'], const IntegerValue(172));
+      expect(
+        values['driving_privileges'],
+        ArrayValue([
+          DictionaryValue({
+            'vehicle_category_code': const StringValue('B'),
+            'issue_date': DateValue(DateTime(2018, 8, 9)),
+            'codes': const ArrayValue([
+              DictionaryValue({'code': IntegerValue(5)}),
+            ]),
+          }),
+        ]),
+      );
+
+      final portrait = actual.attributes.firstWhere((attribute) => attribute.key == 'portrait');
+      expect(portrait.label, {'en': 'Portrait', 'nl': 'Pasfoto'});
+    });
+
+    test('issued mDL card attribute values can be formatted', () {
+      final WalletCard actual = cardMapper.map(CoreMockData.mdlCard);
+      final values = {for (final attribute in actual.attributes) attribute.key: attribute.value};
+
+      const locale = Locale('en');
+      expect(AttributeValueFormatter.formatWithLocale(locale, values['portrait']!), 'Image');
+      expect(AttributeValueFormatter.formatWithLocale(locale, values['height']!), '172');
+      expect(
+        AttributeValueFormatter.formatWithLocale(locale, values['driving_privileges']!),
+        allOf(startsWith('vehicle_category_code: B, issue_date: '), endsWith(', codes: code: 5')),
+      );
+    });
+  });
 }
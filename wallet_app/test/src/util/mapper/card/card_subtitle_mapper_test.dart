@@ -64,6 +64,18 @@ void main() {
       verifyNever(mockAttributeValueMapper.map(_kSampleCardAttributeName.value));
     });
 
+    test('card with `org.iso.18013.5.1.mDL` docType should return `name` attribute string', () {
+      when(mockAttributeValueMapper.map(_kSampleCardAttributeName.value)).thenReturn(const StringValue('Willeke'));
+
+      final Card input =
+          createSampleCard('org.iso.18013.5.1.mDL', [_kSampleCardAttributeName, _kSampleCardAttributeCity]);
+      expect(mapper.map(input), _kSampleNameSubtitle);
+
+      // Check if every supported locale is mapped to a value
+      verify(mockAttributeValueMapper.map(_kSampleCardAttributeName.value))
+          .called(AppLocalizations.supportedLocales.length);
+    });
+
     test('card with `com.example.address` docType should return `city` attribute string', () {
       when(mockAttributeValueMapper.map(_kSampleCardAttributeCity.value)).thenReturn(const StringValue('Den Haag'));
 
@@ -12,7 +12,8 @@ use crate::{
     models::{
         card::Card,
         config::FlutterConfiguration,
-        disclosure::{AcceptDisclosureResult, StartDisclosureResult},
+        device::{LinkedDevicesResult, StartDeviceLinkResult},
+        disclosure::{AcceptDisclosureResult, DisclosureCard, SelfAssertedAttribute, StartDisclosureResult},
         instruction::WalletInstructionResult,
         pin::PinValidationResult,
         uri::IdentifyUriResult,
@@ -272,6 +273,32 @@ pub async fn start_disclosure(uri: String, is_qr_code: bool) -> Result<StartDisc
     Ok(result)
 }
 
+#[async_runtime]
+#[flutter_api_error]
+pub async fn select_disclosure_candidates(selection: Vec<u32>) -> Result<Vec<DisclosureCard>> {
+    let selection = selection.into_iter().map(|index| index as usize).collect::<Vec<_>>();
+
+    let mut wallet = wallet().write().await;
+
+    let documents = wallet.select_disclosure_candidates(&selection)?;
+
+    let cards = DisclosureCard::from_disclosure_documents(documents);
+
+    Ok(cards)
+}
+
+#[async_runtime]
+#[flutter_api_error]
+pub async fn set_disclosure_self_asserted_attributes(attributes: Vec<SelfAssertedAttribute>) -> Result<()> {
+    let attributes = SelfAssertedAttribute::into_self_asserted_attributes(attributes);
+
+    let mut wallet = wallet().write().await;
+
+    wallet.set_disclosure_self_asserted_attributes(attributes)?;
+
+    Ok(())
+}
+
 #[async_runtime]
 #[flutter_api_error]
 pub async fn cancel_disclosure() -> Result<()> {
@@ -327,6 +354,46 @@ pub async fn get_history_for_card(doc_type: String) -> Result<Vec<WalletEvent>>
     Ok(history)
 }
 
+#[async_runtime]
+#[flutter_api_error]
+pub async fn start_device_link(pin: String) -> Result<StartDeviceLinkResult> {
+    let wallet = wallet().write().await;
+
+    let result = wallet.start_device_link(pin).await.try_into()?;
+
+    Ok(result)
+}
+
+#[async_runtime]
+#[flutter_api_error]
+pub async fn link_device(pin: String, link_code: String) -> Result<WalletInstructionResult> {
+    let mut wallet = wallet().write().await;
+
+    let result = wallet.link_device(pin, &link_code).await.try_into()?;
+
+    Ok(result)
+}
+
+#[async_runtime]
+#[flutter_api_error]
+pub async fn get_linked_devices(pin: String) -> Result<LinkedDevicesResult> {
+    let wallet = wallet().write().await;
+
+    let result = wallet.get_linked_devices(pin).await.try_into()?;
+
+    Ok(result)
+}
+
+#[async_runtime]
+#[flutter_api_error]
+pub async fn remove_linked_device(pin: String, device_id: String) -> Result<WalletInstructionResult> {
+    let wallet = wallet().write().await;
+
+    let result = wallet.remove_linked_device(pin, device_id).await.try_into()?;
+
+    Ok(result)
+}
+
 #[async_runtime]
 #[flutter_api_error]
 pub async fn reset_wallet() -> Result<()> {
@@ -111,6 +111,19 @@ pub extern "C" fn wire_start_disclosure(port_: i64, uri: *mut wire_uint_8_list,
     wire_start_disclosure_impl(port_, uri, is_qr_code)
 }
 
+#[no_mangle]
+pub extern "C" fn wire_select_disclosure_candidates(port_: i64, selection: *mut wire_uint_32_list) {
+    wire_select_disclosure_candidates_impl(port_, selection)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_set_disclosure_self_asserted_attributes(
+    port_: i64,
+    attributes: *mut wire_list_self_asserted_attribute,
+) {
+    wire_set_disclosure_self_asserted_attributes_impl(port_, attributes)
+}
+
 #[no_mangle]
 pub extern "C" fn wire_cancel_disclosure(port_: i64) {
     wire_cancel_disclosure_impl(port_)
@@ -136,6 +149,26 @@ pub extern "C" fn wire_get_history_for_card(port_: i64, doc_type: *mut wire_uint
     wire_get_history_for_card_impl(port_, doc_type)
 }
 
+#[no_mangle]
+pub extern "C" fn wire_start_device_link(port_: i64, pin: *mut wire_uint_8_list) {
+    wire_start_device_link_impl(port_, pin)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_link_device(port_: i64, pin: *mut wire_uint_8_list, link_code: *mut wire_uint_8_list) {
+    wire_link_device_impl(port_, pin, link_code)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_get_linked_devices(port_: i64, pin: *mut wire_uint_8_list) {
+    wire_get_linked_devices_impl(port_, pin)
+}
+
+#[no_mangle]
+pub extern "C" fn wire_remove_linked_device(port_: i64, pin: *mut wire_uint_8_list, device_id: *mut wire_uint_8_list) {
+    wire_remove_linked_device_impl(port_, pin, device_id)
+}
+
 #[no_mangle]
 pub extern "C" fn wire_reset_wallet(port_: i64) {
     wire_reset_wallet_impl(port_)
@@ -143,6 +176,24 @@ pub extern "C" fn wire_reset_wallet(port_: i64) {
 
 // Section: allocate functions
 
+#[no_mangle]
+pub extern "C" fn new_list_self_asserted_attribute_0(len: i32) -> *mut wire_list_self_asserted_attribute {
+    let wrap = wire_list_self_asserted_attribute {
+        ptr: support::new_leak_vec_ptr(<wire_SelfAssertedAttribute>::new_with_null_ptr(), len),
+        len,
+    };
+    support::new_leak_box_ptr(wrap)
+}
+
+#[no_mangle]
+pub extern "C" fn new_uint_32_list_0(len: i32) -> *mut wire_uint_32_list {
+    let ans = wire_uint_32_list {
+        ptr: support::new_leak_vec_ptr(Default::default(), len),
+        len,
+    };
+    support::new_leak_box_ptr(ans)
+}
+
 #[no_mangle]
 pub extern "C" fn new_uint_8_list_0(len: i32) -> *mut wire_uint_8_list {
     let ans = wire_uint_8_list {
@@ -162,6 +213,34 @@ impl Wire2Api<String> for *mut wire_uint_8_list {
         String::from_utf8_lossy(&vec).into_owned()
     }
 }
+impl Wire2Api<Vec<SelfAssertedAttribute>> for *mut wire_list_self_asserted_attribute {
+    fn wire2api(self) -> Vec<SelfAssertedAttribute> {
+        let vec = unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        };
+        vec.into_iter().map(Wire2Api::wire2api).collect()
+    }
+}
+impl Wire2Api<SelfAssertedAttribute> for wire_SelfAssertedAttribute {
+    fn wire2api(self) -> SelfAssertedAttribute {
+        SelfAssertedAttribute {
+            doc_type: self.doc_type.wire2api(),
+            namespace: self.namespace.wire2api(),
+            key: self.key.wire2api(),
+            value: self.value.wire2api(),
+        }
+    }
+}
+
+impl Wire2Api<Vec<u32>> for *mut wire_uint_32_list {
+    fn wire2api(self) -> Vec<u32> {
+        unsafe {
+            let wrap = support::box_from_leak_ptr(self);
+            support::vec_from_leak_ptr(wrap.ptr, wrap.len)
+        }
+    }
+}
 
 impl Wire2Api<Vec<u8>> for *mut wire_uint_8_list {
     fn wire2api(self) -> Vec<u8> {
@@ -173,6 +252,29 @@ impl Wire2Api<Vec<u8>> for *mut wire_uint_8_list {
 }
 // Section: wire structs
 
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_list_self_asserted_attribute {
+    ptr: *mut wire_SelfAssertedAttribute,
+    len: i32,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_SelfAssertedAttribute {
+    doc_type: *mut wire_uint_8_list,
+    namespace: *mut wire_uint_8_list,
+    key: *mut wire_uint_8_list,
+    value: *mut wire_uint_8_list,
+}
+
+#[repr(C)]
+#[derive(Clone)]
+pub struct wire_uint_32_list {
+    ptr: *mut u32,
+    len: i32,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct wire_uint_8_list {
@@ -192,6 +294,23 @@ impl<T> NewWithNullPtr for *mut T {
     }
 }
 
+impl NewWithNullPtr for wire_SelfAssertedAttribute {
+    fn new_with_null_ptr() -> Self {
+        Self {
+            doc_type: core::ptr::null_mut(),
+            namespace: core::ptr::null_mut(),
+            key: core::ptr::null_mut(),
+            value: core::ptr::null_mut(),
+        }
+    }
+}
+
+impl Default for wire_SelfAssertedAttribute {
+    fn default() -> Self {
+        Self::new_with_null_ptr()
+    }
+}
+
 // Section: sync execution mode utility
 
 #[no_mangle]
@@ -24,11 +24,16 @@ use crate::models::card::Card;
 use crate::models::card::CardAttribute;
 use crate::models::card::CardPersistence;
 use crate::models::card::CardValue;
+use crate::models::card::CardValueDictionaryEntry;
 use crate::models::card::GenderCardValue;
 use crate::models::card::LocalizedString;
 use crate::models::config::FlutterConfiguration;
+use crate::models::device::LinkedDevice;
+use crate::models::device::LinkedDevicesResult;
+use crate::models::device::StartDeviceLinkResult;
 use crate::models::disclosure::AcceptDisclosureResult;
 use crate::models::disclosure::DisclosureCard;
+use crate::models::disclosure::DisclosureCardCandidates;
 use crate::models::disclosure::DisclosureSessionType;
 use crate::models::disclosure::DisclosureStatus;
 use crate::models::disclosure::DisclosureType;
@@ -36,6 +41,8 @@ use crate::models::disclosure::Image;
 use crate::models::disclosure::MissingAttribute;
 use crate::models::disclosure::Organization;
 use crate::models::disclosure::RequestPolicy;
+use crate::models::disclosure::RequestedSelfAssertedAttribute;
+use crate::models::disclosure::SelfAssertedAttribute;
 use crate::models::disclosure::StartDisclosureResult;
 use crate::models::instruction::WalletInstructionError;
 use crate::models::instruction::WalletInstructionResult;
@@ -297,6 +304,35 @@ fn wire_start_disclosure_impl(
         },
     )
 }
+fn wire_select_disclosure_candidates_impl(port_: MessagePort, selection: impl Wire2Api<Vec<u32>> + UnwindSafe) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, Vec<DisclosureCard>, _>(
+        WrapInfo {
+            debug_name: "select_disclosure_candidates",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_selection = selection.wire2api();
+            move |task_callback| select_disclosure_candidates(api_selection)
+        },
+    )
+}
+fn wire_set_disclosure_self_asserted_attributes_impl(
+    port_: MessagePort,
+    attributes: impl Wire2Api<Vec<SelfAssertedAttribute>> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
+        WrapInfo {
+            debug_name: "set_disclosure_self_asserted_attributes",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_attributes = attributes.wire2api();
+            move |task_callback| set_disclosure_self_asserted_attributes(api_attributes)
+        },
+    )
+}
 fn wire_cancel_disclosure_impl(port_: MessagePort) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
@@ -353,6 +389,68 @@ fn wire_get_history_for_card_impl(port_: MessagePort, doc_type: impl Wire2Api<St
         },
     )
 }
+fn wire_start_device_link_impl(port_: MessagePort, pin: impl Wire2Api<String> + UnwindSafe) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, StartDeviceLinkResult, _>(
+        WrapInfo {
+            debug_name: "start_device_link",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_pin = pin.wire2api();
+            move |task_callback| start_device_link(api_pin)
+        },
+    )
+}
+fn wire_link_device_impl(
+    port_: MessagePort,
+    pin: impl Wire2Api<String> + UnwindSafe,
+    link_code: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, WalletInstructionResult, _>(
+        WrapInfo {
+            debug_name: "link_device",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_pin = pin.wire2api();
+            let api_link_code = link_code.wire2api();
+            move |task_callback| link_device(api_pin, api_link_code)
+        },
+    )
+}
+fn wire_get_linked_devices_impl(port_: MessagePort, pin: impl Wire2Api<String> + UnwindSafe) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, LinkedDevicesResult, _>(
+        WrapInfo {
+            debug_name: "get_linked_devices",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_pin = pin.wire2api();
+            move |task_callback| get_linked_devices(api_pin)
+        },
+    )
+}
+fn wire_remove_linked_device_impl(
+    port_: MessagePort,
+    pin: impl Wire2Api<String> + UnwindSafe,
+    device_id: impl Wire2Api<String> + UnwindSafe,
+) {
+    FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, WalletInstructionResult, _>(
+        WrapInfo {
+            debug_name: "remove_linked_device",
+            port: Some(port_),
+            mode: FfiCallMode::Normal,
+        },
+        move || {
+            let api_pin = pin.wire2api();
+            let api_device_id = device_id.wire2api();
+            move |task_callback| remove_linked_device(api_pin, api_device_id)
+        },
+    )
+}
 fn wire_reset_wallet_impl(port_: MessagePort) {
     FLUTTER_RUST_BRIDGE_HANDLER.wrap::<_, _, _, (), _>(
         WrapInfo {
@@ -391,6 +489,11 @@ impl Wire2Api<bool> for bool {
         self
     }
 }
+impl Wire2Api<u32> for u32 {
+    fn wire2api(self) -> u32 {
+        self
+    }
+}
 impl Wire2Api<u8> for u8 {
     fn wire2api(self) -> u8 {
         self
@@ -473,6 +576,12 @@ impl support::IntoDart for CardValue {
             Self::Boolean { value } => vec![1.into_dart(), value.into_into_dart().into_dart()],
             Self::Date { value } => vec![2.into_dart(), value.into_into_dart().into_dart()],
             Self::Gender { value } => vec![3.into_dart(), value.into_into_dart().into_dart()],
+            Self::Integer { value } => vec![4.into_dart(), value.into_into_dart().into_dart()],
+            Self::DateTime { value } => vec![5.into_dart(), value.into_into_dart().into_dart()],
+            Self::Image { value } => vec![6.into_dart(), value.into_into_dart().into_dart()],
+            Self::Bytes { value } => vec![7.into_dart(), value.into_into_dart().into_dart()],
+            Self::Array { value } => vec![8.into_dart(), value.into_into_dart().into_dart()],
+            Self::Dictionary { value } => vec![9.into_dart(), value.into_into_dart().into_dart()],
         }
         .into_dart()
     }
@@ -484,6 +593,22 @@ impl rust2dart::IntoIntoDart<CardValue> for CardValue {
     }
 }
 
+impl support::IntoDart for CardValueDictionaryEntry {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.key.into_into_dart().into_dart(),
+            self.value.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for CardValueDictionaryEntry {}
+impl rust2dart::IntoIntoDart<CardValueDictionaryEntry> for CardValueDictionaryEntry {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for DisclosureCard {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -501,6 +626,18 @@ impl rust2dart::IntoIntoDart<DisclosureCard> for DisclosureCard {
     }
 }
 
+impl support::IntoDart for DisclosureCardCandidates {
+    fn into_dart(self) -> support::DartAbi {
+        vec![self.cards.into_into_dart().into_dart()].into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for DisclosureCardCandidates {}
+impl rust2dart::IntoIntoDart<DisclosureCardCandidates> for DisclosureCardCandidates {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for DisclosureSessionType {
     fn into_dart(self) -> support::DartAbi {
         match self {
@@ -619,6 +756,39 @@ impl rust2dart::IntoIntoDart<Image> for Image {
     }
 }
 
+impl support::IntoDart for LinkedDevice {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.id.into_into_dart().into_dart(),
+            self.linked_date_time.into_into_dart().into_dart(),
+            self.is_current.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for LinkedDevice {}
+impl rust2dart::IntoIntoDart<LinkedDevice> for LinkedDevice {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for LinkedDevicesResult {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::Ok { devices } => vec![0.into_dart(), devices.into_dart()],
+            Self::InstructionError { error } => vec![1.into_dart(), error.into_into_dart().into_dart()],
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for LinkedDevicesResult {}
+impl rust2dart::IntoIntoDart<LinkedDevicesResult> for LinkedDevicesResult {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for LocalizedString {
     fn into_dart(self) -> support::DartAbi {
         vec![
@@ -708,6 +878,39 @@ impl rust2dart::IntoIntoDart<RequestPolicy> for RequestPolicy {
     }
 }
 
+impl support::IntoDart for RequestedSelfAssertedAttribute {
+    fn into_dart(self) -> support::DartAbi {
+        vec![
+            self.doc_type.into_into_dart().into_dart(),
+            self.namespace.into_into_dart().into_dart(),
+            self.key.into_into_dart().into_dart(),
+        ]
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for RequestedSelfAssertedAttribute {}
+impl rust2dart::IntoIntoDart<RequestedSelfAssertedAttribute> for RequestedSelfAssertedAttribute {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
+impl support::IntoDart for StartDeviceLinkResult {
+    fn into_dart(self) -> support::DartAbi {
+        match self {
+            Self::Ok { link_code } => vec![0.into_dart(), link_code.into_into_dart().into_dart()],
+            Self::InstructionError { error } => vec![1.into_dart(), error.into_into_dart().into_dart()],
+        }
+        .into_dart()
+    }
+}
+impl support::IntoDartExceptPrimitive for StartDeviceLinkResult {}
+impl rust2dart::IntoIntoDart<StartDeviceLinkResult> for StartDeviceLinkResult {
+    fn into_into_dart(self) -> Self {
+        self
+    }
+}
+
 impl support::IntoDart for StartDisclosureResult {
     fn into_dart(self) -> support::DartAbi {
         match self {
@@ -715,6 +918,8 @@ impl support::IntoDart for StartDisclosureResult {
                 relying_party,
                 policy,
                 requested_cards,
+                candidate_cards,
+                requested_self_asserted_attributes,
                 shared_data_with_relying_party_before,
                 session_type,
                 request_purpose,
@@ -725,6 +930,8 @@ impl support::IntoDart for StartDisclosureResult {
                 relying_party.into_into_dart().into_dart(),
                 policy.into_into_dart().into_dart(),
                 requested_cards.into_into_dart().into_dart(),
+                candidate_cards.into_into_dart().into_dart(),
+                requested_self_asserted_attributes.into_into_dart().into_dart(),
                 shared_data_with_relying_party_before.into_into_dart().into_dart(),
                 session_type.into_into_dart().into_dart(),
                 request_purpose.into_into_dart().into_dart(),
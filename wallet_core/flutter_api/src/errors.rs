@@ -6,7 +6,7 @@ use serde::Serialize;
 use wallet::errors::{
     mdoc::{self, HolderError},
     openid4vc::{IssuanceSessionError, OidcError, VpClientError},
-    reqwest, AccountProviderError, DigidSessionError, DisclosureError, HistoryError, InstructionError,
+    reqwest, AccountProviderError, DeviceLinkError, DigidSessionError, DisclosureError, HistoryError, InstructionError,
     PidIssuanceError, ResetError, UriIdentificationError, WalletInitError, WalletRegistrationError, WalletUnlockError,
 };
 
@@ -93,6 +93,7 @@ impl TryFrom<anyhow::Error> for FlutterApiError {
             .or_else(|e| e.downcast::<DisclosureError>().map(Self::from))
             .or_else(|e| e.downcast::<HistoryError>().map(Self::from))
             .or_else(|e| e.downcast::<ResetError>().map(Self::from))
+            .or_else(|e| e.downcast::<DeviceLinkError>().map(Self::from))
             .or_else(|e| e.downcast::<url::ParseError>().map(Self::from))
     }
 }
@@ -234,6 +235,21 @@ impl FlutterApiErrorFields for DisclosureError {
     }
 }
 
+impl FlutterApiErrorFields for DeviceLinkError {
+    fn typ(&self) -> FlutterApiErrorType {
+        match self {
+            DeviceLinkError::NotRegistered | DeviceLinkError::Locked | DeviceLinkError::AlreadyRegistered => {
+                FlutterApiErrorType::WalletState
+            }
+            DeviceLinkError::HardwareAttestation(_) | DeviceLinkError::HardwarePublicKey(_) => {
+                FlutterApiErrorType::HardwareKeyUnsupported
+            }
+            DeviceLinkError::Instruction(error) => FlutterApiErrorType::from(error),
+            _ => FlutterApiErrorType::Generic,
+        }
+    }
+}
+
 impl FlutterApiErrorFields for url::ParseError {
     fn typ(&self) -> FlutterApiErrorType {
         FlutterApiErrorType::WalletState
@@ -264,7 +280,12 @@ impl From<&InstructionError> for FlutterApiErrorType {
     fn from(value: &InstructionError) -> Self {
         match value {
             InstructionError::ServerError(e) => FlutterApiErrorType::from(e),
-            InstructionError::InstructionValidation => FlutterApiErrorType::Server,
+            InstructionError::InstructionValidation | InstructionError::DeviceLinkValidation => {
+                FlutterApiErrorType::Server
+            }
+            InstructionError::Suspended | InstructionError::Revoked | InstructionError::DeviceNotLinked => {
+                FlutterApiErrorType::WalletState
+            }
             _ => FlutterApiErrorType::Generic,
         }
     }
@@ -27,6 +27,17 @@ pub enum CardValue {
     Boolean { value: bool },
     Date { value: String },
     Gender { value: GenderCardValue },
+    Integer { value: i64 },
+    DateTime { value: String },
+    Image { value: Vec<u8> },
+    Bytes { value: Vec<u8> },
+    Array { value: Vec<CardValue> },
+    Dictionary { value: Vec<CardValueDictionaryEntry> },
+}
+
+pub struct CardValueDictionaryEntry {
+    pub key: String,
+    pub value: CardValue,
 }
 
 pub enum GenderCardValue {
@@ -97,6 +108,22 @@ impl From<AttributeValue> for CardValue {
                 value: d.format("%Y-%m-%d").to_string(),
             },
             AttributeValue::Gender(g) => Self::Gender { value: g.into() },
+            AttributeValue::Integer(i) => Self::Integer { value: i },
+            AttributeValue::DateTime(d) => Self::DateTime { value: d.to_rfc3339() },
+            AttributeValue::Image(bytes) => Self::Image { value: bytes },
+            AttributeValue::Bytes(bytes) => Self::Bytes { value: bytes },
+            AttributeValue::Array(values) => Self::Array {
+                value: values.into_iter().map(CardValue::from).collect(),
+            },
+            AttributeValue::Map(entries) => Self::Dictionary {
+                value: entries
+                    .into_iter()
+                    .map(|(key, value)| CardValueDictionaryEntry {
+                        key,
+                        value: value.into(),
+                    })
+                    .collect(),
+            },
         }
     }
 }
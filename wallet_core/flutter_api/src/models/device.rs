@@ -0,0 +1,59 @@
+use wallet::{errors::DeviceLinkError, wallet_common};
+
+use super::instruction::WalletInstructionError;
+
+pub struct LinkedDevice {
+    pub id: String,
+    pub linked_date_time: String,
+    pub is_current: bool,
+}
+
+pub enum StartDeviceLinkResult {
+    Ok { link_code: String },
+    InstructionError { error: WalletInstructionError },
+}
+
+pub enum LinkedDevicesResult {
+    Ok { devices: Vec<LinkedDevice> },
+    InstructionError { error: WalletInstructionError },
+}
+
+impl From<wallet_common::LinkedDevice> for LinkedDevice {
+    fn from(value: wallet_common::LinkedDevice) -> Self {
+        LinkedDevice {
+            id: value.device_id,
+            linked_date_time: value.linked_date_time.to_rfc3339(),
+            is_current: value.is_current,
+        }
+    }
+}
+
+impl TryFrom<Result<String, DeviceLinkError>> for StartDeviceLinkResult {
+    type Error = DeviceLinkError;
+
+    fn try_from(value: Result<String, DeviceLinkError>) -> Result<Self, Self::Error> {
+        match value {
+            Ok(link_code) => Ok(StartDeviceLinkResult::Ok { link_code }),
+            Err(DeviceLinkError::Instruction(instruction_error)) => Ok(StartDeviceLinkResult::InstructionError {
+                error: instruction_error.try_into().map_err(DeviceLinkError::Instruction)?,
+            }),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl TryFrom<Result<Vec<wallet_common::LinkedDevice>, DeviceLinkError>> for LinkedDevicesResult {
+    type Error = DeviceLinkError;
+
+    fn try_from(value: Result<Vec<wallet_common::LinkedDevice>, DeviceLinkError>) -> Result<Self, Self::Error> {
+        match value {
+            Ok(devices) => Ok(LinkedDevicesResult::Ok {
+                devices: devices.into_iter().map(LinkedDevice::from).collect(),
+            }),
+            Err(DeviceLinkError::Instruction(instruction_error)) => Ok(LinkedDevicesResult::InstructionError {
+                error: instruction_error.try_into().map_err(DeviceLinkError::Instruction)?,
+            }),
+            Err(error) => Err(error),
+        }
+    }
+}
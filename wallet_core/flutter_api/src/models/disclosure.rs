@@ -1,8 +1,9 @@
+use itertools::Itertools;
 use url::Url;
 
 use wallet::{
     errors::DisclosureError,
-    mdoc::{ReaderRegistration, SessionType},
+    mdoc::{AttributeIdentifier, DataElementValue, Entry, ReaderRegistration, SelfAssertedAttributes, SessionType},
     DisclosureDocument, DisclosureProposal, MissingDisclosureAttributes,
 };
 
@@ -51,6 +52,27 @@ pub struct DisclosureCard {
     pub attributes: Vec<CardAttribute>,
 }
 
+/// All of the cards that match a single requested doc type, from which one can be selected for disclosure.
+pub struct DisclosureCardCandidates {
+    pub cards: Vec<DisclosureCard>,
+}
+
+/// An attribute requested by the relying party of which the user provides the value, instead of it being taken
+/// from a card. As the value is not signed by an issuer, it should be presented to the user as unverified.
+pub struct RequestedSelfAssertedAttribute {
+    pub doc_type: String,
+    pub namespace: String,
+    pub key: String,
+}
+
+/// The value entered by the user for a [`RequestedSelfAssertedAttribute`], which is disclosed unverified.
+pub struct SelfAssertedAttribute {
+    pub doc_type: String,
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+}
+
 pub enum DisclosureStatus {
     Success,
     Cancelled,
@@ -81,6 +103,8 @@ pub enum StartDisclosureResult {
         relying_party: Organization,
         policy: RequestPolicy,
         requested_cards: Vec<DisclosureCard>,
+        candidate_cards: Vec<DisclosureCardCandidates>,
+        requested_self_asserted_attributes: Vec<RequestedSelfAssertedAttribute>,
         shared_data_with_relying_party_before: bool,
         session_type: DisclosureSessionType,
         request_purpose: Vec<LocalizedString>,
@@ -171,11 +195,49 @@ impl From<&ReaderRegistration> for RequestPolicy {
 }
 
 impl DisclosureCard {
-    fn from_disclosure_documents(documents: Vec<DisclosureDocument>) -> Vec<Self> {
+    pub fn from_disclosure_documents(documents: Vec<DisclosureDocument>) -> Vec<Self> {
         documents.into_iter().map(DisclosureCard::from).collect()
     }
 }
 
+impl From<Vec<DisclosureDocument>> for DisclosureCardCandidates {
+    fn from(value: Vec<DisclosureDocument>) -> Self {
+        DisclosureCardCandidates {
+            cards: DisclosureCard::from_disclosure_documents(value),
+        }
+    }
+}
+
+impl From<AttributeIdentifier> for RequestedSelfAssertedAttribute {
+    fn from(value: AttributeIdentifier) -> Self {
+        RequestedSelfAssertedAttribute {
+            doc_type: value.doc_type,
+            namespace: value.namespace,
+            key: value.attribute,
+        }
+    }
+}
+
+impl SelfAssertedAttribute {
+    pub fn into_self_asserted_attributes(attributes: Vec<Self>) -> SelfAssertedAttributes {
+        let mut self_asserted_attributes = SelfAssertedAttributes::default();
+
+        for attribute in attributes {
+            self_asserted_attributes
+                .entry(attribute.doc_type)
+                .or_default()
+                .entry(attribute.namespace)
+                .or_default()
+                .push(Entry {
+                    name: attribute.key,
+                    value: DataElementValue::Text(attribute.value),
+                });
+        }
+
+        self_asserted_attributes
+    }
+}
+
 impl From<DisclosureDocument> for DisclosureCard {
     fn from(value: DisclosureDocument) -> Self {
         DisclosureCard {
@@ -200,7 +262,16 @@ impl MissingAttribute {
     fn from_missing_disclosure_attributes(attributes: Vec<MissingDisclosureAttributes>) -> Vec<Self> {
         attributes
             .into_iter()
-            .flat_map(|doc_attributes| doc_attributes.attributes.into_iter())
+            .flat_map(|doc_attributes| {
+                let doc_type = doc_attributes.doc_type;
+
+                doc_attributes
+                    .attributes
+                    .into_iter()
+                    .map(move |(key, labels)| ((doc_type, key), labels))
+            })
+            // The same attribute may be missing from multiple cards of the same type, only report it once.
+            .unique_by(|(attribute, _)| *attribute)
             .map(|(_, labels)| {
                 let labels = labels
                     .into_iter()
@@ -229,6 +300,16 @@ impl TryFrom<Result<DisclosureProposal, DisclosureError>> for StartDisclosureRes
                     relying_party: proposal.reader_registration.organization.into(),
                     policy,
                     requested_cards: DisclosureCard::from_disclosure_documents(proposal.documents),
+                    candidate_cards: proposal
+                        .candidates
+                        .into_iter()
+                        .map(DisclosureCardCandidates::from)
+                        .collect(),
+                    requested_self_asserted_attributes: proposal
+                        .requested_self_asserted_attributes
+                        .into_iter()
+                        .map(RequestedSelfAssertedAttribute::from)
+                        .collect(),
                     shared_data_with_relying_party_before: proposal.shared_data_with_relying_party_before,
                     session_type: proposal.session_type.into(),
                     request_purpose,
@@ -1,4 +1,4 @@
-use wallet::errors::{InstructionError, PidIssuanceError, WalletUnlockError};
+use wallet::errors::{DeviceLinkError, InstructionError, PidIssuanceError, WalletUnlockError};
 
 pub enum WalletInstructionResult {
     Ok,
@@ -79,3 +79,24 @@ impl TryFrom<Result<(), PidIssuanceError>> for WalletInstructionResult {
         }
     }
 }
+
+/// This conversion distinguishes between 3 distinct cases:
+///
+/// 1. In case of a successful result, [`WalletInstructionResult::Ok`] will be returned.
+/// 2. In case of an expected and/or specific error case a different variant of
+///    [`WalletInstructionResult`] by mapping the nested [InstructionError].
+/// 3. In any other cases, this is an unexpected and/or generic error and the
+///    [`DeviceLinkError`] will be returned unchanged.
+impl TryFrom<Result<(), DeviceLinkError>> for WalletInstructionResult {
+    type Error = DeviceLinkError;
+
+    fn try_from(value: Result<(), DeviceLinkError>) -> Result<Self, Self::Error> {
+        match value {
+            Ok(_) => Ok(WalletInstructionResult::Ok),
+            Err(DeviceLinkError::Instruction(instruction_error)) => Ok(WalletInstructionResult::InstructionError {
+                error: instruction_error.try_into().map_err(DeviceLinkError::Instruction)?,
+            }),
+            Err(error) => Err(error),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod card;
 pub mod config;
+pub mod device;
 pub mod disclosure;
 pub mod instruction;
 pub mod pin;
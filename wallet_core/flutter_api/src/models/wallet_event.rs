@@ -44,6 +44,7 @@ impl From<HistoryEvent> for WalletEvents {
                 })
                 .collect(),
             HistoryEvent::Disclosure {
+                id: _,
                 status,
                 r#type,
                 timestamp,
@@ -61,6 +62,8 @@ impl From<HistoryEvent> for WalletEvents {
                     r#type: r#type.into(),
                 }]
             }
+            // Deletion requests are not yet shown in the history of the app.
+            HistoryEvent::DeletionRequest { .. } => vec![],
         };
         WalletEvents(result)
     }
@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use p256::ecdsa::{Signature, VerifyingKey};
+
+use wallet_common::keys::{EcdsaKey, SecureEcdsaKey, WithIdentifier};
+
+use crate::{
+    model::hsm::Hsm,
+    service::{ConfiguredHsm, HsmError},
+};
+
+/// An ECDSA private key that resides in the HSM, referred to by its identifier.
+pub struct HsmEcdsaKey {
+    identifier: String,
+    hsm: ConfiguredHsm,
+}
+
+impl HsmEcdsaKey {
+    pub fn new(identifier: String, hsm: ConfiguredHsm) -> Self {
+        Self { identifier, hsm }
+    }
+}
+
+impl EcdsaKey for HsmEcdsaKey {
+    type Error = HsmError;
+
+    async fn verifying_key(&self) -> Result<VerifyingKey, Self::Error> {
+        self.hsm.get_verifying_key(&self.identifier).await
+    }
+
+    async fn try_sign(&self, msg: &[u8]) -> Result<Signature, Self::Error> {
+        Hsm::sign_ecdsa(&self.hsm, &self.identifier, Arc::new(msg.into())).await
+    }
+}
+
+impl WithIdentifier for HsmEcdsaKey {
+    fn identifier(&self) -> &str {
+        &self.identifier
+    }
+}
+
+impl SecureEcdsaKey for HsmEcdsaKey {}
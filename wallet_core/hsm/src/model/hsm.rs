@@ -3,7 +3,7 @@ use std::{error::Error, sync::Arc};
 use futures::future;
 use p256::ecdsa::{Signature, VerifyingKey};
 
-use crate::model::{encrypted::Encrypted, wallet_user::WalletId, wrapped_key::WrappedKey};
+use crate::model::{encrypted::Encrypted, wrapped_key::WrappedKey};
 
 pub fn key_identifier(prefix: &str, identifier: &str) -> String {
     format!("{prefix}_{identifier}")
@@ -12,6 +12,9 @@ pub fn key_identifier(prefix: &str, identifier: &str) -> String {
 pub trait WalletUserHsm {
     type Error: Error + Send + Sync;
 
+    /// The version of the wrapping key that is used for newly wrapped keys.
+    fn wrapping_key_version(&self) -> u32;
+
     async fn generate_wrapped_key(&self) -> Result<(VerifyingKey, WrappedKey), Self::Error>;
 
     async fn generate_wrapped_keys(
@@ -25,11 +28,11 @@ pub trait WalletUserHsm {
         .await
     }
 
-    async fn generate_key(&self, wallet_id: &WalletId, identifier: &str) -> Result<VerifyingKey, Self::Error>;
+    async fn generate_key(&self, wallet_id: &str, identifier: &str) -> Result<VerifyingKey, Self::Error>;
 
     async fn generate_keys(
         &self,
-        wallet_id: &WalletId,
+        wallet_id: &str,
         identifiers: &[&str],
     ) -> Result<Vec<(String, VerifyingKey)>, Self::Error> {
         future::try_join_all(identifiers.iter().map(|identifier| async move {
@@ -41,11 +44,52 @@ pub trait WalletUserHsm {
 
     async fn sign_wrapped(&self, wrapped_key: WrappedKey, data: Arc<Vec<u8>>) -> Result<Signature, Self::Error>;
 
-    async fn sign(&self, wallet_id: &WalletId, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature, Self::Error>;
+    async fn sign_wrapped_multiple(
+        &self,
+        wrapped_keys: Vec<(WrappedKey, Arc<Vec<u8>>)>,
+    ) -> Result<Vec<Signature>, Self::Error> {
+        future::try_join_all(
+            wrapped_keys
+                .into_iter()
+                .map(|(wrapped_key, data)| self.sign_wrapped(wrapped_key, data)),
+        )
+        .await
+    }
+
+    async fn sign(&self, wallet_id: &str, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature, Self::Error>;
+
+    /// Perform an ECDH key agreement between the wrapped key and `public_key`, returning the raw shared secret, which is
+    /// the x-coordinate of the resulting point.
+    async fn diffie_hellman_wrapped(
+        &self,
+        wrapped_key: WrappedKey,
+        public_key: VerifyingKey,
+    ) -> Result<Vec<u8>, Self::Error>;
+
+    async fn diffie_hellman_wrapped_multiple(
+        &self,
+        wrapped_keys: Vec<WrappedKey>,
+        public_key: VerifyingKey,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        future::try_join_all(
+            wrapped_keys
+                .into_iter()
+                .map(|wrapped_key| self.diffie_hellman_wrapped(wrapped_key, public_key)),
+        )
+        .await
+    }
+
+    /// Unwrap a key that is wrapped with a non-retired version of the wrapping key and wrap it again with the current
+    /// version.
+    async fn rewrap(&self, wrapped_key: WrappedKey) -> Result<WrappedKey, Self::Error>;
+
+    async fn rewrap_multiple(&self, wrapped_keys: Vec<WrappedKey>) -> Result<Vec<WrappedKey>, Self::Error> {
+        future::try_join_all(wrapped_keys.into_iter().map(|wrapped_key| self.rewrap(wrapped_key))).await
+    }
 
     async fn sign_multiple(
         &self,
-        wallet_id: &WalletId,
+        wallet_id: &str,
         identifiers: &[&str],
         data: Arc<Vec<u8>>,
     ) -> Result<Vec<(String, Signature)>, Self::Error> {
@@ -77,7 +121,10 @@ pub mod mock {
 
     use dashmap::DashMap;
     use hmac::{digest::MacError, Hmac, Mac};
-    use p256::ecdsa::{signature::Signer, Signature, SigningKey, VerifyingKey};
+    use p256::{
+        ecdh,
+        ecdsa::{signature::Signer, Signature, SigningKey, VerifyingKey},
+    };
     use rand::rngs::OsRng;
     use sha2::Sha256;
 
@@ -87,7 +134,6 @@ pub mod mock {
         encrypted::{Encrypted, InitializationVector},
         encrypter::{Decrypter, Encrypter},
         hsm::{key_identifier, Hsm, WalletUserHsm},
-        wallet_user::WalletId,
         wrapped_key::WrappedKey,
     };
 
@@ -137,13 +183,17 @@ pub mod mock {
     impl<E: Error + Send + Sync + From<MacError>> WalletUserHsm for MockPkcs11Client<E> {
         type Error = E;
 
+        fn wrapping_key_version(&self) -> u32 {
+            0
+        }
+
         async fn generate_wrapped_key(&self) -> Result<(VerifyingKey, WrappedKey), Self::Error> {
             let key = SigningKey::random(&mut OsRng);
             let verifying_key = *key.verifying_key();
-            Ok((verifying_key, WrappedKey::new(key.to_bytes().to_vec())))
+            Ok((verifying_key, WrappedKey::new(key.to_bytes().to_vec(), 0)))
         }
 
-        async fn generate_key(&self, wallet_id: &WalletId, identifier: &str) -> Result<VerifyingKey, Self::Error> {
+        async fn generate_key(&self, wallet_id: &str, identifier: &str) -> Result<VerifyingKey, Self::Error> {
             let key = SigningKey::random(&mut OsRng);
             let verifying_key = *key.verifying_key();
             self.0.insert(key_identifier(wallet_id, identifier), key);
@@ -157,14 +207,24 @@ pub mod mock {
             Ok(signature)
         }
 
-        async fn sign(
+        async fn diffie_hellman_wrapped(
             &self,
-            wallet_id: &WalletId,
-            identifier: &str,
-            data: Arc<Vec<u8>>,
-        ) -> Result<Signature, Self::Error> {
+            wrapped_key: WrappedKey,
+            public_key: VerifyingKey,
+        ) -> Result<Vec<u8>, Self::Error> {
+            let wrapped_key: Vec<u8> = wrapped_key.into();
+            let key = SigningKey::from_slice(&wrapped_key).unwrap();
+            let shared_secret = ecdh::diffie_hellman(key.as_nonzero_scalar(), public_key.as_affine());
+            Ok(shared_secret.raw_secret_bytes().to_vec())
+        }
+
+        async fn sign(&self, wallet_id: &str, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature, Self::Error> {
             Hsm::sign_ecdsa(self, &key_identifier(wallet_id, identifier), data).await
         }
+
+        async fn rewrap(&self, wrapped_key: WrappedKey) -> Result<WrappedKey, Self::Error> {
+            Ok(WrappedKey::new(wrapped_key.into(), 0))
+        }
     }
 
     impl<E: Error + Send + Sync + From<MacError>> Hsm for MockPkcs11Client<E> {
@@ -0,0 +1,5 @@
+pub mod encrypted;
+pub mod encrypter;
+pub mod hsm;
+pub mod versioned_key;
+pub mod wrapped_key;
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+/// A key in the HSM of which multiple versions can exist, because it is rotated periodically. The current version is
+/// used for wrapping or encrypting, while any earlier version that is not retired can still be used for unwrapping or
+/// decrypting existing data. Version 0 refers to the key by its plain identifier, so that keys created before
+/// versioning remain usable. Later versions have the version number appended to the identifier.
+#[derive(Debug, Clone)]
+pub struct VersionedKey {
+    identifier: String,
+    current_version: u32,
+    retired_versions: HashSet<u32>,
+}
+
+impl VersionedKey {
+    pub fn new(identifier: String, current_version: u32, retired_versions: HashSet<u32>) -> Self {
+        assert!(
+            !retired_versions.contains(&current_version),
+            "the current key version cannot be retired"
+        );
+
+        Self {
+            identifier,
+            current_version,
+            retired_versions,
+        }
+    }
+
+    pub fn current_version(&self) -> u32 {
+        self.current_version
+    }
+
+    pub fn current_identifier(&self) -> String {
+        versioned_identifier(&self.identifier, self.current_version)
+    }
+
+    /// The identifier of the specified version of the key, unless that version is retired or does not exist yet.
+    pub fn identifier(&self, version: u32) -> Option<String> {
+        (version <= self.current_version && !self.retired_versions.contains(&version))
+            .then(|| versioned_identifier(&self.identifier, version))
+    }
+}
+
+impl From<String> for VersionedKey {
+    fn from(identifier: String) -> Self {
+        Self::new(identifier, 0, HashSet::new())
+    }
+}
+
+fn versioned_identifier(identifier: &str, version: u32) -> String {
+    match version {
+        0 => identifier.to_string(),
+        version => format!("{identifier}_v{version}"),
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Debug, Clone)]
+pub struct WrappedKey {
+    wrapped_key_bytes: Vec<u8>,
+    wrapping_key_version: u32,
+}
+
+impl WrappedKey {
+    pub fn new(wrapped_key_bytes: Vec<u8>, wrapping_key_version: u32) -> Self {
+        Self {
+            wrapped_key_bytes,
+            wrapping_key_version,
+        }
+    }
+
+    /// The version of the wrapping key that was used to wrap this key.
+    pub fn wrapping_key_version(&self) -> u32 {
+        self.wrapping_key_version
+    }
+}
+
+impl From<WrappedKey> for Vec<u8> {
+    fn from(value: WrappedKey) -> Self {
+        value.wrapped_key_bytes
+    }
+}
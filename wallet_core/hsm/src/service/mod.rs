@@ -0,0 +1,1176 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    mechanism::{
+        aead::GcmParams,
+        elliptic_curve::{EcKdf, Ecdh1DeriveParams},
+        Mechanism,
+    },
+    object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+    session::Session,
+    types::AuthPin,
+};
+use der::{asn1::OctetString, Decode, Encode};
+use p256::{
+    ecdsa::{Signature, VerifyingKey},
+    pkcs8::AssociatedOid,
+    NistP256,
+};
+use r2d2_cryptoki::{Pool, SessionManager, SessionType};
+use sec1::EcParameters;
+use tokio::sync::Semaphore;
+use tracing::{debug, warn};
+
+use crate::model::{
+    encrypted::{Encrypted, InitializationVector},
+    encrypter::{Decrypter, Encrypter},
+    hsm,
+    hsm::{Hsm, WalletUserHsm},
+    versioned_key::VersionedKey,
+    wrapped_key::WrappedKey,
+};
+use wallet_common::{spawn, utils::sha256};
+
+pub use self::software::SoftwareHsm;
+
+mod software;
+
+#[derive(Debug, thiserror::Error)]
+pub enum HsmError {
+    #[error("pkcs11 error: {0}")]
+    Pkcs11(#[from] cryptoki::error::Error),
+
+    #[error("r2d2 error: {0}")]
+    R2d2(#[from] r2d2_cryptoki::r2d2::Error),
+
+    #[error("sec1 error: {0}")]
+    Sec1(#[from] sec1::der::Error),
+
+    #[error("no initialized slot available")]
+    NoInitializedSlotAvailable,
+
+    #[error("p256 error: {0}")]
+    P256(#[from] p256::ecdsa::Error),
+
+    #[error("attribute not found: '{0}'")]
+    AttributeNotFound(String),
+
+    #[error("key not found: '{0}'")]
+    KeyNotFound(String),
+
+    #[error("key version {0} is retired or does not exist")]
+    UnavailableKeyVersion(u32),
+
+    #[error("maximum number of batch sessions should be positive and lower than the maximum number of sessions")]
+    InvalidMaxBatchSessions,
+
+    #[error("hmac error: {0}")]
+    Hmac(#[from] hmac::digest::MacError),
+
+    #[error("aes-gcm error: {0}")]
+    AesGcm(#[from] aes_gcm::Error),
+
+    #[error("key file io error: {0}")]
+    KeyFileIo(#[from] std::io::Error),
+
+    #[error("key file serialization error: {0}")]
+    KeyFileSerialization(#[from] serde_json::Error),
+
+    #[error("software HSM is not allowed in production unless explicitly enabled")]
+    SoftwareHsmNotAllowed,
+}
+
+type Result<T> = std::result::Result<T, HsmError>;
+
+pub(crate) struct PrivateKeyHandle(ObjectHandle);
+pub(crate) struct PublicKeyHandle(ObjectHandle);
+
+const AES_AUTHENTICATION_TAG_BITS: u64 = 128;
+
+enum HandleType {
+    Public,
+    Private,
+}
+
+pub(crate) enum SigningMechanism {
+    Ecdsa256,
+    Sha256Hmac,
+}
+
+pub(crate) trait Pkcs11Client {
+    async fn generate_generic_secret_key(&self, identifier: &str) -> Result<PrivateKeyHandle>;
+    async fn generate_session_signing_key_pair(&self) -> Result<(PublicKeyHandle, PrivateKeyHandle)>;
+    async fn generate_signing_key_pair(&self, identifier: &str) -> Result<(PublicKeyHandle, PrivateKeyHandle)>;
+    async fn get_private_key_handle(&self, identifier: &str) -> Result<PrivateKeyHandle>;
+    async fn get_public_key_handle(&self, identifier: &str) -> Result<PublicKeyHandle>;
+    async fn get_verifying_key(&self, public_key_handle: PublicKeyHandle) -> Result<VerifyingKey>;
+    async fn wrap_key(
+        &self,
+        wrapping_key: PrivateKeyHandle,
+        wrapping_key_version: u32,
+        key: PrivateKeyHandle,
+    ) -> Result<WrappedKey>;
+    async fn unwrap_signing_key(
+        &self,
+        unwrapping_key: PrivateKeyHandle,
+        wrapped_key: WrappedKey,
+    ) -> Result<PrivateKeyHandle>;
+    async fn delete_key(&self, private_key_handle: PrivateKeyHandle) -> Result<()>;
+    async fn sign(
+        &self,
+        private_key_handle: PrivateKeyHandle,
+        mechanism: SigningMechanism,
+        data: Arc<Vec<u8>>,
+    ) -> Result<Vec<u8>>;
+    async fn verify(
+        &self,
+        private_key_handle: PrivateKeyHandle,
+        mechanism: SigningMechanism,
+        data: Arc<Vec<u8>>,
+        signature: Vec<u8>,
+    ) -> Result<()>;
+    async fn random_bytes(&self, length: u32) -> Result<Vec<u8>>;
+    async fn encrypt(
+        &self,
+        key_handle: PrivateKeyHandle,
+        iv: InitializationVector,
+        data: Vec<u8>,
+    ) -> Result<(Vec<u8>, InitializationVector)>;
+    async fn decrypt(
+        &self,
+        key_handle: PrivateKeyHandle,
+        iv: InitializationVector,
+        encrypted_data: Vec<u8>,
+    ) -> Result<Vec<u8>>;
+}
+
+#[derive(Clone)]
+pub struct Pkcs11Hsm {
+    pool: Pool,
+    wrapping_key: VersionedKey,
+    // Limits the number of sessions that are used for batch operations at the same time, so that the pool is never
+    // exhausted by batches alone.
+    batch_permits: Arc<Semaphore>,
+    batch_checkout_timeout: Duration,
+}
+
+impl Pkcs11Hsm {
+    pub fn new(
+        library_path: PathBuf,
+        user_pin: String,
+        max_sessions: u8,
+        max_session_lifetime: Duration,
+        max_batch_sessions: u8,
+        batch_checkout_timeout: Duration,
+        wrapping_key: VersionedKey,
+    ) -> Result<Self> {
+        if max_batch_sessions == 0 || max_batch_sessions >= max_sessions {
+            return Err(HsmError::InvalidMaxBatchSessions);
+        }
+
+        let pkcs11_client = Pkcs11::new(library_path)?;
+        pkcs11_client.initialize(CInitializeArgs::OsThreads)?;
+
+        let slot = *pkcs11_client
+            .get_slots_with_initialized_token()?
+            .first()
+            .ok_or(HsmError::NoInitializedSlotAvailable)?;
+
+        let manager = SessionManager::new(pkcs11_client, slot, SessionType::RwUser(AuthPin::new(user_pin)));
+
+        let pool = Pool::builder()
+            .max_size(max_sessions.into())
+            .max_lifetime(Some(max_session_lifetime))
+            // This makes a pkcs11 call every time a connection is check out of the pool and should be evaluated in a future performance test.
+            .test_on_check_out(true)
+            .build(manager)
+            .unwrap();
+
+        Ok(Self {
+            pool,
+            wrapping_key,
+            batch_permits: Arc::new(Semaphore::new(max_batch_sessions.into())),
+            batch_checkout_timeout,
+        })
+    }
+
+    fn unwrapping_key_identifier(&self, version: u32) -> Result<String> {
+        unwrapping_key_identifier(&self.wrapping_key, version)
+    }
+
+    async fn get_key_handle(&self, identifier: &str, handle_type: HandleType) -> Result<ObjectHandle> {
+        let pool = self.pool.clone();
+        let identifier = String::from(identifier);
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            find_key_handle(&session, &identifier, handle_type)
+        })
+        .await
+    }
+
+    /// Run a batch of operations on a single session. Waits for a batch permit first, so that at most
+    /// `max_batch_sessions` batches are in progress at the same time, and fails when no session can be checked out
+    /// of the pool within the batch checkout timeout. The time spent waiting and executing is logged per batch.
+    async fn batch<F, R>(&self, operation: &'static str, size: usize, fun: F) -> Result<R>
+    where
+        F: FnOnce(&Session) -> Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let started = Instant::now();
+        let _permit = self
+            .batch_permits
+            .acquire()
+            .await
+            .expect("batch semaphore should never be closed");
+
+        let pool = self.pool.clone();
+        let checkout_timeout = self.batch_checkout_timeout;
+
+        let (waited, result) = spawn::blocking(move || {
+            let session = pool.get_timeout(checkout_timeout)?;
+            let waited = started.elapsed();
+            fun(&session).map(|result| (waited, result))
+        })
+        .await?;
+
+        debug!(
+            operation,
+            size,
+            wait_ms = waited.as_millis(),
+            duration_ms = (started.elapsed() - waited).as_millis(),
+            "executed HSM batch operation"
+        );
+
+        Ok(result)
+    }
+}
+
+fn unwrapping_key_identifier(wrapping_key: &VersionedKey, version: u32) -> Result<String> {
+    wrapping_key
+        .identifier(version)
+        .ok_or(HsmError::UnavailableKeyVersion(version))
+}
+
+/// Finds the handle of the version of the wrapping key that is needed to unwrap `wrapped_key`, caching the handles
+/// per version, as a batch usually contains keys that are wrapped with the same version.
+fn find_unwrapping_key_handle(
+    session: &Session,
+    wrapping_key: &VersionedKey,
+    wrapped_key: &WrappedKey,
+    handles: &mut HashMap<u32, ObjectHandle>,
+) -> Result<PrivateKeyHandle> {
+    let version = wrapped_key.wrapping_key_version();
+
+    let handle = match handles.get(&version) {
+        Some(handle) => *handle,
+        None => {
+            let identifier = unwrapping_key_identifier(wrapping_key, version)?;
+            let handle = find_key_handle(session, &identifier, HandleType::Private)?;
+            handles.insert(version, handle);
+            handle
+        }
+    };
+
+    Ok(PrivateKeyHandle(handle))
+}
+
+/// Destroys a session object when dropped. The sessions are returned to the pool after use, so temporary objects such
+/// as unwrapped keys would otherwise remain in the HSM whenever an operation on them fails.
+struct SessionObjectGuard<'a> {
+    session: &'a Session,
+    handle: ObjectHandle,
+}
+
+impl<'a> SessionObjectGuard<'a> {
+    fn new(session: &'a Session, handle: ObjectHandle) -> Self {
+        SessionObjectGuard { session, handle }
+    }
+}
+
+impl Drop for SessionObjectGuard<'_> {
+    fn drop(&mut self) {
+        if let Err(error) = self.session.destroy_object(self.handle) {
+            warn!("could not destroy temporary HSM session object: {error}");
+        }
+    }
+}
+
+fn find_key_handle(session: &Session, identifier: &str, handle_type: HandleType) -> Result<ObjectHandle> {
+    let object_handles = session.find_objects(&[
+        Attribute::Private(matches!(handle_type, HandleType::Private)),
+        Attribute::Label(identifier.into()),
+    ])?;
+    let object_handle = object_handles
+        .first()
+        .cloned()
+        .ok_or_else(|| HsmError::KeyNotFound(String::from(identifier)))?;
+    Ok(object_handle)
+}
+
+fn generate_session_signing_key_pair(session: &Session) -> Result<(PublicKeyHandle, PrivateKeyHandle)> {
+    let mut oid = vec![];
+    EcParameters::NamedCurve(NistP256::OID).encode_to_vec(&mut oid)?;
+
+    let pub_key_template = &[Attribute::EcParams(oid)];
+    let priv_key_template = &[
+        Attribute::Token(false),
+        Attribute::Private(true),
+        Attribute::Extractable(true),
+        Attribute::Derive(false),
+        Attribute::Sign(false),
+    ];
+
+    let (public_handle, private_handle) =
+        session.generate_key_pair(&Mechanism::EccKeyPairGen, pub_key_template, priv_key_template)?;
+
+    Ok((PublicKeyHandle(public_handle), PrivateKeyHandle(private_handle)))
+}
+
+fn generate_signing_key_pair(session: &Session, identifier: &str) -> Result<(PublicKeyHandle, PrivateKeyHandle)> {
+    let mut oid = vec![];
+    EcParameters::NamedCurve(NistP256::OID).encode_to_vec(&mut oid)?;
+
+    let pub_key_template = &[Attribute::EcParams(oid), Attribute::Label(identifier.into())];
+    let priv_key_template = &[
+        Attribute::Token(true),
+        Attribute::Private(true),
+        Attribute::Sensitive(true),
+        Attribute::Extractable(false),
+        Attribute::Derive(false),
+        Attribute::Sign(true),
+        Attribute::Label(identifier.into()),
+    ];
+
+    let (public_handle, private_handle) =
+        session.generate_key_pair(&Mechanism::EccKeyPairGen, pub_key_template, priv_key_template)?;
+
+    Ok((PublicKeyHandle(public_handle), PrivateKeyHandle(private_handle)))
+}
+
+fn get_verifying_key(session: &Session, public_key_handle: &PublicKeyHandle) -> Result<VerifyingKey> {
+    let attr = session
+        .get_attributes(public_key_handle.0, &[AttributeType::EcPoint])?
+        .first()
+        .cloned()
+        .ok_or(HsmError::AttributeNotFound(AttributeType::EcPoint.to_string()))?;
+
+    match attr {
+        Attribute::EcPoint(ec_point) => {
+            let octet_string = OctetString::from_der(&ec_point)?;
+            let public_key = VerifyingKey::from_sec1_bytes(octet_string.as_bytes())?;
+            Ok(public_key)
+        }
+        _ => Err(HsmError::AttributeNotFound(AttributeType::EcPoint.to_string())),
+    }
+}
+
+fn wrap_key(
+    session: &Session,
+    wrapping_key: &PrivateKeyHandle,
+    wrapping_key_version: u32,
+    key: &PrivateKeyHandle,
+) -> Result<WrappedKey> {
+    let wrapped_key_bytes = session.wrap_key(&Mechanism::AesKeyWrapPad, wrapping_key.0, key.0)?;
+    Ok(WrappedKey::new(wrapped_key_bytes, wrapping_key_version))
+}
+
+fn unwrap_signing_key(
+    session: &Session,
+    unwrapping_key: &PrivateKeyHandle,
+    wrapped_key: &[u8],
+) -> Result<PrivateKeyHandle> {
+    let handle = session.unwrap_key(
+        &Mechanism::AesKeyWrapPad,
+        unwrapping_key.0,
+        wrapped_key,
+        &[
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Token(false),
+            Attribute::Private(true),
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+        ],
+    )?;
+    Ok(PrivateKeyHandle(handle))
+}
+
+/// Unwraps a key to a session object that can be wrapped again, which is only used for rewrapping.
+fn unwrap_extractable_signing_key(
+    session: &Session,
+    unwrapping_key: &PrivateKeyHandle,
+    wrapped_key: &[u8],
+) -> Result<PrivateKeyHandle> {
+    let handle = session.unwrap_key(
+        &Mechanism::AesKeyWrapPad,
+        unwrapping_key.0,
+        wrapped_key,
+        &[
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Token(false),
+            Attribute::Private(true),
+            Attribute::Extractable(true),
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+        ],
+    )?;
+    Ok(PrivateKeyHandle(handle))
+}
+
+/// Unwraps a key to a session object that can only be used for ECDH key agreement.
+fn unwrap_key_agreement_key(
+    session: &Session,
+    unwrapping_key: &PrivateKeyHandle,
+    wrapped_key: &[u8],
+) -> Result<PrivateKeyHandle> {
+    let handle = session.unwrap_key(
+        &Mechanism::AesKeyWrapPad,
+        unwrapping_key.0,
+        wrapped_key,
+        &[
+            Attribute::KeyType(KeyType::EC),
+            Attribute::Token(false),
+            Attribute::Private(true),
+            Attribute::Derive(true),
+            Attribute::Sign(false),
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+        ],
+    )?;
+    Ok(PrivateKeyHandle(handle))
+}
+
+/// Derives the raw ECDH shared secret of a private key and a public key, which is extracted from the HSM so that the
+/// caller can derive its own keys from it.
+fn diffie_hellman(
+    session: &Session,
+    private_key_handle: &PrivateKeyHandle,
+    public_key: &VerifyingKey,
+) -> Result<Vec<u8>> {
+    let public_data = public_key.to_encoded_point(false);
+    let mechanism = Mechanism::Ecdh1Derive(Ecdh1DeriveParams::new(EcKdf::null(), public_data.as_bytes()));
+
+    let secret_handle = session.derive_key(
+        &mechanism,
+        private_key_handle.0,
+        &[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::ValueLen(32.into()),
+            Attribute::Token(false),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ],
+    )?;
+    let _secret_guard = SessionObjectGuard::new(session, secret_handle);
+
+    let attr = session.get_attributes(secret_handle, &[AttributeType::Value])?;
+
+    match attr.first() {
+        Some(Attribute::Value(shared_secret)) => Ok(shared_secret.clone()),
+        _ => Err(HsmError::AttributeNotFound(AttributeType::Value.to_string())),
+    }
+}
+
+fn sign(
+    session: &Session,
+    private_key_handle: &PrivateKeyHandle,
+    mechanism: SigningMechanism,
+    data: &[u8],
+) -> Result<Vec<u8>> {
+    let mechanism = match mechanism {
+        SigningMechanism::Ecdsa256 => Mechanism::Ecdsa,
+        SigningMechanism::Sha256Hmac => Mechanism::Sha256Hmac,
+    };
+
+    let signature = session.sign(&mechanism, private_key_handle.0, &sha256(data))?;
+    Ok(signature)
+}
+
+impl Encrypter<VerifyingKey> for Pkcs11Hsm {
+    type Error = HsmError;
+
+    async fn encrypt(
+        &self,
+        key_identifier: &str,
+        data: VerifyingKey,
+    ) -> std::result::Result<Encrypted<VerifyingKey>, Self::Error> {
+        let bytes: Vec<u8> = data.to_sec1_bytes().to_vec();
+        Hsm::encrypt(self, key_identifier, bytes).await
+    }
+}
+
+impl Decrypter<VerifyingKey> for Pkcs11Hsm {
+    type Error = HsmError;
+
+    async fn decrypt(
+        &self,
+        key_identifier: &str,
+        encrypted: Encrypted<VerifyingKey>,
+    ) -> std::result::Result<VerifyingKey, Self::Error> {
+        let decrypted = Hsm::decrypt(self, key_identifier, encrypted).await?;
+        Ok(VerifyingKey::from_sec1_bytes(&decrypted)?)
+    }
+}
+
+impl WalletUserHsm for Pkcs11Hsm {
+    type Error = HsmError;
+
+    fn wrapping_key_version(&self) -> u32 {
+        self.wrapping_key.current_version()
+    }
+
+    async fn generate_wrapped_key(&self) -> Result<(VerifyingKey, WrappedKey)> {
+        let private_wrapping_handle = self
+            .get_private_key_handle(&self.wrapping_key.current_identifier())
+            .await?;
+        let (public_handle, private_handle) = self.generate_session_signing_key_pair().await?;
+
+        let wrapped = self
+            .wrap_key(
+                private_wrapping_handle,
+                self.wrapping_key.current_version(),
+                private_handle,
+            )
+            .await?;
+        let verifying_key = Pkcs11Client::get_verifying_key(self, public_handle).await?;
+        Ok((verifying_key, wrapped))
+    }
+
+    async fn generate_key(&self, wallet_id: &str, identifier: &str) -> Result<VerifyingKey> {
+        let key_identifier = hsm::key_identifier(wallet_id, identifier);
+        let (public_handle, _private_handle) = self.generate_signing_key_pair(&key_identifier).await?;
+        Pkcs11Client::get_verifying_key(self, public_handle).await
+    }
+
+    async fn sign_wrapped(&self, wrapped_key: WrappedKey, data: Arc<Vec<u8>>) -> Result<Signature> {
+        let unwrapping_key_identifier = self.unwrapping_key_identifier(wrapped_key.wrapping_key_version())?;
+        let private_wrapping_handle = self.get_private_key_handle(&unwrapping_key_identifier).await?;
+        let private_handle = self.unwrap_signing_key(private_wrapping_handle, wrapped_key).await?;
+        let signature = Pkcs11Client::sign(self, private_handle, SigningMechanism::Ecdsa256, data).await?;
+        Ok(Signature::from_slice(&signature)?)
+    }
+
+    async fn sign(&self, wallet_id: &str, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature> {
+        let key_identifier = hsm::key_identifier(wallet_id, identifier);
+        let handle = self.get_private_key_handle(&key_identifier).await?;
+        let signature = Pkcs11Client::sign(self, handle, SigningMechanism::Ecdsa256, data).await?;
+        Ok(Signature::from_slice(&signature)?)
+    }
+
+    async fn generate_wrapped_keys(&self, identifiers: &[&str]) -> Result<Vec<(String, VerifyingKey, WrappedKey)>> {
+        let wrapping_key_identifier = self.wrapping_key.current_identifier();
+        let wrapping_key_version = self.wrapping_key.current_version();
+        let identifiers: Vec<String> = identifiers.iter().copied().map(String::from).collect();
+
+        self.batch("generate_wrapped_keys", identifiers.len(), move |session| {
+            let wrapping_handle =
+                PrivateKeyHandle(find_key_handle(session, &wrapping_key_identifier, HandleType::Private)?);
+
+            identifiers
+                .into_iter()
+                .map(|identifier| {
+                    let (public_handle, private_handle) = generate_session_signing_key_pair(session)?;
+                    let _public_guard = SessionObjectGuard::new(session, public_handle.0);
+                    let _private_guard = SessionObjectGuard::new(session, private_handle.0);
+
+                    let wrapped = wrap_key(session, &wrapping_handle, wrapping_key_version, &private_handle)?;
+                    let verifying_key = get_verifying_key(session, &public_handle)?;
+
+                    Ok((identifier, verifying_key, wrapped))
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn generate_keys(&self, wallet_id: &str, identifiers: &[&str]) -> Result<Vec<(String, VerifyingKey)>> {
+        let key_identifiers: Vec<(String, String)> = identifiers
+            .iter()
+            .map(|identifier| (String::from(*identifier), hsm::key_identifier(wallet_id, identifier)))
+            .collect();
+
+        self.batch("generate_keys", key_identifiers.len(), move |session| {
+            key_identifiers
+                .into_iter()
+                .map(|(identifier, key_identifier)| {
+                    let (public_handle, _private_handle) = generate_signing_key_pair(session, &key_identifier)?;
+                    let verifying_key = get_verifying_key(session, &public_handle)?;
+                    Ok((identifier, verifying_key))
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn sign_wrapped_multiple(&self, wrapped_keys: Vec<(WrappedKey, Arc<Vec<u8>>)>) -> Result<Vec<Signature>> {
+        let wrapping_key = self.wrapping_key.clone();
+
+        self.batch("sign_wrapped_multiple", wrapped_keys.len(), move |session| {
+            let mut unwrapping_handles = HashMap::new();
+
+            wrapped_keys
+                .into_iter()
+                .map(|(wrapped_key, data)| {
+                    let unwrapping_handle =
+                        find_unwrapping_key_handle(session, &wrapping_key, &wrapped_key, &mut unwrapping_handles)?;
+                    let wrapped_key: Vec<u8> = wrapped_key.into();
+                    let private_handle = unwrap_signing_key(session, &unwrapping_handle, &wrapped_key)?;
+                    let _private_guard = SessionObjectGuard::new(session, private_handle.0);
+
+                    let signature = sign(session, &private_handle, SigningMechanism::Ecdsa256, &data)?;
+
+                    Ok(Signature::from_slice(&signature)?)
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn diffie_hellman_wrapped(&self, wrapped_key: WrappedKey, public_key: VerifyingKey) -> Result<Vec<u8>> {
+        let mut shared_secrets = self
+            .diffie_hellman_wrapped_multiple(vec![wrapped_key], public_key)
+            .await?;
+        Ok(shared_secrets
+            .pop()
+            .expect("key agreement with a single key should result in a single shared secret"))
+    }
+
+    async fn diffie_hellman_wrapped_multiple(
+        &self,
+        wrapped_keys: Vec<WrappedKey>,
+        public_key: VerifyingKey,
+    ) -> Result<Vec<Vec<u8>>> {
+        let wrapping_key = self.wrapping_key.clone();
+
+        self.batch("diffie_hellman_wrapped_multiple", wrapped_keys.len(), move |session| {
+            let mut unwrapping_handles = HashMap::new();
+
+            wrapped_keys
+                .into_iter()
+                .map(|wrapped_key| {
+                    let unwrapping_handle =
+                        find_unwrapping_key_handle(session, &wrapping_key, &wrapped_key, &mut unwrapping_handles)?;
+                    let wrapped_key: Vec<u8> = wrapped_key.into();
+                    let private_handle = unwrap_key_agreement_key(session, &unwrapping_handle, &wrapped_key)?;
+                    let _private_guard = SessionObjectGuard::new(session, private_handle.0);
+
+                    let shared_secret = diffie_hellman(session, &private_handle, &public_key)?;
+
+                    Ok(shared_secret)
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn rewrap(&self, wrapped_key: WrappedKey) -> Result<WrappedKey> {
+        let mut rewrapped = self.rewrap_multiple(vec![wrapped_key]).await?;
+        Ok(rewrapped
+            .pop()
+            .expect("rewrapping a single key should result in a single key"))
+    }
+
+    async fn rewrap_multiple(&self, wrapped_keys: Vec<WrappedKey>) -> Result<Vec<WrappedKey>> {
+        let wrapping_key = self.wrapping_key.clone();
+
+        self.batch("rewrap_multiple", wrapped_keys.len(), move |session| {
+            let wrapping_handle = PrivateKeyHandle(find_key_handle(
+                session,
+                &wrapping_key.current_identifier(),
+                HandleType::Private,
+            )?);
+            let mut unwrapping_handles = HashMap::new();
+
+            wrapped_keys
+                .into_iter()
+                .map(|wrapped_key| {
+                    let unwrapping_handle =
+                        find_unwrapping_key_handle(session, &wrapping_key, &wrapped_key, &mut unwrapping_handles)?;
+                    let wrapped_key: Vec<u8> = wrapped_key.into();
+                    let private_handle = unwrap_extractable_signing_key(session, &unwrapping_handle, &wrapped_key)?;
+                    let _private_guard = SessionObjectGuard::new(session, private_handle.0);
+
+                    let rewrapped = wrap_key(
+                        session,
+                        &wrapping_handle,
+                        wrapping_key.current_version(),
+                        &private_handle,
+                    )?;
+
+                    Ok(rewrapped)
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn sign_multiple(
+        &self,
+        wallet_id: &str,
+        identifiers: &[&str],
+        data: Arc<Vec<u8>>,
+    ) -> Result<Vec<(String, Signature)>> {
+        let key_identifiers: Vec<(String, String)> = identifiers
+            .iter()
+            .map(|identifier| (String::from(*identifier), hsm::key_identifier(wallet_id, identifier)))
+            .collect();
+
+        self.batch("sign_multiple", key_identifiers.len(), move |session| {
+            key_identifiers
+                .into_iter()
+                .map(|(identifier, key_identifier)| {
+                    let handle = PrivateKeyHandle(find_key_handle(session, &key_identifier, HandleType::Private)?);
+                    let signature = sign(session, &handle, SigningMechanism::Ecdsa256, &data)?;
+                    Ok((identifier, Signature::from_slice(&signature)?))
+                })
+                .collect()
+        })
+        .await
+    }
+}
+
+impl Hsm for Pkcs11Hsm {
+    type Error = HsmError;
+
+    async fn generate_generic_secret_key(&self, identifier: &str) -> std::result::Result<(), Self::Error> {
+        Pkcs11Client::generate_generic_secret_key(self, identifier)
+            .await
+            .map(|_| ())
+    }
+
+    async fn get_verifying_key(&self, identifier: &str) -> Result<VerifyingKey> {
+        let handle = self.get_public_key_handle(identifier).await?;
+        Pkcs11Client::get_verifying_key(self, handle).await
+    }
+
+    async fn delete_key(&self, identifier: &str) -> Result<()> {
+        let handle = self.get_private_key_handle(identifier).await?;
+        Pkcs11Client::delete_key(self, handle).await?;
+        Ok(())
+    }
+
+    async fn sign_ecdsa(&self, identifier: &str, data: Arc<Vec<u8>>) -> std::result::Result<Signature, Self::Error> {
+        let handle = self.get_private_key_handle(identifier).await?;
+        let signature = Pkcs11Client::sign(self, handle, SigningMechanism::Ecdsa256, data).await?;
+        Ok(Signature::from_slice(&signature)?)
+    }
+
+    async fn sign_hmac(&self, identifier: &str, data: Arc<Vec<u8>>) -> std::result::Result<Vec<u8>, Self::Error> {
+        let handle = self.get_private_key_handle(identifier).await?;
+        Pkcs11Client::sign(self, handle, SigningMechanism::Sha256Hmac, data).await
+    }
+
+    async fn verify_hmac(
+        &self,
+        identifier: &str,
+        data: Arc<Vec<u8>>,
+        signature: Vec<u8>,
+    ) -> std::result::Result<(), Self::Error> {
+        let handle = self.get_private_key_handle(identifier).await?;
+        Pkcs11Client::verify(self, handle, SigningMechanism::Sha256Hmac, data, signature).await
+    }
+
+    async fn encrypt<T>(&self, identifier: &str, data: Vec<u8>) -> Result<Encrypted<T>> {
+        let iv = self.random_bytes(32).await?;
+        let handle = self.get_private_key_handle(identifier).await?;
+        let (encrypted_data, initializiation_vector) =
+            Pkcs11Client::encrypt(self, handle, InitializationVector(iv), data).await?;
+        Ok(Encrypted::new(encrypted_data, initializiation_vector))
+    }
+
+    async fn decrypt<T>(&self, identifier: &str, encrypted: Encrypted<T>) -> Result<Vec<u8>> {
+        let handle = self.get_private_key_handle(identifier).await?;
+        Pkcs11Client::decrypt(self, handle, encrypted.iv, encrypted.data).await
+    }
+}
+
+/// The HSM that is selected in the configuration of the Wallet Provider.
+#[derive(Clone)]
+pub enum ConfiguredHsm {
+    Pkcs11(Pkcs11Hsm),
+    Software(SoftwareHsm),
+}
+
+impl Encrypter<VerifyingKey> for ConfiguredHsm {
+    type Error = HsmError;
+
+    async fn encrypt(&self, key_identifier: &str, data: VerifyingKey) -> Result<Encrypted<VerifyingKey>> {
+        match self {
+            Self::Pkcs11(hsm) => Encrypter::encrypt(hsm, key_identifier, data).await,
+            Self::Software(hsm) => Encrypter::encrypt(hsm, key_identifier, data).await,
+        }
+    }
+}
+
+impl Decrypter<VerifyingKey> for ConfiguredHsm {
+    type Error = HsmError;
+
+    async fn decrypt(&self, key_identifier: &str, encrypted: Encrypted<VerifyingKey>) -> Result<VerifyingKey> {
+        match self {
+            Self::Pkcs11(hsm) => Decrypter::decrypt(hsm, key_identifier, encrypted).await,
+            Self::Software(hsm) => Decrypter::decrypt(hsm, key_identifier, encrypted).await,
+        }
+    }
+}
+
+impl WalletUserHsm for ConfiguredHsm {
+    type Error = HsmError;
+
+    fn wrapping_key_version(&self) -> u32 {
+        match self {
+            Self::Pkcs11(hsm) => hsm.wrapping_key_version(),
+            Self::Software(hsm) => hsm.wrapping_key_version(),
+        }
+    }
+
+    async fn generate_wrapped_key(&self) -> Result<(VerifyingKey, WrappedKey)> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.generate_wrapped_key().await,
+            Self::Software(hsm) => hsm.generate_wrapped_key().await,
+        }
+    }
+
+    async fn generate_wrapped_keys(&self, identifiers: &[&str]) -> Result<Vec<(String, VerifyingKey, WrappedKey)>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.generate_wrapped_keys(identifiers).await,
+            Self::Software(hsm) => hsm.generate_wrapped_keys(identifiers).await,
+        }
+    }
+
+    async fn generate_key(&self, wallet_id: &str, identifier: &str) -> Result<VerifyingKey> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.generate_key(wallet_id, identifier).await,
+            Self::Software(hsm) => hsm.generate_key(wallet_id, identifier).await,
+        }
+    }
+
+    async fn generate_keys(&self, wallet_id: &str, identifiers: &[&str]) -> Result<Vec<(String, VerifyingKey)>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.generate_keys(wallet_id, identifiers).await,
+            Self::Software(hsm) => hsm.generate_keys(wallet_id, identifiers).await,
+        }
+    }
+
+    async fn sign_wrapped(&self, wrapped_key: WrappedKey, data: Arc<Vec<u8>>) -> Result<Signature> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.sign_wrapped(wrapped_key, data).await,
+            Self::Software(hsm) => hsm.sign_wrapped(wrapped_key, data).await,
+        }
+    }
+
+    async fn sign_wrapped_multiple(&self, wrapped_keys: Vec<(WrappedKey, Arc<Vec<u8>>)>) -> Result<Vec<Signature>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.sign_wrapped_multiple(wrapped_keys).await,
+            Self::Software(hsm) => hsm.sign_wrapped_multiple(wrapped_keys).await,
+        }
+    }
+
+    async fn sign(&self, wallet_id: &str, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature> {
+        match self {
+            Self::Pkcs11(hsm) => WalletUserHsm::sign(hsm, wallet_id, identifier, data).await,
+            Self::Software(hsm) => WalletUserHsm::sign(hsm, wallet_id, identifier, data).await,
+        }
+    }
+
+    async fn diffie_hellman_wrapped(&self, wrapped_key: WrappedKey, public_key: VerifyingKey) -> Result<Vec<u8>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.diffie_hellman_wrapped(wrapped_key, public_key).await,
+            Self::Software(hsm) => hsm.diffie_hellman_wrapped(wrapped_key, public_key).await,
+        }
+    }
+
+    async fn diffie_hellman_wrapped_multiple(
+        &self,
+        wrapped_keys: Vec<WrappedKey>,
+        public_key: VerifyingKey,
+    ) -> Result<Vec<Vec<u8>>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.diffie_hellman_wrapped_multiple(wrapped_keys, public_key).await,
+            Self::Software(hsm) => hsm.diffie_hellman_wrapped_multiple(wrapped_keys, public_key).await,
+        }
+    }
+
+    async fn rewrap(&self, wrapped_key: WrappedKey) -> Result<WrappedKey> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.rewrap(wrapped_key).await,
+            Self::Software(hsm) => hsm.rewrap(wrapped_key).await,
+        }
+    }
+
+    async fn rewrap_multiple(&self, wrapped_keys: Vec<WrappedKey>) -> Result<Vec<WrappedKey>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.rewrap_multiple(wrapped_keys).await,
+            Self::Software(hsm) => hsm.rewrap_multiple(wrapped_keys).await,
+        }
+    }
+
+    async fn sign_multiple(
+        &self,
+        wallet_id: &str,
+        identifiers: &[&str],
+        data: Arc<Vec<u8>>,
+    ) -> Result<Vec<(String, Signature)>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.sign_multiple(wallet_id, identifiers, data).await,
+            Self::Software(hsm) => hsm.sign_multiple(wallet_id, identifiers, data).await,
+        }
+    }
+}
+
+impl Hsm for ConfiguredHsm {
+    type Error = HsmError;
+
+    async fn generate_generic_secret_key(&self, identifier: &str) -> Result<()> {
+        match self {
+            Self::Pkcs11(hsm) => Hsm::generate_generic_secret_key(hsm, identifier).await,
+            Self::Software(hsm) => Hsm::generate_generic_secret_key(hsm, identifier).await,
+        }
+    }
+
+    async fn get_verifying_key(&self, identifier: &str) -> Result<VerifyingKey> {
+        match self {
+            Self::Pkcs11(hsm) => Hsm::get_verifying_key(hsm, identifier).await,
+            Self::Software(hsm) => Hsm::get_verifying_key(hsm, identifier).await,
+        }
+    }
+
+    async fn delete_key(&self, identifier: &str) -> Result<()> {
+        match self {
+            Self::Pkcs11(hsm) => Hsm::delete_key(hsm, identifier).await,
+            Self::Software(hsm) => Hsm::delete_key(hsm, identifier).await,
+        }
+    }
+
+    async fn sign_ecdsa(&self, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.sign_ecdsa(identifier, data).await,
+            Self::Software(hsm) => hsm.sign_ecdsa(identifier, data).await,
+        }
+    }
+
+    async fn sign_hmac(&self, identifier: &str, data: Arc<Vec<u8>>) -> Result<Vec<u8>> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.sign_hmac(identifier, data).await,
+            Self::Software(hsm) => hsm.sign_hmac(identifier, data).await,
+        }
+    }
+
+    async fn verify_hmac(&self, identifier: &str, data: Arc<Vec<u8>>, signature: Vec<u8>) -> Result<()> {
+        match self {
+            Self::Pkcs11(hsm) => hsm.verify_hmac(identifier, data, signature).await,
+            Self::Software(hsm) => hsm.verify_hmac(identifier, data, signature).await,
+        }
+    }
+
+    async fn encrypt<T>(&self, identifier: &str, data: Vec<u8>) -> Result<Encrypted<T>> {
+        match self {
+            Self::Pkcs11(hsm) => Hsm::encrypt(hsm, identifier, data).await,
+            Self::Software(hsm) => Hsm::encrypt(hsm, identifier, data).await,
+        }
+    }
+
+    async fn decrypt<T>(&self, identifier: &str, encrypted: Encrypted<T>) -> Result<Vec<u8>> {
+        match self {
+            Self::Pkcs11(hsm) => Hsm::decrypt(hsm, identifier, encrypted).await,
+            Self::Software(hsm) => Hsm::decrypt(hsm, identifier, encrypted).await,
+        }
+    }
+}
+
+impl Pkcs11Client for Pkcs11Hsm {
+    async fn generate_generic_secret_key(&self, identifier: &str) -> Result<PrivateKeyHandle> {
+        let pool = self.pool.clone();
+        let identifier = String::from(identifier);
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+
+            let priv_key_template = &[
+                Attribute::Token(true),
+                Attribute::Private(true),
+                Attribute::Sensitive(true),
+                Attribute::Sign(true),
+                Attribute::Class(ObjectClass::SECRET_KEY),
+                Attribute::KeyType(KeyType::GENERIC_SECRET),
+                Attribute::ValueLen(32.into()),
+                Attribute::Label(identifier.clone().into()),
+            ];
+
+            let private_handle = session.generate_key(&Mechanism::GenericSecretKeyGen, priv_key_template)?;
+
+            Ok(PrivateKeyHandle(private_handle))
+        })
+        .await
+    }
+
+    async fn generate_session_signing_key_pair(&self) -> Result<(PublicKeyHandle, PrivateKeyHandle)> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            generate_session_signing_key_pair(&session)
+        })
+        .await
+    }
+
+    async fn generate_signing_key_pair(&self, identifier: &str) -> Result<(PublicKeyHandle, PrivateKeyHandle)> {
+        let pool = self.pool.clone();
+        let identifier = String::from(identifier);
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            generate_signing_key_pair(&session, &identifier)
+        })
+        .await
+    }
+
+    async fn get_private_key_handle(&self, identifier: &str) -> Result<PrivateKeyHandle> {
+        self.get_key_handle(identifier, HandleType::Private)
+            .await
+            .map(PrivateKeyHandle)
+    }
+
+    async fn get_public_key_handle(&self, identifier: &str) -> Result<PublicKeyHandle> {
+        self.get_key_handle(identifier, HandleType::Public)
+            .await
+            .map(PublicKeyHandle)
+    }
+
+    async fn get_verifying_key(&self, public_key_handle: PublicKeyHandle) -> Result<VerifyingKey> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            get_verifying_key(&session, &public_key_handle)
+        })
+        .await
+    }
+
+    async fn wrap_key(
+        &self,
+        wrapping_key: PrivateKeyHandle,
+        wrapping_key_version: u32,
+        key: PrivateKeyHandle,
+    ) -> Result<WrappedKey> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            wrap_key(&session, &wrapping_key, wrapping_key_version, &key)
+        })
+        .await
+    }
+
+    async fn unwrap_signing_key(
+        &self,
+        unwrapping_key: PrivateKeyHandle,
+        wrapped_key: WrappedKey,
+    ) -> Result<PrivateKeyHandle> {
+        let pool = self.pool.clone();
+        let wrapped_key: Vec<u8> = wrapped_key.into();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            unwrap_signing_key(&session, &unwrapping_key, &wrapped_key)
+        })
+        .await
+    }
+
+    async fn delete_key(&self, private_key_handle: PrivateKeyHandle) -> Result<()> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            session.destroy_object(private_key_handle.0)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn sign(
+        &self,
+        private_key_handle: PrivateKeyHandle,
+        mechanism: SigningMechanism,
+        data: Arc<Vec<u8>>,
+    ) -> Result<Vec<u8>> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            sign(&session, &private_key_handle, mechanism, &data)
+        })
+        .await
+    }
+
+    async fn verify(
+        &self,
+        private_key_handle: PrivateKeyHandle,
+        mechanism: SigningMechanism,
+        data: Arc<Vec<u8>>,
+        signature: Vec<u8>,
+    ) -> Result<()> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let mechanism = match mechanism {
+                SigningMechanism::Ecdsa256 => Mechanism::Ecdsa,
+                SigningMechanism::Sha256Hmac => Mechanism::Sha256Hmac,
+            };
+
+            let session = pool.get()?;
+            session.verify(&mechanism, private_key_handle.0, &sha256(&data), &signature)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn random_bytes(&self, length: u32) -> Result<Vec<u8>> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            let data = session.generate_random_vec(length)?;
+            Ok(data)
+        })
+        .await
+    }
+
+    async fn encrypt(
+        &self,
+        key_handle: PrivateKeyHandle,
+        iv: InitializationVector,
+        data: Vec<u8>,
+    ) -> Result<(Vec<u8>, InitializationVector)> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            let gcm_params = GcmParams::new(&iv.0, &[], AES_AUTHENTICATION_TAG_BITS.into());
+            let encrypted_data = session.encrypt(&Mechanism::AesGcm(gcm_params), key_handle.0, &data)?;
+            Ok((encrypted_data, iv))
+        })
+        .await
+    }
+
+    async fn decrypt(
+        &self,
+        key_handle: PrivateKeyHandle,
+        iv: InitializationVector,
+        encrypted_data: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let pool = self.pool.clone();
+
+        spawn::blocking(move || {
+            let session = pool.get()?;
+            let gcm_params = GcmParams::new(&iv.0, &[], AES_AUTHENTICATION_TAG_BITS.into());
+            let data = session.decrypt(&Mechanism::AesGcm(gcm_params), key_handle.0, &encrypted_data)?;
+            Ok(data)
+        })
+        .await
+    }
+}
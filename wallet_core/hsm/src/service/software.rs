@@ -0,0 +1,547 @@
+use std::{
+    collections::HashMap,
+    fs,
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
+use hmac::{Hmac, Mac};
+use p256::{
+    ecdh,
+    ecdsa::{signature::Signer, Signature, SigningKey, VerifyingKey},
+    elliptic_curve::rand_core::OsRng,
+};
+use ring::pbkdf2;
+use serde::{Deserialize, Serialize};
+use serde_with::{base64::Base64, serde_as};
+use sha2::Sha256;
+
+use crate::model::{
+    encrypted::{Encrypted, InitializationVector},
+    encrypter::{Decrypter, Encrypter},
+    hsm,
+    hsm::{Hsm, WalletUserHsm},
+    versioned_key::VersionedKey,
+    wrapped_key::WrappedKey,
+};
+use wallet_common::utils::random_bytes;
+
+use super::{HsmError, Result};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const NONCE_LENGTH: usize = 12;
+const SALT_LENGTH: usize = 32;
+const SECRET_KEY_LENGTH: usize = 32;
+const PBKDF2_ITERATIONS: NonZeroU32 = match NonZeroU32::new(600_000) {
+    Some(iterations) => iterations,
+    None => unreachable!(),
+};
+
+/// The keys of the software HSM, which are kept in memory and persisted to the key file on every change.
+#[serde_as]
+#[derive(Default, Serialize, Deserialize)]
+struct KeyStore {
+    #[serde_as(as = "HashMap<_, Base64>")]
+    signing_keys: HashMap<String, Vec<u8>>,
+    #[serde_as(as = "HashMap<_, Base64>")]
+    secret_keys: HashMap<String, Vec<u8>>,
+}
+
+/// The parameters of the derivation of the key file encryption key from the passphrase, which are stored in the header
+/// of the key file so that they can be strengthened later on without breaking existing key files.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "algorithm")]
+enum KeyDerivation {
+    #[serde(rename = "PBKDF2-HMAC-SHA256")]
+    Pbkdf2HmacSha256 {
+        iterations: NonZeroU32,
+        #[serde_as(as = "Base64")]
+        salt: Vec<u8>,
+    },
+}
+
+impl KeyDerivation {
+    fn new() -> Self {
+        KeyDerivation::Pbkdf2HmacSha256 {
+            iterations: PBKDF2_ITERATIONS,
+            salt: random_bytes(SALT_LENGTH),
+        }
+    }
+
+    fn cipher(&self, passphrase: &str) -> Aes256Gcm {
+        let mut key = [0u8; SECRET_KEY_LENGTH];
+
+        match self {
+            KeyDerivation::Pbkdf2HmacSha256 { iterations, salt } => pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                *iterations,
+                salt,
+                passphrase.as_bytes(),
+                &mut key,
+            ),
+        }
+
+        Aes256Gcm::new_from_slice(&key).expect("derived key should have the AES-256 key length")
+    }
+}
+
+/// The key file contains the [`KeyStore`], encrypted with AES-256-GCM using a key that is derived from the passphrase
+/// as described by its [`KeyDerivation`] header.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+struct KeyFile {
+    key_derivation: KeyDerivation,
+    #[serde_as(as = "Base64")]
+    nonce: Vec<u8>,
+    #[serde_as(as = "Base64")]
+    ciphertext: Vec<u8>,
+}
+
+struct SoftwareHsmInner {
+    key_file_path: PathBuf,
+    key_file_cipher: Aes256Gcm,
+    key_derivation: KeyDerivation,
+    wrapping_key: VersionedKey,
+    keys: Mutex<KeyStore>,
+}
+
+/// An implementation of the HSM traits that keeps its keys in an encrypted local key file, for development and
+/// testing without a PKCS#11 library. The key file is protected by a passphrase, but the keys are held in memory in
+/// plain text, so this is not suitable for production use.
+#[derive(Clone)]
+pub struct SoftwareHsm(Arc<SoftwareHsmInner>);
+
+impl SoftwareHsm {
+    /// Open the key file at `key_file_path`, which is created when it does not exist yet. As a release build is
+    /// regarded as production use, this is refused for a release build unless `allow_production` is set.
+    pub fn open(
+        key_file_path: PathBuf,
+        passphrase: &str,
+        wrapping_key: VersionedKey,
+        allow_production: bool,
+    ) -> Result<Self> {
+        if !cfg!(debug_assertions) && !allow_production {
+            return Err(HsmError::SoftwareHsmNotAllowed);
+        }
+
+        let (key_derivation, keys, key_file_cipher) = if key_file_path.exists() {
+            let key_file: KeyFile = serde_json::from_slice(&fs::read(&key_file_path)?)?;
+            if key_file.nonce.len() != NONCE_LENGTH {
+                return Err(HsmError::AesGcm(aes_gcm::Error));
+            }
+
+            let cipher = key_file.key_derivation.cipher(passphrase);
+            let plaintext = cipher.decrypt(Nonce::from_slice(&key_file.nonce), key_file.ciphertext.as_slice())?;
+
+            (key_file.key_derivation, serde_json::from_slice(&plaintext)?, cipher)
+        } else {
+            let key_derivation = KeyDerivation::new();
+            let cipher = key_derivation.cipher(passphrase);
+
+            (key_derivation, KeyStore::default(), cipher)
+        };
+
+        let hsm = Self(Arc::new(SoftwareHsmInner {
+            key_file_path,
+            key_file_cipher,
+            key_derivation,
+            wrapping_key,
+            keys: Mutex::new(keys),
+        }));
+
+        // Make sure the key file exists and that the current version of the wrapping key is present.
+        hsm.ensure_secret_key(&hsm.0.wrapping_key.current_identifier())?;
+
+        Ok(hsm)
+    }
+
+    /// Generate a signing key with the given identifier, unless it already exists.
+    pub fn ensure_signing_key(&self, identifier: &str) -> Result<()> {
+        self.update_keys(|keys| {
+            keys.signing_keys
+                .entry(String::from(identifier))
+                .or_insert_with(|| SigningKey::random(&mut OsRng).to_bytes().to_vec());
+        })
+    }
+
+    /// Generate a secret key with the given identifier, unless it already exists.
+    pub fn ensure_secret_key(&self, identifier: &str) -> Result<()> {
+        self.update_keys(|keys| {
+            keys.secret_keys
+                .entry(String::from(identifier))
+                .or_insert_with(|| random_bytes(SECRET_KEY_LENGTH));
+        })
+    }
+
+    fn update_keys<R>(&self, update: impl FnOnce(&mut KeyStore) -> R) -> Result<R> {
+        let mut keys = self.0.keys.lock().unwrap();
+        let result = update(&mut keys);
+
+        let nonce = random_bytes(NONCE_LENGTH);
+        let ciphertext = self
+            .0
+            .key_file_cipher
+            .encrypt(Nonce::from_slice(&nonce), serde_json::to_vec(&*keys)?.as_slice())?;
+        let key_file = KeyFile {
+            key_derivation: self.0.key_derivation.clone(),
+            nonce,
+            ciphertext,
+        };
+
+        // Write to a temporary file first, so that the key file is never left behind partially written.
+        let temporary_path = self.0.key_file_path.with_extension("tmp");
+        fs::write(&temporary_path, serde_json::to_vec(&key_file)?)?;
+        fs::rename(temporary_path, &self.0.key_file_path)?;
+
+        Ok(result)
+    }
+
+    fn signing_key(&self, identifier: &str) -> Result<SigningKey> {
+        let keys = self.0.keys.lock().unwrap();
+        let bytes = keys
+            .signing_keys
+            .get(identifier)
+            .ok_or_else(|| HsmError::KeyNotFound(String::from(identifier)))?;
+
+        Ok(SigningKey::from_slice(bytes)?)
+    }
+
+    fn secret_key(&self, identifier: &str) -> Result<Vec<u8>> {
+        let keys = self.0.keys.lock().unwrap();
+        keys.secret_keys
+            .get(identifier)
+            .cloned()
+            .ok_or_else(|| HsmError::KeyNotFound(String::from(identifier)))
+    }
+
+    fn cipher(&self, identifier: &str) -> Result<Aes256Gcm> {
+        let key = self.secret_key(identifier)?;
+        Ok(Aes256Gcm::new_from_slice(&key).expect("secret keys should have the AES-256 key length"))
+    }
+
+    fn wrap(&self, key: &SigningKey) -> Result<WrappedKey> {
+        let nonce = random_bytes(NONCE_LENGTH);
+        let ciphertext = self
+            .cipher(&self.0.wrapping_key.current_identifier())?
+            .encrypt(Nonce::from_slice(&nonce), key.to_bytes().as_slice())?;
+
+        Ok(WrappedKey::new(
+            [nonce, ciphertext].concat(),
+            self.0.wrapping_key.current_version(),
+        ))
+    }
+
+    fn unwrap(&self, wrapped_key: WrappedKey) -> Result<SigningKey> {
+        let version = wrapped_key.wrapping_key_version();
+        let unwrapping_key_identifier = self
+            .0
+            .wrapping_key
+            .identifier(version)
+            .ok_or(HsmError::UnavailableKeyVersion(version))?;
+
+        let wrapped_key: Vec<u8> = wrapped_key.into();
+        if wrapped_key.len() < NONCE_LENGTH {
+            return Err(HsmError::AesGcm(aes_gcm::Error));
+        }
+        let (nonce, ciphertext) = wrapped_key.split_at(NONCE_LENGTH);
+
+        let key_bytes = self
+            .cipher(&unwrapping_key_identifier)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)?;
+
+        Ok(SigningKey::from_slice(&key_bytes)?)
+    }
+
+    fn hmac(&self, identifier: &str, data: &[u8]) -> Result<HmacSha256> {
+        let key = self.secret_key(identifier)?;
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&key).expect("HMAC can take a key of any size");
+        mac.update(data);
+        Ok(mac)
+    }
+}
+
+impl Encrypter<VerifyingKey> for SoftwareHsm {
+    type Error = HsmError;
+
+    async fn encrypt(&self, key_identifier: &str, data: VerifyingKey) -> Result<Encrypted<VerifyingKey>> {
+        Hsm::encrypt(self, key_identifier, data.to_sec1_bytes().to_vec()).await
+    }
+}
+
+impl Decrypter<VerifyingKey> for SoftwareHsm {
+    type Error = HsmError;
+
+    async fn decrypt(&self, key_identifier: &str, encrypted: Encrypted<VerifyingKey>) -> Result<VerifyingKey> {
+        let decrypted = Hsm::decrypt(self, key_identifier, encrypted).await?;
+        Ok(VerifyingKey::from_sec1_bytes(&decrypted)?)
+    }
+}
+
+impl WalletUserHsm for SoftwareHsm {
+    type Error = HsmError;
+
+    fn wrapping_key_version(&self) -> u32 {
+        self.0.wrapping_key.current_version()
+    }
+
+    async fn generate_wrapped_key(&self) -> Result<(VerifyingKey, WrappedKey)> {
+        let key = SigningKey::random(&mut OsRng);
+        let wrapped_key = self.wrap(&key)?;
+
+        Ok((*key.verifying_key(), wrapped_key))
+    }
+
+    async fn generate_key(&self, wallet_id: &str, identifier: &str) -> Result<VerifyingKey> {
+        let key = SigningKey::random(&mut OsRng);
+        let verifying_key = *key.verifying_key();
+
+        self.update_keys(|keys| {
+            keys.signing_keys
+                .insert(hsm::key_identifier(wallet_id, identifier), key.to_bytes().to_vec())
+        })?;
+
+        Ok(verifying_key)
+    }
+
+    async fn sign_wrapped(&self, wrapped_key: WrappedKey, data: Arc<Vec<u8>>) -> Result<Signature> {
+        let key = self.unwrap(wrapped_key)?;
+        Ok(Signer::sign(&key, data.as_ref()))
+    }
+
+    async fn sign(&self, wallet_id: &str, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature> {
+        Hsm::sign_ecdsa(self, &hsm::key_identifier(wallet_id, identifier), data).await
+    }
+
+    async fn diffie_hellman_wrapped(&self, wrapped_key: WrappedKey, public_key: VerifyingKey) -> Result<Vec<u8>> {
+        let key = self.unwrap(wrapped_key)?;
+        let shared_secret = ecdh::diffie_hellman(key.as_nonzero_scalar(), public_key.as_affine());
+        Ok(shared_secret.raw_secret_bytes().to_vec())
+    }
+
+    async fn rewrap(&self, wrapped_key: WrappedKey) -> Result<WrappedKey> {
+        let key = self.unwrap(wrapped_key)?;
+        self.wrap(&key)
+    }
+}
+
+impl Hsm for SoftwareHsm {
+    type Error = HsmError;
+
+    async fn generate_generic_secret_key(&self, identifier: &str) -> Result<()> {
+        self.update_keys(|keys| {
+            keys.secret_keys
+                .insert(String::from(identifier), random_bytes(SECRET_KEY_LENGTH))
+        })?;
+
+        Ok(())
+    }
+
+    async fn get_verifying_key(&self, identifier: &str) -> Result<VerifyingKey> {
+        Ok(*self.signing_key(identifier)?.verifying_key())
+    }
+
+    async fn delete_key(&self, identifier: &str) -> Result<()> {
+        let deleted = self.update_keys(|keys| {
+            keys.signing_keys.remove(identifier).is_some() || keys.secret_keys.remove(identifier).is_some()
+        })?;
+
+        if !deleted {
+            return Err(HsmError::KeyNotFound(String::from(identifier)));
+        }
+
+        Ok(())
+    }
+
+    async fn sign_ecdsa(&self, identifier: &str, data: Arc<Vec<u8>>) -> Result<Signature> {
+        Ok(Signer::sign(&self.signing_key(identifier)?, data.as_ref()))
+    }
+
+    async fn sign_hmac(&self, identifier: &str, data: Arc<Vec<u8>>) -> Result<Vec<u8>> {
+        Ok(self.hmac(identifier, &data)?.finalize().into_bytes().to_vec())
+    }
+
+    async fn verify_hmac(&self, identifier: &str, data: Arc<Vec<u8>>, signature: Vec<u8>) -> Result<()> {
+        Ok(self.hmac(identifier, &data)?.verify_slice(&signature)?)
+    }
+
+    async fn encrypt<T>(&self, identifier: &str, data: Vec<u8>) -> Result<Encrypted<T>> {
+        let nonce = random_bytes(NONCE_LENGTH);
+        let encrypted = self
+            .cipher(identifier)?
+            .encrypt(Nonce::from_slice(&nonce), data.as_slice())?;
+
+        Ok(Encrypted::new(encrypted, InitializationVector(nonce)))
+    }
+
+    async fn decrypt<T>(&self, identifier: &str, encrypted: Encrypted<T>) -> Result<Vec<u8>> {
+        if encrypted.iv.0.len() != NONCE_LENGTH {
+            return Err(HsmError::AesGcm(aes_gcm::Error));
+        }
+
+        let decrypted = self
+            .cipher(identifier)?
+            .decrypt(Nonce::from_slice(&encrypted.iv.0), encrypted.data.as_slice())?;
+
+        Ok(decrypted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, fs, sync::Arc};
+
+    use assert_matches::assert_matches;
+    use p256::ecdsa::signature::Verifier;
+    use tempfile::TempDir;
+
+    use crate::model::{
+        encrypted::Encrypted,
+        hsm::{Hsm, WalletUserHsm},
+        versioned_key::VersionedKey,
+    };
+    use wallet_common::utils::random_bytes;
+
+    use crate::service::HsmError;
+
+    use super::SoftwareHsm;
+
+    const WRAPPING_KEY_IDENTIFIER: &str = "attestation_wrapping_key";
+
+    fn open_hsm(dir: &TempDir, passphrase: &str) -> Result<SoftwareHsm, HsmError> {
+        open_hsm_with_wrapping_key(dir, passphrase, WRAPPING_KEY_IDENTIFIER.to_string().into())
+    }
+
+    fn open_hsm_with_wrapping_key(
+        dir: &TempDir,
+        passphrase: &str,
+        wrapping_key: VersionedKey,
+    ) -> Result<SoftwareHsm, HsmError> {
+        SoftwareHsm::open(dir.path().join("keys.json"), passphrase, wrapping_key, false)
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_persisted() {
+        let dir = TempDir::new().unwrap();
+
+        let hsm = open_hsm(&dir, "passphrase").unwrap();
+        hsm.ensure_signing_key("signing_key").unwrap();
+        hsm.ensure_secret_key("secret_key").unwrap();
+
+        let verifying_key = hsm.get_verifying_key("signing_key").await.unwrap();
+        let data = Arc::new(random_bytes(32));
+        let encrypted: Encrypted<Vec<u8>> = Hsm::encrypt(&hsm, "secret_key", data.to_vec()).await.unwrap();
+        let (wrapped_verifying_key, wrapped_key) = hsm.generate_wrapped_key().await.unwrap();
+
+        // Opening the key file again should result in the same keys, which should not be replaced.
+        let hsm = open_hsm(&dir, "passphrase").unwrap();
+        hsm.ensure_signing_key("signing_key").unwrap();
+        hsm.ensure_secret_key("secret_key").unwrap();
+
+        assert_eq!(hsm.get_verifying_key("signing_key").await.unwrap(), verifying_key);
+        assert_eq!(
+            Hsm::decrypt(&hsm, "secret_key", encrypted).await.unwrap(),
+            data.to_vec()
+        );
+
+        let signature = hsm.sign_wrapped(wrapped_key, Arc::clone(&data)).await.unwrap();
+        wrapped_verifying_key.verify(&data, &signature).unwrap();
+    }
+
+    #[test]
+    fn test_key_file_header() {
+        let dir = TempDir::new().unwrap();
+
+        open_hsm(&dir, "passphrase").unwrap();
+
+        let key_file: serde_json::Value =
+            serde_json::from_slice(&fs::read(dir.path().join("keys.json")).unwrap()).unwrap();
+        let key_derivation = &key_file["key_derivation"];
+
+        assert_eq!(key_derivation["algorithm"], "PBKDF2-HMAC-SHA256");
+        assert_eq!(key_derivation["iterations"], 600_000);
+        assert!(key_derivation["salt"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_passphrase() {
+        let dir = TempDir::new().unwrap();
+
+        open_hsm(&dir, "passphrase").unwrap();
+
+        assert_matches!(open_hsm(&dir, "wrong_passphrase").err(), Some(HsmError::AesGcm(_)));
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_hmac() {
+        let dir = TempDir::new().unwrap();
+        let hsm = open_hsm(&dir, "passphrase").unwrap();
+
+        let wallet_id = "wallet_id".to_string();
+        let data = Arc::new(random_bytes(32));
+
+        let verifying_key = hsm.generate_key(&wallet_id, "key").await.unwrap();
+        let signature = WalletUserHsm::sign(&hsm, &wallet_id, "key", Arc::clone(&data))
+            .await
+            .unwrap();
+        verifying_key.verify(&data, &signature).unwrap();
+
+        hsm.generate_generic_secret_key("hmac_key").await.unwrap();
+        let mac = hsm.sign_hmac("hmac_key", Arc::clone(&data)).await.unwrap();
+        hsm.verify_hmac("hmac_key", Arc::clone(&data), mac).await.unwrap();
+        assert_matches!(
+            hsm.verify_hmac("hmac_key", data, random_bytes(32)).await,
+            Err(HsmError::Hmac(_))
+        );
+
+        Hsm::delete_key(&hsm, "wallet_id_key").await.unwrap();
+        assert_matches!(
+            hsm.get_verifying_key("wallet_id_key").await,
+            Err(HsmError::KeyNotFound(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rewrap() {
+        let dir = TempDir::new().unwrap();
+        let data = Arc::new(random_bytes(32));
+
+        let hsm = open_hsm(&dir, "passphrase").unwrap();
+        let (verifying_key, wrapped_key) = hsm.generate_wrapped_key().await.unwrap();
+        assert_eq!(wrapped_key.wrapping_key_version(), 0);
+
+        // After rotating the wrapping key, keys that are wrapped with the previous version can still be used.
+        let hsm = open_hsm_with_wrapping_key(
+            &dir,
+            "passphrase",
+            VersionedKey::new(WRAPPING_KEY_IDENTIFIER.to_string(), 1, HashSet::new()),
+        )
+        .unwrap();
+        assert_eq!(hsm.wrapping_key_version(), 1);
+
+        let signature = hsm.sign_wrapped(wrapped_key.clone(), Arc::clone(&data)).await.unwrap();
+        verifying_key.verify(&data, &signature).unwrap();
+
+        let rewrapped_key = hsm.rewrap(wrapped_key.clone()).await.unwrap();
+        assert_eq!(rewrapped_key.wrapping_key_version(), 1);
+
+        // After retiring the previous version, only the rewrapped key can be used.
+        let hsm = open_hsm_with_wrapping_key(
+            &dir,
+            "passphrase",
+            VersionedKey::new(WRAPPING_KEY_IDENTIFIER.to_string(), 1, HashSet::from([0])),
+        )
+        .unwrap();
+
+        assert_matches!(
+            hsm.sign_wrapped(wrapped_key, Arc::clone(&data)).await,
+            Err(HsmError::UnavailableKeyVersion(0))
+        );
+
+        let signature = hsm.sign_wrapped(rewrapped_key, Arc::clone(&data)).await.unwrap();
+        verifying_key.verify(&data, &signature).unwrap();
+    }
+}
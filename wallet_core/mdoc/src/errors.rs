@@ -1,5 +1,6 @@
 use crate::{
     holder::HolderError,
+    proximity::ProximityError,
     server_keys::KeysError,
     utils::{cose::CoseError, crypto::CryptoError, serialization::CborError, x509::CertificateError},
     verifier::VerificationError,
@@ -23,4 +24,6 @@ pub enum Error {
     KeysError(#[from] KeysError),
     #[error("certificate error: {0}")]
     CertificateError(#[from] CertificateError),
+    #[error("proximity error: {0}")]
+    Proximity(#[from] ProximityError),
 }
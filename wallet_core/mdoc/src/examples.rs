@@ -17,9 +17,10 @@ use webpki::TrustAnchor;
 use wallet_common::generator::Generator;
 
 use crate::{
+    unsigned::Entry,
     utils::serialization::{cbor_deserialize, cbor_serialize},
     verifier::ItemsRequests,
-    DeviceAuthenticationBytes, DeviceRequest, DeviceResponse, ItemsRequest, ReaderAuthenticationBytes,
+    DeviceAuthenticationBytes, DeviceRequest, DeviceResponse, ItemsRequest, NameSpace, ReaderAuthenticationBytes,
 };
 
 pub const EXAMPLE_DOC_TYPE: &str = "org.iso.18013.5.1.mDL";
@@ -139,9 +140,52 @@ impl Examples {
                 ]),
             )]),
             request_info: None,
+            device_name_spaces: None,
         }]
         .into()
     }
+
+    /// The attributes of the mDL that are disclosed in the example [`DeviceResponse`].
+    pub fn mdl_attributes() -> IndexMap<NameSpace, Vec<Entry>> {
+        DeviceResponse::example().documents.unwrap()[0]
+            .issuer_signed
+            .name_spaces
+            .as_ref()
+            .unwrap()
+            .as_ref()
+            .iter()
+            .map(|(name_space, attributes)| (name_space.clone(), attributes.into()))
+            .collect()
+    }
+
+    /// The attributes of the mDL that are disclosed in the example [`DeviceResponse`], complemented with the
+    /// mandatory data elements that are missing from it, so that it can be issued.
+    pub fn full_mdl_attributes() -> IndexMap<NameSpace, Vec<Entry>> {
+        let mut attributes = Self::mdl_attributes();
+        attributes.get_mut(EXAMPLE_NAMESPACE).unwrap().extend(
+            [
+                ("given_name", Value::Text("John".to_string())),
+                (
+                    "birth_date",
+                    Value::Tag(1004, Value::Text("1980-01-01".to_string()).into()),
+                ),
+                ("issuing_country", Value::Text("US".to_string())),
+                (
+                    "issuing_authority",
+                    Value::Text("Utopia Department of Motor Vehicles".to_string()),
+                ),
+                ("un_distinguishing_sign", Value::Text("USA".to_string())),
+                ("age_over_18", Value::Bool(true)),
+            ]
+            .into_iter()
+            .map(|(name, value)| Entry {
+                name: name.to_string(),
+                value,
+            }),
+        );
+
+        attributes
+    }
 }
 
 // Functions for parsing ECDSA private/public key strings from ISO spec appendix D
@@ -195,6 +239,7 @@ pub mod mock {
                 issuer_signed,
                 &IsoCertTimeGenerator,
                 trust_anchors,
+                &[],
             )
             .unwrap()
         }
@@ -219,6 +264,7 @@ mod tests {
                 CertificateUsage::Mdl,
                 &IsoCertTimeGenerator,
                 Examples::iaca_trust_anchors(),
+                &[],
             )
             .unwrap()
             .0
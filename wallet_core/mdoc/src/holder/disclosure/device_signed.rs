@@ -2,19 +2,51 @@ use coset::{iana, CoseMac0Builder, Header, HeaderBuilder};
 
 use indexmap::IndexMap;
 use p256::{PublicKey, SecretKey};
+use ring::hmac;
 
 use crate::{
     errors::Result,
     iso::*,
     utils::{
-        cose::{sign_coses, ClonePayload},
-        crypto::dh_hmac_key,
+        cose::{sign_coses, ClonePayload, CoseError},
+        crypto::{dh_hmac_key, hmac_key},
         keys::{KeyFactory, MdocEcdsaKey},
         serialization::{cbor_serialize, TaggedBytes},
     },
 };
 
+/// The way in which the holder authenticates disclosed mdocs to the verifier, see ISO 18013-5 section 9.1.3.
+#[derive(Debug, Clone, Copy)]
+pub enum DeviceAuthMethod<'a> {
+    /// Sign with the mdoc private key, which allows the verifier to prove to third parties that the holder disclosed.
+    Signature,
+    /// Compute a MAC with a key derived from the mdoc private key and the ephemeral key of the verifier, which only
+    /// convinces the verifier itself.
+    Mac {
+        reader_key: &'a PublicKey,
+        session_transcript: &'a SessionTranscript,
+    },
+}
+
 impl DeviceSigned {
+    pub async fn new<K, KF>(
+        keys_and_challenges: Vec<(K, &[u8])>,
+        device_auth_method: DeviceAuthMethod<'_>,
+        key_factory: &KF,
+    ) -> Result<Vec<DeviceSigned>>
+    where
+        K: MdocEcdsaKey,
+        KF: KeyFactory<Key = K>,
+    {
+        match device_auth_method {
+            DeviceAuthMethod::Signature => Self::new_signatures(keys_and_challenges, key_factory).await,
+            DeviceAuthMethod::Mac {
+                reader_key,
+                session_transcript,
+            } => Self::new_macs(keys_and_challenges, reader_key, session_transcript, key_factory).await,
+        }
+    }
+
     pub async fn new_signatures<K, KF>(
         keys_and_challenges: Vec<(K, &[u8])>,
         key_factory: &KF,
@@ -36,6 +68,37 @@ impl DeviceSigned {
         Ok(signed)
     }
 
+    /// Compute a MAC over each challenge, using the EMacKey derived from the ECDH shared secret of the respective
+    /// key and `reader_pub_key`, which the `key_factory` computes without exposing the private keys.
+    pub async fn new_macs<K, KF>(
+        keys_and_challenges: Vec<(K, &[u8])>,
+        reader_pub_key: &PublicKey,
+        session_transcript: &SessionTranscript,
+        key_factory: &KF,
+    ) -> Result<Vec<DeviceSigned>>
+    where
+        K: MdocEcdsaKey,
+        KF: KeyFactory<Key = K>,
+    {
+        let (keys, challenges): (Vec<_>, Vec<_>) = keys_and_challenges.into_iter().unzip();
+
+        let shared_secrets = key_factory
+            .diffie_hellman_with_existing_keys(keys.iter().collect(), reader_pub_key)
+            .await
+            .map_err(|error| CoseError::KeyAgreement(error.into()))?;
+
+        let session_transcript_bts = cbor_serialize(&TaggedBytes(session_transcript))?;
+
+        shared_secrets
+            .into_iter()
+            .zip(challenges)
+            .map(|(shared_secret, challenge)| {
+                let key = hmac_key(&shared_secret, &session_transcript_bts, "EMacKey", 32)?;
+                Ok(Self::new_mac_with_key(&key, challenge.to_vec()))
+            })
+            .collect()
+    }
+
     pub fn new_mac(
         private_key: &SecretKey,
         reader_pub_key: &PublicKey,
@@ -50,18 +113,21 @@ impl DeviceSigned {
             32,
         )?;
 
+        Ok(Self::new_mac_with_key(&key, cbor_serialize(device_auth)?))
+    }
+
+    fn new_mac_with_key(key: &hmac::Key, device_auth_bts: Vec<u8>) -> DeviceSigned {
         let cose = CoseMac0Builder::new()
-            .payload(cbor_serialize(device_auth)?)
-            .protected(HeaderBuilder::new().algorithm(iana::Algorithm::ES256).build())
-            .create_tag(&[], |data| ring::hmac::sign(&key, data).as_ref().into())
+            .payload(device_auth_bts)
+            .protected(HeaderBuilder::new().algorithm(iana::Algorithm::HMAC_256_256).build())
+            .create_tag(&[], |data| hmac::sign(key, data).as_ref().into())
             .build()
             .clone_without_payload();
 
-        let device_signed = DeviceSigned {
+        DeviceSigned {
             name_spaces: IndexMap::new().into(),
             device_auth: DeviceAuth::DeviceMac(cose.into()),
-        };
-        Ok(device_signed)
+        }
     }
 }
 
@@ -72,6 +138,9 @@ mod tests {
     use crate::{
         examples::{Example, Examples, IsoCertTimeGenerator},
         holder::Mdoc,
+        software_key_factory::SoftwareKeyFactory,
+        utils::{keys::KeyFactory, serialization::cbor_serialize},
+        verifier::ValidityPolicy,
         DeviceAuthenticationBytes, DeviceSigned, Document,
     };
 
@@ -105,7 +174,51 @@ mod tests {
                 Some(&eph_reader_key),
                 &session_transcript,
                 &IsoCertTimeGenerator,
+                &ValidityPolicy::default(),
+                Examples::iaca_trust_anchors(),
+                &[],
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_new_macs_device_signed() {
+        let mdoc = Mdoc::new_example_mock();
+        let eph_reader_key = Examples::ephemeral_reader_key();
+        let session_transcript = DeviceAuthenticationBytes::example().0 .0.session_transcript;
+
+        // The key factory performs the key agreement on behalf of the mdoc private key, which it never exposes.
+        let key_factory = SoftwareKeyFactory::default();
+        let key = key_factory.generate_existing(
+            mdoc.private_key_id(),
+            Examples::static_device_key().verifying_key().to_owned(),
+        );
+        let device_auth_bts = cbor_serialize(&DeviceAuthenticationBytes::example()).unwrap();
+
+        let mut mac_device_signed = DeviceSigned::new_macs(
+            vec![(key, device_auth_bts.as_slice())],
+            &eph_reader_key.public_key(),
+            &session_transcript,
+            &key_factory,
+        )
+        .await
+        .unwrap();
+
+        let document = Document {
+            doc_type: mdoc.doc_type.clone(),
+            issuer_signed: mdoc.issuer_signed.clone(),
+            device_signed: mac_device_signed.remove(0),
+            errors: None,
+        };
+
+        document
+            .verify(
+                Some(&eph_reader_key),
+                &session_transcript,
+                &IsoCertTimeGenerator,
+                &ValidityPolicy::default(),
                 Examples::iaca_trust_anchors(),
+                &[],
             )
             .unwrap();
     }
@@ -9,7 +9,6 @@ use crate::{
     holder::HolderError,
     identifiers::{AttributeIdentifier, AttributeIdentifierHolder},
     mdocs::DocType,
-    utils::serialization::{self, CborSeq, TaggedBytes},
     ItemsRequest,
 };
 
@@ -34,13 +33,16 @@ use super::{proposed_document::ProposedDocument, MdocDataSource};
 ///   tries to match the order of the request as best as possible. However,
 ///   considering the previous point the order is not an exact match when the
 ///   request contains the same `DocType` multiple times.
-/// * It is a known limitation that `DisclosureRequestMatch::MissingAttributes`
-///   only contains the missing attributes for one of the `Mdoc`s for a
-///   particular `DocType`. Which one it chooses is undefined.
+/// * `DisclosureRequestMatch::MissingAttributes` contains one entry for every
+///   `Mdoc` of a `DocType` that cannot be satisfied, listing the attributes missing
+///   from that particular `Mdoc`. If no `Mdoc` is present at all for a `DocType`,
+///   a single entry with all of the attributes requested for it is included.
+///   Mdocs of a `DocType` for which at least one candidate is available are not
+///   reported on.
 #[derive(Debug)]
 pub enum DisclosureRequestMatch<I> {
     Candidates(IndexMap<DocType, Vec<ProposedDocument<I>>>),
-    MissingAttributes(Vec<AttributeIdentifier>), // TODO: Report on missing attributes per `Mdoc` candidate. (PVW-1392)
+    MissingAttributes(Vec<Vec<AttributeIdentifier>>),
 }
 
 impl<I> DisclosureRequestMatch<I> {
@@ -81,6 +83,24 @@ impl<I> DisclosureRequestMatch<I> {
             },
         );
 
+        // Collect the self-asserted attributes that are requested per `doc_type` in the same way. These do not
+        // influence which `Mdoc`s match the request, as the holder provides their values itself.
+        let mut requested_self_asserted_attributes_by_doc_type = items_requests
+            .clone()
+            .into_iter()
+            .flat_map(|items_request| items_request.self_asserted_attribute_identifiers())
+            .fold(
+                HashMap::<_, IndexSet<_>>::new(),
+                |mut requested_attributes, attribute_identifier| {
+                    requested_attributes
+                        .entry(attribute_identifier.doc_type.clone())
+                        .or_default()
+                        .insert(attribute_identifier);
+
+                    requested_attributes
+                },
+            );
+
         // Each `Vec<Mdoc>` that is returned from storage should contain `Mdoc`s
         // that have the same `doc_type`. Below, we iterate over all of these
         // `Vec`s and perform the following steps:
@@ -131,24 +151,26 @@ impl<I> DisclosureRequestMatch<I> {
                     }
                 }
 
-                // Calculate the `DeviceAuthentication` for this `doc_type` and turn it into bytes,
-                // so that it can be used as a challenge when constructing `DeviceSigned` later on.
-                let device_authentication = DeviceAuthenticationKeyed::new(doc_type, session_transcript);
-                let device_signed_challenge =
-                    serialization::cbor_serialize(&TaggedBytes(CborSeq(device_authentication)))?;
+                // Calculate the `DeviceAuthentication` for this `doc_type`, so that it can be used as a challenge
+                // when constructing `DeviceSigned` later on. Any self-asserted attribute values provided by the
+                // holder are added to it before that.
+                let device_authentication = DeviceAuthenticationKeyed::new(doc_type, session_transcript).into_owned();
+                let requested_self_asserted_attributes = requested_self_asserted_attributes_by_doc_type
+                    .remove(doc_type)
+                    .unwrap_or_default();
 
                 // Get all the candidates and missing attributes from the provided `Mdoc`s.
                 let (candidates, missing_attributes) =
                     ProposedDocument::candidates_and_missing_attributes_from_stored_mdocs(
                         doc_type_stored_mdocs,
                         &requested_attributes,
-                        device_signed_challenge,
+                        &requested_self_asserted_attributes,
+                        device_authentication,
                     )?;
 
-                // If we have multiple `Mdoc`s with missing attributes, just record the first one.
-                // TODO: Report on missing attributes for multiple `Mdoc` candidates. (PVW-1392)
-                if let Some(missing_attributes) = missing_attributes.into_iter().next() {
-                    all_missing_attributes.push(missing_attributes);
+                // Only record the missing attributes for every `Mdoc` if none of them satisfy the request.
+                if candidates.is_empty() {
+                    all_missing_attributes.extend(missing_attributes);
                 }
 
                 Ok((doc_type.to_string(), candidates))
@@ -166,7 +188,6 @@ impl<I> DisclosureRequestMatch<I> {
             // the requested attributes for any `doc_type` we did not see at all.
             let missing_attributes = all_missing_attributes
                 .into_iter()
-                .flatten()
                 .chain(
                     // Get the `doc_type`s from the original request so that we
                     // can preserve the original order as much as possible.
@@ -179,7 +200,7 @@ impl<I> DisclosureRequestMatch<I> {
                         // Note that this removes the attributes from that `HashMap`, so that
                         // we can take ownership and avoid cloning the `AttributeIdentifier`s.
                         .flat_map(|doc_type| requested_attributes_by_doc_type.remove(doc_type))
-                        .flatten(),
+                        .map(|attributes| attributes.into_iter().collect()),
                 )
                 .collect();
 
@@ -206,6 +227,7 @@ mod tests {
             TestDocument, TestDocuments,
         },
         unsigned::Entry,
+        utils::serialization::TaggedBytes,
         verifier::SessionType,
         Attributes, IssuerNameSpaces, IssuerSignedItem,
     };
@@ -228,6 +250,17 @@ mod tests {
     )]
     #[case(pid_given_name(), pid_full_name(), missing_attributes(pid_family_name()))]
     #[case(pid_full_name(), addr_street(), missing_attributes(addr_street()))]
+    #[case(pid_given_name() + pid_full_name(), pid_full_name(), candidates(pid_full_name()))]
+    #[case(
+        pid_given_name() + pid_family_name(),
+        pid_full_name(),
+        missing_attributes(pid_family_name() + pid_given_name())
+    )]
+    #[case(
+        pid_given_name() + pid_family_name(),
+        pid_full_name() + addr_street(),
+        missing_attributes(pid_family_name() + pid_given_name() + addr_street())
+    )]
     #[tokio::test]
     async fn test_match_stored_documents(
         #[case] stored_documents: TestDocuments,
@@ -261,14 +294,20 @@ mod tests {
     #[derive(Debug, PartialEq)]
     enum ExpectedDisclosureRequestMatch {
         Candidates(TestDocuments),
-        MissingAttributes(IndexSet<AttributeIdentifier>),
+        MissingAttributes(Vec<IndexSet<AttributeIdentifier>>),
     }
 
     fn candidates(candidates: TestDocuments) -> ExpectedDisclosureRequestMatch {
         ExpectedDisclosureRequestMatch::Candidates(candidates)
     }
     fn missing_attributes(missing_attributes: TestDocuments) -> ExpectedDisclosureRequestMatch {
-        ExpectedDisclosureRequestMatch::MissingAttributes(missing_attributes.attribute_identifiers())
+        // Every `TestDocument` represents the attributes missing from a single `Mdoc` candidate.
+        let missing_attributes = missing_attributes
+            .into_iter()
+            .map(|document| TestDocuments::from(vec![document]).attribute_identifiers())
+            .collect();
+
+        ExpectedDisclosureRequestMatch::MissingAttributes(missing_attributes)
     }
 
     impl<T> From<DisclosureRequestMatch<T>> for ExpectedDisclosureRequestMatch {
@@ -282,9 +321,12 @@ mod tests {
                         .collect();
                     Self::Candidates(candidates.into())
                 }
-                DisclosureRequestMatch::MissingAttributes(missing) => {
-                    Self::MissingAttributes(missing.into_iter().collect())
-                }
+                DisclosureRequestMatch::MissingAttributes(missing) => Self::MissingAttributes(
+                    missing
+                        .into_iter()
+                        .map(|attributes| attributes.into_iter().collect())
+                        .collect(),
+                ),
             }
         }
     }
@@ -1,11 +1,15 @@
+use assert_matches::assert_matches;
+use ciborium::Value;
 use indexmap::IndexMap;
 
 use crate::{
-    errors::Result,
+    errors::{Error, Result},
     examples::{
         Example, Examples, IsoCertTimeGenerator, EXAMPLE_ATTR_NAME, EXAMPLE_ATTR_VALUE, EXAMPLE_DOC_TYPE,
         EXAMPLE_NAMESPACE,
     },
+    holder::HolderError,
+    identifiers::AttributeIdentifier,
     iso::{
         device_retrieval::{DeviceRequest, ItemsRequest, ReaderAuthenticationBytes},
         disclosure::DeviceResponse,
@@ -13,11 +17,15 @@ use crate::{
     },
     software_key_factory::SoftwareKeyFactory,
     test::{self, DebugCollapseBts},
+    unsigned::Entry,
     utils::serialization::{CborSeq, TaggedBytes},
+    verifier::{ItemsRequests, ValidityPolicy},
     SessionTranscript,
 };
 
-use super::{test::MockMdocDataSource, DisclosureRequestMatch};
+use super::{test::MockMdocDataSource, DeviceAuthMethod, DisclosureRequestMatch, ProposedDocumentCandidates};
+
+const SELF_ASSERTED_NAMESPACE: &str = "com.example.self_asserted";
 
 /// This function uses the `MockMdocDataSource` to provide the mdoc from the example
 /// `DeviceResponse` in the standard. This is used to match against a `DeviceRequest`
@@ -41,10 +49,13 @@ async fn create_example_device_response(
         _ => panic!("should have found a valid candidate in DeviceRequest"),
     };
 
-    let device_response =
-        DeviceResponse::from_proposed_documents(vec![proposed_document], &SoftwareKeyFactory::default())
-            .await
-            .unwrap();
+    let device_response = DeviceResponse::from_proposed_documents(
+        vec![proposed_document],
+        DeviceAuthMethod::Signature,
+        &SoftwareKeyFactory::default(),
+    )
+    .await
+    .unwrap();
 
     Ok(device_response)
 }
@@ -71,7 +82,7 @@ async fn do_and_verify_iso_example_disclosure() {
         .doc_requests
         .first()
         .unwrap()
-        .verify(&session_transcript, &IsoCertTimeGenerator, reader_trust_anchors)
+        .verify(&session_transcript, &IsoCertTimeGenerator, reader_trust_anchors, &[])
         .unwrap();
     let reader_x509_subject = certificate.unwrap().subject();
 
@@ -94,7 +105,9 @@ async fn do_and_verify_iso_example_disclosure() {
             None,
             &session_transcript,
             &IsoCertTimeGenerator,
+            &ValidityPolicy::default(),
             Examples::iaca_trust_anchors(),
+            &[],
         )
         .unwrap();
     println!("DisclosedAttributes: {:#?}", DebugCollapseBts::from(&disclosed_attrs));
@@ -119,6 +132,7 @@ async fn iso_examples_custom_disclosure() {
             IndexMap::from([(EXAMPLE_ATTR_NAME.to_string(), false)]),
         )]),
         request_info: None,
+        device_name_spaces: None,
     }]);
     println!("My Request: {:#?}", DebugCollapseBts::from(&request));
 
@@ -133,7 +147,9 @@ async fn iso_examples_custom_disclosure() {
             None,
             &session_transcript,
             &IsoCertTimeGenerator,
+            &ValidityPolicy::default(),
             Examples::iaca_trust_anchors(),
+            &[],
         )
         .unwrap();
     println!("My Disclosure: {:#?}", DebugCollapseBts::from(&disclosed_attrs));
@@ -147,3 +163,142 @@ async fn iso_examples_custom_disclosure() {
         &EXAMPLE_ATTR_VALUE,
     );
 }
+
+/// Disclose an attribute of the example mdoc from the spec, along with a self-asserted attribute.
+#[tokio::test]
+async fn iso_examples_self_asserted_disclosure() {
+    let items_request = ItemsRequest {
+        doc_type: EXAMPLE_DOC_TYPE.to_string(),
+        name_spaces: IndexMap::from([(
+            EXAMPLE_NAMESPACE.to_string(),
+            IndexMap::from([(EXAMPLE_ATTR_NAME.to_string(), false)]),
+        )]),
+        request_info: None,
+        device_name_spaces: Some(IndexMap::from([(
+            SELF_ASSERTED_NAMESPACE.to_string(),
+            IndexMap::from([("email".to_string(), false)]),
+        )])),
+    };
+    let session_transcript = DeviceAuthenticationBytes::example().0 .0.session_transcript;
+
+    let request_match =
+        DisclosureRequestMatch::new([&items_request], &MockMdocDataSource::default(), &session_transcript)
+            .await
+            .unwrap();
+    let mut candidates = match request_match {
+        DisclosureRequestMatch::Candidates(candidates) => ProposedDocumentCandidates::new(candidates),
+        _ => panic!("should have found a valid candidate in DeviceRequest"),
+    };
+
+    assert_eq!(
+        candidates.requested_self_asserted_attributes(),
+        IndexMap::from([(
+            EXAMPLE_DOC_TYPE.to_string(),
+            vec![AttributeIdentifier {
+                doc_type: EXAMPLE_DOC_TYPE.to_string(),
+                namespace: SELF_ASSERTED_NAMESPACE.to_string(),
+                attribute: "email".to_string(),
+            }]
+        )])
+    );
+
+    // Disclosing without providing the value of the self-asserted attribute should fail.
+    let error = DeviceResponse::from_proposed_documents(
+        candidates.selected().cloned().collect(),
+        DeviceAuthMethod::Signature,
+        &SoftwareKeyFactory::default(),
+    )
+    .await
+    .expect_err("disclosing without self-asserted attributes should fail");
+    assert_matches!(
+        error,
+        Error::Holder(HolderError::SelfAssertedAttributesMismatch { missing, unexpected })
+            if missing.len() == 1 && unexpected.is_empty()
+    );
+
+    // Providing an attribute that was not requested should fail as well.
+    let email = Entry {
+        name: "email".to_string(),
+        value: Value::Text("jane@example.com".to_string()),
+    };
+    let phone = Entry {
+        name: "phone".to_string(),
+        value: Value::Text("0612345678".to_string()),
+    };
+    let error = candidates
+        .set_self_asserted_attributes(IndexMap::from([(
+            EXAMPLE_DOC_TYPE.to_string(),
+            IndexMap::from([(SELF_ASSERTED_NAMESPACE.to_string(), vec![email.clone(), phone])]),
+        )]))
+        .expect_err("providing unrequested self-asserted attributes should fail");
+    assert_matches!(
+        error,
+        Error::Holder(HolderError::SelfAssertedAttributesMismatch { missing, unexpected })
+            if missing.is_empty() && unexpected.len() == 1
+    );
+
+    candidates
+        .set_self_asserted_attributes(IndexMap::from([(
+            EXAMPLE_DOC_TYPE.to_string(),
+            IndexMap::from([(SELF_ASSERTED_NAMESPACE.to_string(), vec![email.clone()])]),
+        )]))
+        .unwrap();
+
+    let resp = DeviceResponse::from_proposed_documents(
+        candidates.selected().cloned().collect(),
+        DeviceAuthMethod::Signature,
+        &SoftwareKeyFactory::default(),
+    )
+    .await
+    .unwrap();
+
+    let items_requests = ItemsRequests::from(vec![items_request]);
+    items_requests.match_against_response(&resp).unwrap();
+
+    let disclosed_attrs = resp
+        .verify(
+            None,
+            &session_transcript,
+            &IsoCertTimeGenerator,
+            &ValidityPolicy::default(),
+            Examples::iaca_trust_anchors(),
+            &[],
+        )
+        .unwrap();
+
+    // The self-asserted attribute is disclosed separately from the attributes signed by the issuer.
+    test::assert_disclosure_contains(
+        &disclosed_attrs,
+        EXAMPLE_DOC_TYPE,
+        EXAMPLE_NAMESPACE,
+        EXAMPLE_ATTR_NAME,
+        &EXAMPLE_ATTR_VALUE,
+    );
+    let document_attrs = disclosed_attrs.get(EXAMPLE_DOC_TYPE).unwrap();
+    assert!(!document_attrs.attributes.contains_key(SELF_ASSERTED_NAMESPACE));
+    assert_eq!(
+        document_attrs.self_asserted_attributes,
+        IndexMap::from([(SELF_ASSERTED_NAMESPACE.to_string(), vec![email])])
+    );
+
+    // Tampering with the self-asserted attribute should invalidate the device signature.
+    let mut resp = resp;
+    let documents = resp.documents.as_mut().unwrap();
+    documents[0]
+        .device_signed
+        .name_spaces
+        .0
+        .get_mut(SELF_ASSERTED_NAMESPACE)
+        .unwrap()
+        .insert("email".to_string(), Value::Text("john@example.com".to_string()));
+
+    resp.verify(
+        None,
+        &session_transcript,
+        &IsoCertTimeGenerator,
+        &ValidityPolicy::default(),
+        Examples::iaca_trust_anchors(),
+        &[],
+    )
+    .expect_err("verifying tampered self-asserted attributes should fail");
+}
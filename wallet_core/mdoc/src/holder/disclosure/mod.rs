@@ -2,12 +2,16 @@ use std::{collections::HashSet, error::Error};
 
 use super::Mdoc;
 
+pub use device_signed::DeviceAuthMethod;
 pub use disclosure_request_match::DisclosureRequestMatch;
-pub use proposed_document::{ProposedDocument, ProposedDocumentAttributes};
+pub use proposed_document::{ProposedDocument, ProposedDocumentAttributes, ProposedDocumentCandidates};
 pub use session::{
-    DisclosureMissingAttributes, DisclosureProposal, DisclosureSession, DisclosureUriSource, ProposedAttributes,
+    CandidateAttributes, DisclosureMissingAttributes, DisclosureProposal, DisclosureSession, DisclosureUriSource,
+    ProposedAttributes, SelfAssertedAttributes,
 };
 
+pub(crate) use session::{verify_device_request, VerifierSessionDataCheckResult};
+
 mod device_signed;
 mod disclosure_request_match;
 mod engagement;
@@ -20,7 +24,7 @@ mod session;
 #[cfg(test)]
 mod iso_tests;
 #[cfg(test)]
-mod test;
+pub(crate) mod test;
 
 #[derive(Debug, Clone)]
 pub struct StoredMdoc<I> {
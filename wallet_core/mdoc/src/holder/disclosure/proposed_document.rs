@@ -2,20 +2,23 @@ use indexmap::{IndexMap, IndexSet};
 
 use crate::{
     errors::Result,
+    holder::HolderError,
     identifiers::AttributeIdentifier,
     iso::{
-        disclosure::{DeviceSigned, Document, IssuerSigned},
+        disclosure::{DeviceNameSpaces, DeviceSigned, Document, IssuerSigned},
+        engagement::DeviceAuthenticationKeyed,
         mdocs::DocType,
     },
     unsigned::Entry,
     utils::{
         keys::{KeyFactory, MdocEcdsaKey},
+        serialization::{cbor_serialize, CborSeq, TaggedBytes},
         x509::Certificate,
     },
     NameSpace,
 };
 
-use super::StoredMdoc;
+use super::{DeviceAuthMethod, SelfAssertedAttributes, StoredMdoc};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ProposedDocumentAttributes {
@@ -30,10 +33,176 @@ pub struct ProposedDocument<I> {
     pub private_key_id: String,
     pub doc_type: DocType,
     pub issuer_signed: IssuerSigned,
-    pub device_signed_challenge: Vec<u8>,
+    /// The challenge to sign when disclosing, which also contains the self-asserted attribute values, if any.
+    pub device_authentication: DeviceAuthenticationKeyed<'static>,
+    /// The self-asserted attributes requested by the verifier, of which the holder has to provide the values.
+    pub requested_self_asserted_attributes: IndexSet<AttributeIdentifier>,
     pub issuer_certificate: Certificate,
 }
 
+/// This type contains all of the [`ProposedDocument`] candidates for every `DocType` in a
+/// disclosure request, along with the candidate that is selected for disclosure for each
+/// of these `DocType`s. Initially, the first candidate is selected for every `DocType`.
+#[derive(Debug, Clone)]
+pub struct ProposedDocumentCandidates<I> {
+    candidates_by_doc_type: IndexMap<DocType, Vec<ProposedDocument<I>>>,
+    selection: Vec<usize>,
+}
+
+impl<I> ProposedDocumentCandidates<I> {
+    /// Note that every `DocType` is expected to have at least one candidate,
+    /// which is guaranteed by `DisclosureRequestMatch::Candidates`.
+    pub fn new(candidates_by_doc_type: IndexMap<DocType, Vec<ProposedDocument<I>>>) -> Self {
+        let selection = vec![0; candidates_by_doc_type.len()];
+
+        ProposedDocumentCandidates {
+            candidates_by_doc_type,
+            selection,
+        }
+    }
+
+    /// Return the issuer and attributes of every candidate, per `DocType`.
+    pub fn candidate_attributes(&self) -> IndexMap<DocType, Vec<ProposedDocumentAttributes>> {
+        self.candidates_by_doc_type
+            .iter()
+            .map(|(doc_type, candidates)| {
+                let attributes = candidates
+                    .iter()
+                    .map(|candidate| candidate.proposed_attributes())
+                    .collect();
+
+                (doc_type.clone(), attributes)
+            })
+            .collect()
+    }
+
+    /// Return the index of the selected candidate for every `DocType`.
+    pub fn selection(&self) -> &[usize] {
+        &self.selection
+    }
+
+    /// Select a candidate for every `DocType`, by providing their indices in the order returned
+    /// by [`Self::candidate_attributes()`]. The current selection is left intact on error.
+    pub fn select(&mut self, selection: &[usize]) -> Result<()> {
+        let is_valid = selection.len() == self.candidates_by_doc_type.len()
+            && self
+                .candidates_by_doc_type
+                .values()
+                .zip(selection)
+                .all(|(candidates, index)| *index < candidates.len());
+
+        if !is_valid {
+            return Err(HolderError::InvalidCandidateSelection(selection.to_vec()).into());
+        }
+
+        self.selection = selection.to_vec();
+
+        Ok(())
+    }
+
+    /// Return the selected candidate for every `DocType`.
+    pub fn selected(&self) -> impl Iterator<Item = &ProposedDocument<I>> {
+        self.candidates_by_doc_type
+            .values()
+            .zip(&self.selection)
+            .map(|(candidates, index)| &candidates[*index])
+    }
+
+    /// Return the self-asserted attributes requested by the verifier, for every `DocType` for which there are any.
+    pub fn requested_self_asserted_attributes(&self) -> IndexMap<DocType, Vec<AttributeIdentifier>> {
+        self.candidates_by_doc_type
+            .iter()
+            .filter_map(|(doc_type, candidates)| {
+                // Every candidate for a `DocType` was created from the same request.
+                let requested_attributes = &candidates.first()?.requested_self_asserted_attributes;
+
+                (!requested_attributes.is_empty())
+                    .then(|| (doc_type.clone(), requested_attributes.iter().cloned().collect()))
+            })
+            .collect()
+    }
+
+    /// Provide the values of the requested self-asserted attributes, per `DocType`, which will be included in the
+    /// disclosure of every candidate of that `DocType`. Exactly the requested attributes should be provided, which
+    /// means that an empty map is only valid if none were requested. The current values are left intact on error.
+    pub fn set_self_asserted_attributes(&mut self, mut self_asserted_attributes: SelfAssertedAttributes) -> Result<()> {
+        let device_name_spaces = self
+            .candidates_by_doc_type
+            .iter()
+            .map(|(doc_type, candidates)| {
+                let attributes = self_asserted_attributes.swap_remove(doc_type).unwrap_or_default();
+                let device_name_spaces = device_name_spaces_from_entries(attributes);
+
+                // Every candidate for a `DocType` was created from the same request,
+                // so we only need to check the provided attributes once.
+                if let Some(candidate) = candidates.first() {
+                    candidate.check_self_asserted_attributes(&device_name_spaces)?;
+                }
+
+                Ok(device_name_spaces)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Any remaining attributes are for a `DocType` that was not requested at all.
+        if !self_asserted_attributes.is_empty() {
+            let unexpected = self_asserted_attributes
+                .into_iter()
+                .flat_map(|(doc_type, attributes)| {
+                    attribute_identifiers_from_device_name_spaces(
+                        &doc_type,
+                        &device_name_spaces_from_entries(attributes),
+                    )
+                })
+                .collect();
+
+            return Err(HolderError::SelfAssertedAttributesMismatch {
+                missing: Vec::new(),
+                unexpected,
+            }
+            .into());
+        }
+
+        self.candidates_by_doc_type
+            .values_mut()
+            .zip(device_name_spaces)
+            .for_each(|(candidates, device_name_spaces)| {
+                candidates.iter_mut().for_each(|candidate| {
+                    candidate.device_authentication.device_name_spaces_bytes = device_name_spaces.clone().into();
+                })
+            });
+
+        Ok(())
+    }
+}
+
+fn device_name_spaces_from_entries(attributes: IndexMap<NameSpace, Vec<Entry>>) -> DeviceNameSpaces {
+    attributes
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(name_space, entries)| {
+            let items = entries.into_iter().map(|entry| (entry.name, entry.value)).collect();
+
+            (name_space, items)
+        })
+        .collect()
+}
+
+fn attribute_identifiers_from_device_name_spaces(
+    doc_type: &str,
+    device_name_spaces: &DeviceNameSpaces,
+) -> IndexSet<AttributeIdentifier> {
+    device_name_spaces
+        .iter()
+        .flat_map(|(name_space, items)| {
+            items.keys().map(|attribute| AttributeIdentifier {
+                doc_type: doc_type.to_string(),
+                namespace: name_space.clone(),
+                attribute: attribute.clone(),
+            })
+        })
+        .collect()
+}
+
 impl<I> ProposedDocument<I> {
     /// For a given set of `Mdoc`s with the same `doc_type`, return two `Vec`s:
     /// * A `Vec<ProposedDocument>` that contains all of the proposed
@@ -46,7 +215,8 @@ impl<I> ProposedDocument<I> {
     pub(super) fn candidates_and_missing_attributes_from_stored_mdocs(
         stored_mdocs: Vec<StoredMdoc<I>>,
         requested_attributes: &IndexSet<AttributeIdentifier>,
-        device_signed_challenge: Vec<u8>,
+        requested_self_asserted_attributes: &IndexSet<AttributeIdentifier>,
+        device_authentication: DeviceAuthenticationKeyed<'static>,
     ) -> Result<(Vec<Self>, Vec<Vec<AttributeIdentifier>>)> {
         let mut all_missing_attributes = Vec::new();
 
@@ -76,9 +246,14 @@ impl<I> ProposedDocument<I> {
         let document_count = satisfying_documents.len();
         let proposed_documents = satisfying_documents
             .into_iter()
-            .zip(itertools::repeat_n(device_signed_challenge, document_count))
-            .map(|(stored_mdoc, device_signed_challenge)| {
-                ProposedDocument::try_from_stored_mdoc(stored_mdoc, requested_attributes, device_signed_challenge)
+            .zip(itertools::repeat_n(device_authentication, document_count))
+            .map(|(stored_mdoc, device_authentication)| {
+                ProposedDocument::try_from_stored_mdoc(
+                    stored_mdoc,
+                    requested_attributes,
+                    requested_self_asserted_attributes.clone(),
+                    device_authentication,
+                )
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -90,7 +265,8 @@ impl<I> ProposedDocument<I> {
     fn try_from_stored_mdoc(
         stored_mdoc: StoredMdoc<I>,
         requested_attributes: &IndexSet<AttributeIdentifier>,
-        device_signed_challenge: Vec<u8>,
+        requested_self_asserted_attributes: IndexSet<AttributeIdentifier>,
+        device_authentication: DeviceAuthenticationKeyed<'static>,
     ) -> Result<Self> {
         let StoredMdoc {
             id: source_identifier,
@@ -141,12 +317,44 @@ impl<I> ProposedDocument<I> {
             private_key_id: mdoc.private_key_id,
             doc_type: mdoc.doc_type,
             issuer_signed,
-            device_signed_challenge,
+            device_authentication,
+            requested_self_asserted_attributes,
             issuer_certificate,
         };
         Ok(proposed_document)
     }
 
+    /// Return the values of the self-asserted attributes that will be disclosed, which are unverified.
+    pub fn self_asserted_attributes(&self) -> IndexMap<NameSpace, Vec<Entry>> {
+        self.device_authentication
+            .device_name_spaces_bytes
+            .0
+            .iter()
+            .map(|(name_space, items)| (name_space.clone(), items.iter().map(Entry::from).collect()))
+            .collect()
+    }
+
+    /// Check that the self-asserted attributes in `device_name_spaces` are exactly those that were requested.
+    fn check_self_asserted_attributes(&self, device_name_spaces: &DeviceNameSpaces) -> Result<()> {
+        let provided_attributes = attribute_identifiers_from_device_name_spaces(&self.doc_type, device_name_spaces);
+
+        let missing = self
+            .requested_self_asserted_attributes
+            .difference(&provided_attributes)
+            .cloned()
+            .collect::<Vec<_>>();
+        let unexpected = provided_attributes
+            .difference(&self.requested_self_asserted_attributes)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() || !unexpected.is_empty() {
+            return Err(HolderError::SelfAssertedAttributesMismatch { missing, unexpected }.into());
+        }
+
+        Ok(())
+    }
+
     /// Return the issuer and attributes contained within this [`ProposedDocument`].
     pub fn proposed_attributes(&self) -> ProposedDocumentAttributes {
         let issuer = self.issuer_certificate.clone();
@@ -165,26 +373,34 @@ impl<I> ProposedDocument<I> {
         ProposedDocumentAttributes { issuer, attributes }
     }
 
-    /// Convert multiple [`ProposedDocument`] to [`Document`] by signing the challenge using the provided `key_factory`.
+    /// Convert multiple [`ProposedDocument`] to [`Document`] by signing the challenge or computing a MAC over it,
+    /// depending on `device_auth_method`, using the provided `key_factory`.
     pub(super) async fn sign_multiple<KF, K>(
         key_factory: &KF,
         proposed_documents: Vec<ProposedDocument<I>>,
+        device_auth_method: DeviceAuthMethod<'_>,
     ) -> Result<Vec<Document>>
     where
         KF: KeyFactory<Key = K>,
         K: MdocEcdsaKey,
     {
-        let keys_and_challenges = proposed_documents
+        let (keys, challenges): (Vec<K>, Vec<Vec<u8>>) = proposed_documents
             .iter()
             .map(|doc| {
+                // Refuse to disclose without the values of all of the requested self-asserted attributes.
+                doc.check_self_asserted_attributes(&doc.device_authentication.device_name_spaces_bytes.0)?;
+
                 let public_key = doc.issuer_signed.public_key()?;
                 let key: K = key_factory.generate_existing(&doc.private_key_id, public_key);
-                let challenge = doc.device_signed_challenge.as_slice();
+                let challenge = cbor_serialize(&TaggedBytes(CborSeq(&doc.device_authentication)))?;
                 Ok((key, challenge))
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+        let keys_and_challenges = keys.into_iter().zip(challenges.iter().map(Vec::as_slice)).collect();
 
-        let device_signed = DeviceSigned::new_signatures(keys_and_challenges, key_factory).await?;
+        let device_signed = DeviceSigned::new(keys_and_challenges, device_auth_method, key_factory).await?;
 
         let documents = proposed_documents
             .into_iter()
@@ -192,7 +408,10 @@ impl<I> ProposedDocument<I> {
             .map(|(proposed_doc, device_signed)| Document {
                 doc_type: proposed_doc.doc_type,
                 issuer_signed: proposed_doc.issuer_signed,
-                device_signed,
+                device_signed: DeviceSigned {
+                    name_spaces: proposed_doc.device_authentication.device_name_spaces_bytes,
+                    ..device_signed
+                },
                 errors: None,
             })
             .collect();
@@ -212,10 +431,7 @@ mod tests {
         holder::Mdoc,
         iso::disclosure::DeviceAuth,
         software_key_factory::SoftwareKeyFactory,
-        utils::{
-            cose::{self, CoseError},
-            serialization::TaggedBytes,
-        },
+        utils::cose::{self, CoseError},
     };
 
     use super::{super::test::*, *};
@@ -234,13 +450,24 @@ mod tests {
         let requested_attributes =
             example_identifiers_from_attributes(["driving_privileges", "family_name", "document_number"]);
 
-        let proposed_document =
-            ProposedDocument::try_from_stored_mdoc(stored_mdoc, &requested_attributes, b"foobar".to_vec()).unwrap();
+        let requested_self_asserted_attributes = example_identifiers_from_attributes(["email"]);
+
+        let proposed_document = ProposedDocument::try_from_stored_mdoc(
+            stored_mdoc,
+            &requested_attributes,
+            requested_self_asserted_attributes.clone(),
+            create_example_proposed_document().device_authentication,
+        )
+        .unwrap();
 
         assert_eq!(proposed_document.source_identifier, id);
         assert_eq!(proposed_document.doc_type, doc_type);
         assert_eq!(proposed_document.private_key_id, private_key_id);
-        assert_eq!(proposed_document.device_signed_challenge, b"foobar");
+        assert_eq!(
+            proposed_document.requested_self_asserted_attributes,
+            requested_self_asserted_attributes
+        );
+        assert!(proposed_document.self_asserted_attributes().is_empty());
 
         let attributes_identifiers = proposed_document
             .issuer_signed
@@ -299,7 +526,8 @@ mod tests {
             ProposedDocument::candidates_and_missing_attributes_from_stored_mdocs(
                 stored_mdocs,
                 &requested_attributes,
-                b"challenge".to_vec(),
+                &IndexSet::new(),
+                create_example_proposed_document().device_authentication,
             )
             .unwrap();
 
@@ -336,6 +564,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_proposed_document_candidates_select() {
+        let proposed_document1 = create_example_proposed_document();
+        let proposed_document2 = ProposedDocument {
+            source_identifier: "id_5678".to_string(),
+            ..proposed_document1.clone()
+        };
+        let doc_type = proposed_document1.doc_type.clone();
+
+        let mut candidates = ProposedDocumentCandidates::new(IndexMap::from([(
+            doc_type.clone(),
+            vec![proposed_document1, proposed_document2],
+        )]));
+
+        // The first candidate should be selected by default.
+        assert_eq!(candidates.selection(), [0]);
+        assert_eq!(
+            candidates
+                .selected()
+                .map(|document| document.source_identifier.as_str())
+                .collect::<Vec<_>>(),
+            ["id_1234"]
+        );
+
+        let candidate_attributes = candidates.candidate_attributes();
+        assert_eq!(candidate_attributes.len(), 1);
+        assert_eq!(candidate_attributes.get(&doc_type).unwrap().len(), 2);
+
+        candidates
+            .select(&[1])
+            .expect("selecting the second candidate should succeed");
+
+        assert_eq!(candidates.selection(), [1]);
+        assert_eq!(
+            candidates
+                .selected()
+                .map(|document| document.source_identifier.as_str())
+                .collect::<Vec<_>>(),
+            ["id_5678"]
+        );
+
+        // Selecting a non-existent candidate or the wrong number of candidates should fail.
+        for selection in [vec![2], vec![], vec![0, 0]] {
+            let error = candidates
+                .select(&selection)
+                .expect_err("selecting candidates should not succeed");
+
+            assert_matches!(
+                error,
+                Error::Holder(HolderError::InvalidCandidateSelection(indices)) if indices == selection
+            );
+        }
+
+        assert_eq!(candidates.selection(), [1]);
+    }
+
     #[tokio::test]
     async fn test_proposed_document_sign_multiple() {
         // Create a `ProposedDocument` from the example `Mdoc`.
@@ -348,7 +632,7 @@ mod tests {
         // The example proposed document should be signed with the example static key.
         let key = Examples::static_device_key();
         let expected_cose = cose::sign_cose(
-            &proposed_document.device_signed_challenge,
+            &cbor_serialize(&TaggedBytes(CborSeq(&proposed_document.device_authentication))).unwrap(),
             Header::default(),
             &key,
             false,
@@ -356,9 +640,13 @@ mod tests {
         .await
         .unwrap();
 
-        let mut documents = ProposedDocument::sign_multiple(&SoftwareKeyFactory::default(), vec![proposed_document])
-            .await
-            .expect("Could not sign ProposedDocument");
+        let mut documents = ProposedDocument::sign_multiple(
+            &SoftwareKeyFactory::default(),
+            vec![proposed_document],
+            DeviceAuthMethod::Signature,
+        )
+        .await
+        .expect("Could not sign ProposedDocument");
 
         let document = documents.remove(0);
 
@@ -384,7 +672,7 @@ mod tests {
         };
 
         // Conversion to `Document` should simply forward the signing error.
-        let error = ProposedDocument::sign_multiple(&key_factory, vec![proposed_document])
+        let error = ProposedDocument::sign_multiple(&key_factory, vec![proposed_document], DeviceAuthMethod::Signature)
             .await
             .expect_err("Signing ProposedDocument should have resulted in an error");
 
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use indexmap::IndexSet;
-use wallet_common::generator::Generator;
+use wallet_common::{generator::Generator, revocation_list::DerCertificateRevocationList};
 use webpki::TrustAnchor;
 
 use crate::{
@@ -31,12 +31,13 @@ impl DeviceRequest {
     /// Note that since each DocRequest carries its own reader authentication, the spec allows the
     /// the DocRequests to be signed by distinct readers. TODO maybe support this (PVW-2368).
     /// For now, this function requires either none of the DocRequests to be signed, or all of them
-    /// by the same reader.
+    /// by the same reader. The reader certificates are checked for revocation against `crls`.
     pub fn verify(
         &self,
         session_transcript: &SessionTranscript,
         time: &impl Generator<DateTime<Utc>>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Option<(Certificate, ReaderRegistration)>> {
         // If there are no doc requests or none of them have reader authentication, return `None`.
         if self.doc_requests.iter().all(|d| d.reader_auth.is_none()) {
@@ -56,7 +57,9 @@ impl DeviceRequest {
                 |result_cert, doc_request| -> Result<_> {
                     // This `.unwrap()` is safe, because `.verify()` will only return `None`
                     // if `reader_auth` is absent, the presence of which we checked above.
-                    let doc_request_cert = doc_request.verify(session_transcript, time, trust_anchors)?.unwrap();
+                    let doc_request_cert = doc_request
+                        .verify(session_transcript, time, trust_anchors, crls)?
+                        .unwrap();
 
                     // If there is a certificate from a previous iteration, compare our certificate to that.
                     if let Some(result_cert) = result_cert {
@@ -104,6 +107,7 @@ impl DocRequest {
         session_transcript: &SessionTranscript,
         time: &impl Generator<DateTime<Utc>>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Option<Certificate>> {
         // If reader authentication is present, verify it and return the certificate.
         self.reader_auth
@@ -116,7 +120,7 @@ impl DocRequest {
 
                 // Perform verification and return the `Certificate`.
                 let cose = reader_auth.clone_with_payload(serialization::cbor_serialize(&reader_auth_payload)?);
-                cose.verify_against_trust_anchors(CertificateUsage::ReaderAuth, time, trust_anchors)?;
+                cose.verify_against_trust_anchors(CertificateUsage::ReaderAuth, time, trust_anchors, crls)?;
                 let cert = cose.signing_cert()?;
 
                 Ok(cert)
@@ -162,7 +166,7 @@ mod tests {
             .collect::<Vec<_>>();
 
         let verified_reader_registration = device_request
-            .verify(&session_transcript, &TimeGenerator, &trust_anchors)
+            .verify(&session_transcript, &TimeGenerator, &trust_anchors, &[])
             .expect("Could not verify DeviceRequest");
 
         assert_eq!(
@@ -174,7 +178,7 @@ mod tests {
         let device_request = DeviceRequest::from_items_requests(vec![items_request.clone(), items_request.clone()]);
 
         let no_reader_registration = device_request
-            .verify(&session_transcript, &TimeGenerator, &trust_anchors)
+            .verify(&session_transcript, &TimeGenerator, &trust_anchors, &[])
             .expect("Could not verify DeviceRequest");
 
         assert!(no_reader_registration.is_none());
@@ -188,7 +192,7 @@ mod tests {
 
         // Verifying this `DeviceRequest` should result in a `HolderError::ReaderAuthsInconsistent` error.
         let error = device_request
-            .verify(&session_transcript, &TimeGenerator, &trust_anchors)
+            .verify(&session_transcript, &TimeGenerator, &trust_anchors, &[])
             .expect_err("Verifying DeviceRequest should have resulted in an error");
 
         assert_matches!(error, Error::Holder(HolderError::ReaderAuthsInconsistent));
@@ -213,6 +217,7 @@ mod tests {
                 &session_transcript,
                 &TimeGenerator,
                 &[(&der_trust_anchor.owned_trust_anchor).into()],
+                &[],
             )
             .expect("Could not verify DeviceRequest");
 
@@ -225,6 +230,7 @@ mod tests {
                 &session_transcript,
                 &TimeGenerator,
                 &[(&other_der_trust_anchor.owned_trust_anchor).into()],
+                &[],
             )
             .expect_err("Verifying DeviceRequest should have resulted in an error");
 
@@ -241,6 +247,7 @@ mod tests {
                 &session_transcript,
                 &TimeGenerator,
                 &[(&der_trust_anchor.owned_trust_anchor).into()],
+                &[],
             )
             .expect("Could not verify DeviceRequest");
 
@@ -4,19 +4,21 @@ use crate::{
     utils::keys::{KeyFactory, MdocEcdsaKey},
 };
 
-use super::proposed_document::ProposedDocument;
+use super::{device_signed::DeviceAuthMethod, proposed_document::ProposedDocument};
 
 impl DeviceResponse {
     pub async fn from_proposed_documents<I, KF, K>(
         proposed_documents: Vec<ProposedDocument<I>>,
+        device_auth_method: DeviceAuthMethod<'_>,
         key_factory: &KF,
     ) -> Result<Self>
     where
         KF: KeyFactory<Key = K>,
         K: MdocEcdsaKey,
     {
-        // Convert all of the `ProposedDocument` entries to `Document` by signing them.
-        let documents = ProposedDocument::<I>::sign_multiple(key_factory, proposed_documents).await?;
+        // Convert all of the `ProposedDocument` entries to `Document` by signing them or computing their MACs.
+        let documents =
+            ProposedDocument::<I>::sign_multiple(key_factory, proposed_documents, device_auth_method).await?;
 
         // Create a `DeviceResponse` containing the documents.
         let device_response = DeviceResponse {
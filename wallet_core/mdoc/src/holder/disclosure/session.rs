@@ -1,11 +1,12 @@
 use futures::future::TryFutureExt;
 use indexmap::IndexMap;
+use p256::PublicKey;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 use url::Url;
 use webpki::TrustAnchor;
 
-use wallet_common::generator::TimeGenerator;
+use wallet_common::{generator::TimeGenerator, revocation_list::DerCertificateRevocationList};
 
 use crate::{
     device_retrieval::DeviceRequest,
@@ -14,7 +15,8 @@ use crate::{
     errors::{Error, Result},
     holder::{DisclosureError, DisclosureResult, HolderError, HttpClient, HttpClientError, HttpClientResult},
     identifiers::AttributeIdentifier,
-    mdocs::DocType,
+    mdocs::{DocType, NameSpace},
+    unsigned::Entry,
     utils::{
         crypto::SessionKey,
         keys::{KeyFactory, MdocEcdsaKey},
@@ -26,8 +28,8 @@ use crate::{
 };
 
 use super::{
-    proposed_document::{ProposedDocument, ProposedDocumentAttributes},
-    DisclosureRequestMatch, MdocDataSource,
+    proposed_document::{ProposedDocumentAttributes, ProposedDocumentCandidates},
+    DeviceAuthMethod, DisclosureRequestMatch, MdocDataSource,
 };
 
 const REFERRER_URL: &str = "https://referrer.url/";
@@ -38,6 +40,9 @@ pub struct VerifierUrlParameters {
 }
 
 pub type ProposedAttributes = IndexMap<DocType, ProposedDocumentAttributes>;
+pub type CandidateAttributes = IndexMap<DocType, Vec<ProposedDocumentAttributes>>;
+/// The values of self-asserted attributes provided by the holder, which are not signed by the issuer.
+pub type SelfAssertedAttributes = IndexMap<DocType, IndexMap<NameSpace, Vec<Entry>>>;
 
 /// This represents a started disclosure session, which can be in one of two states.
 /// Regardless of which state it is in, it provides the `ReaderRegistration` through
@@ -54,7 +59,9 @@ pub type ProposedAttributes = IndexMap<DocType, ProposedDocumentAttributes>;
 /// based on the requested attributes and we are waiting for user approval to disclose
 /// these attributes to the verifier using the `disclose()` method. Information about
 /// the proposal can be retrieved from the `DisclosureProposal` type using the
-/// `proposed_attributes()` method.
+/// `proposed_attributes()` method. If multiple `Mdoc`s satisfy the request for a
+/// `DocType`, all of these can be inspected using `candidate_attributes()` and one
+/// of them may be chosen using `select_candidates()`.
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum DisclosureSession<H, I> {
@@ -65,7 +72,7 @@ pub enum DisclosureSession<H, I> {
 #[derive(Debug)]
 pub struct DisclosureMissingAttributes<H> {
     data: CommonDisclosureData<H>,
-    missing_attributes: Vec<AttributeIdentifier>,
+    missing_attributes: Vec<Vec<AttributeIdentifier>>,
 }
 
 #[derive(Debug)]
@@ -73,7 +80,10 @@ pub struct DisclosureProposal<H, I> {
     return_url: Option<Url>,
     data: CommonDisclosureData<H>,
     device_key: SessionKey,
-    proposed_documents: Vec<ProposedDocument<I>>,
+    // The ephemeral key of the verifier and the session transcript, with which the disclosed mdocs are authenticated.
+    verifier_public_key: PublicKey,
+    session_transcript: SessionTranscript,
+    candidates: ProposedDocumentCandidates<I>,
 }
 
 #[derive(Debug)]
@@ -92,9 +102,9 @@ pub enum DisclosureUriSource {
     QrCode,
 }
 
-enum VerifierSessionDataCheckResult<I> {
-    MissingAttributes(Vec<AttributeIdentifier>),
-    ProposedDocuments(Vec<ProposedDocument<I>>),
+pub(crate) enum VerifierSessionDataCheckResult<I> {
+    MissingAttributes(Vec<Vec<AttributeIdentifier>>),
+    Candidates(ProposedDocumentCandidates<I>),
 }
 
 impl DisclosureUriSource {
@@ -125,6 +135,7 @@ where
         disclosure_uri_source: DisclosureUriSource,
         mdoc_data_source: &S,
         trust_anchors: &[TrustAnchor<'a>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Self>
     where
         S: MdocDataSource<MdocIdentifier = I>,
@@ -156,6 +167,7 @@ where
 
         // Derive the session transcript and keys in both directions from the
         // `ReaderEngagement`, the `DeviceEngagement` and the ephemeral private key.
+        let verifier_public_key = reader_engagement.verifier_public_key()?;
         let (transcript, reader_key, device_key) = reader_engagement.transcript_and_keys_for_device_engagement(
             session_type,
             &device_engagement,
@@ -186,7 +198,7 @@ where
             async { session_data.decrypt_and_deserialize(&reader_key) }
                 .and_then(|device_request| async move {
                     let (check_result, certificate, reader_registration) =
-                        Self::verify_device_request(&device_request, transcript_ref, mdoc_data_source, trust_anchors)
+                        verify_device_request(&device_request, transcript_ref, mdoc_data_source, trust_anchors, crls)
                             .await?;
 
                     Ok((
@@ -216,14 +228,14 @@ where
                     missing_attributes,
                 })
             }
-            VerifierSessionDataCheckResult::ProposedDocuments(proposed_documents) => {
-                DisclosureSession::Proposal(DisclosureProposal {
-                    return_url,
-                    data,
-                    device_key,
-                    proposed_documents,
-                })
-            }
+            VerifierSessionDataCheckResult::Candidates(candidates) => DisclosureSession::Proposal(DisclosureProposal {
+                return_url,
+                data,
+                device_key,
+                verifier_public_key,
+                session_transcript: transcript,
+                candidates,
+            }),
         };
 
         Ok(session)
@@ -248,63 +260,6 @@ where
         Err(error)
     }
 
-    /// Internal helper function for processing and checking the contents of a
-    /// `SessionData` received from the verifier, which should contain a `DeviceRequest`.
-    async fn verify_device_request<'a, S>(
-        device_request: &DeviceRequest,
-        session_transcript: &SessionTranscript,
-        mdoc_data_source: &S,
-        trust_anchors: &[TrustAnchor<'a>],
-    ) -> Result<(VerifierSessionDataCheckResult<I>, Certificate, ReaderRegistration)>
-    where
-        S: MdocDataSource<MdocIdentifier = I>,
-    {
-        // A device request without any attributes is useless, so return an error.
-        if !device_request.has_attributes() {
-            return Err(HolderError::NoAttributesRequested.into());
-        }
-
-        // Verify reader authentication and decode `ReaderRegistration` from it at the same time.
-        // Reader authentication is required to be present at this time.
-        let (certificate, reader_registration) = device_request
-            .verify(session_transcript, &TimeGenerator, trust_anchors)?
-            .ok_or(HolderError::ReaderAuthMissing)?;
-
-        // Fetch documents from the database, calculate which ones satisfy the request and
-        // formulate proposals for those documents. If there is a mismatch, return an error.
-        let matches =
-            DisclosureRequestMatch::new(device_request.items_requests(), mdoc_data_source, session_transcript).await?;
-        let candidates_by_doc_type = match matches {
-            DisclosureRequestMatch::Candidates(candidates) => candidates,
-            DisclosureRequestMatch::MissingAttributes(missing_attributes) => {
-                // Attributes are missing, return these.
-                let result = VerifierSessionDataCheckResult::MissingAttributes(missing_attributes);
-
-                return Ok((result, certificate, reader_registration));
-            }
-        };
-
-        // If we have multiple candidates for any of the doc types, return an error.
-        // TODO: Support having the user choose between multiple candidates. (PVW-1392)
-        if candidates_by_doc_type.values().any(|candidates| candidates.len() > 1) {
-            let duplicate_doc_types = candidates_by_doc_type
-                .into_iter()
-                .filter(|(_, candidates)| candidates.len() > 1)
-                .map(|(doc_type, _)| doc_type)
-                .collect();
-
-            return Err(HolderError::MultipleCandidates(duplicate_doc_types).into());
-        }
-
-        // Now that we know that we have exactly one candidate for every `doc_type`,
-        // we can flatten these candidates to a 1-dimensional `Vec`.
-        let proposed_documents = candidates_by_doc_type.into_values().flatten().collect::<Vec<_>>();
-
-        let result = VerifierSessionDataCheckResult::ProposedDocuments(proposed_documents);
-
-        Ok((result, certificate, reader_registration))
-    }
-
     fn data(&self) -> &CommonDisclosureData<H> {
         match self {
             DisclosureSession::MissingAttributes(session) => &session.data,
@@ -332,8 +287,53 @@ where
     }
 }
 
+/// Helper function for checking a `DeviceRequest` received from the verifier, which is shared
+/// between online disclosure sessions and proximity sessions.
+pub(crate) async fn verify_device_request<'a, S, I>(
+    device_request: &DeviceRequest,
+    session_transcript: &SessionTranscript,
+    mdoc_data_source: &S,
+    trust_anchors: &[TrustAnchor<'a>],
+    crls: &[DerCertificateRevocationList],
+) -> Result<(VerifierSessionDataCheckResult<I>, Certificate, ReaderRegistration)>
+where
+    S: MdocDataSource<MdocIdentifier = I>,
+{
+    // A device request without any attributes is useless, so return an error.
+    if !device_request.has_attributes() {
+        return Err(HolderError::NoAttributesRequested.into());
+    }
+
+    // Verify reader authentication and decode `ReaderRegistration` from it at the same time.
+    // Reader authentication is required to be present at this time.
+    let (certificate, reader_registration) = device_request
+        .verify(session_transcript, &TimeGenerator, trust_anchors, crls)?
+        .ok_or(HolderError::ReaderAuthMissing)?;
+
+    // Fetch documents from the database, calculate which ones satisfy the request and
+    // formulate proposals for those documents. If there is a mismatch, return an error.
+    let matches =
+        DisclosureRequestMatch::new(device_request.items_requests(), mdoc_data_source, session_transcript).await?;
+    let candidates_by_doc_type = match matches {
+        DisclosureRequestMatch::Candidates(candidates) => candidates,
+        DisclosureRequestMatch::MissingAttributes(missing_attributes) => {
+            // Attributes are missing, return these.
+            let result = VerifierSessionDataCheckResult::MissingAttributes(missing_attributes);
+
+            return Ok((result, certificate, reader_registration));
+        }
+    };
+
+    // Keep all of the candidates, so that the user may choose between them.
+    let candidates = ProposedDocumentCandidates::new(candidates_by_doc_type);
+    let result = VerifierSessionDataCheckResult::Candidates(candidates);
+
+    Ok((result, certificate, reader_registration))
+}
+
 impl<H> DisclosureMissingAttributes<H> {
-    pub fn missing_attributes(&self) -> &[AttributeIdentifier] {
+    /// Returns the missing attributes for every `Mdoc` candidate.
+    pub fn missing_attributes(&self) -> &[Vec<AttributeIdentifier>] {
         &self.missing_attributes
     }
 }
@@ -348,21 +348,41 @@ where
     }
 
     pub fn proposed_source_identifiers(&self) -> Vec<&I> {
-        self.proposed_documents
-            .iter()
+        self.candidates
+            .selected()
             .map(|document| &document.source_identifier)
             .collect()
     }
 
     pub fn proposed_attributes(&self) -> ProposedAttributes {
         // Get all of the attributes to be disclosed from the
-        // prepared `IssuerSigned` on the `ProposedDocument`s.
-        self.proposed_documents
-            .iter()
+        // prepared `IssuerSigned` on the selected `ProposedDocument`s.
+        self.candidates
+            .selected()
             .map(|document| (document.doc_type.clone(), document.proposed_attributes()))
             .collect()
     }
 
+    pub fn candidate_attributes(&self) -> CandidateAttributes {
+        self.candidates.candidate_attributes()
+    }
+
+    pub fn selected_candidates(&self) -> &[usize] {
+        self.candidates.selection()
+    }
+
+    pub fn select_candidates(&mut self, selection: &[usize]) -> Result<()> {
+        self.candidates.select(selection)
+    }
+
+    pub fn requested_self_asserted_attributes(&self) -> IndexMap<DocType, Vec<AttributeIdentifier>> {
+        self.candidates.requested_self_asserted_attributes()
+    }
+
+    pub fn set_self_asserted_attributes(&mut self, self_asserted_attributes: SelfAssertedAttributes) -> Result<()> {
+        self.candidates.set_self_asserted_attributes(self_asserted_attributes)
+    }
+
     pub async fn disclose<KF, K>(&self, key_factory: &KF) -> DisclosureResult<(), Error>
     where
         KF: KeyFactory<Key = K>,
@@ -370,15 +390,20 @@ where
     {
         info!("disclose proposed documents");
 
-        // Clone the proposed documents and construct a `DeviceResponse` by
-        // signing these, then encrypt the response with the device key.
-        let proposed_documents = self.proposed_documents.to_vec();
+        // Clone the selected proposed documents and construct a `DeviceResponse` by authenticating
+        // these with a MAC for the verifier, then encrypt the response with the device key.
+        let proposed_documents = self.candidates.selected().cloned().collect();
 
-        info!("sign proposed documents");
+        info!("compute MACs over proposed documents");
 
-        let device_response = DeviceResponse::from_proposed_documents(proposed_documents, key_factory)
-            .await
-            .map_err(DisclosureError::before_sharing)?;
+        let device_auth_method = DeviceAuthMethod::Mac {
+            reader_key: &self.verifier_public_key,
+            session_transcript: &self.session_transcript,
+        };
+        let device_response =
+            DeviceResponse::from_proposed_documents(proposed_documents, device_auth_method, key_factory)
+                .await
+                .map_err(DisclosureError::before_sharing)?;
 
         info!("serialize and encrypt device response");
 
@@ -443,7 +468,7 @@ mod tests {
         software_key_factory::SoftwareKeyFactory,
         utils::{
             cose::{ClonePayload, CoseError},
-            crypto::SessionKeyUser,
+            crypto::{dh_hmac_key, SessionKeyUser},
             serialization::{CborSeq, TaggedBytes},
         },
         Error,
@@ -510,8 +535,8 @@ mod tests {
 
                 // Extract the public keys from the `MobileSecurityObject`
                 let public_keys: Vec<VerifyingKey> = proposal
-                    .proposed_documents
-                    .iter()
+                    .candidates
+                    .selected()
                     .map(|proposed_document| {
                         let TaggedBytes(mso) = proposed_document
                             .issuer_signed
@@ -577,10 +602,12 @@ mod tests {
             SessionTranscript::new_iso(session_type, &reader_engagement, &device_engagement).unwrap();
 
         // Use the `SessionTranscript` to reconstruct the `DeviceAuthentication`
-        // for every `Document` received in order to verify the signatures received
-        // for each of these.
+        // for every `Document` received in order to verify the MACs received
+        // for each of these, using the ephemeral key of the verifier.
         assert_eq!(documents.len(), public_keys.len());
 
+        let session_transcript_bts = serialization::cbor_serialize(&TaggedBytes(&session_transcript)).unwrap();
+
         documents
             .into_iter()
             .zip(public_keys)
@@ -588,11 +615,19 @@ mod tests {
                 let device_authentication = DeviceAuthenticationKeyed::new(&document.doc_type, &session_transcript);
                 let device_authentication_bytes =
                     serialization::cbor_serialize(&TaggedBytes(CborSeq(device_authentication))).unwrap();
+                let mac_key = dh_hmac_key(
+                    &verifier_session.reader_ephemeral_key,
+                    &public_key.into(),
+                    &session_transcript_bts,
+                    "EMacKey",
+                    32,
+                )
+                .unwrap();
 
                 match document.device_signed.device_auth {
-                    DeviceAuth::DeviceSignature(signature) => signature
+                    DeviceAuth::DeviceMac(mac) => mac
                         .clone_with_payload(device_authentication_bytes)
-                        .verify(&public_key)
+                        .verify(&mac_key)
                         .expect("Device authentication for document does not match public key"),
                     _ => panic!("Unexpected device authentication in DeviceResponse"),
                 }
@@ -701,8 +736,10 @@ mod tests {
 
         let expected_missing_attributes = example_identifiers_from_attributes(["driving_privileges"]);
 
+        // Only the single `Mdoc` candidate should be reported on.
+        assert_eq!(missing_attr_session.missing_attributes().len(), 1);
         itertools::assert_equal(
-            missing_attr_session.missing_attributes().iter(),
+            missing_attr_session.missing_attributes()[0].iter(),
             expected_missing_attributes.iter(),
         );
     }
@@ -753,8 +790,10 @@ mod tests {
             "driving_privileges",
         ]);
 
+        // As no `Mdoc` is present at all, all requested attributes should be reported as a single entry.
+        assert_eq!(missing_attr_session.missing_attributes().len(), 1);
         itertools::assert_equal(
-            missing_attr_session.missing_attributes().iter(),
+            missing_attr_session.missing_attributes()[0].iter(),
             expected_missing_attributes.iter(),
         );
     }
@@ -975,6 +1014,7 @@ mod tests {
             DisclosureUriSource::Link,
             &mdoc_data_source,
             &[],
+            &[],
         )
         .await
         .expect_err("Starting disclosure session should have resulted in an error");
@@ -1247,11 +1287,12 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_disclosure_session_start_error_multiple_candidates() {
+    async fn test_disclosure_session_start_multiple_candidates() {
         // Starting a `DisclosureSession` when the database contains multiple
-        // candidates for the same `doc_type` should result in an error.
-        let mut payloads = Vec::with_capacity(2);
-        let error = disclosure_session_start(
+        // candidates for the same `doc_type` should result in a proposal
+        // containing all of these candidates, with the first one selected.
+        let mut payloads = Vec::with_capacity(1);
+        let (disclosure_session, _, _) = disclosure_session_start(
             SessionType::SameDevice,
             DisclosureUriSource::Link,
             ReaderCertificateKind::WithReaderRegistration,
@@ -1265,15 +1306,34 @@ mod tests {
             identity,
         )
         .await
-        .expect_err("Starting disclosure session should have resulted in an error");
+        .expect("Could not start disclosure session");
 
-        assert_matches!(
-            error,
-            Error::Holder(HolderError::MultipleCandidates(doc_types)) if doc_types == vec![EXAMPLE_DOC_TYPE.to_string()]
-        );
-        assert_eq!(payloads.len(), 2);
+        let mut proposal_session = match disclosure_session {
+            DisclosureSession::MissingAttributes(_) => panic!("Disclosure session should not have missing attributes"),
+            DisclosureSession::Proposal(session) => session,
+        };
 
-        test_payload_session_data_error(payloads.last().unwrap(), SessionStatus::Termination);
+        let candidate_attributes = proposal_session.candidate_attributes();
+        assert_eq!(candidate_attributes.len(), 1);
+        assert_eq!(candidate_attributes.get(EXAMPLE_DOC_TYPE).unwrap().len(), 2);
+        assert_eq!(proposal_session.selected_candidates(), [0]);
+        assert_eq!(proposal_session.proposed_source_identifiers(), ["id_1"]);
+
+        // Selecting the second candidate should change the proposed document.
+        proposal_session
+            .select_candidates(&[1])
+            .expect("Could not select disclosure candidates");
+
+        assert_eq!(proposal_session.selected_candidates(), [1]);
+        assert_eq!(proposal_session.proposed_source_identifiers(), ["id_2"]);
+
+        // Selecting a candidate that does not exist should result in an error.
+        let error = proposal_session
+            .select_candidates(&[2])
+            .expect_err("Selecting disclosure candidates should have resulted in an error");
+
+        assert_matches!(error, Error::Holder(HolderError::InvalidCandidateSelection(_)));
+        assert_eq!(proposal_session.selected_candidates(), [1]);
     }
 
     #[tokio::test]
@@ -1332,7 +1392,12 @@ mod tests {
                 session_type,
             },
             device_key,
-            proposed_documents: vec![create_example_proposed_document()],
+            verifier_public_key: pubkey,
+            session_transcript,
+            candidates: ProposedDocumentCandidates::new(IndexMap::from([(
+                EXAMPLE_DOC_TYPE.to_string(),
+                vec![create_example_proposed_document()],
+            )])),
         });
 
         (proposal_session, payload_receiver)
@@ -21,7 +21,7 @@ use crate::{
             DeviceRequest, DocRequest, ItemsRequest, ReaderAuthenticationBytes, ReaderAuthenticationKeyed,
         },
         disclosure::{SessionData, SessionStatus},
-        engagement::{DeviceEngagement, ReaderEngagement, SessionTranscript},
+        engagement::{DeviceAuthenticationKeyed, DeviceEngagement, ReaderEngagement, SessionTranscript},
     },
     server_keys::KeyPair,
     utils::{
@@ -67,6 +67,7 @@ pub fn items_request(
             attributes.map(|attribute| (attribute.into(), false)).collect(),
         )]),
         request_info: None,
+        device_name_spaces: None,
     }
 }
 
@@ -129,13 +130,16 @@ pub fn create_example_proposed_document() -> ProposedDocument<MdocIdentifier> {
     let mdoc = Mdoc::new_example_mock();
 
     let issuer_certificate = mdoc.issuer_certificate().unwrap();
+    let session_transcript = create_basic_session_transcript(SessionType::SameDevice);
+    let device_authentication = DeviceAuthenticationKeyed::new(&mdoc.doc_type, &session_transcript).into_owned();
 
     ProposedDocument {
         source_identifier: "id_1234".to_string(),
         private_key_id: mdoc.private_key_id,
         doc_type: mdoc.doc_type,
         issuer_signed: mdoc.issuer_signed,
-        device_signed_challenge: b"signing_challenge".to_vec(),
+        device_authentication,
+        requested_self_asserted_attributes: IndexSet::new(),
         issuer_certificate,
     }
 }
@@ -292,7 +296,7 @@ pub struct MockVerifierSession<F> {
     pub trust_anchors: Vec<DerTrustAnchor>,
     private_key: KeyPair,
     pub reader_engagement: ReaderEngagement,
-    reader_ephemeral_key: SecretKey,
+    pub reader_ephemeral_key: SecretKey,
     pub reader_engagement_bytes_override: Option<Vec<u8>>,
     pub items_requests: Vec<ItemsRequest>,
     transform_device_request: F,
@@ -512,6 +516,7 @@ where
         disclosure_uri_source,
         &mdoc_data_source,
         &verifier_session.trust_anchors(),
+        &[],
     )
     .await;
 
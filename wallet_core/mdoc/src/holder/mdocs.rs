@@ -6,7 +6,7 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use webpki::TrustAnchor;
 
-use wallet_common::generator::Generator;
+use wallet_common::{generator::Generator, revocation_list::DerCertificateRevocationList};
 
 use crate::{
     identifiers::AttributeIdentifier,
@@ -57,14 +57,15 @@ pub struct Mdoc {
 }
 
 impl Mdoc {
-    /// Construct a new `Mdoc`, verifying it against the specified thrust anchors before returning it.
+    /// Construct a new `Mdoc`, verifying it against the specified thrust anchors and CRLs before returning it.
     pub fn new<K: MdocEcdsaKey>(
         private_key_id: String,
         issuer_signed: IssuerSigned,
         time: &impl Generator<DateTime<Utc>>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> crate::Result<Mdoc> {
-        let (_, mso) = issuer_signed.verify(ValidityRequirement::AllowNotYetValid, time, trust_anchors)?;
+        let (_, mso) = issuer_signed.verify(ValidityRequirement::AllowNotYetValid, time, trust_anchors, crls)?;
         let mdoc = Mdoc {
             doc_type: mso.doc_type,
             private_key_id,
@@ -89,6 +90,15 @@ impl Mdoc {
             .unwrap_or_default()
     }
 
+    /// Identifier of the mdoc's private key, e.g. within the Wallet Provider.
+    pub fn private_key_id(&self) -> &str {
+        &self.private_key_id
+    }
+
+    pub fn issuer_signed(&self) -> &IssuerSigned {
+        &self.issuer_signed
+    }
+
     pub fn issuer_certificate(&self) -> Result<Certificate, CoseError> {
         self.issuer_signed.issuer_auth.signing_cert()
     }
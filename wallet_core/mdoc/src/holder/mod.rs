@@ -4,6 +4,7 @@ pub use webpki::TrustAnchor;
 
 use crate::{
     errors::Error,
+    identifiers::AttributeIdentifier,
     iso::*,
     utils::{
         reader_auth,
@@ -50,8 +51,13 @@ pub enum HolderError {
     ReaderRegistrationValidation(#[from] reader_auth::ValidationError),
     #[error("could not retrieve docs from source: {0}")]
     MdocDataSource(#[source] Box<dyn std::error::Error + Send + Sync>),
-    #[error("multiple candidates for disclosure is unsupported, found for doc types: {}", .0.join(", "))]
-    MultipleCandidates(Vec<DocType>),
+    #[error("invalid selection of disclosure candidates: {0:?}")]
+    InvalidCandidateSelection(Vec<usize>),
+    #[error("self-asserted attributes do not match request, missing: {missing:?}, unexpected: {unexpected:?}")]
+    SelfAssertedAttributesMismatch {
+        missing: Vec<AttributeIdentifier>,
+        unexpected: Vec<AttributeIdentifier>,
+    },
     #[error("verifier returned error in response to disclosure: {0:?}")]
     DisclosureResponse(SessionStatus),
 }
@@ -1,9 +1,10 @@
 use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     holder::Mdoc,
     iso::{
-        device_retrieval::{DeviceRequest, ItemsRequest},
+        device_retrieval::{DeviceRequest, ItemsRequest, NameSpaces},
         disclosure::IssuerSigned,
         mdocs::{DataElementIdentifier, DocType, NameSpace},
     },
@@ -11,7 +12,7 @@ use crate::{
     Document,
 };
 
-#[derive(PartialEq, Eq, Hash, Clone)]
+#[derive(PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct AttributeIdentifier {
     pub doc_type: DocType,
     pub namespace: NameSpace,
@@ -58,6 +59,21 @@ impl Document {
     pub fn issuer_signed_attribute_identifiers(&self) -> IndexSet<AttributeIdentifier> {
         self.issuer_signed.attribute_identifiers(&self.doc_type)
     }
+
+    pub fn device_signed_attribute_identifiers(&self) -> IndexSet<AttributeIdentifier> {
+        self.device_signed
+            .name_spaces
+            .0
+            .iter()
+            .flat_map(|(namespace, items)| {
+                items.keys().map(|attribute| AttributeIdentifier {
+                    doc_type: self.doc_type.to_owned(),
+                    namespace: namespace.to_owned(),
+                    attribute: attribute.to_owned(),
+                })
+            })
+            .collect()
+    }
 }
 
 pub trait AttributeIdentifierHolder {
@@ -83,19 +99,33 @@ impl AttributeIdentifierHolder for DeviceRequest {
 
 impl AttributeIdentifierHolder for ItemsRequest {
     fn attribute_identifiers(&self) -> IndexSet<AttributeIdentifier> {
-        self.name_spaces
-            .iter()
-            .flat_map(|(namespace, attributes)| {
-                attributes.into_iter().map(|(attribute, _)| AttributeIdentifier {
-                    doc_type: self.doc_type.to_owned(),
-                    namespace: namespace.to_owned(),
-                    attribute: attribute.to_owned(),
-                })
-            })
-            .collect()
+        name_spaces_attribute_identifiers(&self.doc_type, &self.name_spaces)
+    }
+}
+
+impl ItemsRequest {
+    /// Returns the identifiers of the self-asserted attributes that are requested to be device signed.
+    pub fn self_asserted_attribute_identifiers(&self) -> IndexSet<AttributeIdentifier> {
+        self.device_name_spaces
+            .as_ref()
+            .map(|name_spaces| name_spaces_attribute_identifiers(&self.doc_type, name_spaces))
+            .unwrap_or_default()
     }
 }
 
+fn name_spaces_attribute_identifiers(doc_type: &str, name_spaces: &NameSpaces) -> IndexSet<AttributeIdentifier> {
+    name_spaces
+        .iter()
+        .flat_map(|(namespace, attributes)| {
+            attributes.into_iter().map(|(attribute, _)| AttributeIdentifier {
+                doc_type: doc_type.to_owned(),
+                namespace: namespace.to_owned(),
+                attribute: attribute.to_owned(),
+            })
+        })
+        .collect()
+}
+
 #[cfg(any(test, feature = "test"))]
 mod tests {
     use super::*;
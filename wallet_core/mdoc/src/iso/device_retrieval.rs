@@ -82,6 +82,12 @@ pub struct ItemsRequest {
 
     /// Free-form additional information.
     pub request_info: Option<IndexMap<String, Value>>,
+
+    /// The attribute names that the RP wishes the holder to provide itself, as self-asserted attributes in the
+    /// [`DeviceSigned`](super::disclosure::DeviceSigned) part of the disclosed document. As these are not signed by
+    /// the issuer, the RP should treat their values as unverified.
+    /// This is a custom and optional field. Other implementations should ignore it.
+    pub device_name_spaces: Option<NameSpaces>,
 }
 
 /// The attribute names that the RP wishes disclosed, grouped per namespace, as part of a [`ItemsRequest`].
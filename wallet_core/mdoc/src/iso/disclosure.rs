@@ -12,6 +12,7 @@ use serde_with::skip_serializing_none;
 use std::fmt::Debug;
 
 use crate::{
+    iso::engagement::ESenderKeyBytes,
     iso::mdocs::*,
     utils::{
         cose::MdocCose,
@@ -102,6 +103,16 @@ pub type Errors = IndexMap<NameSpace, ErrorItems>;
 pub type ErrorItems = IndexMap<DataElementIdentifier, ErrorCode>;
 pub type ErrorCode = i32;
 
+/// The first message sent by the reader in a proximity session, after having received the
+/// [`DeviceEngagement`](super::DeviceEngagement) of the holder. Contains the ephemeral public key of the reader
+/// and the encrypted [`DeviceRequest`](super::DeviceRequest).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEstablishment {
+    pub e_reader_key: ESenderKeyBytes,
+    pub data: ByteBuf,
+}
+
 /// Contains an encrypted mdoc disclosure protocol message, and a status code containing an error code or a code
 /// that aborts the session.
 #[derive(Serialize, Deserialize, Debug, Clone)]
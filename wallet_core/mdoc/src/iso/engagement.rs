@@ -56,10 +56,18 @@ impl<'a> DeviceAuthenticationKeyed<'a> {
             device_name_spaces_bytes: Default::default(),
         }
     }
+
+    pub fn into_owned(self) -> DeviceAuthenticationKeyed<'static> {
+        DeviceAuthenticationKeyed {
+            device_authentication: self.device_authentication,
+            session_transcript: Cow::Owned(self.session_transcript.into_owned()),
+            doc_type: Cow::Owned(self.doc_type.into_owned()),
+            device_name_spaces_bytes: self.device_name_spaces_bytes,
+        }
+    }
 }
 
-#[cfg_attr(any(test, feature = "examples"), derive(Deserialize))]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionTranscriptKeyed {
     pub device_engagement_bytes: Option<DeviceEngagementBytes>,
     pub ereader_key_bytes: Option<ESenderKeyBytes>,
@@ -100,6 +108,17 @@ impl SessionTranscript {
         Ok(transcript)
     }
 
+    /// Create the [`SessionTranscript`] for a proximity session that was started by the reader scanning
+    /// the QR code containing the [`DeviceEngagement`] of the holder.
+    pub fn new_qr(device_engagement: &DeviceEngagement, ereader_key_bytes: ESenderKeyBytes) -> Self {
+        SessionTranscriptKeyed {
+            device_engagement_bytes: Some(device_engagement.clone().into()),
+            ereader_key_bytes: Some(ereader_key_bytes),
+            handover: Handover::QrHandover,
+        }
+        .into()
+    }
+
     pub fn new_oid4vp(response_uri: &BaseUrl, client_id: &str, nonce: String, mdoc_nonce: &str) -> Self {
         let handover = OID4VPHandover {
             client_id_hash: ByteBuf::from(sha256(&cbor_serialize(&[client_id, mdoc_nonce]).unwrap())),
@@ -138,8 +157,7 @@ pub enum Handover {
     Oid4vpHandover(CborSeq<OID4VPHandover>),
 }
 
-#[cfg_attr(any(test, feature = "examples"), derive(Deserialize))]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OID4VPHandover {
     /// Must be `SHA256(CBOR_encode([client_id, mdoc_nonce]))`
     pub client_id_hash: ByteBuf,
@@ -148,8 +166,7 @@ pub struct OID4VPHandover {
     pub nonce: String,
 }
 
-#[cfg_attr(any(test, feature = "examples"), derive(Deserialize))]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NFCHandover {
     pub handover_select_message: ByteBuf,
     pub handover_request_message: Option<ByteBuf>,
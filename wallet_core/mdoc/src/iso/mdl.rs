@@ -0,0 +1,351 @@
+//! Data model of the mobile driving licence (mDL), as defined in ISO 18013-5 section 7.2.
+//!
+//! The main citizen of this module is [`MdlDataElement`], which describes the type of each data element in the
+//! [`MDL_NAMESPACE`] and whether it is mandatory. The data elements of an mDL can be checked against this schema
+//! using [`validate_mdl_attributes()`].
+
+use chrono::{DateTime, NaiveDate, ParseError};
+use ciborium::{tag, value::Value};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use crate::{unsigned::Entry, DataElementIdentifier, DataElementValue, NameSpace};
+
+pub const MDL_DOCTYPE: &str = "org.iso.18013.5.1.mDL";
+pub const MDL_NAMESPACE: &str = "org.iso.18013.5.1";
+
+/// Prefix of the `age_over_NN` data elements, of which there may be any number.
+const AGE_OVER_PREFIX: &str = "age_over_";
+
+/// A date without time, serialized as a string value as specified in RFC 3339 tagged with CBOR tag 1004,
+/// e.g. `1004("2019-10-20")`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FullDate(pub tag::Required<String, 1004>);
+
+impl From<NaiveDate> for FullDate {
+    fn from(date: NaiveDate) -> Self {
+        FullDate(tag::Required(date.format("%Y-%m-%d").to_string()))
+    }
+}
+
+impl TryFrom<&FullDate> for NaiveDate {
+    type Error = ParseError;
+
+    fn try_from(value: &FullDate) -> Result<NaiveDate, Self::Error> {
+        NaiveDate::parse_from_str(&value.0 .0, "%Y-%m-%d")
+    }
+}
+
+/// A vehicle category that the holder of the mDL is allowed to drive, see ISO 18013-5 section 7.2.4.
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrivingPrivilege {
+    pub vehicle_category_code: String,
+    pub issue_date: Option<FullDate>,
+    pub expiry_date: Option<FullDate>,
+    pub codes: Option<Vec<DrivingPrivilegeCode>>,
+}
+
+/// A restriction or condition that applies to a [`DrivingPrivilege`].
+#[skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrivingPrivilegeCode {
+    pub code: String,
+    pub sign: Option<String>,
+    pub value: Option<String>,
+}
+
+impl DrivingPrivilege {
+    fn has_valid_dates(&self) -> bool {
+        [&self.issue_date, &self.expiry_date]
+            .into_iter()
+            .flatten()
+            .all(|date| NaiveDate::try_from(date).is_ok())
+    }
+}
+
+/// The type of the value of an mDL data element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MdlValueType {
+    Text,
+    Uint,
+    Bool,
+    Bytes,
+    /// A [`FullDate`].
+    FullDate,
+    /// A `tdate`, i.e. a date-time tagged with CBOR tag 0.
+    Tdate,
+    /// Either a `tdate` or a [`FullDate`].
+    TdateOrFullDate,
+    /// An array of [`DrivingPrivilege`]s.
+    DrivingPrivileges,
+}
+
+impl MdlValueType {
+    pub fn matches(&self, value: &DataElementValue) -> bool {
+        match (self, value) {
+            (Self::Text, Value::Text(_)) => true,
+            (Self::Uint, Value::Integer(i)) => u64::try_from(*i).is_ok(),
+            (Self::Bool, Value::Bool(_)) => true,
+            (Self::Bytes, Value::Bytes(_)) => true,
+            (Self::FullDate, Value::Tag(1004, value)) => value
+                .as_text()
+                .is_some_and(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()),
+            (Self::Tdate, Value::Tag(0, value)) => value
+                .as_text()
+                .is_some_and(|date_time| DateTime::parse_from_rfc3339(date_time).is_ok()),
+            (Self::TdateOrFullDate, value) => Self::Tdate.matches(value) || Self::FullDate.matches(value),
+            (Self::DrivingPrivileges, value) => value
+                .deserialized::<Vec<DrivingPrivilege>>()
+                .is_ok_and(|privileges| privileges.iter().all(DrivingPrivilege::has_valid_dates)),
+            _ => false,
+        }
+    }
+}
+
+/// A data element in the [`MDL_NAMESPACE`], see table 5 of ISO 18013-5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MdlDataElement {
+    pub identifier: &'static str,
+    pub value_type: MdlValueType,
+    pub is_mandatory: bool,
+}
+
+impl MdlDataElement {
+    const fn mandatory(identifier: &'static str, value_type: MdlValueType) -> Self {
+        Self {
+            identifier,
+            value_type,
+            is_mandatory: true,
+        }
+    }
+
+    const fn optional(identifier: &'static str, value_type: MdlValueType) -> Self {
+        Self {
+            identifier,
+            value_type,
+            is_mandatory: false,
+        }
+    }
+
+    /// Look up a data element by its identifier. Apart from the data elements in [`MDL_DATA_ELEMENTS`], this
+    /// recognizes the `age_over_NN` data elements for any two-digit `NN`.
+    pub fn find(identifier: &str) -> Option<Self> {
+        MDL_DATA_ELEMENTS
+            .iter()
+            .find(|element| element.identifier == identifier)
+            .copied()
+            .or_else(|| {
+                identifier
+                    .strip_prefix(AGE_OVER_PREFIX)
+                    .filter(|age| age.len() == 2 && age.chars().all(|c| c.is_ascii_digit()))
+                    // The identifier is not `'static`, so use a generic one in the description of the data element.
+                    .map(|_| Self::optional("age_over_NN", MdlValueType::Bool))
+            })
+    }
+}
+
+/// All data elements in the [`MDL_NAMESPACE`], except for `age_over_NN`. The mandatory data elements are listed first.
+pub const MDL_DATA_ELEMENTS: &[MdlDataElement] = &[
+    MdlDataElement::mandatory("family_name", MdlValueType::Text),
+    MdlDataElement::mandatory("given_name", MdlValueType::Text),
+    MdlDataElement::mandatory("birth_date", MdlValueType::FullDate),
+    MdlDataElement::mandatory("issue_date", MdlValueType::TdateOrFullDate),
+    MdlDataElement::mandatory("expiry_date", MdlValueType::TdateOrFullDate),
+    MdlDataElement::mandatory("issuing_country", MdlValueType::Text),
+    MdlDataElement::mandatory("issuing_authority", MdlValueType::Text),
+    MdlDataElement::mandatory("document_number", MdlValueType::Text),
+    MdlDataElement::mandatory("portrait", MdlValueType::Bytes),
+    MdlDataElement::mandatory("driving_privileges", MdlValueType::DrivingPrivileges),
+    MdlDataElement::mandatory("un_distinguishing_sign", MdlValueType::Text),
+    MdlDataElement::optional("administrative_number", MdlValueType::Text),
+    MdlDataElement::optional("sex", MdlValueType::Uint),
+    MdlDataElement::optional("height", MdlValueType::Uint),
+    MdlDataElement::optional("weight", MdlValueType::Uint),
+    MdlDataElement::optional("eye_colour", MdlValueType::Text),
+    MdlDataElement::optional("hair_colour", MdlValueType::Text),
+    MdlDataElement::optional("birth_place", MdlValueType::Text),
+    MdlDataElement::optional("resident_address", MdlValueType::Text),
+    MdlDataElement::optional("portrait_capture_date", MdlValueType::Tdate),
+    MdlDataElement::optional("age_in_years", MdlValueType::Uint),
+    MdlDataElement::optional("age_birth_year", MdlValueType::Uint),
+    MdlDataElement::optional("issuing_jurisdiction", MdlValueType::Text),
+    MdlDataElement::optional("nationality", MdlValueType::Text),
+    MdlDataElement::optional("resident_city", MdlValueType::Text),
+    MdlDataElement::optional("resident_state", MdlValueType::Text),
+    MdlDataElement::optional("resident_postal_code", MdlValueType::Text),
+    MdlDataElement::optional("resident_country", MdlValueType::Text),
+    MdlDataElement::optional("family_name_national_character", MdlValueType::Text),
+    MdlDataElement::optional("given_name_national_character", MdlValueType::Text),
+    MdlDataElement::optional("signature_usual_mark", MdlValueType::Bytes),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum MdlError {
+    #[error("unknown mDL data element: {0}")]
+    UnknownDataElement(DataElementIdentifier),
+    #[error("mDL data element {name} does not match expected type {expected_type:?}: {value:?}")]
+    ValueTypeMismatch {
+        name: DataElementIdentifier,
+        expected_type: MdlValueType,
+        value: DataElementValue,
+    },
+    #[error("mDL data element occurs more than once: {0}")]
+    DuplicateDataElement(DataElementIdentifier),
+    #[error("missing mandatory mDL data element: {0}")]
+    MissingDataElement(&'static str),
+}
+
+/// Validate the data elements in the [`MDL_NAMESPACE`] against the mDL data model. If `require_mandatory` is set, all
+/// mandatory data elements have to be present, which should be the case when issuing an mDL but not when only some
+/// of its data elements are disclosed. Other namespaces, such as domestic namespaces, are not checked.
+pub fn validate_mdl_attributes(
+    attributes: &IndexMap<NameSpace, Vec<Entry>>,
+    require_mandatory: bool,
+) -> Result<(), MdlError> {
+    let entries = attributes.get(MDL_NAMESPACE).map(Vec::as_slice).unwrap_or_default();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let element =
+            MdlDataElement::find(&entry.name).ok_or_else(|| MdlError::UnknownDataElement(entry.name.clone()))?;
+
+        if !element.value_type.matches(&entry.value) {
+            return Err(MdlError::ValueTypeMismatch {
+                name: entry.name.clone(),
+                expected_type: element.value_type,
+                value: entry.value.clone(),
+            });
+        }
+
+        if entries[..index].iter().any(|other| other.name == entry.name) {
+            return Err(MdlError::DuplicateDataElement(entry.name.clone()));
+        }
+    }
+
+    if require_mandatory {
+        if let Some(missing) = MDL_DATA_ELEMENTS
+            .iter()
+            .filter(|element| element.is_mandatory)
+            .find(|element| !entries.iter().any(|entry| entry.name == element.identifier))
+        {
+            return Err(MdlError::MissingDataElement(missing.identifier));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use rstest::rstest;
+
+    use crate::examples::{Examples, EXAMPLE_DOC_TYPE, EXAMPLE_NAMESPACE};
+
+    use super::*;
+
+    fn full_date(date: &str) -> Value {
+        Value::Tag(1004, Value::Text(date.to_string()).into())
+    }
+
+    #[test]
+    fn test_mdl_constants_match_iso_examples() {
+        assert_eq!(MDL_DOCTYPE, EXAMPLE_DOC_TYPE);
+        assert_eq!(MDL_NAMESPACE, EXAMPLE_NAMESPACE);
+    }
+
+    #[test]
+    fn test_validate_iso_example_mdl() {
+        // The example only discloses some of the data elements of the mDL.
+        let attributes = Examples::mdl_attributes();
+        validate_mdl_attributes(&attributes, false).expect("ISO example mDL should be valid");
+        assert_matches!(
+            validate_mdl_attributes(&attributes, true),
+            Err(MdlError::MissingDataElement("given_name"))
+        );
+
+        validate_mdl_attributes(&Examples::full_mdl_attributes(), true).expect("full mDL should be valid");
+    }
+
+    #[test]
+    fn test_iso_example_driving_privileges() {
+        let attributes = Examples::mdl_attributes();
+        let privileges = attributes[MDL_NAMESPACE]
+            .iter()
+            .find(|entry| entry.name == "driving_privileges")
+            .unwrap()
+            .value
+            .deserialized::<Vec<DrivingPrivilege>>()
+            .unwrap();
+
+        assert_eq!(
+            privileges
+                .iter()
+                .map(|privilege| privilege.vehicle_category_code.as_str())
+                .collect::<Vec<_>>(),
+            vec!["A", "B"]
+        );
+        assert_eq!(
+            NaiveDate::try_from(privileges[0].issue_date.as_ref().unwrap()).unwrap(),
+            NaiveDate::from_ymd_opt(2018, 8, 9).unwrap()
+        );
+
+        // Serializing the privileges should result in the original value.
+        assert_eq!(
+            Value::serialized(&privileges).unwrap(),
+            attributes[MDL_NAMESPACE]
+                .iter()
+                .find(|entry| entry.name == "driving_privileges")
+                .unwrap()
+                .value
+        );
+    }
+
+    #[rstest]
+    #[case("family_name", Value::Integer(1.into()), false)]
+    #[case("birth_date", Value::Text("1980-01-01".to_string()), false)]
+    #[case("birth_date", full_date("1980-13-01"), false)]
+    #[case("issue_date", Value::Tag(0, Value::Text("2020-10-01T13:30:02Z".to_string()).into()), true)]
+    #[case("portrait", Value::Text("not an image".to_string()), false)]
+    #[case("age_over_21", Value::Bool(false), true)]
+    #[case("age_over_21", Value::Text("no".to_string()), false)]
+    #[case("sex", Value::Integer((-1).into()), false)]
+    #[case("driving_privileges", Value::Array(vec![]), true)]
+    #[case("driving_privileges", Value::Array(vec![Value::Map(vec![])]), false)]
+    fn test_mdl_value_type(#[case] name: &str, #[case] value: Value, #[case] is_valid: bool) {
+        let element = MdlDataElement::find(name).unwrap();
+
+        assert_eq!(element.value_type.matches(&value), is_valid);
+    }
+
+    #[test]
+    fn test_validate_mdl_errors() {
+        let mut attributes = Examples::full_mdl_attributes();
+        attributes.get_mut(MDL_NAMESPACE).unwrap().push(Entry {
+            name: "age_over_eighteen".to_string(),
+            value: Value::Bool(true),
+        });
+        assert_matches!(
+            validate_mdl_attributes(&attributes, true),
+            Err(MdlError::UnknownDataElement(name)) if name == "age_over_eighteen"
+        );
+
+        let mut attributes = Examples::full_mdl_attributes();
+        attributes.get_mut(MDL_NAMESPACE).unwrap().push(Entry {
+            name: "family_name".to_string(),
+            value: Value::Text("Smith".to_string()),
+        });
+        assert_matches!(
+            validate_mdl_attributes(&attributes, true),
+            Err(MdlError::DuplicateDataElement(name)) if name == "family_name"
+        );
+
+        let mut attributes = Examples::full_mdl_attributes();
+        attributes.get_mut(MDL_NAMESPACE).unwrap()[0].value = Value::Bool(true);
+        assert_matches!(
+            validate_mdl_attributes(&attributes, true),
+            Err(MdlError::ValueTypeMismatch { name, expected_type: MdlValueType::Text, .. }) if name == "family_name"
+        );
+    }
+}
@@ -169,6 +169,10 @@ pub enum MobileSecurityObjectVersion {
 pub enum DigestAlgorithm {
     #[serde(rename = "SHA-256")]
     SHA256,
+    #[serde(rename = "SHA-384")]
+    SHA384,
+    #[serde(rename = "SHA-512")]
+    SHA512,
 }
 
 #[skip_serializing_none]
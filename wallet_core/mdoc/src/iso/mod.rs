@@ -16,3 +16,6 @@ pub use disclosure::*;
 
 pub mod engagement;
 pub use engagement::*;
+
+pub mod mdl;
+pub use mdl::*;
@@ -5,7 +5,8 @@ use nutype::nutype;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    utils::serialization::TaggedBytes, Attributes, DataElementIdentifier, DataElementValue, DocType, NameSpace, Tdate,
+    utils::serialization::{data_element_value, TaggedBytes},
+    Attributes, DataElementIdentifier, DataElementValue, DocType, NameSpace, Tdate,
 };
 
 #[nutype(
@@ -41,6 +42,7 @@ pub struct UnsignedMdoc {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Entry {
     pub name: DataElementIdentifier,
+    #[serde(with = "data_element_value")]
     pub value: DataElementValue,
 }
 
@@ -57,11 +59,24 @@ impl From<&Attributes> for Vec<Entry> {
     }
 }
 
+impl From<(&DataElementIdentifier, &DataElementValue)> for Entry {
+    fn from((name, value): (&DataElementIdentifier, &DataElementValue)) -> Self {
+        Entry {
+            name: name.clone(),
+            value: value.clone(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use ciborium::Value;
     use regex::Regex;
 
-    use crate::test::data;
+    use crate::{
+        test::data,
+        utils::serialization::{cbor_deserialize, cbor_serialize},
+    };
 
     use super::*;
 
@@ -90,4 +105,40 @@ mod tests {
 
         serde_json::from_str::<UnsignedMdoc>(&unsigned_json_cc_100).expect("should be valid JSON of UnsignedMdoc");
     }
+
+    #[test]
+    fn test_entry_json_round_trip() {
+        let entry = Entry {
+            name: "driving_privileges".to_string(),
+            value: Value::Array(vec![Value::Map(vec![
+                (
+                    Value::Text("vehicle_category_code".to_string()),
+                    Value::Text("A".to_string()),
+                ),
+                (
+                    Value::Text("issue_date".to_string()),
+                    Value::Tag(1004, Box::new(Value::Text("2018-08-09".to_string()))),
+                ),
+                (
+                    Value::Text("codes".to_string()),
+                    Value::Array(vec![Value::Integer(5.into())]),
+                ),
+                (Value::Text("signature".to_string()), Value::Bytes(vec![0, 1, 42])),
+            ])]),
+        };
+
+        // Byte strings and tags should survive a JSON round trip...
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains(r#"{"@@BYTES@@":"AAEq"}"#));
+        assert!(json.contains(r#"{"@@TAGGED@@":[1004,"2018-08-09"]}"#));
+        assert_eq!(serde_json::from_str::<Entry>(&json).unwrap(), entry);
+
+        // ...while the CBOR serialization should contain the value as is.
+        let cbor = cbor_serialize(&entry).unwrap();
+        assert_eq!(cbor_deserialize::<Entry, _>(cbor.as_slice()).unwrap(), entry);
+        assert_eq!(
+            cbor_deserialize::<IndexMap<String, Value>, _>(cbor.as_slice()).unwrap()["value"],
+            entry.value
+        );
+    }
 }
@@ -2,6 +2,8 @@ use chrono::Utc;
 use ciborium::value::Value;
 use coset::{CoseSign1, HeaderBuilder};
 
+use wallet_common::keys::EcdsaKey;
+
 use crate::{
     iso::*,
     server_keys::KeyPair,
@@ -14,7 +16,11 @@ use crate::{
 };
 
 impl IssuerSigned {
-    pub async fn sign(unsigned_mdoc: UnsignedMdoc, device_public_key: CoseKey, key: &KeyPair) -> Result<Self> {
+    pub async fn sign(
+        unsigned_mdoc: UnsignedMdoc,
+        device_public_key: CoseKey,
+        key: &KeyPair<impl EcdsaKey>,
+    ) -> Result<Self> {
         let now = Utc::now();
         let validity = ValidityInfo {
             signed: now.into(),
@@ -110,7 +116,7 @@ mod tests {
 
         // The IssuerSigned should be valid
         issuer_signed
-            .verify(ValidityRequirement::Valid, &TimeGenerator, trust_anchors)
+            .verify(ValidityRequirement::Valid, &TimeGenerator, trust_anchors, &[])
             .unwrap();
 
         // The issuer certificate generated above should be included in the IssuerAuth
@@ -130,6 +136,7 @@ mod tests {
             issuer_signed,
             &TimeGenerator,
             trust_anchors,
+            &[],
         )
         .unwrap();
         mdoc.compare_unsigned(&unsigned).unwrap();
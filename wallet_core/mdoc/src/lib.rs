@@ -7,6 +7,9 @@ pub mod holder;
 pub mod issuer;
 pub mod verifier;
 
+// Offline disclosure between holder and reader over a pluggable transport
+pub mod proximity;
+
 // Data types shared between servers
 pub mod server_keys;
 pub mod server_state;
@@ -0,0 +1,309 @@
+use p256::{ecdsa::VerifyingKey, PublicKey, SecretKey};
+use rand_core::OsRng;
+use tracing::{info, warn};
+use webpki::TrustAnchor;
+
+use wallet_common::revocation_list::DerCertificateRevocationList;
+
+use crate::{
+    device_retrieval::DeviceRequest,
+    disclosure::{DeviceResponse, SessionData, SessionEstablishment, SessionStatus},
+    engagement::{DeviceEngagement, Engagement, EngagementVersion, SessionTranscript},
+    errors::{Error, Result},
+    holder::{
+        verify_device_request, CandidateAttributes, DeviceAuthMethod, DisclosureError, DisclosureResult, HolderError,
+        MdocDataSource, ProposedAttributes, ProposedDocumentCandidates, VerifierSessionDataCheckResult,
+    },
+    identifiers::AttributeIdentifier,
+    utils::{
+        crypto::{SessionKey, SessionKeyUser},
+        keys::{KeyFactory, MdocEcdsaKey},
+        reader_auth::ReaderRegistration,
+        serialization::CborError,
+        x509::Certificate,
+    },
+};
+
+use super::{receive_message, send_message, Transport};
+
+/// The holder side of a proximity session, which is started after the reader has scanned the [`DeviceEngagement`]
+/// and sent its [`SessionEstablishment`]. Like [`DisclosureSession`](crate::holder::DisclosureSession), this
+/// either contains the attributes that are missing to satisfy the request of the reader, or a proposal of
+/// attributes to be disclosed after approval of the user.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum ProximityDisclosureSession<T, I> {
+    MissingAttributes(ProximityMissingAttributes<T>),
+    Proposal(ProximityProposal<T, I>),
+}
+
+#[derive(Debug)]
+pub struct ProximityMissingAttributes<T> {
+    data: CommonProximityData<T>,
+    missing_attributes: Vec<Vec<AttributeIdentifier>>,
+}
+
+#[derive(Debug)]
+pub struct ProximityProposal<T, I> {
+    data: CommonProximityData<T>,
+    device_key: SessionKey,
+    // The ephemeral key of the reader and the session transcript, with which the disclosed mdocs are authenticated.
+    reader_public_key: PublicKey,
+    session_transcript: SessionTranscript,
+    candidates: ProposedDocumentCandidates<I>,
+}
+
+#[derive(Debug)]
+struct CommonProximityData<T> {
+    transport: T,
+    certificate: Certificate,
+    reader_registration: ReaderRegistration,
+}
+
+impl DeviceEngagement {
+    /// Create a new [`DeviceEngagement`] containing a fresh ephemeral key, to be shown to the reader
+    /// in a QR code when starting a proximity session.
+    pub fn new_proximity_device_engagement() -> Result<(DeviceEngagement, SecretKey)> {
+        let privkey = SecretKey::random(&mut OsRng);
+
+        let engagement = Engagement {
+            version: EngagementVersion::V1_0,
+            security: Some((&privkey.public_key()).try_into()?),
+            connection_methods: None,
+            origin_infos: vec![],
+        };
+
+        Ok((engagement.into(), privkey))
+    }
+}
+
+impl<T, I> ProximityDisclosureSession<T, I>
+where
+    T: Transport,
+{
+    /// Wait for the [`SessionEstablishment`] of the reader on the `transport` and check the [`DeviceRequest`]
+    /// contained in it against the mdocs in `mdoc_data_source`.
+    pub async fn start<'a, S>(
+        mut transport: T,
+        device_engagement: &DeviceEngagement,
+        ephemeral_privkey: SecretKey,
+        mdoc_data_source: &S,
+        trust_anchors: &[TrustAnchor<'a>],
+        crls: &[DerCertificateRevocationList],
+    ) -> Result<Self>
+    where
+        S: MdocDataSource<MdocIdentifier = I>,
+    {
+        info!("start proximity disclosure session");
+
+        // Receive the `SessionEstablishment`, report back to the reader if it could not be decoded.
+        let session_establishment: SessionEstablishment = match receive_message(&mut transport).await {
+            Ok(session_establishment) => session_establishment,
+            Err(error) => return Self::report_error_back(error, &mut transport).await,
+        };
+
+        // Derive the session transcript and keys in both directions from our `DeviceEngagement`,
+        // the ephemeral key of the reader and our ephemeral private key.
+        let keys = Self::transcript_and_keys(device_engagement, &session_establishment, &ephemeral_privkey);
+        let (reader_public_key, session_transcript, reader_key, device_key) = match keys {
+            Ok(keys) => keys,
+            Err(error) => return Self::report_error_back(error, &mut transport).await,
+        };
+
+        // Decrypt and verify the received `DeviceRequest`.
+        let result = async {
+            let device_request: DeviceRequest = session_establishment
+                .session_data()
+                .decrypt_and_deserialize(&reader_key)?;
+
+            verify_device_request(
+                &device_request,
+                &session_transcript,
+                mdoc_data_source,
+                trust_anchors,
+                crls,
+            )
+            .await
+        }
+        .await;
+        let (check_result, certificate, reader_registration) = match result {
+            Ok(result) => result,
+            Err(error) => return Self::report_error_back(error, &mut transport).await,
+        };
+
+        let data = CommonProximityData {
+            transport,
+            certificate,
+            reader_registration,
+        };
+
+        let session = match check_result {
+            VerifierSessionDataCheckResult::MissingAttributes(missing_attributes) => {
+                ProximityDisclosureSession::MissingAttributes(ProximityMissingAttributes {
+                    data,
+                    missing_attributes,
+                })
+            }
+            VerifierSessionDataCheckResult::Candidates(candidates) => {
+                ProximityDisclosureSession::Proposal(ProximityProposal {
+                    data,
+                    device_key,
+                    reader_public_key,
+                    session_transcript,
+                    candidates,
+                })
+            }
+        };
+
+        Ok(session)
+    }
+
+    fn transcript_and_keys(
+        device_engagement: &DeviceEngagement,
+        session_establishment: &SessionEstablishment,
+        ephemeral_privkey: &SecretKey,
+    ) -> Result<(PublicKey, SessionTranscript, SessionKey, SessionKey)> {
+        let reader_public_key: PublicKey = VerifyingKey::try_from(&session_establishment.e_reader_key.0)?.into();
+        let session_transcript =
+            SessionTranscript::new_qr(device_engagement, session_establishment.e_reader_key.clone());
+
+        let reader_key = SessionKey::new(
+            ephemeral_privkey,
+            &reader_public_key,
+            &session_transcript,
+            SessionKeyUser::Reader,
+        )?;
+        let device_key = SessionKey::new(
+            ephemeral_privkey,
+            &reader_public_key,
+            &session_transcript,
+            SessionKeyUser::Device,
+        )?;
+
+        Ok((reader_public_key, session_transcript, reader_key, device_key))
+    }
+
+    async fn report_error_back<R>(error: Error, transport: &mut T) -> Result<R> {
+        // Determine the category of the error, so we can report on it.
+        let error_session_data = match error {
+            Error::Cbor(CborError::Deserialization(_)) => SessionData::new_decoding_error(),
+            Error::Crypto(_) => SessionData::new_encryption_error(),
+            _ => SessionData::new_termination(),
+        };
+
+        warn!(
+            "reporting error back with status: {:?}",
+            error_session_data.status.unwrap()
+        );
+
+        // Ignore any errors, as we are already returning one.
+        let _ = send_message(transport, &error_session_data).await;
+
+        Err(error)
+    }
+
+    fn data(&self) -> &CommonProximityData<T> {
+        match self {
+            ProximityDisclosureSession::MissingAttributes(session) => &session.data,
+            ProximityDisclosureSession::Proposal(session) => &session.data,
+        }
+    }
+
+    pub fn reader_registration(&self) -> &ReaderRegistration {
+        &self.data().reader_registration
+    }
+
+    pub fn verifier_certificate(&self) -> &Certificate {
+        &self.data().certificate
+    }
+
+    pub async fn terminate(self) -> Result<()> {
+        let mut data = match self {
+            ProximityDisclosureSession::MissingAttributes(session) => session.data,
+            ProximityDisclosureSession::Proposal(session) => session.data,
+        };
+
+        send_message(&mut data.transport, &SessionData::new_termination()).await
+    }
+}
+
+impl<T> ProximityMissingAttributes<T> {
+    /// Returns the missing attributes for every `Mdoc` candidate.
+    pub fn missing_attributes(&self) -> &[Vec<AttributeIdentifier>] {
+        &self.missing_attributes
+    }
+}
+
+impl<T, I> ProximityProposal<T, I>
+where
+    T: Transport,
+    I: Clone,
+{
+    pub fn proposed_source_identifiers(&self) -> Vec<&I> {
+        self.candidates
+            .selected()
+            .map(|document| &document.source_identifier)
+            .collect()
+    }
+
+    pub fn proposed_attributes(&self) -> ProposedAttributes {
+        self.candidates
+            .selected()
+            .map(|document| (document.doc_type.clone(), document.proposed_attributes()))
+            .collect()
+    }
+
+    pub fn candidate_attributes(&self) -> CandidateAttributes {
+        self.candidates.candidate_attributes()
+    }
+
+    pub fn select_candidates(&mut self, selection: &[usize]) -> Result<()> {
+        self.candidates.select(selection)
+    }
+
+    /// Send the selected attributes to the reader and wait for it to end the session.
+    pub async fn disclose<KF, K>(mut self, key_factory: &KF) -> DisclosureResult<(), Error>
+    where
+        KF: KeyFactory<Key = K>,
+        K: MdocEcdsaKey,
+    {
+        info!("disclose proposed documents over proximity transport");
+
+        let proposed_documents = self.candidates.selected().cloned().collect();
+        let device_auth_method = DeviceAuthMethod::Mac {
+            reader_key: &self.reader_public_key,
+            session_transcript: &self.session_transcript,
+        };
+        let device_response =
+            DeviceResponse::from_proposed_documents(proposed_documents, device_auth_method, key_factory)
+                .await
+                .map_err(DisclosureError::before_sharing)?;
+
+        let session_data = SessionData::serialize_and_encrypt(&device_response, &self.device_key)
+            .map_err(DisclosureError::before_sharing)?;
+
+        // Whether or not the data was actually received by the reader is unknown when sending fails,
+        // so we have to assume it was.
+        send_message(&mut self.data.transport, &session_data)
+            .await
+            .map_err(DisclosureError::after_sharing)?;
+
+        // The reader should respond by ending the session, any other status indicates an error.
+        let response: SessionData = receive_message(&mut self.data.transport)
+            .await
+            .map_err(DisclosureError::after_sharing)?;
+
+        match response.status {
+            Some(status) if status != SessionStatus::Termination => {
+                warn!("sending device response failed with status: {status:?}");
+                Err(DisclosureError::after_sharing(
+                    HolderError::DisclosureResponse(status).into(),
+                ))
+            }
+            _ => {
+                info!("sending device response succeeded");
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use tokio::sync::mpsc;
+
+use super::Transport;
+
+/// An in-memory [`Transport`], of which two connected instances are created by [`LoopbackTransport::new_pair()`].
+/// This allows running a holder and a reader proximity session against each other within the same process.
+#[derive(Debug)]
+pub struct LoopbackTransport {
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+    receiver: mpsc::UnboundedReceiver<Vec<u8>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoopbackTransportError {
+    #[error("other side of loopback transport was dropped")]
+    Disconnected,
+}
+
+impl LoopbackTransport {
+    pub fn new_pair() -> (Self, Self) {
+        let (sender_a, receiver_a) = mpsc::unbounded_channel();
+        let (sender_b, receiver_b) = mpsc::unbounded_channel();
+
+        let transport_a = LoopbackTransport {
+            sender: sender_a,
+            receiver: receiver_b,
+        };
+        let transport_b = LoopbackTransport {
+            sender: sender_b,
+            receiver: receiver_a,
+        };
+
+        (transport_a, transport_b)
+    }
+}
+
+impl Transport for LoopbackTransport {
+    type Error = LoopbackTransportError;
+
+    async fn send(&mut self, message: Vec<u8>) -> Result<(), Self::Error> {
+        self.sender
+            .send(message)
+            .map_err(|_| LoopbackTransportError::Disconnected)
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Self::Error> {
+        self.receiver.recv().await.ok_or(LoopbackTransportError::Disconnected)
+    }
+}
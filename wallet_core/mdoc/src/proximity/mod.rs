@@ -0,0 +1,324 @@
+//! Offline (proximity) disclosure of mdocs as specified by ISO 18013-5, for both the holder and the reader.
+//!
+//! Contrary to the online disclosure in the [`holder`](crate::holder) and [`verifier`](crate::verifier) modules,
+//! the holder starts a proximity session by showing its [`DeviceEngagement`] in a QR code, which includes its
+//! ephemeral key. The reader responds with a [`SessionEstablishment`] containing its own ephemeral key and the
+//! encrypted [`DeviceRequest`](crate::DeviceRequest), after which the encrypted
+//! [`DeviceResponse`](crate::DeviceResponse) and the session termination are exchanged as [`SessionData`] messages.
+//!
+//! These messages are sent over a [`Transport`], which abstracts over the actual communication channel
+//! (e.g. BLE or NFC). The [`LoopbackTransport`] connects a holder and reader within the same process.
+
+use base64::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    errors::Result,
+    iso::{
+        disclosure::{SessionData, SessionEstablishment, SessionStatus},
+        engagement::DeviceEngagement,
+    },
+    utils::serialization::{cbor_deserialize, cbor_serialize},
+};
+
+pub use holder::{ProximityDisclosureSession, ProximityMissingAttributes, ProximityProposal};
+pub use loopback::{LoopbackTransport, LoopbackTransportError};
+pub use reader::ReaderProximitySession;
+
+mod holder;
+mod loopback;
+mod reader;
+
+/// The URI scheme of the QR code containing the [`DeviceEngagement`], as specified by ISO 18013-5.
+const QR_CODE_URI_SCHEME: &str = "mdoc:";
+
+#[derive(thiserror::Error, Debug)]
+pub enum ProximityError {
+    #[error("transport error: {0}")]
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("QR code does not contain an \"{QR_CODE_URI_SCHEME}\" URI")]
+    QrCodeUriScheme,
+    #[error("could not decode device engagement in QR code: {0}")]
+    QrCodeUriDecoding(#[from] base64::DecodeError),
+    #[error("device engagement is missing security information")]
+    DeviceEngagementSecurityMissing,
+    #[error("other party ended session with status: {0:?}")]
+    SessionStatus(SessionStatus),
+}
+
+/// A bidirectional channel over which the holder and the reader exchange the messages of a proximity session.
+/// Implementations are responsible for any framing required by the underlying channel, so that each call to
+/// [`Transport::receive()`] returns exactly one message as passed to [`Transport::send()`] by the other side.
+pub trait Transport {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    async fn send(&mut self, message: Vec<u8>) -> Result<(), Self::Error>;
+    async fn receive(&mut self) -> Result<Vec<u8>, Self::Error>;
+}
+
+impl DeviceEngagement {
+    /// Encode this [`DeviceEngagement`] as URI, to be shown to the reader in a QR code.
+    pub fn to_qr_code_uri(&self) -> Result<String> {
+        let uri = format!(
+            "{}{}",
+            QR_CODE_URI_SCHEME,
+            BASE64_URL_SAFE_NO_PAD.encode(cbor_serialize(self)?)
+        );
+
+        Ok(uri)
+    }
+
+    /// Decode a [`DeviceEngagement`] from the URI contained in a scanned QR code.
+    pub fn from_qr_code_uri(uri: &str) -> Result<Self> {
+        let encoded = uri
+            .strip_prefix(QR_CODE_URI_SCHEME)
+            .ok_or(ProximityError::QrCodeUriScheme)?;
+        let device_engagement = cbor_deserialize(
+            BASE64_URL_SAFE_NO_PAD
+                .decode(encoded)
+                .map_err(ProximityError::QrCodeUriDecoding)?
+                .as_slice(),
+        )?;
+
+        Ok(device_engagement)
+    }
+}
+
+impl SessionEstablishment {
+    /// Convert to [`SessionData`], so that the contained [`DeviceRequest`](crate::DeviceRequest) can be decrypted.
+    fn session_data(&self) -> SessionData {
+        SessionData {
+            data: Some(self.data.clone()),
+            status: None,
+        }
+    }
+}
+
+async fn send_message<T, M>(transport: &mut T, message: &M) -> Result<()>
+where
+    T: Transport,
+    M: Serialize,
+{
+    let bytes = cbor_serialize(message)?;
+    transport
+        .send(bytes)
+        .await
+        .map_err(|error| ProximityError::Transport(error.into()))?;
+
+    Ok(())
+}
+
+async fn receive_message<T, M>(transport: &mut T) -> Result<M>
+where
+    T: Transport,
+    M: DeserializeOwned,
+{
+    let bytes = transport
+        .receive()
+        .await
+        .map_err(|error| ProximityError::Transport(error.into()))?;
+    let message = cbor_deserialize(bytes.as_slice())?;
+
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use webpki::TrustAnchor;
+
+    use wallet_common::trust_anchor::DerTrustAnchor;
+
+    use crate::{
+        examples::{Examples, IsoCertTimeGenerator, EXAMPLE_DOC_TYPE, EXAMPLE_NAMESPACE},
+        holder::disclosure::test::{example_items_request, MockMdocDataSource, EXAMPLE_ATTRIBUTES},
+        server_keys::KeyPair,
+        software_key_factory::SoftwareKeyFactory,
+        utils::reader_auth::ReaderRegistration,
+        verifier::ValidityPolicy,
+        Error,
+    };
+
+    use super::*;
+
+    struct TestReader {
+        private_key: KeyPair,
+        trust_anchors: Vec<DerTrustAnchor>,
+    }
+
+    impl TestReader {
+        fn new() -> Self {
+            let reader_registration = ReaderRegistration {
+                attributes: ReaderRegistration::create_attributes(
+                    EXAMPLE_DOC_TYPE.to_string(),
+                    EXAMPLE_NAMESPACE.to_string(),
+                    EXAMPLE_ATTRIBUTES.iter().copied(),
+                ),
+                ..ReaderRegistration::new_mock()
+            };
+
+            let ca = KeyPair::generate_reader_mock_ca().unwrap();
+            let trust_anchors = vec![DerTrustAnchor::from_der(ca.certificate().as_bytes().to_vec()).unwrap()];
+            let private_key = ca.generate_reader_mock(reader_registration.into()).unwrap();
+
+            TestReader {
+                private_key,
+                trust_anchors,
+            }
+        }
+
+        fn trust_anchors(&self) -> Vec<TrustAnchor<'_>> {
+            self.trust_anchors
+                .iter()
+                .map(|anchor| (&anchor.owned_trust_anchor).into())
+                .collect()
+        }
+    }
+
+    /// Scan the QR code of the holder and start both sides of the session over a loopback transport.
+    async fn start_sessions(
+        mdoc_data_source: MockMdocDataSource,
+    ) -> (
+        ProximityDisclosureSession<LoopbackTransport, String>,
+        ReaderProximitySession<LoopbackTransport>,
+    ) {
+        let reader = TestReader::new();
+        let (holder_transport, reader_transport) = LoopbackTransport::new_pair();
+
+        let (device_engagement, device_privkey) = DeviceEngagement::new_proximity_device_engagement().unwrap();
+        let qr_code_uri = device_engagement.to_qr_code_uri().unwrap();
+
+        let scanned_device_engagement = DeviceEngagement::from_qr_code_uri(&qr_code_uri).unwrap();
+        let reader_session = ReaderProximitySession::start(
+            reader_transport,
+            &scanned_device_engagement,
+            vec![example_items_request()].into(),
+            &reader.private_key,
+        )
+        .await
+        .expect("could not start reader session");
+
+        let holder_session = ProximityDisclosureSession::start(
+            holder_transport,
+            &device_engagement,
+            device_privkey,
+            &mdoc_data_source,
+            &reader.trust_anchors(),
+            &[],
+        )
+        .await
+        .expect("could not start holder session");
+
+        (holder_session, reader_session)
+    }
+
+    #[test]
+    fn test_device_engagement_qr_code_uri() {
+        let (device_engagement, _) = DeviceEngagement::new_proximity_device_engagement().unwrap();
+
+        let uri = device_engagement.to_qr_code_uri().unwrap();
+        assert!(uri.starts_with("mdoc:"));
+
+        let decoded = DeviceEngagement::from_qr_code_uri(&uri).unwrap();
+        assert_eq!(
+            cbor_serialize(&decoded).unwrap(),
+            cbor_serialize(&device_engagement).unwrap()
+        );
+
+        let error = DeviceEngagement::from_qr_code_uri(&uri.replace("mdoc:", "https:")).unwrap_err();
+        assert_matches!(error, Error::Proximity(ProximityError::QrCodeUriScheme));
+    }
+
+    #[tokio::test]
+    async fn test_proximity_disclosure() {
+        let (holder_session, reader_session) = start_sessions(MockMdocDataSource::default()).await;
+
+        let proposal = match holder_session {
+            ProximityDisclosureSession::Proposal(proposal) => proposal,
+            _ => panic!("expected disclosure proposal"),
+        };
+        assert_eq!(
+            proposal.proposed_attributes()[EXAMPLE_DOC_TYPE].attributes[EXAMPLE_NAMESPACE].len(),
+            EXAMPLE_ATTRIBUTES.len()
+        );
+
+        let key_factory = SoftwareKeyFactory::default();
+        let validity_policy = ValidityPolicy::default();
+        let (disclose_result, disclosed_attributes) = tokio::join!(
+            proposal.disclose(&key_factory),
+            reader_session.receive_response(
+                &IsoCertTimeGenerator,
+                &validity_policy,
+                Examples::iaca_trust_anchors(),
+                &[]
+            )
+        );
+
+        disclose_result.expect("could not disclose attributes");
+        let disclosed_attributes = disclosed_attributes.expect("could not verify disclosed attributes");
+
+        let disclosed_names = disclosed_attributes[EXAMPLE_DOC_TYPE].attributes[EXAMPLE_NAMESPACE]
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(disclosed_names, EXAMPLE_ATTRIBUTES);
+    }
+
+    #[tokio::test]
+    async fn test_proximity_disclosure_terminated() {
+        let (holder_session, reader_session) = start_sessions(MockMdocDataSource::default()).await;
+
+        holder_session.terminate().await.unwrap();
+
+        let error = reader_session
+            .receive_response(
+                &IsoCertTimeGenerator,
+                &ValidityPolicy::default(),
+                Examples::iaca_trust_anchors(),
+                &[],
+            )
+            .await
+            .unwrap_err();
+        assert_matches!(
+            error,
+            Error::Proximity(ProximityError::SessionStatus(SessionStatus::Termination))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_proximity_disclosure_missing_attributes() {
+        let (holder_session, _reader_session) = start_sessions(MockMdocDataSource::new()).await;
+
+        let missing_attributes = match holder_session {
+            ProximityDisclosureSession::MissingAttributes(session) => session,
+            _ => panic!("expected missing attributes"),
+        };
+        assert_eq!(
+            missing_attributes.missing_attributes()[0].len(),
+            EXAMPLE_ATTRIBUTES.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_proximity_session_establishment_decoding_error() {
+        let (mut reader_transport, holder_transport) = LoopbackTransport::new_pair();
+        let (device_engagement, device_privkey) = DeviceEngagement::new_proximity_device_engagement().unwrap();
+
+        reader_transport.send(b"not cbor".to_vec()).await.unwrap();
+
+        let error = ProximityDisclosureSession::<_, String>::start(
+            holder_transport,
+            &device_engagement,
+            device_privkey,
+            &MockMdocDataSource::default(),
+            &[],
+            &[],
+        )
+        .await
+        .unwrap_err();
+        assert_matches!(error, Error::Cbor(_));
+
+        let response: SessionData = receive_message(&mut reader_transport).await.unwrap();
+        assert_eq!(response.status, Some(SessionStatus::DecodingError));
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, Utc};
+use p256::{ecdsa::VerifyingKey, PublicKey, SecretKey};
+use rand_core::OsRng;
+use tracing::{info, warn};
+use webpki::TrustAnchor;
+
+use wallet_common::{generator::Generator, revocation_list::DerCertificateRevocationList};
+
+use crate::{
+    disclosure::{DeviceResponse, SessionData, SessionEstablishment},
+    engagement::{DeviceEngagement, SessionTranscript},
+    errors::{Error, Result},
+    server_keys::KeyPair,
+    utils::{
+        cose::CoseKey,
+        crypto::{SessionKey, SessionKeyUser},
+        serialization::{CborError, TaggedBytes},
+    },
+    verifier::{DisclosedAttributes, ItemsRequests, ValidityPolicy},
+};
+
+use super::{receive_message, send_message, ProximityError, Transport};
+
+/// The reader side of a proximity session, which is started after scanning the [`DeviceEngagement`] of the holder.
+#[derive(Debug)]
+pub struct ReaderProximitySession<T> {
+    transport: T,
+    items_requests: ItemsRequests,
+    ephemeral_privkey: SecretKey,
+    session_transcript: SessionTranscript,
+    device_key: SessionKey,
+}
+
+impl<T> ReaderProximitySession<T>
+where
+    T: Transport,
+{
+    /// Send a [`SessionEstablishment`] containing the encrypted [`DeviceRequest`](crate::DeviceRequest)
+    /// for `items_requests` to the holder, signed using `private_key` for reader authentication.
+    pub async fn start(
+        mut transport: T,
+        device_engagement: &DeviceEngagement,
+        items_requests: ItemsRequests,
+        private_key: &KeyPair,
+    ) -> Result<Self> {
+        info!("start proximity reader session");
+
+        let device_public_key: PublicKey = device_engagement
+            .0
+            .security
+            .as_ref()
+            .ok_or(ProximityError::DeviceEngagementSecurityMissing)?
+            .try_into()?;
+
+        // Generate our ephemeral key and derive the session transcript and keys in both directions from it.
+        let ephemeral_privkey = SecretKey::random(&mut OsRng);
+        let e_reader_key = CoseKey::try_from(&VerifyingKey::from(ephemeral_privkey.public_key()))?;
+        let session_transcript = SessionTranscript::new_qr(device_engagement, TaggedBytes(e_reader_key.clone()));
+
+        let reader_key = SessionKey::new(
+            &ephemeral_privkey,
+            &device_public_key,
+            &session_transcript,
+            SessionKeyUser::Reader,
+        )?;
+        let device_key = SessionKey::new(
+            &ephemeral_privkey,
+            &device_public_key,
+            &session_transcript,
+            SessionKeyUser::Device,
+        )?;
+
+        let device_request = items_requests
+            .to_device_request(&session_transcript, None, private_key)
+            .await?;
+        let session_data = SessionData::serialize_and_encrypt(&device_request, &reader_key)?;
+        let session_establishment = SessionEstablishment {
+            e_reader_key: TaggedBytes(e_reader_key),
+            // This unwrap is safe, as encrypted session data always contains data.
+            data: session_data.data.unwrap(),
+        };
+
+        send_message(&mut transport, &session_establishment).await?;
+
+        let session = ReaderProximitySession {
+            transport,
+            items_requests,
+            ephemeral_privkey,
+            session_transcript,
+            device_key,
+        };
+
+        Ok(session)
+    }
+
+    /// Wait for the [`DeviceResponse`] of the holder, verify it against `validity_policy` and end the session.
+    pub async fn receive_response(
+        mut self,
+        time: &impl Generator<DateTime<Utc>>,
+        validity_policy: &ValidityPolicy,
+        trust_anchors: &[TrustAnchor<'_>],
+        crls: &[DerCertificateRevocationList],
+    ) -> Result<DisclosedAttributes> {
+        info!("receive device response over proximity transport");
+
+        let session_data: SessionData = receive_message(&mut self.transport).await?;
+
+        // The holder may have ended the session, e.g. because the user declined.
+        if let Some(status) = session_data.status {
+            return Err(ProximityError::SessionStatus(status).into());
+        }
+
+        let result = self.verify_response(&session_data, time, validity_policy, trust_anchors, crls);
+
+        // End the session, reporting back if we could not decode the response.
+        let response = match result {
+            Err(Error::Cbor(CborError::Deserialization(_))) => SessionData::new_decoding_error(),
+            Err(Error::Crypto(_)) => SessionData::new_encryption_error(),
+            _ => SessionData::new_termination(),
+        };
+        if let Err(error) = &result {
+            warn!("verifying device response failed: {error}");
+        }
+
+        send_message(&mut self.transport, &response).await?;
+
+        result
+    }
+
+    fn verify_response(
+        &self,
+        session_data: &SessionData,
+        time: &impl Generator<DateTime<Utc>>,
+        validity_policy: &ValidityPolicy,
+        trust_anchors: &[TrustAnchor<'_>],
+        crls: &[DerCertificateRevocationList],
+    ) -> Result<DisclosedAttributes> {
+        let device_response: DeviceResponse = session_data.decrypt_and_deserialize(&self.device_key)?;
+
+        let disclosed_attributes = device_response.verify(
+            Some(&self.ephemeral_privkey),
+            &self.session_transcript,
+            time,
+            validity_policy,
+            trust_anchors,
+            crls,
+        )?;
+        self.items_requests.match_against_response(&device_response)?;
+
+        Ok(disclosed_attributes)
+    }
+}
@@ -1,7 +1,8 @@
 use std::fmt::{Debug, Formatter};
 
+use indexmap::IndexMap;
 use p256::{
-    ecdsa::{Signature, SigningKey},
+    ecdsa::{Signature, SigningKey, VerifyingKey},
     pkcs8::DecodePrivateKey,
 };
 
@@ -9,8 +10,10 @@ use wallet_common::keys::{EcdsaKey, SecureEcdsaKey};
 
 use crate::{utils::x509::Certificate, Result};
 
-pub struct KeyPair {
-    private_key: SigningKey,
+/// A private key along with its certificate, used by servers to sign. The private key defaults to a [`SigningKey`],
+/// but can be any [`EcdsaKey`], such as a key that resides in an HSM.
+pub struct KeyPair<S = SigningKey> {
+    private_key: S,
     certificate: Certificate,
 }
 
@@ -22,14 +25,24 @@ pub enum KeysError {
     KeyGeneration(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
 }
 
-impl KeyPair {
-    pub fn new(private_key: SigningKey, certificate: Certificate) -> KeyPair {
+impl<S> KeyPair<S> {
+    pub fn new(private_key: S, certificate: Certificate) -> KeyPair<S> {
         KeyPair {
             private_key,
             certificate,
         }
     }
 
+    pub fn private_key(&self) -> &S {
+        &self.private_key
+    }
+
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+}
+
+impl KeyPair {
     pub fn from_der(private_key: &[u8], cert: &[u8]) -> Result<KeyPair> {
         let key = Self::new(
             SigningKey::from_pkcs8_der(private_key).map_err(KeysError::DerParsing)?,
@@ -37,17 +50,9 @@ impl KeyPair {
         );
         Ok(key)
     }
-
-    pub fn private_key(&self) -> &SigningKey {
-        &self.private_key
-    }
-
-    pub fn certificate(&self) -> &Certificate {
-        &self.certificate
-    }
 }
 
-impl Debug for KeyPair {
+impl<S> Debug for KeyPair<S> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("KeyPair")
             .field("certificate", &self.certificate)
@@ -55,43 +60,68 @@ impl Debug for KeyPair {
     }
 }
 
-impl From<KeyPair> for Certificate {
-    fn from(source: KeyPair) -> Certificate {
+impl<S> From<KeyPair<S>> for Certificate {
+    fn from(source: KeyPair<S>) -> Certificate {
         source.certificate
     }
 }
 
-impl EcdsaKey for KeyPair {
-    type Error = p256::ecdsa::Error;
+impl<S: EcdsaKey> EcdsaKey for KeyPair<S> {
+    type Error = S::Error;
 
-    async fn verifying_key(&self) -> std::result::Result<p256::ecdsa::VerifyingKey, Self::Error> {
-        Ok(*self.private_key.verifying_key())
+    async fn verifying_key(&self) -> std::result::Result<VerifyingKey, Self::Error> {
+        self.private_key.verifying_key().await
     }
 
     async fn try_sign(&self, msg: &[u8]) -> std::result::Result<Signature, Self::Error> {
-        p256::ecdsa::signature::Signer::try_sign(&self.private_key, msg)
+        self.private_key.try_sign(msg).await
     }
 }
-impl SecureEcdsaKey for KeyPair {}
+
+// The private key of a server is considered to be kept secure, regardless of where it is stored.
+impl<S: EcdsaKey> SecureEcdsaKey for KeyPair<S> {}
 
 pub trait KeyRing {
-    fn key_pair(&self, id: &str) -> Option<&KeyPair>;
+    type Key: EcdsaKey;
+
+    /// The key pair that should currently be used to sign for `id`.
+    fn key_pair(&self, id: &str) -> Option<&KeyPair<Self::Key>>;
+
     fn contains_key_pair(&self, id: &str) -> bool {
         self.key_pair(id).is_some()
     }
+
+    /// The key pair for `id` that has the specified certificate, if that key pair may currently be used to sign.
+    /// This allows signing with the key pair that was announced earlier, even if the key ring has since rolled over
+    /// to a new key pair.
+    fn key_pair_for_certificate(&self, id: &str, certificate: &Certificate) -> Option<&KeyPair<Self::Key>> {
+        self.key_pair(id)
+            .filter(|key_pair| key_pair.certificate() == certificate)
+    }
+
+    /// All certificates per `id` that are currently used or will be used to sign, which may be published.
+    fn certificates(&self) -> IndexMap<String, Vec<Certificate>>;
 }
 
 /// An implementation of [`KeyRing`] containing a single key.
 pub struct SingleKeyRing(pub KeyPair);
 
 impl KeyRing for SingleKeyRing {
+    type Key = SigningKey;
+
     fn key_pair(&self, _: &str) -> Option<&KeyPair> {
         Some(&self.0)
     }
+
+    /// As the key is used for any `id`, no certificates can be published per `id`.
+    fn certificates(&self) -> IndexMap<String, Vec<Certificate>> {
+        IndexMap::new()
+    }
 }
 
 #[cfg(any(test, feature = "generate"))]
 mod generate {
+    use chrono::{DateTime, Utc};
     use p256::{
         ecdsa::SigningKey,
         pkcs8::{
@@ -100,15 +130,19 @@ mod generate {
         },
     };
     use rcgen::{
-        BasicConstraints, Certificate as RcgenCertificate, CertificateParams, CustomExtension, DnType, IsCa, SanType,
+        BasicConstraints, Certificate as RcgenCertificate, CertificateParams, CertificateRevocationList,
+        CertificateRevocationListParams, CrlDistributionPoint, CustomExtension, DnType, IsCa, KeyIdMethod,
+        RevokedCertParams, SanType, SerialNumber,
     };
     use time::OffsetDateTime;
 
+    use wallet_common::revocation_list::DerCertificateRevocationList;
+
     use crate::{
         server_keys::KeyPair,
         utils::x509::{
-            CertificateConfiguration, CertificateError, CertificateType, CertificateUsage, MdocCertificateExtension,
-            OID_EXT_KEY_USAGE,
+            Certificate, CertificateConfiguration, CertificateError, CertificateType, CertificateUsage,
+            MdocCertificateExtension, OID_EXT_KEY_USAGE,
         },
     };
 
@@ -145,6 +179,49 @@ mod generate {
             let cert_unsigned =
                 RcgenCertificate::from_params(cert_params).map_err(CertificateError::GeneratingFailed)?;
 
+            let certificate = cert_unsigned.serialize_der_with_signer(&self.to_rcgen_ca()?)?;
+            let private_key = Self::rcgen_cert_privkey(&cert_unsigned)?;
+
+            Ok(KeyPair::new(private_key, certificate.into()))
+        }
+
+        /// Generate a Certificate Revocation List (CRL) signed with this CA, in which the specified certificates
+        /// are revoked as of `this_update`.
+        pub fn generate_crl(
+            &self,
+            revoked: &[Certificate],
+            this_update: DateTime<Utc>,
+            next_update: DateTime<Utc>,
+        ) -> Result<DerCertificateRevocationList, CertificateError> {
+            let this_update = to_offset_date_time(this_update);
+            let revoked_certs = revoked
+                .iter()
+                .map(|certificate| {
+                    Ok(RevokedCertParams {
+                        serial_number: SerialNumber::from(certificate.serial_number()?),
+                        revocation_time: this_update,
+                        reason_code: None,
+                        invalidity_date: None,
+                    })
+                })
+                .collect::<Result<_, CertificateError>>()?;
+
+            let crl = CertificateRevocationList::from_params(CertificateRevocationListParams {
+                this_update,
+                next_update: to_offset_date_time(next_update),
+                // CRL numbers should increase monotonically, which the issuance time does.
+                crl_number: SerialNumber::from(this_update.unix_timestamp().to_be_bytes().to_vec()),
+                issuing_distribution_point: None,
+                revoked_certs,
+                alg: &rcgen::PKCS_ECDSA_P256_SHA256,
+                key_identifier_method: KeyIdMethod::Sha256,
+            })?;
+            let der = crl.serialize_der_with_signer(&self.to_rcgen_ca()?)?;
+
+            DerCertificateRevocationList::from_der(der).map_err(CertificateError::RevocationListParsing)
+        }
+
+        fn to_rcgen_ca(&self) -> Result<RcgenCertificate, CertificateError> {
             let ca_keypair = rcgen::KeyPair::from_der(
                 &self
                     .private_key()
@@ -157,10 +234,7 @@ mod generate {
                 ca_keypair,
             )?)?;
 
-            let certificate = cert_unsigned.serialize_der_with_signer(&ca)?;
-            let private_key = Self::rcgen_cert_privkey(&cert_unsigned)?;
-
-            Ok(KeyPair::new(private_key, certificate.into()))
+            Ok(ca)
         }
 
         fn rcgen_cert_privkey(cert: &RcgenCertificate) -> Result<SigningKey, CertificateError> {
@@ -169,6 +243,10 @@ mod generate {
         }
     }
 
+    fn to_offset_date_time(time: DateTime<Utc>) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(time.timestamp()).unwrap()
+    }
+
     impl From<CertificateConfiguration> for CertificateParams {
         fn from(source: CertificateConfiguration) -> Self {
             let mut result = CertificateParams::default();
@@ -178,6 +256,11 @@ mod generate {
             if let Some(not_after) = source.not_after.and_then(|ts| ts.timestamp_nanos_opt()) {
                 result.not_after = OffsetDateTime::from_unix_timestamp_nanos(not_after as i128).unwrap();
             }
+            if !source.crl_distribution_points.is_empty() {
+                result.crl_distribution_points = vec![CrlDistributionPoint {
+                    uris: source.crl_distribution_points,
+                }];
+            }
             result
         }
     }
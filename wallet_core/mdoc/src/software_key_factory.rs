@@ -1,12 +1,16 @@
 use std::{collections::HashMap, iter};
 
 use futures::future;
-use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use p256::{
+    ecdh,
+    ecdsa::{Signature, SigningKey, VerifyingKey},
+    PublicKey,
+};
 use parking_lot::Mutex;
 
 use rand_core::OsRng;
 use wallet_common::{
-    keys::{software::SoftwareEcdsaKey, EcdsaKey},
+    keys::{software::SoftwareEcdsaKey, EcdsaKey, WithIdentifier},
     utils,
 };
 
@@ -136,6 +140,29 @@ impl KeyFactory for SoftwareKeyFactory {
 
         Ok(result)
     }
+
+    async fn diffie_hellman_with_existing_keys(
+        &self,
+        keys: Vec<&Self::Key>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let signing_keys = self.signing_keys.lock();
+
+        let shared_secrets = keys
+            .into_iter()
+            .map(|key| {
+                let signing_key = signing_keys
+                    .get(key.identifier())
+                    .expect("called diffie_hellman_with_existing_keys() with unknown key");
+
+                ecdh::diffie_hellman(signing_key.as_nonzero_scalar(), public_key.as_affine())
+                    .raw_secret_bytes()
+                    .to_vec()
+            })
+            .collect();
+
+        Ok(shared_secrets)
+    }
 }
 
 impl Default for SoftwareKeyFactory {
@@ -182,6 +182,7 @@ impl TestDocument {
             issuer_signed,
             &TimeGenerator,
             &[(ca.certificate().try_into().unwrap())],
+            &[],
         )
         .unwrap()
     }
@@ -228,6 +229,7 @@ impl From<TestDocument> for ItemsRequest {
                 )
             })),
             request_info: None,
+            device_name_spaces: None,
         }
     }
 }
@@ -84,10 +84,13 @@ pub struct SharingPolicy {
     pub intent_to_share: bool,
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[skip_serializing_none]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeletionPolicy {
     pub deleteable: bool,
+    /// The endpoint to which the wallet can send a request to delete the attributes disclosed in a session.
+    pub deletion_request_url: Option<Url>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -144,7 +147,10 @@ pub mod mock {
                     max_duration_in_minutes: Some(60 * 24 * 365),
                 },
                 sharing_policy: SharingPolicy { intent_to_share: true },
-                deletion_policy: DeletionPolicy { deleteable: true },
+                deletion_policy: DeletionPolicy {
+                    deleteable: true,
+                    deletion_request_url: Some("https://example.com/disclosure/deletion_request".parse().unwrap()),
+                },
                 organization,
                 request_origin_base_url: "https://example.com/".parse().unwrap(),
                 attributes: Default::default(),
@@ -404,6 +410,7 @@ mod tests {
             doc_type: doc_type.to_owned(),
             name_spaces,
             request_info: None,
+            device_name_spaces: None,
         }
     }
 
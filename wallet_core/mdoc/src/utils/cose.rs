@@ -6,14 +6,17 @@ use chrono::{DateTime, Utc};
 use ciborium::value::Value;
 use coset::{
     iana, sig_structure_data, CoseMac0, CoseMac0Builder, CoseSign1, CoseSign1Builder, Header, HeaderBuilder, Label,
-    ProtectedHeader, SignatureContext,
+    ProtectedHeader, RegisteredLabelWithPrivate, SignatureContext,
 };
 use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
-use ring::hmac;
+use ring::{
+    hmac,
+    signature::{UnparsedPublicKey, ECDSA_P384_SHA384_FIXED, ED25519},
+};
 use serde::{de::DeserializeOwned, Serialize};
 use webpki::TrustAnchor;
 
-use wallet_common::{generator::Generator, keys::SecureEcdsaKey};
+use wallet_common::{generator::Generator, keys::SecureEcdsaKey, revocation_list::DerCertificateRevocationList};
 
 use crate::{
     server_keys::KeysError,
@@ -43,6 +46,13 @@ pub enum CoseError {
     EcdsaSignatureParsingFailed(p256::ecdsa::Error),
     #[error("ECDSA signature verification failed: {0}")]
     EcdsaSignatureVerificationFailed(p256::ecdsa::Error),
+    #[error("signature verification failed")]
+    SignatureVerificationFailed,
+    #[error("signature algorithm does not match key: expected {expected:?}, found {found:?}")]
+    AlgorithmMismatch {
+        expected: iana::Algorithm,
+        found: Option<RegisteredLabelWithPrivate<iana::Algorithm>>,
+    },
     #[error("MAC verification failed")]
     MacVerificationFailed,
     #[error(transparent)]
@@ -55,26 +65,99 @@ pub enum CoseError {
     Signing(Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error("no signature received")]
     SignatureMissing(),
+    #[error("key agreement failed: {0}")]
+    KeyAgreement(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+/// Public key with which a [`CoseSign1`] can be verified, for each of the supported COSE signature algorithms.
+/// Apart from P-256, which we also use for signing, these are only supported for verification, so that we can
+/// accept mdocs from issuers using other algorithms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoseVerifyingKey {
+    /// ES256: ECDSA using P-256 and SHA-256.
+    P256(VerifyingKey),
+    /// ES384: ECDSA using P-384 and SHA-384, containing the uncompressed SEC1 encoding of the public key.
+    P384(Vec<u8>),
+    /// ES512: ECDSA using P-521 and SHA-512, containing the uncompressed SEC1 encoding of the public key.
+    P521(Vec<u8>),
+    /// EdDSA using Ed25519, containing the 32-byte public key.
+    Ed25519(Vec<u8>),
+}
+
+impl CoseVerifyingKey {
+    /// The COSE algorithm that signatures made with the private key of this public key use.
+    pub fn algorithm(&self) -> iana::Algorithm {
+        match self {
+            CoseVerifyingKey::P256(_) => iana::Algorithm::ES256,
+            CoseVerifyingKey::P384(_) => iana::Algorithm::ES384,
+            CoseVerifyingKey::P521(_) => iana::Algorithm::ES512,
+            CoseVerifyingKey::Ed25519(_) => iana::Algorithm::EdDSA,
+        }
+    }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<(), CoseError> {
+        match self {
+            CoseVerifyingKey::P256(key) => {
+                let sig = &Signature::try_from(signature).map_err(CoseError::EcdsaSignatureParsingFailed)?;
+                key.verify(data, sig)
+                    .map_err(CoseError::EcdsaSignatureVerificationFailed)
+            }
+            CoseVerifyingKey::P384(key) => UnparsedPublicKey::new(&ECDSA_P384_SHA384_FIXED, key)
+                .verify(data, signature)
+                .map_err(|_| CoseError::SignatureVerificationFailed),
+            // P-521 is not supported by `ring`, so `p521` is used instead.
+            CoseVerifyingKey::P521(key) => {
+                let key = p521::ecdsa::VerifyingKey::from_sec1_bytes(key)
+                    .map_err(|_| CoseError::SignatureVerificationFailed)?;
+                let sig = p521::ecdsa::Signature::from_slice(signature)
+                    .map_err(|_| CoseError::SignatureVerificationFailed)?;
+                key.verify(data, &sig)
+                    .map_err(|_| CoseError::SignatureVerificationFailed)
+            }
+            CoseVerifyingKey::Ed25519(key) => UnparsedPublicKey::new(&ED25519, key)
+                .verify(data, signature)
+                .map_err(|_| CoseError::SignatureVerificationFailed),
+        }
+    }
+}
+
+impl From<VerifyingKey> for CoseVerifyingKey {
+    fn from(key: VerifyingKey) -> Self {
+        CoseVerifyingKey::P256(key)
+    }
+}
+
+impl From<&VerifyingKey> for CoseVerifyingKey {
+    fn from(key: &VerifyingKey) -> Self {
+        CoseVerifyingKey::P256(*key)
+    }
 }
 
 impl Cose for CoseSign1 {
-    type Key = VerifyingKey;
+    type Key = CoseVerifyingKey;
     fn payload(&self) -> &Option<Vec<u8>> {
         &self.payload
     }
     fn unprotected(&self) -> &Header {
         &self.unprotected
     }
-    fn verify(&self, key: &VerifyingKey) -> Result<(), CoseError> {
+    fn verify(&self, key: &CoseVerifyingKey) -> Result<(), CoseError> {
+        // The algorithm in the protected header has to match the key, so that a signature can not be
+        // verified using an algorithm other than the one the signer intended.
+        let expected = key.algorithm();
+        if self.protected.header.alg != Some(RegisteredLabelWithPrivate::Assigned(expected)) {
+            return Err(CoseError::AlgorithmMismatch {
+                expected,
+                found: self.protected.header.alg.clone(),
+            });
+        }
+
         self.verify_signature(b"", |sig, data| {
             if self.payload.is_none() {
                 return Err(CoseError::MissingPayload);
             }
 
-            let sig = &Signature::try_from(sig).map_err(CoseError::EcdsaSignatureParsingFailed)?;
             key.verify(data, sig)
-                .map_err(CoseError::EcdsaSignatureVerificationFailed)?;
-            Ok(())
         })
     }
 }
@@ -220,12 +303,13 @@ impl<T> MdocCose<CoseSign1, T> {
     }
 
     /// Verify the COSE against the specified trust anchors, using the certificate(s) in the `x5chain` COSE header
-    /// as intermediate certificates.
+    /// as intermediate certificates, and checking that none of them is revoked by the specified CRLs.
     pub fn verify_against_trust_anchors(
         &self,
         usage: CertificateUsage,
         time: &impl Generator<DateTime<Utc>>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<T, CoseError>
     where
         T: DeserializeOwned,
@@ -233,11 +317,11 @@ impl<T> MdocCose<CoseSign1, T> {
         let cert = self.signing_cert()?;
 
         // Verify the certificate against the trusted IACAs
-        cert.verify(usage, &[], time, trust_anchors)
+        cert.verify(usage, &[], time, trust_anchors, crls)
             .map_err(CoseError::Certificate)?;
 
         // Grab the certificate's public key and verify the Cose
-        let issuer_pk = cert.public_key().map_err(CoseError::Certificate)?;
+        let issuer_pk = cert.cose_verifying_key().map_err(CoseError::Certificate)?;
         self.verify_and_parse(&issuer_pk)
     }
 }
@@ -454,9 +538,20 @@ impl coset::AsCborValue for CoseKey {
 
 #[cfg(test)]
 mod tests {
-    use coset::{Header, HeaderBuilder, Label};
+    use coset::{iana, CoseSign1Builder, Header, HeaderBuilder, Label};
+    use hex_literal::hex;
     use p256::ecdsa::SigningKey;
+    use p521::ecdsa::signature::RandomizedSigner;
     use rand_core::OsRng;
+    use rcgen::{
+        BasicConstraints, Certificate as RcgenCertificate, CertificateParams, DnType, IsCa, SignatureAlgorithm,
+        PKCS_ECDSA_P384_SHA384, PKCS_ED25519,
+    };
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, Ed25519KeyPair, KeyPair as _, ECDSA_P384_SHA384_FIXED_SIGNING},
+    };
+    use rstest::rstest;
     use serde::{Deserialize, Serialize};
 
     use wallet_common::generator::TimeGenerator;
@@ -466,11 +561,12 @@ mod tests {
         utils::{
             cose::{self, CoseError},
             issuer_auth::IssuerRegistration,
-            x509::CertificateUsage,
+            serialization::cbor_serialize,
+            x509::{Certificate, CertificateError, CertificateType, CertificateUsage},
         },
     };
 
-    use super::{ClonePayload, MdocCose};
+    use super::{ClonePayload, CoseVerifyingKey, MdocCose};
 
     #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
     struct ToyMessage {
@@ -492,9 +588,10 @@ mod tests {
         let payload = ToyMessage::default();
         let cose = MdocCose::sign(&payload, Header::default(), &key, true).await.unwrap();
 
-        cose.verify(key.verifying_key()).unwrap();
+        let verifying_key = key.verifying_key().into();
+        cose.verify(&verifying_key).unwrap();
 
-        let verified = cose.verify_and_parse(key.verifying_key()).unwrap();
+        let verified = cose.verify_and_parse(&verifying_key).unwrap();
         assert_eq!(payload, verified);
 
         let parsed_not_verified = cose.dangerous_parse_unverified().unwrap();
@@ -506,11 +603,12 @@ mod tests {
         let key = SigningKey::random(&mut OsRng);
         let payload = ToyMessage::default();
         let mut cose = MdocCose::sign(&payload, Header::default(), &key, true).await.unwrap();
+        let verifying_key = key.verifying_key().into();
 
         // Verification should fail if the signature is changed
         cose.0.signature[0] = !cose.0.signature[0]; // invert bits
         assert!(matches!(
-            cose.verify(key.verifying_key()),
+            cose.verify(&verifying_key),
             Err(CoseError::EcdsaSignatureVerificationFailed(_))
         ));
 
@@ -518,7 +616,7 @@ mod tests {
         let len = cose.0.signature.len();
         cose.0.signature.remove(len - 1);
         assert!(matches!(
-            cose.verify(key.verifying_key()),
+            cose.verify(&verifying_key),
             Err(CoseError::EcdsaSignatureParsingFailed(_))
         ));
     }
@@ -576,7 +674,7 @@ mod tests {
         assert_eq!(issuer_key_pair.certificate().as_bytes(), header_cert.as_bytes());
 
         let trust_anchor = ca.certificate().try_into().unwrap();
-        cose.verify_against_trust_anchors(CertificateUsage::Mdl, &TimeGenerator, &[trust_anchor])
+        cose.verify_against_trust_anchors(CertificateUsage::Mdl, &TimeGenerator, &[trust_anchor], &[])
             .unwrap();
     }
 
@@ -594,7 +692,187 @@ mod tests {
 
         // Adding the payload should result in a cose containing our payload again
         let with_payload = without_payload.clone_with_payload(payload_bts.clone());
-        let verified = with_payload.verify_and_parse(key.verifying_key()).unwrap();
+        let verified = with_payload.verify_and_parse(&key.verifying_key().into()).unwrap();
         assert_eq!(payload, verified);
     }
+
+    #[tokio::test]
+    async fn cose_algorithm_mismatch() {
+        let key = SigningKey::random(&mut OsRng);
+        let payload = ToyMessage::default();
+        let mut cose = MdocCose::sign(&payload, Header::default(), &key, true).await.unwrap();
+
+        // Verification should fail if the protected header claims another algorithm than that of the key
+        cose.0.protected.header.alg = Some(coset::RegisteredLabelWithPrivate::Assigned(iana::Algorithm::ES384));
+        assert!(matches!(
+            cose.verify(&key.verifying_key().into()),
+            Err(CoseError::AlgorithmMismatch {
+                expected: iana::Algorithm::ES256,
+                ..
+            })
+        ));
+    }
+
+    type SignFn = Box<dyn Fn(&[u8]) -> Vec<u8>>;
+
+    /// Sign `payload` into a COSE using `alg`, with a certificate chain that uses the same algorithm throughout,
+    /// as an issuer using a curve other than P-256 would.
+    fn sign_with_foreign_issuer(
+        payload: &ToyMessage,
+        alg: &'static SignatureAlgorithm,
+    ) -> (MdocCose<coset::CoseSign1, ToyMessage>, Certificate) {
+        let mut ca_params = CertificateParams::new([]);
+        ca_params.alg = alg;
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+        ca_params.distinguished_name.push(DnType::CommonName, "ca.example.com");
+        let ca = RcgenCertificate::from_params(ca_params).unwrap();
+
+        let mut issuer_params = CertificateParams::new(["cert.example.com".to_string()]);
+        issuer_params.alg = alg;
+        issuer_params
+            .distinguished_name
+            .push(DnType::CommonName, "cert.example.com");
+        issuer_params.custom_extensions = CertificateType::from(IssuerRegistration::new_mock())
+            .to_custom_exts()
+            .unwrap();
+        let issuer = RcgenCertificate::from_params(issuer_params).unwrap();
+        let issuer_cert = Certificate::from(issuer.serialize_der_with_signer(&ca).unwrap());
+
+        let private_key = issuer.get_key_pair().serialized_der();
+        let (cose_alg, sign): (_, SignFn) = if alg == &PKCS_ED25519 {
+            let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(private_key).unwrap();
+            (
+                iana::Algorithm::EdDSA,
+                Box::new(move |data| key_pair.sign(data).as_ref().to_vec()),
+            )
+        } else {
+            let rng = SystemRandom::new();
+            let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P384_SHA384_FIXED_SIGNING, private_key, &rng).unwrap();
+            assert_eq!(key_pair.public_key().as_ref()[0], 0x04);
+            (
+                iana::Algorithm::ES384,
+                Box::new(move |data| key_pair.sign(&rng, data).unwrap().as_ref().to_vec()),
+            )
+        };
+
+        let cose = CoseSign1Builder::new()
+            .protected(HeaderBuilder::new().algorithm(cose_alg).build())
+            .unprotected(cose::new_certificate_header(&issuer_cert))
+            .payload(cbor_serialize(payload).unwrap())
+            .create_signature(&[], |data| sign(data))
+            .build();
+
+        (cose.into(), Certificate::from(ca.serialize_der().unwrap()))
+    }
+
+    #[rstest]
+    #[case(&PKCS_ECDSA_P384_SHA384)]
+    #[case(&PKCS_ED25519)]
+    fn cose_with_foreign_issuer_certificate(#[case] alg: &'static SignatureAlgorithm) {
+        let payload = ToyMessage::default();
+        let (cose, ca_cert) = sign_with_foreign_issuer(&payload, alg);
+
+        let verifying_key = cose.signing_cert().unwrap().cose_verifying_key().unwrap();
+        if alg == &PKCS_ED25519 {
+            assert!(matches!(verifying_key, CoseVerifyingKey::Ed25519(_)));
+        } else {
+            assert!(matches!(verifying_key, CoseVerifyingKey::P384(_)));
+        }
+
+        let trust_anchor = (&ca_cert).try_into().unwrap();
+        let verified = cose
+            .verify_against_trust_anchors(CertificateUsage::Mdl, &TimeGenerator, &[trust_anchor], &[])
+            .unwrap();
+        assert_eq!(payload, verified);
+
+        // Verification should fail if the signature is changed
+        let mut invalid_cose = cose.clone();
+        invalid_cose.0.signature[0] = !invalid_cose.0.signature[0];
+        assert!(matches!(
+            invalid_cose.verify(&verifying_key),
+            Err(CoseError::SignatureVerificationFailed)
+        ));
+    }
+
+    /// A P-521 CA certificate, which `rcgen` cannot generate.
+    const P521_CA_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIICIzCCAYWgAwIBAgIUPU1BLmpVbqT4546bBs79R1nssWcwCgYIKoZIzj0EAwQw
+GTEXMBUGA1UEAwwOY2EuZXhhbXBsZS5jb20wIBcNMjQwMTAxMDAwMDAwWhgPMjEy
+NDAxMDEwMDAwMDBaMBkxFzAVBgNVBAMMDmNhLmV4YW1wbGUuY29tMIGbMBAGByqG
+SM49AgEGBSuBBAAjA4GGAAQBiY8izBe0q5eoGjxQLlBbGVlNlm77NhK3a8M+HH31
+rb3FdfvEdfemK2MSLDOUaxG7ubd02lNhMt19VkqAnh0O7oIAu0asEGNHIxyyIT19
+4Ur64Fs75ibieUoNxu3sPsPYa/lSZ5QBmvX8H7twLP8G2r8CYNQFO71MEY/Mdfox
+QsOXfj+jZjBkMB0GA1UdDgQWBBSPw4Vd3jWGdEY2hSqJ/yzgbNyfrDAfBgNVHSME
+GDAWgBSPw4Vd3jWGdEY2hSqJ/yzgbNyfrDASBgNVHRMBAf8ECDAGAQH/AgEAMA4G
+A1UdDwEB/wQEAwIBBjAKBggqhkjOPQQDBAOBiwAwgYcCQgCuGVR8L5w9jy9VFkZ+
+6o82aGjV7eN5wBvzNNShgetd23RRDOhbvU2iYOjo5H8Zz4hBeWHHpxfNRipBEu5R
+bcxwlQJBWyum+KF67q9mrAjYzwOedebn9yv/IsP/Q4lbOhcsYjyt3Qw+YfhVjSxt
+zkfn73Iys0G3Hg7acLY3WKXcQ0y3Z6o=
+-----END CERTIFICATE-----";
+
+    /// A P-521 mdoc issuer certificate, signed by [`P521_CA_CERTIFICATE`] using ecdsa-with-SHA512.
+    const P521_ISSUER_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIICNDCCAZWgAwIBAgIUJThQQ8zWmZOPwDbc6NglJPughB4wCgYIKoZIzj0EAwQw
+GTEXMBUGA1UEAwwOY2EuZXhhbXBsZS5jb20wIBcNMjQwMTAxMDAwMDAwWhgPMjEy
+NDAxMDEwMDAwMDBaMBsxGTAXBgNVBAMMEGNlcnQuZXhhbXBsZS5jb20wgZswEAYH
+KoZIzj0CAQYFK4EEACMDgYYABAHzw7s2nEGLoEb1y0v+m0Llwg7iE7lWfraV3Tul
+u2d194667xHb+k1tEjomJI6oQYcpQ9lxGVqc6aFTuuBR1oGRFQA487riOVBXBMKd
+VRYBpifsL9TkOaMfT+6XAcW3bWCfVKRvXg+TkH5uUkjqTQ0hCLKWAItD3ZrStRyQ
+hENx4+DNz6N0MHIwDAYDVR0TAQH/BAIwADAOBgNVHQ8BAf8EBAMCB4AwEgYDVR0l
+BAswCQYHKIGMXQUBAjAdBgNVHQ4EFgQUrVuxl0aZ9LCmNlgY8AQDKLEedvMwHwYD
+VR0jBBgwFoAUj8OFXd41hnRGNoUqif8s4Gzcn6wwCgYIKoZIzj0EAwQDgYwAMIGI
+AkIBVGd+qrm5Rpj0e5Oz9DXkIhvibl/5yMQpmhd7rGD0Nv+YAq/IOXkzmmH9Nvzh
+/4bJEpVd5T/TN/ygICi2N/w58+UCQgC3QWyVa4wjYBoKWzguyLocJ8nYTC+XxVij
+nCjniz5RfqLH5obCsvEPd1zz3sttFeBkKAr+64ljx/KF0ZXAHSRN+w==
+-----END CERTIFICATE-----";
+
+    /// The private key of [`P521_ISSUER_CERTIFICATE`].
+    const P521_ISSUER_PRIVATE_KEY: [u8; 66] = hex!(
+        "00aff1396416748dab88c37db96f60b3626ed2c156ade9c84be5ba1068ffcbc397167243aaf71dd7783519dc72d7384e7306b77e2722e4cbd704df4becec1f98b0c3"
+    );
+
+    #[test]
+    fn cose_with_p521_issuer_certificate() {
+        let payload = ToyMessage::default();
+        let issuer_cert = Certificate::from_pem(P521_ISSUER_CERTIFICATE).unwrap();
+        let ca_cert = Certificate::from_pem(P521_CA_CERTIFICATE).unwrap();
+
+        let signing_key = p521::ecdsa::SigningKey::from_slice(&P521_ISSUER_PRIVATE_KEY).unwrap();
+        let cose: MdocCose<coset::CoseSign1, ToyMessage> = CoseSign1Builder::new()
+            .protected(HeaderBuilder::new().algorithm(iana::Algorithm::ES512).build())
+            .unprotected(cose::new_certificate_header(&issuer_cert))
+            .payload(cbor_serialize(&payload).unwrap())
+            .create_signature(&[], |data| {
+                let signature: p521::ecdsa::Signature = signing_key.sign_with_rng(&mut OsRng, data);
+                signature.to_vec()
+            })
+            .build()
+            .into();
+
+        let verifying_key = issuer_cert.cose_verifying_key().unwrap();
+        assert!(matches!(verifying_key, CoseVerifyingKey::P521(_)));
+
+        // Both the certificate chain and the COSE itself use P-521
+        let trust_anchor = (&ca_cert).try_into().unwrap();
+        let verified = cose
+            .verify_against_trust_anchors(CertificateUsage::Mdl, &TimeGenerator, &[trust_anchor], &[])
+            .unwrap();
+        assert_eq!(payload, verified);
+
+        // Verification should fail if the signature is changed
+        let mut invalid_cose = cose.clone();
+        invalid_cose.0.signature[0] = !invalid_cose.0.signature[0];
+        assert!(matches!(
+            invalid_cose.verify(&verifying_key),
+            Err(CoseError::SignatureVerificationFailed)
+        ));
+
+        // The certificate chain should not verify against another trust anchor
+        let (_, other_ca_cert) = sign_with_foreign_issuer(&payload, &PKCS_ECDSA_P384_SHA384);
+        let other_trust_anchor = (&other_ca_cert).try_into().unwrap();
+        assert!(matches!(
+            cose.verify_against_trust_anchors(CertificateUsage::Mdl, &TimeGenerator, &[other_trust_anchor], &[]),
+            Err(CoseError::Certificate(CertificateError::Verification(_)))
+        ));
+    }
 }
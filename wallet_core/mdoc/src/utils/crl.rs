@@ -0,0 +1,279 @@
+//! Loading of Certificate Revocation Lists (CRLs), against which certificates are checked for revocation
+//! in [`Certificate::verify()`](super::x509::Certificate::verify).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use tokio::{
+    task::{self, JoinHandle},
+    time::{self, MissedTickBehavior},
+};
+use tracing::warn;
+use x509_parser::pem;
+
+use wallet_common::revocation_list::DerCertificateRevocationList;
+
+use super::x509::CertificateError;
+
+const PEM_CRL_HEADER: &str = "X509 CRL";
+
+#[derive(thiserror::Error, Debug)]
+pub enum CrlError {
+    #[error("could not read CRL file {0}: {1}")]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("could not parse CRL file {0}: {1}")]
+    Parsing(PathBuf, #[source] CertificateError),
+}
+
+/// Parse a CRL that is either DER encoded or PEM encoded with an `X509 CRL` header.
+pub fn parse_crl(bytes: &[u8]) -> Result<DerCertificateRevocationList, CertificateError> {
+    let der = match pem::parse_x509_pem(bytes) {
+        Ok((_, pem)) if pem.label == PEM_CRL_HEADER => pem.contents,
+        Ok((_, pem)) => {
+            return Err(CertificateError::UnexpectedPemHeader {
+                found: pem.label,
+                expected: PEM_CRL_HEADER.to_string(),
+            })
+        }
+        Err(_) => bytes.to_vec(),
+    };
+
+    DerCertificateRevocationList::from_der(der).map_err(CertificateError::RevocationListParsing)
+}
+
+/// Read all CRLs from the specified paths, each of which is either a CRL file or a directory containing CRL files.
+pub fn read_crls(paths: &[PathBuf]) -> Result<Vec<DerCertificateRevocationList>, CrlError> {
+    let mut crls = Vec::new();
+
+    for path in paths {
+        if path.is_dir() {
+            let mut files = fs::read_dir(path)
+                .and_then(|entries| {
+                    entries
+                        .map(|entry| entry.map(|entry| entry.path()))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .map_err(|error| CrlError::Io(path.clone(), error))?;
+            // Sort the files to make the order of the resulting CRLs deterministic.
+            files.sort();
+
+            for file in files.iter().filter(|file| file.is_file()) {
+                crls.push(read_crl(file)?);
+            }
+        } else {
+            crls.push(read_crl(path)?);
+        }
+    }
+
+    Ok(crls)
+}
+
+fn read_crl(path: &Path) -> Result<DerCertificateRevocationList, CrlError> {
+    let bytes = fs::read(path).map_err(|error| CrlError::Io(path.to_path_buf(), error))?;
+    parse_crl(&bytes).map_err(|error| CrlError::Parsing(path.to_path_buf(), error))
+}
+
+/// A set of CRLs that is read from files and kept in memory. Using [`CrlCache::start_refresh_task()`], the CRLs are
+/// periodically read from the files again in the background, so that updated CRLs can be put in place while the
+/// application is running.
+#[derive(Debug, Default)]
+pub struct CrlCache {
+    paths: Vec<PathBuf>,
+    refresh_interval: Duration,
+    crls: RwLock<Vec<DerCertificateRevocationList>>,
+}
+
+impl CrlCache {
+    /// Read the CRLs from the specified files or directories, which will be read again every `refresh_interval` once
+    /// the refresh task is started. Reading the CRLs initially has to succeed, to catch configuration errors early.
+    pub fn load(paths: Vec<PathBuf>, refresh_interval: Duration) -> Result<Self, CrlError> {
+        let cache = Self {
+            crls: RwLock::new(read_crls(&paths)?),
+            paths,
+            refresh_interval,
+        };
+
+        Ok(cache)
+    }
+
+    /// Read the CRLs from the configured files again. If this fails, the current CRLs are retained.
+    /// Note that this reads the files synchronously, so this should not be called directly from async code.
+    pub fn refresh(&self) -> Result<(), CrlError> {
+        let crls = read_crls(&self.paths)?;
+        *self.crls.write().unwrap() = crls;
+
+        Ok(())
+    }
+
+    /// Returns the current CRLs, as last read from the files.
+    pub fn crls(&self) -> Vec<DerCertificateRevocationList> {
+        self.crls.read().unwrap().clone()
+    }
+
+    /// Start a background task that refreshes the CRLs every refresh interval, reading the files on the blocking
+    /// thread pool. As failing to refresh is not a reason to stop using the previously read CRLs, a failure is logged.
+    /// No task is started if there are no files to read or if the refresh interval is zero.
+    pub fn start_refresh_task(self: Arc<Self>) -> Option<JoinHandle<()>> {
+        if self.paths.is_empty() || self.refresh_interval.is_zero() {
+            return None;
+        }
+
+        // The first tick of the interval completes immediately, so delay it as the CRLs were just read.
+        let mut interval = time::interval_at(time::Instant::now() + self.refresh_interval, self.refresh_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let task = tokio::spawn(async move {
+            loop {
+                interval.tick().await;
+
+                let cache = Arc::clone(&self);
+                match task::spawn_blocking(move || cache.refresh()).await {
+                    Ok(Ok(())) => (),
+                    Ok(Err(error)) => warn!("could not refresh CRLs, using previously read CRLs: {error}"),
+                    Err(error) => warn!("CRL refresh task failed, using previously read CRLs: {error}"),
+                }
+            }
+        });
+
+        Some(task)
+    }
+}
+
+impl From<Vec<DerCertificateRevocationList>> for CrlCache {
+    /// Create a [`CrlCache`] with a fixed set of CRLs, which is never refreshed.
+    fn from(crls: Vec<DerCertificateRevocationList>) -> Self {
+        Self {
+            crls: RwLock::new(crls),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use base64::prelude::*;
+    use chrono::{Duration as ChronoDuration, Utc};
+
+    use crate::{server_keys::KeyPair, utils::issuer_auth::IssuerRegistration};
+
+    use super::*;
+
+    fn to_pem(label: &str, der: &[u8]) -> String {
+        format!(
+            "-----BEGIN {label}-----\n{}\n-----END {label}-----\n",
+            BASE64_STANDARD.encode(der)
+        )
+    }
+
+    fn generate_crl(ca: &KeyPair, revoked: &[&KeyPair]) -> DerCertificateRevocationList {
+        let now = Utc::now();
+        let revoked = revoked
+            .iter()
+            .map(|key_pair| key_pair.certificate().clone())
+            .collect::<Vec<_>>();
+
+        ca.generate_crl(&revoked, now, now + ChronoDuration::days(1)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_crl() {
+        let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+        let crl = generate_crl(&ca, &[]);
+
+        let pem = to_pem(PEM_CRL_HEADER, &crl.der_bytes);
+        assert_eq!(parse_crl(pem.as_bytes()).unwrap(), crl);
+        assert_eq!(parse_crl(&crl.der_bytes).unwrap(), crl);
+
+        let pem = to_pem("CERTIFICATE", ca.certificate().as_bytes());
+        assert_matches!(
+            parse_crl(pem.as_bytes()),
+            Err(CertificateError::UnexpectedPemHeader { found, .. }) if found == "CERTIFICATE"
+        );
+        assert_matches!(
+            parse_crl(ca.certificate().as_bytes()),
+            Err(CertificateError::RevocationListParsing(_))
+        );
+    }
+
+    #[test]
+    fn test_crl_cache_refresh() {
+        let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+        let issuer = ca
+            .generate("mycert", IssuerRegistration::new_mock().into(), Default::default())
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let crl_path = dir.path().join("ca.crl");
+        let empty_crl = generate_crl(&ca, &[]);
+        fs::write(&crl_path, &empty_crl.der_bytes).unwrap();
+
+        // With a long refresh interval, the cache should keep the CRLs it read initially.
+        let cache = CrlCache::load(vec![dir.path().to_path_buf()], Duration::from_secs(3600)).unwrap();
+        assert_eq!(cache.crls(), vec![empty_crl.clone()]);
+
+        let revoking_crl = generate_crl(&ca, &[&issuer]);
+        fs::write(&crl_path, &revoking_crl.der_bytes).unwrap();
+        assert_eq!(cache.crls(), vec![empty_crl.clone()]);
+
+        // An explicit refresh should pick up the new CRL.
+        cache.refresh().unwrap();
+        assert_eq!(cache.crls(), vec![revoking_crl.clone()]);
+
+        // A failing refresh should retain the current CRLs.
+        fs::write(&crl_path, b"invalid").unwrap();
+        assert_matches!(cache.refresh(), Err(CrlError::Parsing(path, _)) if path == crl_path);
+        assert_eq!(cache.crls(), vec![revoking_crl]);
+
+        assert_matches!(
+            CrlCache::load(vec![dir.path().join("nonexistent")], Duration::ZERO),
+            Err(CrlError::Io(_, _))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_crl_cache_refresh_task() {
+        let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+        let issuer = ca
+            .generate("mycert", IssuerRegistration::new_mock().into(), Default::default())
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let crl_path = dir.path().join("ca.crl");
+        let empty_crl = generate_crl(&ca, &[]);
+        fs::write(&crl_path, &empty_crl.der_bytes).unwrap();
+
+        // No refresh task should be started for a fixed set of CRLs or a zero refresh interval.
+        assert!(Arc::new(CrlCache::from(vec![empty_crl.clone()]))
+            .start_refresh_task()
+            .is_none());
+        assert!(
+            Arc::new(CrlCache::load(vec![crl_path.clone()], Duration::ZERO).unwrap())
+                .start_refresh_task()
+                .is_none()
+        );
+
+        let refresh_interval = Duration::from_millis(50);
+        let cache = Arc::new(CrlCache::load(vec![crl_path.clone()], refresh_interval).unwrap());
+        let task = Arc::clone(&cache).start_refresh_task().unwrap();
+
+        let revoking_crl = generate_crl(&ca, &[&issuer]);
+        fs::write(&crl_path, &revoking_crl.der_bytes).unwrap();
+        assert_eq!(cache.crls(), vec![empty_crl]);
+
+        // After the refresh interval has passed, the task should pick up the new CRL.
+        for _ in 0..100 {
+            if cache.crls() == vec![revoking_crl.clone()] {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(cache.crls(), vec![revoking_crl]);
+
+        task.abort();
+    }
+}
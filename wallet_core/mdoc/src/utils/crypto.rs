@@ -1,4 +1,4 @@
-//! Cryptographic utilities: SHA256, ECDSA, Diffie-Hellman, HKDF, and key conversion functions.
+//! Cryptographic utilities: SHA-2 digests, ECDSA, Diffie-Hellman, HKDF, and key conversion functions.
 
 use std::fmt::Debug;
 
@@ -9,7 +9,7 @@ use aes_gcm::{
 use ciborium::value::Value;
 use coset::{iana, CoseKeyBuilder, Label};
 use p256::{ecdh, ecdsa::VerifyingKey, EncodedPoint, PublicKey, SecretKey};
-use ring::hmac;
+use ring::{digest, hmac};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_bytes::ByteBuf;
 use x509_parser::nom::AsBytes;
@@ -18,10 +18,10 @@ use wallet_common::utils::{hkdf, sha256};
 
 use crate::{
     utils::{
-        cose::CoseKey,
+        cose::{CoseKey, CoseVerifyingKey},
         serialization::{cbor_serialize, CborError, TaggedBytes},
     },
-    CipherSuiteIdentifier, Result, Security, SecurityKeyed, SessionData, SessionTranscript,
+    CipherSuiteIdentifier, DigestAlgorithm, Result, Security, SecurityKeyed, SessionData, SessionTranscript,
 };
 
 use super::serialization::cbor_deserialize;
@@ -42,6 +42,8 @@ pub enum CryptoError {
     KeyCoordinateParseFailed,
     #[error("key parse failed: {0}")]
     KeyParseFailed(#[from] p256::ecdsa::Error),
+    #[error("unsupported curve: {0:?}")]
+    KeyUnsupportedCurve(Value),
     #[error("AES encryption/decryption failed")]
     Aes,
     #[error("AES encryption/decryption failed: missing ciphertext")]
@@ -54,6 +56,20 @@ pub fn cbor_digest<T: Serialize>(val: &T) -> std::result::Result<Vec<u8>, CborEr
     Ok(digest)
 }
 
+/// Computes the digest of the CBOR encoding of the argument, using the specified digest algorithm.
+pub fn cbor_digest_with_algorithm<T: Serialize>(
+    val: &T,
+    algorithm: &DigestAlgorithm,
+) -> std::result::Result<Vec<u8>, CborError> {
+    let algorithm = match algorithm {
+        DigestAlgorithm::SHA256 => &digest::SHA256,
+        DigestAlgorithm::SHA384 => &digest::SHA384,
+        DigestAlgorithm::SHA512 => &digest::SHA512,
+    };
+    let digest = digest::digest(algorithm, cbor_serialize(val)?.as_ref());
+    Ok(digest.as_ref().to_vec())
+}
+
 /// Using Diffie-Hellman and the HKDF from RFC 5869, compute a HMAC key.
 pub fn dh_hmac_key(privkey: &SecretKey, pubkey: &PublicKey, salt: &[u8], info: &str, len: usize) -> Result<hmac::Key> {
     let dh = ecdh::diffie_hellman(privkey.to_nonzero_scalar(), pubkey.as_affine());
@@ -116,6 +132,52 @@ impl TryFrom<&CoseKey> for VerifyingKey {
     }
 }
 
+impl TryFrom<&CoseKey> for CoseVerifyingKey {
+    type Error = CryptoError;
+    fn try_from(key: &CoseKey) -> std::result::Result<Self, Self::Error> {
+        let param = |label: i64| {
+            key.0
+                .params
+                .iter()
+                .find(|(l, _)| *l == Label::Int(label))
+                .map(|(_, value)| value)
+        };
+        let bytes_param = |label: i64| {
+            param(label)
+                .ok_or(CryptoError::KeyMissingCoordinate)?
+                .as_bytes()
+                .ok_or(CryptoError::KeyCoordinateParseFailed)
+        };
+
+        let curve = param(-1).ok_or(CryptoError::KeyMissingKeyID)?;
+        let curve_is = |expected: iana::EllipticCurve| *curve == Value::Integer((expected as i64).into());
+
+        match key.0.kty {
+            coset::RegisteredLabel::Assigned(iana::KeyType::EC2) if curve_is(iana::EllipticCurve::P_256) => {
+                Ok(CoseVerifyingKey::P256(key.try_into()?))
+            }
+            coset::RegisteredLabel::Assigned(iana::KeyType::EC2)
+                if curve_is(iana::EllipticCurve::P_384) || curve_is(iana::EllipticCurve::P_521) =>
+            {
+                // Construct the uncompressed SEC1 encoding of the point.
+                let point = [[0x04].as_slice(), bytes_param(-2)?, bytes_param(-3)?].concat();
+                if curve_is(iana::EllipticCurve::P_384) {
+                    Ok(CoseVerifyingKey::P384(point))
+                } else {
+                    Ok(CoseVerifyingKey::P521(point))
+                }
+            }
+            coset::RegisteredLabel::Assigned(iana::KeyType::OKP) if curve_is(iana::EllipticCurve::Ed25519) => {
+                Ok(CoseVerifyingKey::Ed25519(bytes_param(-2)?.to_vec()))
+            }
+            coset::RegisteredLabel::Assigned(iana::KeyType::EC2 | iana::KeyType::OKP) => {
+                Err(CryptoError::KeyUnsupportedCurve(curve.clone()))
+            }
+            _ => Err(CryptoError::KeyWrongType),
+        }
+    }
+}
+
 impl TryFrom<&PublicKey> for Security {
     type Error = CryptoError;
 
@@ -236,14 +298,20 @@ impl SessionData {
 
 #[cfg(test)]
 mod test {
-    use p256::SecretKey;
+    use coset::{iana, CoseKeyBuilder};
+    use p256::{ecdsa::SigningKey, SecretKey};
     use rand_core::OsRng;
+    use rstest::rstest;
 
     use serde::{Deserialize, Serialize};
 
-    use crate::{examples::Example, DeviceAuthenticationBytes, SessionData};
+    use crate::{
+        examples::Example,
+        utils::cose::{CoseKey, CoseVerifyingKey},
+        DeviceAuthenticationBytes, DigestAlgorithm, SessionData,
+    };
 
-    use super::{SessionKey, SessionKeyUser};
+    use super::{cbor_digest, cbor_digest_with_algorithm, CryptoError, SessionKey, SessionKeyUser};
 
     #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
     struct ToyMessage {
@@ -281,4 +349,59 @@ mod test {
         let decrypted = session_data.decrypt_and_deserialize(&key).unwrap();
         assert_eq!(plaintext, decrypted);
     }
+
+    #[rstest]
+    #[case(DigestAlgorithm::SHA256, 32)]
+    #[case(DigestAlgorithm::SHA384, 48)]
+    #[case(DigestAlgorithm::SHA512, 64)]
+    fn test_cbor_digest_with_algorithm(#[case] algorithm: DigestAlgorithm, #[case] expected_len: usize) {
+        let digest = cbor_digest_with_algorithm(&ToyMessage::default(), &algorithm).unwrap();
+
+        assert_eq!(digest.len(), expected_len);
+        if algorithm == DigestAlgorithm::SHA256 {
+            assert_eq!(digest, cbor_digest(&ToyMessage::default()).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_cose_key_to_cose_verifying_key() {
+        let p256_key = *SigningKey::random(&mut OsRng).verifying_key();
+        let cose_key = CoseKey::try_from(&p256_key).unwrap();
+        assert_eq!(
+            CoseVerifyingKey::try_from(&cose_key).unwrap(),
+            CoseVerifyingKey::P256(p256_key)
+        );
+
+        let cose_key =
+            CoseKey(CoseKeyBuilder::new_ec2_pub_key(iana::EllipticCurve::P_384, vec![1; 48], vec![2; 48]).build());
+        assert_eq!(
+            CoseVerifyingKey::try_from(&cose_key).unwrap(),
+            CoseVerifyingKey::P384([vec![4], vec![1; 48], vec![2; 48]].concat())
+        );
+
+        let cose_key = CoseKey(
+            CoseKeyBuilder::new_okp_key()
+                .param(-1, (iana::EllipticCurve::Ed25519 as i64).into())
+                .param(-2, vec![3; 32].into())
+                .build(),
+        );
+        assert_eq!(
+            CoseVerifyingKey::try_from(&cose_key).unwrap(),
+            CoseVerifyingKey::Ed25519(vec![3; 32])
+        );
+
+        let cose_key =
+            CoseKey(CoseKeyBuilder::new_ec2_pub_key(iana::EllipticCurve::P_521, vec![1; 66], vec![2; 66]).build());
+        assert_eq!(
+            CoseVerifyingKey::try_from(&cose_key).unwrap(),
+            CoseVerifyingKey::P521([vec![4], vec![1; 66], vec![2; 66]].concat())
+        );
+
+        let cose_key =
+            CoseKey(CoseKeyBuilder::new_ec2_pub_key(iana::EllipticCurve::Secp256k1, vec![1; 32], vec![2; 32]).build());
+        assert!(matches!(
+            CoseVerifyingKey::try_from(&cose_key),
+            Err(CryptoError::KeyUnsupportedCurve(_))
+        ));
+    }
 }
@@ -1,6 +1,9 @@
 use std::error::Error;
 
-use p256::ecdsa::{Signature, VerifyingKey};
+use p256::{
+    ecdsa::{Signature, VerifyingKey},
+    PublicKey,
+};
 use serde::{Deserialize, Serialize};
 
 use wallet_common::keys::{SecureEcdsaKey, WithIdentifier};
@@ -41,6 +44,14 @@ pub trait KeyFactory {
         &self,
         messages_and_keys: Vec<(Vec<u8>, Vec<&Self::Key>)>,
     ) -> Result<Vec<Vec<Signature>>, Self::Error>;
+
+    /// Perform an ECDH key agreement between each of the keys and `public_key`, returning the raw shared secrets
+    /// in the same order as the keys.
+    async fn diffie_hellman_with_existing_keys(
+        &self,
+        keys: Vec<&Self::Key>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Vec<u8>>, Self::Error>;
 }
 
 #[cfg(any(test, feature = "software_keys"))]
@@ -1,5 +1,6 @@
 pub mod auth;
 pub mod cose;
+pub mod crl;
 pub mod keys;
 pub mod serialization;
 pub mod x509;
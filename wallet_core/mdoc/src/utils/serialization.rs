@@ -259,9 +259,7 @@ where
 // We can't derive `Deserialize` with the `untagged` Serde enum deserializer, because unfortunately it is not able to
 // deserialize the SchemeHandoverBytes variant.
 // For the other direction (serializing), however, the `untagged` enum serializer is used and works fine.
-// Note that this implementation is only ever used to deserialize the examples from the spec in `examples.rs`.
 // For each variant a unit test is included to check that serializing and deserializing agree with each other.
-#[cfg(any(test, feature = "examples"))]
 impl<'de> Deserialize<'de> for Handover {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         let val = Value::deserialize(deserializer)?;
@@ -458,6 +456,95 @@ impl<'de, T: DeserializeOwned> Deserialize<'de> for CborBase64<T> {
     }
 }
 
+/// (De)serialization of [`DataElementValue`]s that is lossless for human readable formats such as JSON.
+///
+/// When serializing to CBOR the value is (de)serialized as is. JSON however has no notion of byte strings or tags,
+/// which would otherwise be converted to an array of integers and a map that cannot be converted back, respectively.
+/// Instead, these are encoded as `{"@@BYTES@@": <base64url>}` and `{"@@TAGGED@@": [<tag>, <value>]}`, recursively.
+/// Use with `#[serde(with = "data_element_value")]`.
+pub mod data_element_value {
+    use itertools::Itertools;
+
+    use super::*;
+
+    const BYTES_KEY: &str = "@@BYTES@@";
+    const TAGGED_KEY: &str = "@@TAGGED@@";
+
+    pub fn serialize<S: Serializer>(value: &DataElementValue, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            to_human_readable(value).serialize(serializer)
+        } else {
+            value.serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DataElementValue, D::Error> {
+        if deserializer.is_human_readable() {
+            from_human_readable(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+        } else {
+            Value::deserialize(deserializer)
+        }
+    }
+
+    fn to_human_readable(value: &Value) -> Value {
+        match value {
+            Value::Bytes(bytes) => Value::Map(vec![(
+                Value::Text(BYTES_KEY.to_string()),
+                Value::Text(BASE64_URL_SAFE_NO_PAD.encode(bytes)),
+            )]),
+            Value::Tag(tag, value) => Value::Map(vec![(
+                Value::Text(TAGGED_KEY.to_string()),
+                Value::Array(vec![Value::Integer((*tag).into()), to_human_readable(value)]),
+            )]),
+            Value::Array(values) => Value::Array(values.iter().map(to_human_readable).collect()),
+            Value::Map(entries) => Value::Map(
+                entries
+                    .iter()
+                    .map(|(key, value)| (to_human_readable(key), to_human_readable(value)))
+                    .collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
+
+    fn from_human_readable(value: Value) -> Result<Value, String> {
+        match value {
+            Value::Map(mut entries) if entries.len() == 1 && entries[0].0.as_text() == Some(BYTES_KEY) => {
+                let (_, bytes) = entries.pop().unwrap();
+                let bytes = bytes
+                    .into_text()
+                    .map_err(|_| "expected base64url encoded bytes".to_string())?;
+                let bytes = BASE64_URL_SAFE_NO_PAD.decode(bytes).map_err(|e| e.to_string())?;
+                Ok(Value::Bytes(bytes))
+            }
+            Value::Map(mut entries) if entries.len() == 1 && entries[0].0.as_text() == Some(TAGGED_KEY) => {
+                let (_, tagged) = entries.pop().unwrap();
+                let (tag, value) = match tagged.into_array() {
+                    Ok(tagged) => tagged.into_iter().collect_tuple(),
+                    Err(_) => None,
+                }
+                .ok_or_else(|| "expected tag and value".to_string())?;
+                let tag = tag
+                    .into_integer()
+                    .ok()
+                    .and_then(|tag| u64::try_from(tag).ok())
+                    .ok_or_else(|| "expected unsigned integer tag".to_string())?;
+                Ok(Value::Tag(tag, Box::new(from_human_readable(value)?)))
+            }
+            Value::Array(values) => Ok(Value::Array(
+                values.into_iter().map(from_human_readable).collect::<Result<_, _>>()?,
+            )),
+            Value::Map(entries) => Ok(Value::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| Ok((from_human_readable(key)?, from_human_readable(value)?)))
+                    .collect::<Result<_, String>>()?,
+            )),
+            value => Ok(value),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
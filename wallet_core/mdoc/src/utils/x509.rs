@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 use chrono::{DateTime, Utc};
 use indexmap::IndexMap;
@@ -7,25 +7,43 @@ use p256::{
     elliptic_curve::pkcs8::DecodePublicKey,
     pkcs8::der::{asn1::Utf8StringRef, Decode, SliceReader},
 };
+use p521::ecdsa::signature::Verifier;
+use rustls_webpki_102::{
+    alg_id,
+    types::{
+        AlgorithmIdentifier, CertificateDer, Der, InvalidSignature, SignatureVerificationAlgorithm,
+        TrustAnchor as ChainTrustAnchor, UnixTime,
+    },
+    BorrowedCertRevocationList, CertRevocationList, ExpirationPolicy, RevocationOptionsBuilder, UnknownStatusPolicy,
+};
 use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
 use serde_bytes::ByteBuf;
-use webpki::{EndEntityCert, Time, TrustAnchor, ECDSA_P256_SHA256};
+use webpki::{EndEntityCert, TrustAnchor};
 use x509_parser::{
     der_parser::Oid,
-    extensions::GeneralName,
+    extensions::{DistributionPointName, GeneralName, ParsedExtension},
     nom::{self, AsBytes},
+    oid_registry::{OID_EC_P256, OID_KEY_TYPE_EC_PUBLIC_KEY, OID_NIST_EC_P384, OID_NIST_EC_P521, OID_SIG_ED25519},
     pem,
     prelude::{ExtendedKeyUsage, FromDer, PEMError, X509Certificate, X509Error},
 };
 
-use wallet_common::{generator::Generator, trust_anchor::DerTrustAnchor};
+use wallet_common::{
+    generator::Generator, revocation_list::DerCertificateRevocationList, trust_anchor::DerTrustAnchor,
+};
 
-use super::{issuer_auth::IssuerRegistration, reader_auth::ReaderRegistration};
+use super::{cose::CoseVerifyingKey, issuer_auth::IssuerRegistration, reader_auth::ReaderRegistration};
 
 #[derive(thiserror::Error, Debug)]
 pub enum CertificateError {
     #[error("certificate verification failed: {0}")]
-    Verification(#[source] webpki::Error),
+    Verification(#[source] rustls_webpki_102::Error),
+    #[error("certificate has been revoked")]
+    Revoked,
+    #[error("certificate revocation list has expired")]
+    RevocationListExpired,
+    #[error("certificate revocation list parsing failed: {0}")]
+    RevocationListParsing(#[source] webpki::Error),
     #[error("certificate parsing for validation failed: {0}")]
     ValidationParsing(#[from] webpki::Error),
     #[error("certificate content parsing failed: {0}")]
@@ -37,6 +55,8 @@ pub enum CertificateError {
     GeneratingFailed(#[from] rcgen::RcgenError),
     #[error("failed to parse certificate public key: {0}")]
     KeyParsingFailed(p256::pkcs8::spki::Error),
+    #[error("unsupported certificate public key type: {0}")]
+    UnsupportedKeyType(String),
     #[error("EKU count incorrect ({0})")]
     IncorrectEkuCount(usize),
     #[error("EKU incorrect")]
@@ -55,6 +75,38 @@ pub enum CertificateError {
 
 pub const OID_EXT_KEY_USAGE: &[u64] = &[2, 5, 29, 37];
 
+/// The signature algorithms that we accept in certificate chains. Apart from P-256, which we use ourselves, these
+/// allow us to verify mdocs from issuers using other curves.
+static SUPPORTED_SIGNATURE_ALGORITHMS: &[&dyn SignatureVerificationAlgorithm] = &[
+    rustls_webpki_102::ring::ECDSA_P256_SHA256,
+    rustls_webpki_102::ring::ECDSA_P256_SHA384,
+    rustls_webpki_102::ring::ECDSA_P384_SHA256,
+    rustls_webpki_102::ring::ECDSA_P384_SHA384,
+    rustls_webpki_102::ring::ED25519,
+    &EcdsaP521Sha512,
+];
+
+/// ECDSA using P-521 and SHA-512, which is not supported by `ring` and is therefore implemented using `p521`.
+#[derive(Debug)]
+struct EcdsaP521Sha512;
+
+impl SignatureVerificationAlgorithm for EcdsaP521Sha512 {
+    fn verify_signature(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<(), InvalidSignature> {
+        let public_key = p521::ecdsa::VerifyingKey::from_sec1_bytes(public_key).map_err(|_| InvalidSignature)?;
+        let signature = p521::ecdsa::Signature::from_der(signature).map_err(|_| InvalidSignature)?;
+
+        public_key.verify(message, &signature).map_err(|_| InvalidSignature)
+    }
+
+    fn public_key_alg_id(&self) -> AlgorithmIdentifier {
+        alg_id::ECDSA_P521
+    }
+
+    fn signature_alg_id(&self) -> AlgorithmIdentifier {
+        alg_id::ECDSA_SHA512
+    }
+}
+
 /// An x509 certificate, unifying functionality from the following crates:
 ///
 /// - parsing data: `x509_parser`
@@ -150,30 +202,98 @@ impl Certificate {
         }
     }
 
-    /// Verify the certificate against the specified trust anchors.
+    /// Verify the certificate against the specified trust anchors. If any of the certificates in the chain,
+    /// including intermediate certificates, is present in a CRL issued by its issuer, [`CertificateError::Revoked`]
+    /// is returned. A CRL of an issuer in the chain whose `nextUpdate` time has passed can no longer be relied upon,
+    /// in which case [`CertificateError::RevocationListExpired`] is returned.
     pub fn verify(
         &self,
         usage: CertificateUsage,
         intermediate_certs: &[&[u8]],
         time: &impl Generator<DateTime<Utc>>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<(), CertificateError> {
-        self.to_webpki()?
+        // The chain is verified using a newer version of `webpki`, which allows for the P-521 algorithm above.
+        let certificate = CertificateDer::from(self.as_bytes());
+        let intermediate_certs = intermediate_certs
+            .iter()
+            .map(|intermediate_cert| CertificateDer::from(*intermediate_cert))
+            .collect::<Vec<_>>();
+        let trust_anchors = trust_anchors
+            .iter()
+            .map(|trust_anchor| ChainTrustAnchor {
+                subject: Der::from_slice(trust_anchor.subject),
+                subject_public_key_info: Der::from_slice(trust_anchor.spki),
+                name_constraints: trust_anchor.name_constraints.map(Der::from_slice),
+            })
+            .collect::<Vec<_>>();
+        let crls = crls
+            .iter()
+            .map(|crl| BorrowedCertRevocationList::from_der(&crl.der_bytes).map(CertRevocationList::from))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(CertificateError::Verification)?;
+        let crls = crls.iter().collect::<Vec<_>>();
+        // Certificates whose issuer did not provide a CRL are not considered to be revoked.
+        let revocation = RevocationOptionsBuilder::new(&crls).ok().map(|builder| {
+            builder
+                .with_status_policy(UnknownStatusPolicy::Allow)
+                .with_expiration_policy(ExpirationPolicy::Enforce)
+                .build()
+        });
+
+        rustls_webpki_102::EndEntityCert::try_from(&certificate)
+            .map_err(CertificateError::Verification)?
             .verify_for_usage(
-                &[&ECDSA_P256_SHA256],
-                trust_anchors,
-                intermediate_certs,
-                Time::from_seconds_since_unix_epoch(time.generate().timestamp() as u64),
-                webpki::KeyUsage::required(usage.to_eku()),
-                &[],
+                SUPPORTED_SIGNATURE_ALGORITHMS,
+                &trust_anchors,
+                &intermediate_certs,
+                UnixTime::since_unix_epoch(Duration::from_secs(time.generate().timestamp() as u64)),
+                rustls_webpki_102::KeyUsage::required(usage.to_eku()),
+                revocation,
+                None,
             )
-            .map_err(CertificateError::Verification)
+            .map(|_| ())
+            .map_err(|error| match error {
+                rustls_webpki_102::Error::CertRevoked => CertificateError::Revoked,
+                rustls_webpki_102::Error::CrlExpired => CertificateError::RevocationListExpired,
+                error => CertificateError::Verification(error),
+            })
     }
 
     pub fn public_key(&self) -> Result<VerifyingKey, CertificateError> {
         VerifyingKey::from_public_key_der(self.to_x509()?.public_key().raw).map_err(CertificateError::KeyParsingFailed)
     }
 
+    /// Returns the public key of the certificate for any of the curves that we support for verifying COSEs.
+    pub fn cose_verifying_key(&self) -> Result<CoseVerifyingKey, CertificateError> {
+        let x509 = self.to_x509()?;
+        let spki = x509.public_key();
+        let algorithm = &spki.algorithm.algorithm;
+
+        if *algorithm == OID_SIG_ED25519 {
+            return Ok(CoseVerifyingKey::Ed25519(spki.subject_public_key.data.to_vec()));
+        }
+
+        if *algorithm != OID_KEY_TYPE_EC_PUBLIC_KEY {
+            return Err(CertificateError::UnsupportedKeyType(algorithm.to_id_string()));
+        }
+
+        let curve = spki
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|parameters| parameters.as_oid().ok())
+            .ok_or_else(|| CertificateError::UnsupportedKeyType(algorithm.to_id_string()))?;
+
+        match curve {
+            curve if curve == OID_EC_P256 => Ok(self.public_key()?.into()),
+            curve if curve == OID_NIST_EC_P384 => Ok(CoseVerifyingKey::P384(spki.subject_public_key.data.to_vec())),
+            curve if curve == OID_NIST_EC_P521 => Ok(CoseVerifyingKey::P521(spki.subject_public_key.data.to_vec())),
+            curve => Err(CertificateError::UnsupportedKeyType(curve.to_id_string())),
+        }
+    }
+
     /// Convert the certificate to a [`X509Certificate`] from the `x509_parser` crate, to read its contents.
     pub fn to_x509(&self) -> Result<X509Certificate, CertificateError> {
         self.try_into()
@@ -222,6 +342,36 @@ impl Certificate {
         .transpose()
     }
 
+    /// Returns the serial number of the certificate as a big-endian unsigned integer.
+    pub fn serial_number(&self) -> Result<Vec<u8>, CertificateError> {
+        Ok(self.to_x509()?.serial.to_bytes_be())
+    }
+
+    /// Returns the URIs of the CRL distribution points in the certificate, from which the CRLs that may revoke this
+    /// certificate can be retrieved.
+    pub fn crl_distribution_points(&self) -> Result<Vec<String>, CertificateError> {
+        let x509 = self.to_x509()?;
+        let uris = x509
+            .iter_extensions()
+            .filter_map(|ext| match ext.parsed_extension() {
+                ParsedExtension::CRLDistributionPoints(points) => Some(points.iter()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|point| match &point.distribution_point {
+                Some(DistributionPointName::FullName(names)) => Some(names.iter()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|name| match name {
+                GeneralName::URI(uri) => Some(uri.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        Ok(uris)
+    }
+
     /// Returns the first DNS SAN, if any, from the certificate.
     pub fn san_dns_name(&self) -> Result<Option<String>, CertificateError> {
         let san = self.to_x509()?.subject_alternative_name()?.and_then(|ext| {
@@ -355,6 +505,7 @@ where
 pub struct CertificateConfiguration {
     pub not_before: Option<DateTime<Utc>>,
     pub not_after: Option<DateTime<Utc>>,
+    pub crl_distribution_points: Vec<String>,
 }
 
 #[cfg(test)]
@@ -405,6 +556,7 @@ mod test {
         let config = CertificateConfiguration {
             not_before: Some(now),
             not_after: Some(later),
+            ..Default::default()
         };
         let ca = KeyPair::generate_ca("myca", config).unwrap();
 
@@ -424,6 +576,7 @@ mod test {
         let config = CertificateConfiguration {
             not_before: Some(start),
             not_after: Some(end),
+            ..Default::default()
         };
 
         let mdl = IssuerRegistration::new_mock().into();
@@ -433,9 +586,12 @@ mod test {
         let ca_trustanchor: TrustAnchor = ca.certificate().try_into().unwrap();
         let error = issuer_key_pair
             .certificate()
-            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor])
+            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor], &[])
             .expect_err("Expected verify to fail");
-        assert_matches!(error, CertificateError::Verification(webpki::Error::CertNotValidYet));
+        assert_matches!(
+            error,
+            CertificateError::Verification(rustls_webpki_102::Error::CertNotValidYet)
+        );
     }
 
     #[test]
@@ -449,6 +605,7 @@ mod test {
         let config = CertificateConfiguration {
             not_before: Some(start),
             not_after: Some(end),
+            ..Default::default()
         };
 
         let mdl = IssuerRegistration::new_mock().into();
@@ -458,9 +615,12 @@ mod test {
         let ca_trustanchor: TrustAnchor = ca.certificate().try_into().unwrap();
         let error = issuer_key_pair
             .certificate()
-            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor])
+            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor], &[])
             .expect_err("Expected verify to fail");
-        assert_matches!(error, CertificateError::Verification(webpki::Error::CertExpired));
+        assert_matches!(
+            error,
+            CertificateError::Verification(rustls_webpki_102::Error::CertExpired)
+        );
     }
 
     #[test]
@@ -473,7 +633,7 @@ mod test {
         let ca_trustanchor: TrustAnchor = ca.certificate().try_into().unwrap();
         issuer_key_pair
             .certificate()
-            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor])
+            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor], &[])
             .unwrap();
 
         // Verify whether the parsed CertificateType equals the original Mdl usage
@@ -493,6 +653,7 @@ mod test {
         let config = CertificateConfiguration {
             not_before: Some(now),
             not_after: Some(later),
+            ..Default::default()
         };
 
         let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
@@ -503,7 +664,7 @@ mod test {
         let ca_trustanchor: TrustAnchor = ca.certificate().try_into().unwrap();
         issuer_key_pair
             .certificate()
-            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor])
+            .verify(CertificateUsage::Mdl, &[], &TimeGenerator, &[ca_trustanchor], &[])
             .unwrap();
 
         // Verify whether the parsed CertificateType equals the original Mdl usage
@@ -525,7 +686,13 @@ mod test {
         let ca_trustanchor: TrustAnchor = ca.certificate().try_into().unwrap();
         reader_key_pair
             .certificate()
-            .verify(CertificateUsage::ReaderAuth, &[], &TimeGenerator, &[ca_trustanchor])
+            .verify(
+                CertificateUsage::ReaderAuth,
+                &[],
+                &TimeGenerator,
+                &[ca_trustanchor],
+                &[],
+            )
             .unwrap();
 
         // Verify whether the parsed CertificateType equals the original ReaderAuth usage
@@ -545,6 +712,7 @@ mod test {
         let config = CertificateConfiguration {
             not_before: Some(now),
             not_after: Some(later),
+            ..Default::default()
         };
 
         let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
@@ -555,7 +723,13 @@ mod test {
         let ca_trustanchor: TrustAnchor = ca.certificate().try_into().unwrap();
         reader_key_pair
             .certificate()
-            .verify(CertificateUsage::ReaderAuth, &[], &TimeGenerator, &[ca_trustanchor])
+            .verify(
+                CertificateUsage::ReaderAuth,
+                &[],
+                &TimeGenerator,
+                &[ca_trustanchor],
+                &[],
+            )
             .unwrap();
 
         // Verify whether the parsed CertificateType equals the original ReaderAuth usage
@@ -567,6 +741,79 @@ mod test {
         assert_certificate_validity(&x509_cert, now, later);
     }
 
+    #[test]
+    fn generate_and_verify_revoked_certs() {
+        let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+        let issuer_key_pair = ca
+            .generate("mycert", IssuerRegistration::new_mock().into(), Default::default())
+            .unwrap();
+        let reader_key_pair = ca
+            .generate("myreader", ReaderRegistration::new_mock().into(), Default::default())
+            .unwrap();
+
+        let now = Utc::now();
+        let next_update = now + Duration::days(1);
+        let issuer_crl = ca
+            .generate_crl(&[issuer_key_pair.certificate().clone()], now, next_update)
+            .unwrap();
+        let reader_crl = ca
+            .generate_crl(&[reader_key_pair.certificate().clone()], now, next_update)
+            .unwrap();
+
+        let verify = |key_pair: &KeyPair, usage: CertificateUsage, crl| {
+            let ca_trustanchor: TrustAnchor = ca.certificate().try_into().unwrap();
+            key_pair
+                .certificate()
+                .verify(usage, &[], &TimeGenerator, &[ca_trustanchor], &[crl])
+        };
+
+        // Each certificate should be rejected by the CRL that revokes it, but not by the other one.
+        let error = verify(&issuer_key_pair, CertificateUsage::Mdl, issuer_crl.clone()).expect_err("should be revoked");
+        assert_matches!(error, CertificateError::Revoked);
+        verify(&reader_key_pair, CertificateUsage::ReaderAuth, issuer_crl).unwrap();
+
+        let error =
+            verify(&reader_key_pair, CertificateUsage::ReaderAuth, reader_crl.clone()).expect_err("should be revoked");
+        assert_matches!(error, CertificateError::Revoked);
+        verify(&issuer_key_pair, CertificateUsage::Mdl, reader_crl).unwrap();
+
+        // A CRL of another CA with the same name should not be accepted.
+        let other_ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+        let other_crl = other_ca
+            .generate_crl(&[issuer_key_pair.certificate().clone()], now, next_update)
+            .unwrap();
+        let error = verify(&issuer_key_pair, CertificateUsage::Mdl, other_crl).expect_err("should fail");
+        assert_matches!(
+            error,
+            CertificateError::Verification(rustls_webpki_102::Error::InvalidCrlSignatureForPublicKey)
+        );
+
+        // A CRL whose next update has passed should not be relied upon, even if it does not revoke the certificate.
+        let expired_crl = ca
+            .generate_crl(&[], now - Duration::days(2), now - Duration::days(1))
+            .unwrap();
+        let error = verify(&issuer_key_pair, CertificateUsage::Mdl, expired_crl).expect_err("should fail");
+        assert_matches!(error, CertificateError::RevocationListExpired);
+    }
+
+    #[test]
+    fn generate_cert_with_crl_distribution_points() {
+        let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+        let config = CertificateConfiguration {
+            crl_distribution_points: vec!["https://example.com/myca.crl".to_string()],
+            ..Default::default()
+        };
+        let issuer_key_pair = ca
+            .generate("mycert", IssuerRegistration::new_mock().into(), config)
+            .unwrap();
+
+        assert_eq!(
+            issuer_key_pair.certificate().crl_distribution_points().unwrap(),
+            vec!["https://example.com/myca.crl"]
+        );
+        assert!(ca.certificate().crl_distribution_points().unwrap().is_empty());
+    }
+
     fn assert_certificate_default_validity(certificate: &X509Certificate) {
         let not_before = certificate.validity().not_before.to_datetime();
         let not_after = certificate.validity().not_after.to_datetime();
@@ -607,6 +854,7 @@ mod test {
         let config = CertificateConfiguration {
             not_before: Some(start),
             not_after: Some(end),
+            ..Default::default()
         };
         KeyPair::generate_ca("myca", config).unwrap()
     }
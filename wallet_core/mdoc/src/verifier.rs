@@ -12,7 +12,7 @@ use p256::SecretKey;
 use rand_core::OsRng;
 use ring::hmac;
 use serde::{Deserialize, Serialize};
-use serde_with::{hex::Hex, serde_as};
+use serde_with::{hex::Hex, serde_as, DurationSeconds};
 use strfmt::strfmt;
 use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
@@ -23,6 +23,7 @@ use wallet_common::{
     account::serialization::DerSecretKey,
     config::wallet_config::BaseUrl,
     generator::{Generator, TimeGenerator},
+    revocation_list::DerCertificateRevocationList,
     trust_anchor::OwnedTrustAnchor,
     utils,
 };
@@ -37,8 +38,9 @@ use crate::{
     },
     unsigned::Entry,
     utils::{
-        cose::{self, ClonePayload, MdocCose},
-        crypto::{cbor_digest, dh_hmac_key, SessionKey, SessionKeyUser},
+        cose::{self, ClonePayload, CoseVerifyingKey, MdocCose},
+        crl::CrlCache,
+        crypto::{cbor_digest_with_algorithm, dh_hmac_key, SessionKey, SessionKeyUser},
         serialization::{cbor_deserialize, cbor_serialize, CborBase64, CborSeq, TaggedBytes},
         x509::CertificateUsage,
     },
@@ -53,6 +55,14 @@ pub struct DocumentDisclosedAttributes {
     pub attributes: IndexMap<NameSpace, Vec<Entry>>,
     pub issuer: Vec<String>,
     pub validity_info: ValidityInfo,
+    /// Self-asserted attributes, grouped per namespace. These are authenticated by the holder, but not signed by the
+    /// issuer, so their values are unverified.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub self_asserted_attributes: IndexMap<NameSpace, Vec<Entry>>,
+    /// The reasons why the mdoc is considered stale, which are only present if the [`ValidityPolicy`] of the verifier
+    /// accepts stale mdocs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub staleness: Vec<Staleness>,
 }
 /// All attributes that were disclosed in a [`DeviceResponse`], as computed by [`DeviceResponse::verify()`].
 pub type DisclosedAttributes = IndexMap<DocType, DocumentDisclosedAttributes>;
@@ -75,6 +85,8 @@ pub enum VerificationError {
     AttributeVerificationFailed,
     #[error("missing ephemeral key")]
     EphemeralKeyMissing,
+    #[error("DeviceMac not supported for mdoc key with algorithm {0:?}")]
+    UnsupportedDeviceMacKey(coset::iana::Algorithm),
     #[error("validity error: {0}")]
     Validity(#[from] ValidityError),
     #[error("missing OriginInfo in engagement: {0}")]
@@ -145,6 +157,8 @@ pub struct WaitingForResponse {
     their_key: SessionKey,
     ephemeral_privkey: DerSecretKey,
     session_transcript_data: SessionTranscriptData,
+    #[serde(default)]
+    validity_policy: ValidityPolicy,
 }
 
 /// State for a session that has ended (for any reason).
@@ -350,6 +364,7 @@ pub struct UseCases(HashMap<String, UseCase>);
 pub struct UseCase {
     pub key_pair: KeyPair,
     pub session_type_return_url: SessionTypeReturnUrl,
+    pub validity_policy: ValidityPolicy,
 }
 
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
@@ -366,13 +381,18 @@ pub struct Verifier<S> {
     sessions: Arc<S>,
     cleanup_task: JoinHandle<()>,
     trust_anchors: Vec<OwnedTrustAnchor>,
+    crls: Arc<CrlCache>,
+    crl_refresh_task: Option<JoinHandle<()>>,
     ephemeral_id_secret: hmac::Key,
 }
 
 impl<S> Drop for Verifier<S> {
     fn drop(&mut self) {
-        // Stop the task at the next .await
+        // Stop the tasks at the next .await
         self.cleanup_task.abort();
+        if let Some(crl_refresh_task) = &self.crl_refresh_task {
+            crl_refresh_task.abort();
+        }
     }
 }
 
@@ -387,22 +407,28 @@ where
     /// - `sessions` will contain all sessions.
     /// - `trust_anchors` contains self-signed X509 CA certificates acting as trust anchor for the mdoc verification:
     ///   the mdoc verification function [`Document::verify()`] returns true if the mdoc verifies against one of these CAs.
+    /// - `crls` contains the CRLs against which the issuer certificates of the mdocs are checked for revocation,
+    ///   which are refreshed in the background if the [`CrlCache`] was loaded from files.
     pub fn new(
         use_cases: UseCases,
         sessions: S,
         trust_anchors: Vec<OwnedTrustAnchor>,
+        crls: CrlCache,
         ephemeral_id_secret: hmac::Key,
     ) -> Self
     where
         S: Send + Sync + 'static,
     {
         let sessions = Arc::new(sessions);
+        let crls = Arc::new(crls);
 
         Self {
             use_cases,
             cleanup_task: Arc::clone(&sessions).start_cleanup_task(CLEANUP_INTERVAL_SECONDS),
+            crl_refresh_task: Arc::clone(&crls).start_refresh_task(),
             sessions,
             trust_anchors,
+            crls,
             ephemeral_id_secret,
         }
     }
@@ -505,6 +531,7 @@ where
                         .map(Into::<TrustAnchor<'_>>::into)
                         .collect::<Vec<_>>()
                         .as_slice(),
+                    &self.crls.crls(),
                 );
                 Ok((response, session.state.into()))
             }
@@ -774,7 +801,15 @@ impl Session<Created> {
             .process_device_engagement_inner(&device_engagement, verifier_url.clone(), session_type, use_cases)
             .await
         {
-            Ok((response, items_requests, return_url_nonce, their_key, ephemeral_privkey, reader_engagement)) => (
+            Ok((
+                response,
+                items_requests,
+                return_url_nonce,
+                their_key,
+                ephemeral_privkey,
+                reader_engagement,
+                validity_policy,
+            )) => (
                 response,
                 Ok(self.transition_wait_for_response(
                     items_requests,
@@ -782,6 +817,7 @@ impl Session<Created> {
                     their_key,
                     ephemeral_privkey,
                     SessionTranscriptData::new(session_type, reader_engagement, device_engagement),
+                    validity_policy,
                 )),
             ),
             Err(e) => {
@@ -810,6 +846,7 @@ impl Session<Created> {
         SessionKey,
         SecretKey,
         ReaderEngagement,
+        ValidityPolicy,
     )> {
         Self::verify_origin_infos(&device_engagement.0.origin_infos)?;
 
@@ -852,7 +889,9 @@ impl Session<Created> {
         };
 
         let device_request = self
-            .new_device_request(&session_transcript, return_url, &use_case.key_pair)
+            .state()
+            .items_requests
+            .to_device_request(&session_transcript, return_url, &use_case.key_pair)
             .await?;
 
         // Compute the AES keys with which we and the device encrypt responses
@@ -884,6 +923,7 @@ impl Session<Created> {
             their_key,
             self.state().ephemeral_privkey.clone().0,
             reader_engagement,
+            use_case.validity_policy,
         ))
     }
 
@@ -927,6 +967,7 @@ impl Session<Created> {
         their_key: SessionKey,
         ephemeral_privkey: SecretKey,
         session_transcript_data: SessionTranscriptData,
+        validity_policy: ValidityPolicy,
     ) -> Session<WaitingForResponse> {
         self.transition(WaitingForResponse {
             items_requests,
@@ -934,38 +975,7 @@ impl Session<Created> {
             their_key,
             ephemeral_privkey: ephemeral_privkey.into(),
             session_transcript_data,
-        })
-    }
-
-    async fn new_device_request(
-        &self,
-        session_transcript: &SessionTranscript,
-        return_url: Option<Url>,
-        private_key: &KeyPair,
-    ) -> Result<DeviceRequest> {
-        let doc_requests = try_join_all(self.state().items_requests.0.iter().map(|items_request| async {
-            let items_request = items_request.clone().into();
-            let reader_auth = ReaderAuthenticationKeyed::new(session_transcript, &items_request);
-            let cose = MdocCose::<_, ReaderAuthenticationBytes>::sign(
-                &TaggedBytes(CborSeq(reader_auth)),
-                cose::new_certificate_header(private_key.certificate()),
-                private_key,
-                false,
-            )
-            .await?;
-            let cose = MdocCose::from(cose.0);
-            let doc_request = DocRequest {
-                items_request,
-                reader_auth: Some(cose),
-            };
-            Result::<DocRequest>::Ok(doc_request)
-        }))
-        .await?;
-
-        Ok(DeviceRequest {
-            doc_requests,
-            return_url,
-            ..Default::default()
+            validity_policy,
         })
     }
 }
@@ -977,6 +987,7 @@ impl Session<WaitingForResponse> {
         self,
         session_data: SessionData,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> (SessionData, Session<Done>) {
         info!("Session({}): process response", self.state.token);
 
@@ -985,7 +996,7 @@ impl Session<WaitingForResponse> {
             return (SessionData::new_termination(), self.transition_abort(status));
         };
 
-        let (response, next) = match self.process_response_inner(&session_data, trust_anchors) {
+        let (response, next) = match self.process_response_inner(&session_data, trust_anchors, crls) {
             Ok((response, disclosed_attributes, return_url_nonce)) => {
                 (response, self.transition_finish(disclosed_attributes, return_url_nonce))
             }
@@ -1006,6 +1017,7 @@ impl Session<WaitingForResponse> {
         &self,
         session_data: &SessionData,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<(SessionData, DisclosedAttributes, Option<String>)> {
         debug!(
             "Session({}): decrypting and deserializing device response",
@@ -1024,7 +1036,9 @@ impl Session<WaitingForResponse> {
             Some(&self.state().ephemeral_privkey.0),
             &session_transcript,
             &TimeGenerator,
+            &self.state().validity_policy,
             trust_anchors,
+            crls,
         )?;
 
         debug!(
@@ -1089,6 +1103,40 @@ impl From<SessionStatus> for SessionResult {
 }
 
 impl ItemsRequests {
+    /// Create a [`DeviceRequest`] for these [`ItemsRequest`]s, including reader authentication
+    /// over the [`SessionTranscript`] for each of them.
+    pub async fn to_device_request(
+        &self,
+        session_transcript: &SessionTranscript,
+        return_url: Option<Url>,
+        private_key: &KeyPair,
+    ) -> Result<DeviceRequest> {
+        let doc_requests = try_join_all(self.0.iter().map(|items_request| async {
+            let items_request = items_request.clone().into();
+            let reader_auth = ReaderAuthenticationKeyed::new(session_transcript, &items_request);
+            let cose = MdocCose::<_, ReaderAuthenticationBytes>::sign(
+                &TaggedBytes(CborSeq(reader_auth)),
+                cose::new_certificate_header(private_key.certificate()),
+                private_key,
+                false,
+            )
+            .await?;
+            let cose = MdocCose::from(cose.0);
+            let doc_request = DocRequest {
+                items_request,
+                reader_auth: Some(cose),
+            };
+            Result::<DocRequest>::Ok(doc_request)
+        }))
+        .await?;
+
+        Ok(DeviceRequest {
+            doc_requests,
+            return_url,
+            ..Default::default()
+        })
+    }
+
     /// Checks that all `requested` attributes are disclosed in this [`DeviceResponse`].
     pub fn match_against_response(&self, device_response: &DeviceResponse) -> Result<()> {
         let not_found: Vec<_> = self
@@ -1101,8 +1149,18 @@ impl ItemsRequests {
                     .and_then(|docs| docs.iter().find(|doc| doc.doc_type == items_request.doc_type))
                     .map_or_else(
                         // If the entire document is missing then all requested attributes are missing
-                        || items_request.attribute_identifiers().into_iter().collect(),
-                        |doc| items_request.match_against_issuer_signed(doc),
+                        || {
+                            items_request
+                                .attribute_identifiers()
+                                .into_iter()
+                                .chain(items_request.self_asserted_attribute_identifiers())
+                                .collect()
+                        },
+                        |doc| {
+                            let mut missing = items_request.match_against_issuer_signed(doc);
+                            missing.extend(items_request.match_against_device_signed(doc));
+                            missing
+                        },
                     )
             })
             .collect();
@@ -1123,13 +1181,17 @@ impl DeviceResponse {
     /// - `device_authentication_bts` - the [`DeviceAuthenticationBytes`] acting as the challenge, i.e., that have
     ///   to be signed by the holder.
     /// - `time` - a generator of the current time.
+    /// - `validity_policy` - the policy against which the [`ValidityInfo`] of each mdoc is verified.
     /// - `trust_anchors` - trust anchors against which verification is done.
+    /// - `crls` - CRLs against which the issuer certificates are checked for revocation.
     pub fn verify(
         &self,
         eph_reader_key: Option<&SecretKey>,
         session_transcript: &SessionTranscript,
         time: &impl Generator<DateTime<Utc>>,
+        validity_policy: &ValidityPolicy,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<DisclosedAttributes> {
         if let Some(errors) = &self.document_errors {
             return Err(VerificationError::DeviceResponseErrors(errors.clone()).into());
@@ -1146,7 +1208,14 @@ impl DeviceResponse {
         for doc in self.documents.as_ref().unwrap() {
             debug!("verifying document with doc_type: {}", doc.doc_type);
             let (doc_type, doc_attrs) = doc
-                .verify(eph_reader_key, session_transcript, time, trust_anchors)
+                .verify(
+                    eph_reader_key,
+                    session_transcript,
+                    time,
+                    validity_policy,
+                    trust_anchors,
+                    crls,
+                )
                 .map_err(|e| {
                     warn!("document verification failed: {e}");
                     e
@@ -1167,29 +1236,101 @@ pub enum ValidityError {
     NotYetValid(String),
     #[error("expired at {0}")]
     Expired(String),
+    #[error("maximum age exceeded: signed at {0}")]
+    MaxAgeExceeded(String),
+    #[error("expected update has passed: update expected at {0}")]
+    ExpectedUpdatePassed(String),
 }
 
 /// Indicate how a [`ValidityInfo`] should be verified against the current date.
+#[derive(Debug, Clone, Copy)]
 pub enum ValidityRequirement {
     /// The [`ValidityInfo`] must not be expired, but it is allowed to be not yet valid.
     AllowNotYetValid,
     /// The [`ValidityInfo`] must be valid now and not be expired.
     Valid,
+    /// The [`ValidityInfo`] must be valid now and not be expired, taking into account the clock skew allowed by the
+    /// [`ValidityPolicy`], and must additionally satisfy the staleness requirements of the policy.
+    Policy(ValidityPolicy),
+}
+
+/// The policy of a verifier for the [`ValidityInfo`] of disclosed mdocs.
+#[serde_as]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidityPolicy {
+    /// The allowed difference between the clocks of the verifier and the issuer, by which the validity period of the
+    /// mdoc is extended on both ends.
+    #[serde(default, rename = "clock_skew_in_sec")]
+    #[serde_as(as = "DurationSeconds")]
+    pub clock_skew: Duration,
+    /// The maximum time since the mdoc was signed, after which the mdoc is considered stale.
+    #[serde(default, rename = "max_age_in_sec")]
+    #[serde_as(as = "Option<DurationSeconds>")]
+    pub max_age: Option<Duration>,
+    /// Consider the mdoc stale once its `expected_update` has passed.
+    #[serde(default)]
+    pub check_expected_update: bool,
+    /// Accept stale mdocs, flagging them in [`DocumentDisclosedAttributes`], instead of rejecting them.
+    #[serde(default)]
+    pub accept_stale: bool,
+}
+
+/// The reason why an mdoc is considered stale according to a [`ValidityPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Staleness {
+    MaxAgeExceeded,
+    ExpectedUpdatePassed,
 }
 
 impl ValidityInfo {
+    /// Verify the [`ValidityInfo`] against `time`, returning the reasons why it is considered stale if the
+    /// [`ValidityPolicy`] in `validity` accepts stale mdocs.
     pub fn verify_is_valid_at(
         &self,
         time: DateTime<Utc>,
         validity: ValidityRequirement,
-    ) -> std::result::Result<(), ValidityError> {
-        if matches!(validity, ValidityRequirement::Valid) && time < DateTime::<Utc>::try_from(&self.valid_from)? {
-            Err(ValidityError::NotYetValid(self.valid_from.0 .0.clone()))
-        } else if time > DateTime::<Utc>::try_from(&self.valid_until)? {
-            Err(ValidityError::Expired(self.valid_from.0 .0.clone()))
-        } else {
-            Ok(())
+    ) -> std::result::Result<Vec<Staleness>, ValidityError> {
+        let (allow_not_yet_valid, policy) = match validity {
+            ValidityRequirement::AllowNotYetValid => (true, ValidityPolicy::default()),
+            ValidityRequirement::Valid => (false, ValidityPolicy::default()),
+            ValidityRequirement::Policy(policy) => (false, policy),
+        };
+
+        // Extend the current time to a window that accounts for the allowed clock skew.
+        let clock_skew = chrono::Duration::from_std(policy.clock_skew).unwrap_or(chrono::Duration::max_value());
+        let earliest = time.checked_sub_signed(clock_skew).unwrap_or(DateTime::<Utc>::MIN_UTC);
+        let latest = time.checked_add_signed(clock_skew).unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+        if !allow_not_yet_valid && latest < DateTime::<Utc>::try_from(&self.valid_from)? {
+            return Err(ValidityError::NotYetValid(self.valid_from.0 .0.clone()));
+        }
+        if earliest > DateTime::<Utc>::try_from(&self.valid_until)? {
+            return Err(ValidityError::Expired(self.valid_until.0 .0.clone()));
+        }
+
+        let mut staleness = Vec::new();
+
+        if let Some(max_age) = policy.max_age {
+            let max_age = chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::max_value());
+            if earliest.signed_duration_since(DateTime::<Utc>::try_from(&self.signed)?) > max_age {
+                if !policy.accept_stale {
+                    return Err(ValidityError::MaxAgeExceeded(self.signed.0 .0.clone()));
+                }
+                staleness.push(Staleness::MaxAgeExceeded);
+            }
+        }
+
+        if let Some(expected_update) = self.expected_update.as_ref().filter(|_| policy.check_expected_update) {
+            if earliest > DateTime::<Utc>::try_from(expected_update)? {
+                if !policy.accept_stale {
+                    return Err(ValidityError::ExpectedUpdatePassed(expected_update.0 .0.clone()));
+                }
+                staleness.push(Staleness::ExpectedUpdatePassed);
+            }
         }
+
+        Ok(staleness)
     }
 }
 
@@ -1199,12 +1340,14 @@ impl IssuerSigned {
         validity: ValidityRequirement,
         time: &impl Generator<DateTime<Utc>>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<(DocumentDisclosedAttributes, MobileSecurityObject)> {
         let TaggedBytes(mso) =
             self.issuer_auth
-                .verify_against_trust_anchors(CertificateUsage::Mdl, time, trust_anchors)?;
+                .verify_against_trust_anchors(CertificateUsage::Mdl, time, trust_anchors, crls)?;
 
-        mso.validity_info
+        let staleness = mso
+            .validity_info
             .verify_is_valid_at(time.generate(), validity)
             .map_err(VerificationError::Validity)?;
 
@@ -1226,6 +1369,8 @@ impl IssuerSigned {
                 attributes: attrs,
                 issuer: self.issuer_auth.signing_cert()?.iter_common_name()?,
                 validity_info: mso.validity_info.clone(),
+                self_asserted_attributes: IndexMap::new(),
+                staleness,
             },
             mso,
         ))
@@ -1248,7 +1393,7 @@ impl MobileSecurityObject {
     }
 
     /// Given an `IssuerSignedItem` i.e. an attribute, verify that its digest is correctly included in the MSO.
-    fn verify_attr_digest(&self, namespace: &NameSpace, item: &IssuerSignedItemBytes) -> Result<()> {
+    pub fn verify_attr_digest(&self, namespace: &NameSpace, item: &IssuerSignedItemBytes) -> Result<()> {
         let digest_id = item.0.digest_id;
         let digest = self
             .value_digests
@@ -1258,7 +1403,7 @@ impl MobileSecurityObject {
             .0
             .get(&digest_id)
             .ok_or_else(|| VerificationError::MissingDigestID(digest_id))?;
-        if *digest != cbor_digest(item)? {
+        if *digest != cbor_digest_with_algorithm(item, &self.digest_algorithm)? {
             return Err(VerificationError::AttributeVerificationFailed.into());
         }
         Ok(())
@@ -1271,13 +1416,15 @@ impl Document {
         eph_reader_key: Option<&SecretKey>,
         session_transcript: &SessionTranscript,
         time: &impl Generator<DateTime<Utc>>,
+        validity_policy: &ValidityPolicy,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<(DocType, DocumentDisclosedAttributes)> {
         debug!("verifying document with doc_type: {:?}", &self.doc_type);
         debug!("verify issuer_signed");
-        let (attrs, mso) = self
-            .issuer_signed
-            .verify(ValidityRequirement::Valid, time, trust_anchors)?;
+        let (mut attrs, mso) =
+            self.issuer_signed
+                .verify(ValidityRequirement::Policy(*validity_policy), time, trust_anchors, crls)?;
 
         debug!("verifying mso.doc_type matches document doc_type");
         if self.doc_type != mso.doc_type {
@@ -1290,12 +1437,15 @@ impl Document {
 
         debug!("serializing session transcript");
         let session_transcript_bts = cbor_serialize(&TaggedBytes(session_transcript))?;
-        let device_authentication = DeviceAuthenticationKeyed::new(&self.doc_type, session_transcript);
+        let device_authentication = DeviceAuthenticationKeyed {
+            device_name_spaces_bytes: self.device_signed.name_spaces.clone(),
+            ..DeviceAuthenticationKeyed::new(&self.doc_type, session_transcript)
+        };
         debug!("serializing device_authentication");
         let device_authentication_bts = cbor_serialize(&TaggedBytes(CborSeq(device_authentication)))?;
 
         debug!("extracting device_key");
-        let device_key = (&mso.device_key_info.device_key).try_into()?;
+        let device_key: CoseVerifyingKey = (&mso.device_key_info.device_key).try_into()?;
         match &self.device_signed.device_auth {
             DeviceAuth::DeviceSignature(sig) => {
                 debug!("verifying DeviceSignature");
@@ -1304,6 +1454,10 @@ impl Document {
             }
             DeviceAuth::DeviceMac(mac) => {
                 debug!("verifying DeviceMac");
+                // We only support key agreement, and therefore MACs, for P-256 mdoc keys.
+                let CoseVerifyingKey::P256(device_key) = device_key else {
+                    return Err(VerificationError::UnsupportedDeviceMacKey(device_key.algorithm()).into());
+                };
                 let mac_key = dh_hmac_key(
                     eph_reader_key.ok_or_else(|| VerificationError::EphemeralKeyMissing)?,
                     &device_key.into(),
@@ -1317,6 +1471,16 @@ impl Document {
         }
         debug!("signature valid");
 
+        // The self-asserted attributes are authenticated by the signature or MAC above, as they are part of the
+        // `DeviceAuthentication`. They are not signed by the issuer however, which is why they are kept separate.
+        attrs.self_asserted_attributes = self
+            .device_signed
+            .name_spaces
+            .0
+            .iter()
+            .map(|(namespace, items)| (namespace.clone(), items.iter().map(Entry::from).collect()))
+            .collect();
+
         Ok((mso.doc_type, attrs))
     }
 }
@@ -1330,6 +1494,15 @@ impl ItemsRequest {
             .filter(|attribute| !document_identifiers.contains(attribute))
             .collect()
     }
+
+    /// Returns requested self-asserted attributes, if any, that are not present in the `device_signed`.
+    pub fn match_against_device_signed(&self, document: &Document) -> Vec<AttributeIdentifier> {
+        let document_identifiers = document.device_signed_attribute_identifiers();
+        self.self_asserted_attribute_identifiers()
+            .into_iter()
+            .filter(|attribute| !document_identifiers.contains(attribute))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -1411,6 +1584,97 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn validity_info_expired_reports_valid_until() {
+        let validity = new_validity_info(-2, -1);
+        let valid_until = validity.valid_until.0 .0.clone();
+
+        assert_matches!(
+            validity.verify_is_valid_at(Utc::now(), ValidityRequirement::Valid),
+            Err(ValidityError::Expired(time)) if time == valid_until
+        );
+    }
+
+    #[test]
+    fn validity_info_policy() {
+        let now = Utc::now();
+        let two_days = std::time::Duration::from_secs(2 * 24 * 60 * 60);
+
+        // Clock skew extends the validity period on both ends.
+        let skew_policy = ValidityRequirement::Policy(ValidityPolicy {
+            clock_skew: two_days,
+            ..Default::default()
+        });
+        new_validity_info(-2, -1)
+            .verify_is_valid_at(now, skew_policy)
+            .expect("expired mdoc within clock skew should be valid");
+        new_validity_info(1, 2)
+            .verify_is_valid_at(now, skew_policy)
+            .expect("not yet valid mdoc within clock skew should be valid");
+        assert_matches!(
+            new_validity_info(-5, -3).verify_is_valid_at(now, skew_policy),
+            Err(ValidityError::Expired(_))
+        );
+
+        // The mdoc was signed at `now`, so it exceeds the maximum age two days later.
+        let validity = new_validity_info(-1, 10);
+        let max_age_policy = ValidityPolicy {
+            max_age: Some(two_days),
+            ..Default::default()
+        };
+        assert!(validity
+            .verify_is_valid_at(now + Duration::days(1), ValidityRequirement::Policy(max_age_policy))
+            .unwrap()
+            .is_empty());
+        assert_matches!(
+            validity.verify_is_valid_at(now + Duration::days(3), ValidityRequirement::Policy(max_age_policy)),
+            Err(ValidityError::MaxAgeExceeded(_))
+        );
+        assert_eq!(
+            validity
+                .verify_is_valid_at(
+                    now + Duration::days(3),
+                    ValidityRequirement::Policy(ValidityPolicy {
+                        accept_stale: true,
+                        ..max_age_policy
+                    })
+                )
+                .unwrap(),
+            vec![Staleness::MaxAgeExceeded]
+        );
+
+        // The expected update is only checked when the policy requires it.
+        let validity = ValidityInfo {
+            expected_update: Some(now.add(Duration::days(1)).into()),
+            ..new_validity_info(-1, 10)
+        };
+        let expected_update_policy = ValidityPolicy {
+            check_expected_update: true,
+            ..Default::default()
+        };
+        let later = now + Duration::days(2);
+        assert!(validity
+            .verify_is_valid_at(later, ValidityRequirement::Valid)
+            .unwrap()
+            .is_empty());
+        assert_matches!(
+            validity.verify_is_valid_at(later, ValidityRequirement::Policy(expected_update_policy)),
+            Err(ValidityError::ExpectedUpdatePassed(_))
+        );
+        assert_eq!(
+            validity
+                .verify_is_valid_at(
+                    later,
+                    ValidityRequirement::Policy(ValidityPolicy {
+                        accept_stale: true,
+                        ..expected_update_policy
+                    })
+                )
+                .unwrap(),
+            vec![Staleness::ExpectedUpdatePassed]
+        );
+    }
+
     /// Verify the example disclosure from the standard.
     #[test]
     fn verify_iso_example_disclosure() {
@@ -1435,7 +1699,9 @@ mod tests {
                 Some(&eph_reader_key),
                 &DeviceAuthenticationBytes::example().0 .0.session_transcript, // To be signed by device key found in MSO
                 &IsoCertTimeGenerator,
+                &ValidityPolicy::default(),
                 trust_anchors,
+                &[],
             )
             .unwrap();
         println!("DisclosedAttributes: {:#?}", DebugCollapseBts::from(&disclosed_attrs));
@@ -1462,6 +1728,7 @@ mod tests {
         vec![ItemsRequest {
             doc_type: DISCLOSURE_DOC_TYPE.to_string(),
             request_info: None,
+            device_name_spaces: None,
             name_spaces: IndexMap::from([(
                 DISCLOSURE_NAME_SPACE.to_string(),
                 IndexMap::from_iter(
@@ -1490,6 +1757,7 @@ mod tests {
                 UseCase {
                     key_pair: ca.generate_reader_mock(reader_registration.clone()).unwrap(),
                     session_type_return_url: SessionTypeReturnUrl::Neither,
+                    validity_policy: ValidityPolicy::default(),
                 },
             ),
             (
@@ -1497,6 +1765,7 @@ mod tests {
                 UseCase {
                     key_pair: ca.generate_reader_mock(reader_registration.clone()).unwrap(),
                     session_type_return_url: SessionTypeReturnUrl::SameDevice,
+                    validity_policy: ValidityPolicy::default(),
                 },
             ),
             (
@@ -1504,6 +1773,7 @@ mod tests {
                 UseCase {
                     key_pair: ca.generate_reader_mock(reader_registration).unwrap(),
                     session_type_return_url: SessionTypeReturnUrl::Both,
+                    validity_policy: ValidityPolicy::default(),
                 },
             ),
         ])
@@ -1515,6 +1785,7 @@ mod tests {
             use_cases,
             session_store,
             trust_anchors,
+            Default::default(),
             hmac::Key::generate(hmac::HMAC_SHA256, &rand::SystemRandom::new()).unwrap(),
         )
     }
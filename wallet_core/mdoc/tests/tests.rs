@@ -34,7 +34,7 @@ use nl_wallet_mdoc::{
     },
     verifier::{
         DisclosureData, ItemsRequests, ReturnUrlTemplate, SessionType, SessionTypeReturnUrl, StatusResponse, UseCase,
-        VerificationError, Verifier,
+        ValidityPolicy, VerificationError, Verifier,
     },
     Error, ReaderEngagement,
 };
@@ -91,6 +91,7 @@ fn setup_verifier_test(
             UseCase {
                 key_pair: ca.generate_reader_mock(reader_registration.clone()).unwrap(),
                 session_type_return_url: SessionTypeReturnUrl::Neither,
+                validity_policy: ValidityPolicy::default(),
             },
         ),
         (
@@ -98,6 +99,7 @@ fn setup_verifier_test(
             UseCase {
                 key_pair: ca.generate_reader_mock(reader_registration.clone()).unwrap(),
                 session_type_return_url: SessionTypeReturnUrl::SameDevice,
+                validity_policy: ValidityPolicy::default(),
             },
         ),
         (
@@ -105,6 +107,7 @@ fn setup_verifier_test(
             UseCase {
                 key_pair: ca.generate_reader_mock(reader_registration).unwrap(),
                 session_type_return_url: SessionTypeReturnUrl::Both,
+                validity_policy: ValidityPolicy::default(),
             },
         ),
     ])
@@ -114,6 +117,7 @@ fn setup_verifier_test(
         use_cases,
         MemorySessionStore::default(),
         mdoc_trust_anchors.iter().map(|anchor| anchor.into()).collect(),
+        Default::default(),
         hmac::Key::generate(hmac::HMAC_SHA256, &rand::SystemRandom::new()).unwrap(),
     )
     .into();
@@ -340,6 +344,7 @@ async fn test_disclosure(
         disclosure_uri_source,
         &mdoc_data_source,
         &[(&verifier_ca).try_into().unwrap()],
+        &[],
     )
     .await
     .expect("starting disclosure session should succeed");
@@ -0,0 +1,299 @@
+//! Verification of the signatures, digests and validity of a decoded [`Message`], reported per check.
+
+use std::fmt::{Display, Formatter};
+
+use chrono::{DateTime, Utc};
+use p256::SecretKey;
+use webpki::TrustAnchor;
+
+use wallet_common::{generator::Generator, revocation_list::DerCertificateRevocationList};
+
+use nl_wallet_mdoc::{
+    utils::{
+        serialization::{cbor_deserialize, TaggedBytes},
+        x509::CertificateUsage,
+    },
+    verifier::{ValidityPolicy, ValidityRequirement, VerificationError},
+    Document, Error, IssuerSigned, MobileSecurityObject, SessionTranscript,
+};
+
+use crate::Message;
+
+/// A [`Generator`] that always returns the time at which the data should be verified.
+pub struct FixedTimeGenerator(pub DateTime<Utc>);
+
+impl Generator<DateTime<Utc>> for FixedTimeGenerator {
+    fn generate(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+pub enum CheckResult {
+    Passed,
+    Failed(Error),
+    Skipped(&'static str),
+}
+
+/// The result of a single verification step, e.g. the issuer signature or the digest of one attribute.
+#[derive(Debug)]
+pub struct Check {
+    pub name: String,
+    pub result: CheckResult,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, result: Result<(), impl Into<Error>>) -> Self {
+        Check {
+            name: name.into(),
+            result: match result {
+                Ok(()) => CheckResult::Passed,
+                Err(error) => CheckResult::Failed(error.into()),
+            },
+        }
+    }
+
+    fn skipped(name: impl Into<String>, reason: &'static str) -> Self {
+        Check {
+            name: name.into(),
+            result: CheckResult::Skipped(reason),
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self.result, CheckResult::Failed(_))
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.result {
+            CheckResult::Passed => write!(f, "{}: OK", self.name),
+            CheckResult::Failed(error) => write!(f, "{}: FAILED: {}", self.name, error),
+            CheckResult::Skipped(reason) => write!(f, "{}: SKIPPED: {}", self.name, reason),
+        }
+    }
+}
+
+/// The context against which a [`Message`] is verified.
+pub struct VerificationContext<'a> {
+    pub time: FixedTimeGenerator,
+    pub trust_anchors: &'a [TrustAnchor<'a>],
+    pub reader_trust_anchors: &'a [TrustAnchor<'a>],
+    pub crls: &'a [DerCertificateRevocationList],
+    pub session_transcript: Option<&'a SessionTranscript>,
+    /// The ephemeral private key of the reader, which is needed when the device authenticates using a MAC.
+    pub reader_private_key: Option<&'a SecretKey>,
+}
+
+impl Message {
+    /// Perform all checks that apply to this type of message. Checks that depend on earlier failed checks
+    /// are omitted, while checks that require a [`SessionTranscript`] that was not supplied are skipped.
+    pub fn verify(&self, context: &VerificationContext) -> Vec<Check> {
+        let mut checks = Vec::new();
+
+        match self {
+            Message::IssuerSigned(issuer_signed) => {
+                verify_issuer_signed("", issuer_signed, context, &mut checks);
+            }
+            Message::Mso(mso) => verify_validity("", mso, context, &mut checks),
+            Message::DeviceResponse(device_response) => {
+                let status = if let Some(errors) = &device_response.document_errors {
+                    Err(VerificationError::DeviceResponseErrors(errors.clone()))
+                } else if device_response.status != 0 {
+                    Err(VerificationError::UnexpectedStatus(device_response.status))
+                } else if device_response.documents.is_none() {
+                    Err(VerificationError::NoDocuments)
+                } else {
+                    Ok(())
+                };
+                checks.push(Check::new("status", status));
+
+                for document in device_response.documents.iter().flatten() {
+                    verify_document(document, context, &mut checks);
+                }
+            }
+            Message::DeviceRequest(device_request) => {
+                let name = "reader authentication";
+                let check = match context.session_transcript {
+                    Some(session_transcript) => {
+                        match device_request.verify(
+                            session_transcript,
+                            &context.time,
+                            context.reader_trust_anchors,
+                            context.crls,
+                        ) {
+                            Ok(Some(_)) => Check::new(name, Ok::<_, Error>(())),
+                            Ok(None) => Check::skipped(name, "no reader authentication present"),
+                            Err(error) => Check::new(name, Err(error)),
+                        }
+                    }
+                    None => Check::skipped(name, "no session transcript supplied"),
+                };
+                checks.push(check);
+            }
+            Message::ReaderEngagement(_) | Message::SessionTranscript(_) => {}
+        }
+
+        checks
+    }
+}
+
+fn verify_validity(prefix: &str, mso: &MobileSecurityObject, context: &VerificationContext, checks: &mut Vec<Check>) {
+    let validity = mso
+        .validity_info
+        .verify_is_valid_at(context.time.generate(), ValidityRequirement::Valid)
+        .map(|_| ())
+        .map_err(VerificationError::Validity);
+    checks.push(Check::new(format!("{prefix}validity"), validity));
+}
+
+fn verify_issuer_signed(
+    prefix: &str,
+    issuer_signed: &IssuerSigned,
+    context: &VerificationContext,
+    checks: &mut Vec<Check>,
+) -> Option<MobileSecurityObject> {
+    let signature = issuer_signed.issuer_auth.verify_against_trust_anchors(
+        CertificateUsage::Mdl,
+        &context.time,
+        context.trust_anchors,
+        context.crls,
+    );
+    checks.push(Check::new(format!("{prefix}issuer signature"), signature.map(|_| ())));
+
+    // Parse the MSO regardless of the signature being valid, so that the other checks may still be performed.
+    let mso = match issuer_signed.issuer_auth.0.payload.as_ref() {
+        Some(payload) => cbor_deserialize(payload.as_slice()).map(|TaggedBytes(mso)| mso),
+        None => {
+            checks.push(Check::skipped(format!("{prefix}mso"), "no payload present"));
+            return None;
+        }
+    };
+    let mso: MobileSecurityObject = match mso {
+        Ok(mso) => mso,
+        Err(error) => {
+            checks.push(Check::new(format!("{prefix}mso"), Err(error)));
+            return None;
+        }
+    };
+
+    verify_validity(prefix, &mso, context, checks);
+
+    for (namespace, items) in issuer_signed
+        .name_spaces
+        .iter()
+        .flat_map(|name_spaces| name_spaces.as_ref())
+    {
+        for item in items.as_ref() {
+            checks.push(Check::new(
+                format!("{prefix}digest {namespace}/{}", item.0.element_identifier),
+                mso.verify_attr_digest(namespace, item),
+            ));
+        }
+    }
+
+    Some(mso)
+}
+
+fn verify_document(document: &Document, context: &VerificationContext, checks: &mut Vec<Check>) {
+    let prefix = format!("{}: ", document.doc_type);
+
+    let Some(mso) = verify_issuer_signed(&prefix, &document.issuer_signed, context, checks) else {
+        return;
+    };
+
+    let doc_type = if document.doc_type == mso.doc_type {
+        Ok(())
+    } else {
+        Err(VerificationError::WrongDocType {
+            document: document.doc_type.clone(),
+            mso: mso.doc_type,
+        })
+    };
+    checks.push(Check::new(format!("{prefix}doc type"), doc_type));
+
+    let name = format!("{prefix}device authentication");
+    let check = match context.session_transcript {
+        // This repeats the checks above, but any failure of those has been reported already.
+        Some(session_transcript) => Check::new(
+            name,
+            document
+                .verify(
+                    context.reader_private_key,
+                    session_transcript,
+                    &context.time,
+                    &ValidityPolicy::default(),
+                    context.trust_anchors,
+                    context.crls,
+                )
+                .map(|_| ()),
+        ),
+        None => Check::skipped(name, "no session transcript supplied"),
+    };
+    checks.push(check);
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use nl_wallet_mdoc::{
+        examples::{Example, Examples},
+        DeviceResponse,
+    };
+
+    use super::*;
+
+    fn verify_example(time: DateTime<Utc>) -> Vec<Check> {
+        let context = VerificationContext {
+            time: FixedTimeGenerator(time),
+            trust_anchors: Examples::iaca_trust_anchors(),
+            reader_trust_anchors: &[],
+            crls: &[],
+            session_transcript: None,
+            reader_private_key: None,
+        };
+
+        Message::DeviceResponse(DeviceResponse::example()).verify(&context)
+    }
+
+    #[test]
+    fn test_verify_device_response() {
+        let checks = verify_example(Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap());
+
+        assert!(checks.iter().all(|check| !check.is_failed()));
+        assert!(checks.iter().any(
+            |check| check.name == "org.iso.18013.5.1.mDL: digest org.iso.18013.5.1/family_name"
+                && matches!(check.result, CheckResult::Passed)
+        ));
+        assert!(matches!(
+            checks.last().unwrap().result,
+            CheckResult::Skipped("no session transcript supplied")
+        ));
+    }
+
+    #[test]
+    fn test_verify_device_response_expired() {
+        let checks = verify_example(Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap());
+
+        let failed = checks
+            .iter()
+            .filter(|check| check.is_failed())
+            .map(|check| check.name.as_str())
+            .collect::<Vec<_>>();
+
+        // The certificate has expired as well as the MSO, but the digests are still checked.
+        assert_eq!(
+            failed,
+            vec![
+                "org.iso.18013.5.1.mDL: issuer signature",
+                "org.iso.18013.5.1.mDL: validity"
+            ]
+        );
+        assert!(matches!(
+            &checks[2].result,
+            CheckResult::Failed(Error::Verification(VerificationError::Validity(_)))
+        ));
+    }
+}
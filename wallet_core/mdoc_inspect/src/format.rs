@@ -0,0 +1,214 @@
+//! Printing of generic CBOR values as CBOR diagnostic notation (RFC 8949, section 8) or as JSON.
+//!
+//! Byte strings tagged with tag 24 (encoded CBOR data item) are decoded and printed inline,
+//! as these are used throughout ISO 18013-5 to wrap nested data structures.
+
+use ciborium::Value;
+use clap::ValueEnum;
+use serde_json::json;
+
+use nl_wallet_mdoc::utils::serialization::cbor_deserialize;
+
+const INDENT: &str = "  ";
+const TAG_ENCODED_CBOR: u64 = 24;
+/// Tags of RFC 3339 date-time strings, epoch-based date-times and RFC 3339 full-date strings respectively.
+const TAGS_DATE: [u64; 3] = [0, 1, 1004];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// CBOR diagnostic notation
+    Diagnostic,
+    /// JSON, in which byte strings are encoded as hex and other tags as objects
+    Json,
+}
+
+pub fn format_value(value: &Value, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Diagnostic => to_diagnostic(value),
+        // Serializing a `serde_json::Value` cannot fail.
+        OutputFormat::Json => serde_json::to_string_pretty(&to_json(value)).unwrap(),
+    }
+}
+
+/// Decode the contents of a byte string tagged with tag 24, if it contains valid CBOR.
+fn decode_embedded(tag: u64, value: &Value) -> Option<Value> {
+    match (tag, value) {
+        (TAG_ENCODED_CBOR, Value::Bytes(bytes)) => cbor_deserialize(bytes.as_slice()).ok(),
+        _ => None,
+    }
+}
+
+pub fn to_diagnostic(value: &Value) -> String {
+    let mut output = String::new();
+    write_diagnostic(value, 0, &mut output);
+
+    output
+}
+
+fn write_diagnostic(value: &Value, depth: usize, output: &mut String) {
+    match value {
+        Value::Integer(integer) => output.push_str(&i128::from(*integer).to_string()),
+        Value::Bytes(bytes) => output.push_str(&format!("h'{}'", hex::encode(bytes))),
+        Value::Float(float) if float.is_nan() => output.push_str("NaN"),
+        Value::Float(float) if float.is_infinite() => output.push_str(if float.is_sign_positive() {
+            "Infinity"
+        } else {
+            "-Infinity"
+        }),
+        Value::Float(float) => output.push_str(&format!("{float:?}")),
+        // Serializing a string cannot fail and results in the same escaping as diagnostic notation.
+        Value::Text(text) => output.push_str(&serde_json::to_string(text).unwrap()),
+        Value::Bool(bool) => output.push_str(&bool.to_string()),
+        Value::Null => output.push_str("null"),
+        Value::Tag(tag, tagged) => match decode_embedded(*tag, tagged) {
+            Some(embedded) => {
+                output.push_str(&format!("{tag}(<< "));
+                write_diagnostic(&embedded, depth, output);
+                output.push_str(" >>)");
+            }
+            None => {
+                output.push_str(&format!("{tag}("));
+                write_diagnostic(tagged, depth, output);
+                output.push(')');
+            }
+        },
+        Value::Array(array) => write_collection(('[', ']'), array.iter(), depth, output, |item, output| {
+            write_diagnostic(item, depth + 1, output)
+        }),
+        Value::Map(map) => write_collection(('{', '}'), map.iter(), depth, output, |(key, value), output| {
+            write_diagnostic(key, depth + 1, output);
+            output.push_str(": ");
+            write_diagnostic(value, depth + 1, output);
+        }),
+        // `Value` is non-exhaustive, but all of its variants are covered above.
+        _ => output.push_str("undefined"),
+    }
+}
+
+fn write_collection<I, T>(
+    (open, close): (char, char),
+    items: I,
+    depth: usize,
+    output: &mut String,
+    write_item: impl Fn(T, &mut String),
+) where
+    I: ExactSizeIterator<Item = T>,
+{
+    output.push(open);
+
+    if items.len() > 0 {
+        let count = items.len();
+        for (index, item) in items.enumerate() {
+            output.push('\n');
+            output.push_str(&INDENT.repeat(depth + 1));
+            write_item(item, output);
+            if index + 1 < count {
+                output.push(',');
+            }
+        }
+        output.push('\n');
+        output.push_str(&INDENT.repeat(depth));
+    }
+
+    output.push(close);
+}
+
+pub fn to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Integer(integer) => {
+            let integer = i128::from(*integer);
+            i64::try_from(integer)
+                .map(serde_json::Value::from)
+                .or_else(|_| u64::try_from(integer).map(serde_json::Value::from))
+                .unwrap_or_else(|_| integer.to_string().into())
+        }
+        Value::Bytes(bytes) => hex::encode(bytes).into(),
+        Value::Float(float) => serde_json::Number::from_f64(*float)
+            .map(serde_json::Value::Number)
+            .unwrap_or_else(|| float.to_string().into()),
+        Value::Text(text) => text.clone().into(),
+        Value::Bool(bool) => (*bool).into(),
+        Value::Null => serde_json::Value::Null,
+        Value::Tag(tag, tagged) => match decode_embedded(*tag, tagged) {
+            Some(embedded) => to_json(&embedded),
+            // Tagged dates are more readable without the tag.
+            None if TAGS_DATE.contains(tag) => to_json(tagged),
+            None => json!({ "tag": tag, "value": to_json(tagged) }),
+        },
+        Value::Array(array) => array.iter().map(to_json).collect(),
+        Value::Map(map) => map
+            .iter()
+            .map(|(key, value)| {
+                let key = match key {
+                    Value::Text(text) => text.clone(),
+                    key => to_diagnostic(key),
+                };
+                (key, to_json(value))
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into(),
+        _ => serde_json::Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ciborium::cbor;
+
+    use nl_wallet_mdoc::utils::serialization::cbor_serialize;
+
+    use super::*;
+
+    fn example_value() -> Value {
+        let embedded = cbor_serialize(&cbor!({ "a" => [1, -2] }).unwrap()).unwrap();
+
+        cbor!({
+            "text" => "quote\"",
+            1 => Value::Bytes(vec![0xde, 0xad]),
+            "tagged" => Value::Tag(24, Box::new(Value::Bytes(embedded))),
+            "date" => Value::Tag(1004, Box::new(Value::Text("2024-01-01".to_string()))),
+            "other" => Value::Tag(18, Box::new(Value::Null)),
+            "empty" => [],
+            "float" => 1.5,
+            "bool" => true,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_diagnostic() {
+        let expected = r#"{
+  "text": "quote\"",
+  1: h'dead',
+  "tagged": 24(<< {
+    "a": [
+      1,
+      -2
+    ]
+  } >>),
+  "date": 1004("2024-01-01"),
+  "other": 18(null),
+  "empty": [],
+  "float": 1.5,
+  "bool": true
+}"#;
+
+        assert_eq!(to_diagnostic(&example_value()), expected);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let expected = json!({
+            "text": "quote\"",
+            "1": "dead",
+            "tagged": { "a": [1, -2] },
+            "date": "2024-01-01",
+            "other": { "tag": 18, "value": null },
+            "empty": [],
+            "float": 1.5,
+            "bool": true,
+        });
+
+        assert_eq!(to_json(&example_value()), expected);
+    }
+}
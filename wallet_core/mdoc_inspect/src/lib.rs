@@ -0,0 +1,167 @@
+//! Decoding, pretty-printing and verification of the CBOR data structures of `nl_wallet_mdoc`,
+//! for debugging interoperability problems with other mdoc implementations.
+
+use anyhow::{Context, Result};
+use base64::prelude::*;
+use ciborium::Value;
+use clap::ValueEnum;
+
+use nl_wallet_mdoc::{
+    utils::serialization::{cbor_deserialize, TaggedBytes},
+    DeviceRequest, DeviceResponse, IssuerSigned, MobileSecurityObject, ReaderEngagement, SessionTranscript,
+};
+
+pub mod check;
+pub mod format;
+
+/// The encoding of the input data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputEncoding {
+    /// Detect the encoding, trying hex and base64 before falling back to raw CBOR
+    Auto,
+    Hex,
+    /// Standard or URL-safe base64, with or without padding
+    Base64,
+    Cbor,
+}
+
+/// The data structures that can be inspected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MessageType {
+    IssuerSigned,
+    Mso,
+    DeviceResponse,
+    DeviceRequest,
+    ReaderEngagement,
+    SessionTranscript,
+}
+
+/// A decoded data structure, see [`MessageType`].
+#[derive(Debug)]
+pub enum Message {
+    IssuerSigned(IssuerSigned),
+    Mso(MobileSecurityObject),
+    DeviceResponse(DeviceResponse),
+    DeviceRequest(DeviceRequest),
+    ReaderEngagement(ReaderEngagement),
+    SessionTranscript(SessionTranscript),
+}
+
+/// Convert the input to CBOR bytes according to the specified encoding.
+pub fn decode_input(input: &[u8], encoding: InputEncoding) -> Result<Vec<u8>> {
+    let text = || std::str::from_utf8(input).map(|text| text.split_whitespace().collect::<String>());
+
+    match encoding {
+        InputEncoding::Hex => Ok(hex::decode(text()?)?),
+        InputEncoding::Base64 => decode_base64(&text()?),
+        InputEncoding::Cbor => Ok(input.to_vec()),
+        InputEncoding::Auto => {
+            let decoded = text().ok().and_then(|text| {
+                hex::decode(&text)
+                    .ok()
+                    .or_else(|| decode_base64(&text).ok())
+                    .filter(|_| !text.is_empty())
+            });
+
+            Ok(decoded.unwrap_or_else(|| input.to_vec()))
+        }
+    }
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let bytes = BASE64_STANDARD_NO_PAD
+        .decode(text)
+        .or_else(|_| BASE64_URL_SAFE_NO_PAD.decode(text))?;
+
+    Ok(bytes)
+}
+
+impl Message {
+    /// Parse the CBOR bytes as the specified type of data structure.
+    pub fn parse(message_type: MessageType, bytes: &[u8]) -> Result<Self> {
+        let message = match message_type {
+            MessageType::IssuerSigned => Message::IssuerSigned(cbor_deserialize(bytes)?),
+            // Accept both a bare MSO and the MSO wrapped in a CBOR tag 24, as it occurs in an `IssuerSigned`.
+            MessageType::Mso => {
+                Message::Mso(cbor_deserialize(bytes).or_else(|_| cbor_deserialize(bytes).map(|TaggedBytes(mso)| mso))?)
+            }
+            MessageType::DeviceResponse => Message::DeviceResponse(cbor_deserialize(bytes)?),
+            MessageType::DeviceRequest => Message::DeviceRequest(cbor_deserialize(bytes)?),
+            MessageType::ReaderEngagement => Message::ReaderEngagement(cbor_deserialize(bytes)?),
+            MessageType::SessionTranscript => Message::SessionTranscript(cbor_deserialize(bytes)?),
+        };
+
+        Ok(message)
+    }
+}
+
+/// Decode the input and parse it as the specified type of data structure, returning the generic CBOR
+/// representation for printing as well.
+pub fn read_message(input: &[u8], encoding: InputEncoding, message_type: MessageType) -> Result<(Message, Value)> {
+    let bytes = decode_input(input, encoding).context("could not decode input")?;
+    let value: Value = cbor_deserialize(bytes.as_slice()).context("input is not valid CBOR")?;
+    let message =
+        Message::parse(message_type, &bytes).with_context(|| format!("input is not a valid {message_type:?}"))?;
+
+    Ok((message, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use nl_wallet_mdoc::{examples::Example, utils::serialization::cbor_serialize};
+
+    use super::*;
+
+    #[test]
+    fn test_decode_input() {
+        let bytes = DeviceResponse::example_bts();
+        let hex = DeviceResponse::example_hex();
+        let base64 = BASE64_STANDARD.encode(&bytes);
+        let base64_url = BASE64_URL_SAFE_NO_PAD.encode(&bytes);
+
+        assert_eq!(decode_input(hex.as_bytes(), InputEncoding::Hex).unwrap(), bytes);
+        assert_eq!(decode_input(base64.as_bytes(), InputEncoding::Base64).unwrap(), bytes);
+        assert_eq!(decode_input(&bytes, InputEncoding::Cbor).unwrap(), bytes);
+
+        for input in [hex.as_bytes(), base64.as_bytes(), base64_url.as_bytes(), &bytes] {
+            assert_eq!(decode_input(input, InputEncoding::Auto).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_read_message() {
+        let (message, _) = read_message(
+            DeviceResponse::example_hex().as_bytes(),
+            InputEncoding::Auto,
+            MessageType::DeviceResponse,
+        )
+        .unwrap();
+        assert!(matches!(message, Message::DeviceResponse(_)));
+
+        // An MSO should be accepted both with and without its tag 24 wrapper.
+        let mso = DeviceResponse::example().documents.unwrap()[0]
+            .issuer_signed
+            .issuer_auth
+            .0
+            .payload
+            .clone()
+            .unwrap();
+        let untagged = cbor_deserialize::<TaggedBytes<MobileSecurityObject>, _>(mso.as_slice())
+            .map(|TaggedBytes(mso)| cbor_serialize(&mso).unwrap())
+            .unwrap();
+        for bytes in [mso, untagged] {
+            assert!(matches!(
+                read_message(&bytes, InputEncoding::Cbor, MessageType::Mso).unwrap().0,
+                Message::Mso(_)
+            ));
+        }
+
+        read_message(
+            DeviceResponse::example_hex().as_bytes(),
+            InputEncoding::Auto,
+            MessageType::DeviceRequest,
+        )
+        .expect_err("parsing a DeviceResponse as DeviceRequest should fail");
+    }
+}
@@ -0,0 +1,164 @@
+use std::{fs, io::Read, path::PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use clio::Input;
+use p256::{pkcs8::DecodePrivateKey, SecretKey};
+use webpki::TrustAnchor;
+
+use nl_wallet_mdoc::{
+    utils::{crl::read_crls, x509::Certificate},
+    SessionTranscript,
+};
+use wallet_common::trust_anchor::DerTrustAnchor;
+
+use mdoc_inspect::{
+    check::{FixedTimeGenerator, VerificationContext},
+    format::{format_value, OutputFormat},
+    read_message, InputEncoding, Message, MessageType,
+};
+
+/// Decode, pretty-print and optionally verify mdoc data structures
+#[derive(Parser)]
+#[command(author, version, about, long_about)]
+struct Cli {
+    /// Path to the input file, or "-" to read from standard input
+    #[arg(value_parser, default_value = "-")]
+    input: Input,
+    /// The type of data structure contained in the input
+    #[arg(short, long, value_enum)]
+    r#type: MessageType,
+    /// The encoding of the input
+    #[arg(short, long, value_enum, default_value = "auto")]
+    encoding: InputEncoding,
+    /// The output format
+    #[arg(short, long, value_enum, default_value = "diagnostic")]
+    format: OutputFormat,
+    /// Verify signatures, digests and validity, reporting the result per check
+    #[arg(long, default_value = "false")]
+    verify: bool,
+    /// Path to an issuer CA certificate in PEM or DER format to verify issuer signatures against
+    #[arg(short = 'a', long)]
+    trust_anchor: Vec<PathBuf>,
+    /// Path to a reader CA certificate in PEM or DER format to verify reader authentication against
+    #[arg(long)]
+    reader_trust_anchor: Vec<PathBuf>,
+    /// Path to a CRL in PEM or DER format, or a directory containing CRLs, to check certificates for revocation
+    #[arg(long)]
+    crl: Vec<PathBuf>,
+    /// The time at which to verify, in RFC 3339 format, defaults to the current time
+    #[arg(long)]
+    time: Option<DateTime<Utc>>,
+    /// Path to the SessionTranscript of the session, which is required to verify device and reader authentication
+    #[arg(long)]
+    session_transcript: Option<PathBuf>,
+    /// Path to the ephemeral private key of the reader in PKCS#8 PEM or DER format, which is required to verify
+    /// device authentication that uses a MAC instead of a signature
+    #[arg(long)]
+    reader_private_key: Option<PathBuf>,
+}
+
+fn read_trust_anchors(paths: &[PathBuf]) -> Result<Vec<DerTrustAnchor>> {
+    paths
+        .iter()
+        .map(|path| {
+            let contents = fs::read(path).with_context(|| format!("could not read '{}'", path.display()))?;
+            let der = match std::str::from_utf8(&contents) {
+                Ok(pem) if pem.trim_start().starts_with("-----BEGIN") => {
+                    Certificate::from_pem(pem)?.as_bytes().to_vec()
+                }
+                _ => contents,
+            };
+            let trust_anchor = DerTrustAnchor::from_der(der)
+                .map_err(|error| anyhow!("invalid trust anchor '{}': {error:?}", path.display()))?;
+
+            Ok(trust_anchor)
+        })
+        .collect()
+}
+
+fn to_trust_anchors(trust_anchors: &[DerTrustAnchor]) -> Vec<TrustAnchor<'_>> {
+    trust_anchors
+        .iter()
+        .map(|anchor| (&anchor.owned_trust_anchor).into())
+        .collect()
+}
+
+fn read_session_transcript(path: &PathBuf, encoding: InputEncoding) -> Result<SessionTranscript> {
+    let contents = fs::read(path).with_context(|| format!("could not read '{}'", path.display()))?;
+
+    match read_message(&contents, encoding, MessageType::SessionTranscript)? {
+        (Message::SessionTranscript(session_transcript), _) => Ok(session_transcript),
+        _ => unreachable!(),
+    }
+}
+
+fn read_reader_private_key(path: &PathBuf) -> Result<SecretKey> {
+    let contents = fs::read(path).with_context(|| format!("could not read '{}'", path.display()))?;
+    let private_key = match std::str::from_utf8(&contents) {
+        Ok(pem) if pem.trim_start().starts_with("-----BEGIN") => SecretKey::from_pkcs8_pem(pem),
+        _ => SecretKey::from_pkcs8_der(&contents),
+    }
+    .map_err(|error| anyhow!("invalid reader private key '{}': {error}", path.display()))?;
+
+    Ok(private_key)
+}
+
+impl Cli {
+    fn execute(mut self) -> Result<()> {
+        let mut input = Vec::new();
+        self.input.read_to_end(&mut input)?;
+
+        let (message, value) = read_message(&input, self.encoding, self.r#type)?;
+        println!("{}", format_value(&value, self.format));
+
+        if !self.verify {
+            return Ok(());
+        }
+
+        let trust_anchors = read_trust_anchors(&self.trust_anchor)?;
+        let reader_trust_anchors = read_trust_anchors(&self.reader_trust_anchor)?;
+        let crls = read_crls(&self.crl)?;
+        let session_transcript = self
+            .session_transcript
+            .as_ref()
+            .map(|path| read_session_transcript(path, self.encoding))
+            .transpose()?;
+        let reader_private_key = self
+            .reader_private_key
+            .as_ref()
+            .map(read_reader_private_key)
+            .transpose()?;
+
+        let trust_anchors = to_trust_anchors(&trust_anchors);
+        let reader_trust_anchors = to_trust_anchors(&reader_trust_anchors);
+        let context = VerificationContext {
+            time: FixedTimeGenerator(self.time.unwrap_or_else(Utc::now)),
+            trust_anchors: &trust_anchors,
+            reader_trust_anchors: &reader_trust_anchors,
+            crls: &crls,
+            session_transcript: session_transcript.as_ref(),
+            reader_private_key: reader_private_key.as_ref(),
+        };
+
+        let checks = message.verify(&context);
+        println!();
+        for check in &checks {
+            println!("{check}");
+        }
+
+        let failed = checks.iter().filter(|check| check.is_failed()).count();
+        if failed > 0 {
+            bail!("{failed} of {} checks failed", checks.len());
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    cli.execute()?;
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::process::Command;
+
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use assert_fs::{prelude::*, TempDir};
+use p256::pkcs8::{EncodePrivateKey, LineEnding};
+use predicates::prelude::*;
+
+use nl_wallet_mdoc::{
+    examples::{Example, Examples},
+    utils::serialization::cbor_serialize,
+    DeviceAuthenticationBytes, DeviceResponse,
+};
+
+/// The IACA certificate from the ISO 18013-5 examples, which signed the example `DeviceResponse`.
+const IACA_CERTIFICATE_HEX: &str = "308201ce30820173a00302010202142ab4edd052b2582f4c6ad96186de70f4de5a3994300a06082a8648ce3d04030230233114301206035504030c0b75746f7069612069616361310b3009060355040613025553301e170d3230313030313030303030305a170d3239303932393030303030305a30233114301206035504030c0b75746f7069612069616361310b30090603550406130255533059301306072a8648ce3d020106082a8648ce3d030107034200042c3e103dbc07b25c5a770aeedfa5d8bd15417e3e676142461a7875e3b4188a2221e6423599d1db19aaef66f923d394b61709549bcec2ea6ff60ec75268f2e094a38184308181301e0603551d120417301581136578616d706c65406578616d706c652e636f6d301c0603551d1f041530133011a00fa00d820b6578616d706c652e636f6d301d0603551d0e0416041454fa2383a04c28e0d930792261c80c4881d2c00b300e0603551d0f0101ff04040302010630120603551d130101ff040830060101ff020100300a06082a8648ce3d0403020349003046022100ec897f0b8ae51028288955031f860069659b75989af7129fa609c24299a5c787022100d088d8741f5d05b360ef6e85023e90df1d31dd1e6701a88efe9a7103021f986c";
+
+fn setup_files() -> Result<TempDir> {
+    let temp = TempDir::new()?;
+    temp.child("response.hex").write_str(DeviceResponse::example_hex())?;
+    temp.child("iaca.der")
+        .write_binary(&hex::decode(IACA_CERTIFICATE_HEX)?)?;
+
+    Ok(temp)
+}
+
+#[test]
+fn inspect_device_response_diagnostic() -> Result<()> {
+    let temp = setup_files()?;
+
+    let mut cmd = Command::cargo_bin("mdoc-inspect")?;
+    cmd.arg(temp.child("response.hex").path())
+        .arg("--type")
+        .arg("device-response");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""docType": "org.iso.18013.5.1.mDL""#))
+        .stdout(predicate::str::contains("24(<<"));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_device_response_json_from_stdin() -> Result<()> {
+    // Writing to stdin is only supported by the `Command` wrapper of `assert_cmd`.
+    let mut cmd = assert_cmd::Command::cargo_bin("mdoc-inspect")?;
+    cmd.args(["--type", "device-response", "--format", "json", "--encoding", "hex"])
+        .write_stdin(DeviceResponse::example_hex());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""elementIdentifier": "family_name""#));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_device_response_verify() -> Result<()> {
+    let temp = setup_files()?;
+
+    let mut cmd = Command::cargo_bin("mdoc-inspect")?;
+    cmd.arg(temp.child("response.hex").path())
+        .args([
+            "--type",
+            "device-response",
+            "--verify",
+            "--time",
+            "2021-01-01T00:00:00Z",
+        ])
+        .arg("--trust-anchor")
+        .arg(temp.child("iaca.der").path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("org.iso.18013.5.1.mDL: issuer signature: OK"))
+        .stdout(predicate::str::contains(
+            "org.iso.18013.5.1.mDL: digest org.iso.18013.5.1/family_name: OK",
+        ))
+        .stdout(predicate::str::contains(
+            "org.iso.18013.5.1.mDL: device authentication: SKIPPED",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_device_response_verify_device_mac() -> Result<()> {
+    let temp = setup_files()?;
+
+    // The example `DeviceResponse` authenticates the device using a MAC, which requires both
+    // the `SessionTranscript` and the ephemeral private key of the reader.
+    let session_transcript = &DeviceAuthenticationBytes::example().0 .0.session_transcript;
+    temp.child("session_transcript.hex")
+        .write_str(&hex::encode(cbor_serialize(session_transcript)?))?;
+    temp.child("reader.pem")
+        .write_str(&Examples::ephemeral_reader_key().to_pkcs8_pem(LineEnding::LF)?)?;
+
+    let mut cmd = Command::cargo_bin("mdoc-inspect")?;
+    cmd.arg(temp.child("response.hex").path())
+        .args([
+            "--type",
+            "device-response",
+            "--verify",
+            "--time",
+            "2021-01-01T00:00:00Z",
+        ])
+        .arg("--trust-anchor")
+        .arg(temp.child("iaca.der").path())
+        .arg("--session-transcript")
+        .arg(temp.child("session_transcript.hex").path())
+        .arg("--reader-private-key")
+        .arg(temp.child("reader.pem").path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("org.iso.18013.5.1.mDL: issuer signature: OK"))
+        .stdout(predicate::str::contains(
+            "org.iso.18013.5.1.mDL: device authentication: OK",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_device_response_verify_device_mac_without_reader_key() -> Result<()> {
+    let temp = setup_files()?;
+
+    let session_transcript = &DeviceAuthenticationBytes::example().0 .0.session_transcript;
+    temp.child("session_transcript.hex")
+        .write_str(&hex::encode(cbor_serialize(session_transcript)?))?;
+
+    let mut cmd = Command::cargo_bin("mdoc-inspect")?;
+    cmd.arg(temp.child("response.hex").path())
+        .args([
+            "--type",
+            "device-response",
+            "--verify",
+            "--time",
+            "2021-01-01T00:00:00Z",
+        ])
+        .arg("--trust-anchor")
+        .arg(temp.child("iaca.der").path())
+        .arg("--session-transcript")
+        .arg(temp.child("session_transcript.hex").path());
+    cmd.assert().failure().stdout(predicate::str::contains(
+        "org.iso.18013.5.1.mDL: device authentication: FAILED",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_device_response_verify_expired() -> Result<()> {
+    let temp = setup_files()?;
+
+    let mut cmd = Command::cargo_bin("mdoc-inspect")?;
+    cmd.arg(temp.child("response.hex").path())
+        .args([
+            "--type",
+            "device-response",
+            "--verify",
+            "--time",
+            "2022-01-01T00:00:00Z",
+        ])
+        .arg("--trust-anchor")
+        .arg(temp.child("iaca.der").path());
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("org.iso.18013.5.1.mDL: validity: FAILED"))
+        .stderr(predicate::str::contains("checks failed"));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_wrong_type() -> Result<()> {
+    let temp = setup_files()?;
+
+    let mut cmd = Command::cargo_bin("mdoc-inspect")?;
+    cmd.arg(temp.child("response.hex").path())
+        .args(["--type", "device-request"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("input is not a valid DeviceRequest"));
+
+    Ok(())
+}
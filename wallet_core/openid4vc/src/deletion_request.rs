@@ -0,0 +1,274 @@
+//! Requests from the wallet to a Relying Party to delete the attributes that were disclosed to it in an earlier
+//! OpenID4VP session. The wallet sends these to the endpoint that the Relying Party advertises in the
+//! [`DeletionPolicy`](nl_wallet_mdoc::utils::reader_auth::DeletionPolicy) of its reader registration.
+//!
+//! A deletion request is a JWT which includes the public key with which it can be verified in its header, much
+//! like a DPoP JWT (see [`crate::dpop`]). The wallet signs each deletion request with a fresh key, so that Relying
+//! Parties cannot link deletion requests from the same wallet to each other. The session is identified by the `state`
+//! that the verifier included in its Authorization Request, which the wallet stores in its history.
+//!
+//! Anyone can compute a valid signature, so the signature does not prove that the sender took part in the session.
+//! Instead, after a successful disclosure the verifier returns a `deletion_token` in its
+//! [`VpResponse`](crate::openid4vp::VpResponse): a HMAC over the `client_id` and the session, which only the wallet
+//! that disclosed receives. The verifier checks this token before forwarding a deletion request to the Relying Party,
+//! see [`Verifier::verify_deletion_request()`](crate::verifier::Verifier::verify_deletion_request).
+//!
+//! Example deletion request JWT header and body:
+//! ```json
+//! {
+//!   "typ": "deletion-request+jwt",
+//!   "alg": "ES256",
+//!   "jwk": {
+//!     "kty": "EC",
+//!     "crv": "P-256"
+//!     "x": "l8tFrhx-34tV3hRICRDY9zCkDlpBhF42UQUfWVAWBFs",
+//!     "y": "9VE4jf_Ok_o64zbTTlcuNJajHmt6v9TDVrU0CdvGRDA",
+//!   }
+//! }
+//! .
+//! {
+//!   "client_id": "example.com",
+//!   "session_reference": "dS8kDW3H7FVfqJPVpYCKXFMW9eqGXQYp",
+//!   "deletion_token": "5b1d6c2e4f1a8b9d0e3c7a6f2b4d8e1c9a0f3b5d7c2e6a4f8b1d3c5e7a9f0b2d",
+//!   "attributes": [
+//!     { "doc_type": "com.example.pid", "namespace": "com.example.pid", "attribute": "bsn" }
+//!   ],
+//!   "jti": "-BwC3ESc6acc2lTc",
+//!   "iat": 1562262616
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use chrono::{serde::ts_seconds, DateTime, Utc};
+use futures::TryFutureExt;
+use jsonwebtoken::{Algorithm, Validation};
+use p256::ecdsa::VerifyingKey;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use nl_wallet_mdoc::identifiers::AttributeIdentifier;
+use wallet_common::{
+    jwt::{EcdsaDecodingKey, Jwt, JwtError},
+    keys::EcdsaKey,
+    utils::random_string,
+};
+
+use crate::{
+    disclosure_session::VpMessageClientError,
+    jwt::{jwk_jwt_header, jwk_to_p256, JwkConversionError},
+    ErrorResponse,
+};
+
+pub const DELETION_REQUEST_JWT_TYPE: &str = "deletion-request+jwt";
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeletionRequestError {
+    #[error("unexpected JWT type: expected {DELETION_REQUEST_JWT_TYPE}, found {0:?}")]
+    UnexpectedJwtType(Option<String>),
+    #[error("missing JWK")]
+    MissingJwk,
+    #[error("failed to convert key from/to JWK format: {0}")]
+    JwkConversion(#[from] JwkConversionError),
+    #[error("JWT decoding failed: {0}")]
+    JwtDecodingFailed(#[from] jsonwebtoken::errors::Error),
+    #[error("JWT error: {0}")]
+    Jwt(#[from] JwtError),
+    #[error("deletion request contains no attributes")]
+    NoAttributes,
+    #[error("deletion token does not match the session")]
+    InvalidDeletionToken,
+    #[error("unknown relying party: {0}")]
+    UnknownRelyingParty(String),
+    #[error("relying party does not accept deletion requests: {0}")]
+    DeletionNotSupported(String),
+    #[error("could not forward deletion request to relying party: {0}")]
+    Forwarding(#[source] reqwest::Error),
+}
+
+/// The payload of a deletion request JWT.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletionRequestPayload {
+    /// The `client_id` of the Relying Party, i.e. the SAN DNS name of its certificate.
+    pub client_id: String,
+    /// The `state` of the Authorization Request of the session in which the attributes were disclosed.
+    pub session_reference: String,
+    /// The token that the verifier issued to the wallet at the end of the session, see the module documentation.
+    pub deletion_token: String,
+    pub attributes: Vec<AttributeIdentifier>,
+    pub jti: String,
+    #[serde(with = "ts_seconds")]
+    pub iat: DateTime<Utc>,
+}
+
+/// The form that the wallet posts to the deletion request endpoint of the Relying Party.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletionRequestForm {
+    pub deletion_request: Jwt<DeletionRequestPayload>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeletionRequest(pub Jwt<DeletionRequestPayload>);
+
+impl DeletionRequest {
+    pub async fn new(
+        private_key: &impl EcdsaKey,
+        client_id: String,
+        session_reference: String,
+        deletion_token: String,
+        attributes: Vec<AttributeIdentifier>,
+    ) -> Result<Self, DeletionRequestError> {
+        let header = jwk_jwt_header(DELETION_REQUEST_JWT_TYPE, private_key).await?;
+
+        let payload = DeletionRequestPayload {
+            client_id,
+            session_reference,
+            deletion_token,
+            attributes,
+            jti: random_string(32),
+            iat: Utc::now(),
+        };
+
+        let jwt = Jwt::sign(&payload, &header, private_key).await?;
+        Ok(Self(jwt))
+    }
+
+    /// Verify the deletion request JWT against the public key inside its header, returning its payload and that
+    /// public key. Note that, like for DPoP, the `iat` and `jti` fields are not checked by the verifier. Neither is the
+    /// `deletion_token`, which requires the secret of the verifier.
+    pub fn verify(&self) -> Result<(DeletionRequestPayload, VerifyingKey), DeletionRequestError> {
+        let header = jsonwebtoken::decode_header(&self.0 .0)?;
+        if header.typ.as_deref() != Some(DELETION_REQUEST_JWT_TYPE) {
+            return Err(DeletionRequestError::UnexpectedJwtType(header.typ));
+        }
+        let verifying_key = jwk_to_p256(&header.jwk.ok_or(DeletionRequestError::MissingJwk)?)?;
+
+        let mut validation_options = Validation::new(Algorithm::ES256);
+        validation_options.required_spec_claims = HashSet::default();
+        let payload = jsonwebtoken::decode::<DeletionRequestPayload>(
+            &self.0 .0,
+            &EcdsaDecodingKey::from(&verifying_key).0,
+            &validation_options,
+        )?
+        .claims;
+
+        if payload.attributes.is_empty() {
+            return Err(DeletionRequestError::NoAttributes);
+        }
+
+        Ok((payload, verifying_key))
+    }
+
+    /// Send the deletion request to the endpoint of the Relying Party.
+    pub async fn send(self, http_client: &reqwest::Client, url: Url) -> Result<(), VpMessageClientError> {
+        http_client
+            .post(url)
+            .form(&DeletionRequestForm {
+                deletion_request: self.0,
+            })
+            .send()
+            .map_err(VpMessageClientError::from)
+            .and_then(|response| async {
+                // If the HTTP response code is 4xx or 5xx, parse the JSON as an error
+                let status = response.status();
+                if status.is_client_error() || status.is_server_error() {
+                    let error = response.json::<ErrorResponse<String>>().await?;
+                    Err(VpMessageClientError::ErrorResponse(error))
+                } else {
+                    Ok(())
+                }
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use p256::{ecdsa::SigningKey, elliptic_curve::rand_core::OsRng};
+
+    use super::*;
+
+    fn attributes() -> Vec<AttributeIdentifier> {
+        vec![AttributeIdentifier {
+            doc_type: "com.example.pid".to_string(),
+            namespace: "com.example.pid".to_string(),
+            attribute: "bsn".to_string(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn test_deletion_request() {
+        let private_key = SigningKey::random(&mut OsRng);
+
+        let deletion_request = DeletionRequest::new(
+            &private_key,
+            "example.com".to_string(),
+            "session_token".to_string(),
+            "deletion_token".to_string(),
+            attributes(),
+        )
+        .await
+        .unwrap();
+
+        let (payload, verifying_key) = deletion_request.verify().expect("deletion request should be valid");
+
+        assert_eq!(verifying_key, *private_key.verifying_key());
+        assert_eq!(payload.client_id, "example.com");
+        assert_eq!(payload.session_reference, "session_token");
+        assert_eq!(payload.deletion_token, "deletion_token");
+        assert_eq!(payload.attributes, attributes());
+    }
+
+    #[tokio::test]
+    async fn test_deletion_request_error_no_attributes() {
+        let private_key = SigningKey::random(&mut OsRng);
+
+        let deletion_request = DeletionRequest::new(
+            &private_key,
+            "example.com".to_string(),
+            "session_token".to_string(),
+            "deletion_token".to_string(),
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        assert_matches!(deletion_request.verify(), Err(DeletionRequestError::NoAttributes));
+    }
+
+    #[tokio::test]
+    async fn test_deletion_request_error_signature() {
+        let private_key = SigningKey::random(&mut OsRng);
+
+        let deletion_request = DeletionRequest::new(
+            &private_key,
+            "example.com".to_string(),
+            "session_token".to_string(),
+            "deletion_token".to_string(),
+            attributes(),
+        )
+        .await
+        .unwrap();
+
+        // Replace the payload with that of another deletion request, which invalidates the signature.
+        let other_request = DeletionRequest::new(
+            &private_key,
+            "example.com".to_string(),
+            "other_session_token".to_string(),
+            "deletion_token".to_string(),
+            attributes(),
+        )
+        .await
+        .unwrap();
+
+        let mut parts = deletion_request.0 .0.split('.').collect::<Vec<_>>();
+        parts[1] = other_request.0 .0.split('.').nth(1).unwrap();
+        let tampered_request = DeletionRequest(parts.join(".").into());
+
+        assert_matches!(
+            tampered_request.verify(),
+            Err(DeletionRequestError::JwtDecodingFailed(_))
+        );
+    }
+}
@@ -1,19 +1,22 @@
 use derive_more::From;
 use futures::TryFutureExt;
-use itertools::Itertools;
+use indexmap::IndexMap;
 use mime::Mime;
 use once_cell::sync::Lazy;
+use p256::PublicKey;
 use reqwest::{header::ACCEPT, Method, Response};
 use tracing::{info, warn};
 
-use wallet_common::{config::wallet_config::BaseUrl, jwt::Jwt, utils::random_string};
+use wallet_common::{
+    config::wallet_config::BaseUrl, jwt::Jwt, revocation_list::DerCertificateRevocationList, utils::random_string,
+};
 
 use nl_wallet_mdoc::{
     disclosure::DeviceResponse,
     engagement::SessionTranscript,
     holder::{
-        DisclosureError, DisclosureRequestMatch, DisclosureUriSource, MdocDataSource, ProposedAttributes,
-        ProposedDocument, TrustAnchor,
+        CandidateAttributes, DeviceAuthMethod, DisclosureError, DisclosureRequestMatch, DisclosureUriSource,
+        MdocDataSource, ProposedAttributes, ProposedDocumentCandidates, SelfAssertedAttributes, TrustAnchor,
     },
     identifiers::AttributeIdentifier,
     utils::{
@@ -22,6 +25,7 @@ use nl_wallet_mdoc::{
         x509::{Certificate, CertificateError, CertificateType},
     },
     verifier::SessionType,
+    DocType,
 };
 
 use crate::{
@@ -51,10 +55,10 @@ pub enum VpClientError {
     MatchRequestedAttributes(#[source] nl_wallet_mdoc::Error),
     #[error("error parsing RP certificate: {0}")]
     RpCertificate(#[from] CertificateError),
-    #[error("multiple candidates for disclosure is unsupported, found for doc types: {}", .0.join(", "))]
-    MultipleCandidates(Vec<String>),
     #[error("error encrypting Authorization Response: {0}")]
     AuthResponseEncryption(#[from] AuthResponseError),
+    #[error("error converting verifier encryption key to P-256 public key: {0}")]
+    ReaderKey(#[source] p256::elliptic_curve::Error),
     #[error("error deserializing request_uri object: {0}")]
     RequestUri(#[source] serde_urlencoded::de::Error),
     #[error("missing session_type query parameter in request URI")]
@@ -83,11 +87,7 @@ pub trait VpMessageClient {
         wallet_nonce: Option<String>,
     ) -> Result<Jwt<VpAuthorizationRequest>, VpMessageClientError>;
 
-    async fn send_authorization_response(
-        &self,
-        url: BaseUrl,
-        jwe: String,
-    ) -> Result<Option<BaseUrl>, VpMessageClientError>;
+    async fn send_authorization_response(&self, url: BaseUrl, jwe: String) -> Result<VpResponse, VpMessageClientError>;
 
     async fn send_error(
         &self,
@@ -155,11 +155,7 @@ impl VpMessageClient for HttpVpMessageClient {
             .await
     }
 
-    async fn send_authorization_response(
-        &self,
-        url: BaseUrl,
-        jwe: String,
-    ) -> Result<Option<BaseUrl>, VpMessageClientError> {
+    async fn send_authorization_response(&self, url: BaseUrl, jwe: String) -> Result<VpResponse, VpMessageClientError> {
         self.http_client
             .post(url.into_inner())
             .form(&VpToken { vp_token: jwe })
@@ -192,20 +188,20 @@ impl VpMessageClient for HttpVpMessageClient {
             .error_for_status()?;
 
         let response = Self::deserialize_vp_response(response).await?;
-        Ok(response)
+        Ok(response.redirect_uri)
     }
 }
 
 impl HttpVpMessageClient {
     /// If the RP does not wish to specify a redirect URI, e.g. in case of cross device flows, then the spec does not say
     /// whether the RP should send an empty JSON object, i.e. `{}`, or no body at all. So this function accepts both.
-    async fn deserialize_vp_response(response: Response) -> Result<Option<BaseUrl>, VpMessageClientError> {
+    async fn deserialize_vp_response(response: Response) -> Result<VpResponse, VpMessageClientError> {
         let response_bytes = response.bytes().await?;
         if response_bytes.is_empty() {
-            return Ok(None);
+            return Ok(VpResponse::default());
         }
         let response: VpResponse = serde_json::from_slice(&response_bytes)?;
-        Ok(response.redirect_uri)
+        Ok(response)
     }
 }
 
@@ -218,13 +214,13 @@ pub enum DisclosureSession<H, I> {
 #[derive(Debug)]
 pub struct DisclosureMissingAttributes<H> {
     data: CommonDisclosureData<H>,
-    missing_attributes: Vec<AttributeIdentifier>,
+    missing_attributes: Vec<Vec<AttributeIdentifier>>,
 }
 
 #[derive(Debug)]
 pub struct DisclosureProposal<H, I> {
     data: CommonDisclosureData<H>,
-    proposed_documents: Vec<ProposedDocument<I>>,
+    candidates: ProposedDocumentCandidates<I>,
     mdoc_nonce: String,
 }
 
@@ -238,8 +234,8 @@ struct CommonDisclosureData<H> {
 }
 
 enum VerifierSessionDataCheckResult<I> {
-    MissingAttributes(Vec<AttributeIdentifier>),
-    ProposedDocuments(Vec<ProposedDocument<I>>),
+    MissingAttributes(Vec<Vec<AttributeIdentifier>>),
+    Candidates(ProposedDocumentCandidates<I>),
 }
 
 impl<H, I> DisclosureSession<H, I>
@@ -252,6 +248,7 @@ where
         uri_source: DisclosureUriSource,
         mdoc_data_source: &S,
         trust_anchors: &[TrustAnchor<'a>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Self, VpClientError>
     where
         S: MdocDataSource<MdocIdentifier = I>,
@@ -289,7 +286,7 @@ where
             .get_authorization_request(request_uri_object.request_uri.clone(), request_nonce.clone())
             .await?;
 
-        let (auth_request, certificate) = VpAuthorizationRequest::verify(&jws, trust_anchors, request_nonce)?;
+        let (auth_request, certificate) = VpAuthorizationRequest::verify(&jws, trust_anchors, crls, request_nonce)?;
 
         let mdoc_nonce = random_string(32);
         let session_transcript = SessionTranscript::new_oid4vp(
@@ -326,13 +323,11 @@ where
                     missing_attributes,
                 })
             }
-            VerifierSessionDataCheckResult::ProposedDocuments(proposed_documents) => {
-                DisclosureSession::Proposal(DisclosureProposal {
-                    data,
-                    proposed_documents,
-                    mdoc_nonce,
-                })
-            }
+            VerifierSessionDataCheckResult::Candidates(candidates) => DisclosureSession::Proposal(DisclosureProposal {
+                data,
+                candidates,
+                mdoc_nonce,
+            }),
         };
 
         Ok(session)
@@ -412,22 +407,9 @@ where
             }
         };
 
-        // If we have multiple candidates for any of the doc types, return an error.
-        // TODO: Support having the user choose between multiple candidates. (PVW-1392)
-        if candidates_by_doc_type.values().any(|candidates| candidates.len() > 1) {
-            let duplicate_doc_types = candidates_by_doc_type
-                .into_iter()
-                .filter(|(_, candidates)| candidates.len() > 1)
-                .map(|(doc_type, _)| doc_type)
-                .collect();
-
-            return Err(VpClientError::MultipleCandidates(duplicate_doc_types));
-        }
-
-        // Now that we know that we have exactly one candidate for every `doc_type`,
-        // we can flatten these candidates to a 1-dimensional `Vec`.
-        let proposed_documents = candidates_by_doc_type.into_values().flatten().collect_vec();
-        let result = VerifierSessionDataCheckResult::ProposedDocuments(proposed_documents);
+        // Keep all of the candidates, so that the user may choose between them.
+        let candidates = ProposedDocumentCandidates::new(candidates_by_doc_type);
+        let result = VerifierSessionDataCheckResult::Candidates(candidates);
 
         Ok((result, reader_registration))
     }
@@ -459,7 +441,8 @@ where
 }
 
 impl<H> DisclosureMissingAttributes<H> {
-    pub fn missing_attributes(&self) -> &[AttributeIdentifier] {
+    /// Returns the missing attributes for every `Mdoc` candidate.
+    pub fn missing_attributes(&self) -> &[Vec<AttributeIdentifier>] {
         &self.missing_attributes
     }
 }
@@ -470,37 +453,92 @@ where
     I: Clone,
 {
     pub fn proposed_source_identifiers(&self) -> Vec<&I> {
-        self.proposed_documents
-            .iter()
+        self.candidates
+            .selected()
             .map(|document| &document.source_identifier)
             .collect()
     }
 
+    /// Returns the `state` of the Authorization Request, which the verifier can use to identify the session.
+    pub fn session_reference(&self) -> Option<&str> {
+        self.data.auth_request.state.as_deref()
+    }
+
     pub fn proposed_attributes(&self) -> ProposedAttributes {
         // Get all of the attributes to be disclosed from the
-        // prepared `IssuerSigned` on the `ProposedDocument`s.
-        self.proposed_documents
-            .iter()
+        // prepared `IssuerSigned` on the selected `ProposedDocument`s.
+        self.candidates
+            .selected()
             .map(|document| (document.doc_type.clone(), document.proposed_attributes()))
             .collect()
     }
 
-    pub async fn disclose<KF, K>(&self, key_factory: &KF) -> Result<Option<BaseUrl>, DisclosureError<VpClientError>>
+    /// Returns the attributes of all of the candidates that match the request, per doc type.
+    pub fn candidate_attributes(&self) -> CandidateAttributes {
+        self.candidates.candidate_attributes()
+    }
+
+    /// Returns the index of the selected candidate for every doc type, in the order of [`Self::candidate_attributes`].
+    pub fn selected_candidates(&self) -> &[usize] {
+        self.candidates.selection()
+    }
+
+    /// Selects the candidate to disclose for every doc type, using indices into [`Self::candidate_attributes`].
+    pub fn select_candidates(&mut self, selection: &[usize]) -> Result<(), nl_wallet_mdoc::Error> {
+        self.candidates.select(selection)
+    }
+
+    /// Returns the self-asserted attributes that the verifier requested, per doc type, of which the values
+    /// should be provided using [`Self::set_self_asserted_attributes`] before disclosing.
+    pub fn requested_self_asserted_attributes(&self) -> IndexMap<DocType, Vec<AttributeIdentifier>> {
+        self.candidates.requested_self_asserted_attributes()
+    }
+
+    /// Sets the values of the requested self-asserted attributes, which are disclosed along with the selected
+    /// candidates but are not signed by their issuer.
+    pub fn set_self_asserted_attributes(
+        &mut self,
+        self_asserted_attributes: SelfAssertedAttributes,
+    ) -> Result<(), nl_wallet_mdoc::Error> {
+        self.candidates.set_self_asserted_attributes(self_asserted_attributes)
+    }
+
+    /// Disclose the selected candidates, returning the response of the verifier, which contains the redirect URI and
+    /// the deletion token, if any.
+    pub async fn disclose<KF, K>(&self, key_factory: &KF) -> Result<VpResponse, DisclosureError<VpClientError>>
     where
         KF: KeyFactory<Key = K>,
         K: MdocEcdsaKey,
     {
         info!("disclose proposed documents");
 
-        // Clone the proposed documents and construct a `DeviceResponse` by
-        // signing these, then encrypt the response to the RP's public key.
-        let proposed_documents = self.proposed_documents.clone();
+        // Clone the selected proposed documents and construct a `DeviceResponse`
+        // by authenticating these, then encrypt the response to the RP's public key.
+        let proposed_documents = self.candidates.selected().cloned().collect();
 
-        info!("sign proposed documents");
+        // The key that the RP wants us to encrypt our response to doubles as its ephemeral reader key,
+        // so that we can authenticate the disclosed documents using a MAC instead of a signature.
+        let reader_key = PublicKey::from_jwk_str(&self.data.auth_request.encryption_pubkey.to_string())
+            .map_err(|err| DisclosureError::before_sharing(VpClientError::ReaderKey(err)))?;
+        let session_transcript = SessionTranscript::new_oid4vp(
+            &self.data.auth_request.response_uri,
+            &self.data.auth_request.client_id,
+            self.data.auth_request.nonce.clone(),
+            &self.mdoc_nonce,
+        );
 
-        let device_response = DeviceResponse::from_proposed_documents(proposed_documents, key_factory)
-            .await
-            .map_err(|err| DisclosureError::before_sharing(VpClientError::DeviceResponse(err)))?;
+        info!("authenticate proposed documents");
+
+        let device_response = DeviceResponse::from_proposed_documents(
+            proposed_documents,
+            DeviceAuthMethod::Mac {
+                reader_key: &reader_key,
+                session_transcript: &session_transcript,
+            },
+            key_factory,
+        )
+        .await
+        .map_err(|err| DisclosureError::before_sharing(VpClientError::DeviceResponse(err)))?;
 
         info!("serialize and encrypt Authorization Response");
 
@@ -509,7 +547,7 @@ where
 
         info!("send Authorization Response to verifier");
 
-        let redirect_uri = self
+        let response = self
             .data
             .client
             .send_authorization_response(self.data.auth_request.response_uri.clone(), jwe)
@@ -519,7 +557,7 @@ where
             })?;
 
         info!("sending Authorization Response succeeded");
-        Ok(redirect_uri)
+        Ok(response)
     }
 }
 
@@ -5,6 +5,7 @@ use url::Url;
 use wallet_common::http_error::{HttpJsonError, HttpJsonErrorType};
 
 use crate::{
+    deletion_request::DeletionRequestError,
     issuer::{CredentialRequestError, IssuanceError, TokenRequestError},
     verifier::{GetAuthRequestError, PostAuthResponseError, SessionError, VerificationError},
 };
@@ -121,7 +122,8 @@ impl From<TokenRequestError> for ErrorResponse<TokenErrorCode> {
         ErrorResponse {
             error: match err {
                 TokenRequestError::IssuanceError(IssuanceError::SessionStore(_))
-                | TokenRequestError::AttributeService(_) => TokenErrorCode::ServerError,
+                | TokenRequestError::AttributeService(_)
+                | TokenRequestError::InvalidMdl(_) => TokenErrorCode::ServerError,
                 TokenRequestError::IssuanceError(_) => TokenErrorCode::InvalidRequest,
                 TokenRequestError::UnsupportedTokenRequestType => TokenErrorCode::UnsupportedGrantType,
             },
@@ -240,6 +242,52 @@ impl ErrorStatusCode for PostAuthResponseErrorCode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionRequestErrorCode {
+    InvalidRequest,
+    InvalidDeletionToken,
+    UnknownRelyingParty,
+    DeletionNotSupported,
+
+    ServerError,
+}
+
+impl From<DeletionRequestError> for ErrorResponse<DeletionRequestErrorCode> {
+    fn from(err: DeletionRequestError) -> Self {
+        let description = err.to_string();
+        ErrorResponse {
+            error: match err {
+                DeletionRequestError::UnexpectedJwtType(_)
+                | DeletionRequestError::MissingJwk
+                | DeletionRequestError::JwkConversion(_)
+                | DeletionRequestError::JwtDecodingFailed(_)
+                | DeletionRequestError::Jwt(_)
+                | DeletionRequestError::NoAttributes => DeletionRequestErrorCode::InvalidRequest,
+                DeletionRequestError::InvalidDeletionToken => DeletionRequestErrorCode::InvalidDeletionToken,
+                DeletionRequestError::UnknownRelyingParty(_) => DeletionRequestErrorCode::UnknownRelyingParty,
+                DeletionRequestError::DeletionNotSupported(_) => DeletionRequestErrorCode::DeletionNotSupported,
+                DeletionRequestError::Forwarding(_) => DeletionRequestErrorCode::ServerError,
+            },
+            error_description: Some(description),
+            error_uri: None,
+        }
+    }
+}
+
+impl ErrorStatusCode for DeletionRequestErrorCode {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeletionRequestErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+            DeletionRequestErrorCode::InvalidDeletionToken => StatusCode::FORBIDDEN,
+            DeletionRequestErrorCode::UnknownRelyingParty | DeletionRequestErrorCode::DeletionNotSupported => {
+                StatusCode::NOT_FOUND
+            }
+            DeletionRequestErrorCode::ServerError => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
 // The `VerificationError` and `VerificationErrorCode` is handled differently from the errors above:
 // instead of returning them as an `ErrorResponse`, they are returned as a `HttpJsonErrorBody`.
 // This is because the endpoints that return these errors are not part of a protocol from the
@@ -22,7 +22,10 @@ use nl_wallet_mdoc::{
     },
     ATTR_RANDOM_LENGTH,
 };
-use wallet_common::{config::wallet_config::BaseUrl, generator::TimeGenerator, jwt::JwtError};
+use wallet_common::{
+    config::wallet_config::BaseUrl, generator::TimeGenerator, jwt::JwtError,
+    revocation_list::DerCertificateRevocationList,
+};
 
 use crate::{
     credential::{
@@ -94,6 +97,7 @@ pub trait IssuanceSession<H = HttpVcMessageClient> {
         base_url: BaseUrl,
         token_request: TokenRequest,
         trust_anchors: &[TrustAnchor<'_>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<(Self, Vec<AttestationPreview>), IssuanceSessionError>
     where
         Self: Sized;
@@ -101,6 +105,7 @@ pub trait IssuanceSession<H = HttpVcMessageClient> {
     async fn accept_issuance<K: MdocEcdsaKey>(
         &self,
         mdoc_trust_anchors: &[TrustAnchor<'_>],
+        crls: &[DerCertificateRevocationList],
         key_factory: impl KeyFactory<Key = K>,
         credential_issuer_identifier: BaseUrl,
     ) -> Result<Vec<MdocCopies>, IssuanceSessionError>;
@@ -316,6 +321,7 @@ impl<H: VcMessageClient> IssuanceSession<H> for HttpIssuanceSession<H> {
         base_url: BaseUrl,
         token_request: TokenRequest,
         trust_anchors: &[TrustAnchor<'_>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<(Self, Vec<AttestationPreview>), IssuanceSessionError> {
         let token_endpoint = Self::discover_token_endpoint(&message_client, &base_url).await?;
 
@@ -340,7 +346,7 @@ impl<H: VcMessageClient> IssuanceSession<H> for HttpIssuanceSession<H> {
             .iter()
             .try_for_each(|preview| {
                 let issuer: &Certificate = preview.as_ref();
-                issuer.verify(CertificateUsage::Mdl, &[], &TimeGenerator, trust_anchors)
+                issuer.verify(CertificateUsage::Mdl, &[], &TimeGenerator, trust_anchors, crls)
             })?;
 
         let attestation_previews = token_response.attestation_previews.into_inner();
@@ -367,6 +373,7 @@ impl<H: VcMessageClient> IssuanceSession<H> for HttpIssuanceSession<H> {
     async fn accept_issuance<K: MdocEcdsaKey>(
         &self,
         trust_anchors: &[TrustAnchor<'_>],
+        crls: &[DerCertificateRevocationList],
         key_factory: impl KeyFactory<Key = K>,
         credential_issuer_identifier: BaseUrl,
     ) -> Result<Vec<MdocCopies>, IssuanceSessionError> {
@@ -467,7 +474,7 @@ impl<H: VcMessageClient> IssuanceSession<H> for HttpIssuanceSession<H> {
                     .map(|(cred_response, (pubkey, key_id))| {
                         // Convert the response into an `Mdoc`, verifying it against both the
                         // trust anchors and the `UnsignedMdoc` we received in the preview.
-                        cred_response.into_mdoc::<K>(key_id, &pubkey, preview, trust_anchors)
+                        cred_response.into_mdoc::<K>(key_id, &pubkey, preview, trust_anchors, crls)
                     })
                     .collect::<Result<_, _>>()?;
 
@@ -501,6 +508,7 @@ impl CredentialResponse {
         verifying_key: &VerifyingKey,
         preview: &AttestationPreview,
         trust_anchors: &[TrustAnchor<'_>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Mdoc, IssuanceSessionError> {
         let issuer_signed = match self {
             CredentialResponse::MsoMdoc { credential } => credential.0,
@@ -542,7 +550,7 @@ impl CredentialResponse {
         }
 
         // Construct the new mdoc; this also verifies it against the trust anchors.
-        let mdoc = Mdoc::new::<K>(key_id, issuer_signed, &TimeGenerator, trust_anchors)
+        let mdoc = Mdoc::new::<K>(key_id, issuer_signed, &TimeGenerator, trust_anchors, crls)
             .map_err(IssuanceSessionError::MdocVerification)?;
 
         // Check that our mdoc contains exactly the attributes the issuer said it would have
@@ -665,6 +673,7 @@ mod tests {
             "https://example.com".parse().unwrap(),
             token_request,
             trust_anchors,
+            &[],
         )
         .await
         .unwrap_err();
@@ -707,6 +716,7 @@ mod tests {
             "https://example.com".parse().unwrap(),
             token_request,
             trust_anchors,
+            &[],
         )
         .await
         .unwrap();
@@ -716,6 +726,7 @@ mod tests {
         let error = client
             .accept_issuance(
                 trust_anchors,
+                &[],
                 SoftwareKeyFactory::default(),
                 "https://example.com".parse().unwrap(),
             )
@@ -738,6 +749,7 @@ mod tests {
                 &mdoc_public_key,
                 &preview,
                 &[((&ca_cert).try_into().unwrap())],
+                &[],
             )
             .expect("should be able to convert CredentialResponse into Mdoc");
     }
@@ -755,6 +767,7 @@ mod tests {
                 &other_public_key,
                 &preview,
                 &[((&ca_cert).try_into().unwrap())],
+                &[],
             )
             .expect_err("should not be able to convert CredentialResponse into Mdoc");
 
@@ -788,6 +801,7 @@ mod tests {
                 &mdoc_public_key,
                 &preview,
                 &[((&ca_cert).try_into().unwrap())],
+                &[],
             )
             .expect_err("should not be able to convert CredentialResponse into Mdoc");
 
@@ -823,6 +837,7 @@ mod tests {
                 &mdoc_public_key,
                 &preview,
                 &[((&ca_cert).try_into().unwrap())],
+                &[],
             )
             .expect_err("should not be able to convert CredentialResponse into Mdoc");
 
@@ -836,7 +851,7 @@ mod tests {
         // Converting a `CredentialResponse` into an `Mdoc` that is
         // validated against incorrect trust anchors should fail.
         let error = credential_response
-            .into_mdoc::<SoftwareEcdsaKey>("key_id".to_string(), &mdoc_public_key, &preview, &[])
+            .into_mdoc::<SoftwareEcdsaKey>("key_id".to_string(), &mdoc_public_key, &preview, &[], &[])
             .expect_err("should not be able to convert CredentialResponse into Mdoc");
 
         assert_matches!(error, IssuanceSessionError::MdocVerification(_))
@@ -864,6 +879,7 @@ mod tests {
                 &mdoc_public_key,
                 &preview,
                 &[((&ca_cert).try_into().unwrap())],
+                &[],
             )
             .expect_err("should not be able to convert CredentialResponse into Mdoc");
 
@@ -17,7 +17,7 @@ use nl_wallet_mdoc::{
     },
     unsigned::UnsignedMdoc,
     utils::{crypto::CryptoError, serialization::CborError},
-    IssuerSigned,
+    validate_mdl_attributes, IssuerSigned, MdlError, MDL_DOCTYPE,
 };
 use wallet_common::{config::wallet_config::BaseUrl, jwt::EcdsaDecodingKey, nonempty::NonEmpty, utils::random_string};
 
@@ -66,6 +66,8 @@ pub enum TokenRequestError {
     UnsupportedTokenRequestType,
     #[error("failed to get attributes to be issued: {0}")]
     AttributeService(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error("attributes to be issued do not conform to the mDL data model: {0}")]
+    InvalidMdl(#[source] MdlError),
 }
 
 /// Errors that can occur during handling of the (batch) credential request.
@@ -211,7 +213,7 @@ pub struct Issuer<A, K, S> {
     attr_service: A,
     issuer_data: IssuerData<K>,
     cleanup_task: JoinHandle<()>,
-    pub metadata: IssuerMetadata,
+    metadata: IssuerMetadata,
 }
 
 /// Fields of the [`Issuer`] needed by the issuance functions.
@@ -283,6 +285,8 @@ where
                     credential_identifiers_supported: Some(false),
                     display: None,
                     credential_configurations_supported: HashMap::new(),
+                    // Determined per request by `Issuer::metadata()`.
+                    mdoc_issuer_certificates: None,
                 },
                 signed_metadata: None,
             },
@@ -431,6 +435,16 @@ where
         Ok(())
     }
 
+    /// The metadata of this issuer. The issuer certificates are included as they are at the time of calling, so that
+    /// certificates of key pairs that are no longer active are withdrawn and those of new key pairs are published.
+    pub fn metadata(&self) -> IssuerMetadata {
+        let mut metadata = self.metadata.clone();
+        metadata.issuer_config.mdoc_issuer_certificates =
+            Some(self.issuer_data.private_keys.certificates()).filter(|certificates| !certificates.is_empty());
+
+        metadata
+    }
+
     pub async fn oauth_metadata(&self) -> Result<oidc::Config, A::Error> {
         self.attr_service
             .oauth_metadata(&self.issuer_data.credential_issuer_identifier)
@@ -511,6 +525,15 @@ impl Session<Created> {
             .await
             .map_err(|e| TokenRequestError::AttributeService(Box::new(e)))?;
 
+        // Make sure that an mDL conforms to its data model before it is offered to the wallet.
+        previews
+            .as_ref()
+            .iter()
+            .map(AsRef::<UnsignedMdoc>::as_ref)
+            .filter(|unsigned_mdoc| unsigned_mdoc.doc_type == MDL_DOCTYPE)
+            .try_for_each(|unsigned_mdoc| validate_mdl_attributes(unsigned_mdoc.attributes.as_ref(), true))
+            .map_err(TokenRequestError::InvalidMdl)?;
+
         let c_nonce = random_string(32);
         let dpop_nonce = random_string(32);
 
@@ -617,13 +640,12 @@ impl Session<WaitingForResponse> {
         // - If it names a doctype and we are offering a single attestation of that doctype, return that.
         // - If it names no doctype and we are offering a single attestation, return that.
         // NB: the OpenID4VCI specification leaves open how to make this determination, this is our own behaviour.
-        let unsigned = match credential_request.doctype {
+        let preview = match credential_request.doctype {
             Some(ref requested_doctype) => {
                 let offered_mdocs: Vec<_> = session_data
                     .attestation_previews
                     .iter()
-                    .map(AsRef::as_ref)
-                    .filter(|unsigned: &&UnsignedMdoc| unsigned.doc_type == *requested_doctype)
+                    .filter(|preview| AsRef::<UnsignedMdoc>::as_ref(preview).doc_type == *requested_doctype)
                     .collect();
                 match offered_mdocs.len() {
                     1 => Ok(*offered_mdocs.first().unwrap()),
@@ -634,18 +656,13 @@ impl Session<WaitingForResponse> {
                 }
             }
             None => match session_data.attestation_previews.len() {
-                1 => Ok(session_data.attestation_previews.first().unwrap().as_ref()),
+                1 => Ok(session_data.attestation_previews.first().unwrap()),
                 _ => Err(CredentialRequestError::UseBatchIssuance),
             },
         }?;
 
-        let credential_response = verify_pop_and_sign_attestation(
-            &session_data.c_nonce,
-            &credential_request,
-            unsigned.clone(),
-            issuer_data,
-        )
-        .await?;
+        let credential_response =
+            verify_pop_and_sign_attestation(&session_data.c_nonce, &credential_request, preview, issuer_data).await?;
 
         Ok(credential_response)
     }
@@ -702,12 +719,14 @@ impl Session<WaitingForResponse> {
                 .credential_requests
                 .as_ref()
                 .iter()
-                .zip(session_data.attestation_previews.iter().flat_map(|preview| {
-                    itertools::repeat_n::<&UnsignedMdoc>(preview.as_ref(), preview.copy_count().into())
-                }))
-                .map(|(cred_req, unsigned_mdoc)| async move {
-                    verify_pop_and_sign_attestation(&session_data.c_nonce, cred_req, unsigned_mdoc.clone(), issuer_data)
-                        .await
+                .zip(
+                    session_data
+                        .attestation_previews
+                        .iter()
+                        .flat_map(|preview| itertools::repeat_n(preview, preview.copy_count().into())),
+                )
+                .map(|(cred_req, preview)| async move {
+                    verify_pop_and_sign_attestation(&session_data.c_nonce, cred_req, preview, issuer_data).await
                 }),
         )
         .await?;
@@ -751,9 +770,11 @@ impl<T: IssuanceState> Session<T> {
 pub(crate) async fn verify_pop_and_sign_attestation(
     c_nonce: &str,
     cred_req: &CredentialRequest,
-    unsigned_mdoc: UnsignedMdoc,
+    preview: &AttestationPreview,
     issuer_data: &IssuerData<impl KeyRing>,
 ) -> Result<CredentialResponse, CredentialRequestError> {
+    let AttestationPreview::MsoMdoc { unsigned_mdoc, issuer } = preview;
+
     if !matches!(cred_req.format, Format::MsoMdoc) {
         return Err(CredentialRequestError::UnsupportedCredentialFormat(cred_req.format));
     }
@@ -780,14 +801,15 @@ pub(crate) async fn verify_pop_and_sign_attestation(
         .try_into()
         .map_err(CredentialRequestError::CoseKeyConversion)?;
 
-    let private_key =
-        issuer_data
-            .private_keys
-            .key_pair(&unsigned_mdoc.doc_type)
-            .ok_or(CredentialRequestError::MissingPrivateKey(
-                unsigned_mdoc.doc_type.clone(),
-            ))?;
-    let issuer_signed = IssuerSigned::sign(unsigned_mdoc, mdoc_public_key, private_key)
+    // Sign with the key pair of which the certificate was announced in the preview, which need not be the current
+    // key pair of the doctype if the key ring has rolled over in the meantime.
+    let private_key = issuer_data
+        .private_keys
+        .key_pair_for_certificate(&unsigned_mdoc.doc_type, issuer)
+        .ok_or(CredentialRequestError::MissingPrivateKey(
+            unsigned_mdoc.doc_type.clone(),
+        ))?;
+    let issuer_signed = IssuerSigned::sign(unsigned_mdoc.clone(), mdoc_public_key, private_key)
         .await
         .map_err(CredentialRequestError::AttestationSigning)?;
 
@@ -32,6 +32,7 @@ use wallet_common::{
     generator::Generator,
     jwt::{Jwt, JwtError},
     keys::EcdsaKey,
+    revocation_list::DerCertificateRevocationList,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -161,10 +162,12 @@ pub enum JwtX5cError {
 }
 
 /// Verify the JWS against the provided trust anchors, using the X.509 certificate(s) present in the `x5c` JWT header.
+/// None of these certificates may be revoked by the provided CRLs.
 pub fn verify_against_trust_anchors<T: DeserializeOwned, A: ToString>(
     jwt: &Jwt<T>,
     audience: &[A],
     trust_anchors: &[TrustAnchor],
+    crls: &[DerCertificateRevocationList],
     time: &impl Generator<DateTime<Utc>>,
 ) -> Result<(T, Certificate), JwtX5cError> {
     let header = jsonwebtoken::decode_header(&jwt.0).map_err(JwtError::Validation)?;
@@ -185,7 +188,13 @@ pub fn verify_against_trust_anchors<T: DeserializeOwned, A: ToString>(
     let leaf_cert = certs.pop().ok_or(JwtX5cError::MissingCertificates)?;
     let intermediate_certs = certs.iter().map(|cert| cert.as_bytes()).collect_vec();
     leaf_cert
-        .verify(CertificateUsage::ReaderAuth, &intermediate_certs, time, trust_anchors)
+        .verify(
+            CertificateUsage::ReaderAuth,
+            &intermediate_certs,
+            time,
+            trust_anchors,
+            crls,
+        )
         .map_err(JwtX5cError::CertificateValidation)?;
 
     // The leaf certificate is trusted, we can now use its public key to verify the JWS.
@@ -230,6 +239,7 @@ pub async fn sign_with_certificate<T: Serialize>(payload: &T, keypair: &KeyPair)
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use chrono::{Duration, Utc};
     use futures::StreamExt;
     use p256::{ecdsa::SigningKey, elliptic_curve::rand_core::OsRng};
     use serde::{Deserialize, Serialize};
@@ -261,9 +271,14 @@ mod tests {
         let jwt = sign_with_certificate(&payload, &keypair).await.unwrap();
 
         let audience: &[String] = &[];
-        let (deserialized, leaf_cert) =
-            verify_against_trust_anchors(&jwt, audience, &[ca.certificate().try_into().unwrap()], &TimeGenerator)
-                .unwrap();
+        let (deserialized, leaf_cert) = verify_against_trust_anchors(
+            &jwt,
+            audience,
+            &[ca.certificate().try_into().unwrap()],
+            &[],
+            &TimeGenerator,
+        )
+        .unwrap();
 
         assert_eq!(deserialized, payload);
         assert_eq!(leaf_cert, *keypair.certificate());
@@ -284,6 +299,7 @@ mod tests {
             &jwt,
             audience,
             &[other_ca.certificate().try_into().unwrap()],
+            &[],
             &TimeGenerator,
         )
         .unwrap_err();
@@ -293,6 +309,31 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_parse_and_verify_jwt_with_revoked_cert() {
+        let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+        let keypair = ca.generate_reader_mock(None).unwrap();
+
+        let payload = json!({"hello": "world"});
+        let jwt = sign_with_certificate(&payload, &keypair).await.unwrap();
+
+        let now = Utc::now();
+        let crl = ca
+            .generate_crl(&[keypair.certificate().clone()], now, now + Duration::days(1))
+            .unwrap();
+
+        let audience: &[String] = &[];
+        let err = verify_against_trust_anchors(
+            &jwt,
+            audience,
+            &[ca.certificate().try_into().unwrap()],
+            &[crl],
+            &TimeGenerator,
+        )
+        .unwrap_err();
+        assert_matches!(err, JwtX5cError::CertificateValidation(CertificateError::Revoked));
+    }
+
     #[test]
     fn jwk_p256_key_conversion() {
         let private_key = SigningKey::random(&mut OsRng);
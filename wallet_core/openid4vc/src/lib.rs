@@ -22,6 +22,7 @@ pub mod metadata;
 
 pub mod oidc;
 
+pub mod deletion_request;
 pub mod disclosure_session;
 pub mod openid4vp;
 pub mod presentation_exchange;
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
 use chrono::{serde::ts_seconds, DateTime, Utc};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use serde_with::skip_serializing_none;
+
+use nl_wallet_mdoc::utils::x509::Certificate;
 use wallet_common::{config::wallet_config::BaseUrl, jwt::Jwt};
 
 /// Credential issuer metadata, as per
@@ -90,6 +93,10 @@ pub struct IssuerData {
     /// Wallet which Credential is being offered. The value is an object that contains metadata about a specific
     /// Credential.
     pub credential_configurations_supported: HashMap<String, CredentialMetadata>,
+
+    /// The certificates per doctype with which the Credential Issuer signs mdocs, including those of keys that it
+    /// will roll over to. This is a custom and optional field. Other implementations should ignore it.
+    pub mdoc_issuer_certificates: Option<IndexMap<String, Vec<Certificate>>>,
 }
 
 impl IssuerData {
@@ -9,7 +9,7 @@ use nl_wallet_mdoc::{
     holder::{Mdoc, MdocCopies, MdocDataSource, StoredMdoc, TrustAnchor},
     utils::keys::{KeyFactory, MdocEcdsaKey},
 };
-use wallet_common::config::wallet_config::BaseUrl;
+use wallet_common::{config::wallet_config::BaseUrl, revocation_list::DerCertificateRevocationList};
 
 use crate::{
     issuance_session::{HttpVcMessageClient, IssuanceSession, IssuanceSessionError},
@@ -41,6 +41,7 @@ impl IssuanceSession for MockIssuanceSession {
         _: BaseUrl,
         _: TokenRequest,
         _: &[TrustAnchor<'_>],
+        _: &[DerCertificateRevocationList],
     ) -> Result<(Self, Vec<AttestationPreview>), IssuanceSessionError>
     where
         Self: Sized,
@@ -51,6 +52,7 @@ impl IssuanceSession for MockIssuanceSession {
     async fn accept_issuance<K: MdocEcdsaKey>(
         &self,
         _: &[TrustAnchor<'_>],
+        _: &[DerCertificateRevocationList],
         _: impl KeyFactory<Key = K>,
         _: BaseUrl,
     ) -> Result<Vec<MdocCopies>, IssuanceSessionError> {
@@ -126,6 +128,7 @@ impl IssuerMetadata {
                 credential_identifiers_supported: None,
                 display: None,
                 credential_configurations_supported: HashMap::new(),
+                mdoc_issuer_certificates: None,
             },
             signed_metadata: None,
         }
@@ -9,6 +9,7 @@ use josekit::{
     jwt::JwtPayload,
     JoseError,
 };
+use p256::SecretKey;
 use serde::{Deserialize, Serialize};
 use serde_with::{formats::PreferOne, serde_as, skip_serializing_none, OneOrMany};
 
@@ -18,13 +19,14 @@ use nl_wallet_mdoc::{
         serialization::CborBase64,
         x509::{Certificate, CertificateError},
     },
-    verifier::{DisclosedAttributes, ItemsRequests},
+    verifier::{DisclosedAttributes, ItemsRequests, ValidityPolicy},
     DeviceResponse, SessionTranscript,
 };
 use wallet_common::{
     config::wallet_config::BaseUrl,
     generator::{Generator, TimeGenerator},
     jwt::Jwt,
+    revocation_list::DerCertificateRevocationList,
     utils::random_string,
 };
 
@@ -204,6 +206,17 @@ pub enum VpFormat {
 pub enum FormatAlg {
     #[default]
     ES256,
+    ES384,
+    ES512,
+    EdDSA,
+}
+
+impl FormatAlg {
+    /// The algorithms of issuer and device signatures that we can verify in disclosed mdocs, which the verifier
+    /// advertises in its Authorization Request. This excludes ES512, as P-521 is not supported by our cryptography.
+    pub fn verifiable() -> IndexSet<FormatAlg> {
+        IndexSet::from([FormatAlg::ES256, FormatAlg::ES384, FormatAlg::EdDSA])
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -288,12 +301,14 @@ impl VpAuthorizationRequest {
     pub fn verify(
         jws: &Jwt<VpAuthorizationRequest>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
         wallet_nonce: Option<String>,
     ) -> Result<(IsoVpAuthorizationRequest, Certificate), AuthRequestValidationError> {
         let (auth_request, rp_cert) = jwt::verify_against_trust_anchors(
             jws,
             &[VpAuthorizationRequestAudience::SelfIssued],
             trust_anchors,
+            crls,
             &TimeGenerator,
         )?;
 
@@ -361,7 +376,7 @@ impl IsoVpAuthorizationRequest {
                     keys: vec![encryption_pubkey.clone()],
                 },
                 vp_formats: VpFormat::MsoMdoc {
-                    alg: IndexSet::from([FormatAlg::ES256]),
+                    alg: FormatAlg::verifiable(),
                 },
                 authorization_encryption_alg_values_supported: VpAlgValues::EcdhEs,
                 authorization_encryption_enc_values_supported: VpEncValues::A128GCM,
@@ -382,7 +397,7 @@ impl From<IsoVpAuthorizationRequest> for VpAuthorizationRequest {
                 nonce: Some(value.nonce),
                 response_mode: Some(ResponseMode::DirectPostJwt),
                 redirect_uri: None,
-                state: None,
+                state: value.state,
                 authorization_details: None,
                 request_uri: None,
                 code_challenge: None,
@@ -508,6 +523,8 @@ pub enum AuthResponseError {
     Json(#[from] serde_json::Error),
     #[error("error parsing JWK: {0}")]
     JwkConversion(#[source] JoseError),
+    #[error("error converting encryption key to P-256 secret key: {0}")]
+    ReaderKeyConversion(#[source] p256::elliptic_curve::Error),
     #[error("error encrypting/decrypting JWE: {0}")]
     Jwe(#[source] JoseError),
     #[error("apv (nonce) field in JWE had incorrect value")]
@@ -639,11 +656,25 @@ impl VpAuthorizationResponse {
         private_key: &EcKeyPair,
         auth_request: &IsoVpAuthorizationRequest,
         time: &impl Generator<DateTime<Utc>>,
+        validity_policy: &ValidityPolicy,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<DisclosedAttributes, AuthResponseError> {
         let (response, mdoc_nonce) = Self::decrypt(jwe, private_key, &auth_request.nonce)?;
 
-        response.verify(auth_request, &mdoc_nonce, time, trust_anchors)
+        // The encryption key doubles as the ephemeral reader key, with which the mdocs may be authenticated by MAC.
+        let eph_reader_key = SecretKey::from_jwk_str(&private_key.to_jwk_key_pair().to_string())
+            .map_err(AuthResponseError::ReaderKeyConversion)?;
+
+        response.verify(
+            auth_request,
+            &mdoc_nonce,
+            Some(&eph_reader_key),
+            time,
+            validity_policy,
+            trust_anchors,
+            crls,
+        )
     }
 
     pub fn decrypt(
@@ -675,12 +706,16 @@ impl VpAuthorizationResponse {
         Ok(&device_response.0)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn verify(
         &self,
         auth_request: &IsoVpAuthorizationRequest,
         mdoc_nonce: &str,
+        eph_reader_key: Option<&SecretKey>,
         time: &impl Generator<DateTime<Utc>>,
+        validity_policy: &ValidityPolicy,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<DisclosedAttributes, AuthResponseError> {
         // Verify the cryptographic integrity of the disclosed attributes.
         let session_transcript = SessionTranscript::new_oid4vp(
@@ -691,7 +726,14 @@ impl VpAuthorizationResponse {
         );
         let device_response = self.device_response()?;
         let disclosed_attrs = device_response
-            .verify(None, &session_transcript, time, trust_anchors)
+            .verify(
+                eph_reader_key,
+                &session_transcript,
+                time,
+                validity_policy,
+                trust_anchors,
+                crls,
+            )
             .map_err(AuthResponseError::Verification)?;
 
         // Check that we received all attributes that we requested
@@ -719,9 +761,13 @@ impl VpAuthorizationResponse {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct VpResponse {
     pub redirect_uri: Option<BaseUrl>,
+    /// Issued by the verifier after a successful disclosure, which the wallet includes in a later deletion request
+    /// to prove that it took part in the session. See [`crate::deletion_request`].
+    pub deletion_token: Option<String>,
 }
 
 #[cfg(test)]
@@ -737,6 +783,7 @@ mod tests {
         server_keys::KeyPair,
         software_key_factory::SoftwareKeyFactory,
         utils::serialization::{cbor_serialize, CborBase64, CborSeq, TaggedBytes},
+        verifier::ValidityPolicy,
         DeviceAuthenticationKeyed, DeviceResponse, DeviceResponseVersion, DeviceSigned, Document, SessionTranscript,
     };
     use wallet_common::keys::software::SoftwareEcdsaKey;
@@ -813,7 +860,7 @@ mod tests {
 
         let auth_request_jwt = jwt::sign_with_certificate(&auth_request, &rp_keypair).await.unwrap();
 
-        VpAuthorizationRequest::verify(&auth_request_jwt, &[ca.certificate().try_into().unwrap()], None).unwrap();
+        VpAuthorizationRequest::verify(&auth_request_jwt, &[ca.certificate().try_into().unwrap()], &[], None).unwrap();
     }
 
     #[test]
@@ -941,7 +988,9 @@ mod tests {
                 None,
                 session_transcript,
                 &IsoCertTimeGenerator,
+                &ValidityPolicy::default(),
                 Examples::iaca_trust_anchors(),
+                &[],
             )
             .unwrap();
 
@@ -967,8 +1016,11 @@ mod tests {
             .verify(
                 &auth_request,
                 mdoc_nonce,
+                None,
                 &IsoCertTimeGenerator,
+                &ValidityPolicy::default(),
                 Examples::iaca_trust_anchors(),
+                &[],
             )
             .unwrap();
     }
@@ -4,7 +4,7 @@
 //! Other fields are left out of the various structs and enums for now, and some fields that are optional per
 //! Presentation Exchange that are always used by the ISO 18013-7 profile are mandatory here.
 
-use indexmap::{IndexMap, IndexSet};
+use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -64,6 +64,11 @@ pub enum PdConversionError {
 pub struct Field {
     pub path: Vec<String>,
     pub intent_to_retain: bool,
+
+    /// Whether the holder should provide the value of this attribute itself, in which case it is not signed by the
+    /// issuer and therefore unverified. This is a custom and optional field. Other implementations should ignore it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub self_asserted: bool,
 }
 
 /// Per ISO 18013.7, the field paths in a Presentation Definition must all be a JSONPath expression of the form
@@ -99,17 +104,26 @@ impl From<&ItemsRequests> for PresentationDefinition {
                 .map(|items_request| InputDescriptor {
                     id: items_request.doc_type.clone(),
                     format: VpFormat::MsoMdoc {
-                        alg: IndexSet::from([FormatAlg::ES256]),
+                        alg: FormatAlg::verifiable(),
                     },
                     constraints: Constraints {
                         limit_disclosure: LimitDisclosure::Required,
                         fields: items_request
                             .name_spaces
                             .iter()
-                            .flat_map(|(namespace, attrs)| {
-                                attrs.iter().map(|(attr, intent_to_retain)| Field {
+                            .map(|name_spaces| (name_spaces, false))
+                            .chain(
+                                items_request
+                                    .device_name_spaces
+                                    .iter()
+                                    .flatten()
+                                    .map(|name_spaces| (name_spaces, true)),
+                            )
+                            .flat_map(|((namespace, attrs), self_asserted)| {
+                                attrs.iter().map(move |(attr, intent_to_retain)| Field {
                                     path: vec![format!("$['{}']['{}']", namespace.as_str(), attr.as_str())],
                                     intent_to_retain: *intent_to_retain,
+                                    self_asserted,
                                 })
                             })
                             .collect(),
@@ -128,14 +142,21 @@ impl TryFrom<&PresentationDefinition> for ItemsRequests {
             .input_descriptors
             .iter()
             .map(|input_descriptor| {
+                // Our mdoc keys only support ES256 (or a MAC), so the verifier has to accept that.
                 let VpFormat::MsoMdoc { alg } = &input_descriptor.format;
                 if !alg.contains(&FormatAlg::ES256) {
                     return Err(PdConversionError::UnsupportedAlgs);
                 }
 
                 let mut name_spaces: IndexMap<String, IndexMap<String, bool>> = IndexMap::new();
+                let mut device_name_spaces: IndexMap<String, IndexMap<String, bool>> = IndexMap::new();
                 for field in &input_descriptor.constraints.fields {
                     let (namespace, attr) = field.parse_paths()?;
+                    let name_spaces = if field.self_asserted {
+                        &mut device_name_spaces
+                    } else {
+                        &mut name_spaces
+                    };
                     name_spaces
                         .entry(namespace)
                         .or_default()
@@ -145,6 +166,7 @@ impl TryFrom<&PresentationDefinition> for ItemsRequests {
                 Ok(ItemsRequest {
                     doc_type: input_descriptor.id.clone(),
                     request_info: None,
+                    device_name_spaces: (!device_name_spaces.is_empty()).then_some(device_name_spaces),
                     name_spaces,
                 })
             })
@@ -225,12 +247,13 @@ impl PresentationSubmission {
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use indexmap::IndexMap;
     use rstest::rstest;
     use serde_json::json;
 
     use nl_wallet_mdoc::{examples::Examples, verifier::ItemsRequests};
 
-    use super::{FormatAlg, LimitDisclosure, PresentationDefinition, VpFormat, FIELD_PATH_REGEX};
+    use super::{FormatAlg, LimitDisclosure, PdConversionError, PresentationDefinition, VpFormat, FIELD_PATH_REGEX};
 
     #[rstest]
     #[case("$['namespace']['attribute_name']", true)]
@@ -254,6 +277,46 @@ mod tests {
         assert_eq!(items_requests, converted);
     }
 
+    #[test]
+    fn convert_pd_itemsrequests_self_asserted() {
+        let mut items_requests: ItemsRequests = Examples::items_requests();
+        items_requests.0[0].device_name_spaces = Some(IndexMap::from([(
+            "com.example.self_asserted".to_string(),
+            IndexMap::from([("email".to_string(), false)]),
+        )]));
+
+        let pd: PresentationDefinition = (&items_requests).into();
+
+        let fields = &pd.input_descriptors[0].constraints.fields;
+        assert!(fields[..fields.len() - 1].iter().all(|field| !field.self_asserted));
+        assert!(fields.last().unwrap().self_asserted);
+
+        let converted: ItemsRequests = (&pd).try_into().unwrap();
+
+        assert_eq!(items_requests, converted);
+    }
+
+    #[rstest]
+    #[case(vec![FormatAlg::ES256], true)]
+    #[case(vec![FormatAlg::ES384, FormatAlg::ES256, FormatAlg::EdDSA], true)]
+    #[case(vec![FormatAlg::ES384, FormatAlg::ES512], false)]
+    fn convert_pd_itemsrequests_format_alg(#[case] algs: Vec<FormatAlg>, #[case] should_convert: bool) {
+        let mut pd: PresentationDefinition = (&Examples::items_requests()).into();
+        pd.input_descriptors.iter_mut().for_each(|input_descriptor| {
+            input_descriptor.format = VpFormat::MsoMdoc {
+                alg: algs.iter().copied().collect(),
+            };
+        });
+
+        let result = ItemsRequests::try_from(&pd);
+
+        if should_convert {
+            result.unwrap();
+        } else {
+            assert_matches!(result, Err(PdConversionError::UnsupportedAlgs));
+        }
+    }
+
     #[test]
     fn deserialize_example_presentation_definition() {
         let example_json = json!(
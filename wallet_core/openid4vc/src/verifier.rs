@@ -26,9 +26,9 @@ use nl_wallet_mdoc::{
         Expirable, HasProgress, Progress, SessionState, SessionStore, SessionStoreError, SessionToken,
         CLEANUP_INTERVAL_SECONDS,
     },
-    utils::x509::CertificateError,
+    utils::{crl::CrlCache, x509::CertificateError},
     verifier::{
-        DisclosedAttributes, ItemsRequests, ReturnUrlTemplate, SessionType, SessionTypeReturnUrl,
+        DisclosedAttributes, ItemsRequests, ReturnUrlTemplate, SessionType, SessionTypeReturnUrl, ValidityPolicy,
         EPHEMERAL_ID_VALIDITY_SECONDS,
     },
 };
@@ -36,11 +36,13 @@ use wallet_common::{
     config::wallet_config::BaseUrl,
     generator::Generator,
     jwt::{Jwt, JwtError},
+    revocation_list::DerCertificateRevocationList,
     trust_anchor::OwnedTrustAnchor,
     utils::random_string,
 };
 
 use crate::{
+    deletion_request::{DeletionRequest, DeletionRequestError, DeletionRequestPayload},
     jwt,
     openid4vp::{
         AuthRequestError, AuthResponseError, IsoVpAuthorizationRequest, RequestUriMethod, VpAuthorizationRequest,
@@ -177,6 +179,8 @@ pub struct WaitingForResponse {
     auth_request: IsoVpAuthorizationRequest,
     encryption_key: EncryptionPrivateKey,
     redirect_uri: Option<RedirectUri>,
+    #[serde(default)]
+    validity_policy: ValidityPolicy,
 }
 
 /// State for a session that has ended (for any reason).
@@ -386,10 +390,15 @@ pub struct UseCase {
     pub key_pair: KeyPair,
     pub client_id: String,
     pub session_type_return_url: SessionTypeReturnUrl,
+    pub validity_policy: ValidityPolicy,
 }
 
 impl UseCase {
-    pub fn new(key_pair: KeyPair, session_type_return_url: SessionTypeReturnUrl) -> Result<Self, VerificationError> {
+    pub fn new(
+        key_pair: KeyPair,
+        session_type_return_url: SessionTypeReturnUrl,
+        validity_policy: ValidityPolicy,
+    ) -> Result<Self, VerificationError> {
         let client_id = key_pair
             .certificate()
             .san_dns_name()?
@@ -398,6 +407,7 @@ impl UseCase {
             key_pair,
             client_id,
             session_type_return_url,
+            validity_policy,
         })
     }
 }
@@ -407,13 +417,18 @@ pub struct Verifier<S> {
     sessions: Arc<S>,
     cleanup_task: JoinHandle<()>,
     trust_anchors: Vec<OwnedTrustAnchor>,
+    crls: Arc<CrlCache>,
+    crl_refresh_task: Option<JoinHandle<()>>,
     ephemeral_id_secret: hmac::Key,
 }
 
 impl<S> Drop for Verifier<S> {
     fn drop(&mut self) {
-        // Stop the task at the next .await
+        // Stop the tasks at the next .await
         self.cleanup_task.abort();
+        if let Some(crl_refresh_task) = &self.crl_refresh_task {
+            crl_refresh_task.abort();
+        }
     }
 }
 
@@ -428,22 +443,28 @@ where
     /// - `sessions` will contain all sessions.
     /// - `trust_anchors` contains self-signed X509 CA certificates acting as trust anchor for the mdoc verification:
     ///   the mdoc verification function [`Document::verify()`] returns true if the mdoc verifies against one of these CAs.
-    /// - `ephemeral_id_secret` is used as a HMAC secret to create ephemeral session IDs.
+    /// - `crls` contains the CRLs against which the certificates of the mdocs are checked for revocation,
+    ///   which are refreshed in the background if the [`CrlCache`] was loaded from files.
+    /// - `ephemeral_id_secret` is used as a HMAC secret to create ephemeral session IDs and deletion tokens.
     pub fn new(
         use_cases: UseCases,
         sessions: S,
         trust_anchors: Vec<OwnedTrustAnchor>,
+        crls: CrlCache,
         ephemeral_id_secret: hmac::Key,
     ) -> Self
     where
         S: Send + Sync + 'static,
     {
         let sessions = Arc::new(sessions);
+        let crls = Arc::new(crls);
         Self {
             use_cases,
             cleanup_task: sessions.clone().start_cleanup_task(CLEANUP_INTERVAL_SECONDS),
+            crl_refresh_task: Arc::clone(&crls).start_refresh_task(),
             sessions,
             trust_anchors,
+            crls,
             ephemeral_id_secret,
         }
     }
@@ -595,6 +616,7 @@ where
         time: &impl Generator<DateTime<Utc>>,
     ) -> Result<VpResponse, WithRedirectUri<PostAuthResponseError>> {
         let session: Session<WaitingForResponse> = self.get_session(session_token).await?;
+        let client_id = session.state().auth_request.client_id.clone();
 
         let (result, next) = session.process_authorization_response(
             wallet_response,
@@ -604,8 +626,18 @@ where
                 .map(Into::<TrustAnchor<'_>>::into)
                 .collect_vec()
                 .as_slice(),
+            &self.crls.crls(),
         );
 
+        // Only a wallet that successfully disclosed receives a token with which it can request deletion later on.
+        let result = match next.state().session_result {
+            SessionResult::Done { .. } => result.map(|response| VpResponse {
+                deletion_token: Some(self.generate_deletion_token(&client_id, session_token)),
+                ..response
+            }),
+            _ => result,
+        };
+
         self.sessions.write(next.into(), false).await.map_err(|err| {
             WithRedirectUri::new(
                 SessionError::SessionStore(err).into(),
@@ -725,6 +757,40 @@ impl<S> Verifier<S> {
         // default (de)serialization of DateTime is the RFC 3339 format
         format!("{}|{}", session_token, time.to_rfc3339()).into()
     }
+
+    /// Verify a deletion request sent by a wallet, which requires it to contain the deletion token that was issued
+    /// to the wallet at the end of the disclosure session it refers to. Returns the payload of the deletion request.
+    pub fn verify_deletion_request(
+        &self,
+        deletion_request: &DeletionRequest,
+    ) -> Result<DeletionRequestPayload, DeletionRequestError> {
+        let (payload, _) = deletion_request.verify()?;
+
+        let deletion_token =
+            hex::decode(&payload.deletion_token).map_err(|_| DeletionRequestError::InvalidDeletionToken)?;
+        hmac::verify(
+            &self.ephemeral_id_secret,
+            &Self::format_deletion_token_payload(&payload.client_id, &payload.session_reference),
+            &deletion_token,
+        )
+        .map_err(|_| DeletionRequestError::InvalidDeletionToken)?;
+
+        Ok(payload)
+    }
+
+    fn generate_deletion_token(&self, client_id: &str, session_token: &SessionToken) -> String {
+        let deletion_token = hmac::sign(
+            &self.ephemeral_id_secret,
+            &Self::format_deletion_token_payload(client_id, session_token.as_ref()),
+        );
+        hex::encode(deletion_token)
+    }
+
+    // formats the payload to hash to the deletion token, which is prefixed so that it can never
+    // be equal to the payload of an ephemeral ID, as both are signed using the same secret
+    fn format_deletion_token_payload(client_id: &str, session_token: &str) -> Vec<u8> {
+        format!("deletion|{}|{}", client_id, session_token).into()
+    }
 }
 
 #[serde_as]
@@ -801,11 +867,12 @@ impl Session<Created> {
             .process_get_request_inner(session_token, response_uri, session_type, wallet_nonce, use_cases)
             .await
         {
-            Ok((jws, auth_request, redirect_uri, enc_keypair)) => {
+            Ok((jws, auth_request, redirect_uri, enc_keypair, validity_policy)) => {
                 let next = WaitingForResponse {
                     auth_request,
                     encryption_key: EncryptionPrivateKey::from(enc_keypair),
                     redirect_uri,
+                    validity_policy,
                 };
                 let next = self.transition(next);
                 Ok((jws, next))
@@ -837,6 +904,7 @@ impl Session<Created> {
             IsoVpAuthorizationRequest,
             Option<RedirectUri>,
             EcKeyPair,
+            ValidityPolicy,
         ),
         WithRedirectUri<GetAuthRequestError>,
     > {
@@ -870,12 +938,25 @@ impl Session<Created> {
         )
         .map_err(|err| WithRedirectUri::new(err.into(), uri_from_option(&redirect_uri)))?;
 
+        // Include the session token as state, so that the wallet can refer to this session later on,
+        // e.g. when requesting the Relying Party to delete the disclosed attributes.
+        let auth_request = IsoVpAuthorizationRequest {
+            state: Some(session_token.to_string()),
+            ..auth_request
+        };
+
         let vp_auth_request = VpAuthorizationRequest::from(auth_request.clone());
         let jws = jwt::sign_with_certificate(&vp_auth_request, &usecase.key_pair)
             .await
             .map_err(|err| WithRedirectUri::new(err.into(), uri_from_option(&redirect_uri)))?;
 
-        Ok((jws, auth_request, redirect_uri, encryption_keypair))
+        Ok((
+            jws,
+            auth_request,
+            redirect_uri,
+            encryption_keypair,
+            usecase.validity_policy,
+        ))
     }
 
     fn redirect_uri_and_nonce(
@@ -925,6 +1006,7 @@ impl Session<WaitingForResponse> {
         wallet_response: WalletAuthResponse,
         time: &impl Generator<DateTime<Utc>>,
         trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
     ) -> (
         Result<VpResponse, WithRedirectUri<PostAuthResponseError>>,
         Session<Done>,
@@ -962,7 +1044,9 @@ impl Session<WaitingForResponse> {
             self.state().encryption_key.as_ref(),
             &self.state().auth_request,
             time,
+            &self.state().validity_policy,
             trust_anchors,
+            crls,
         ) {
             Ok(disclosed) => {
                 let redirect_uri_nonce = self.state().redirect_uri.as_ref().map(|u| u.nonce.clone());
@@ -983,6 +1067,7 @@ impl Session<WaitingForResponse> {
     fn ok_response(&self) -> VpResponse {
         VpResponse {
             redirect_uri: uri_from_option(&self.state().redirect_uri),
+            deletion_token: None,
         }
     }
 
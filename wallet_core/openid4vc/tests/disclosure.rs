@@ -1,27 +1,33 @@
 use std::{collections::HashMap, str::FromStr, sync::Arc};
 
+use assert_matches::assert_matches;
 use chrono::Utc;
 use itertools::Itertools;
 use josekit::jwk::alg::ec::{EcCurve, EcKeyPair};
+use p256::{ecdsa::SigningKey, elliptic_curve::rand_core::OsRng, PublicKey, SecretKey};
 use ring::{hmac, rand};
 use rstest::rstest;
 
 use nl_wallet_mdoc::{
     examples::{Examples, IsoCertTimeGenerator},
-    holder::{DisclosureRequestMatch, DisclosureUriSource, TrustAnchor},
+    holder::{DeviceAuthMethod, DisclosureRequestMatch, DisclosureUriSource, TrustAnchor},
+    identifiers::AttributeIdentifier,
     server_keys::KeyPair,
     server_state::{MemorySessionStore, SessionToken},
     software_key_factory::SoftwareKeyFactory,
     unsigned::Entry,
     utils::reader_auth::ReaderRegistration,
-    verifier::{ReturnUrlTemplate, SessionType, SessionTypeReturnUrl},
+    verifier::{ReturnUrlTemplate, SessionType, SessionTypeReturnUrl, ValidityPolicy},
     DeviceResponse, SessionTranscript,
 };
 use openid4vc::{
+    deletion_request::{DeletionRequest, DeletionRequestError},
     disclosure_session::{DisclosureSession, VpMessageClient, VpMessageClientError},
     jwt,
     mock::MockMdocDataSource,
-    openid4vp::{IsoVpAuthorizationRequest, VpAuthorizationRequest, VpAuthorizationResponse, VpRequestUriObject},
+    openid4vp::{
+        IsoVpAuthorizationRequest, VpAuthorizationRequest, VpAuthorizationResponse, VpRequestUriObject, VpResponse,
+    },
     verifier::{DisclosureData, StatusResponse, UseCase, Verifier, VerifierUrlParameters, VpToken, WalletAuthResponse},
     ErrorResponse, VpAuthorizationErrorCode,
 };
@@ -59,8 +65,11 @@ async fn disclosure_direct() {
         .verify(
             &iso_auth_request,
             &mdoc_nonce,
+            Some(&SecretKey::from_jwk_str(&encryption_keypair.to_jwk_key_pair().to_string()).unwrap()),
             &IsoCertTimeGenerator,
+            &ValidityPolicy::default(),
             Examples::iaca_trust_anchors(),
+            &[],
         )
         .unwrap();
 
@@ -81,7 +90,7 @@ async fn disclosure_jwe(auth_request: Jwt<VpAuthorizationRequest>, trust_anchors
     let mdoc_nonce = "mdoc_nonce".to_string();
 
     // Verify the Authorization Request JWE and read the requested attributes.
-    let (auth_request, _) = VpAuthorizationRequest::verify(&auth_request, trust_anchors, None).unwrap();
+    let (auth_request, _) = VpAuthorizationRequest::verify(&auth_request, trust_anchors, &[], None).unwrap();
 
     // Check if we have the requested attributes.
     let session_transcript = SessionTranscript::new_oid4vp(
@@ -103,9 +112,17 @@ async fn disclosure_jwe(auth_request: Jwt<VpAuthorizationRequest>, trust_anchors
 
     // Compute the disclosure.
     let key_factory = SoftwareKeyFactory::default();
-    let device_response = DeviceResponse::from_proposed_documents(to_disclose, &key_factory)
-        .await
-        .unwrap();
+    let reader_key = PublicKey::from_jwk_str(&auth_request.encryption_pubkey.to_string()).unwrap();
+    let device_response = DeviceResponse::from_proposed_documents(
+        to_disclose,
+        DeviceAuthMethod::Mac {
+            reader_key: &reader_key,
+            session_transcript: &session_transcript,
+        },
+        &key_factory,
+    )
+    .await
+    .unwrap();
 
     // Put the disclosure in an Authorization Response and encrypt it.
     VpAuthorizationResponse::new_encrypted(device_response, &auth_request, &mdoc_nonce).unwrap()
@@ -136,6 +153,7 @@ async fn disclosure_using_message_client() {
         DisclosureUriSource::Link,
         &mdocs,
         trust_anchors,
+        &[],
     )
     .await
     .unwrap();
@@ -148,6 +166,54 @@ async fn disclosure_using_message_client() {
     proposal.disclose(key_factory).await.unwrap();
 }
 
+#[tokio::test]
+async fn disclosure_multiple_candidates() {
+    let ca = KeyPair::generate_ca("myca", Default::default()).unwrap();
+    let trust_anchors = &[ca.certificate().try_into().unwrap()];
+    let rp_keypair = ca
+        .generate_reader_mock(Some(ReaderRegistration::new_mock_from_requests(
+            &Examples::items_requests(),
+        )))
+        .unwrap();
+
+    // Initialize the "wallet" with two copies of the same mdoc.
+    let mut mdocs = MockMdocDataSource::default();
+    mdocs.mdocs.push(mdocs.mdocs.first().unwrap().clone());
+    let key_factory = &SoftwareKeyFactory::default();
+
+    // Start a session at the "RP"
+    let message_client = DirectMockVpMessageClient::new(rp_keypair);
+    let request_uri = message_client.start_session();
+
+    // Perform the first part of the session, resulting in the proposed disclosure.
+    let session = DisclosureSession::start(
+        message_client,
+        &request_uri,
+        DisclosureUriSource::Link,
+        &mdocs,
+        trust_anchors,
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let DisclosureSession::Proposal(mut proposal) = session else {
+        panic!("should have requested attributes")
+    };
+
+    // Both mdocs should be offered as a candidate, with the first one selected.
+    let candidate_attributes = proposal.candidate_attributes();
+    assert_eq!(candidate_attributes.len(), 1);
+    assert_eq!(candidate_attributes.values().next().unwrap().len(), 2);
+    assert_eq!(proposal.proposed_source_identifiers(), [&"id_1".to_string()]);
+
+    // Select the second candidate and finish the disclosure.
+    proposal.select_candidates(&[1]).unwrap();
+    assert_eq!(proposal.proposed_source_identifiers(), [&"id_2".to_string()]);
+
+    proposal.disclose(key_factory).await.unwrap();
+}
+
 // A mock implementation of the `VpMessageClient` trait that implements the RP side of OpenID4VP
 // directly in its methods.
 struct DirectMockVpMessageClient {
@@ -220,11 +286,7 @@ impl VpMessageClient for DirectMockVpMessageClient {
         Ok(jws)
     }
 
-    async fn send_authorization_response(
-        &self,
-        url: BaseUrl,
-        jwe: String,
-    ) -> Result<Option<BaseUrl>, VpMessageClientError> {
+    async fn send_authorization_response(&self, url: BaseUrl, jwe: String) -> Result<VpResponse, VpMessageClientError> {
         assert_eq!(url, self.response_uri);
 
         let (auth_response, mdoc_nonce) =
@@ -233,8 +295,11 @@ impl VpMessageClient for DirectMockVpMessageClient {
             .verify(
                 &self.auth_request.clone().try_into().unwrap(),
                 &mdoc_nonce,
+                Some(&SecretKey::from_jwk_str(&self.encryption_keypair.to_jwk_key_pair().to_string()).unwrap()),
                 &IsoCertTimeGenerator,
+                &ValidityPolicy::default(),
                 Examples::iaca_trust_anchors(),
+                &[],
             )
             .unwrap();
 
@@ -248,7 +313,7 @@ impl VpMessageClient for DirectMockVpMessageClient {
             }
         );
 
-        Ok(None)
+        Ok(VpResponse::default())
     }
 
     async fn send_error(
@@ -273,12 +338,18 @@ async fn test_client_and_server(#[case] session_type: SessionType, #[case] uri_s
         .generate_reader_mock(Some(ReaderRegistration::new_mock_from_requests(&items_requests)))
         .unwrap();
     let trust_anchors = &[ca.certificate().try_into().unwrap()];
+    let client_id = disclosure_key.certificate().san_dns_name().unwrap().unwrap();
 
     // Initialize the verifier
     let verifier = Arc::new(MockVerifier::new(
         HashMap::from([(
             "usecase_id".to_string(),
-            UseCase::new(disclosure_key, SessionTypeReturnUrl::SameDevice).unwrap(),
+            UseCase::new(
+                disclosure_key,
+                SessionTypeReturnUrl::SameDevice,
+                ValidityPolicy::default(),
+            )
+            .unwrap(),
         )])
         .into(),
         MemorySessionStore::default(),
@@ -286,6 +357,7 @@ async fn test_client_and_server(#[case] session_type: SessionType, #[case] uri_s
             .iter()
             .map(OwnedTrustAnchor::from)
             .collect_vec(),
+        Default::default(),
         hmac::Key::generate(hmac::HMAC_SHA256, &rand::SystemRandom::new()).unwrap(),
     ));
 
@@ -306,7 +378,7 @@ async fn test_client_and_server(#[case] session_type: SessionType, #[case] uri_s
     let mdocs = MockMdocDataSource::default();
     let key_factory = SoftwareKeyFactory::default();
     let message_client = VerifierMockVpMessageClient::new(Arc::clone(&verifier));
-    let session = DisclosureSession::start(message_client, &request_uri, uri_source, &mdocs, trust_anchors)
+    let session = DisclosureSession::start(message_client, &request_uri, uri_source, &mdocs, trust_anchors, &[])
         .await
         .unwrap();
 
@@ -315,7 +387,10 @@ async fn test_client_and_server(#[case] session_type: SessionType, #[case] uri_s
     };
 
     // Finish the disclosure.
-    let redirect_uri = proposal.disclose(&key_factory).await.unwrap();
+    let VpResponse {
+        redirect_uri,
+        deletion_token,
+    } = proposal.disclose(&key_factory).await.unwrap();
 
     // If a redirect URI is present then the wallet would navigate to it, informing the RP of the
     // redirect URI nonce which it will need when retrieving the disclosed attributes.
@@ -340,6 +415,43 @@ async fn test_client_and_server(#[case] session_type: SessionType, #[case] uri_s
             value: "Doe".into()
         }
     );
+
+    // The wallet can use the deletion token it received to request deletion of the disclosed attributes.
+    let deletion_token = deletion_token.expect("verifier should issue a deletion token");
+    let deletion_request = DeletionRequest::new(
+        &SigningKey::random(&mut OsRng),
+        client_id.clone(),
+        session_token.to_string(),
+        deletion_token.clone(),
+        vec![AttributeIdentifier {
+            doc_type: "org.iso.18013.5.1.mDL".to_string(),
+            namespace: "org.iso.18013.5.1".to_string(),
+            attribute: "family_name".to_string(),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let payload = verifier
+        .verify_deletion_request(&deletion_request)
+        .expect("deletion request should be valid");
+    assert_eq!(payload.session_reference, session_token.as_ref());
+
+    // The deletion token cannot be used for any other session.
+    let other_deletion_request = DeletionRequest::new(
+        &SigningKey::random(&mut OsRng),
+        client_id,
+        SessionToken::new_random().to_string(),
+        deletion_token,
+        payload.attributes,
+    )
+    .await
+    .unwrap();
+
+    assert_matches!(
+        verifier.verify_deletion_request(&other_deletion_request),
+        Err(DeletionRequestError::InvalidDeletionToken)
+    );
 }
 
 async fn request_uri_from_status_endpoint(
@@ -403,11 +515,7 @@ impl VpMessageClient for VerifierMockVpMessageClient {
         Ok(jws)
     }
 
-    async fn send_authorization_response(
-        &self,
-        url: BaseUrl,
-        jwe: String,
-    ) -> Result<Option<BaseUrl>, VpMessageClientError> {
+    async fn send_authorization_response(&self, url: BaseUrl, jwe: String) -> Result<VpResponse, VpMessageClientError> {
         let path_segments = url.as_ref().path_segments().unwrap().collect_vec();
         let session_token = SessionToken::new(path_segments[path_segments.len() - 2]);
 
@@ -421,7 +529,7 @@ impl VpMessageClient for VerifierMockVpMessageClient {
             .await
             .unwrap();
 
-        Ok(response.redirect_uri)
+        Ok(response)
     }
 
     async fn send_error(
@@ -6,12 +6,13 @@ use indexmap::IndexMap;
 use url::Url;
 
 use nl_wallet_mdoc::{
+    examples::Examples,
     server_keys::{KeyPair, SingleKeyRing},
     server_state::{MemorySessionStore, SessionState},
     software_key_factory::SoftwareKeyFactory,
     unsigned::{Entry, UnsignedMdoc},
     utils::{issuer_auth::IssuerRegistration, x509::Certificate},
-    Tdate,
+    NameSpace, Tdate, MDL_DOCTYPE,
 };
 use openid4vc::{
     credential::{CredentialRequestProof, CredentialRequests, CredentialResponses},
@@ -21,13 +22,17 @@ use openid4vc::{
     metadata::IssuerMetadata,
     oidc,
     token::{AccessToken, AttestationPreview, TokenRequest, TokenResponseWithPreviews},
-    CredentialErrorCode,
+    CredentialErrorCode, TokenErrorCode,
 };
 use wallet_common::{config::wallet_config::BaseUrl, nonempty::NonEmpty};
 
 type MockIssuer = Issuer<MockAttributeService, SingleKeyRing, MemorySessionStore<IssuanceData>>;
 
 fn setup() -> (MockIssuer, Certificate, BaseUrl) {
+    setup_with_mdl(None)
+}
+
+fn setup_with_mdl(mdl_attributes: Option<IndexMap<NameSpace, Vec<Entry>>>) -> (MockIssuer, Certificate, BaseUrl) {
     let ca = KeyPair::generate_issuer_mock_ca().unwrap();
     let keypair = ca.generate_issuer_mock(IssuerRegistration::new_mock().into()).unwrap();
     let server_url: BaseUrl = "https://example.com/".parse().unwrap();
@@ -36,6 +41,7 @@ fn setup() -> (MockIssuer, Certificate, BaseUrl) {
         MemorySessionStore::default(),
         MockAttributeService {
             issuer_cert: keypair.certificate().clone(),
+            mdl_attributes,
         },
         SingleKeyRing(keypair),
         &server_url,
@@ -55,12 +61,18 @@ async fn accept_issuance() {
         server_url.clone(),
         TokenRequest::new_mock(),
         &[(&ca).try_into().unwrap()],
+        &[],
     )
     .await
     .unwrap();
 
     let mdoc_copies = session
-        .accept_issuance(&[(&ca).try_into().unwrap()], SoftwareKeyFactory::default(), server_url)
+        .accept_issuance(
+            &[(&ca).try_into().unwrap()],
+            &[],
+            SoftwareKeyFactory::default(),
+            server_url,
+        )
         .await
         .unwrap();
 
@@ -87,6 +99,7 @@ async fn reject_issuance() {
         server_url,
         TokenRequest::new_mock(),
         &[(&ca).try_into().unwrap()],
+        &[],
     )
     .await
     .unwrap();
@@ -107,12 +120,18 @@ async fn wrong_access_token() {
         server_url.clone(),
         TokenRequest::new_mock(),
         &[(&ca).try_into().unwrap()],
+        &[],
     )
     .await
     .unwrap();
 
     let result = session
-        .accept_issuance(&[(&ca).try_into().unwrap()], SoftwareKeyFactory::default(), server_url)
+        .accept_issuance(
+            &[(&ca).try_into().unwrap()],
+            &[],
+            SoftwareKeyFactory::default(),
+            server_url,
+        )
         .await
         .unwrap_err();
 
@@ -135,12 +154,18 @@ async fn invalid_dpop() {
         server_url.clone(),
         TokenRequest::new_mock(),
         &[(&ca).try_into().unwrap()],
+        &[],
     )
     .await
     .unwrap();
 
     let result = session
-        .accept_issuance(&[(&ca).try_into().unwrap()], SoftwareKeyFactory::default(), server_url)
+        .accept_issuance(
+            &[(&ca).try_into().unwrap()],
+            &[],
+            SoftwareKeyFactory::default(),
+            server_url,
+        )
         .await
         .unwrap_err();
 
@@ -163,12 +188,18 @@ async fn invalid_pop() {
         server_url.clone(),
         TokenRequest::new_mock(),
         &[(&ca).try_into().unwrap()],
+        &[],
     )
     .await
     .unwrap();
 
     let result = session
-        .accept_issuance(&[(&ca).try_into().unwrap()], SoftwareKeyFactory::default(), server_url)
+        .accept_issuance(
+            &[(&ca).try_into().unwrap()],
+            &[],
+            SoftwareKeyFactory::default(),
+            server_url,
+        )
         .await
         .unwrap_err();
 
@@ -178,6 +209,62 @@ async fn invalid_pop() {
     ));
 }
 
+#[tokio::test]
+async fn accept_issuance_mdl() {
+    let (issuer, ca, server_url) = setup_with_mdl(Some(Examples::full_mdl_attributes()));
+    let message_client = MockOpenidMessageClient::new(issuer);
+
+    let (session, previews) = HttpIssuanceSession::start_issuance(
+        message_client,
+        server_url.clone(),
+        TokenRequest::new_mock(),
+        &[(&ca).try_into().unwrap()],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        AsRef::<UnsignedMdoc>::as_ref(previews.last().unwrap()).doc_type,
+        MDL_DOCTYPE
+    );
+
+    let mdoc_copies = session
+        .accept_issuance(
+            &[(&ca).try_into().unwrap()],
+            &[],
+            SoftwareKeyFactory::default(),
+            server_url,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(mdoc_copies.len(), 3);
+}
+
+#[tokio::test]
+async fn invalid_mdl() {
+    // The ISO example mDL lacks some of the mandatory data elements, so it cannot be issued.
+    let (issuer, ca, server_url) = setup_with_mdl(Some(Examples::mdl_attributes()));
+    let message_client = MockOpenidMessageClient::new(issuer);
+
+    let result = HttpIssuanceSession::start_issuance(
+        message_client,
+        server_url,
+        TokenRequest::new_mock(),
+        &[(&ca).try_into().unwrap()],
+        &[],
+    )
+    .await
+    .map(|_| ())
+    .unwrap_err();
+
+    assert!(matches!(
+        result,
+        IssuanceSessionError::TokenRequest(err) if matches!(err.error, TokenErrorCode::ServerError)
+    ));
+}
+
 // Helpers and mocks
 
 /// An implementation of [`OpenidMessageClient`] that sends its messages to the contained issuer
@@ -316,6 +403,7 @@ const MOCK_ATTRS: [(&str, &str); 2] = [("first_name", "John"), ("family_name", "
 
 struct MockAttributeService {
     issuer_cert: Certificate,
+    mdl_attributes: Option<IndexMap<NameSpace, Vec<Entry>>>,
 }
 
 impl AttributeService for MockAttributeService {
@@ -326,7 +414,7 @@ impl AttributeService for MockAttributeService {
         _session: &SessionState<Created>,
         _token_request: TokenRequest,
     ) -> Result<NonEmpty<Vec<AttestationPreview>>, Self::Error> {
-        let previews = vec![
+        let mut previews = vec![
             AttestationPreview::MsoMdoc {
                 unsigned_mdoc: UnsignedMdoc {
                     doc_type: MOCK_PID_DOCTYPE.to_string(),
@@ -370,6 +458,18 @@ impl AttributeService for MockAttributeService {
                 issuer: self.issuer_cert.clone(),
             },
         ];
+        if let Some(mdl_attributes) = &self.mdl_attributes {
+            previews.push(AttestationPreview::MsoMdoc {
+                unsigned_mdoc: UnsignedMdoc {
+                    doc_type: MDL_DOCTYPE.to_string(),
+                    copy_count: NonZeroU8::new(1).unwrap(),
+                    valid_from: Tdate::now(),
+                    valid_until: Utc::now().add(Days::new(365)).into(),
+                    attributes: mdl_attributes.clone().try_into().unwrap(),
+                },
+                issuer: self.issuer_cert.clone(),
+            });
+        }
         Ok(previews.try_into().unwrap())
     }
 
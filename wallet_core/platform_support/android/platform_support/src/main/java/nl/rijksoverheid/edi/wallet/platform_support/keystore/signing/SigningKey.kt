@@ -35,11 +35,12 @@ class SigningKey(keyAlias: String) : KeyStoreKey(keyAlias) {
             NoSuchAlgorithmException::class,
             IllegalStateException::class
         )
-        fun createKey(context: Context, keyAlias: String) {
+        fun createKey(context: Context, keyAlias: String, attestationChallenge: ByteArray? = null) {
             val spec = KeyGenParameterSpec.Builder(keyAlias, KeyProperties.PURPOSE_SIGN)
                 .setAlgorithmParameterSpec(ECGenParameterSpec("secp256r1"))
                 .setDigests(KeyProperties.DIGEST_SHA256)
                 .setStrongBoxBackedCompat(context, true)
+                .apply { if (attestationChallenge != null) setAttestationChallenge(attestationChallenge) }
 
             KeyPairGenerator.getInstance(
                 KeyProperties.KEY_ALGORITHM_EC,
@@ -60,6 +61,19 @@ class SigningKey(keyAlias: String) : KeyStoreKey(keyAlias) {
         }
     }
 
+    /**
+     * Returns the DER encoded Key Attestation certificate chain of this key,
+     * starting with the leaf certificate.
+     */
+    @Throws(KeyException::class)
+    fun certificateChain(): List<List<UByte>> {
+        try {
+            return keyStore.getCertificateChain(keyAlias).map { it.encoded.toUByteList() }
+        } catch (ex: Exception) {
+            throw KeyStoreKeyError.FetchKeyError(ex).keyException
+        }
+    }
+
     @Throws(KeyException::class)
     fun sign(payload: List<UByte>): List<UByte> {
         try {
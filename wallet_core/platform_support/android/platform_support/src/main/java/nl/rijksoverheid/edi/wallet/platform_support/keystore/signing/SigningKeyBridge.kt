@@ -3,6 +3,8 @@ package nl.rijksoverheid.edi.wallet.platform_support.keystore.signing
 import android.content.Context
 import nl.rijksoverheid.edi.wallet.platform_support.keystore.KeyBridge
 import nl.rijksoverheid.edi.wallet.platform_support.keystore.KeyStoreKeyError
+import nl.rijksoverheid.edi.wallet.platform_support.util.toByteArray
+import uniffi.platform_support.AttestationData
 import uniffi.platform_support.KeyStoreException
 import uniffi.platform_support.SigningKeyBridge as RustSigningBridge
 
@@ -33,6 +35,24 @@ class SigningKeyBridge(context: Context) : KeyBridge(context), RustSigningBridge
         return key.sign(payload)
     }
 
+    /**
+     * A key can only be attested when it is generated, so this replaces any existing
+     * key with a new one that has the [challengeHash] as its attestation challenge.
+     */
+    override fun attest(identifier: String, challengeHash: List<UByte>): AttestationData {
+        val keyAlias = SIGN_KEY_PREFIX + identifier
+        try {
+            verifyDeviceUnlocked()
+            if (keyExists(keyAlias)) deleteEntry(keyAlias)
+            SigningKey.createKey(context, keyAlias, challengeHash.toByteArray())
+            val key = SigningKey(keyAlias).takeIf { it.isConsideredValid }!!
+            return AttestationData.Android(key.certificateChain())
+        } catch (ex: Exception) {
+            if (ex is KeyStoreException) throw ex
+            throw KeyStoreKeyError.CreateKeyError(ex).keyException
+        }
+    }
+
     override fun delete(identifier: String) {
         val keyAlias = SIGN_KEY_PREFIX + identifier
         keyStore.deleteEntry(keyAlias)
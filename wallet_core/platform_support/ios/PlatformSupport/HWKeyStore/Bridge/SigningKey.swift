@@ -5,6 +5,7 @@
 //  Created by Wallet Developer on 24/02/2023.
 //
 
+import DeviceCheck
 import Foundation
 
 final class SigningKey {
@@ -32,6 +33,40 @@ extension SigningKey: SigningKeyBridge {
         }
     }
 
+    // App Attest attests a key of its own, which is bound to the Secure Enclave key
+    // by Rust through the challenge hash, that is passed as client data hash.
+    func attest(identifier _: String, challengeHash: [UInt8]) throws -> AttestationData {
+        guard #available(iOS 14.0, *), DCAppAttestService.shared.isSupported else {
+            throw KeyStoreError.KeyError(reason: "App Attest is not supported on this device")
+        }
+
+        let service = DCAppAttestService.shared
+
+        // DCAppAttestService is asynchronous only, so wait for the result on this background thread.
+        let semaphore = DispatchSemaphore(value: 0)
+        var result: Result<Data, Error>?
+
+        service.generateKey { keyId, error in
+            guard let keyId else {
+                result = .failure(error!)
+                semaphore.signal()
+                return
+            }
+
+            service.attestKey(keyId, clientDataHash: Data(challengeHash)) { attestationObject, error in
+                result = attestationObject.map { .success($0) } ?? .failure(error!)
+                semaphore.signal()
+            }
+        }
+        semaphore.wait()
+
+        do {
+            return try .apple(attestationObject: Array(result!.get()))
+        } catch {
+            throw KeyStoreError.KeyError(reason: "Could not attest key: \(error.localizedDescription)")
+        }
+    }
+
     func delete(identifier: String) throws {
         do {
             return try self.secureEnclaveKey(for: identifier).delete()
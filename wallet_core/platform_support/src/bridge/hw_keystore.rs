@@ -2,7 +2,7 @@ use std::fmt::Debug;
 
 use super::get_platform_support;
 
-pub use crate::hw_keystore::KeyStoreError;
+pub use crate::hw_keystore::{AttestationData, KeyStoreError};
 
 // this is required to catch UnexpectedUniFFICallbackError
 impl From<uniffi::UnexpectedUniFFICallbackError> for KeyStoreError {
@@ -15,6 +15,7 @@ impl From<uniffi::UnexpectedUniFFICallbackError> for KeyStoreError {
 pub trait SigningKeyBridge: Send + Sync + Debug {
     fn public_key(&self, identifier: String) -> Result<Vec<u8>, KeyStoreError>;
     fn sign(&self, identifier: String, payload: Vec<u8>) -> Result<Vec<u8>, KeyStoreError>;
+    fn attest(&self, identifier: String, challenge_hash: Vec<u8>) -> Result<AttestationData, KeyStoreError>;
     fn delete(&self, identifier: String) -> Result<(), KeyStoreError>;
 }
 
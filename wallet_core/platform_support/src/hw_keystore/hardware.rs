@@ -11,13 +11,15 @@ use p256::{
 use parking_lot::Mutex;
 
 use wallet_common::{
+    account::messages::auth::PlatformAttestation,
     keys::{EcdsaKey, EncryptionKey, SecureEcdsaKey, SecureEncryptionKey, StoredByIdentifier, WithIdentifier},
     spawn,
+    utils::sha256,
 };
 
 use crate::bridge::hw_keystore::{get_encryption_key_bridge, get_signing_key_bridge};
 
-use super::{HardwareKeyStoreError, KeyStoreError, PlatformEcdsaKey, PlatformEncryptionKey};
+use super::{AttestedEcdsaKey, HardwareKeyStoreError, KeyStoreError, PlatformEcdsaKey, PlatformEncryptionKey};
 
 /// A static hash map of sets that contains all the identifiers for which an instance
 /// of that type currently exists within the application, keyed by the type's `TypeId`.
@@ -123,6 +125,34 @@ impl StoredByIdentifier for HardwareEcdsaKey {
 
 impl PlatformEcdsaKey for HardwareEcdsaKey {}
 
+impl AttestedEcdsaKey for HardwareEcdsaKey {
+    type AttestationError = HardwareKeyStoreError;
+
+    async fn attest(&self, challenge: &[u8]) -> Result<PlatformAttestation, Self::AttestationError> {
+        let identifier = self.unique_key.identifier.clone();
+        let challenge = challenge.to_vec();
+
+        let attestation = spawn::blocking(|| {
+            let bridge = get_signing_key_bridge();
+
+            // Apple App Attest attests a separate key, so the hardware key is bound to
+            // the attestation by including its public key in the client data hash.
+            let challenge_hash = if cfg!(target_os = "ios") {
+                let public_key_bytes = bridge.public_key(identifier.clone())?;
+
+                sha256(&[challenge, public_key_bytes].concat())
+            } else {
+                sha256(&challenge)
+            };
+
+            bridge.attest(identifier, challenge_hash)
+        })
+        .await?;
+
+        Ok(attestation.into())
+    }
+}
+
 // HardwareEncryptionKey wraps EncryptionKeyBridge from native.
 pub struct HardwareEncryptionKey {
     unique_key: UniqueKey,
@@ -1,6 +1,9 @@
 pub mod hardware;
 
-use wallet_common::keys::{SecureEcdsaKey, SecureEncryptionKey, StoredByIdentifier};
+use wallet_common::{
+    account::messages::auth::PlatformAttestation,
+    keys::{SecureEcdsaKey, SecureEncryptionKey, StoredByIdentifier},
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum HardwareKeyStoreError {
@@ -21,6 +24,22 @@ pub enum KeyStoreError {
     BridgingError { reason: String },
 }
 
+// implementation of AttestationData from UDL
+#[derive(Debug)]
+pub enum AttestationData {
+    Android { certificate_chain: Vec<Vec<u8>> },
+    Apple { attestation_object: Vec<u8> },
+}
+
+impl From<AttestationData> for PlatformAttestation {
+    fn from(value: AttestationData) -> Self {
+        match value {
+            AttestationData::Android { certificate_chain } => PlatformAttestation::Android { certificate_chain },
+            AttestationData::Apple { attestation_object } => PlatformAttestation::Apple { attestation_object },
+        }
+    }
+}
+
 /// Contract for ECDSA private keys suitable for use in the wallet, e.g. as the authentication key for the WP.
 /// Should be sufficiently secured e.g. through Android's TEE/StrongBox or Apple's SE.
 /// Handles to private keys are requested through [`ConstructibleWithIdentifier::new()`].
@@ -29,6 +48,16 @@ pub trait PlatformEcdsaKey: StoredByIdentifier + SecureEcdsaKey {
     // from EcdsaKey: verifying_key(), try_sign(), sign()
 }
 
+/// Contract for [`PlatformEcdsaKey`]s of which the platform can attest that they are stored in secure hardware,
+/// by a genuine instance of the Wallet app. The Wallet Provider requires this when linking a new device.
+pub trait AttestedEcdsaKey: PlatformEcdsaKey {
+    type AttestationError: std::error::Error + Send + Sync + 'static;
+
+    /// Attest the key, bound to the `challenge` of the Wallet Provider. Note that on Android this replaces the key,
+    /// as a key can only be attested when it is generated, so this should be called before the key is used.
+    async fn attest(&self, challenge: &[u8]) -> Result<PlatformAttestation, Self::AttestationError>;
+}
+
 pub trait PlatformEncryptionKey: StoredByIdentifier + SecureEncryptionKey {
     // from StoredByIdentifier: new_unique(), delete(), identifier()
     // from EncryptionKey: encrypt(), decrypt()
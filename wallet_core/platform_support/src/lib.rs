@@ -8,7 +8,7 @@ pub mod hardware_test;
 
 // import generated Rust bindings
 use crate::bridge::{
-    hw_keystore::{EncryptionKeyBridge, KeyStoreError, SigningKeyBridge},
+    hw_keystore::{AttestationData, EncryptionKeyBridge, KeyStoreError, SigningKeyBridge},
     init_platform_support,
     utils::{UtilitiesBridge, UtilitiesError},
 };
@@ -104,6 +104,7 @@ async fn main() {
         items_requests: vec![ItemsRequest {
             doc_type: "com.example.pid".to_owned(),
             request_info: None,
+            device_name_spaces: None,
             name_spaces: IndexMap::from([(
                 "com.example.pid".to_owned(),
                 IndexMap::from_iter(
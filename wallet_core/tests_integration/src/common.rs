@@ -1,19 +1,19 @@
 use std::{
     net::{IpAddr, TcpListener},
+    path::Path,
     process,
     str::FromStr,
     time::Duration,
 };
 
 use ctor::ctor;
-use indexmap::IndexMap;
 use jsonwebtoken::{Algorithm, EncodingKey, Header};
 use reqwest::Certificate;
 use sea_orm::{Database, DatabaseConnection, EntityTrait, PaginatorTrait};
 use tokio::time;
 
 use configuration_server::settings::Settings as CsSettings;
-use nl_wallet_mdoc::{server_state::SessionState, utils::x509};
+use nl_wallet_mdoc::server_state::SessionState;
 use openid4vc::{
     issuance_session::HttpIssuanceSession,
     issuer::{AttributeService, Created},
@@ -35,10 +35,11 @@ use wallet_common::{
     nonempty::NonEmpty,
     reqwest::trusted_reqwest_client_builder,
 };
-use wallet_provider::settings::Settings as WpSettings;
+use wallet_provider::settings::{Hsm as WpHsm, PinPolicySettings as WpPinPolicySettings, Settings as WpSettings};
+use wallet_provider_domain::model::hsm::Hsm;
 use wallet_provider_persistence::entity::wallet_user;
 use wallet_server::{
-    pid::mock::MockAttributesLookup,
+    pid::{attributes::AttributeCertificates, mock::MockAttributesLookup},
     settings::{RequesterAuth, Server, Settings as WsSettings},
     store::SessionStoreVariant,
 };
@@ -112,6 +113,25 @@ pub async fn setup_wallet_and_env(
     served_wallet_config.pid_issuance.pid_issuer_url = local_pid_base_url(&ws_settings.wallet_server.port);
     served_wallet_config.account_server.base_url = local_wp_base_url(&wp_settings.webserver.port);
 
+    // The keys of a software HSM are generated when its key file is created, so the wallet should trust those instead
+    // of the keys in the configuration.
+    if let WpHsm::Software { .. } = wp_settings.hsm {
+        let hsm = wp_settings.configured_hsm().expect("Could not open software HSM");
+        let certificate_public_key = hsm
+            .get_verifying_key(&wp_settings.certificate_signing_key_identifier)
+            .await
+            .unwrap();
+        let instruction_result_public_key = hsm
+            .get_verifying_key(&wp_settings.instruction_result_signing_key_identifier)
+            .await
+            .unwrap();
+
+        for config in [&mut wallet_config, &mut served_wallet_config] {
+            config.account_server.certificate_public_key = certificate_public_key.into();
+            config.account_server.instruction_result_public_key = instruction_result_public_key.into();
+        }
+    }
+
     cs_settings.wallet_config_jwt = config_jwt(&served_wallet_config);
 
     let certificates = ws_settings.issuer.certificates();
@@ -184,7 +204,22 @@ pub fn wallet_provider_settings() -> WpSettings {
     let mut settings = WpSettings::new().expect("Could not read settings");
     settings.webserver.ip = IpAddr::from_str("127.0.0.1").unwrap();
     settings.webserver.port = port;
-    settings.pin_policy.timeouts = vec![200, 400, 600].into_iter().map(Duration::from_millis).collect();
+    settings.pin_policy = WpPinPolicySettings::Rounds {
+        rounds: 4,
+        attempts_per_round: 4,
+        timeouts: vec![200, 400, 600].into_iter().map(Duration::from_millis).collect(),
+    };
+    settings
+}
+
+/// Use a software HSM with a new key file in `key_file_dir` for the Wallet Provider, instead of the configured HSM.
+pub fn wallet_provider_settings_with_software_hsm(key_file_dir: &Path) -> WpSettings {
+    let mut settings = wallet_provider_settings();
+    settings.hsm = WpHsm::Software {
+        key_file: key_file_dir.join("wallet_provider_keys.json"),
+        passphrase: String::from("software_hsm_passphrase"),
+        allow_production: false,
+    };
     settings
 }
 
@@ -315,7 +350,7 @@ pub async fn do_pid_issuance(mut wallet: WalletWithMocks, pin: String) -> Wallet
     wallet
 }
 
-pub struct MockAttributeService(pub IndexMap<String, x509::Certificate>);
+pub struct MockAttributeService(pub AttributeCertificates);
 
 impl AttributeService for MockAttributeService {
     type Error = std::convert::Infallible;
@@ -329,10 +364,7 @@ impl AttributeService for MockAttributeService {
             .attributes("999991772")
             .unwrap()
             .into_iter()
-            .map(|unsigned_mdoc| AttestationPreview::MsoMdoc {
-                issuer: self.0[&unsigned_mdoc.doc_type].clone(),
-                unsigned_mdoc,
-            })
+            .map(|unsigned_mdoc| self.0.try_unsigned_mdoc_to_attestion_preview(unsigned_mdoc).unwrap())
             .collect::<Vec<_>>();
         Ok(attributes.try_into().unwrap())
     }
@@ -0,0 +1,81 @@
+use std::{collections::HashMap, net::IpAddr, str::FromStr};
+
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use tests_integration::common::*;
+use wallet_common::utils::random_string;
+use wallet_provider::settings::{AdminServer, OperatorApiKeys};
+
+const OPERATOR: &str = "helpdesk";
+const API_KEY: &str = "admin_api_key";
+
+#[tokio::test]
+async fn test_admin_api() {
+    let admin_port = find_listener_port();
+
+    let mut settings = wallet_provider_settings();
+    settings.admin_server = Some(AdminServer {
+        ip: IpAddr::from_str("127.0.0.1").unwrap(),
+        port: admin_port,
+        operator_api_keys: OperatorApiKeys::try_from(HashMap::from([(OPERATOR.to_string(), API_KEY.to_string())]))
+            .unwrap(),
+    });
+
+    start_wallet_provider(settings).await;
+
+    let client = reqwest::Client::new();
+    let base_url = format!("http://localhost:{}/admin/v1", admin_port);
+    let wallet_id = random_string(32);
+
+    // Requests without the API key should be rejected.
+    let response = client
+        .get(format!("{base_url}/wallet_users/{wallet_id}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .get(format!("{base_url}/wallet_users/{wallet_id}"))
+        .bearer_auth("unknown_api_key")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .get(format!("{base_url}/wallet_users/{wallet_id}"))
+        .query(&[("reason", "reported stolen")])
+        .bearer_auth(API_KEY)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = client
+        .put(format!("{base_url}/wallet_users/{wallet_id}/state"))
+        .bearer_auth(API_KEY)
+        .json(&serde_json::json!({ "state": "suspended" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Only the lookup should have been audited, as the state of an unknown wallet user cannot be changed.
+    let response = client
+        .get(format!("{base_url}/audit_log"))
+        .query(&[("wallet_id", &wallet_id)])
+        .bearer_auth(API_KEY)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let entries = response.json::<Vec<Value>>().await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["wallet_id"], wallet_id.as_str());
+    assert_eq!(entries[0]["action"], "lookup_wallet_user");
+    assert_eq!(entries[0]["operator"], OPERATOR);
+    assert_eq!(entries[0]["reason"], "reported stolen");
+}
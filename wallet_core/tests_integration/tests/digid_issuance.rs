@@ -94,6 +94,7 @@ async fn test_pid_issuance_digid_bridge() {
         server_url.clone(),
         token_request,
         &wallet_config.mdoc_trust_anchors(),
+        &wallet_config.mdoc_crls,
     )
     .await
     .unwrap();
@@ -101,6 +102,7 @@ async fn test_pid_issuance_digid_bridge() {
     let mdocs = pid_issuer_client
         .accept_issuance(
             &trust_anchors(&default_configuration()),
+            &[],
             SoftwareKeyFactory::default(),
             server_url,
         )
@@ -268,6 +268,7 @@ async fn test_disclosure_without_pid() {
         items_requests: vec![ItemsRequest {
             doc_type: "com.example.pid".to_owned(),
             request_info: None,
+            device_name_spaces: None,
             name_spaces: IndexMap::from([(
                 "com.example.pid".to_owned(),
                 IndexMap::from_iter(
@@ -6,6 +6,7 @@ use tokio::time::sleep;
 
 use tests_integration::common::*;
 use wallet::errors::{InstructionError, WalletUnlockError};
+use wallet_provider::settings::PinPolicySettings;
 
 #[tokio::test]
 #[serial]
@@ -34,9 +35,11 @@ async fn test_block() {
     let pin = "112234".to_string();
 
     let mut settings = wallet_provider_settings();
-    settings.pin_policy.rounds = 1;
-    settings.pin_policy.attempts_per_round = 2;
-    settings.pin_policy.timeouts = vec![];
+    settings.pin_policy = PinPolicySettings::Rounds {
+        rounds: 1,
+        attempts_per_round: 2,
+        timeouts: vec![],
+    };
 
     let mut wallet = setup_wallet_and_env(config_server_settings(), settings, wallet_server_settings()).await;
     wallet = do_wallet_registration(wallet, pin).await;
@@ -0,0 +1,50 @@
+use tempfile::TempDir;
+use url::Url;
+
+use openid4vc::token::TokenRequest;
+use tests_integration::common::*;
+use wallet::mock::MockDigidSession;
+use wallet_common::utils;
+
+#[tokio::test]
+async fn test_wallet_provider_with_software_hsm() {
+    let digid_context = MockDigidSession::start_context();
+    digid_context.expect().return_once(|_, _| {
+        let mut session = MockDigidSession::default();
+
+        session.expect_into_token_request().return_once(|_url| {
+            Ok(TokenRequest {
+                grant_type: openid4vc::token::TokenRequestGrantType::PreAuthorizedCode {
+                    pre_authorized_code: utils::random_string(32).into(),
+                },
+                code_verifier: Some("my_code_verifier".to_string()),
+                client_id: Some("my_client_id".to_string()),
+                redirect_uri: Some("redirect://here".parse().unwrap()),
+            })
+        });
+
+        Ok((session, Url::parse("http://localhost/").unwrap()))
+    });
+
+    let key_file_dir = TempDir::new().unwrap();
+    let settings = wallet_provider_settings_with_software_hsm(key_file_dir.path());
+    let connection = database_connection(&settings).await;
+
+    let pin = "112233".to_string();
+    let mut wallet = setup_wallet_and_env(config_server_settings(), settings, wallet_server_settings()).await;
+
+    // Registration uses the certificate signing key and the PIN public key encryption key.
+    let before = wallet_user_count(&connection).await;
+    wallet = do_wallet_registration(wallet, pin.clone()).await;
+    assert_eq!(wallet_user_count(&connection).await, before + 1);
+
+    // Issuance uses keys that are wrapped with the attestation wrapping key.
+    wallet = do_pid_issuance(wallet, pin.clone()).await;
+
+    // Unlocking checks the PIN, which decrypts the PIN public key and signs the instruction result.
+    wallet.lock();
+    wallet.unlock(pin).await.expect("Should unlock wallet");
+    assert!(!wallet.is_locked());
+
+    assert!(key_file_dir.path().join("wallet_provider_keys.json").exists());
+}
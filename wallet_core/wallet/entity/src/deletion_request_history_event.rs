@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+
+use crate::{deletion_request_history_event_doc_type, disclosure_history_event::EventStatus, history_doc_type};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "deletion_request_history_event")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub disclosure_history_event_id: Uuid,
+    pub relying_party_certificate: Vec<u8>,
+    pub status: EventStatus,
+    pub attributes: Json,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Related<history_doc_type::Entity> for Entity {
+    fn to() -> RelationDef {
+        deletion_request_history_event_doc_type::Relation::HistoryDocType.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(
+            deletion_request_history_event_doc_type::Relation::HistoryEvent
+                .def()
+                .rev(),
+        )
+    }
+}
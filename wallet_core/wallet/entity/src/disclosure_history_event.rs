@@ -33,6 +33,8 @@ pub struct Model {
     pub status: EventStatus,
     pub attributes: Option<Json>,
     pub r#type: EventType,
+    pub session_reference: Option<String>,
+    pub deletion_token: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,8 +1,8 @@
 use sea_orm::entity::prelude::*;
 
 use crate::{
-    disclosure_history_event, disclosure_history_event_doc_type, issuance_history_event,
-    issuance_history_event_doc_type,
+    deletion_request_history_event, deletion_request_history_event_doc_type, disclosure_history_event,
+    disclosure_history_event_doc_type, issuance_history_event, issuance_history_event_doc_type,
 };
 
 #[derive(Clone, Debug, Eq, PartialEq, DeriveEntityModel)]
@@ -37,3 +37,17 @@ impl Related<issuance_history_event::Entity> for Entity {
         Some(issuance_history_event_doc_type::Relation::HistoryDocType.def().rev())
     }
 }
+
+impl Related<deletion_request_history_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        deletion_request_history_event_doc_type::Relation::HistoryEvent.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(
+            deletion_request_history_event_doc_type::Relation::HistoryDocType
+                .def()
+                .rev(),
+        )
+    }
+}
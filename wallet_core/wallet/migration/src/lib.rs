@@ -3,6 +3,8 @@ pub use sea_orm_migration::prelude::*;
 mod m20230425_140221_create_keyed_data_table;
 mod m20230922_095234_create_mdoc_tables;
 mod m20231115_100948_create_history_tables;
+mod m20240612_101500_create_history_indexes;
+mod m20240705_093000_create_deletion_request_history_tables;
 
 pub struct Migrator;
 
@@ -12,6 +14,8 @@ impl MigratorTrait for Migrator {
             Box::new(m20230425_140221_create_keyed_data_table::Migration),
             Box::new(m20230922_095234_create_mdoc_tables::Migration),
             Box::new(m20231115_100948_create_history_tables::Migration),
+            Box::new(m20240612_101500_create_history_indexes::Migration),
+            Box::new(m20240705_093000_create_deletion_request_history_tables::Migration),
         ]
     }
 }
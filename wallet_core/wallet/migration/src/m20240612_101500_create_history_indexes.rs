@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use sea_orm_migration::prelude::*;
+
+const ISSUANCE_TIMESTAMP_INDEX: &str = "idx_issuance_history_event_timestamp_id";
+const DISCLOSURE_TIMESTAMP_INDEX: &str = "idx_disclosure_history_event_timestamp_id";
+const DISCLOSURE_RELYING_PARTY_INDEX: &str = "idx_disclosure_history_event_relying_party_certificate";
+const DISCLOSURE_TYPE_STATUS_INDEX: &str = "idx_disclosure_history_event_type_status";
+const ISSUANCE_DOC_TYPE_INDEX: &str = "idx_issuance_history_event_doc_type_history_doc_type_id";
+const DISCLOSURE_DOC_TYPE_INDEX: &str = "idx_disclosure_history_event_doc_type_history_doc_type_id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // These indexes support the (paginated) history queries, which are ordered
+        // by timestamp and id and optionally filtered by relying party, type and status.
+        manager
+            .create_index(
+                Index::create()
+                    .name(ISSUANCE_TIMESTAMP_INDEX)
+                    .table(IssuanceHistoryEvent::Table)
+                    .col(IssuanceHistoryEvent::Timestamp)
+                    .col(IssuanceHistoryEvent::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(DISCLOSURE_TIMESTAMP_INDEX)
+                    .table(DisclosureHistoryEvent::Table)
+                    .col(DisclosureHistoryEvent::Timestamp)
+                    .col(DisclosureHistoryEvent::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(DISCLOSURE_RELYING_PARTY_INDEX)
+                    .table(DisclosureHistoryEvent::Table)
+                    .col(DisclosureHistoryEvent::RelyingPartyCertificate)
+                    .col(DisclosureHistoryEvent::Timestamp)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(DISCLOSURE_TYPE_STATUS_INDEX)
+                    .table(DisclosureHistoryEvent::Table)
+                    .col(DisclosureHistoryEvent::Type)
+                    .col(DisclosureHistoryEvent::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        // The primary keys of the mapping tables start with the event id,
+        // so add indexes for looking up events by their doc_type.
+        manager
+            .create_index(
+                Index::create()
+                    .name(ISSUANCE_DOC_TYPE_INDEX)
+                    .table(IssuanceHistoryEventDocType::Table)
+                    .col(IssuanceHistoryEventDocType::HistoryDocTypeId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(DISCLOSURE_DOC_TYPE_INDEX)
+                    .table(DisclosureHistoryEventDocType::Table)
+                    .col(DisclosureHistoryEventDocType::HistoryDocTypeId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (name, table) in [
+            (
+                DISCLOSURE_DOC_TYPE_INDEX,
+                DisclosureHistoryEventDocType::Table.into_iden(),
+            ),
+            (ISSUANCE_DOC_TYPE_INDEX, IssuanceHistoryEventDocType::Table.into_iden()),
+            (DISCLOSURE_TYPE_STATUS_INDEX, DisclosureHistoryEvent::Table.into_iden()),
+            (
+                DISCLOSURE_RELYING_PARTY_INDEX,
+                DisclosureHistoryEvent::Table.into_iden(),
+            ),
+            (DISCLOSURE_TIMESTAMP_INDEX, DisclosureHistoryEvent::Table.into_iden()),
+            (ISSUANCE_TIMESTAMP_INDEX, IssuanceHistoryEvent::Table.into_iden()),
+        ] {
+            manager
+                .drop_index(Index::drop().name(name).table(table).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum IssuanceHistoryEvent {
+    Table,
+    Id,
+    Timestamp,
+}
+
+#[derive(DeriveIden)]
+enum DisclosureHistoryEvent {
+    Table,
+    Id,
+    Timestamp,
+    RelyingPartyCertificate,
+    Status,
+    Type,
+}
+
+#[derive(DeriveIden)]
+enum IssuanceHistoryEventDocType {
+    Table,
+    HistoryDocTypeId,
+}
+
+#[derive(DeriveIden)]
+enum DisclosureHistoryEventDocType {
+    Table,
+    HistoryDocTypeId,
+}
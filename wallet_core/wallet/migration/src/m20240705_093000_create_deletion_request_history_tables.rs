@@ -0,0 +1,172 @@
+use async_trait::async_trait;
+use sea_orm_migration::prelude::*;
+
+const DELETION_REQUEST_TIMESTAMP_INDEX: &str = "idx_deletion_request_history_event_timestamp_id";
+const DELETION_REQUEST_DOC_TYPE_INDEX: &str = "idx_deletion_request_history_event_doc_type_history_doc_type_id";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The session reference is needed to refer to a disclosure session when requesting deletion of its attributes,
+        // along with the deletion token that the verifier issued after a successful disclosure.
+        // Note that SQLite only supports adding a single column per statement.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DisclosureHistoryEvent::Table)
+                    .add_column(ColumnDef::new(DisclosureHistoryEvent::SessionReference).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DisclosureHistoryEvent::Table)
+                    .add_column(ColumnDef::new(DisclosureHistoryEvent::DeletionToken).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeletionRequestHistoryEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DeletionRequestHistoryEvent::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionRequestHistoryEvent::Timestamp)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionRequestHistoryEvent::DisclosureHistoryEventId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionRequestHistoryEvent::RelyingPartyCertificate)
+                            .binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(DeletionRequestHistoryEvent::Status).text().not_null())
+                    .col(
+                        ColumnDef::new(DeletionRequestHistoryEvent::Attributes)
+                            .json()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeletionRequestHistoryEventDocType::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DeletionRequestHistoryEventDocType::DeletionRequestHistoryEventId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(DeletionRequestHistoryEventDocType::HistoryDocTypeId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(DeletionRequestHistoryEventDocType::DeletionRequestHistoryEventId)
+                            .col(DeletionRequestHistoryEventDocType::HistoryDocTypeId),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(DELETION_REQUEST_TIMESTAMP_INDEX)
+                    .table(DeletionRequestHistoryEvent::Table)
+                    .col(DeletionRequestHistoryEvent::Timestamp)
+                    .col(DeletionRequestHistoryEvent::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(DELETION_REQUEST_DOC_TYPE_INDEX)
+                    .table(DeletionRequestHistoryEventDocType::Table)
+                    .col(DeletionRequestHistoryEventDocType::HistoryDocTypeId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(DeletionRequestHistoryEventDocType::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(DeletionRequestHistoryEvent::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DisclosureHistoryEvent::Table)
+                    .drop_column(DisclosureHistoryEvent::DeletionToken)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DisclosureHistoryEvent::Table)
+                    .drop_column(DisclosureHistoryEvent::SessionReference)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DisclosureHistoryEvent {
+    Table,
+    SessionReference,
+    DeletionToken,
+}
+
+#[derive(DeriveIden)]
+enum DeletionRequestHistoryEvent {
+    Table,
+    Id,
+    Timestamp,
+    DisclosureHistoryEventId,
+    RelyingPartyCertificate,
+    Status,
+    Attributes,
+}
+
+#[derive(DeriveIden)]
+enum DeletionRequestHistoryEventDocType {
+    Table,
+    DeletionRequestHistoryEventId,
+    HistoryDocTypeId,
+}
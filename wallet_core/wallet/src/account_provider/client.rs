@@ -7,7 +7,7 @@ use url::Url;
 use wallet_common::{
     account::{
         messages::{
-            auth::{Certificate, Challenge, Registration, WalletCertificate},
+            auth::{Certificate, Challenge, DeviceLink, Registration, WalletCertificate},
             errors::{AccountError, AccountErrorType},
             instructions::{
                 Instruction, InstructionChallengeRequestMessage, InstructionEndpoint, InstructionResult,
@@ -142,6 +142,17 @@ impl AccountProviderClient for HttpAccountProviderClient {
         Ok(cert.certificate)
     }
 
+    async fn link_device(
+        &self,
+        base_url: &BaseUrl,
+        link_message: SignedDouble<DeviceLink>,
+    ) -> Result<WalletCertificate, AccountProviderError> {
+        let url = base_url.join("linkdevice");
+        let cert: Certificate = self.send_json_post_request(url, &link_message).await?;
+
+        Ok(cert.certificate)
+    }
+
     async fn instruction_challenge(
         &self,
         base_url: &BaseUrl,
@@ -6,7 +6,7 @@ use url::ParseError;
 use wallet_common::{
     account::{
         messages::{
-            auth::{Registration, WalletCertificate},
+            auth::{DeviceLink, Registration, WalletCertificate},
             errors::{AccountError, AccountErrorType},
             instructions::{Instruction, InstructionChallengeRequestMessage, InstructionEndpoint, InstructionResult},
         },
@@ -47,6 +47,12 @@ pub trait AccountProviderClient {
         registration_message: SignedDouble<Registration>,
     ) -> Result<WalletCertificate, AccountProviderError>;
 
+    async fn link_device(
+        &self,
+        base_url: &BaseUrl,
+        link_message: SignedDouble<DeviceLink>,
+    ) -> Result<WalletCertificate, AccountProviderError>;
+
     async fn instruction_challenge(
         &self,
         base_url: &BaseUrl,
@@ -153,7 +153,9 @@ pub fn default_configuration() -> WalletConfiguration {
         },
         disclosure: DisclosureConfiguration {
             rp_trust_anchors: parse_trust_anchors(config_default!(RP_TRUST_ANCHORS)),
+            rp_crls: vec![],
         },
         mdoc_trust_anchors: parse_trust_anchors(config_default!(MDOC_TRUST_ANCHORS)),
+        mdoc_crls: vec![],
     }
 }
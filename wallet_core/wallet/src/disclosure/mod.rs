@@ -5,8 +5,8 @@ use uuid::Uuid;
 
 use nl_wallet_mdoc::{
     holder::{
-        CborHttpClient, DisclosureError, DisclosureMissingAttributes, DisclosureProposal, DisclosureResult,
-        DisclosureSession, MdocDataSource, ProposedAttributes, TrustAnchor,
+        CandidateAttributes, CborHttpClient, DisclosureError, DisclosureMissingAttributes, DisclosureProposal,
+        DisclosureResult, DisclosureSession, MdocDataSource, ProposedAttributes, SelfAssertedAttributes, TrustAnchor,
     },
     identifiers::AttributeIdentifier,
     utils::{
@@ -16,8 +16,11 @@ use nl_wallet_mdoc::{
     },
     verifier::SessionType,
 };
-use openid4vc::disclosure_session::{HttpVpMessageClient, VpClientError};
-use wallet_common::reqwest::default_reqwest_client_builder;
+use openid4vc::{
+    deletion_request::DeletionRequest,
+    disclosure_session::{HttpVpMessageClient, VpClientError},
+};
+use wallet_common::{reqwest::default_reqwest_client_builder, revocation_list::DerCertificateRevocationList};
 
 pub use nl_wallet_mdoc::holder::DisclosureUriSource;
 
@@ -32,6 +35,15 @@ pub enum MdocDisclosureSessionState<M, P> {
     Proposal(P),
 }
 
+/// The response of the verifier to a successful disclosure.
+#[derive(Debug, Clone, Default)]
+pub struct DisclosureResponse {
+    /// The URL to return the user to, if any.
+    pub return_url: Option<Url>,
+    /// The token with which the wallet can later request deletion of the disclosed attributes, if issued.
+    pub deletion_token: Option<String>,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum MdocDisclosureError {
     #[error("error in mdoc disclosure session: {0}")]
@@ -52,6 +64,7 @@ pub trait MdocDisclosureSession<D> {
         disclosure_uri_source: DisclosureUriSource,
         mdoc_data_source: &D,
         trust_anchors: &[TrustAnchor<'a>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Self, MdocDisclosureError>
     where
         Self: Sized;
@@ -59,21 +72,38 @@ pub trait MdocDisclosureSession<D> {
     fn rp_certificate(&self) -> &Certificate;
     fn reader_registration(&self) -> &ReaderRegistration;
     fn session_state(&self) -> MdocDisclosureSessionState<&Self::MissingAttributes, &Self::Proposal>;
+    fn session_state_mut(&mut self) -> MdocDisclosureSessionState<&mut Self::MissingAttributes, &mut Self::Proposal>;
     fn session_type(&self) -> SessionType;
 
     async fn terminate(self) -> Result<(), MdocDisclosureError>;
+
+    /// Sends a request to delete attributes that were disclosed in an earlier session
+    /// to the endpoint advertised by the Relying Party.
+    async fn send_deletion_request(url: Url, deletion_request: DeletionRequest) -> Result<(), MdocDisclosureError>;
 }
 
 #[cfg_attr(any(test, feature = "mock"), mockall::automock)]
 pub trait MdocDisclosureMissingAttributes {
-    fn missing_attributes(&self) -> &[AttributeIdentifier];
+    /// Returns the missing attributes for every `Mdoc` candidate.
+    fn missing_attributes(&self) -> &[Vec<AttributeIdentifier>];
 }
 
 pub trait MdocDisclosureProposal {
     fn proposed_source_identifiers(&self) -> Vec<Uuid>;
     fn proposed_attributes(&self) -> ProposedAttributes;
-
-    async fn disclose<KF, K>(&self, key_factory: &KF) -> DisclosureResult<Option<Url>, MdocDisclosureError>
+    /// Returns the attributes of every `Mdoc` candidate that matches the request, per doc type.
+    fn candidate_attributes(&self) -> CandidateAttributes;
+    /// Selects the candidate to disclose for every doc type, by index into [`Self::candidate_attributes`].
+    fn select_candidates(&mut self, selection: &[usize]) -> Result<(), nl_wallet_mdoc::Error>;
+    /// Returns the self-asserted attributes requested by the verifier, of which the user has to provide the values.
+    fn requested_self_asserted_attributes(&self) -> Vec<AttributeIdentifier>;
+    /// Sets the values of the self-asserted attributes listed by [`Self::requested_self_asserted_attributes`].
+    fn set_self_asserted_attributes(&mut self, attributes: SelfAssertedAttributes)
+        -> Result<(), nl_wallet_mdoc::Error>;
+    /// Returns the reference by which the verifier identifies the session, if any.
+    fn session_reference(&self) -> Option<String>;
+
+    async fn disclose<KF, K>(&self, key_factory: &KF) -> DisclosureResult<DisclosureResponse, MdocDisclosureError>
     where
         KF: KeyFactory<Key = K>,
         K: MdocEcdsaKey;
@@ -83,6 +113,17 @@ type VpDisclosureSession = openid4vc::disclosure_session::DisclosureSession<Http
 type VpDisclosureMissingAttributes = openid4vc::disclosure_session::DisclosureMissingAttributes<HttpVpMessageClient>;
 type VpDisclosureProposal = openid4vc::disclosure_session::DisclosureProposal<HttpVpMessageClient, Uuid>;
 
+async fn post_deletion_request(url: Url, deletion_request: DeletionRequest) -> Result<(), MdocDisclosureError> {
+    let http_client = default_reqwest_client_builder()
+        .build()
+        .expect("Could not build reqwest HTTP client");
+
+    deletion_request
+        .send(&http_client, url)
+        .await
+        .map_err(|error| VpClientError::Request(error).into())
+}
+
 impl<D> MdocDisclosureSession<D> for VpDisclosureSession
 where
     D: MdocDataSource<MdocIdentifier = Uuid>,
@@ -100,6 +141,7 @@ where
         uri_source: DisclosureUriSource,
         mdoc_data_source: &D,
         trust_anchors: &[TrustAnchor<'a>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Self, MdocDisclosureError>
     where
         Self: Sized,
@@ -114,6 +156,7 @@ where
             uri_source,
             mdoc_data_source,
             trust_anchors,
+            crls,
         )
         .await?;
 
@@ -135,6 +178,15 @@ where
         }
     }
 
+    fn session_state_mut(
+        &mut self,
+    ) -> MdocDisclosureSessionState<&mut VpDisclosureMissingAttributes, &mut VpDisclosureProposal> {
+        match self {
+            Self::MissingAttributes(session) => MdocDisclosureSessionState::MissingAttributes(session),
+            Self::Proposal(session) => MdocDisclosureSessionState::Proposal(session),
+        }
+    }
+
     fn session_type(&self) -> SessionType {
         self.session_type()
     }
@@ -142,10 +194,14 @@ where
     async fn terminate(self) -> Result<(), MdocDisclosureError> {
         Ok(self.terminate().await?)
     }
+
+    async fn send_deletion_request(url: Url, deletion_request: DeletionRequest) -> Result<(), MdocDisclosureError> {
+        post_deletion_request(url, deletion_request).await
+    }
 }
 
 impl MdocDisclosureMissingAttributes for VpDisclosureMissingAttributes {
-    fn missing_attributes(&self) -> &[AttributeIdentifier] {
+    fn missing_attributes(&self) -> &[Vec<AttributeIdentifier>] {
         self.missing_attributes()
     }
 }
@@ -159,16 +215,46 @@ impl MdocDisclosureProposal for VpDisclosureProposal {
         self.proposed_attributes()
     }
 
-    async fn disclose<KF, K>(&self, key_factory: &KF) -> DisclosureResult<Option<Url>, MdocDisclosureError>
+    fn candidate_attributes(&self) -> CandidateAttributes {
+        self.candidate_attributes()
+    }
+
+    fn select_candidates(&mut self, selection: &[usize]) -> Result<(), nl_wallet_mdoc::Error> {
+        self.select_candidates(selection)
+    }
+
+    fn requested_self_asserted_attributes(&self) -> Vec<AttributeIdentifier> {
+        self.requested_self_asserted_attributes()
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    fn set_self_asserted_attributes(
+        &mut self,
+        attributes: SelfAssertedAttributes,
+    ) -> Result<(), nl_wallet_mdoc::Error> {
+        self.set_self_asserted_attributes(attributes)
+    }
+
+    fn session_reference(&self) -> Option<String> {
+        self.session_reference().map(str::to_string)
+    }
+
+    async fn disclose<KF, K>(&self, key_factory: &KF) -> DisclosureResult<DisclosureResponse, MdocDisclosureError>
     where
         KF: KeyFactory<Key = K>,
         K: MdocEcdsaKey,
     {
-        let redirect_uri = self
+        let response = self
             .disclose(key_factory)
             .await
             .map_err(|err| DisclosureError::new(err.data_shared, err.error.into()))?;
-        Ok(redirect_uri.map(|u| u.into_inner()))
+
+        Ok(DisclosureResponse {
+            return_url: response.redirect_uri.map(|u| u.into_inner()),
+            deletion_token: response.deletion_token,
+        })
     }
 }
 
@@ -189,6 +275,7 @@ where
         disclosure_uri_source: DisclosureUriSource,
         mdoc_data_source: &D,
         trust_anchors: &[TrustAnchor<'a>],
+        crls: &[DerCertificateRevocationList],
     ) -> Result<Self, MdocDisclosureError> {
         let http_client = default_reqwest_client_builder()
             .build()
@@ -200,6 +287,7 @@ where
             disclosure_uri_source,
             mdoc_data_source,
             trust_anchors,
+            crls,
         )
         .await?;
 
@@ -226,17 +314,33 @@ where
         }
     }
 
+    fn session_state_mut(
+        &mut self,
+    ) -> MdocDisclosureSessionState<
+        &mut DisclosureMissingAttributes<CborHttpClient>,
+        &mut DisclosureProposal<CborHttpClient, Uuid>,
+    > {
+        match self {
+            DisclosureSession::MissingAttributes(session) => MdocDisclosureSessionState::MissingAttributes(session),
+            DisclosureSession::Proposal(session) => MdocDisclosureSessionState::Proposal(session),
+        }
+    }
+
     async fn terminate(self) -> Result<(), MdocDisclosureError> {
         Ok(self.terminate().await?)
     }
 
+    async fn send_deletion_request(url: Url, deletion_request: DeletionRequest) -> Result<(), MdocDisclosureError> {
+        post_deletion_request(url, deletion_request).await
+    }
+
     fn session_type(&self) -> SessionType {
         self.session_type()
     }
 }
 
 impl MdocDisclosureMissingAttributes for DisclosureMissingAttributes<CborHttpClient> {
-    fn missing_attributes(&self) -> &[AttributeIdentifier] {
+    fn missing_attributes(&self) -> &[Vec<AttributeIdentifier>] {
         self.missing_attributes()
     }
 }
@@ -250,7 +354,34 @@ impl MdocDisclosureProposal for DisclosureProposal<CborHttpClient, Uuid> {
         self.proposed_attributes()
     }
 
-    async fn disclose<KF, K>(&self, key_factory: &KF) -> DisclosureResult<Option<Url>, MdocDisclosureError>
+    fn candidate_attributes(&self) -> CandidateAttributes {
+        self.candidate_attributes()
+    }
+
+    fn select_candidates(&mut self, selection: &[usize]) -> Result<(), nl_wallet_mdoc::Error> {
+        self.select_candidates(selection)
+    }
+
+    fn requested_self_asserted_attributes(&self) -> Vec<AttributeIdentifier> {
+        self.requested_self_asserted_attributes()
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    fn set_self_asserted_attributes(
+        &mut self,
+        attributes: SelfAssertedAttributes,
+    ) -> Result<(), nl_wallet_mdoc::Error> {
+        self.set_self_asserted_attributes(attributes)
+    }
+
+    fn session_reference(&self) -> Option<String> {
+        // The ISO 18013-5 protocol does not provide a reference to the session.
+        None
+    }
+
+    async fn disclose<KF, K>(&self, key_factory: &KF) -> DisclosureResult<DisclosureResponse, MdocDisclosureError>
     where
         KF: KeyFactory<Key = K>,
         K: MdocEcdsaKey,
@@ -259,7 +390,11 @@ impl MdocDisclosureProposal for DisclosureProposal<CborHttpClient, Uuid> {
             .await
             .map_err(|err| DisclosureError::new(err.data_shared, err.error.into()))?;
 
-        Ok(self.return_url().cloned())
+        // The ISO 18013-5 protocol does not support deletion requests.
+        Ok(DisclosureResponse {
+            return_url: self.return_url().cloned(),
+            deletion_token: None,
+        })
     }
 }
 
@@ -273,7 +408,11 @@ mod mock {
     use once_cell::sync::Lazy;
     use parking_lot::Mutex;
 
-    use nl_wallet_mdoc::{holder::DisclosureError, server_keys::KeyPair, verifier::SessionType};
+    use nl_wallet_mdoc::{
+        holder::{DisclosureError, HolderError},
+        server_keys::KeyPair,
+        verifier::SessionType,
+    };
 
     use super::*;
 
@@ -282,6 +421,8 @@ mod mock {
 
     pub static NEXT_START_ERROR: Lazy<Mutex<Option<nl_wallet_mdoc::Error>>> = Lazy::new(|| Mutex::new(None));
     pub static NEXT_MOCK_FIELDS: Lazy<Mutex<Option<MockFields>>> = Lazy::new(|| Mutex::new(None));
+    pub static NEXT_DELETION_REQUEST_ERROR: Lazy<Mutex<Option<VpClientError>>> = Lazy::new(|| Mutex::new(None));
+    pub static SENT_DELETION_REQUESTS: Lazy<Mutex<Vec<(Url, DeletionRequest)>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
     // For convenience, the default `SessionState` is a proposal.
     impl Default for SessionState {
@@ -295,10 +436,16 @@ mod mock {
         pub return_url: Option<Url>,
         pub proposed_source_identifiers: Vec<Uuid>,
         pub proposed_attributes: ProposedAttributes,
+        /// If empty, the proposed attributes are the only candidate for every doc type.
+        pub candidate_attributes: CandidateAttributes,
         pub disclosure_count: Arc<AtomicUsize>,
         pub next_error: Mutex<Option<nl_wallet_mdoc::Error>>,
         pub attributes_shared: bool,
         pub session_type: SessionType,
+        pub session_reference: Option<String>,
+        pub deletion_token: Option<String>,
+        pub requested_self_asserted_attributes: Vec<AttributeIdentifier>,
+        pub self_asserted_attributes: SelfAssertedAttributes,
     }
 
     impl Default for MockMdocDisclosureProposal {
@@ -307,10 +454,15 @@ mod mock {
                 return_url: Default::default(),
                 proposed_source_identifiers: Default::default(),
                 proposed_attributes: Default::default(),
+                candidate_attributes: Default::default(),
                 disclosure_count: Default::default(),
                 next_error: Default::default(),
                 attributes_shared: Default::default(),
                 session_type: SessionType::SameDevice,
+                session_reference: Default::default(),
+                deletion_token: Default::default(),
+                requested_self_asserted_attributes: Default::default(),
+                self_asserted_attributes: Default::default(),
             }
         }
     }
@@ -324,7 +476,58 @@ mod mock {
             self.proposed_attributes.clone()
         }
 
-        async fn disclose<KF, K>(&self, _key_factory: &KF) -> DisclosureResult<Option<Url>, MdocDisclosureError>
+        fn candidate_attributes(&self) -> CandidateAttributes {
+            if !self.candidate_attributes.is_empty() {
+                return self.candidate_attributes.clone();
+            }
+
+            self.proposed_attributes
+                .iter()
+                .map(|(doc_type, attributes)| (doc_type.clone(), vec![attributes.clone()]))
+                .collect()
+        }
+
+        fn select_candidates(&mut self, selection: &[usize]) -> Result<(), nl_wallet_mdoc::Error> {
+            let candidate_attributes = self.candidate_attributes();
+            let invalid_selection =
+                || nl_wallet_mdoc::Error::from(HolderError::InvalidCandidateSelection(selection.to_vec()));
+
+            if selection.len() != candidate_attributes.len() {
+                return Err(invalid_selection());
+            }
+
+            // Replace the proposed attributes with those of the selected candidates.
+            self.proposed_attributes = candidate_attributes
+                .into_iter()
+                .zip(selection)
+                .map(|((doc_type, candidates), index)| {
+                    let attributes = candidates.into_iter().nth(*index).ok_or_else(invalid_selection)?;
+
+                    Ok((doc_type, attributes))
+                })
+                .collect::<Result<_, nl_wallet_mdoc::Error>>()?;
+
+            Ok(())
+        }
+
+        fn requested_self_asserted_attributes(&self) -> Vec<AttributeIdentifier> {
+            self.requested_self_asserted_attributes.clone()
+        }
+
+        fn set_self_asserted_attributes(
+            &mut self,
+            attributes: SelfAssertedAttributes,
+        ) -> Result<(), nl_wallet_mdoc::Error> {
+            self.self_asserted_attributes = attributes;
+
+            Ok(())
+        }
+
+        fn session_reference(&self) -> Option<String> {
+            self.session_reference.clone()
+        }
+
+        async fn disclose<KF, K>(&self, _key_factory: &KF) -> DisclosureResult<DisclosureResponse, MdocDisclosureError>
         where
             KF: KeyFactory<Key = K>,
             K: MdocEcdsaKey,
@@ -336,7 +539,10 @@ mod mock {
             self.disclosure_count
                 .store(self.disclosure_count.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
 
-            Ok(self.return_url.clone())
+            Ok(DisclosureResponse {
+                return_url: self.return_url.clone(),
+                deletion_token: self.deletion_token.clone(),
+            })
         }
     }
 
@@ -358,6 +564,15 @@ mod mock {
         pub fn next_start_error(error: nl_wallet_mdoc::Error) {
             NEXT_START_ERROR.lock().replace(error);
         }
+
+        pub fn next_deletion_request_error(error: VpClientError) {
+            NEXT_DELETION_REQUEST_ERROR.lock().replace(error);
+        }
+
+        /// Returns and clears the deletion requests that were sent through this mock.
+        pub fn take_sent_deletion_requests() -> Vec<(Url, DeletionRequest)> {
+            std::mem::take(&mut SENT_DELETION_REQUESTS.lock())
+        }
     }
 
     /// The reader key, generated once for testing.
@@ -399,6 +614,7 @@ mod mock {
             disclosure_uri_source: DisclosureUriSource,
             _mdoc_data_source: &D,
             _trust_anchors: &[TrustAnchor<'a>],
+            _crls: &[DerCertificateRevocationList],
         ) -> Result<Self, MdocDisclosureError> {
             if let Some(error) = NEXT_START_ERROR.lock().take() {
                 Err(error)?;
@@ -428,6 +644,17 @@ mod mock {
             }
         }
 
+        fn session_state_mut(
+            &mut self,
+        ) -> MdocDisclosureSessionState<&mut Self::MissingAttributes, &mut Self::Proposal> {
+            match self.session_state {
+                MdocDisclosureSessionState::MissingAttributes(ref mut session) => {
+                    MdocDisclosureSessionState::MissingAttributes(session)
+                }
+                MdocDisclosureSessionState::Proposal(ref mut session) => MdocDisclosureSessionState::Proposal(session),
+            }
+        }
+
         fn reader_registration(&self) -> &ReaderRegistration {
             &self.reader_registration
         }
@@ -445,5 +672,15 @@ mod mock {
         fn session_type(&self) -> SessionType {
             self.session_type
         }
+
+        async fn send_deletion_request(url: Url, deletion_request: DeletionRequest) -> Result<(), MdocDisclosureError> {
+            if let Some(error) = NEXT_DELETION_REQUEST_ERROR.lock().take() {
+                Err(error)?;
+            }
+
+            SENT_DELETION_REQUESTS.lock().push((url, deletion_request));
+
+            Ok(())
+        }
     }
 }
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use indexmap::IndexMap;
 use once_cell::sync::Lazy;
 
+use nl_wallet_mdoc::{MDL_DOCTYPE, MDL_NAMESPACE};
+
 use super::{mdoc::AttributeValueType, AttributeKey, AttributeLabels, ADDRESS_DOCTYPE, PID_DOCTYPE};
 
 #[derive(Debug, Clone)]
@@ -227,5 +229,332 @@ pub(super) static MDOC_DOCUMENT_MAPPING: Lazy<MdocDocumentMapping> = Lazy::new(|
                 ),
             ]),
         ),
+        (
+            MDL_DOCTYPE,
+            IndexMap::from([
+                (
+                    (MDL_NAMESPACE, "given_name"),
+                    DataElementValueMapping {
+                        key: "given_name",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "First names"), ("nl", "Voornamen")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "family_name"),
+                    DataElementValueMapping {
+                        key: "family_name",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Surname"), ("nl", "Achternaam")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "birth_date"),
+                    DataElementValueMapping {
+                        key: "birth_date",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Birth date"), ("nl", "Geboortedatum")]),
+                        value_type: AttributeValueType::Date,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "portrait"),
+                    DataElementValueMapping {
+                        key: "portrait",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Portrait"), ("nl", "Pasfoto")]),
+                        value_type: AttributeValueType::Image,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "document_number"),
+                    DataElementValueMapping {
+                        key: "document_number",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Document number"), ("nl", "Documentnummer")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "issue_date"),
+                    DataElementValueMapping {
+                        key: "issue_date",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Issue date"), ("nl", "Datum van afgifte")]),
+                        // ISO 18013-5 allows the issue and expiry dates to be either a `tdate` or a `full-date`.
+                        value_type: AttributeValueType::Structured,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "expiry_date"),
+                    DataElementValueMapping {
+                        key: "expiry_date",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Expiry date"), ("nl", "Vervaldatum")]),
+                        value_type: AttributeValueType::Structured,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "issuing_country"),
+                    DataElementValueMapping {
+                        key: "issuing_country",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Issuing country"), ("nl", "Land van afgifte")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "issuing_authority"),
+                    DataElementValueMapping {
+                        key: "issuing_authority",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Issuing authority"), ("nl", "Afgegeven door")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "un_distinguishing_sign"),
+                    DataElementValueMapping {
+                        key: "un_distinguishing_sign",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Country code"), ("nl", "Landcode")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "driving_privileges"),
+                    DataElementValueMapping {
+                        key: "driving_privileges",
+                        is_mandatory: true,
+                        key_labels: HashMap::from([("en", "Driving privileges"), ("nl", "Rijbevoegdheden")]),
+                        value_type: AttributeValueType::Structured,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "administrative_number"),
+                    DataElementValueMapping {
+                        key: "administrative_number",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Administrative number"), ("nl", "Administratienummer")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "sex"),
+                    DataElementValueMapping {
+                        key: "sex",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Gender"), ("nl", "Geslacht")]),
+                        value_type: AttributeValueType::Gender,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "height"),
+                    DataElementValueMapping {
+                        key: "height",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Height (cm)"), ("nl", "Lengte (cm)")]),
+                        value_type: AttributeValueType::Integer,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "weight"),
+                    DataElementValueMapping {
+                        key: "weight",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Weight (kg)"), ("nl", "Gewicht (kg)")]),
+                        value_type: AttributeValueType::Integer,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "eye_colour"),
+                    DataElementValueMapping {
+                        key: "eye_colour",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Eye colour"), ("nl", "Oogkleur")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "hair_colour"),
+                    DataElementValueMapping {
+                        key: "hair_colour",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Hair colour"), ("nl", "Haarkleur")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "birth_place"),
+                    DataElementValueMapping {
+                        key: "birth_place",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Place of birth"), ("nl", "Geboorteplaats")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "resident_address"),
+                    DataElementValueMapping {
+                        key: "resident_address",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Address"), ("nl", "Adres")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "resident_city"),
+                    DataElementValueMapping {
+                        key: "resident_city",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "City, town or village"), ("nl", "Woonplaats")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "resident_state"),
+                    DataElementValueMapping {
+                        key: "resident_state",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "State or province"), ("nl", "Staat of provincie")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "resident_postal_code"),
+                    DataElementValueMapping {
+                        key: "resident_postal_code",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Postal code"), ("nl", "Postcode")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "resident_country"),
+                    DataElementValueMapping {
+                        key: "resident_country",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Country"), ("nl", "Land")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "portrait_capture_date"),
+                    DataElementValueMapping {
+                        key: "portrait_capture_date",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Portrait capture date"), ("nl", "Datum van pasfoto")]),
+                        value_type: AttributeValueType::DateTime,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "age_in_years"),
+                    DataElementValueMapping {
+                        key: "age_in_years",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Age"), ("nl", "Leeftijd")]),
+                        value_type: AttributeValueType::Integer,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "age_birth_year"),
+                    DataElementValueMapping {
+                        key: "age_birth_year",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Year of birth"), ("nl", "Geboortejaar")]),
+                        value_type: AttributeValueType::Integer,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "age_over_16"),
+                    DataElementValueMapping {
+                        key: "age_over_16",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Older than 16"), ("nl", "Ouder dan 16")]),
+                        value_type: AttributeValueType::Bool,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "age_over_18"),
+                    DataElementValueMapping {
+                        key: "age_over_18",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Older than 18"), ("nl", "Ouder dan 18")]),
+                        value_type: AttributeValueType::Bool,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "age_over_21"),
+                    DataElementValueMapping {
+                        key: "age_over_21",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Older than 21"), ("nl", "Ouder dan 21")]),
+                        value_type: AttributeValueType::Bool,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "age_over_65"),
+                    DataElementValueMapping {
+                        key: "age_over_65",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Older than 65"), ("nl", "Ouder dan 65")]),
+                        value_type: AttributeValueType::Bool,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "issuing_jurisdiction"),
+                    DataElementValueMapping {
+                        key: "issuing_jurisdiction",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Issuing jurisdiction"), ("nl", "Uitgevende jurisdictie")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "nationality"),
+                    DataElementValueMapping {
+                        key: "nationality",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Nationality"), ("nl", "Nationaliteit")]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "family_name_national_character"),
+                    DataElementValueMapping {
+                        key: "family_name_national_character",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([
+                            ("en", "Surname in national characters"),
+                            ("nl", "Achternaam in nationale tekens"),
+                        ]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "given_name_national_character"),
+                    DataElementValueMapping {
+                        key: "given_name_national_character",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([
+                            ("en", "First names in national characters"),
+                            ("nl", "Voornamen in nationale tekens"),
+                        ]),
+                        value_type: AttributeValueType::String,
+                    },
+                ),
+                (
+                    (MDL_NAMESPACE, "signature_usual_mark"),
+                    DataElementValueMapping {
+                        key: "signature_usual_mark",
+                        is_mandatory: false,
+                        key_labels: HashMap::from([("en", "Signature"), ("nl", "Handtekening")]),
+                        value_type: AttributeValueType::Image,
+                    },
+                ),
+            ]),
+        ),
     ])
 });
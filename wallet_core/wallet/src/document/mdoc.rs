@@ -1,7 +1,8 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use ciborium::value::Integer;
 use indexmap::IndexMap;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use nl_wallet_mdoc::{
     holder::{ProposedAttributes, ProposedDocumentAttributes},
@@ -56,15 +57,23 @@ pub enum DocumentMdocError {
 pub enum AttributeValueType {
     String,
     Bool,
+    Integer,
+    /// A `full-date`, either as plain text or tagged with CBOR tag 1004.
     Date,
+    /// A `tdate`, either as plain text or tagged with CBOR tag 0.
+    DateTime,
     Gender,
+    /// A byte string containing an image, e.g. a JPEG portrait.
+    Image,
+    /// Any (nested) value, which is converted generically. See [`AttributeValue::from_structured_value()`].
+    Structured,
 }
 
 // TODO: Think about refactoring/renaming DisclosureType. We currently have
 // DisclosureType here, *and* in disclosure_history_event.rs, EventType, *and*
 // in flutter_api's disclosure.rs again as DisclosureType. Things to think about
 // when refactoring: why this many and not just one.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum DisclosureType {
     Login,
     Regular,
@@ -244,6 +253,11 @@ impl TryFrom<(DataElementValue, &DataElementValueMapping)> for Attribute {
     }
 }
 
+/// CBOR tag for a `tdate`, see RFC 8949.
+const CBOR_TAG_TDATE: u64 = 0;
+/// CBOR tag for a `full-date`, see RFC 8943.
+const CBOR_TAG_FULL_DATE: u64 = 1004;
+
 impl TryFrom<(AttributeValueType, DataElementValue)> for AttributeValue {
     type Error = DataElementValue;
 
@@ -251,21 +265,83 @@ impl TryFrom<(AttributeValueType, DataElementValue)> for AttributeValue {
         match value {
             (AttributeValueType::String, DataElementValue::Text(s)) => Ok(Self::String(s)),
             (AttributeValueType::Bool, DataElementValue::Bool(b)) => Ok(Self::Boolean(b)),
-            (AttributeValueType::Date, DataElementValue::Text(ref s)) => {
-                let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| value.1)?;
+            (AttributeValueType::Integer, DataElementValue::Integer(i)) => {
+                let i = i64::try_from(i).map_err(|_| value.1)?;
+
+                Ok(Self::Integer(i))
+            }
+            (AttributeValueType::Date, ref data_element_value) => {
+                let date = parse_full_date(data_element_value).ok_or(value.1)?;
 
                 Ok(Self::Date(date))
             }
+            (AttributeValueType::DateTime, ref data_element_value) => {
+                let date_time = parse_tdate(data_element_value).ok_or(value.1)?;
+
+                Ok(Self::DateTime(date_time))
+            }
             (AttributeValueType::Gender, DataElementValue::Integer(i)) => {
                 let gender = GenderAttributeValue::try_from(i).map_err(|_| value.1)?;
 
                 Ok(Self::Gender(gender))
             }
+            (AttributeValueType::Image, DataElementValue::Bytes(bytes)) => Ok(Self::Image(bytes)),
+            (AttributeValueType::Structured, ref data_element_value) => {
+                Self::from_structured_value(data_element_value).ok_or(value.1)
+            }
             _ => Err(value.1),
         }
     }
 }
 
+impl AttributeValue {
+    /// Generically convert any (nested) CBOR value, for which the type is not known in advance. Tagged dates are
+    /// recognized, but untagged text is always converted to a string and byte strings are not interpreted as images.
+    /// Floating point numbers, `null` and maps with non-text keys are not supported.
+    fn from_structured_value(value: &DataElementValue) -> Option<Self> {
+        let attribute_value = match value {
+            DataElementValue::Text(s) => Self::String(s.clone()),
+            DataElementValue::Bool(b) => Self::Boolean(*b),
+            DataElementValue::Integer(i) => Self::Integer(i64::try_from(*i).ok()?),
+            DataElementValue::Bytes(bytes) => Self::Bytes(bytes.clone()),
+            DataElementValue::Tag(CBOR_TAG_FULL_DATE, _) => Self::Date(parse_full_date(value)?),
+            DataElementValue::Tag(CBOR_TAG_TDATE, _) => Self::DateTime(parse_tdate(value)?),
+            DataElementValue::Array(values) => {
+                Self::Array(values.iter().map(Self::from_structured_value).collect::<Option<_>>()?)
+            }
+            DataElementValue::Map(entries) => Self::Map(
+                entries
+                    .iter()
+                    .map(|(key, value)| Some((key.as_text()?.to_string(), Self::from_structured_value(value)?)))
+                    .collect::<Option<_>>()?,
+            ),
+            _ => return None,
+        };
+
+        Some(attribute_value)
+    }
+}
+
+fn parse_full_date(value: &DataElementValue) -> Option<NaiveDate> {
+    let s = match value {
+        DataElementValue::Text(s) => s,
+        DataElementValue::Tag(CBOR_TAG_FULL_DATE, value) => value.as_text()?,
+        _ => return None,
+    };
+
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
+fn parse_tdate(value: &DataElementValue) -> Option<DateTime<Utc>> {
+    let s = match value {
+        DataElementValue::Text(s) => s,
+        DataElementValue::Tag(CBOR_TAG_TDATE, value) => value.as_text()?,
+        _ => return None,
+    };
+
+    DateTime::parse_from_rfc3339(s).ok().map(|date_time| date_time.to_utc())
+}
+
 impl TryFrom<Integer> for GenderAttributeValue {
     type Error = ();
 
@@ -326,6 +402,23 @@ impl MissingDisclosureAttributes {
 
         Ok(missing_disclosure_attributes)
     }
+
+    // Translate the missing attributes for every `Mdoc` candidate to a `Vec<MissingDisclosureAttributes>`,
+    // with an entry per candidate. The doc types are sorted canonically, while the order of the candidates
+    // for a particular doc type is preserved.
+    pub(crate) fn from_mdoc_candidates_missing_attributes(
+        candidates_missing_attributes: Vec<Vec<AttributeIdentifier>>,
+    ) -> Result<Vec<Self>, DocumentMdocError> {
+        let mut missing_disclosure_attributes = candidates_missing_attributes
+            .into_iter()
+            .map(Self::from_mdoc_missing_attributes)
+            .flatten_ok()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        missing_disclosure_attributes.sort_by_key(|attributes| super::doc_type_priority(attributes.doc_type));
+
+        Ok(missing_disclosure_attributes)
+    }
 }
 
 impl DisclosureDocument {
@@ -361,7 +454,7 @@ pub mod tests {
     use once_cell::sync::Lazy;
     use rstest::rstest;
 
-    use nl_wallet_mdoc::{server_keys::KeyPair, Tdate};
+    use nl_wallet_mdoc::{examples::Examples, server_keys::KeyPair, Tdate, MDL_DOCTYPE};
 
     use super::{
         super::{ADDRESS_DOCTYPE, PID_DOCTYPE},
@@ -587,6 +680,100 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_mdl_to_document_mapping() {
+        let document = Document::from_mdoc_attributes(
+            DocumentPersistence::InMemory,
+            MDL_DOCTYPE,
+            Examples::full_mdl_attributes(),
+            IssuerRegistration::new_mock(),
+        )
+        .expect("Could not convert mDL to document");
+
+        assert_eq!(document.doc_type, MDL_DOCTYPE);
+        assert_eq!(
+            document.attributes.keys().cloned().collect::<Vec<_>>(),
+            vec![
+                "given_name",
+                "family_name",
+                "birth_date",
+                "portrait",
+                "document_number",
+                "issue_date",
+                "expiry_date",
+                "issuing_country",
+                "issuing_authority",
+                "un_distinguishing_sign",
+                "driving_privileges",
+                "age_over_18"
+            ]
+        );
+        assert_matches!(
+            document.attributes.get("portrait").unwrap(),
+            Attribute {
+                key_labels: _,
+                value: AttributeValue::Image(portrait),
+            } if portrait.starts_with(&[0xff, 0xd8]) // JPEG start of image marker
+        );
+        assert_matches!(
+            document.attributes.get("issue_date").unwrap(),
+            Attribute {
+                key_labels: _,
+                value: AttributeValue::Date(issue_date),
+            } if issue_date == &NaiveDate::from_ymd_opt(2019, 10, 20).unwrap()
+        );
+        assert_eq!(
+            document.attributes.get("driving_privileges").unwrap().value,
+            AttributeValue::Array(
+                [
+                    ("A", NaiveDate::from_ymd_opt(2018, 8, 9)),
+                    ("B", NaiveDate::from_ymd_opt(2017, 2, 23))
+                ]
+                .into_iter()
+                .map(|(vehicle_category_code, issue_date)| {
+                    AttributeValue::Map(IndexMap::from([
+                        (
+                            "vehicle_category_code".to_string(),
+                            AttributeValue::String(vehicle_category_code.to_string()),
+                        ),
+                        ("issue_date".to_string(), AttributeValue::Date(issue_date.unwrap())),
+                        (
+                            "expiry_date".to_string(),
+                            AttributeValue::Date(NaiveDate::from_ymd_opt(2024, 10, 20).unwrap()),
+                        ),
+                    ]))
+                })
+                .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn test_iso_example_mdl_to_proposed_disclosure_document_mapping() {
+        // The ISO example only discloses some of the data elements of the mDL, which should not result in a
+        // `DocumentMdocError::MissingAttribute` error.
+        let disclosure_document = DisclosureDocument::from_mdoc_attributes(
+            MDL_DOCTYPE,
+            ProposedDocumentAttributes {
+                attributes: Examples::mdl_attributes(),
+                issuer: ISSUER_KEY.certificate().clone(),
+            },
+        )
+        .expect("Could not convert attributes to proposed disclosure document");
+
+        assert_eq!(
+            disclosure_document.attributes.keys().cloned().collect::<Vec<_>>(),
+            vec![
+                "family_name",
+                "portrait",
+                "document_number",
+                "issue_date",
+                "expiry_date",
+                "driving_privileges"
+            ]
+        );
+    }
+
     #[test]
     fn test_unsigned_mdoc_to_document_mapping_doc_type_error() {
         // Test changing the doc_type.
@@ -965,6 +1152,44 @@ pub mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_missing_disclosure_attributes_from_mdoc_candidates_missing_attributes() {
+        let candidates_missing_attributes = vec![
+            vec!["com.example.address/com.example.address/resident_street"
+                .parse()
+                .unwrap()],
+            vec!["com.example.pid/com.example.pid/bsn".parse().unwrap()],
+            vec![
+                "com.example.pid/com.example.pid/gender".parse().unwrap(),
+                "com.example.pid/com.example.pid/bsn".parse().unwrap(),
+            ],
+        ];
+
+        let missing =
+            MissingDisclosureAttributes::from_mdoc_candidates_missing_attributes(candidates_missing_attributes)
+                .expect("Could not convert attribute identifiers to missing disclosure attributes");
+
+        // Every candidate should result in a separate entry, where the PID candidates come first.
+        let missing = missing
+            .into_iter()
+            .map(|missing_attributes| {
+                (
+                    missing_attributes.doc_type,
+                    missing_attributes.attributes.into_keys().collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            missing,
+            vec![
+                ("com.example.pid", vec!["bsn"]),
+                ("com.example.pid", vec!["gender", "bsn"]),
+                ("com.example.address", vec!["resident_street"]),
+            ]
+        );
+    }
+
     #[rstest]
     #[case(create_bsn_only_unsigned_pid_mdoc(), DisclosureType::Login)]
     #[case(create_minimal_unsigned_pid_mdoc(), DisclosureType::Regular)]
@@ -980,4 +1205,86 @@ pub mod tests {
 
         assert_eq!(DisclosureType::from_proposed_attributes(&pa), expected)
     }
+
+    #[rstest]
+    #[case(AttributeValueType::Integer, DataElementValue::Integer(180.into()), AttributeValue::Integer(180))]
+    #[case(
+        AttributeValueType::Date,
+        DataElementValue::Tag(1004, DataElementValue::Text("1997-05-10".to_string()).into()),
+        AttributeValue::Date(NaiveDate::from_ymd_opt(1997, 5, 10).unwrap())
+    )]
+    #[case(
+        AttributeValueType::DateTime,
+        DataElementValue::Tag(0, DataElementValue::Text("2024-03-01T12:00:00+01:00".to_string()).into()),
+        AttributeValue::DateTime("2024-03-01T11:00:00Z".parse().unwrap())
+    )]
+    #[case(
+        AttributeValueType::DateTime,
+        DataElementValue::Text("2024-03-01T11:00:00Z".to_string()),
+        AttributeValue::DateTime("2024-03-01T11:00:00Z".parse().unwrap())
+    )]
+    #[case(
+        AttributeValueType::Image,
+        DataElementValue::Bytes(vec![0xff, 0xd8, 0xff]),
+        AttributeValue::Image(vec![0xff, 0xd8, 0xff])
+    )]
+    #[case(
+        AttributeValueType::Structured,
+        DataElementValue::Array(vec![DataElementValue::Map(vec![
+            (DataElementValue::Text("vehicle_category_code".to_string()), DataElementValue::Text("B".to_string())),
+            (
+                DataElementValue::Text("issue_date".to_string()),
+                DataElementValue::Tag(1004, DataElementValue::Text("2018-08-09".to_string()).into()),
+            ),
+            (
+                DataElementValue::Text("codes".to_string()),
+                DataElementValue::Array(vec![DataElementValue::Map(vec![(
+                    DataElementValue::Text("code".to_string()),
+                    DataElementValue::Integer(5.into()),
+                )])]),
+            ),
+            (DataElementValue::Text("signature".to_string()), DataElementValue::Bytes(vec![1, 2, 3])),
+        ])]),
+        AttributeValue::Array(vec![AttributeValue::Map(IndexMap::from([
+            ("vehicle_category_code".to_string(), AttributeValue::String("B".to_string())),
+            ("issue_date".to_string(), AttributeValue::Date(NaiveDate::from_ymd_opt(2018, 8, 9).unwrap())),
+            (
+                "codes".to_string(),
+                AttributeValue::Array(vec![AttributeValue::Map(IndexMap::from([(
+                    "code".to_string(),
+                    AttributeValue::Integer(5),
+                )]))]),
+            ),
+            ("signature".to_string(), AttributeValue::Bytes(vec![1, 2, 3])),
+        ]))])
+    )]
+    fn test_attribute_value_from_data_element_value(
+        #[case] value_type: AttributeValueType,
+        #[case] value: DataElementValue,
+        #[case] expected: AttributeValue,
+    ) {
+        assert_eq!(AttributeValue::try_from((value_type, value)).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(AttributeValueType::Integer, DataElementValue::Integer(u64::MAX.into()))]
+    #[case(
+        AttributeValueType::Date,
+        DataElementValue::Tag(0, DataElementValue::Text("1997-05-10".to_string()).into())
+    )]
+    #[case(AttributeValueType::DateTime, DataElementValue::Text("1997-05-10".to_string()))]
+    #[case(AttributeValueType::Image, DataElementValue::Text("not an image".to_string()))]
+    #[case(AttributeValueType::Structured, DataElementValue::Array(vec![DataElementValue::Null]))]
+    #[case(
+        AttributeValueType::Structured,
+        DataElementValue::Map(vec![(DataElementValue::Integer(1.into()), DataElementValue::Bool(true))])
+    )]
+    fn test_attribute_value_from_data_element_value_error(
+        #[case] value_type: AttributeValueType,
+        #[case] value: DataElementValue,
+    ) {
+        let error = AttributeValue::try_from((value_type, value.clone())).expect_err("conversion should fail");
+
+        assert_eq!(error, value);
+    }
 }
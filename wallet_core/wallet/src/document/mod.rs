@@ -3,10 +3,10 @@ mod mdoc;
 
 use std::collections::HashMap;
 
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use indexmap::IndexMap;
 
-use nl_wallet_mdoc::utils::issuer_auth::IssuerRegistration;
+use nl_wallet_mdoc::{utils::issuer_auth::IssuerRegistration, MDL_DOCTYPE};
 
 pub use mdoc::{AttributeValueType, DisclosureType, DocumentMdocError};
 
@@ -16,7 +16,7 @@ pub use mdoc::tests::{
     create_minimal_unsigned_pid_mdoc,
 };
 
-const PID_DOCTYPE: &str = "com.example.pid";
+pub(crate) const PID_DOCTYPE: &str = "com.example.pid";
 const ADDRESS_DOCTYPE: &str = "com.example.address";
 
 pub type DocumentType = &'static str;
@@ -51,8 +51,14 @@ pub struct Attribute {
 pub enum AttributeValue {
     String(String),
     Boolean(bool),
+    Integer(i64),
     Date(NaiveDate),
+    DateTime(DateTime<Utc>),
     Gender(GenderAttributeValue),
+    Image(Vec<u8>),
+    Bytes(Vec<u8>),
+    Array(Vec<AttributeValue>),
+    Map(IndexMap<String, AttributeValue>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -81,6 +87,7 @@ fn doc_type_priority(doc_type: &str) -> usize {
     match doc_type {
         PID_DOCTYPE => 0,
         ADDRESS_DOCTYPE => 1,
+        MDL_DOCTYPE => 2,
         _ => usize::MAX,
     }
 }
@@ -112,6 +119,7 @@ mod tests {
             empty_document(ADDRESS_DOCTYPE),
             empty_document("bar"),
             empty_document(PID_DOCTYPE),
+            empty_document(MDL_DOCTYPE),
             empty_document("baz"),
         ];
 
@@ -121,6 +129,9 @@ mod tests {
             .map(|document| document.doc_type)
             .collect::<Vec<_>>();
 
-        assert_eq!(doc_types, vec![PID_DOCTYPE, ADDRESS_DOCTYPE, "foo", "bar", "baz"]);
+        assert_eq!(
+            doc_types,
+            vec![PID_DOCTYPE, ADDRESS_DOCTYPE, MDL_DOCTYPE, "foo", "bar", "baz"]
+        );
     }
 }
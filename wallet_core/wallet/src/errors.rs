@@ -21,7 +21,8 @@ pub use crate::{
     pin::{key::PinKeyError, validation::PinValidationError},
     storage::{KeyFileError, StorageError},
     wallet::{
-        DisclosureError, EventConversionError, EventStorageError, HistoryError, PidIssuanceError, ResetError,
-        UriIdentificationError, WalletInitError, WalletRegistrationError, WalletUnlockError,
+        BackupError, DeletionError, DeviceLinkError, DisclosureError, EventConversionError, EventStorageError,
+        HistoryError, PidIssuanceError, PinRecoveryError, ResetError, UriIdentificationError, WalletInitError,
+        WalletRegistrationError, WalletUnlockError,
     },
 };
@@ -1,11 +1,17 @@
 use std::iter;
 
-use p256::ecdsa::{signature, signature::Verifier, Signature, VerifyingKey};
+use p256::{
+    ecdsa::{signature, signature::Verifier, Signature, VerifyingKey},
+    PublicKey,
+};
 
 use nl_wallet_mdoc::utils::keys::{KeyFactory, MdocEcdsaKey, MdocKeyType};
 use platform_support::hw_keystore::PlatformEcdsaKey;
 use wallet_common::{
-    account::messages::instructions::{GenerateKey, GenerateKeyResult, Sign},
+    account::{
+        messages::instructions::{DiffieHellman, DiffieHellmanResult, GenerateKey, GenerateKeyResult, Sign},
+        serialization::DerVerifyingKey,
+    },
     keys::{EcdsaKey, SecureEcdsaKey, WithIdentifier},
     utils::random_string,
 };
@@ -125,6 +131,22 @@ where
 
         Ok(signatures)
     }
+
+    async fn diffie_hellman_with_existing_keys(
+        &self,
+        keys: Vec<&Self::Key>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let result: DiffieHellmanResult = self
+            .instruction_client
+            .send(DiffieHellman {
+                identifiers: keys.into_iter().map(|key| key.identifier.clone()).collect(),
+                public_key: DerVerifyingKey(VerifyingKey::from(public_key)),
+            })
+            .await?;
+
+        Ok(result.shared_secrets)
+    }
 }
 
 impl<S, K, A> WithIdentifier for RemoteEcdsaKey<'_, S, K, A> {
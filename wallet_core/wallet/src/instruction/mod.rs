@@ -26,10 +26,20 @@ pub enum InstructionError {
     Timeout { timeout_millis: u64 },
     #[error("unlock permanently disabled")]
     Blocked,
+    #[error("wallet is suspended by the Wallet Provider")]
+    Suspended,
+    #[error("wallet is revoked by the Wallet Provider")]
+    Revoked,
     #[error("server error: {0}")]
     ServerError(#[source] AccountProviderError),
     #[error("Wallet Provider could not validate instruction")]
     InstructionValidation,
+    #[error("Wallet Provider could not verify PIN recovery")]
+    PinRecoveryValidation,
+    #[error("device is not linked to the wallet account")]
+    DeviceNotLinked,
+    #[error("Wallet Provider could not validate device link")]
+    DeviceLinkValidation,
     #[error("could not sign instruction: {0}")]
     Signing(#[source] wallet_common::account::errors::Error),
     #[error("could not validate instruction result received from Wallet Provider: {0}")]
@@ -57,10 +67,28 @@ impl From<AccountProviderError> for InstructionError {
             AccountProviderError::Response(AccountProviderResponseError::Account(AccountError::AccountBlocked, _)) => {
                 Self::Blocked
             }
+            AccountProviderError::Response(AccountProviderResponseError::Account(
+                AccountError::AccountSuspended,
+                _,
+            )) => Self::Suspended,
+            AccountProviderError::Response(AccountProviderResponseError::Account(AccountError::AccountRevoked, _)) => {
+                Self::Revoked
+            }
             AccountProviderError::Response(AccountProviderResponseError::Account(
                 AccountError::InstructionValidation,
                 _,
             )) => Self::InstructionValidation,
+            AccountProviderError::Response(AccountProviderResponseError::Account(
+                AccountError::PinRecoveryValidation,
+                _,
+            )) => Self::PinRecoveryValidation,
+            AccountProviderError::Response(AccountProviderResponseError::Account(AccountError::DeviceNotLinked, _)) => {
+                Self::DeviceNotLinked
+            }
+            AccountProviderError::Response(AccountProviderResponseError::Account(
+                AccountError::DeviceLinkValidation,
+                _,
+            )) => Self::DeviceLinkValidation,
             value => Self::ServerError(value),
         }
     }
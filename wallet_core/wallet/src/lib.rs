@@ -20,24 +20,34 @@ pub use crate::{
     },
     pin::validation::validate_pin,
     wallet::{
-        ConfigCallback, DisclosureProposal, DocumentsCallback, EventStatus, HistoryEvent, LockCallback, UriType, Wallet,
+        generate_recovery_secret, ConfigCallback, DisclosureProposal, DocumentsCallback, EventStatus, HistoryEvent,
+        HistoryExport, HistoryPage, HistoryReport, HistoryReportDocument, HistoryReportEvent,
+        HistoryReportRelyingParty, LockCallback, RelyingPartyFilter, UriType, Wallet, WalletBackup, WalletEventCursor,
+        WalletEventQuery, WalletEventType,
     },
 };
 
 pub mod mdoc {
     pub use nl_wallet_mdoc::{
+        holder::SelfAssertedAttributes,
+        identifiers::AttributeIdentifier,
+        unsigned::Entry,
         utils::{
             auth::{Image, ImageType, LocalizedStrings, Organization},
             reader_auth::{DeletionPolicy, ReaderRegistration, RetentionPolicy, SharingPolicy},
         },
         verifier::SessionType,
+        DataElementValue,
     };
 }
 
 pub mod wallet_common {
-    pub use wallet_common::config::wallet_config::{
-        AccountServerConfiguration, BaseUrl, DisclosureConfiguration, LockTimeoutConfiguration,
-        PidIssuanceConfiguration, WalletConfiguration,
+    pub use wallet_common::{
+        account::messages::instructions::LinkedDevice,
+        config::wallet_config::{
+            AccountServerConfiguration, BaseUrl, DisclosureConfiguration, LockTimeoutConfiguration,
+            PidIssuanceConfiguration, WalletConfiguration,
+        },
     };
 }
 
@@ -0,0 +1,38 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use nl_wallet_mdoc::holder::Mdoc;
+
+use super::{
+    data::{InstructionData, KeyedData, RegistrationData, RestoreData},
+    event_log::WalletEvent,
+};
+
+/// The keys of the [`KeyedData`] that is bound to the device and the registration with the Wallet Provider.
+/// This data is never part of a backup and is left untouched when importing one.
+pub const DEVICE_BOUND_DATA_KEYS: [&str; 3] = [RegistrationData::KEY, InstructionData::KEY, RestoreData::KEY];
+
+/// The contents of [`super::Storage`] that are included in a wallet backup, which is all
+/// of the keyed data (except for [`DEVICE_BOUND_DATA_KEYS`]), all mdoc copies and all history events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupContents {
+    pub keyed_data: IndexMap<String, serde_json::Value>,
+    pub mdocs: Vec<BackupMdoc>,
+    pub events: Vec<WalletEvent>,
+}
+
+/// All of the copies of a single mdoc, including how often each copy was disclosed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupMdoc {
+    pub copies: Vec<BackupMdocCopy>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupMdocCopy {
+    pub mdoc: Mdoc,
+    pub disclosure_count: u32,
+}
+
+pub fn is_device_bound_data_key(key: &str) -> bool {
+    DEVICE_BOUND_DATA_KEYS.contains(&key)
+}
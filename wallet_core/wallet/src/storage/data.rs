@@ -1,8 +1,11 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::{base64::Base64, serde_as};
 
+use nl_wallet_mdoc::utils::serialization::{cbor_deserialize, cbor_serialize, CborError};
 use wallet_common::account::messages::auth::WalletCertificate;
 
+use super::backup::BackupContents;
+
 pub trait KeyedData: Serialize + DeserializeOwned {
     const KEY: &'static str;
 }
@@ -20,6 +23,29 @@ pub struct InstructionData {
     pub instruction_sequence_number: u64,
 }
 
+/// Contains the CBOR encoded [`BackupContents`] of a backup that still needs to be imported
+/// after the wallet was registered using that backup. This allows the import to be resumed.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreData {
+    #[serde_as(as = "Base64")]
+    pub contents: Vec<u8>,
+}
+
+impl RestoreData {
+    pub fn new(contents: &BackupContents) -> Result<Self, CborError> {
+        let restore_data = RestoreData {
+            contents: cbor_serialize(contents)?,
+        };
+
+        Ok(restore_data)
+    }
+
+    pub fn contents(&self) -> Result<BackupContents, CborError> {
+        cbor_deserialize(self.contents.as_slice())
+    }
+}
+
 impl KeyedData for RegistrationData {
     const KEY: &'static str = "registration";
 }
@@ -27,3 +53,7 @@ impl KeyedData for RegistrationData {
 impl KeyedData for InstructionData {
     const KEY: &'static str = "instructions";
 }
+
+impl KeyedData for RestoreData {
+    const KEY: &'static str = "restore";
+}
@@ -1,32 +1,35 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::{cmp::Reverse, collections::HashSet, path::PathBuf};
 
 use futures::try_join;
 use migration::SimpleExpr;
 use sea_orm::{
     sea_query::{Alias, BinOper, Expr, IntoColumnRef, Query},
-    ActiveModelTrait, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, IntoSimpleExpr, JoinType, ModelTrait,
-    QueryFilter, QueryOrder, QueryResult, QuerySelect, RelationDef, RelationTrait, Select, Set, StatementBuilder,
-    TransactionTrait,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, IntoSimpleExpr, JoinType,
+    ModelTrait, QueryFilter, QueryOrder, QueryResult, QuerySelect, QueryTrait, RelationDef, RelationTrait, Select, Set,
+    StatementBuilder, TransactionTrait,
 };
 use tokio::fs;
 use tracing::warn;
 use uuid::Uuid;
 
 use entity::{
+    deletion_request_history_event, deletion_request_history_event_doc_type,
     disclosure_history_event::{self, EventStatus},
     disclosure_history_event_doc_type, history_doc_type, issuance_history_event, issuance_history_event_doc_type,
     keyed_data, mdoc, mdoc_copy,
 };
 use nl_wallet_mdoc::{
-    holder::MdocCopies,
+    holder::{Mdoc, MdocCopies},
     utils::serialization::{cbor_deserialize, cbor_serialize, CborError},
 };
 use platform_support::hw_keystore::PlatformEncryptionKey;
 
 use super::{
+    backup::{is_device_bound_data_key, BackupContents, BackupMdoc, BackupMdocCopy, DEVICE_BOUND_DATA_KEYS},
     data::KeyedData,
     database::{Database, SqliteUrl},
     event_log::{WalletEvent, WalletEventModel},
+    event_query::{RelyingPartyFilter, WalletEventPage, WalletEventQuery, WalletEventType},
     key_file,
     sql_cipher_key::SqlCipherKey,
     Storage, StorageError, StorageResult, StorageState, StoredMdocCopy,
@@ -221,9 +224,131 @@ impl<K> DatabaseStorage<K> {
         Ok(())
     }
 
+    /// Inserts all of the provided mdocs, where each mdoc consists of one or more copies and their disclosure count.
+    async fn insert_mdoc_copies(connection: &impl ConnectionTrait, mdocs: Vec<Vec<(Mdoc, u32)>>) -> StorageResult<()> {
+        // Construct a vec of tuples of 1 `mdoc` and 1 or more `mdoc_copy` models,
+        // based on the unique mdocs, to be inserted into the database.
+        let mdoc_models = mdocs
+            .into_iter()
+            .filter(|copies| !copies.is_empty())
+            .map(|copies| {
+                let mdoc_id = Uuid::new_v4();
+
+                let copy_models = copies
+                    .iter()
+                    .map(|(mdoc, disclosure_count)| {
+                        let model = mdoc_copy::ActiveModel {
+                            id: Set(Uuid::new_v4()),
+                            mdoc_id: Set(mdoc_id),
+                            disclosure_count: Set(*disclosure_count),
+                            mdoc: Set(cbor_serialize(&mdoc)?),
+                        };
+
+                        Ok(model)
+                    })
+                    .collect::<Result<Vec<_>, CborError>>()?;
+
+                // `copies` is guaranteed to contain at least one value because of the filter() above.
+                let doc_type = copies.into_iter().next().unwrap().0.doc_type;
+                let mdoc_model = mdoc::ActiveModel {
+                    id: Set(mdoc_id),
+                    doc_type: Set(doc_type),
+                };
+
+                Ok((mdoc_model, copy_models))
+            })
+            .collect::<Result<Vec<_>, CborError>>()?;
+
+        if mdoc_models.is_empty() {
+            return Ok(());
+        }
+
+        // Make two separate vecs out of the vec of tuples.
+        let (mdoc_models, copy_models): (Vec<_>, Vec<_>) = mdoc_models.into_iter().unzip();
+
+        mdoc::Entity::insert_many(mdoc_models).exec(connection).await?;
+        mdoc_copy::Entity::insert_many(copy_models.into_iter().flatten())
+            .exec(connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn insert_wallet_event(connection: &impl ConnectionTrait, event: WalletEvent) -> StorageResult<()> {
+        let event_doc_types = event.associated_doc_types();
+
+        // Find existing doc_type entities
+        let existing_doc_type_entities = history_doc_type::Entity::find()
+            .filter(history_doc_type::Column::DocType.is_in(event_doc_types.clone()))
+            .all(connection)
+            .await?;
+
+        // Get Vec of existing doc_types
+        let existing_doc_types = existing_doc_type_entities
+            .iter()
+            .map(|e| e.doc_type.as_str())
+            .collect::<Vec<_>>();
+
+        // Determine what new doc_type entries need to be inserted
+        let new_doc_type_entities = event_doc_types
+            .into_iter()
+            .filter(|doc_type| !existing_doc_types.contains(doc_type))
+            .map(|doc_type| history_doc_type::Model {
+                id: Uuid::new_v4(),
+                doc_type: doc_type.to_owned(),
+            })
+            .collect::<Vec<_>>();
+
+        // Insert the history event
+        match WalletEventModel::try_from(event)? {
+            WalletEventModel::Issuance(event_entity) => {
+                Self::insert_history_event_and_doc_type_mappings(
+                    connection,
+                    issuance_history_event::ActiveModel::from(event_entity),
+                    new_doc_type_entities,
+                    existing_doc_type_entities,
+                    |(event, doc_type_id)| issuance_history_event_doc_type::ActiveModel {
+                        issuance_history_event_id: event.id.clone(),
+                        history_doc_type_id: Set(doc_type_id),
+                    },
+                )
+                .await?;
+            }
+            WalletEventModel::Disclosure(event_entity) => {
+                Self::insert_history_event_and_doc_type_mappings(
+                    connection,
+                    disclosure_history_event::ActiveModel::from(event_entity),
+                    new_doc_type_entities,
+                    existing_doc_type_entities,
+                    |(event, doc_type_id)| disclosure_history_event_doc_type::ActiveModel {
+                        disclosure_history_event_id: event.id.clone(),
+                        history_doc_type_id: Set(doc_type_id),
+                    },
+                )
+                .await?;
+            }
+            WalletEventModel::DeletionRequest(event_entity) => {
+                Self::insert_history_event_and_doc_type_mappings(
+                    connection,
+                    deletion_request_history_event::ActiveModel::from(event_entity),
+                    new_doc_type_entities,
+                    existing_doc_type_entities,
+                    |(event, doc_type_id)| deletion_request_history_event_doc_type::ActiveModel {
+                        deletion_request_history_event_id: event.id.clone(),
+                        history_doc_type_id: Set(doc_type_id),
+                    },
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn combine_history_events(
         issuance_events: Vec<issuance_history_event::Model>,
         disclosure_events: Vec<disclosure_history_event::Model>,
+        deletion_request_events: Vec<deletion_request_history_event::Model>,
     ) -> StorageResult<Vec<WalletEvent>> {
         let mut issuance_events: Vec<WalletEvent> = issuance_events
             .into_iter()
@@ -233,11 +358,69 @@ impl<K> DatabaseStorage<K> {
             .into_iter()
             .map(WalletEvent::try_from)
             .collect::<Result<Vec<_>, _>>()?;
+        let mut deletion_request_events: Vec<WalletEvent> = deletion_request_events
+            .into_iter()
+            .map(WalletEvent::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
 
         issuance_events.append(&mut disclosure_events);
-        issuance_events.sort_by(|a, b| b.timestamp().cmp(a.timestamp()));
+        issuance_events.append(&mut deletion_request_events);
+        issuance_events.sort_by_key(|event| Reverse(event.cursor()));
         Ok(issuance_events)
     }
+
+    /// Applies the cursor and date range of the [`WalletEventQuery`] to a history event query
+    /// and orders the result, fetching one more event than the limit to detect a next page.
+    fn select_history_events_page<E>(
+        select: Select<E>,
+        query: &WalletEventQuery,
+        timestamp_column: E::Column,
+        id_column: E::Column,
+    ) -> Select<E>
+    where
+        E: EntityTrait,
+    {
+        select
+            .apply_if(query.after, |select, after| {
+                select.filter(
+                    Condition::any().add(timestamp_column.lt(after.timestamp)).add(
+                        Condition::all()
+                            .add(timestamp_column.eq(after.timestamp))
+                            .add(id_column.lt(after.id)),
+                    ),
+                )
+            })
+            .apply_if(query.from, |select, from| select.filter(timestamp_column.gte(from)))
+            .apply_if(query.until, |select, until| select.filter(timestamp_column.lt(until)))
+            .order_by_desc(timestamp_column)
+            .order_by_desc(id_column)
+            .limit(query.limit.map(|limit| limit + 1))
+    }
+
+    /// Finds the distinct reader certificates in the history that match the [`RelyingPartyFilter`].
+    async fn query_relying_party_certificates(
+        connection: &impl ConnectionTrait,
+        relying_party: &RelyingPartyFilter,
+    ) -> Result<Vec<Vec<u8>>, DbErr> {
+        if let RelyingPartyFilter::Certificate(certificate) = relying_party {
+            return Ok(vec![certificate.as_bytes().to_vec()]);
+        }
+
+        let certificates: Vec<Vec<u8>> = disclosure_history_event::Entity::find()
+            .select_only()
+            .column(disclosure_history_event::Column::RelyingPartyCertificate)
+            .distinct()
+            .into_tuple()
+            .all(connection)
+            .await?;
+
+        let matching_certificates = certificates
+            .into_iter()
+            .filter(|certificate| relying_party.matches(&certificate.clone().into()))
+            .collect();
+
+        Ok(matching_certificates)
+    }
 }
 
 impl<K> DatabaseStorage<K>
@@ -367,51 +550,25 @@ where
         Ok(())
     }
 
-    async fn insert_mdocs(&mut self, mdocs: Vec<MdocCopies>) -> StorageResult<()> {
-        // Construct a vec of tuples of 1 `mdoc` and 1 or more `mdoc_copy` models,
-        // based on the unique `MdocCopies`, to be inserted into the database.
-        let mdoc_models = mdocs
-            .into_iter()
-            .filter(|mdoc_copies| !mdoc_copies.cred_copies.is_empty())
-            .map(|mdoc_copies| {
-                let mdoc_id = Uuid::new_v4();
-
-                let copy_models = mdoc_copies
-                    .cred_copies
-                    .iter()
-                    .map(|mdoc| {
-                        let model = mdoc_copy::ActiveModel {
-                            id: Set(Uuid::new_v4()),
-                            mdoc_id: Set(mdoc_id),
-                            mdoc: Set(cbor_serialize(&mdoc)?),
-                            ..Default::default()
-                        };
-
-                        Ok(model)
-                    })
-                    .collect::<Result<Vec<_>, CborError>>()?;
+    /// Delete data entry from the key-value table, if present.
+    async fn delete_data<D: KeyedData>(&mut self) -> StorageResult<()> {
+        let database = self.database()?;
 
-                // `mdoc_copies.cred_copies` is guaranteed to contain at least one value because of the filter() above.
-                let doc_type = mdoc_copies.cred_copies.into_iter().next().unwrap().doc_type;
-                let mdoc_model = mdoc::ActiveModel {
-                    id: Set(mdoc_id),
-                    doc_type: Set(doc_type),
-                };
+        keyed_data::Entity::delete_by_id(D::KEY)
+            .exec(database.connection())
+            .await?;
 
-                Ok((mdoc_model, copy_models))
-            })
-            .collect::<Result<Vec<_>, CborError>>()?;
+        Ok(())
+    }
 
-        // Make two separate vecs out of the vec of tuples.
-        let (mdoc_models, copy_models): (Vec<_>, Vec<_>) = mdoc_models.into_iter().unzip();
+    async fn insert_mdocs(&mut self, mdocs: Vec<MdocCopies>) -> StorageResult<()> {
+        let mdocs = mdocs
+            .into_iter()
+            .map(|mdoc_copies| mdoc_copies.cred_copies.into_iter().map(|mdoc| (mdoc, 0)).collect())
+            .collect();
 
         let transaction = self.database()?.connection().begin().await?;
-
-        mdoc::Entity::insert_many(mdoc_models).exec(&transaction).await?;
-        mdoc_copy::Entity::insert_many(copy_models.into_iter().flatten())
-            .exec(&transaction)
-            .await?;
-
+        Self::insert_mdoc_copies(&transaction, mdocs).await?;
         transaction.commit().await?;
 
         Ok(())
@@ -447,61 +604,7 @@ where
 
     async fn log_wallet_event(&mut self, event: WalletEvent) -> StorageResult<()> {
         let transaction = self.database()?.connection().begin().await?;
-
-        let event_doc_types = event.associated_doc_types();
-
-        // Find existing doc_type entities
-        let existing_doc_type_entities = history_doc_type::Entity::find()
-            .filter(history_doc_type::Column::DocType.is_in(event_doc_types.clone()))
-            .all(&transaction)
-            .await?;
-
-        // Get Vec of existing doc_types
-        let existing_doc_types = existing_doc_type_entities
-            .iter()
-            .map(|e| e.doc_type.as_str())
-            .collect::<Vec<_>>();
-
-        // Determine what new doc_type entries need to be inserted
-        let new_doc_type_entities = event_doc_types
-            .into_iter()
-            .filter(|doc_type| !existing_doc_types.contains(doc_type))
-            .map(|doc_type| history_doc_type::Model {
-                id: Uuid::new_v4(),
-                doc_type: doc_type.to_owned(),
-            })
-            .collect::<Vec<_>>();
-
-        // Insert the history event
-        match WalletEventModel::try_from(event)? {
-            WalletEventModel::Issuance(event_entity) => {
-                Self::insert_history_event_and_doc_type_mappings(
-                    &transaction,
-                    issuance_history_event::ActiveModel::from(event_entity),
-                    new_doc_type_entities,
-                    existing_doc_type_entities,
-                    |(event, doc_type_id)| issuance_history_event_doc_type::ActiveModel {
-                        issuance_history_event_id: event.id.clone(),
-                        history_doc_type_id: Set(doc_type_id),
-                    },
-                )
-                .await?;
-            }
-            WalletEventModel::Disclosure(event_entity) => {
-                Self::insert_history_event_and_doc_type_mappings(
-                    &transaction,
-                    disclosure_history_event::ActiveModel::from(event_entity),
-                    new_doc_type_entities,
-                    existing_doc_type_entities,
-                    |(event, doc_type_id)| disclosure_history_event_doc_type::ActiveModel {
-                        disclosure_history_event_id: event.id.clone(),
-                        history_doc_type_id: Set(doc_type_id),
-                    },
-                )
-                .await?;
-            }
-        }
-
+        Self::insert_wallet_event(&transaction, event).await?;
         transaction.commit().await?;
 
         Ok(())
@@ -518,9 +621,17 @@ where
             .order_by_desc(disclosure_history_event::Column::Timestamp)
             .all(connection);
 
-        let (issuance_events, disclosure_events) = try_join!(fetch_issuance_events, fetch_disclosure_events)?;
+        let fetch_deletion_request_events = deletion_request_history_event::Entity::find()
+            .order_by_desc(deletion_request_history_event::Column::Timestamp)
+            .all(connection);
+
+        let (issuance_events, disclosure_events, deletion_request_events) = try_join!(
+            fetch_issuance_events,
+            fetch_disclosure_events,
+            fetch_deletion_request_events
+        )?;
 
-        Self::combine_history_events(issuance_events, disclosure_events)
+        Self::combine_history_events(issuance_events, disclosure_events, deletion_request_events)
     }
 
     async fn fetch_recent_wallet_events(&self) -> StorageResult<Vec<WalletEvent>> {
@@ -536,9 +647,20 @@ where
             .order_by_desc(disclosure_history_event::Column::Timestamp)
             .all(connection);
 
-        let (issuance_events, disclosure_events) = try_join!(fetch_issuance_events, fetch_disclosure_events)?;
+        let fetch_deletion_request_events = deletion_request_history_event::Entity::find()
+            .filter(Self::newer_than_31_days(
+                deletion_request_history_event::Column::Timestamp,
+            ))
+            .order_by_desc(deletion_request_history_event::Column::Timestamp)
+            .all(connection);
+
+        let (issuance_events, disclosure_events, deletion_request_events) = try_join!(
+            fetch_issuance_events,
+            fetch_disclosure_events,
+            fetch_deletion_request_events
+        )?;
 
-        Self::combine_history_events(issuance_events, disclosure_events)
+        Self::combine_history_events(issuance_events, disclosure_events, deletion_request_events)
     }
 
     async fn fetch_wallet_events_by_doc_type(&self, doc_type: &str) -> StorageResult<Vec<WalletEvent>> {
@@ -558,10 +680,134 @@ where
             disclosure_history_event_doc_type::Relation::HistoryDocType.def(),
             disclosure_history_event::Column::Timestamp,
         );
+        let fetch_deletion_request_events = Self::query_history_events_by_doc_type(
+            doc_type,
+            connection,
+            deletion_request_history_event_doc_type::Relation::HistoryEvent.def(),
+            deletion_request_history_event_doc_type::Relation::HistoryDocType.def(),
+            deletion_request_history_event::Column::Timestamp,
+        );
+
+        let (issuance_events, disclosure_events, deletion_request_events) = try_join!(
+            fetch_issuance_events,
+            fetch_disclosure_events,
+            fetch_deletion_request_events
+        )?;
+
+        Self::combine_history_events(issuance_events, disclosure_events, deletion_request_events)
+    }
+
+    async fn fetch_wallet_event(&self, id: Uuid) -> StorageResult<Option<WalletEvent>> {
+        let connection = self.database()?.connection();
+
+        let fetch_issuance_event = issuance_history_event::Entity::find_by_id(id).one(connection);
+        let fetch_disclosure_event = disclosure_history_event::Entity::find_by_id(id).one(connection);
+        let fetch_deletion_request_event = deletion_request_history_event::Entity::find_by_id(id).one(connection);
+
+        let (issuance_event, disclosure_event, deletion_request_event) = try_join!(
+            fetch_issuance_event,
+            fetch_disclosure_event,
+            fetch_deletion_request_event
+        )?;
+
+        let event = Self::combine_history_events(
+            issuance_event.into_iter().collect(),
+            disclosure_event.into_iter().collect(),
+            deletion_request_event.into_iter().collect(),
+        )?
+        .into_iter()
+        .next();
+
+        Ok(event)
+    }
 
-        let (issuance_events, disclosure_events) = try_join!(fetch_issuance_events, fetch_disclosure_events)?;
+    async fn query_wallet_events(&self, query: &WalletEventQuery) -> StorageResult<WalletEventPage> {
+        let connection = self.database()?.connection();
+
+        let fetch_issuance_events = async {
+            if !query.includes_issuance() {
+                return Ok(Vec::new());
+            }
+
+            Self::select_history_events_page(
+                issuance_history_event::Entity::find(),
+                query,
+                issuance_history_event::Column::Timestamp,
+                issuance_history_event::Column::Id,
+            )
+            .all(connection)
+            .await
+        };
+
+        let relying_party_certificates = match query.relying_party.as_ref() {
+            Some(relying_party) => Some(Self::query_relying_party_certificates(connection, relying_party).await?),
+            None => None,
+        };
+
+        let fetch_disclosure_events = async {
+            let disclosure_types = query.disclosure_types();
+            if disclosure_types.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            Self::select_history_events_page(
+                disclosure_history_event::Entity::find(),
+                query,
+                disclosure_history_event::Column::Timestamp,
+                disclosure_history_event::Column::Id,
+            )
+            .filter(
+                disclosure_history_event::Column::Type.is_in(
+                    disclosure_types
+                        .into_iter()
+                        .map(disclosure_history_event::EventType::from),
+                ),
+            )
+            .apply_if((!query.statuses.is_empty()).then_some(()), |select, _| {
+                select.filter(
+                    disclosure_history_event::Column::Status
+                        .is_in(query.statuses.iter().cloned().map(EventStatus::from)),
+                )
+            })
+            .apply_if(relying_party_certificates.clone(), |select, certificates| {
+                select.filter(disclosure_history_event::Column::RelyingPartyCertificate.is_in(certificates))
+            })
+            .all(connection)
+            .await
+        };
+
+        let fetch_deletion_request_events = async {
+            if !query.includes_event_type(WalletEventType::DeletionRequest) {
+                return Ok(Vec::new());
+            }
+
+            Self::select_history_events_page(
+                deletion_request_history_event::Entity::find(),
+                query,
+                deletion_request_history_event::Column::Timestamp,
+                deletion_request_history_event::Column::Id,
+            )
+            .apply_if((!query.statuses.is_empty()).then_some(()), |select, _| {
+                select.filter(
+                    deletion_request_history_event::Column::Status
+                        .is_in(query.statuses.iter().cloned().map(EventStatus::from)),
+                )
+            })
+            .apply_if(relying_party_certificates.clone(), |select, certificates| {
+                select.filter(deletion_request_history_event::Column::RelyingPartyCertificate.is_in(certificates))
+            })
+            .all(connection)
+            .await
+        };
+
+        let (issuance_events, disclosure_events, deletion_request_events) = try_join!(
+            fetch_issuance_events,
+            fetch_disclosure_events,
+            fetch_deletion_request_events
+        )?;
+        let events = Self::combine_history_events(issuance_events, disclosure_events, deletion_request_events)?;
 
-        Self::combine_history_events(issuance_events, disclosure_events)
+        Ok(WalletEventPage::from_ordered_events(events, query.limit))
     }
 
     async fn did_share_data_with_relying_party(
@@ -589,13 +835,122 @@ where
 
         Ok(exists)
     }
+
+    async fn export_backup(&self) -> StorageResult<BackupContents> {
+        let connection = self.database()?.connection();
+
+        let keyed_data = keyed_data::Entity::find()
+            .filter(keyed_data::Column::Key.is_not_in(DEVICE_BOUND_DATA_KEYS))
+            .order_by_asc(keyed_data::Column::Key)
+            .all(connection)
+            .await?
+            .into_iter()
+            .map(|model| (model.key, model.data))
+            .collect();
+
+        let mdocs = mdoc::Entity::find()
+            .find_with_related(mdoc_copy::Entity)
+            .order_by_asc(mdoc_copy::Column::DisclosureCount)
+            .all(connection)
+            .await?
+            .into_iter()
+            .map(|(_, copy_models)| {
+                let copies = copy_models
+                    .into_iter()
+                    .map(|model| {
+                        let copy = BackupMdocCopy {
+                            mdoc: cbor_deserialize(model.mdoc.as_slice())?,
+                            disclosure_count: model.disclosure_count,
+                        };
+
+                        Ok(copy)
+                    })
+                    .collect::<Result<_, CborError>>()?;
+
+                Ok(BackupMdoc { copies })
+            })
+            .collect::<Result<_, CborError>>()?;
+
+        let events = self.fetch_wallet_events().await?;
+
+        Ok(BackupContents {
+            keyed_data,
+            mdocs,
+            events,
+        })
+    }
+
+    /// Replace all data that is not bound to this device with the contents of a backup, in one transaction.
+    async fn import_backup(&mut self, contents: BackupContents) -> StorageResult<()> {
+        let transaction = self.database()?.connection().begin().await?;
+
+        keyed_data::Entity::delete_many()
+            .filter(keyed_data::Column::Key.is_not_in(DEVICE_BOUND_DATA_KEYS))
+            .exec(&transaction)
+            .await?;
+        mdoc_copy::Entity::delete_many().exec(&transaction).await?;
+        mdoc::Entity::delete_many().exec(&transaction).await?;
+        issuance_history_event_doc_type::Entity::delete_many()
+            .exec(&transaction)
+            .await?;
+        disclosure_history_event_doc_type::Entity::delete_many()
+            .exec(&transaction)
+            .await?;
+        deletion_request_history_event_doc_type::Entity::delete_many()
+            .exec(&transaction)
+            .await?;
+        issuance_history_event::Entity::delete_many().exec(&transaction).await?;
+        disclosure_history_event::Entity::delete_many()
+            .exec(&transaction)
+            .await?;
+        deletion_request_history_event::Entity::delete_many()
+            .exec(&transaction)
+            .await?;
+        history_doc_type::Entity::delete_many().exec(&transaction).await?;
+
+        let keyed_data_models = contents
+            .keyed_data
+            .into_iter()
+            .filter(|(key, _)| !is_device_bound_data_key(key))
+            .map(|(key, data)| keyed_data::ActiveModel {
+                key: Set(key),
+                data: Set(data),
+            })
+            .collect::<Vec<_>>();
+
+        if !keyed_data_models.is_empty() {
+            keyed_data::Entity::insert_many(keyed_data_models)
+                .exec(&transaction)
+                .await?;
+        }
+
+        let mdocs = contents
+            .mdocs
+            .into_iter()
+            .map(|mdoc| {
+                mdoc.copies
+                    .into_iter()
+                    .map(|copy| (copy.mdoc, copy.disclosure_count))
+                    .collect()
+            })
+            .collect();
+        Self::insert_mdoc_copies(&transaction, mdocs).await?;
+
+        for event in contents.events {
+            Self::insert_wallet_event(&transaction, event).await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
     use std::mem;
 
-    use chrono::{TimeZone, Utc};
+    use chrono::{Duration, TimeZone, Utc};
     use once_cell::sync::Lazy;
     use tokio::fs;
 
@@ -609,7 +964,7 @@ pub(crate) mod tests {
         account::messages::auth::WalletCertificate, keys::software::SoftwareEncryptionKey, utils::random_bytes,
     };
 
-    use crate::storage::data::RegistrationData;
+    use crate::storage::{data::RegistrationData, event_log::EventStatus, event_query::WalletEventType};
 
     use super::*;
 
@@ -876,6 +1231,89 @@ pub(crate) mod tests {
         assert!(fetched_unique_doctype_mismatch.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_backup_storage() {
+        let mut storage = open_test_database_storage().await;
+
+        // Fill the database with a registration, mdoc copies and a history event.
+        storage
+            .insert_data(&RegistrationData {
+                pin_salt: vec![1, 2, 3, 4],
+                wallet_certificate: WalletCertificate::from("thisisdefinitelyvalid"),
+            })
+            .await
+            .unwrap();
+
+        let mdoc = Mdoc::new_example_mock();
+        storage
+            .insert_mdocs(vec![MdocCopies::from([mdoc.clone(), mdoc.clone()].to_vec())])
+            .await
+            .unwrap();
+        let mdoc_copy_id = storage
+            .fetch_unique_mdocs()
+            .await
+            .unwrap()
+            .first()
+            .unwrap()
+            .mdoc_copy_id;
+        storage
+            .increment_mdoc_copies_usage_count(vec![mdoc_copy_id])
+            .await
+            .unwrap();
+
+        let event = WalletEvent::issuance_from_str(
+            vec![PID_DOCTYPE],
+            Utc.with_ymd_and_hms(2023, 11, 29, 10, 50, 45).unwrap(),
+            ISSUER_KEY.certificate().clone(),
+        );
+        storage.log_wallet_event(event.clone()).await.unwrap();
+
+        // The exported contents should not include the registration.
+        let contents = storage.export_backup().await.expect("Could not export backup");
+
+        assert!(contents.keyed_data.is_empty());
+        assert_eq!(contents.events, vec![event.clone()]);
+        assert_eq!(contents.mdocs.len(), 1);
+        let disclosure_counts = contents.mdocs[0]
+            .copies
+            .iter()
+            .map(|copy| {
+                assert_eq!(copy.mdoc, mdoc);
+                copy.disclosure_count
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(disclosure_counts, vec![0, 1]);
+
+        // Importing the contents into another database should replace its mdocs and history, but not its registration.
+        let mut other_storage = open_test_database_storage().await;
+        let other_registration = RegistrationData {
+            pin_salt: vec![5, 6, 7, 8],
+            wallet_certificate: WalletCertificate::from("thisisalsovalid"),
+        };
+        other_storage.insert_data(&other_registration).await.unwrap();
+        other_storage
+            .log_wallet_event(WalletEvent::disclosure_cancel(
+                Utc.with_ymd_and_hms(2023, 11, 30, 10, 50, 45).unwrap(),
+                READER_KEY.certificate().clone(),
+            ))
+            .await
+            .unwrap();
+
+        other_storage
+            .import_backup(contents.clone())
+            .await
+            .expect("Could not import backup");
+
+        let fetched_registration = other_storage
+            .fetch_data::<RegistrationData>()
+            .await
+            .unwrap()
+            .expect("Registration data not present in storage");
+        assert_eq!(fetched_registration.pin_salt, other_registration.pin_salt);
+        assert_eq!(other_storage.fetch_wallet_events().await.unwrap(), vec![event]);
+        assert_eq!(other_storage.export_backup().await.unwrap(), contents);
+    }
+
     #[tokio::test]
     async fn test_event_log_storage_ordering() {
         let mut storage = open_test_database_storage().await;
@@ -897,6 +1335,24 @@ pub(crate) mod tests {
         test_history_by_doc_type(&mut storage).await;
     }
 
+    #[tokio::test]
+    async fn test_event_log_storage_query() {
+        let mut storage = open_test_database_storage().await;
+
+        // State should be Opened.
+        let state = storage.state().await.unwrap();
+        assert!(matches!(state, StorageState::Opened));
+
+        test_history_query(&mut storage).await;
+    }
+
+    #[tokio::test]
+    async fn test_event_log_storage_deletion_request() {
+        let mut storage = open_test_database_storage().await;
+
+        test_history_deletion_request(&mut storage).await;
+    }
+
     #[tokio::test]
     async fn test_storing_disclosure_cancel_event() {
         let mut storage = open_test_database_storage().await;
@@ -1123,4 +1579,188 @@ pub(crate) mod tests {
             vec![disclosure_pid_and_address, issuance,]
         );
     }
+
+    pub(crate) async fn test_history_query(storage: &mut impl Storage) {
+        let timestamp = Utc.with_ymd_and_hms(2023, 11, 11, 11, 11, 00).unwrap();
+
+        let other_reader_key = KeyPair::generate_reader_mock_ca()
+            .unwrap()
+            .generate_reader_mock(ReaderRegistration::new_mock().into())
+            .unwrap();
+
+        let issuance = WalletEvent::issuance_from_str(vec![PID_DOCTYPE], timestamp, ISSUER_KEY.certificate().clone());
+        let disclosure = WalletEvent::disclosure_from_str(
+            vec![PID_DOCTYPE],
+            timestamp + Duration::days(1),
+            READER_KEY.certificate().clone(),
+            ISSUER_KEY.certificate(),
+        );
+        let disclosure_cancel =
+            WalletEvent::disclosure_cancel(timestamp + Duration::days(2), other_reader_key.certificate().clone());
+        let disclosure_error =
+            WalletEvent::disclosure_error(timestamp + Duration::days(3), READER_KEY.certificate().clone());
+
+        for event in [&disclosure, &issuance, &disclosure_error, &disclosure_cancel] {
+            storage.log_wallet_event(event.clone()).await.unwrap();
+        }
+
+        // Paginate through all events, newest first.
+        let query = WalletEventQuery {
+            limit: Some(3),
+            ..Default::default()
+        };
+        let page = storage.query_wallet_events(&query).await.unwrap();
+        assert_eq!(
+            page.events,
+            vec![disclosure_error.clone(), disclosure_cancel.clone(), disclosure.clone()]
+        );
+        assert_eq!(page.next_cursor, Some(disclosure.cursor()));
+
+        let query = WalletEventQuery {
+            after: page.next_cursor,
+            ..query
+        };
+        let page = storage.query_wallet_events(&query).await.unwrap();
+        assert_eq!(
+            page,
+            WalletEventPage {
+                events: vec![issuance.clone()],
+                next_cursor: None,
+            }
+        );
+
+        // Filter on a date range.
+        let query = WalletEventQuery {
+            from: Some(timestamp + Duration::days(1)),
+            until: Some(timestamp + Duration::days(3)),
+            ..Default::default()
+        };
+        assert_eq!(
+            storage.query_wallet_events(&query).await.unwrap().events,
+            vec![disclosure_cancel.clone(), disclosure.clone()]
+        );
+
+        // Filter on event type and status.
+        let query = WalletEventQuery {
+            event_types: HashSet::from([WalletEventType::Issuance]),
+            ..Default::default()
+        };
+        assert_eq!(
+            storage.query_wallet_events(&query).await.unwrap().events,
+            vec![issuance.clone()]
+        );
+
+        let query = WalletEventQuery {
+            event_types: HashSet::from([WalletEventType::Login]),
+            ..Default::default()
+        };
+        assert!(storage.query_wallet_events(&query).await.unwrap().events.is_empty());
+
+        let query = WalletEventQuery {
+            statuses: HashSet::from([EventStatus::Success]),
+            ..Default::default()
+        };
+        assert_eq!(
+            storage.query_wallet_events(&query).await.unwrap().events,
+            vec![disclosure.clone(), issuance.clone()]
+        );
+
+        let query = WalletEventQuery {
+            statuses: HashSet::from([EventStatus::Error, EventStatus::Cancelled]),
+            ..Default::default()
+        };
+        assert_eq!(
+            storage.query_wallet_events(&query).await.unwrap().events,
+            vec![disclosure_error.clone(), disclosure_cancel.clone()]
+        );
+
+        // Filter on relying party, either by certificate or by organization.
+        let query = WalletEventQuery {
+            relying_party: Some(RelyingPartyFilter::Certificate(READER_KEY.certificate().clone())),
+            ..Default::default()
+        };
+        assert_eq!(
+            storage.query_wallet_events(&query).await.unwrap().events,
+            vec![disclosure_error.clone(), disclosure.clone()]
+        );
+
+        let query = WalletEventQuery {
+            relying_party: Some(RelyingPartyFilter::Organization("Organization".to_string())),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let page = storage.query_wallet_events(&query).await.unwrap();
+        assert_eq!(page.events, vec![disclosure_error, disclosure_cancel.clone()]);
+        assert_eq!(page.next_cursor, Some(disclosure_cancel.cursor()));
+
+        let query = WalletEventQuery {
+            relying_party: Some(RelyingPartyFilter::Organization("Unknown".to_string())),
+            ..Default::default()
+        };
+        assert!(storage.query_wallet_events(&query).await.unwrap().events.is_empty());
+    }
+
+    pub(crate) async fn test_history_deletion_request(storage: &mut impl Storage) {
+        let timestamp = Utc.with_ymd_and_hms(2023, 11, 11, 11, 11, 00).unwrap();
+
+        let disclosure = WalletEvent::disclosure_from_str(
+            vec![PID_DOCTYPE],
+            timestamp,
+            READER_KEY.certificate().clone(),
+            ISSUER_KEY.certificate(),
+        );
+        let deletion_request = WalletEvent::deletion_request_from_str(
+            vec![PID_DOCTYPE],
+            timestamp + Duration::days(1),
+            READER_KEY.certificate().clone(),
+        );
+
+        for event in [&disclosure, &deletion_request] {
+            storage.log_wallet_event(event.clone()).await.unwrap();
+        }
+
+        // Both events should be retrievable by their identifier, including the session reference of the disclosure.
+        assert_eq!(
+            storage.fetch_wallet_event(*disclosure.id()).await.unwrap(),
+            Some(disclosure.clone())
+        );
+        assert_eq!(
+            storage.fetch_wallet_event(*deletion_request.id()).await.unwrap(),
+            Some(deletion_request.clone())
+        );
+        assert_eq!(storage.fetch_wallet_event(Uuid::new_v4()).await.unwrap(), None);
+
+        assert_eq!(
+            storage.fetch_wallet_events().await.unwrap(),
+            vec![deletion_request.clone(), disclosure.clone()]
+        );
+        assert_eq!(
+            storage.fetch_wallet_events_by_doc_type(PID_DOCTYPE).await.unwrap(),
+            vec![deletion_request.clone(), disclosure.clone()]
+        );
+        assert!(storage
+            .fetch_wallet_events_by_doc_type(ADDRESS_DOCTYPE)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let query = WalletEventQuery {
+            event_types: HashSet::from([WalletEventType::DeletionRequest]),
+            ..Default::default()
+        };
+        assert_eq!(
+            storage.query_wallet_events(&query).await.unwrap().events,
+            vec![deletion_request.clone()]
+        );
+
+        let query = WalletEventQuery {
+            relying_party: Some(RelyingPartyFilter::Certificate(READER_KEY.certificate().clone())),
+            statuses: HashSet::from([EventStatus::Success]),
+            ..Default::default()
+        };
+        assert_eq!(
+            storage.query_wallet_events(&query).await.unwrap().events,
+            vec![deletion_request, disclosure]
+        );
+    }
 }
@@ -3,9 +3,10 @@ use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub use entity::{disclosure_history_event, issuance_history_event};
+pub use entity::{deletion_request_history_event, disclosure_history_event, issuance_history_event};
 use nl_wallet_mdoc::{
     holder::{Mdoc, ProposedAttributes, ProposedDocumentAttributes},
+    identifiers::AttributeIdentifier,
     unsigned::Entry,
     utils::{cose::CoseError, x509::Certificate},
     DataElementIdentifier, DataElementValue, DocType, NameSpace,
@@ -13,9 +14,12 @@ use nl_wallet_mdoc::{
 
 use crate::document::DisclosureType;
 
+use super::event_query::WalletEventCursor;
+
 // TODO: Think about refactoring/renaming EventStatus.
 // For rationale, see comment for DisclosureType in mdoc.rs.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum EventStatus {
     Success,
     Error,
@@ -32,9 +36,9 @@ impl From<EventStatus> for disclosure_history_event::EventStatus {
     }
 }
 
-impl From<&disclosure_history_event::Model> for EventStatus {
-    fn from(source: &disclosure_history_event::Model) -> Self {
-        match source.status {
+impl From<&disclosure_history_event::EventStatus> for EventStatus {
+    fn from(source: &disclosure_history_event::EventStatus) -> Self {
+        match source {
             disclosure_history_event::EventStatus::Success => Self::Success,
             disclosure_history_event::EventStatus::Error => Self::Error,
             disclosure_history_event::EventStatus::Cancelled => Self::Cancelled,
@@ -60,7 +64,7 @@ impl From<&disclosure_history_event::Model> for DisclosureType {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum WalletEvent {
     Issuance {
         id: Uuid,
@@ -74,6 +78,20 @@ pub enum WalletEvent {
         reader_certificate: Certificate,
         status: EventStatus,
         r#type: DisclosureType,
+        /// Identifies the disclosure session at the verifier, used to refer to it in a deletion request.
+        session_reference: Option<String>,
+        /// Issued by the verifier after a successful disclosure, which proves to it that a deletion request comes
+        /// from the wallet that disclosed the attributes.
+        deletion_token: Option<String>,
+    },
+    DeletionRequest {
+        id: Uuid,
+        /// The identifier of the disclosure event of which the attributes were requested to be deleted.
+        disclosure_id: Uuid,
+        attributes: Vec<AttributeIdentifier>,
+        timestamp: DateTime<Utc>,
+        reader_certificate: Certificate,
+        status: EventStatus,
     },
 }
 
@@ -91,6 +109,8 @@ impl WalletEvent {
         reader_certificate: Certificate,
         status: EventStatus,
         r#type: DisclosureType,
+        session_reference: Option<String>,
+        deletion_token: Option<String>,
     ) -> Self {
         Self::Disclosure {
             id: Uuid::new_v4(),
@@ -99,6 +119,24 @@ impl WalletEvent {
             reader_certificate,
             status,
             r#type,
+            session_reference,
+            deletion_token,
+        }
+    }
+
+    pub fn new_deletion_request(
+        disclosure_id: Uuid,
+        attributes: Vec<AttributeIdentifier>,
+        reader_certificate: Certificate,
+        status: EventStatus,
+    ) -> Self {
+        Self::DeletionRequest {
+            id: Uuid::new_v4(),
+            disclosure_id,
+            attributes,
+            timestamp: Utc::now(),
+            reader_certificate,
+            status,
         }
     }
 
@@ -114,6 +152,9 @@ impl WalletEvent {
                 ..
             } => mdocs.keys().map(String::as_str).collect(),
             Self::Disclosure { documents: None, .. } => Default::default(),
+            Self::DeletionRequest { attributes, .. } => {
+                attributes.iter().map(|attribute| attribute.doc_type.as_str()).collect()
+            }
         }
     }
 
@@ -121,6 +162,23 @@ impl WalletEvent {
         match self {
             Self::Issuance { timestamp, .. } => timestamp,
             Self::Disclosure { timestamp, .. } => timestamp,
+            Self::DeletionRequest { timestamp, .. } => timestamp,
+        }
+    }
+
+    pub fn id(&self) -> &Uuid {
+        match self {
+            Self::Issuance { id, .. } => id,
+            Self::Disclosure { id, .. } => id,
+            Self::DeletionRequest { id, .. } => id,
+        }
+    }
+
+    /// Returns the position of this event in the ordered list of events.
+    pub fn cursor(&self) -> WalletEventCursor {
+        WalletEventCursor {
+            timestamp: *self.timestamp(),
+            id: *self.id(),
         }
     }
 }
@@ -130,11 +188,13 @@ impl TryFrom<disclosure_history_event::Model> for WalletEvent {
     fn try_from(event: disclosure_history_event::Model) -> Result<Self, Self::Error> {
         let result = Self::Disclosure {
             id: event.id,
-            status: EventStatus::from(&event),
+            status: EventStatus::from(&event.status),
             r#type: DisclosureType::from(&event),
             documents: event.attributes.map(serde_json::from_value).transpose()?,
             timestamp: event.timestamp,
             reader_certificate: event.relying_party_certificate.into(),
+            session_reference: event.session_reference,
+            deletion_token: event.deletion_token,
         };
         Ok(result)
     }
@@ -152,10 +212,26 @@ impl TryFrom<issuance_history_event::Model> for WalletEvent {
     }
 }
 
+impl TryFrom<deletion_request_history_event::Model> for WalletEvent {
+    type Error = serde_json::Error;
+    fn try_from(event: deletion_request_history_event::Model) -> Result<Self, Self::Error> {
+        let result = Self::DeletionRequest {
+            id: event.id,
+            disclosure_id: event.disclosure_history_event_id,
+            status: EventStatus::from(&event.status),
+            attributes: serde_json::from_value(event.attributes)?,
+            timestamp: event.timestamp,
+            reader_certificate: event.relying_party_certificate.into(),
+        };
+        Ok(result)
+    }
+}
+
 /// Enumerates the different database models for a [`WalletEvent`].
 pub(crate) enum WalletEventModel {
     Issuance(issuance_history_event::Model),
     Disclosure(disclosure_history_event::Model),
+    DeletionRequest(deletion_request_history_event::Model),
 }
 
 impl TryFrom<WalletEvent> for WalletEventModel {
@@ -174,6 +250,8 @@ impl TryFrom<WalletEvent> for WalletEventModel {
                 timestamp,
                 reader_certificate,
                 r#type,
+                session_reference,
+                deletion_token,
             } => Self::Disclosure(disclosure_history_event::Model {
                 attributes: documents.map(serde_json::to_value).transpose()?,
                 id,
@@ -181,6 +259,23 @@ impl TryFrom<WalletEvent> for WalletEventModel {
                 relying_party_certificate: reader_certificate.into(),
                 status: status.into(),
                 r#type: r#type.into(),
+                session_reference,
+                deletion_token,
+            }),
+            WalletEvent::DeletionRequest {
+                id,
+                disclosure_id,
+                attributes,
+                timestamp,
+                reader_certificate,
+                status,
+            } => Self::DeletionRequest(deletion_request_history_event::Model {
+                attributes: serde_json::to_value(attributes)?,
+                id,
+                timestamp,
+                disclosure_history_event_id: disclosure_id,
+                relying_party_certificate: reader_certificate.into(),
+                status: status.into(),
             }),
         };
         Ok(result)
@@ -325,6 +420,8 @@ mod test {
                 reader_certificate,
                 status: EventStatus::Success,
                 r#type: DisclosureType::Regular,
+                session_reference: Some(Uuid::new_v4().to_string()),
+                deletion_token: Some(Uuid::new_v4().to_string()),
             }
         }
 
@@ -346,6 +443,8 @@ mod test {
                 reader_certificate,
                 status: EventStatus::Error,
                 r#type: DisclosureType::Regular,
+                session_reference: None,
+                deletion_token: None,
             }
         }
 
@@ -357,6 +456,8 @@ mod test {
                 reader_certificate,
                 status: EventStatus::Cancelled,
                 r#type: DisclosureType::Regular,
+                session_reference: None,
+                deletion_token: None,
             }
         }
 
@@ -368,6 +469,31 @@ mod test {
                 reader_certificate,
                 status: EventStatus::Error,
                 r#type: DisclosureType::Regular,
+                session_reference: None,
+                deletion_token: None,
+            }
+        }
+
+        pub fn deletion_request_from_str(
+            doc_types: Vec<&str>,
+            timestamp: DateTime<Utc>,
+            reader_certificate: Certificate,
+        ) -> Self {
+            let attributes = doc_types
+                .into_iter()
+                .map(|doc_type| AttributeIdentifier {
+                    doc_type: doc_type.to_string(),
+                    namespace: doc_type.to_string(),
+                    attribute: "bsn".to_string(),
+                })
+                .collect();
+            Self::DeletionRequest {
+                id: Uuid::new_v4(),
+                disclosure_id: Uuid::new_v4(),
+                attributes,
+                timestamp,
+                reader_certificate,
+                status: EventStatus::Success,
             }
         }
     }
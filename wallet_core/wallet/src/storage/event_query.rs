@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use nl_wallet_mdoc::utils::{
+    reader_auth::ReaderRegistration,
+    x509::{Certificate, MdocCertificateExtension},
+};
+
+use crate::document::DisclosureType;
+
+use super::event_log::{EventStatus, WalletEvent};
+
+/// The type of a [`WalletEvent`], as can be selected for in a [`WalletEventQuery`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WalletEventType {
+    Issuance,
+    Disclosure,
+    Login,
+    DeletionRequest,
+}
+
+/// Selects the relying party of disclosure events, either by its exact reader certificate or
+/// by the legal name of its organization (in any of the languages provided in the registration).
+#[derive(Debug, Clone, PartialEq)]
+pub enum RelyingPartyFilter {
+    Certificate(Certificate),
+    Organization(String),
+}
+
+/// Points to a position in the ordered list of [`WalletEvent`]s. Events are always ordered newest first,
+/// using the event identifier as tie-breaker for events that have exactly the same timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WalletEventCursor {
+    pub timestamp: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+/// Describes a (filtered) page of [`WalletEvent`]s. All filters are optional, an empty set of event types or
+/// statuses means that no filtering takes place on that field. Note that issuance events are considered to have
+/// the [`EventStatus::Success`] status and will never match a relying party filter.
+#[derive(Debug, Clone, Default)]
+pub struct WalletEventQuery {
+    /// Only return events that are strictly older than this cursor, i.e. the next page.
+    pub after: Option<WalletEventCursor>,
+    /// The maximum amount of events on the page.
+    pub limit: Option<u64>,
+    /// Only return events with a timestamp equal to or later than this moment.
+    pub from: Option<DateTime<Utc>>,
+    /// Only return events with a timestamp before this moment.
+    pub until: Option<DateTime<Utc>>,
+    pub event_types: HashSet<WalletEventType>,
+    pub statuses: HashSet<EventStatus>,
+    pub relying_party: Option<RelyingPartyFilter>,
+}
+
+/// The result of a [`WalletEventQuery`]. If there are more events to be fetched,
+/// `next_cursor` should be used as the `after` field of the query for the next page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletEventPage {
+    pub events: Vec<WalletEvent>,
+    pub next_cursor: Option<WalletEventCursor>,
+}
+
+impl WalletEventQuery {
+    pub fn includes_event_type(&self, event_type: WalletEventType) -> bool {
+        self.event_types.is_empty() || self.event_types.contains(&event_type)
+    }
+
+    pub fn includes_status(&self, status: &EventStatus) -> bool {
+        self.statuses.is_empty() || self.statuses.contains(status)
+    }
+
+    /// Returns if issuance events may be part of the result at all.
+    pub fn includes_issuance(&self) -> bool {
+        self.includes_event_type(WalletEventType::Issuance)
+            && self.includes_status(&EventStatus::Success)
+            && self.relying_party.is_none()
+    }
+
+    /// Returns the disclosure types that may be part of the result.
+    pub fn disclosure_types(&self) -> Vec<DisclosureType> {
+        [
+            (WalletEventType::Disclosure, DisclosureType::Regular),
+            (WalletEventType::Login, DisclosureType::Login),
+        ]
+        .into_iter()
+        .filter(|(event_type, _)| self.includes_event_type(*event_type))
+        .map(|(_, disclosure_type)| disclosure_type)
+        .collect()
+    }
+
+    /// Checks if a single [`WalletEvent`] matches all of the filters in this query, disregarding the limit.
+    pub fn matches(&self, event: &WalletEvent) -> bool {
+        if self.after.is_some_and(|after| event.cursor() >= after)
+            || self.from.is_some_and(|from| *event.timestamp() < from)
+            || self.until.is_some_and(|until| *event.timestamp() >= until)
+        {
+            return false;
+        }
+
+        match event {
+            WalletEvent::Issuance { .. } => self.includes_issuance(),
+            WalletEvent::Disclosure {
+                reader_certificate,
+                status,
+                r#type,
+                ..
+            } => {
+                self.disclosure_types().contains(r#type)
+                    && self.includes_status(status)
+                    && self
+                        .relying_party
+                        .as_ref()
+                        .map_or(true, |relying_party| relying_party.matches(reader_certificate))
+            }
+            WalletEvent::DeletionRequest {
+                reader_certificate,
+                status,
+                ..
+            } => {
+                self.includes_event_type(WalletEventType::DeletionRequest)
+                    && self.includes_status(status)
+                    && self
+                        .relying_party
+                        .as_ref()
+                        .map_or(true, |relying_party| relying_party.matches(reader_certificate))
+            }
+        }
+    }
+}
+
+impl RelyingPartyFilter {
+    /// Checks if the reader certificate matches this filter. A certificate that
+    /// does not contain a valid [`ReaderRegistration`] never matches an organization.
+    pub fn matches(&self, reader_certificate: &Certificate) -> bool {
+        match self {
+            Self::Certificate(certificate) => certificate == reader_certificate,
+            Self::Organization(legal_name) => ReaderRegistration::from_certificate(reader_certificate)
+                .ok()
+                .flatten()
+                .is_some_and(|registration| {
+                    registration
+                        .organization
+                        .legal_name
+                        .0
+                        .values()
+                        .any(|name| name == legal_name)
+                }),
+        }
+    }
+}
+
+impl WalletEventPage {
+    /// Creates a page from events that match the query, truncating them to the limit.
+    /// The events are expected to be ordered according to [`WalletEventCursor`], newest first.
+    pub fn from_ordered_events(mut events: Vec<WalletEvent>, limit: Option<u64>) -> Self {
+        let next_cursor = match limit {
+            Some(limit) if events.len() as u64 > limit => {
+                events.truncate(limit as usize);
+                events.last().map(WalletEvent::cursor)
+            }
+            _ => None,
+        };
+
+        Self { events, next_cursor }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use once_cell::sync::Lazy;
+
+    use nl_wallet_mdoc::{server_keys::KeyPair, utils::issuer_auth::IssuerRegistration};
+
+    use super::*;
+
+    static ISSUER_KEY: Lazy<KeyPair> = Lazy::new(|| {
+        let issuer_ca = KeyPair::generate_issuer_mock_ca().unwrap();
+        issuer_ca
+            .generate_issuer_mock(IssuerRegistration::new_mock().into())
+            .unwrap()
+    });
+
+    static READER_KEY: Lazy<KeyPair> = Lazy::new(|| {
+        let reader_ca = KeyPair::generate_reader_mock_ca().unwrap();
+        reader_ca
+            .generate_reader_mock(ReaderRegistration::new_mock().into())
+            .unwrap()
+    });
+
+    #[test]
+    fn test_wallet_event_query_matches() {
+        let timestamp = Utc.with_ymd_and_hms(2023, 11, 11, 11, 11, 00).unwrap();
+        let issuance =
+            WalletEvent::issuance_from_str(vec!["com.example.pid"], timestamp, ISSUER_KEY.certificate().clone());
+        let cancel = WalletEvent::disclosure_cancel(timestamp + Duration::days(1), READER_KEY.certificate().clone());
+
+        let query = WalletEventQuery::default();
+        assert!(query.matches(&issuance));
+        assert!(query.matches(&cancel));
+
+        let query = WalletEventQuery {
+            statuses: HashSet::from([EventStatus::Cancelled]),
+            ..Default::default()
+        };
+        assert!(!query.matches(&issuance));
+        assert!(query.matches(&cancel));
+
+        let query = WalletEventQuery {
+            event_types: HashSet::from([WalletEventType::Issuance, WalletEventType::Login]),
+            ..Default::default()
+        };
+        assert!(query.matches(&issuance));
+        assert!(!query.matches(&cancel));
+
+        let query = WalletEventQuery {
+            from: Some(timestamp + Duration::hours(1)),
+            ..Default::default()
+        };
+        assert!(!query.matches(&issuance));
+        assert!(query.matches(&cancel));
+
+        let query = WalletEventQuery {
+            until: Some(timestamp + Duration::days(1)),
+            ..Default::default()
+        };
+        assert!(query.matches(&issuance));
+        assert!(!query.matches(&cancel));
+
+        let query = WalletEventQuery {
+            after: Some(cancel.cursor()),
+            ..Default::default()
+        };
+        assert!(query.matches(&issuance));
+        assert!(!query.matches(&cancel));
+
+        let legal_name = ReaderRegistration::new_mock()
+            .organization
+            .legal_name
+            .0
+            .into_values()
+            .next()
+            .unwrap();
+        for relying_party in [
+            RelyingPartyFilter::Certificate(READER_KEY.certificate().clone()),
+            RelyingPartyFilter::Organization(legal_name),
+        ] {
+            let query = WalletEventQuery {
+                relying_party: Some(relying_party),
+                ..Default::default()
+            };
+            assert!(!query.matches(&issuance));
+            assert!(query.matches(&cancel));
+        }
+
+        let query = WalletEventQuery {
+            relying_party: Some(RelyingPartyFilter::Organization("Unknown".to_string())),
+            ..Default::default()
+        };
+        assert!(!query.matches(&cancel));
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
 
 use chrono::{Duration, Utc};
 use sea_orm::DbErr;
@@ -12,8 +15,10 @@ use nl_wallet_mdoc::{
 use crate::storage::event_log::WalletEventModel;
 
 use super::{
+    backup::{is_device_bound_data_key, BackupContents, BackupMdoc, BackupMdocCopy},
     data::{KeyedData, RegistrationData},
     event_log::WalletEvent,
+    event_query::{WalletEventPage, WalletEventQuery},
     Storage, StorageResult, StorageState, StoredMdocCopy,
 };
 
@@ -21,7 +26,7 @@ use super::{
 #[derive(Debug)]
 pub struct MockStorage {
     pub state: StorageState,
-    pub data: HashMap<&'static str, String>,
+    pub data: HashMap<String, String>,
     pub mdocs: MdocsMap,
     pub mdoc_copies_usage_counts: HashMap<Uuid, u32>,
     pub event_log: Vec<WalletEvent>,
@@ -33,7 +38,10 @@ impl MockStorage {
         let mut data = HashMap::new();
 
         if let Some(registration) = registration {
-            data.insert(RegistrationData::KEY, serde_json::to_string(&registration).unwrap());
+            data.insert(
+                RegistrationData::KEY.to_string(),
+                serde_json::to_string(&registration).unwrap(),
+            );
         }
 
         let mdocs = MdocsMap::new();
@@ -94,7 +102,8 @@ impl Storage for MockStorage {
             panic!("Registration already present");
         }
 
-        self.data.insert(D::KEY, serde_json::to_string(&data).unwrap());
+        self.data
+            .insert(D::KEY.to_string(), serde_json::to_string(&data).unwrap());
 
         Ok(())
     }
@@ -106,7 +115,16 @@ impl Storage for MockStorage {
             panic!("Registration not present");
         }
 
-        self.data.insert(D::KEY, serde_json::to_string(&data).unwrap());
+        self.data
+            .insert(D::KEY.to_string(), serde_json::to_string(&data).unwrap());
+
+        Ok(())
+    }
+
+    async fn delete_data<D: KeyedData>(&mut self) -> StorageResult<()> {
+        self.check_query_error()?;
+
+        self.data.remove(D::KEY);
 
         Ok(())
     }
@@ -167,6 +185,7 @@ impl Storage for MockStorage {
         let converted_event = match WalletEventModel::try_from(event.clone())? {
             WalletEventModel::Issuance(entity) => entity.try_into()?,
             WalletEventModel::Disclosure(entity) => entity.try_into()?,
+            WalletEventModel::DeletionRequest(entity) => entity.try_into()?,
         };
         assert_eq!(event, converted_event);
         self.event_log.push(converted_event);
@@ -207,15 +226,100 @@ impl Storage for MockStorage {
         Ok(events)
     }
 
+    async fn fetch_wallet_event(&self, id: Uuid) -> StorageResult<Option<WalletEvent>> {
+        self.check_query_error()?;
+
+        let event = self.event_log.iter().find(|event| *event.id() == id).cloned();
+        Ok(event)
+    }
+
+    async fn query_wallet_events(&self, query: &WalletEventQuery) -> StorageResult<WalletEventPage> {
+        self.check_query_error()?;
+
+        let mut events = self
+            .event_log
+            .iter()
+            .filter(|event| query.matches(event))
+            .cloned()
+            .collect::<Vec<_>>();
+        events.sort_by_key(|event| Reverse(event.cursor()));
+        Ok(WalletEventPage::from_ordered_events(events, query.limit))
+    }
+
     async fn did_share_data_with_relying_party(&self, certificate: &Certificate) -> StorageResult<bool> {
         self.check_query_error()?;
 
         let exists = self.event_log.iter().any(|event| match event {
-            WalletEvent::Issuance { .. } => false,
+            WalletEvent::Issuance { .. } | WalletEvent::DeletionRequest { .. } => false,
             WalletEvent::Disclosure { reader_certificate, .. } => reader_certificate == certificate,
         });
         Ok(exists)
     }
+
+    async fn export_backup(&self) -> StorageResult<BackupContents> {
+        self.check_query_error()?;
+
+        let keyed_data = self
+            .data
+            .iter()
+            .filter(|(key, _)| !is_device_bound_data_key(key))
+            .map(|(key, data)| (key.clone(), serde_json::from_str(data).unwrap()))
+            .collect();
+
+        // Note that the disclosure counts are not tracked per copy by this mock.
+        let mdocs = self
+            .mdocs
+            .0
+            .values()
+            .flat_map(|doc_type_mdocs| doc_type_mdocs.values())
+            .map(|mdoc_copies| BackupMdoc {
+                copies: mdoc_copies
+                    .cred_copies
+                    .iter()
+                    .map(|mdoc| BackupMdocCopy {
+                        mdoc: mdoc.clone(),
+                        disclosure_count: 0,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let events = self.fetch_wallet_events().await?;
+
+        Ok(BackupContents {
+            keyed_data,
+            mdocs,
+            events,
+        })
+    }
+
+    async fn import_backup(&mut self, contents: BackupContents) -> StorageResult<()> {
+        self.check_query_error()?;
+
+        self.data.retain(|key, _| is_device_bound_data_key(key));
+        self.data.extend(
+            contents
+                .keyed_data
+                .into_iter()
+                .filter(|(key, _)| !is_device_bound_data_key(key))
+                .map(|(key, data)| (key, data.to_string())),
+        );
+
+        self.mdocs = MdocsMap::new();
+        self.mdocs
+            .add(
+                contents
+                    .mdocs
+                    .into_iter()
+                    .flat_map(|mdoc| mdoc.copies)
+                    .map(|copy| copy.mdoc),
+            )
+            .unwrap();
+        self.mdoc_copies_usage_counts.clear();
+        self.event_log = contents.events;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -223,7 +327,9 @@ mod tests {
     use serde::{Deserialize, Serialize};
 
     use crate::storage::{
-        database_storage::tests::{test_history_by_doc_type, test_history_ordering},
+        database_storage::tests::{
+            test_history_by_doc_type, test_history_deletion_request, test_history_ordering, test_history_query,
+        },
         KeyedData, Storage,
     };
 
@@ -278,4 +384,18 @@ mod tests {
         storage.open().await.unwrap();
         test_history_by_doc_type(&mut storage).await;
     }
+
+    #[tokio::test]
+    async fn history_events_query() {
+        let mut storage = MockStorage::default();
+        storage.open().await.unwrap();
+        test_history_query(&mut storage).await;
+    }
+
+    #[tokio::test]
+    async fn history_events_deletion_request() {
+        let mut storage = MockStorage::default();
+        storage.open().await.unwrap();
+        test_history_deletion_request(&mut storage).await;
+    }
 }
@@ -1,7 +1,9 @@
+mod backup;
 mod data;
 mod database;
 mod database_storage;
 mod event_log;
+mod event_query;
 mod key_file;
 mod sql_cipher_key;
 
@@ -19,9 +21,11 @@ use nl_wallet_mdoc::{
 };
 
 pub use self::{
-    data::{InstructionData, KeyedData, RegistrationData},
+    backup::BackupContents,
+    data::{InstructionData, KeyedData, RegistrationData, RestoreData},
     database_storage::DatabaseStorage,
-    event_log::{EventDocuments, EventStatus, WalletEvent},
+    event_log::{EventAttributes, EventDocuments, EventStatus, WalletEvent},
+    event_query::{RelyingPartyFilter, WalletEventCursor, WalletEventPage, WalletEventQuery, WalletEventType},
     key_file::KeyFileError,
 };
 
@@ -78,6 +82,7 @@ pub trait Storage {
     async fn fetch_data<D: KeyedData>(&self) -> StorageResult<Option<D>>;
     async fn insert_data<D: KeyedData>(&mut self, data: &D) -> StorageResult<()>;
     async fn update_data<D: KeyedData>(&mut self, data: &D) -> StorageResult<()>;
+    async fn delete_data<D: KeyedData>(&mut self) -> StorageResult<()>;
 
     async fn insert_mdocs(&mut self, mdocs: Vec<MdocCopies>) -> StorageResult<()>;
     async fn increment_mdoc_copies_usage_count(&mut self, mdoc_copy_ids: Vec<Uuid>) -> StorageResult<()>;
@@ -88,5 +93,10 @@ pub trait Storage {
     async fn fetch_wallet_events(&self) -> StorageResult<Vec<WalletEvent>>;
     async fn fetch_recent_wallet_events(&self) -> StorageResult<Vec<WalletEvent>>;
     async fn fetch_wallet_events_by_doc_type(&self, doc_type: &str) -> StorageResult<Vec<WalletEvent>>;
+    async fn fetch_wallet_event(&self, id: Uuid) -> StorageResult<Option<WalletEvent>>;
+    async fn query_wallet_events(&self, query: &WalletEventQuery) -> StorageResult<WalletEventPage>;
     async fn did_share_data_with_relying_party(&self, certificate: &Certificate) -> StorageResult<bool>;
+
+    async fn export_backup(&self) -> StorageResult<BackupContents>;
+    async fn import_backup(&mut self, contents: BackupContents) -> StorageResult<()>;
 }
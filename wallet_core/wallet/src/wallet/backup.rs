@@ -0,0 +1,292 @@
+use std::num::NonZeroU32;
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    pbkdf2,
+};
+use serde::{Deserialize, Serialize};
+use serde_with::{base64::Base64, serde_as};
+use tracing::{info, instrument};
+
+use nl_wallet_mdoc::utils::serialization::{cbor_deserialize, cbor_serialize, CborError};
+use wallet_common::utils::{random_bytes, random_string};
+
+use crate::storage::{BackupContents, RegistrationData, RestoreData, Storage, StorageError, StorageResult};
+
+use super::Wallet;
+
+const BACKUP_VERSION: u32 = 1;
+const BACKUP_SALT_LENGTH: usize = 32;
+const PBKDF2_ITERATIONS: NonZeroU32 = match NonZeroU32::new(600_000) {
+    Some(iterations) => iterations,
+    None => unreachable!(),
+};
+
+const RECOVERY_SECRET_LENGTH: usize = 24;
+const RECOVERY_SECRET_MIN_LENGTH: usize = 16;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("wallet is not registered")]
+    NotRegistered,
+    #[error("wallet is locked")]
+    Locked,
+    #[error("recovery secret should contain at least {RECOVERY_SECRET_MIN_LENGTH} characters")]
+    InvalidRecoverySecret,
+    #[error("unsupported backup version: {0}")]
+    UnsupportedVersion(u32),
+    // Do not format original error to prevent potentially leaking key material
+    #[error("could not encrypt backup")]
+    Encryption,
+    #[error("could not decrypt backup, the recovery secret may be incorrect")]
+    Decryption,
+    #[error("could not encode or decode backup contents: {0}")]
+    Cbor(#[from] CborError),
+    #[error("could not read backup contents from database: {0}")]
+    Storage(#[from] StorageError),
+}
+
+/// An encrypted backup of the wallet, which can be stored outside of the device. The contents are encrypted with
+/// AES-256-GCM, using a key that is derived from the recovery secret of the user through PBKDF2-HMAC-SHA256.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletBackup {
+    pub version: u32,
+    #[serde_as(as = "Base64")]
+    pub salt: Vec<u8>,
+    #[serde_as(as = "Base64")]
+    pub nonce: Vec<u8>,
+    #[serde_as(as = "Base64")]
+    pub ciphertext: Vec<u8>,
+}
+
+/// The plaintext of a [`WalletBackup`]. Apart from the [`BackupContents`], this contains the registration data of the
+/// wallet the backup was made with, which serves as proof of ownership of that account when restoring the backup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct BackupPayload {
+    pub registration: RegistrationData,
+    pub contents: BackupContents,
+}
+
+/// Generates a new random recovery secret, which should be presented to the user to write down.
+pub fn generate_recovery_secret() -> String {
+    random_string(RECOVERY_SECRET_LENGTH)
+}
+
+fn backup_key(recovery_secret: &str, salt: &[u8]) -> LessSafeKey {
+    let mut key_bytes = [0u8; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        PBKDF2_ITERATIONS,
+        salt,
+        recovery_secret.as_bytes(),
+        &mut key_bytes,
+    );
+
+    // This can only fail if the key length does not match the algorithm.
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key_bytes).unwrap())
+}
+
+impl WalletBackup {
+    pub(super) fn encrypt(payload: &BackupPayload, recovery_secret: &str) -> Result<Self, BackupError> {
+        if recovery_secret.chars().count() < RECOVERY_SECRET_MIN_LENGTH {
+            return Err(BackupError::InvalidRecoverySecret);
+        }
+
+        let salt = random_bytes(BACKUP_SALT_LENGTH);
+        let nonce = random_bytes(NONCE_LEN);
+        let key = backup_key(recovery_secret, &salt);
+
+        let mut ciphertext = cbor_serialize(payload)?;
+        key.seal_in_place_append_tag(
+            Nonce::try_assume_unique_for_key(&nonce).map_err(|_| BackupError::Encryption)?,
+            Aad::from(BACKUP_VERSION.to_be_bytes()),
+            &mut ciphertext,
+        )
+        .map_err(|_| BackupError::Encryption)?;
+
+        let backup = WalletBackup {
+            version: BACKUP_VERSION,
+            salt,
+            nonce,
+            ciphertext,
+        };
+
+        Ok(backup)
+    }
+
+    pub(super) fn decrypt(&self, recovery_secret: &str) -> Result<BackupPayload, BackupError> {
+        if self.version != BACKUP_VERSION {
+            return Err(BackupError::UnsupportedVersion(self.version));
+        }
+
+        let key = backup_key(recovery_secret, &self.salt);
+
+        let mut plaintext = self.ciphertext.clone();
+        let payload = key
+            .open_in_place(
+                Nonce::try_assume_unique_for_key(&self.nonce).map_err(|_| BackupError::Decryption)?,
+                Aad::from(self.version.to_be_bytes()),
+                &mut plaintext,
+            )
+            .map_err(|_| BackupError::Decryption)?;
+
+        let payload = cbor_deserialize(&*payload)?;
+
+        Ok(payload)
+    }
+}
+
+/// Imports the contents of the backup that was used to register the wallet, if this did not complete previously.
+pub(super) async fn complete_restore(storage: &mut impl Storage) -> StorageResult<()> {
+    if let Some(restore_data) = storage.fetch_data::<RestoreData>().await? {
+        info!("Importing contents of restored backup");
+
+        storage.import_backup(restore_data.contents()?).await?;
+        storage.delete_data::<RestoreData>().await?;
+    }
+
+    Ok(())
+}
+
+impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS> {
+    /// Creates an encrypted backup of the cards and history in the wallet, using the provided recovery secret.
+    /// This backup can be restored on another device by using [`Wallet::register_with_backup`].
+    #[instrument(skip_all)]
+    pub async fn create_backup(&self, recovery_secret: &str) -> Result<WalletBackup, BackupError>
+    where
+        S: Storage,
+    {
+        info!("Creating backup");
+
+        info!("Checking if registered");
+        let registration = self.registration.as_ref().ok_or(BackupError::NotRegistered)?;
+
+        info!("Checking if locked");
+        if self.lock.is_locked() {
+            return Err(BackupError::Locked);
+        }
+
+        info!("Retrieving backup contents from storage");
+        let contents = self.storage.read().await.export_backup().await?;
+
+        let payload = BackupPayload {
+            registration: registration.data.clone(),
+            contents,
+        };
+
+        info!("Encrypting backup");
+        WalletBackup::encrypt(&payload, recovery_secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use nl_wallet_mdoc::holder::Mdoc;
+
+    use crate::storage::WalletEvent;
+
+    use super::{super::test::WalletWithMocks, *};
+
+    const RECOVERY_SECRET: &str = "0123456789abcdefghijklmn";
+
+    #[tokio::test]
+    async fn test_create_backup() {
+        let wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let mdoc = Mdoc::new_example_mock();
+        let event = WalletEvent::new_issuance(Default::default());
+        {
+            let mut storage = wallet.storage.write().await;
+            storage.insert_mdocs(vec![vec![mdoc.clone()].into()]).await.unwrap();
+            storage.log_wallet_event(event.clone()).await.unwrap();
+        }
+
+        let backup = wallet
+            .create_backup(RECOVERY_SECRET)
+            .await
+            .expect("Could not create backup");
+
+        assert_eq!(backup.version, BACKUP_VERSION);
+
+        // The backup can only be decrypted with the recovery secret.
+        assert_matches!(
+            backup.decrypt("some_other_recovery_secret").unwrap_err(),
+            BackupError::Decryption
+        );
+
+        let payload = backup.decrypt(RECOVERY_SECRET).expect("Could not decrypt backup");
+
+        let registration = &wallet.registration.as_ref().unwrap().data;
+        assert_eq!(payload.registration.pin_salt, registration.pin_salt);
+        assert_eq!(
+            payload.registration.wallet_certificate.0,
+            registration.wallet_certificate.0
+        );
+        assert_eq!(payload.contents.mdocs.len(), 1);
+        let copies = &payload.contents.mdocs[0].copies;
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].mdoc, mdoc);
+        assert_eq!(copies[0].disclosure_count, 0);
+        assert_eq!(payload.contents.events, vec![event]);
+    }
+
+    #[tokio::test]
+    async fn test_create_backup_error_recovery_secret() {
+        let wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let error = wallet
+            .create_backup("too_short")
+            .await
+            .expect_err("Creating backup should have resulted in error");
+
+        assert_matches!(error, BackupError::InvalidRecoverySecret);
+    }
+
+    #[tokio::test]
+    async fn test_create_backup_error_not_registered() {
+        let wallet = WalletWithMocks::new_unregistered().await;
+
+        let error = wallet
+            .create_backup(RECOVERY_SECRET)
+            .await
+            .expect_err("Creating backup should have resulted in error");
+
+        assert_matches!(error, BackupError::NotRegistered);
+    }
+
+    #[tokio::test]
+    async fn test_create_backup_error_locked() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        wallet.lock.lock();
+
+        let error = wallet
+            .create_backup(RECOVERY_SECRET)
+            .await
+            .expect_err("Creating backup should have resulted in error");
+
+        assert_matches!(error, BackupError::Locked);
+    }
+
+    #[test]
+    fn test_wallet_backup_unsupported_version() {
+        let payload = BackupPayload {
+            registration: RegistrationData {
+                pin_salt: vec![1, 2, 3],
+                wallet_certificate: "thisisjwt".to_string().into(),
+            },
+            contents: Default::default(),
+        };
+
+        let mut backup = WalletBackup::encrypt(&payload, RECOVERY_SECRET).unwrap();
+        backup.version += 1;
+
+        assert_matches!(
+            backup.decrypt(RECOVERY_SECRET).unwrap_err(),
+            BackupError::UnsupportedVersion(version) if version == BACKUP_VERSION + 1
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use p256::{ecdsa::SigningKey, elliptic_curve::rand_core::OsRng};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+use nl_wallet_mdoc::{
+    identifiers::AttributeIdentifier,
+    utils::{
+        reader_auth::ReaderRegistration,
+        x509::{CertificateError, MdocCertificateExtension},
+    },
+};
+use openid4vc::deletion_request::{self, DeletionRequest};
+use wallet_common::keys::SecureEcdsaKey;
+
+use crate::{
+    disclosure::{MdocDisclosureError, MdocDisclosureSession},
+    storage::{EventDocuments, EventStatus, Storage, StorageError, WalletEvent},
+};
+
+use super::{history::EventStorageError, Wallet};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeletionError {
+    #[error("wallet is not registered")]
+    NotRegistered,
+    #[error("wallet is locked")]
+    Locked,
+    #[error("could not retrieve event from history database: {0}")]
+    EventRetrieval(#[source] StorageError),
+    #[error("disclosure event not found: {0}")]
+    EventNotFound(Uuid),
+    #[error("no attributes were shared in disclosure event: {0}")]
+    NoAttributesShared(Uuid),
+    #[error("disclosure event does not contain a reference to the session: {0}")]
+    NoSessionReference(Uuid),
+    #[error("disclosure event does not contain a deletion token: {0}")]
+    NoDeletionToken(Uuid),
+    #[error("could not read reader registration from certificate: {0}")]
+    Certificate(#[from] CertificateError),
+    #[error("certificate does not contain reader registration")]
+    NoReaderRegistrationFound,
+    #[error("relying party does not accept deletion requests")]
+    DeletionNotSupported,
+    #[error("could not create deletion request: {0}")]
+    DeletionRequest(#[from] deletion_request::DeletionRequestError),
+    #[error("could not send deletion request to relying party: {0}")]
+    Send(#[source] MdocDisclosureError),
+    #[error("could not store event in history database: {0}")]
+    EventStorage(#[source] EventStorageError),
+}
+
+impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS>
+where
+    S: Storage,
+    PEK: SecureEcdsaKey,
+    MDS: MdocDisclosureSession<Self>,
+{
+    /// Requests the relying party to delete the attributes that were shared with it in a successful disclosure
+    /// event, by sending a deletion request to the endpoint advertised in its reader registration. The request,
+    /// along with its outcome, is logged in the history.
+    #[instrument(skip_all)]
+    pub async fn request_deletion(&mut self, disclosure_event_id: Uuid) -> Result<(), DeletionError> {
+        info!("Requesting deletion of disclosed attributes");
+
+        info!("Checking if registered");
+        if self.registration.is_none() {
+            return Err(DeletionError::NotRegistered);
+        }
+
+        info!("Checking if locked");
+        if self.lock.is_locked() {
+            return Err(DeletionError::Locked);
+        }
+
+        info!("Retrieving disclosure event from storage");
+        let event = self
+            .storage
+            .read()
+            .await
+            .fetch_wallet_event(disclosure_event_id)
+            .await
+            .map_err(DeletionError::EventRetrieval)?;
+
+        let (documents, reader_certificate, session_reference, deletion_token) = match event {
+            Some(WalletEvent::Disclosure {
+                documents,
+                reader_certificate,
+                status: EventStatus::Success,
+                session_reference,
+                deletion_token,
+                ..
+            }) => (documents, reader_certificate, session_reference, deletion_token),
+            _ => return Err(DeletionError::EventNotFound(disclosure_event_id)),
+        };
+
+        let attributes = documents
+            .map(disclosed_attribute_identifiers)
+            .filter(|attributes| !attributes.is_empty())
+            .ok_or(DeletionError::NoAttributesShared(disclosure_event_id))?;
+        let session_reference = session_reference.ok_or(DeletionError::NoSessionReference(disclosure_event_id))?;
+        let deletion_token = deletion_token.ok_or(DeletionError::NoDeletionToken(disclosure_event_id))?;
+
+        info!("Checking if relying party accepts deletion requests");
+        let reader_registration = ReaderRegistration::from_certificate(&reader_certificate)?
+            .ok_or(DeletionError::NoReaderRegistrationFound)?;
+        let deletion_policy = reader_registration.deletion_policy;
+        let deletion_request_url = deletion_policy
+            .deleteable
+            .then_some(deletion_policy.deletion_request_url)
+            .flatten()
+            .ok_or(DeletionError::DeletionNotSupported)?;
+        let client_id = reader_certificate
+            .san_dns_name()?
+            .ok_or(DeletionError::DeletionNotSupported)?;
+
+        // Sign the deletion request with a fresh key, so that the relying party cannot link it to other requests
+        // from this wallet. The deletion token proves that the request comes from the wallet that disclosed.
+        info!("Signing deletion request");
+        let deletion_request = DeletionRequest::new(
+            &SigningKey::random(&mut OsRng),
+            client_id,
+            session_reference,
+            deletion_token,
+            attributes.clone(),
+        )
+        .await?;
+
+        info!("Sending deletion request to relying party");
+        let result = MDS::send_deletion_request(deletion_request_url, deletion_request).await;
+
+        let status = match result {
+            Ok(_) => EventStatus::Success,
+            Err(_) => EventStatus::Error,
+        };
+        let event = WalletEvent::new_deletion_request(disclosure_event_id, attributes, reader_certificate, status);
+
+        if let Err(error) = self.store_history_event(event).await {
+            // Only return the storage error if sending the request succeeded, so the original error is not hidden.
+            if result.is_ok() {
+                return Err(DeletionError::EventStorage(error));
+            }
+            error!("Could not store deletion request error in history: {error}");
+        }
+
+        result.map_err(DeletionError::Send)
+    }
+}
+
+fn disclosed_attribute_identifiers(EventDocuments(documents): EventDocuments) -> Vec<AttributeIdentifier> {
+    documents
+        .into_iter()
+        .flat_map(|(doc_type, event_attributes)| {
+            event_attributes
+                .attributes
+                .into_iter()
+                .flat_map(move |(namespace, attributes)| {
+                    let doc_type = doc_type.clone();
+                    attributes.into_keys().map(move |attribute| AttributeIdentifier {
+                        doc_type: doc_type.clone(),
+                        namespace: namespace.clone(),
+                        attribute,
+                    })
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Utc;
+    use serial_test::serial;
+
+    use nl_wallet_mdoc::{server_keys::KeyPair, utils::reader_auth::DeletionPolicy};
+    use openid4vc::disclosure_session::{VpClientError, VpMessageClientError};
+    use wallet_common::keys::EcdsaKey;
+
+    use crate::disclosure::MockMdocDisclosureSession;
+
+    use super::{
+        super::test::{WalletWithMocks, ISSUER_KEY},
+        *,
+    };
+
+    const PID_DOCTYPE: &str = "com.example.pid";
+
+    fn reader_key(deletion_policy: DeletionPolicy) -> KeyPair {
+        let reader_registration = ReaderRegistration {
+            deletion_policy,
+            ..ReaderRegistration::new_mock()
+        };
+
+        KeyPair::generate_reader_mock_ca()
+            .unwrap()
+            .generate_reader_mock(reader_registration.into())
+            .unwrap()
+    }
+
+    async fn wallet_with_disclosure_event(reader_key: &KeyPair) -> (WalletWithMocks, Uuid) {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let event = WalletEvent::disclosure_from_str(
+            vec![PID_DOCTYPE],
+            Utc::now(),
+            reader_key.certificate().clone(),
+            ISSUER_KEY.issuance_key.certificate(),
+        );
+        let event_id = *event.id();
+        wallet.storage.get_mut().log_wallet_event(event).await.unwrap();
+
+        (wallet, event_id)
+    }
+
+    #[tokio::test]
+    #[serial(MockMdocDisclosureSession)]
+    async fn test_wallet_request_deletion() {
+        let reader_key = reader_key(ReaderRegistration::new_mock().deletion_policy);
+        let (mut wallet, event_id) = wallet_with_disclosure_event(&reader_key).await;
+
+        wallet
+            .request_deletion(event_id)
+            .await
+            .expect("requesting deletion should succeed");
+
+        // The deletion request should be sent to the URL in the reader registration.
+        let sent_requests = MockMdocDisclosureSession::take_sent_deletion_requests();
+        assert_eq!(sent_requests.len(), 1);
+
+        let (url, deletion_request) = sent_requests.into_iter().next().unwrap();
+        assert_eq!(
+            Some(url),
+            ReaderRegistration::new_mock().deletion_policy.deletion_request_url
+        );
+
+        // The deletion request should not be signed by the hardware key, so that it cannot be linked to the wallet,
+        // and contain the deletion token and the disclosed attributes.
+        let (payload, verifying_key) = deletion_request.verify().expect("deletion request should be valid");
+        let hw_pubkey = wallet
+            .registration
+            .as_ref()
+            .unwrap()
+            .hw_privkey
+            .verifying_key()
+            .await
+            .unwrap();
+        assert_ne!(verifying_key, hw_pubkey);
+        assert_eq!(
+            payload.client_id,
+            reader_key.certificate().san_dns_name().unwrap().unwrap()
+        );
+        assert!(!payload.attributes.is_empty());
+        assert!(payload
+            .attributes
+            .iter()
+            .all(|attribute| attribute.doc_type == PID_DOCTYPE));
+
+        // A successful deletion request event should be logged.
+        let events = wallet.storage.get_mut().fetch_wallet_events().await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_matches!(
+            &events[0],
+            WalletEvent::DeletionRequest {
+                disclosure_id,
+                attributes,
+                status: EventStatus::Success,
+                ..
+            } if *disclosure_id == event_id && *attributes == payload.attributes
+        );
+        assert_matches!(
+            &events[1],
+            WalletEvent::Disclosure {
+                deletion_token: Some(deletion_token),
+                ..
+            } if *deletion_token == payload.deletion_token
+        );
+    }
+
+    #[tokio::test]
+    #[serial(MockMdocDisclosureSession)]
+    async fn test_wallet_request_deletion_error_send() {
+        let reader_key = reader_key(ReaderRegistration::new_mock().deletion_policy);
+        let (mut wallet, event_id) = wallet_with_disclosure_event(&reader_key).await;
+
+        MockMdocDisclosureSession::next_deletion_request_error(VpClientError::Request(VpMessageClientError::Json(
+            serde_json::from_str::<()>("").unwrap_err(),
+        )));
+
+        let error = wallet
+            .request_deletion(event_id)
+            .await
+            .expect_err("requesting deletion should not succeed");
+
+        assert_matches!(error, DeletionError::Send(_));
+
+        // The failed deletion request should be logged as an error event.
+        let events = wallet.storage.get_mut().fetch_wallet_events().await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_matches!(
+            &events[0],
+            WalletEvent::DeletionRequest {
+                status: EventStatus::Error,
+                ..
+            }
+        );
+    }
+
+    #[tokio::test]
+    #[serial(MockMdocDisclosureSession)]
+    async fn test_wallet_request_deletion_error_not_supported() {
+        let reader_key = reader_key(DeletionPolicy {
+            deleteable: false,
+            deletion_request_url: None,
+        });
+        let (mut wallet, event_id) = wallet_with_disclosure_event(&reader_key).await;
+
+        let error = wallet
+            .request_deletion(event_id)
+            .await
+            .expect_err("requesting deletion should not succeed");
+
+        assert_matches!(error, DeletionError::DeletionNotSupported);
+        assert!(MockMdocDisclosureSession::take_sent_deletion_requests().is_empty());
+
+        // Nothing should be logged, as no request was made.
+        let events = wallet.storage.get_mut().fetch_wallet_events().await.unwrap();
+        assert_eq!(events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_request_deletion_error_event_not_found() {
+        let reader_key = reader_key(ReaderRegistration::new_mock().deletion_policy);
+        let (mut wallet, _) = wallet_with_disclosure_event(&reader_key).await;
+
+        let event_id = Uuid::new_v4();
+        let error = wallet
+            .request_deletion(event_id)
+            .await
+            .expect_err("requesting deletion should not succeed");
+
+        assert_matches!(error, DeletionError::EventNotFound(id) if id == event_id);
+    }
+
+    #[tokio::test]
+    #[serial(MockMdocDisclosureSession)]
+    async fn test_wallet_request_deletion_error_no_deletion_token() {
+        let reader_key = reader_key(ReaderRegistration::new_mock().deletion_policy);
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        // Without the deletion token from the verifier, a deletion request would be rejected.
+        let mut event = WalletEvent::disclosure_from_str(
+            vec![PID_DOCTYPE],
+            Utc::now(),
+            reader_key.certificate().clone(),
+            ISSUER_KEY.issuance_key.certificate(),
+        );
+        if let WalletEvent::Disclosure { deletion_token, .. } = &mut event {
+            *deletion_token = None;
+        }
+        let event_id = *event.id();
+        wallet.storage.get_mut().log_wallet_event(event).await.unwrap();
+
+        let error = wallet
+            .request_deletion(event_id)
+            .await
+            .expect_err("requesting deletion should not succeed");
+
+        assert_matches!(error, DeletionError::NoDeletionToken(id) if id == event_id);
+        assert!(MockMdocDisclosureSession::take_sent_deletion_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wallet_request_deletion_error_locked() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        wallet.lock();
+
+        let error = wallet
+            .request_deletion(Uuid::new_v4())
+            .await
+            .expect_err("requesting deletion should not succeed");
+
+        assert_matches!(error, DeletionError::Locked);
+    }
+}
@@ -0,0 +1,600 @@
+use std::error::Error;
+
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_with::{base64::Base64, serde_as};
+use tracing::{info, instrument};
+
+use platform_support::hw_keystore::{AttestedEcdsaKey, PlatformEcdsaKey};
+use wallet_common::{
+    account::messages::{
+        auth::{DeviceLink, WalletCertificate},
+        instructions::{GetDevices, InstructionEndpoint, LinkedDevice, RemoveDevice, StartDeviceLink},
+    },
+    jwt::JwtError,
+};
+
+use crate::{
+    account_provider::AccountProviderClient,
+    config::ConfigurationRepository,
+    instruction::{InstructionClient, InstructionError},
+    pin::key::PinKey,
+    storage::{RegistrationData, Storage, StorageError, StorageState},
+};
+
+use super::{Wallet, WalletRegistration};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeviceLinkError {
+    #[error("wallet is not registered")]
+    NotRegistered,
+    #[error("wallet is locked")]
+    Locked,
+    #[error("wallet is already registered")]
+    AlreadyRegistered,
+    #[error("device link code could not be decoded")]
+    InvalidLinkCode,
+    #[error("error sending instruction to Wallet Provider: {0}")]
+    Instruction(#[from] InstructionError),
+    #[error("could not attest hardware key: {0}")]
+    HardwareAttestation(#[source] Box<dyn Error + Send + Sync>),
+    #[error("could not get hardware public key: {0}")]
+    HardwarePublicKey(#[source] Box<dyn Error + Send + Sync>),
+    #[error("could not sign device link message: {0}")]
+    Signing(#[source] wallet_common::account::errors::Error),
+    #[error("could not validate wallet certificate received from Wallet Provider: {0}")]
+    CertificateValidation(#[source] JwtError),
+    #[error("public key in wallet certificate received from Wallet Provider does not match hardware public key")]
+    PublicKeyMismatch,
+    #[error("could not store wallet certificate in database: {0}")]
+    StoreCertificate(#[from] StorageError),
+}
+
+/// The contents of the QR code that a registered device shows in order to link a new device to its wallet account.
+/// Next to the device link challenge, this contains the wallet certificate of the registered device, which the Wallet
+/// Provider uses to look up the account, and the PIN salt, so that the new device derives the same PIN key from the PIN.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+struct DeviceLinkCode {
+    #[serde_as(as = "Base64")]
+    challenge: Vec<u8>,
+    certificate: WalletCertificate,
+    #[serde_as(as = "Base64")]
+    pin_salt: Vec<u8>,
+}
+
+impl DeviceLinkCode {
+    fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("device link code should serialize");
+
+        BASE64_URL_SAFE_NO_PAD.encode(json)
+    }
+
+    fn decode(code: &str) -> Result<Self, DeviceLinkError> {
+        let json = BASE64_URL_SAFE_NO_PAD
+            .decode(code)
+            .map_err(|_| DeviceLinkError::InvalidLinkCode)?;
+
+        serde_json::from_slice(&json).map_err(|_| DeviceLinkError::InvalidLinkCode)
+    }
+}
+
+impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS>
+where
+    CR: ConfigurationRepository,
+    S: Storage,
+    PEK: PlatformEcdsaKey,
+    APC: AccountProviderClient,
+{
+    async fn send_device_instruction<I>(&self, pin: String, instruction: I) -> Result<I::Result, DeviceLinkError>
+    where
+        I: InstructionEndpoint + 'static,
+    {
+        info!("Checking if registered");
+        let registration = self.registration.as_ref().ok_or(DeviceLinkError::NotRegistered)?;
+
+        info!("Checking if locked");
+        if self.lock.is_locked() {
+            return Err(DeviceLinkError::Locked);
+        }
+
+        let config = self.config_repository.config();
+        let instruction_result_public_key = config.account_server.instruction_result_public_key.clone().into();
+
+        let remote_instruction = InstructionClient::new(
+            pin,
+            &self.storage,
+            &registration.hw_privkey,
+            &self.account_provider_client,
+            &registration.data,
+            &config.account_server.base_url,
+            &instruction_result_public_key,
+        );
+
+        let result = remote_instruction.send(instruction).await?;
+
+        Ok(result)
+    }
+
+    /// Starts linking a new device to the wallet account, authorized by the PIN. The returned code should be shown as
+    /// a QR code, which is then scanned by the new device and passed to [`Wallet::link_device`].
+    #[instrument(skip_all)]
+    pub async fn start_device_link(&self, pin: String) -> Result<String, DeviceLinkError> {
+        info!("Starting device link");
+
+        let challenge = self.send_device_instruction(pin, StartDeviceLink).await?.challenge;
+
+        // The unwrap is safe, as the registration is checked when sending the instruction.
+        let registration_data = &self.registration.as_ref().unwrap().data;
+        let code = DeviceLinkCode {
+            challenge,
+            certificate: registration_data.wallet_certificate.clone(),
+            pin_salt: registration_data.pin_salt.clone(),
+        };
+
+        info!("Device link challenge received, returning device link code");
+
+        Ok(code.encode())
+    }
+
+    /// Links this unregistered wallet to the wallet account of another device, using the code shown by that device
+    /// and the PIN of the wallet account. On success, the wallet is registered and unlocked.
+    #[instrument(skip_all)]
+    pub async fn link_device(&mut self, pin: String, link_code: &str) -> Result<(), DeviceLinkError>
+    where
+        PEK: AttestedEcdsaKey,
+    {
+        info!("Checking if already registered");
+
+        if self.has_registration() {
+            return Err(DeviceLinkError::AlreadyRegistered);
+        }
+
+        info!("Decoding device link code");
+
+        let code = DeviceLinkCode::decode(link_code)?;
+
+        info!("Creating and attesting hardware private key");
+
+        // The key is attested before its public key is retrieved, as attesting replaces the key on Android.
+        let hw_privkey = Self::hw_privkey();
+        let platform_attestation = hw_privkey
+            .attest(&code.challenge)
+            .await
+            .map_err(|e| DeviceLinkError::HardwareAttestation(e.into()))?;
+        let hw_pubkey = hw_privkey
+            .verifying_key()
+            .await
+            .map_err(|e| DeviceLinkError::HardwarePublicKey(e.into()))?;
+
+        info!("Signing and sending device link message to account server");
+
+        // The message is signed with the PIN key of the wallet account, which is derived using the PIN salt of the
+        // device that started linking.
+        let pin_key = PinKey::new(&pin, &code.pin_salt);
+        let link_message = DeviceLink::new_signed(
+            code.certificate,
+            platform_attestation,
+            &hw_privkey,
+            &pin_key,
+            &code.challenge,
+        )
+        .await
+        .map_err(DeviceLinkError::Signing)?;
+
+        let config = &self.config_repository.config().account_server;
+        let base_url = config.base_url.clone();
+        let certificate_public_key = config.certificate_public_key.clone();
+
+        let wallet_certificate = self
+            .account_provider_client
+            .link_device(&base_url, link_message)
+            .await
+            .map_err(InstructionError::from)?;
+
+        info!("Certificate received from account server, verifying contents");
+
+        let cert_claims = wallet_certificate
+            .parse_and_verify_with_sub(&certificate_public_key.into())
+            .map_err(DeviceLinkError::CertificateValidation)?;
+        if cert_claims.hw_pubkey.0 != hw_pubkey {
+            return Err(DeviceLinkError::PublicKeyMismatch);
+        }
+
+        info!("Storing received registration");
+
+        let storage = self.storage.get_mut();
+        let storage_state = storage.state().await?;
+        if !matches!(storage_state, StorageState::Opened) {
+            storage.open().await?;
+        }
+
+        let data = RegistrationData {
+            pin_salt: code.pin_salt,
+            wallet_certificate,
+        };
+        storage.insert_data(&data).await?;
+
+        self.registration = WalletRegistration { hw_privkey, data }.into();
+
+        info!("Device linked successfully, unlocking wallet");
+        self.lock.unlock();
+
+        Ok(())
+    }
+
+    /// Returns all devices that are linked to the wallet account, including this one.
+    #[instrument(skip_all)]
+    pub async fn get_linked_devices(&self, pin: String) -> Result<Vec<LinkedDevice>, DeviceLinkError> {
+        info!("Retrieving linked devices");
+
+        let result = self.send_device_instruction(pin, GetDevices).await?;
+
+        Ok(result.devices)
+    }
+
+    /// Removes another device from the wallet account, e.g. because it was lost.
+    #[instrument(skip_all)]
+    pub async fn remove_linked_device(&self, pin: String, device_id: String) -> Result<(), DeviceLinkError> {
+        info!("Removing linked device");
+
+        self.send_device_instruction(pin, RemoveDevice { device_id }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Utc;
+    use p256::ecdsa::SigningKey;
+    use rand_core::OsRng;
+    use serde::de::DeserializeOwned;
+
+    use wallet_common::{
+        account::{
+            messages::{
+                auth::PlatformAttestation,
+                auth::WalletCertificateClaims,
+                errors::{AccountError, IncorrectPinData},
+                instructions::{
+                    GetDevicesResult, Instruction, InstructionResult, InstructionResultClaims, StartDeviceLinkResult,
+                },
+            },
+            signed::SequenceNumberComparison,
+        },
+        jwt::Jwt,
+        utils,
+    };
+
+    use crate::{
+        account_provider::{AccountProviderError, AccountProviderResponseError},
+        pin::key as pin_key,
+        storage::KeyedData,
+    };
+
+    use super::{
+        super::test::{WalletWithMocks, ACCOUNT_SERVER_KEYS},
+        *,
+    };
+
+    const PIN: &str = "051097";
+
+    async fn instruction_result<R>(result: R) -> InstructionResult<R>
+    where
+        R: Serialize + DeserializeOwned,
+    {
+        let claims = InstructionResultClaims {
+            result,
+            iss: "wallet_unit_test".to_string(),
+            iat: jsonwebtoken::get_current_timestamp(),
+        };
+
+        Jwt::sign_with_sub(&claims, &ACCOUNT_SERVER_KEYS.instruction_result_signing_key)
+            .await
+            .unwrap()
+    }
+
+    fn expect_instruction_challenge(wallet: &mut WalletWithMocks) -> Vec<u8> {
+        let challenge = utils::random_bytes(32);
+        let challenge_response = challenge.clone();
+
+        wallet
+            .account_provider_client
+            .expect_instruction_challenge()
+            .return_once(move |_, _| Ok(challenge_response));
+
+        challenge
+    }
+
+    async fn link_code(challenge: &[u8], pin_salt: &[u8]) -> String {
+        DeviceLinkCode {
+            challenge: challenge.to_vec(),
+            certificate: WalletWithMocks::valid_certificate().await,
+            pin_salt: pin_salt.to_vec(),
+        }
+        .encode()
+    }
+
+    #[tokio::test]
+    async fn test_start_device_link() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        expect_instruction_challenge(&mut wallet);
+
+        let link_challenge = utils::random_bytes(32);
+        let result = instruction_result(StartDeviceLinkResult {
+            challenge: link_challenge.clone(),
+        })
+        .await;
+        wallet
+            .account_provider_client
+            .expect_instruction()
+            .return_once(move |_, _: Instruction<StartDeviceLink>| Ok(result));
+
+        let code = wallet
+            .start_device_link(PIN.to_string())
+            .await
+            .expect("Could not start device link");
+
+        // The code should contain the challenge, along with the certificate and PIN salt of the wallet.
+        let code = DeviceLinkCode::decode(&code).expect("Could not decode device link code");
+        let registration_data = &wallet.registration.as_ref().unwrap().data;
+
+        assert_eq!(code.challenge, link_challenge);
+        assert_eq!(code.certificate.0, registration_data.wallet_certificate.0);
+        assert_eq!(code.pin_salt, registration_data.pin_salt);
+    }
+
+    #[tokio::test]
+    async fn test_start_device_link_error_locked() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+        wallet.lock();
+
+        let error = wallet
+            .start_device_link(PIN.to_string())
+            .await
+            .expect_err("Starting device link should have resulted in error");
+
+        assert_matches!(error, DeviceLinkError::Locked);
+    }
+
+    #[tokio::test]
+    async fn test_start_device_link_error_not_registered() {
+        let wallet = WalletWithMocks::new_unregistered().await;
+
+        let error = wallet
+            .start_device_link(PIN.to_string())
+            .await
+            .expect_err("Starting device link should have resulted in error");
+
+        assert_matches!(error, DeviceLinkError::NotRegistered);
+    }
+
+    #[tokio::test]
+    async fn test_link_device() {
+        let mut wallet = WalletWithMocks::new_unregistered().await;
+
+        let challenge = utils::random_bytes(32);
+        let pin_salt = pin_key::new_pin_salt();
+        let code = link_code(&challenge, &pin_salt).await;
+
+        // The new certificate is generated for the hardware key of this wallet.
+        let new_cert = WalletWithMocks::valid_certificate().await;
+        let new_cert_response = new_cert.clone();
+        let pin_pubkey = PinKey::new(PIN, &pin_salt).verifying_key().unwrap();
+
+        wallet
+            .account_provider_client
+            .expect_link_device()
+            .return_once(move |_, link_message| {
+                let link = link_message.dangerous_parse_unverified().unwrap();
+
+                // The message should be signed with the hardware key and the PIN key of the wallet account.
+                link_message
+                    .parse_and_verify(
+                        &challenge,
+                        SequenceNumberComparison::EqualTo(0),
+                        &link.payload.hw_pubkey.0,
+                        &pin_pubkey,
+                    )
+                    .expect("Could not verify device link message");
+
+                // The platform attestation should be bound to the challenge.
+                assert_matches!(
+                    link.payload.platform_attestation,
+                    PlatformAttestation::Android { certificate_chain } if certificate_chain == vec![utils::sha256(&challenge)]
+                );
+
+                Ok(new_cert_response)
+            });
+
+        wallet
+            .link_device(PIN.to_string(), &code)
+            .await
+            .expect("Could not link device");
+
+        assert!(wallet.has_registration());
+        assert!(!wallet.is_locked());
+
+        // The PIN salt of the wallet account and the new certificate should be both in memory and in storage.
+        let registration_data = &wallet.registration.as_ref().unwrap().data;
+        assert_eq!(registration_data.pin_salt, pin_salt);
+        assert_eq!(registration_data.wallet_certificate.0, new_cert.0);
+
+        let stored_registration_data: RegistrationData = serde_json::from_str(
+            wallet
+                .storage
+                .get_mut()
+                .data
+                .get(<RegistrationData as KeyedData>::KEY)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored_registration_data.pin_salt, pin_salt);
+        assert_eq!(stored_registration_data.wallet_certificate.0, new_cert.0);
+    }
+
+    #[tokio::test]
+    async fn test_link_device_error_already_registered() {
+        let code = link_code(&utils::random_bytes(32), &pin_key::new_pin_salt()).await;
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let error = wallet
+            .link_device(PIN.to_string(), &code)
+            .await
+            .expect_err("Linking device should have resulted in error");
+
+        assert_matches!(error, DeviceLinkError::AlreadyRegistered);
+    }
+
+    #[tokio::test]
+    async fn test_link_device_error_invalid_link_code() {
+        let mut wallet = WalletWithMocks::new_unregistered().await;
+
+        let error = wallet
+            .link_device(PIN.to_string(), "not a device link code")
+            .await
+            .expect_err("Linking device should have resulted in error");
+
+        assert_matches!(error, DeviceLinkError::InvalidLinkCode);
+        assert!(!wallet.has_registration());
+    }
+
+    #[tokio::test]
+    async fn test_link_device_error_incorrect_pin() {
+        let mut wallet = WalletWithMocks::new_unregistered().await;
+
+        let code = link_code(&utils::random_bytes(32), &pin_key::new_pin_salt()).await;
+
+        wallet.account_provider_client.expect_link_device().return_once(|_, _| {
+            Err(AccountProviderError::Response(AccountProviderResponseError::Account(
+                AccountError::IncorrectPin(IncorrectPinData {
+                    attempts_left_in_round: 3,
+                    is_final_round: false,
+                }),
+                None,
+            )))
+        });
+
+        let error = wallet
+            .link_device(PIN.to_string(), &code)
+            .await
+            .expect_err("Linking device should have resulted in error");
+
+        assert_matches!(
+            error,
+            DeviceLinkError::Instruction(InstructionError::IncorrectPin {
+                attempts_left_in_round: 3,
+                is_final_round: false
+            })
+        );
+        assert!(!wallet.has_registration());
+        assert!(wallet.is_locked());
+        assert!(wallet.storage.get_mut().data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_link_device_error_public_key_mismatch() {
+        let mut wallet = WalletWithMocks::new_unregistered().await;
+
+        let code = link_code(&utils::random_bytes(32), &pin_key::new_pin_salt()).await;
+
+        // Return a certificate for another hardware key.
+        let other_cert = Jwt::sign_with_sub(
+            &WalletCertificateClaims {
+                hw_pubkey: (*SigningKey::random(&mut OsRng).verifying_key()).into(),
+                ..WalletWithMocks::valid_certificate_claims().await
+            },
+            &ACCOUNT_SERVER_KEYS.certificate_signing_key,
+        )
+        .await
+        .unwrap();
+        wallet
+            .account_provider_client
+            .expect_link_device()
+            .return_once(|_, _| Ok(other_cert));
+
+        let error = wallet
+            .link_device(PIN.to_string(), &code)
+            .await
+            .expect_err("Linking device should have resulted in error");
+
+        assert_matches!(error, DeviceLinkError::PublicKeyMismatch);
+        assert!(!wallet.has_registration());
+    }
+
+    #[tokio::test]
+    async fn test_get_linked_devices() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        expect_instruction_challenge(&mut wallet);
+
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let result = instruction_result(GetDevicesResult {
+            devices: vec![LinkedDevice {
+                device_id: "device".to_string(),
+                hw_pubkey: hw_pubkey.into(),
+                linked_date_time: Utc::now(),
+                is_current: true,
+            }],
+        })
+        .await;
+        wallet
+            .account_provider_client
+            .expect_instruction()
+            .return_once(move |_, _: Instruction<GetDevices>| Ok(result));
+
+        let devices = wallet
+            .get_linked_devices(PIN.to_string())
+            .await
+            .expect("Could not get linked devices");
+
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].device_id, "device");
+        assert!(devices[0].is_current);
+    }
+
+    #[tokio::test]
+    async fn test_remove_linked_device() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        expect_instruction_challenge(&mut wallet);
+
+        let result = instruction_result(()).await;
+        wallet.account_provider_client.expect_instruction().return_once(
+            move |_, instruction: Instruction<RemoveDevice>| {
+                let remove_device = instruction.instruction.dangerous_parse_unverified().unwrap().payload;
+                assert_eq!(remove_device.device_id, "lost_device");
+
+                Ok(result)
+            },
+        );
+
+        wallet
+            .remove_linked_device(PIN.to_string(), "lost_device".to_string())
+            .await
+            .expect("Could not remove linked device");
+    }
+
+    #[tokio::test]
+    async fn test_remove_linked_device_error_device_not_linked() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        wallet
+            .account_provider_client
+            .expect_instruction_challenge()
+            .return_once(|_, _| {
+                Err(AccountProviderError::Response(AccountProviderResponseError::Account(
+                    AccountError::DeviceNotLinked,
+                    None,
+                )))
+            });
+
+        let error = wallet
+            .remove_linked_device(PIN.to_string(), "lost_device".to_string())
+            .await
+            .expect_err("Removing linked device should have resulted in error");
+
+        assert_matches!(error, DeviceLinkError::Instruction(InstructionError::DeviceNotLinked));
+    }
+}
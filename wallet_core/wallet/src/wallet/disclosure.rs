@@ -6,7 +6,8 @@ use url::Url;
 use uuid::Uuid;
 
 use nl_wallet_mdoc::{
-    holder::{MdocDataSource, ProposedAttributes, StoredMdoc},
+    holder::{MdocDataSource, ProposedAttributes, SelfAssertedAttributes, StoredMdoc},
+    identifiers::AttributeIdentifier,
     utils::{cose::CoseError, reader_auth::ReaderRegistration, x509::Certificate},
     verifier::SessionType,
 };
@@ -18,8 +19,8 @@ use crate::{
     account_provider::AccountProviderClient,
     config::{ConfigurationRepository, UNIVERSAL_LINK_BASE_URL},
     disclosure::{
-        DisclosureUriError, DisclosureUriSource, MdocDisclosureError, MdocDisclosureMissingAttributes,
-        MdocDisclosureProposal, MdocDisclosureSession, MdocDisclosureSessionState,
+        DisclosureResponse, DisclosureUriError, DisclosureUriSource, MdocDisclosureError,
+        MdocDisclosureMissingAttributes, MdocDisclosureProposal, MdocDisclosureSession, MdocDisclosureSessionState,
     },
     document::{DisclosureDocument, DisclosureType, DocumentMdocError, MissingDisclosureAttributes},
     instruction::{InstructionClient, InstructionError, RemoteEcdsaKeyError, RemoteEcdsaKeyFactory},
@@ -31,6 +32,12 @@ use super::{history::EventStorageError, Wallet};
 #[derive(Debug, Clone)]
 pub struct DisclosureProposal {
     pub documents: Vec<DisclosureDocument>,
+    /// All of the documents that match the request, per requested doc type. The `documents` above
+    /// are selected from these, which can be changed using [`Wallet::select_disclosure_candidates`].
+    pub candidates: Vec<Vec<DisclosureDocument>>,
+    /// The self-asserted attributes that the verifier requested, of which the values should be provided using
+    /// [`Wallet::set_disclosure_self_asserted_attributes`]. These are not signed by an issuer and thus unverified.
+    pub requested_self_asserted_attributes: Vec<AttributeIdentifier>,
     pub reader_registration: ReaderRegistration,
     pub shared_data_with_relying_party_before: bool,
     pub session_type: SessionType,
@@ -60,6 +67,10 @@ pub enum DisclosureError {
         shared_data_with_relying_party_before: bool,
         session_type: SessionType,
     },
+    #[error("invalid selection of disclosure candidates: {0}")]
+    CandidateSelection(#[source] nl_wallet_mdoc::Error),
+    #[error("invalid self-asserted attributes: {0}")]
+    SelfAssertedAttributes(#[source] nl_wallet_mdoc::Error),
     #[error("could not interpret (missing) mdoc attributes: {0}")]
     MdocAttributes(#[source] DocumentMdocError),
     #[error("error sending instruction to Wallet Provider: {0}")]
@@ -100,6 +111,13 @@ impl From<MdocDisclosureError> for DisclosureError {
     }
 }
 
+fn disclosure_documents(proposed_attributes: ProposedAttributes) -> Result<Vec<DisclosureDocument>, DocumentMdocError> {
+    proposed_attributes
+        .into_iter()
+        .map(|(doc_type, attributes)| DisclosureDocument::from_mdoc_attributes(&doc_type, attributes))
+        .collect()
+}
+
 impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS>
 where
     CR: ConfigurationRepository,
@@ -138,7 +156,14 @@ where
         .map_err(DisclosureError::DisclosureUri)?;
 
         // Start the disclosure session based on the `ReaderEngagement`.
-        let session = MDS::start(disclosure_uri, source, self, &config.rp_trust_anchors()).await?;
+        let session = MDS::start(
+            disclosure_uri,
+            source,
+            self,
+            &config.rp_trust_anchors(),
+            &config.rp_crls,
+        )
+        .await?;
 
         let shared_data_with_relying_party_before = self
             .storage
@@ -159,28 +184,29 @@ where
 
                 let missing_attributes = missing_attr_session.missing_attributes().to_vec();
                 let session_type = session.session_type();
-                let error = match MissingDisclosureAttributes::from_mdoc_missing_attributes(missing_attributes) {
-                    Ok(attributes) => {
-                        // If the missing attributes can be translated and shown to the user,
-                        // store the session so that it will only be terminated on user interaction.
-                        // This prevents gleaning of missing attributes by a verifier.
-                        let reader_registration = session.reader_registration().clone().into();
-                        self.disclosure_session.replace(session);
-
-                        DisclosureError::AttributesNotAvailable {
-                            reader_registration,
-                            missing_attributes: attributes,
-                            shared_data_with_relying_party_before,
-                            session_type,
+                let error =
+                    match MissingDisclosureAttributes::from_mdoc_candidates_missing_attributes(missing_attributes) {
+                        Ok(attributes) => {
+                            // If the missing attributes can be translated and shown to the user,
+                            // store the session so that it will only be terminated on user interaction.
+                            // This prevents gleaning of missing attributes by a verifier.
+                            let reader_registration = session.reader_registration().clone().into();
+                            self.disclosure_session.replace(session);
+
+                            DisclosureError::AttributesNotAvailable {
+                                reader_registration,
+                                missing_attributes: attributes,
+                                shared_data_with_relying_party_before,
+                                session_type,
+                            }
                         }
-                    }
-                    // NB: It is a known limitation that, if the missing attributes cannot be
-                    //     translated, the missing attributes cannot be presented to the user,
-                    //     thus user interaction cannot terminate the disclosure session. As
-                    //     a precaution to prevent gleaning of missing attributes we will
-                    //     simply never respond to the verifier in this case.
-                    Err(error) => DisclosureError::MdocAttributes(error),
-                };
+                        // NB: It is a known limitation that, if the missing attributes cannot be
+                        //     translated, the missing attributes cannot be presented to the user,
+                        //     thus user interaction cannot terminate the disclosure session. As
+                        //     a precaution to prevent gleaning of missing attributes we will
+                        //     simply never respond to the verifier in this case.
+                        Err(error) => DisclosureError::MdocAttributes(error),
+                    };
 
                 return Err(error);
             }
@@ -194,16 +220,26 @@ where
 
         let is_login_flow = DisclosureType::from_proposed_attributes(&proposed_attributes).is_login_flow();
 
-        // Prepare a `Vec<ProposedDisclosureDocument>` to report to the caller.
-        let documents: Vec<DisclosureDocument> = proposed_attributes
+        // Prepare a `Vec<ProposedDisclosureDocument>` to report to the caller, both
+        // for the selected documents and for all of the candidates per doc type.
+        let documents = disclosure_documents(proposed_attributes).map_err(DisclosureError::MdocAttributes)?;
+        let candidates = proposal_session
+            .candidate_attributes()
             .into_iter()
-            .map(|(doc_type, attributes)| DisclosureDocument::from_mdoc_attributes(&doc_type, attributes))
+            .map(|(doc_type, candidates)| {
+                candidates
+                    .into_iter()
+                    .map(|attributes| DisclosureDocument::from_mdoc_attributes(&doc_type, attributes))
+                    .collect::<Result<Vec<_>, _>>()
+            })
             .collect::<Result<_, _>>()
             .map_err(DisclosureError::MdocAttributes)?;
 
         // Place this in a `DisclosureProposal`, along with a copy of the `ReaderRegistration`.
         let proposal = DisclosureProposal {
             documents,
+            candidates,
+            requested_self_asserted_attributes: proposal_session.requested_self_asserted_attributes(),
             reader_registration: session.reader_registration().clone(),
             shared_data_with_relying_party_before,
             session_type: session.session_type(),
@@ -215,13 +251,84 @@ where
 
         Ok(proposal)
     }
+
+    /// Selects which of the candidate documents in [`DisclosureProposal::candidates`] should be disclosed,
+    /// by providing an index for every requested doc type. Returns the documents that are now selected.
+    #[instrument(skip_all)]
+    pub fn select_disclosure_candidates(
+        &mut self,
+        selection: &[usize],
+    ) -> Result<Vec<DisclosureDocument>, DisclosureError> {
+        info!("Selecting disclosure candidates");
+
+        info!("Checking if registered");
+        if self.registration.is_none() {
+            return Err(DisclosureError::NotRegistered);
+        }
+
+        info!("Checking if locked");
+        if self.lock.is_locked() {
+            return Err(DisclosureError::Locked);
+        }
+
+        info!("Checking if a disclosure session is present");
+        let session = self.disclosure_session.as_mut().ok_or(DisclosureError::SessionState)?;
+
+        let proposal_session = match session.session_state_mut() {
+            MdocDisclosureSessionState::Proposal(proposal_session) => proposal_session,
+            _ => return Err(DisclosureError::SessionState),
+        };
+
+        proposal_session
+            .select_candidates(selection)
+            .map_err(DisclosureError::CandidateSelection)?;
+
+        disclosure_documents(proposal_session.proposed_attributes()).map_err(DisclosureError::MdocAttributes)
+    }
+
+    /// Provides the values of the self-asserted attributes in
+    /// [`DisclosureProposal::requested_self_asserted_attributes`], which will be disclosed along with the selected
+    /// documents. Exactly the requested attributes should be provided.
+    #[instrument(skip_all)]
+    pub fn set_disclosure_self_asserted_attributes(
+        &mut self,
+        attributes: SelfAssertedAttributes,
+    ) -> Result<(), DisclosureError> {
+        info!("Setting self-asserted disclosure attributes");
+
+        info!("Checking if registered");
+        if self.registration.is_none() {
+            return Err(DisclosureError::NotRegistered);
+        }
+
+        info!("Checking if locked");
+        if self.lock.is_locked() {
+            return Err(DisclosureError::Locked);
+        }
+
+        info!("Checking if a disclosure session is present");
+        let session = self.disclosure_session.as_mut().ok_or(DisclosureError::SessionState)?;
+
+        let proposal_session = match session.session_state_mut() {
+            MdocDisclosureSessionState::Proposal(proposal_session) => proposal_session,
+            _ => return Err(DisclosureError::SessionState),
+        };
+
+        proposal_session
+            .set_self_asserted_attributes(attributes)
+            .map_err(DisclosureError::SelfAssertedAttributes)
+    }
+
     /// When we have missing attributes, we don't have a proposal -> empty proposed_attributes.
     /// When we do have a proposal, give us the proposed attributes then. In both cases, empty
     /// or "real", use from_proposed_attributes to determine the disclosure_type.
     async fn terminate_disclosure_session(&mut self, session: MDS) -> Result<(), DisclosureError> {
-        let proposed_attributes = match session.session_state() {
-            MdocDisclosureSessionState::MissingAttributes(_) => None,
-            MdocDisclosureSessionState::Proposal(proposal_session) => Some(proposal_session.proposed_attributes()),
+        let (proposed_attributes, session_reference) = match session.session_state() {
+            MdocDisclosureSessionState::MissingAttributes(_) => (None, None),
+            MdocDisclosureSessionState::Proposal(proposal_session) => (
+                Some(proposal_session.proposed_attributes()),
+                proposal_session.session_reference(),
+            ),
         };
 
         let disclosure_type = proposed_attributes
@@ -234,6 +341,8 @@ where
             session.rp_certificate().clone(),
             EventStatus::Cancelled,
             disclosure_type,
+            session_reference,
+            None,
         );
 
         session.terminate().await?;
@@ -289,6 +398,7 @@ where
         proposed_attributes: ProposedAttributes,
         data_shared: bool,
         remote_party_certificate: Certificate,
+        session_reference: Option<String>,
     ) -> Result<(), DisclosureError> {
         let disclosure_type = DisclosureType::from_proposed_attributes(&proposed_attributes);
         let event = WalletEvent::new_disclosure(
@@ -296,6 +406,8 @@ where
             remote_party_certificate,
             EventStatus::Error,
             disclosure_type,
+            session_reference,
+            None,
         );
         self.store_history_event(event)
             .await
@@ -350,6 +462,7 @@ where
                     session_proposal.proposed_attributes(),
                     false, // No data was shared yet
                     session.rp_certificate().clone(),
+                    session_proposal.session_reference(),
                 )
                 .await
             {
@@ -376,14 +489,18 @@ where
         // Actually perform disclosure, casting any `InstructionError` that
         // occur during signing to `RemoteEcdsaKeyError::Instruction`.
         let result = session_proposal.disclose(&&remote_key_factory).await;
-        let return_url = match result {
-            Ok(return_url) => return_url,
+        let DisclosureResponse {
+            return_url,
+            deletion_token,
+        } = match result {
+            Ok(response) => response,
             Err(error) => {
                 if let Err(e) = self
                     .log_disclosure_error(
                         session_proposal.proposed_attributes(),
                         error.data_shared,
                         session.rp_certificate().clone(),
+                        session_proposal.session_reference(),
                     )
                     .await
                 {
@@ -394,7 +511,9 @@ where
 
                 if matches!(
                     error,
-                    DisclosureError::Instruction(InstructionError::Timeout { .. } | InstructionError::Blocked)
+                    DisclosureError::Instruction(
+                        InstructionError::Timeout { .. } | InstructionError::Blocked | InstructionError::Revoked
+                    )
                 ) {
                     // On a PIN timeout we should proactively terminate the disclosure session,
                     // and lock the wallet, as the user is probably not the owner of the wallet.
@@ -423,6 +542,7 @@ where
         let proposed_attributes = session_proposal.proposed_attributes();
         let disclosure_type = DisclosureType::from_proposed_attributes(&proposed_attributes);
         let rp_certificate = session.rp_certificate().clone();
+        let session_reference = session_proposal.session_reference();
 
         self.disclosure_session.take();
 
@@ -432,6 +552,8 @@ where
             rp_certificate,
             EventStatus::Success,
             disclosure_type,
+            session_reference,
+            deletion_token,
         );
         self.store_history_event(event)
             .await
@@ -674,7 +796,7 @@ mod tests {
         let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
 
         // Set up an `MdocDisclosureSession` start to return that attributes are not available.
-        let missing_attributes = vec!["com.example.pid/com.example.pid/age_over_18".parse().unwrap()];
+        let missing_attributes = vec![vec!["com.example.pid/com.example.pid/age_over_18".parse().unwrap()]];
         let mut missing_attr_session = MockMdocDisclosureMissingAttributes::default();
         missing_attr_session
             .expect_missing_attributes()
@@ -711,7 +833,7 @@ mod tests {
         let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
 
         // Set up an `MdocDisclosureSession` start to return that attributes are not available.
-        let missing_attributes = vec!["com.example.pid/com.example.pid/foobar".parse().unwrap()];
+        let missing_attributes = vec![vec!["com.example.pid/com.example.pid/foobar".parse().unwrap()]];
         let mut missing_attr_session = MockMdocDisclosureMissingAttributes::default();
         missing_attr_session
             .expect_missing_attributes()
@@ -788,6 +910,208 @@ mod tests {
         assert!(wallet.disclosure_session.is_none());
     }
 
+    fn age_over_18_attributes(value: bool) -> ProposedDocumentAttributes {
+        ProposedDocumentAttributes {
+            attributes: IndexMap::from([(
+                "com.example.pid".to_string(),
+                vec![Entry {
+                    name: "age_over_18".to_string(),
+                    value: DataElementValue::Bool(value),
+                }],
+            )]),
+            issuer: ISSUER_KEY.issuance_key.certificate().clone(),
+        }
+    }
+
+    fn age_over_18_value(document: &DisclosureDocument) -> &AttributeValue {
+        &document.attributes.get("age_over_18").unwrap().value
+    }
+
+    #[tokio::test]
+    #[serial(MockMdocDisclosureSession)]
+    async fn test_wallet_select_disclosure_candidates() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        // Set up an `MdocDisclosureSession` with two candidates for the same doc type.
+        let proposal_session = MockMdocDisclosureProposal {
+            proposed_source_identifiers: vec![PROPOSED_ID],
+            proposed_attributes: IndexMap::from([("com.example.pid".to_string(), age_over_18_attributes(true))]),
+            candidate_attributes: IndexMap::from([(
+                "com.example.pid".to_string(),
+                vec![age_over_18_attributes(true), age_over_18_attributes(false)],
+            )]),
+            ..Default::default()
+        };
+
+        MockMdocDisclosureSession::next_fields(
+            ReaderRegistration::new_mock(),
+            MdocDisclosureSessionState::Proposal(proposal_session),
+        );
+
+        let proposal = wallet
+            .start_disclosure(&DISCLOSURE_URI, DisclosureUriSource::Link)
+            .await
+            .expect("Could not start disclosure");
+
+        // The proposal should contain both candidates, with the first one selected.
+        assert_eq!(proposal.candidates.len(), 1);
+        let candidates = proposal.candidates.first().unwrap();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(age_over_18_value(&candidates[0]), &AttributeValue::Boolean(true));
+        assert_eq!(age_over_18_value(&candidates[1]), &AttributeValue::Boolean(false));
+
+        assert_eq!(proposal.documents.len(), 1);
+        assert_eq!(
+            age_over_18_value(proposal.documents.first().unwrap()),
+            &AttributeValue::Boolean(true)
+        );
+
+        // Selecting the second candidate should return it as the document to be disclosed.
+        let documents = wallet
+            .select_disclosure_candidates(&[1])
+            .expect("Could not select disclosure candidates");
+
+        assert_eq!(documents.len(), 1);
+        assert_eq!(
+            age_over_18_value(documents.first().unwrap()),
+            &AttributeValue::Boolean(false)
+        );
+
+        // The selection should be retained in the disclosure session.
+        let proposal_session = match wallet.disclosure_session.as_ref().unwrap().session_state {
+            MdocDisclosureSessionState::Proposal(ref proposal_session) => proposal_session,
+            _ => panic!("Disclosure session should be a proposal"),
+        };
+        assert_eq!(
+            proposal_session.proposed_attributes,
+            IndexMap::from([("com.example.pid".to_string(), age_over_18_attributes(false))])
+        );
+    }
+
+    #[tokio::test]
+    #[serial(MockMdocDisclosureSession)]
+    async fn test_wallet_select_disclosure_candidates_error_invalid_selection() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let proposal_session = MockMdocDisclosureProposal {
+            proposed_attributes: IndexMap::from([("com.example.pid".to_string(), age_over_18_attributes(true))]),
+            ..Default::default()
+        };
+        wallet.disclosure_session = MockMdocDisclosureSession {
+            session_state: MdocDisclosureSessionState::Proposal(proposal_session),
+            ..Default::default()
+        }
+        .into();
+
+        // Selecting a candidate that does not exist should result in an error.
+        let error = wallet
+            .select_disclosure_candidates(&[1])
+            .expect_err("Selecting disclosure candidates should have resulted in an error");
+
+        assert_matches!(
+            error,
+            DisclosureError::CandidateSelection(nl_wallet_mdoc::Error::Holder(
+                HolderError::InvalidCandidateSelection(selection)
+            )) if selection == [1]
+        );
+        assert!(wallet.disclosure_session.is_some());
+    }
+
+    #[tokio::test]
+    #[serial(MockMdocDisclosureSession)]
+    async fn test_wallet_set_disclosure_self_asserted_attributes() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let requested_attribute = AttributeIdentifier {
+            doc_type: "com.example.pid".to_string(),
+            namespace: "com.example.self_asserted".to_string(),
+            attribute: "email".to_string(),
+        };
+        let proposal_session = MockMdocDisclosureProposal {
+            proposed_attributes: IndexMap::from([("com.example.pid".to_string(), age_over_18_attributes(true))]),
+            requested_self_asserted_attributes: vec![requested_attribute.clone()],
+            ..Default::default()
+        };
+
+        MockMdocDisclosureSession::next_fields(
+            ReaderRegistration::new_mock(),
+            MdocDisclosureSessionState::Proposal(proposal_session),
+        );
+
+        let proposal = wallet
+            .start_disclosure(&DISCLOSURE_URI, DisclosureUriSource::Link)
+            .await
+            .expect("Could not start disclosure");
+
+        // The proposal should list the requested self-asserted attribute.
+        assert_eq!(proposal.requested_self_asserted_attributes, [requested_attribute]);
+
+        let self_asserted_attributes = IndexMap::from([(
+            "com.example.pid".to_string(),
+            IndexMap::from([(
+                "com.example.self_asserted".to_string(),
+                vec![Entry {
+                    name: "email".to_string(),
+                    value: DataElementValue::Text("jane@example.com".to_string()),
+                }],
+            )]),
+        )]);
+
+        wallet
+            .set_disclosure_self_asserted_attributes(self_asserted_attributes.clone())
+            .expect("Could not set self-asserted attributes");
+
+        // The values should be retained in the disclosure session.
+        let proposal_session = match wallet.disclosure_session.as_ref().unwrap().session_state {
+            MdocDisclosureSessionState::Proposal(ref proposal_session) => proposal_session,
+            _ => panic!("Disclosure session should be a proposal"),
+        };
+        assert_eq!(proposal_session.self_asserted_attributes, self_asserted_attributes);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_select_disclosure_candidates_error_session_state() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        // Selecting candidates without a disclosure session should result in an error.
+        let error = wallet
+            .select_disclosure_candidates(&[0])
+            .expect_err("Selecting disclosure candidates should have resulted in an error");
+
+        assert_matches!(error, DisclosureError::SessionState);
+
+        // Selecting candidates for a session with missing attributes should also result in an error.
+        wallet.disclosure_session =
+            MockMdocDisclosureSession {
+                session_state: MdocDisclosureSessionState::MissingAttributes(
+                    MockMdocDisclosureMissingAttributes::default(),
+                ),
+                ..Default::default()
+            }
+            .into();
+
+        let error = wallet
+            .select_disclosure_candidates(&[0])
+            .expect_err("Selecting disclosure candidates should have resulted in an error");
+
+        assert_matches!(error, DisclosureError::SessionState);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_select_disclosure_candidates_error_locked() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        wallet.disclosure_session = MockMdocDisclosureSession::default().into();
+
+        wallet.lock();
+
+        let error = wallet
+            .select_disclosure_candidates(&[0])
+            .expect_err("Selecting disclosure candidates should have resulted in an error");
+
+        assert_matches!(error, DisclosureError::Locked);
+    }
+
     #[tokio::test]
     #[serial(MockMdocDisclosureSession)]
     async fn test_wallet_cancel_disclosure() {
@@ -869,10 +1193,10 @@ mod tests {
         let events = test::setup_mock_recent_history_callback(&mut wallet).await.unwrap();
 
         // Set up an `MdocDisclosureSession` start to return that attributes are not available.
-        let missing_attributes = vec![
+        let missing_attributes = vec![vec![
             "com.example.pid/com.example.pid/bsn".parse().unwrap(),
             "com.example.pid/com.example.pid/age_over_18".parse().unwrap(),
-        ];
+        ]];
         let mut missing_attr_session = MockMdocDisclosureMissingAttributes::default();
         missing_attr_session
             .expect_missing_attributes()
@@ -999,6 +1323,7 @@ mod tests {
             return_url: return_url.clone().into(),
             proposed_source_identifiers: vec![PROPOSED_ID],
             proposed_attributes,
+            deletion_token: Some("deletion_token".to_string()),
             ..Default::default()
         };
 
@@ -1045,6 +1370,16 @@ mod tests {
                 ..
             }
         );
+
+        // Verify that the deletion token is stored along with the event
+        let stored_events = wallet.storage.read().await.fetch_wallet_events().await.unwrap();
+        assert_matches!(
+            &stored_events[0],
+            WalletEvent::Disclosure {
+                deletion_token: Some(deletion_token),
+                ..
+            } if deletion_token == "deletion_token"
+        );
         // Verify that `did_share_data_with_relying_party()` now returns `true`
         assert!(wallet
             .storage
@@ -1287,6 +1622,8 @@ mod tests {
     #[case(InstructionError::IncorrectPin { attempts_left_in_round: 1, is_final_round: false }, false)]
     #[case(InstructionError::Timeout { timeout_millis: 10_000 }, true)]
     #[case(InstructionError::Blocked, true)]
+    #[case(InstructionError::Suspended, false)]
+    #[case(InstructionError::Revoked, true)]
     #[case(InstructionError::InstructionValidation, false)]
     #[tokio::test]
     async fn test_wallet_accept_disclosure_error_instruction(
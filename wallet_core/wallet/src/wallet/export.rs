@@ -0,0 +1,487 @@
+use std::fmt::{self, Display, Formatter};
+
+use base64::prelude::*;
+use chrono::{DateTime, SecondsFormat, Utc};
+use ciborium::Value;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use nl_wallet_mdoc::{
+    identifiers::AttributeIdentifier,
+    utils::{
+        auth::{LocalizedStrings, Organization},
+        issuer_auth::IssuerRegistration,
+        reader_auth::{DeletionPolicy, ReaderRegistration, RetentionPolicy, SharingPolicy},
+        x509::{Certificate, MdocCertificateExtension},
+    },
+    DataElementIdentifier, DocType, NameSpace,
+};
+use wallet_common::{
+    account::messages::auth::WalletCertificate,
+    jwt::{self, Jwt},
+    keys::SecureEcdsaKey,
+};
+
+use crate::{
+    document::DisclosureType,
+    storage::{EventAttributes, EventDocuments, EventStatus, Storage, WalletEvent, WalletEventType},
+};
+
+use super::{
+    history::{EventConversionError, HistoryError},
+    Wallet,
+};
+
+/// The language that is preferred when rendering [`LocalizedStrings`] in the human-readable report.
+const REPORT_LANGUAGE: &str = "en";
+
+/// A self-contained, machine-readable report of all the history events in the wallet. This lists every card that
+/// was received and all of the attributes that were shared with relying parties, including their policies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryReport {
+    pub created_at: DateTime<Utc>,
+    /// The certificate issued by the Wallet Provider, which binds the hardware key that signed this report to the
+    /// wallet.
+    pub wallet_certificate: WalletCertificate,
+    pub events: Vec<HistoryReportEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryReportEvent {
+    pub id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub r#type: WalletEventType,
+    pub status: EventStatus,
+    /// This is only present for disclosure, login and deletion request events.
+    pub relying_party: Option<HistoryReportRelyingParty>,
+    pub documents: Vec<HistoryReportDocument>,
+    /// The attributes of which deletion was requested, this is only present for deletion request events.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deletion_requested_attributes: Vec<AttributeIdentifier>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryReportRelyingParty {
+    pub organization: Organization,
+    pub purpose_statement: LocalizedStrings,
+    pub retention_policy: RetentionPolicy,
+    pub sharing_policy: SharingPolicy,
+    pub deletion_policy: DeletionPolicy,
+    pub certificate: Certificate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryReportDocument {
+    pub doc_type: DocType,
+    pub issuer: Organization,
+    pub issuer_certificate: Certificate,
+    pub attributes: IndexMap<NameSpace, IndexMap<DataElementIdentifier, serde_json::Value>>,
+}
+
+/// Contains the [`HistoryReport`], along with the same report signed as a JWT by the hardware key of the wallet.
+/// The JWT can be verified using the `hw_pubkey` contained in the wallet certificate.
+#[derive(Debug, Clone)]
+pub struct HistoryExport {
+    pub report: HistoryReport,
+    pub signed_report: Jwt<HistoryReport>,
+}
+
+impl HistoryExport {
+    /// Returns the machine-readable report as pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.report)
+    }
+
+    /// Returns the human-readable rendering of the report.
+    pub fn to_text(&self) -> String {
+        self.report.to_string()
+    }
+}
+
+impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS>
+where
+    S: Storage,
+    PEK: SecureEcdsaKey,
+{
+    /// Creates a report of all history events, signed with the hardware key of the wallet.
+    #[instrument(skip_all)]
+    pub async fn export_history(&self) -> Result<HistoryExport, HistoryError> {
+        info!("Exporting history");
+
+        info!("Checking if registered");
+        let registration = self.registration.as_ref().ok_or(HistoryError::NotRegistered)?;
+
+        info!("Checking if locked");
+        if self.lock.is_locked() {
+            return Err(HistoryError::Locked);
+        }
+
+        info!("Retrieving history from storage");
+        let events = self.storage.read().await.fetch_wallet_events().await?;
+
+        let report = HistoryReport {
+            created_at: Utc::now(),
+            wallet_certificate: registration.data.wallet_certificate.clone(),
+            events: events
+                .into_iter()
+                .map(HistoryReportEvent::try_from)
+                .collect::<Result<_, _>>()?,
+        };
+
+        info!("Signing history report");
+        let signed_report = Jwt::sign(&report, &jwt::header(), &registration.hw_privkey)
+            .await
+            .map_err(HistoryError::ReportSigning)?;
+
+        Ok(HistoryExport { report, signed_report })
+    }
+}
+
+impl TryFrom<WalletEvent> for HistoryReportEvent {
+    type Error = EventConversionError;
+
+    fn try_from(source: WalletEvent) -> Result<Self, Self::Error> {
+        let result = match source {
+            WalletEvent::Issuance { id, mdocs, timestamp } => Self {
+                id,
+                timestamp,
+                r#type: WalletEventType::Issuance,
+                status: EventStatus::Success,
+                relying_party: None,
+                documents: report_documents(mdocs)?,
+                deletion_requested_attributes: Vec::new(),
+            },
+            WalletEvent::Disclosure {
+                id,
+                documents,
+                timestamp,
+                reader_certificate,
+                status,
+                r#type,
+                session_reference: _,
+                deletion_token: _,
+            } => Self {
+                id,
+                timestamp,
+                r#type: match r#type {
+                    DisclosureType::Login => WalletEventType::Login,
+                    DisclosureType::Regular => WalletEventType::Disclosure,
+                },
+                status,
+                relying_party: Some(report_relying_party(reader_certificate)?),
+                documents: documents.map(report_documents).transpose()?.unwrap_or_default(),
+                deletion_requested_attributes: Vec::new(),
+            },
+            WalletEvent::DeletionRequest {
+                id,
+                disclosure_id: _,
+                attributes,
+                timestamp,
+                reader_certificate,
+                status,
+            } => Self {
+                id,
+                timestamp,
+                r#type: WalletEventType::DeletionRequest,
+                status,
+                relying_party: Some(report_relying_party(reader_certificate)?),
+                documents: Vec::new(),
+                deletion_requested_attributes: attributes,
+            },
+        };
+
+        Ok(result)
+    }
+}
+
+fn report_relying_party(reader_certificate: Certificate) -> Result<HistoryReportRelyingParty, EventConversionError> {
+    let reader_registration = ReaderRegistration::from_certificate(&reader_certificate)?
+        .ok_or(EventConversionError::NoReaderRegistrationFound)?;
+
+    let relying_party = HistoryReportRelyingParty {
+        organization: reader_registration.organization,
+        purpose_statement: reader_registration.purpose_statement,
+        retention_policy: reader_registration.retention_policy,
+        sharing_policy: reader_registration.sharing_policy,
+        deletion_policy: reader_registration.deletion_policy,
+        certificate: reader_certificate,
+    };
+
+    Ok(relying_party)
+}
+
+fn report_documents(
+    EventDocuments(documents): EventDocuments,
+) -> Result<Vec<HistoryReportDocument>, EventConversionError> {
+    documents
+        .into_iter()
+        .map(|(doc_type, EventAttributes { issuer, attributes })| {
+            let issuer_registration = IssuerRegistration::from_certificate(&issuer)?
+                .ok_or(EventConversionError::NoIssuerRegistrationFound)?;
+
+            let attributes = attributes
+                .into_iter()
+                .map(|(namespace, attributes)| {
+                    let attributes = attributes
+                        .into_iter()
+                        .map(|(name, value)| (name, cbor_to_json(value)))
+                        .collect();
+                    (namespace, attributes)
+                })
+                .collect();
+
+            Ok(HistoryReportDocument {
+                doc_type,
+                issuer: issuer_registration.organization,
+                issuer_certificate: issuer,
+                attributes,
+            })
+        })
+        .collect()
+}
+
+/// Converts a CBOR value to its closest JSON equivalent. Byte strings are encoded using base64, tags are omitted
+/// and map keys that are not text are rendered in CBOR diagnostic notation.
+fn cbor_to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Integer(integer) => {
+            let integer = i128::from(integer);
+            i64::try_from(integer)
+                .map(serde_json::Value::from)
+                .or_else(|_| u64::try_from(integer).map(serde_json::Value::from))
+                .unwrap_or_else(|_| serde_json::Value::String(integer.to_string()))
+        }
+        Value::Bytes(bytes) => serde_json::Value::String(BASE64_STANDARD.encode(bytes)),
+        Value::Float(float) => serde_json::Value::from(float),
+        Value::Text(text) => serde_json::Value::String(text),
+        Value::Bool(bool) => serde_json::Value::Bool(bool),
+        Value::Tag(_, value) => cbor_to_json(*value),
+        Value::Array(values) => serde_json::Value::Array(values.into_iter().map(cbor_to_json).collect()),
+        Value::Map(entries) => serde_json::Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        Value::Text(key) => key,
+                        key => format!("{:?}", key),
+                    };
+                    (key, cbor_to_json(value))
+                })
+                .collect(),
+        ),
+        _ => serde_json::Value::Null,
+    }
+}
+
+fn localized(strings: &LocalizedStrings) -> &str {
+    strings
+        .0
+        .get(REPORT_LANGUAGE)
+        .or_else(|| strings.0.values().next())
+        .map(String::as_str)
+        .unwrap_or_default()
+}
+
+impl Display for HistoryReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Wallet history report")?;
+        writeln!(
+            f,
+            "Created at: {}",
+            self.created_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        )?;
+        writeln!(f, "Number of events: {}", self.events.len())?;
+
+        self.events.iter().try_for_each(|event| write!(f, "\n{}", event))
+    }
+}
+
+impl Display for HistoryReportEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let description = match self.r#type {
+            WalletEventType::Issuance => "Card(s) received",
+            WalletEventType::Disclosure => "Data shared",
+            WalletEventType::Login => "Logged in",
+            WalletEventType::DeletionRequest => "Data deletion requested",
+        };
+        writeln!(
+            f,
+            "{} - {} ({:?})",
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            description,
+            self.status
+        )?;
+
+        if let Some(relying_party) = &self.relying_party {
+            writeln!(
+                f,
+                "  Organization: {}",
+                localized(&relying_party.organization.legal_name)
+            )?;
+            writeln!(f, "  Purpose: {}", localized(&relying_party.purpose_statement))?;
+            match relying_party.retention_policy {
+                RetentionPolicy {
+                    intent_to_retain: true,
+                    max_duration_in_minutes: Some(minutes),
+                } => writeln!(f, "  Retention: at most {} minutes", minutes)?,
+                RetentionPolicy {
+                    intent_to_retain: true,
+                    max_duration_in_minutes: None,
+                } => writeln!(f, "  Retention: data is retained")?,
+                RetentionPolicy {
+                    intent_to_retain: false,
+                    ..
+                } => writeln!(f, "  Retention: data is not retained")?,
+            }
+            writeln!(
+                f,
+                "  Shared with third parties: {}",
+                if relying_party.sharing_policy.intent_to_share {
+                    "yes"
+                } else {
+                    "no"
+                }
+            )?;
+            writeln!(
+                f,
+                "  Deletion can be requested: {}",
+                if relying_party.deletion_policy.deleteable {
+                    "yes"
+                } else {
+                    "no"
+                }
+            )?;
+        }
+
+        for document in &self.documents {
+            writeln!(
+                f,
+                "  Card {} issued by {}",
+                document.doc_type,
+                localized(&document.issuer.legal_name)
+            )?;
+            for (namespace, attributes) in &document.attributes {
+                for (name, value) in attributes {
+                    writeln!(f, "    {}/{}: {}", namespace, name, value)?;
+                }
+            }
+        }
+
+        for attribute in &self.deletion_requested_attributes {
+            writeln!(
+                f,
+                "  Attribute {}/{} of card {}",
+                attribute.namespace, attribute.attribute, attribute.doc_type
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::{Duration, TimeZone};
+
+    use nl_wallet_mdoc::server_keys::KeyPair;
+    use wallet_common::{jwt::EcdsaDecodingKey, keys::EcdsaKey};
+
+    use crate::wallet::test::{WalletWithMocks, ISSUER_KEY};
+
+    use super::*;
+
+    const PID_DOCTYPE: &str = "com.example.pid";
+
+    #[tokio::test]
+    async fn test_export_history() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let reader_key = KeyPair::generate_reader_mock_ca()
+            .unwrap()
+            .generate_reader_mock(ReaderRegistration::new_mock().into())
+            .unwrap();
+
+        let timestamp = Utc.with_ymd_and_hms(2023, 11, 11, 11, 11, 00).unwrap();
+        let issuance_event = WalletEvent::issuance_from_str(
+            vec![PID_DOCTYPE],
+            timestamp,
+            ISSUER_KEY.issuance_key.certificate().clone(),
+        );
+        let disclosure_event = WalletEvent::disclosure_from_str(
+            vec![PID_DOCTYPE],
+            timestamp + Duration::days(1),
+            reader_key.certificate().clone(),
+            ISSUER_KEY.issuance_key.certificate(),
+        );
+        wallet.store_history_event(issuance_event.clone()).await.unwrap();
+        wallet.store_history_event(disclosure_event.clone()).await.unwrap();
+
+        let export = wallet.export_history().await.expect("Could not export history");
+
+        // The report should contain both events, newest first.
+        let report = &export.report;
+        assert_eq!(
+            report.events,
+            vec![disclosure_event.try_into().unwrap(), issuance_event.try_into().unwrap()]
+        );
+
+        let disclosure = report.events.first().unwrap();
+        assert_eq!(disclosure.r#type, WalletEventType::Disclosure);
+        let relying_party = disclosure.relying_party.as_ref().unwrap();
+        assert_eq!(relying_party.organization, ReaderRegistration::new_mock().organization);
+        assert_eq!(&relying_party.certificate, reader_key.certificate());
+        assert!(!disclosure.documents.first().unwrap().attributes.is_empty());
+
+        // The signed report should be verifiable with the hardware public key and be equal to the report.
+        let hw_pubkey = wallet
+            .registration
+            .as_ref()
+            .unwrap()
+            .hw_privkey
+            .verifying_key()
+            .await
+            .unwrap();
+        let verified_report = export
+            .signed_report
+            .parse_and_verify(&(&hw_pubkey).into(), &jwt::validations())
+            .expect("Could not verify signed report");
+        assert_eq!(verified_report.created_at, report.created_at);
+        assert_eq!(verified_report.wallet_certificate.0, report.wallet_certificate.0);
+        assert_eq!(verified_report.events, report.events);
+
+        // The signature should not verify using another key.
+        let other_key = EcdsaDecodingKey::from(reader_key.private_key().verifying_key());
+        assert!(export
+            .signed_report
+            .parse_and_verify(&other_key, &jwt::validations())
+            .is_err());
+
+        // The JSON and text renderings should contain the relying party.
+        let json: serde_json::Value = serde_json::from_str(&export.to_json().unwrap()).unwrap();
+        assert_eq!(json["events"][0]["type"], "disclosure");
+        assert_eq!(json["events"][1]["type"], "issuance");
+
+        let text = export.to_text();
+        assert!(text.contains("Number of events: 2"));
+        assert!(text.contains("Organization: Organization"));
+    }
+
+    #[tokio::test]
+    async fn test_export_history_error_not_registered_or_locked() {
+        let wallet = WalletWithMocks::new_unregistered().await;
+        let error = wallet.export_history().await.unwrap_err();
+        assert_matches!(error, HistoryError::NotRegistered);
+
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+        wallet.lock();
+        let error = wallet.export_history().await.unwrap_err();
+        assert_matches!(error, HistoryError::Locked);
+    }
+}
@@ -1,20 +1,23 @@
 use chrono::{DateTime, Utc};
 use tracing::{info, instrument};
+use uuid::Uuid;
 
 use nl_wallet_mdoc::{
     holder::ProposedDocumentAttributes,
+    identifiers::AttributeIdentifier,
     utils::{
         issuer_auth::IssuerRegistration,
         reader_auth::ReaderRegistration,
         x509::{CertificateError, MdocCertificateExtension},
     },
 };
+use wallet_common::jwt::JwtError;
 
-pub use crate::storage::EventStatus;
+pub use crate::storage::{EventStatus, RelyingPartyFilter, WalletEventCursor, WalletEventQuery, WalletEventType};
 use crate::{
     document::{DisclosureType, DocumentMdocError},
     errors::StorageError,
-    storage::{EventDocuments, Storage, WalletEvent},
+    storage::{EventDocuments, Storage, WalletEvent, WalletEventPage},
     DisclosureDocument, Document, DocumentPersistence,
 };
 
@@ -28,6 +31,8 @@ pub enum HistoryError {
     Locked,
     #[error("could not access history database: {0}")]
     EventStorage(#[from] EventStorageError),
+    #[error("could not sign history report: {0}")]
+    ReportSigning(#[source] JwtError),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -120,6 +125,29 @@ where
         Ok(result)
     }
 
+    /// Retrieves a single page of history events that match the query, newest first. The `next_cursor` of
+    /// the returned [`HistoryPage`] can be used to fetch the next page, using the same filters.
+    #[instrument(skip_all)]
+    pub async fn get_history_page(&self, query: &WalletEventQuery) -> HistoryResult<HistoryPage> {
+        info!("Retrieving history page");
+
+        info!("Checking if registered");
+        if self.registration.is_none() {
+            return Err(HistoryError::NotRegistered);
+        }
+
+        info!("Checking if locked");
+        if self.lock.is_locked() {
+            return Err(HistoryError::Locked);
+        }
+
+        info!("Querying history from storage");
+        let storage = self.storage.read().await;
+        let WalletEventPage { events, next_cursor } = storage.query_wallet_events(query).await?;
+        let events = events.into_iter().map(TryFrom::try_from).collect::<Result<_, _>>()?;
+        Ok(HistoryPage { events, next_cursor })
+    }
+
     async fn emit_recent_history(&mut self) -> Result<(), EventStorageError> {
         info!("Emit recent history from storage");
 
@@ -165,12 +193,27 @@ pub enum HistoryEvent {
         mdocs: Vec<Document>,
     },
     Disclosure {
+        /// Identifies this event when requesting deletion of the disclosed attributes.
+        id: Uuid,
         status: EventStatus,
         r#type: DisclosureType,
         timestamp: DateTime<Utc>,
         reader_registration: Box<ReaderRegistration>,
         attributes: Option<Vec<DisclosureDocument>>,
     },
+    DeletionRequest {
+        disclosure_id: Uuid,
+        status: EventStatus,
+        timestamp: DateTime<Utc>,
+        reader_registration: Box<ReaderRegistration>,
+        attributes: Vec<AttributeIdentifier>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryPage {
+    pub events: Vec<HistoryEvent>,
+    pub next_cursor: Option<WalletEventCursor>,
 }
 
 impl TryFrom<WalletEvent> for HistoryEvent {
@@ -202,13 +245,16 @@ impl TryFrom<WalletEvent> for HistoryEvent {
                     .collect::<Result<_, EventConversionError>>()?,
             },
             WalletEvent::Disclosure {
-                id: _,
+                id,
                 reader_certificate,
                 timestamp,
                 documents,
                 status,
                 r#type,
+                session_reference: _,
+                deletion_token: _,
             } => Self::Disclosure {
+                id,
                 status,
                 r#type,
                 timestamp,
@@ -234,6 +280,24 @@ impl TryFrom<WalletEvent> for HistoryEvent {
                     Box::new(reader_registration)
                 },
             },
+            WalletEvent::DeletionRequest {
+                id: _,
+                disclosure_id,
+                attributes,
+                timestamp,
+                reader_certificate,
+                status,
+            } => Self::DeletionRequest {
+                disclosure_id,
+                status,
+                timestamp,
+                reader_registration: {
+                    let reader_registration = ReaderRegistration::from_certificate(&reader_certificate)?
+                        .ok_or(EventConversionError::NoReaderRegistrationFound)?;
+                    Box::new(reader_registration)
+                },
+                attributes,
+            },
         };
         Ok(result)
     }
@@ -241,7 +305,7 @@ impl TryFrom<WalletEvent> for HistoryEvent {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::{collections::HashSet, sync::Arc};
 
     use assert_matches::assert_matches;
 
@@ -256,7 +320,7 @@ mod tests {
         HistoryEvent,
     };
 
-    use super::{EventStorageError, HistoryError};
+    use super::{EventStatus, EventStorageError, HistoryError, HistoryPage, WalletEventQuery};
 
     const PID_DOCTYPE: &str = "com.example.pid";
     const ADDRESS_DOCTYPE: &str = "com.example.address";
@@ -276,6 +340,12 @@ mod tests {
             .await
             .expect_err("Expect error when Wallet is not registered");
         assert_matches!(error, HistoryError::NotRegistered);
+
+        let error = wallet
+            .get_history_page(&Default::default())
+            .await
+            .expect_err("Expect error when Wallet is not registered");
+        assert_matches!(error, HistoryError::NotRegistered);
     }
 
     #[tokio::test]
@@ -295,6 +365,12 @@ mod tests {
             .await
             .expect_err("Expect error when Wallet is locked");
         assert_matches!(error, HistoryError::Locked);
+
+        let error = wallet
+            .get_history_page(&Default::default())
+            .await
+            .expect_err("Expect error when Wallet is locked");
+        assert_matches!(error, HistoryError::Locked);
     }
 
     #[tokio::test]
@@ -351,8 +427,8 @@ mod tests {
             history,
             vec![
                 address_doc_type_event.clone().try_into().unwrap(),
-                disclosure_error_event.try_into().unwrap(),
-                disclosure_cancelled_event.try_into().unwrap(),
+                disclosure_error_event.clone().try_into().unwrap(),
+                disclosure_cancelled_event.clone().try_into().unwrap(),
                 pid_doc_type_event.clone().try_into().unwrap()
             ]
         );
@@ -362,7 +438,35 @@ mod tests {
         assert_eq!(history, vec![pid_doc_type_event.try_into().unwrap()]);
 
         let history = wallet.get_history_for_card(ADDRESS_DOCTYPE).await.unwrap();
-        assert_eq!(history, vec![address_doc_type_event.try_into().unwrap()]);
+        assert_eq!(history, vec![address_doc_type_event.clone().try_into().unwrap()]);
+
+        // get history page should return the disclosures with a non-success status in pages of one event
+        let query = WalletEventQuery {
+            limit: Some(1),
+            statuses: HashSet::from([EventStatus::Cancelled, EventStatus::Error]),
+            ..Default::default()
+        };
+        let page = wallet.get_history_page(&query).await.unwrap();
+        assert_eq!(
+            page,
+            HistoryPage {
+                events: vec![disclosure_error_event.clone().try_into().unwrap()],
+                next_cursor: Some(disclosure_error_event.cursor()),
+            }
+        );
+
+        let query = WalletEventQuery {
+            after: page.next_cursor,
+            ..query
+        };
+        let page = wallet.get_history_page(&query).await.unwrap();
+        assert_eq!(
+            page,
+            HistoryPage {
+                events: vec![disclosure_cancelled_event.try_into().unwrap()],
+                next_cursor: None,
+            }
+        );
     }
 
     // Tests both setting and clearing the recent_history callback on an unregistered `Wallet`.
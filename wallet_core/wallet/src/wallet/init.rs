@@ -66,6 +66,7 @@ where
             storage: RwLock::new(storage),
             account_provider_client,
             issuance_session: None,
+            pin_recovery_session: None,
             disclosure_session: None,
             lock: WalletLock::new(true),
             registration,
@@ -82,6 +83,11 @@ where
     ) -> Result<Self, WalletInitError> {
         let registration = Self::fetch_registration(&mut storage).await?;
 
+        // If the wallet was registered using a backup, make sure its contents have been imported completely.
+        if registration.is_some() {
+            super::backup::complete_restore(&mut storage).await?;
+        }
+
         let wallet = Self::new(config_repository, storage, account_provider_client, registration);
 
         Ok(wallet)
@@ -106,7 +112,10 @@ where
 mod tests {
     use wallet_common::keys::{software::SoftwareEcdsaKey, EcdsaKey};
 
-    use crate::{pin::key as pin_key, storage::MockStorage};
+    use crate::{
+        pin::key as pin_key,
+        storage::{BackupContents, MockStorage, RestoreData, WalletEvent},
+    };
 
     use super::{
         super::{registration, test::WalletWithMocks},
@@ -185,6 +194,34 @@ mod tests {
         assert_eq!(wallet.registration.unwrap().data.pin_salt, pin_salt);
     }
 
+    // Tests that the contents of a restored backup are imported during initialization, if this did not complete.
+    #[tokio::test]
+    async fn test_wallet_init_complete_restore() {
+        let event = WalletEvent::new_issuance(Default::default());
+        let mut storage = MockStorage::new(
+            StorageState::Unopened,
+            Some(RegistrationData {
+                pin_salt: pin_key::new_pin_salt(),
+                wallet_certificate: "thisisjwt".to_string().into(),
+            }),
+        );
+        let restore_data = RestoreData::new(&BackupContents {
+            events: vec![event.clone()],
+            ..Default::default()
+        })
+        .unwrap();
+        storage.insert_data(&restore_data).await.unwrap();
+
+        let wallet = WalletWithMocks::init_registration_mocks_with_storage(storage)
+            .await
+            .expect("Could not initialize wallet");
+
+        // The contents of the backup should have been imported and the restore data should be removed.
+        let storage = wallet.storage.read().await;
+        assert_eq!(storage.event_log, vec![event]);
+        assert!(storage.fetch_data::<RestoreData>().await.unwrap().is_none());
+    }
+
     // Tests that the Wallet can be initialized multiple times and uses the same hardware key every time.
     #[tokio::test]
     async fn test_wallet_init_hw_privkey() {
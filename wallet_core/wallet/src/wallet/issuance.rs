@@ -198,6 +198,7 @@ where
             config.pid_issuance.pid_issuer_url.clone(),
             token_request,
             &config.mdoc_trust_anchors(),
+            &config.mdoc_crls,
         )
         .await?;
 
@@ -263,6 +264,7 @@ where
         let mdocs_result = pid_issuer
             .accept_issuance(
                 &config.mdoc_trust_anchors(),
+                &config.mdoc_crls,
                 &remote_key_factory,
                 config.pid_issuance.pid_issuer_url.clone(),
             )
@@ -284,12 +286,12 @@ where
                 }
             });
 
-        // If the Wallet Provider returns either a PIN timeout or a permanent block,
+        // If the Wallet Provider returns either a PIN timeout, a permanent block or a revocation,
         // wipe the contents of the wallet and return it to its initial state.
         if matches!(
             mdocs_result,
             Err(PidIssuanceError::Instruction(
-                InstructionError::Timeout { .. } | InstructionError::Blocked
+                InstructionError::Timeout { .. } | InstructionError::Blocked | InstructionError::Revoked
             ))
         ) {
             self.reset_to_initial_state().await;
@@ -930,6 +932,8 @@ mod tests {
     #[case(InstructionError::IncorrectPin { attempts_left_in_round: 1, is_final_round: false }, false)]
     #[case(InstructionError::Timeout { timeout_millis: 10_000 }, true)]
     #[case(InstructionError::Blocked, true)]
+    #[case(InstructionError::Suspended, false)]
+    #[case(InstructionError::Revoked, true)]
     #[case(InstructionError::InstructionValidation, false)]
     #[tokio::test]
     async fn test_accept_pid_issuance_error_instruction(
@@ -97,6 +97,7 @@ mod tests {
     use std::{ops::Deref, sync::Arc};
 
     use assert_matches::assert_matches;
+    use chrono::{TimeDelta, Utc};
     use http::StatusCode;
     use mockall::predicate::*;
 
@@ -335,7 +336,10 @@ mod tests {
     #[tokio::test]
     async fn test_wallet_unlock_error_instruction_timeout() {
         let error = test_wallet_unlock_error_instruction_response(AccountProviderResponseError::Account(
-            AccountError::PinTimeout(PinTimeoutData { time_left_in_ms: 5000 }),
+            AccountError::PinTimeout(PinTimeoutData {
+                time_left_in_ms: 5000,
+                next_attempt_at: Utc::now() + TimeDelta::milliseconds(5000),
+            }),
             None,
         ))
         .await;
@@ -357,6 +361,28 @@ mod tests {
         assert_matches!(error, WalletUnlockError::Instruction(InstructionError::Blocked));
     }
 
+    #[tokio::test]
+    async fn test_wallet_unlock_error_instruction_suspended() {
+        let error = test_wallet_unlock_error_instruction_response(AccountProviderResponseError::Account(
+            AccountError::AccountSuspended,
+            None,
+        ))
+        .await;
+
+        assert_matches!(error, WalletUnlockError::Instruction(InstructionError::Suspended));
+    }
+
+    #[tokio::test]
+    async fn test_wallet_unlock_error_instruction_revoked() {
+        let error = test_wallet_unlock_error_instruction_response(AccountProviderResponseError::Account(
+            AccountError::AccountRevoked,
+            None,
+        ))
+        .await;
+
+        assert_matches!(error, WalletUnlockError::Instruction(InstructionError::Revoked));
+    }
+
     #[tokio::test]
     async fn test_wallet_unlock_error_instruction_validation() {
         let error = test_wallet_unlock_error_instruction_response(AccountProviderResponseError::Account(
@@ -1,10 +1,15 @@
+mod backup;
 mod config;
+mod deletion;
+mod device;
 mod disclosure;
 mod documents;
+mod export;
 mod history;
 mod init;
 mod issuance;
 mod lock;
+mod pin_recovery;
 mod registration;
 mod reset;
 mod uri;
@@ -30,21 +35,27 @@ use crate::{
 };
 
 pub use self::{
+    backup::{generate_recovery_secret, BackupError, WalletBackup},
     config::ConfigCallback,
+    deletion::DeletionError,
+    device::DeviceLinkError,
     disclosure::{DisclosureError, DisclosureProposal},
     documents::DocumentsCallback,
+    export::{HistoryExport, HistoryReport, HistoryReportDocument, HistoryReportEvent, HistoryReportRelyingParty},
     history::{
-        EventConversionError, EventStatus, EventStorageError, HistoryError, HistoryEvent, RecentHistoryCallback,
+        EventConversionError, EventStatus, EventStorageError, HistoryError, HistoryEvent, HistoryPage,
+        RecentHistoryCallback, RelyingPartyFilter, WalletEventCursor, WalletEventQuery, WalletEventType,
     },
     init::WalletInitError,
     issuance::PidIssuanceError,
     lock::{LockCallback, WalletUnlockError},
+    pin_recovery::PinRecoveryError,
     registration::WalletRegistrationError,
     reset::ResetError,
     uri::{UriIdentificationError, UriType},
 };
 
-use self::issuance::PidIssuanceSession;
+use self::{issuance::PidIssuanceSession, pin_recovery::PinRecoverySession};
 
 struct WalletRegistration<K> {
     hw_privkey: K,
@@ -64,6 +75,7 @@ pub struct Wallet<
     storage: RwLock<S>,
     account_provider_client: APC,
     issuance_session: Option<PidIssuanceSession<DS, IC>>,
+    pin_recovery_session: Option<PinRecoverySession<DS>>,
     disclosure_session: Option<MDS>,
     lock: WalletLock,
     registration: Option<WalletRegistration<PEK>>,
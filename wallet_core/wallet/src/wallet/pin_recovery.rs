@@ -0,0 +1,825 @@
+use std::{collections::HashMap, iter};
+
+use futures::future;
+use http::{header, HeaderMap, HeaderValue};
+use p256::{
+    ecdh,
+    ecdsa::{signature::Signer, Signature, SigningKey, VerifyingKey},
+    elliptic_curve::rand_core::OsRng,
+    PublicKey,
+};
+use parking_lot::Mutex;
+
+use tracing::{info, instrument};
+use url::Url;
+
+use nl_wallet_mdoc::utils::{
+    keys::{KeyFactory, MdocEcdsaKey, MdocKeyType},
+    serialization::{cbor_serialize, CborError},
+};
+use openid4vc::issuance_session::{IssuanceSession, IssuanceSessionError};
+use platform_support::hw_keystore::PlatformEcdsaKey;
+use wallet_common::{
+    account::messages::instructions::RecoverPin,
+    config::wallet_config::WalletConfiguration,
+    jwt::JwtError,
+    keys::{EcdsaKey, SecureEcdsaKey, WithIdentifier},
+    reqwest::trusted_reqwest_client_builder,
+    utils::random_string,
+};
+
+use crate::{
+    account_provider::AccountProviderClient,
+    config::{ConfigurationRepository, UNIVERSAL_LINK_BASE_URL},
+    document::PID_DOCTYPE,
+    instruction::{InstructionClient, InstructionError},
+    issuance::{DigidSession, DigidSessionError, HttpDigidSession},
+    pin::{
+        key::{self as pin_key, PinKey, PinKeyError},
+        validation::{validate_pin, PinValidationError},
+    },
+    storage::{RegistrationData, Storage, StorageError},
+};
+
+use super::Wallet;
+
+/// The state of PIN recovery, during which the user re-identifies with DigiD in order to receive a fresh PID.
+pub(super) enum PinRecoverySession<DS = HttpDigidSession> {
+    /// Contains the DigiD session and the factory of the in-memory keys that the fresh PID will be bound to.
+    Digid {
+        session: DS,
+        key_factory: RecoveryKeyFactory,
+    },
+    /// Contains the CBOR encoded `IssuerSigned` of the fresh PID, which is only used as proof of the re-identification,
+    /// and the private key it is bound to.
+    PidReceived {
+        issuer_signed: Vec<u8>,
+        signing_key: SigningKey,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PinRecoveryError {
+    #[error("wallet is not registered")]
+    NotRegistered,
+    #[error("wallet is not locked")]
+    NotLocked,
+    #[error("PIN recovery session is not in the correct state")]
+    SessionState,
+    #[error("new PIN does not adhere to requirements: {0}")]
+    InvalidPin(#[from] PinValidationError),
+    #[error("could not start DigiD session: {0}")]
+    DigidSessionStart(#[source] DigidSessionError),
+    #[error("could not finish DigiD session: {0}")]
+    DigidSessionFinish(#[source] DigidSessionError),
+    #[error("could not retrieve PID from issuer: {0}")]
+    PidIssuer(#[from] IssuanceSessionError),
+    #[error("no PID received from issuer")]
+    MissingRecoveryPid,
+    #[error("PID received from issuer is not bound to a generated key")]
+    MissingRecoveryPidKey,
+    #[error("wallet does not contain a PID")]
+    MissingPid,
+    #[error("could not read PID from database: {0}")]
+    MdocStorage(#[source] StorageError),
+    #[error("could not encode PID: {0}")]
+    PidEncoding(#[source] CborError),
+    #[error("could not derive new PIN public key: {0}")]
+    PinKey(#[from] PinKeyError),
+    #[error("error sending instruction to Wallet Provider: {0}")]
+    Instruction(#[from] InstructionError),
+    #[error("could not get hardware public key: {0}")]
+    HardwarePublicKey(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("could not validate wallet certificate received from Wallet Provider: {0}")]
+    CertificateValidation(#[source] JwtError),
+    #[error("public key in wallet certificate received from Wallet Provider does not match hardware public key")]
+    PublicKeyMismatch,
+    #[error("could not store wallet certificate in database: {0}")]
+    StoreCertificate(#[source] StorageError),
+}
+
+impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS>
+where
+    CR: ConfigurationRepository,
+    DS: DigidSession,
+{
+    #[instrument(skip_all)]
+    pub async fn create_pin_recovery_auth_url(&mut self) -> Result<Url, PinRecoveryError> {
+        info!("Generating DigiD auth URL for PIN recovery, starting OpenID connect discovery");
+
+        info!("Checking if registered");
+        if self.registration.is_none() {
+            return Err(PinRecoveryError::NotRegistered);
+        }
+
+        // PIN recovery is only needed when the wallet cannot be unlocked.
+        info!("Checking if locked");
+        if !self.lock.is_locked() {
+            return Err(PinRecoveryError::NotLocked);
+        }
+
+        info!("Checking if there is an active PIN recovery session");
+        if self.pin_recovery_session.is_some() {
+            return Err(PinRecoveryError::SessionState);
+        }
+
+        let pid_issuance_config = &self.config_repository.config().pid_issuance;
+        let (session, auth_url) = DS::start(
+            pid_issuance_config.clone(),
+            WalletConfiguration::issuance_base_uri(&UNIVERSAL_LINK_BASE_URL)
+                .as_ref()
+                .to_owned(),
+        )
+        .await
+        .map_err(PinRecoveryError::DigidSessionStart)?;
+
+        info!("DigiD auth URL generated");
+        self.pin_recovery_session.replace(PinRecoverySession::Digid {
+            session,
+            key_factory: RecoveryKeyFactory::default(),
+        });
+
+        Ok(auth_url)
+    }
+
+    #[instrument(skip_all)]
+    pub fn cancel_pin_recovery(&mut self) -> Result<(), PinRecoveryError> {
+        info!("PIN recovery cancelled");
+
+        info!("Checking if there is an active PIN recovery session");
+        self.pin_recovery_session.take().ok_or(PinRecoveryError::SessionState)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    pub async fn continue_pin_recovery(&mut self, redirect_uri: Url) -> Result<(), PinRecoveryError>
+    where
+        IS: IssuanceSession,
+    {
+        info!("Received DigiD redirect URI for PIN recovery, processing URI and retrieving access token");
+
+        info!("Checking if registered");
+        if self.registration.is_none() {
+            return Err(PinRecoveryError::NotRegistered);
+        }
+
+        info!("Checking if locked");
+        if !self.lock.is_locked() {
+            return Err(PinRecoveryError::NotLocked);
+        }
+
+        info!("Checking if there is an active DigiD PIN recovery session");
+        let (session, key_factory) = match self.pin_recovery_session.take() {
+            Some(PinRecoverySession::Digid { session, key_factory }) => (session, key_factory),
+            session => {
+                self.pin_recovery_session = session;
+                return Err(PinRecoveryError::SessionState);
+            }
+        };
+
+        let token_request = session
+            .into_token_request(redirect_uri)
+            .await
+            .map_err(PinRecoveryError::DigidSessionFinish)?;
+
+        let config = self.config_repository.config();
+        let http_client = trusted_reqwest_client_builder(config.pid_issuance.digid_trust_anchors())
+            .default_headers(HeaderMap::from_iter([(
+                header::ACCEPT,
+                HeaderValue::from_static(mime::APPLICATION_JSON.as_ref()),
+            )]))
+            .build()
+            .expect("Could not build reqwest HTTP client");
+
+        let (pid_issuer, _) = IS::start_issuance(
+            http_client.into(),
+            config.pid_issuance.pid_issuer_url.clone(),
+            token_request,
+            &config.mdoc_trust_anchors(),
+            &config.mdoc_crls,
+        )
+        .await?;
+
+        // The fresh PID is only used to prove that the user re-identified, after which it is discarded. This is why
+        // its private key is kept in memory, instead of being generated by the Wallet Provider.
+        info!("Accepting PID for PIN recovery using in-memory keys");
+        let mdocs = pid_issuer
+            .accept_issuance(
+                &config.mdoc_trust_anchors(),
+                &config.mdoc_crls,
+                &key_factory,
+                config.pid_issuance.pid_issuer_url.clone(),
+            )
+            .await?;
+
+        let recovery_pid = mdocs
+            .into_iter()
+            .flat_map(|copies| copies.cred_copies.into_iter().next())
+            .find(|mdoc| mdoc.doc_type == PID_DOCTYPE)
+            .ok_or(PinRecoveryError::MissingRecoveryPid)?;
+        let issuer_signed = cbor_serialize(recovery_pid.issuer_signed()).map_err(PinRecoveryError::PidEncoding)?;
+        let signing_key = key_factory
+            .signing_keys
+            .lock()
+            .remove(recovery_pid.private_key_id())
+            .ok_or(PinRecoveryError::MissingRecoveryPidKey)?;
+
+        info!("PID for PIN recovery received successfully");
+        self.pin_recovery_session.replace(PinRecoverySession::PidReceived {
+            issuer_signed,
+            signing_key,
+        });
+
+        Ok(())
+    }
+
+    #[instrument(skip_all)]
+    pub async fn complete_pin_recovery(&mut self, new_pin: String) -> Result<(), PinRecoveryError>
+    where
+        S: Storage,
+        PEK: PlatformEcdsaKey,
+        APC: AccountProviderClient,
+    {
+        info!("Completing PIN recovery");
+
+        info!("Checking if registered");
+        let registration = self.registration.as_ref().ok_or(PinRecoveryError::NotRegistered)?;
+
+        info!("Checking if locked");
+        if !self.lock.is_locked() {
+            return Err(PinRecoveryError::NotLocked);
+        }
+
+        info!("Checking if PID for PIN recovery was received");
+        let Some(PinRecoverySession::PidReceived {
+            issuer_signed: recovery_pid_issuer_signed,
+            signing_key: recovery_pid_signing_key,
+        }) = self.pin_recovery_session.as_ref()
+        else {
+            return Err(PinRecoveryError::SessionState);
+        };
+
+        validate_pin(&new_pin)?;
+
+        // The Wallet Provider compares the fresh PID to the PID currently held by the wallet,
+        // the key of which should be stored by the Wallet Provider.
+        info!("Fetching PID held by the wallet");
+        let pid = self
+            .storage
+            .read()
+            .await
+            .fetch_unique_mdocs_by_doctypes(&[PID_DOCTYPE].into())
+            .await
+            .map_err(PinRecoveryError::MdocStorage)?
+            .into_iter()
+            .next()
+            .ok_or(PinRecoveryError::MissingPid)?
+            .mdoc;
+        let pid_issuer_signed = cbor_serialize(pid.issuer_signed()).map_err(PinRecoveryError::PidEncoding)?;
+
+        let new_registration_data = RegistrationData {
+            pin_salt: pin_key::new_pin_salt(),
+            wallet_certificate: registration.data.wallet_certificate.clone(),
+        };
+        let new_pin_pubkey = PinKey::new(&new_pin, &new_registration_data.pin_salt).verifying_key()?;
+
+        // Signing the new PIN public key with the key of the recovery PID proves to the Wallet Provider that the
+        // recovery PID was issued to this wallet, during this PIN recovery.
+        let recovery_pid_signature: Signature =
+            recovery_pid_signing_key.sign(&RecoverPin::recovery_pid_message(&new_pin_pubkey));
+
+        let config = self.config_repository.config();
+        let instruction_result_public_key = config.account_server.instruction_result_public_key.clone().into();
+
+        // The instruction client signs the instruction with the key derived from the new PIN and salt, which proves
+        // possession of the new PIN key to the Wallet Provider.
+        let remote_instruction = InstructionClient::new(
+            new_pin,
+            &self.storage,
+            &registration.hw_privkey,
+            &self.account_provider_client,
+            &new_registration_data,
+            &config.account_server.base_url,
+            &instruction_result_public_key,
+        );
+
+        info!("Sending PIN recovery instruction to Wallet Provider");
+        let wallet_certificate = remote_instruction
+            .send(RecoverPin {
+                pin_pubkey: new_pin_pubkey.into(),
+                pid_key_identifier: pid.private_key_id().to_string(),
+                pid_issuer_signed,
+                recovery_pid_issuer_signed: recovery_pid_issuer_signed.clone(),
+                recovery_pid_signature: recovery_pid_signature.into(),
+            })
+            .await?;
+
+        info!("Certificate received from account server, verifying contents");
+
+        let hw_pubkey = registration
+            .hw_privkey
+            .verifying_key()
+            .await
+            .map_err(|e| PinRecoveryError::HardwarePublicKey(e.into()))?;
+        let cert_claims = wallet_certificate
+            .parse_and_verify_with_sub(&config.account_server.certificate_public_key.clone().into())
+            .map_err(PinRecoveryError::CertificateValidation)?;
+        if cert_claims.hw_pubkey.0 != hw_pubkey {
+            return Err(PinRecoveryError::PublicKeyMismatch);
+        }
+
+        info!("Storing new PIN salt and wallet certificate");
+
+        let new_registration_data = RegistrationData {
+            wallet_certificate,
+            ..new_registration_data
+        };
+        self.storage
+            .get_mut()
+            .update_data(&new_registration_data)
+            .await
+            .map_err(PinRecoveryError::StoreCertificate)?;
+
+        // The unwrap is safe, as the registration was checked above.
+        self.registration.as_mut().unwrap().data = new_registration_data;
+        self.pin_recovery_session.take();
+
+        info!("PIN recovery successful, unlocking wallet");
+        self.lock.unlock();
+
+        Ok(())
+    }
+}
+
+/// Creates the keys of the fresh PID received during PIN recovery. Since this PID is never disclosed and discarded
+/// after use, these keys are only kept in memory.
+#[derive(Debug, Default)]
+pub(super) struct RecoveryKeyFactory {
+    signing_keys: Mutex<HashMap<String, SigningKey>>,
+}
+
+pub(super) struct RecoveryEcdsaKey {
+    identifier: String,
+    signing_key: SigningKey,
+}
+
+impl EcdsaKey for RecoveryEcdsaKey {
+    type Error = p256::ecdsa::Error;
+
+    async fn verifying_key(&self) -> Result<VerifyingKey, Self::Error> {
+        Ok(*self.signing_key.verifying_key())
+    }
+
+    async fn try_sign(&self, msg: &[u8]) -> Result<Signature, Self::Error> {
+        Signer::try_sign(&self.signing_key, msg)
+    }
+}
+
+// The key of the recovery PID only needs to live as long as the PIN recovery session, so it is sufficiently secure.
+impl SecureEcdsaKey for RecoveryEcdsaKey {}
+
+impl WithIdentifier for RecoveryEcdsaKey {
+    fn identifier(&self) -> &str {
+        &self.identifier
+    }
+}
+
+impl MdocEcdsaKey for RecoveryEcdsaKey {
+    const KEY_TYPE: MdocKeyType = MdocKeyType::Software;
+}
+
+impl KeyFactory for &RecoveryKeyFactory {
+    type Key = RecoveryEcdsaKey;
+    type Error = p256::ecdsa::Error;
+
+    async fn generate_new_multiple(&self, count: u64) -> Result<Vec<Self::Key>, Self::Error> {
+        let keys = iter::repeat_with(|| RecoveryEcdsaKey {
+            identifier: random_string(32),
+            signing_key: SigningKey::random(&mut OsRng),
+        })
+        .take(count as usize)
+        .collect::<Vec<_>>();
+
+        self.signing_keys
+            .lock()
+            .extend(keys.iter().map(|key| (key.identifier.clone(), key.signing_key.clone())));
+
+        Ok(keys)
+    }
+
+    fn generate_existing<I: Into<String>>(&self, identifier: I, public_key: VerifyingKey) -> Self::Key {
+        let identifier = identifier.into();
+        let signing_key = self
+            .signing_keys
+            .lock()
+            .get(&identifier)
+            .expect("called generate_existing() with unknown identifier")
+            .clone();
+
+        assert_eq!(
+            signing_key.verifying_key(),
+            &public_key,
+            "called generate_existing() with incorrect public_key"
+        );
+
+        RecoveryEcdsaKey {
+            identifier,
+            signing_key,
+        }
+    }
+
+    async fn sign_with_new_keys(
+        &self,
+        msg: Vec<u8>,
+        number_of_keys: u64,
+    ) -> Result<Vec<(Self::Key, Signature)>, Self::Error> {
+        let keys = self.generate_new_multiple(number_of_keys).await?;
+
+        future::try_join_all(keys.into_iter().map(|key| async {
+            let signature = key.try_sign(&msg).await?;
+
+            Ok((key, signature))
+        }))
+        .await
+    }
+
+    async fn sign_multiple_with_existing_keys(
+        &self,
+        messages_and_keys: Vec<(Vec<u8>, Vec<&Self::Key>)>,
+    ) -> Result<Vec<Vec<Signature>>, Self::Error> {
+        future::try_join_all(messages_and_keys.into_iter().map(|(msg, keys)| async move {
+            future::try_join_all(keys.into_iter().map(|key| key.try_sign(&msg))).await
+        }))
+        .await
+    }
+
+    async fn diffie_hellman_with_existing_keys(
+        &self,
+        keys: Vec<&Self::Key>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let shared_secrets = keys
+            .into_iter()
+            .map(|key| {
+                ecdh::diffie_hellman(key.signing_key.as_nonzero_scalar(), public_key.as_affine())
+                    .raw_secret_bytes()
+                    .to_vec()
+            })
+            .collect();
+
+        Ok(shared_secrets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use mockall::predicate::*;
+    use p256::ecdsa::{signature::Verifier, VerifyingKey};
+
+    use openid4vc::{
+        mock::MockIssuanceSession,
+        token::{TokenRequest, TokenRequestGrantType},
+    };
+    use wallet_common::{
+        account::{
+            messages::{
+                auth::WalletCertificateClaims,
+                errors::AccountError,
+                instructions::{Instruction, InstructionResultClaims},
+            },
+            signed::SequenceNumberComparison,
+        },
+        jwt::Jwt,
+        utils,
+    };
+
+    use crate::{
+        account_provider::{AccountProviderError, AccountProviderResponseError},
+        document,
+        issuance::MockDigidSession,
+        storage::KeyedData,
+    };
+
+    use super::{
+        super::test::{self, WalletWithMocks, ACCOUNT_SERVER_KEYS},
+        *,
+    };
+
+    const NEW_PIN: &str = "651792";
+    const AUTH_URL: &str = "http://example.com/auth";
+    const REDIRECT_URI: &str = "redirect://here";
+
+    async fn locked_wallet_with_recovery_pid() -> WalletWithMocks {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+        wallet.lock();
+
+        // Store a PID in the wallet and prepare a fresh PID received from DigiD.
+        wallet
+            .storage
+            .get_mut()
+            .insert_mdocs(vec![vec![test::create_full_pid_mdoc().await].into()])
+            .await
+            .unwrap();
+        let signing_key = SigningKey::random(&mut OsRng);
+        let recovery_pid = test::mdoc_from_unsigned_with_key(
+            document::create_full_unsigned_pid_mdoc(),
+            &test::ISSUER_KEY,
+            utils::random_string(16),
+            signing_key.verifying_key(),
+        )
+        .await;
+        wallet.pin_recovery_session = Some(PinRecoverySession::PidReceived {
+            issuer_signed: cbor_serialize(recovery_pid.issuer_signed()).unwrap(),
+            signing_key,
+        });
+
+        wallet
+    }
+
+    #[tokio::test]
+    async fn test_create_pin_recovery_auth_url() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+        wallet.lock();
+
+        let session_start_context = MockDigidSession::start_context();
+        session_start_context
+            .expect()
+            .returning(|_, _| Ok((MockDigidSession::default(), Url::parse(AUTH_URL).unwrap())));
+
+        let auth_url = wallet
+            .create_pin_recovery_auth_url()
+            .await
+            .expect("Could not generate PIN recovery auth URL");
+
+        assert_eq!(auth_url.as_str(), AUTH_URL);
+        assert!(matches!(
+            wallet.pin_recovery_session,
+            Some(PinRecoverySession::Digid { .. })
+        ));
+        assert!(wallet.issuance_session.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_pin_recovery_auth_url_error_not_locked() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+
+        let error = wallet
+            .create_pin_recovery_auth_url()
+            .await
+            .expect_err("PIN recovery auth URL generation should have resulted in error");
+
+        assert_matches!(error, PinRecoveryError::NotLocked);
+    }
+
+    #[tokio::test]
+    async fn test_continue_pin_recovery() {
+        let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
+        wallet.lock();
+
+        let digid_session = {
+            let mut session = MockDigidSession::default();
+
+            session.expect_into_token_request().return_once(|_uri| {
+                Ok(TokenRequest {
+                    grant_type: TokenRequestGrantType::PreAuthorizedCode {
+                        pre_authorized_code: "123".to_string().into(),
+                    },
+                    code_verifier: None,
+                    client_id: None,
+                    redirect_uri: None,
+                })
+            });
+
+            session
+        };
+
+        // The PID received from the issuer should be bound to a key generated by the key factory of the session.
+        let key_factory = RecoveryKeyFactory::default();
+        let recovery_pid_key = (&key_factory).generate_new_multiple(1).await.unwrap().remove(0);
+        let expected_public_key = recovery_pid_key.verifying_key().await.unwrap();
+        wallet.pin_recovery_session = Some(PinRecoverySession::Digid {
+            session: digid_session,
+            key_factory,
+        });
+
+        // The PID is accepted immediately, without showing a preview.
+        let mdoc = test::mdoc_from_unsigned_with_key(
+            document::create_full_unsigned_pid_mdoc(),
+            &test::ISSUER_KEY,
+            recovery_pid_key.identifier,
+            &expected_public_key,
+        )
+        .await;
+        let expected_issuer_signed = cbor_serialize(mdoc.issuer_signed()).unwrap();
+        let start_context = MockIssuanceSession::start_context();
+        start_context.expect().return_once(|| {
+            let mut pid_issuer = MockIssuanceSession::new();
+            pid_issuer.expect_accept().return_once(|| Ok(vec![vec![mdoc].into()]));
+
+            Ok((pid_issuer, vec![]))
+        });
+
+        wallet
+            .continue_pin_recovery(Url::parse(REDIRECT_URI).unwrap())
+            .await
+            .expect("Could not continue PIN recovery");
+
+        assert!(matches!(
+            wallet.pin_recovery_session,
+            Some(PinRecoverySession::PidReceived { ref issuer_signed, ref signing_key })
+                if *issuer_signed == expected_issuer_signed && *signing_key.verifying_key() == expected_public_key
+        ));
+
+        // The fresh PID should not be stored.
+        assert!(wallet.storage.get_mut().mdocs.list().is_empty());
+        assert!(wallet.is_locked());
+    }
+
+    #[tokio::test]
+    async fn test_continue_pin_recovery_error_session_state() {
+        let mut wallet = locked_wallet_with_recovery_pid().await;
+
+        let error = wallet
+            .continue_pin_recovery(Url::parse(REDIRECT_URI).unwrap())
+            .await
+            .expect_err("Continuing PIN recovery should have resulted in error");
+
+        assert_matches!(error, PinRecoveryError::SessionState);
+        assert!(matches!(
+            wallet.pin_recovery_session,
+            Some(PinRecoverySession::PidReceived { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_complete_pin_recovery() {
+        let mut wallet = locked_wallet_with_recovery_pid().await;
+
+        let old_pin_salt = wallet.registration.as_ref().unwrap().data.pin_salt.clone();
+        let Some(PinRecoverySession::PidReceived { ref signing_key, .. }) = wallet.pin_recovery_session else {
+            unreachable!();
+        };
+        let recovery_pid_pubkey = *signing_key.verifying_key();
+        let pid_key_identifier = wallet
+            .storage
+            .get_mut()
+            .fetch_unique_mdocs()
+            .await
+            .unwrap()
+            .remove(0)
+            .mdoc
+            .private_key_id()
+            .to_string();
+
+        let challenge = utils::random_bytes(32);
+        let challenge_response = challenge.clone();
+        wallet
+            .account_provider_client
+            .expect_instruction_challenge()
+            .return_once(move |_, _| Ok(challenge_response));
+
+        let hw_pubkey = wallet
+            .registration
+            .as_ref()
+            .unwrap()
+            .hw_privkey
+            .verifying_key()
+            .await
+            .unwrap();
+        let new_cert = Jwt::sign_with_sub(
+            &WalletCertificateClaims {
+                wallet_id: utils::random_string(32),
+                hw_pubkey: hw_pubkey.into(),
+                pin_pubkey_hash: utils::random_bytes(32),
+                version: 0,
+                iss: "wallet_unit_test".to_string(),
+                iat: jsonwebtoken::get_current_timestamp(),
+            },
+            &ACCOUNT_SERVER_KEYS.certificate_signing_key,
+        )
+        .await
+        .unwrap();
+        let result_claims = InstructionResultClaims {
+            result: new_cert.clone(),
+            iss: "wallet_unit_test".to_string(),
+            iat: jsonwebtoken::get_current_timestamp(),
+        };
+        let result = Jwt::sign_with_sub(&result_claims, &ACCOUNT_SERVER_KEYS.instruction_result_signing_key)
+            .await
+            .unwrap();
+
+        wallet
+            .account_provider_client
+            .expect_instruction()
+            .with(
+                eq(wallet.config_repository.config().account_server.base_url.clone()),
+                always(),
+            )
+            .return_once(move |_, instruction: Instruction<RecoverPin>| {
+                let recover_pin = instruction.instruction.dangerous_parse_unverified().unwrap().payload;
+                let new_pin_pubkey: VerifyingKey = recover_pin.pin_pubkey.0;
+
+                // The instruction should be signed with the new PIN key.
+                instruction
+                    .instruction
+                    .parse_and_verify(
+                        &challenge,
+                        SequenceNumberComparison::LargerThan(1),
+                        &hw_pubkey,
+                        &new_pin_pubkey,
+                    )
+                    .expect("Could not verify recover PIN instruction");
+
+                assert_eq!(recover_pin.pid_key_identifier, pid_key_identifier);
+                assert_ne!(recover_pin.pid_issuer_signed, recover_pin.recovery_pid_issuer_signed);
+
+                // The new PIN public key should be signed with the key of the recovery PID.
+                recovery_pid_pubkey
+                    .verify(
+                        &RecoverPin::recovery_pid_message(&new_pin_pubkey),
+                        &recover_pin.recovery_pid_signature.0,
+                    )
+                    .expect("Could not verify recovery PID signature");
+
+                Ok(result)
+            });
+
+        wallet
+            .complete_pin_recovery(NEW_PIN.to_string())
+            .await
+            .expect("Could not complete PIN recovery");
+
+        assert!(!wallet.is_locked());
+        assert!(wallet.pin_recovery_session.is_none());
+
+        // The new PIN salt and wallet certificate should be both in memory and in storage.
+        let registration_data = &wallet.registration.as_ref().unwrap().data;
+        assert_ne!(registration_data.pin_salt, old_pin_salt);
+        assert_eq!(registration_data.wallet_certificate.0, new_cert.0);
+
+        let stored_registration_data: RegistrationData = serde_json::from_str(
+            wallet
+                .storage
+                .get_mut()
+                .data
+                .get(<RegistrationData as KeyedData>::KEY)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(stored_registration_data.pin_salt, registration_data.pin_salt);
+    }
+
+    #[tokio::test]
+    async fn test_complete_pin_recovery_error_invalid_pin() {
+        let mut wallet = locked_wallet_with_recovery_pid().await;
+
+        let error = wallet
+            .complete_pin_recovery("123456".to_string())
+            .await
+            .expect_err("Completing PIN recovery should have resulted in error");
+
+        // The received PID should be kept, so that the user can choose another PIN.
+        assert_matches!(error, PinRecoveryError::InvalidPin(_));
+        assert!(matches!(
+            wallet.pin_recovery_session,
+            Some(PinRecoverySession::PidReceived { .. })
+        ));
+        assert!(wallet.is_locked());
+    }
+
+    #[tokio::test]
+    async fn test_complete_pin_recovery_error_validation() {
+        let mut wallet = locked_wallet_with_recovery_pid().await;
+        let old_registration_data = wallet.registration.as_ref().unwrap().data.clone();
+
+        wallet
+            .account_provider_client
+            .expect_instruction_challenge()
+            .return_once(|_, _| Ok(utils::random_bytes(32)));
+        wallet
+            .account_provider_client
+            .expect_instruction()
+            .return_once(|_, _: Instruction<RecoverPin>| {
+                Err(AccountProviderError::Response(AccountProviderResponseError::Account(
+                    AccountError::PinRecoveryValidation,
+                    None,
+                )))
+            });
+
+        let error = wallet
+            .complete_pin_recovery(NEW_PIN.to_string())
+            .await
+            .expect_err("Completing PIN recovery should have resulted in error");
+
+        assert_matches!(
+            error,
+            PinRecoveryError::Instruction(InstructionError::PinRecoveryValidation)
+        );
+        assert!(wallet.is_locked());
+
+        let registration_data = &wallet.registration.as_ref().unwrap().data;
+        assert_eq!(registration_data.pin_salt, old_registration_data.pin_salt);
+    }
+}
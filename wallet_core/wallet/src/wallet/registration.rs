@@ -3,7 +3,11 @@ use std::{borrow::Cow, error::Error};
 use tracing::{info, instrument};
 
 use platform_support::hw_keystore::PlatformEcdsaKey;
-use wallet_common::{account::messages::auth::Registration, jwt::JwtError, keys::StoredByIdentifier};
+use wallet_common::{
+    account::messages::auth::{Registration, RegistrationRestore},
+    jwt::JwtError,
+    keys::StoredByIdentifier,
+};
 
 use crate::{
     account_provider::{AccountProviderClient, AccountProviderError},
@@ -12,10 +16,13 @@ use crate::{
         key::{self as pin_key, PinKey},
         validation::{validate_pin, PinValidationError},
     },
-    storage::{RegistrationData, Storage, StorageError, StorageState},
+    storage::{RegistrationData, RestoreData, Storage, StorageError, StorageState},
 };
 
-use super::{Wallet, WalletRegistration};
+use super::{
+    backup::{self, BackupError, BackupPayload, WalletBackup},
+    Wallet, WalletRegistration,
+};
 
 const WALLET_KEY_ID: &str = "wallet";
 
@@ -64,6 +71,10 @@ pub enum WalletRegistrationError {
     PublicKeyMismatch,
     #[error("could not store registration certificate in database: {0}")]
     StoreCertificate(#[from] StorageError),
+    #[error("could not read backup: {0}")]
+    Backup(#[from] BackupError),
+    #[error("could not import backup contents into database: {0}")]
+    ImportBackup(#[source] StorageError),
 }
 
 impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS> {
@@ -84,6 +95,43 @@ impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS> {
 
     #[instrument(skip_all)]
     pub async fn register(&mut self, pin: String) -> Result<(), WalletRegistrationError>
+    where
+        CR: ConfigurationRepository,
+        S: Storage,
+        APC: AccountProviderClient,
+        PEK: PlatformEcdsaKey,
+    {
+        self.register_and_restore(pin, None).await
+    }
+
+    /// Registers the wallet and restores the contents of a backup that was created using [`Wallet::create_backup`].
+    /// The PIN should be the one of the wallet the backup was made with, as this is used to prove ownership of that
+    /// account to the Wallet Provider. The keys of the mdocs in the backup are then migrated to the new account.
+    #[instrument(skip_all)]
+    pub async fn register_with_backup(
+        &mut self,
+        pin: String,
+        backup: &WalletBackup,
+        recovery_secret: &str,
+    ) -> Result<(), WalletRegistrationError>
+    where
+        CR: ConfigurationRepository,
+        S: Storage,
+        APC: AccountProviderClient,
+        PEK: PlatformEcdsaKey,
+    {
+        info!("Decrypting backup");
+
+        let payload = backup.decrypt(recovery_secret)?;
+
+        self.register_and_restore(pin, Some(payload)).await
+    }
+
+    async fn register_and_restore(
+        &mut self,
+        pin: String,
+        backup: Option<BackupPayload>,
+    ) -> Result<(), WalletRegistrationError>
     where
         CR: ConfigurationRepository,
         S: Storage,
@@ -131,7 +179,25 @@ impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS> {
             .verifying_key()
             .await
             .map_err(|e| WalletRegistrationError::HardwarePublicKey(e.into()))?;
-        let registration_message = Registration::new_signed(&hw_privkey, &pin_key, &challenge)
+
+        // When restoring a backup, prove ownership of the old account by signing the challenge with its PIN key.
+        let restore = match backup.as_ref() {
+            Some(backup) => {
+                let old_pin_key = PinKey::new(&pin, &backup.registration.pin_salt);
+                let restore = RegistrationRestore::new_signed(
+                    backup.registration.wallet_certificate.clone(),
+                    &old_pin_key,
+                    &challenge,
+                )
+                .await
+                .map_err(WalletRegistrationError::Signing)?;
+
+                Some(restore)
+            }
+            None => None,
+        };
+
+        let registration_message = Registration::new_signed_with_restore(&hw_privkey, &pin_key, &challenge, restore)
             .await
             .map_err(WalletRegistrationError::Signing)?;
 
@@ -169,12 +235,24 @@ impl<CR, S, PEK, APC, DS, IS, MDS> Wallet<CR, S, PEK, APC, DS, IS, MDS> {
         };
         storage.insert_data(&data).await?;
 
+        // Store the contents of the backup first, so that importing them can be resumed on the next startup.
+        if let Some(backup) = backup {
+            let restore_data = RestoreData::new(&backup.contents).map_err(BackupError::from)?;
+            storage.insert_data(&restore_data).await?;
+        }
+
         // Keep the registration data in memory.
         self.registration = WalletRegistration { hw_privkey, data }.into();
 
         // Unlock the wallet after successful registration
         self.lock.unlock();
 
+        info!("Importing contents of restored backup, if any");
+
+        backup::complete_restore(storage)
+            .await
+            .map_err(WalletRegistrationError::ImportBackup)?;
+
         Ok(())
     }
 }
@@ -189,12 +267,14 @@ mod tests {
 
     use crate::{
         account_provider::AccountProviderResponseError,
+        storage::{BackupContents, WalletEvent},
         wallet::test::{FallibleSoftwareEcdsaKey, ACCOUNT_SERVER_KEYS},
     };
 
     use super::{super::test::WalletWithMocks, *};
 
     const PIN: &str = "051097";
+    const RECOVERY_SECRET: &str = "0123456789abcdefghijklmn";
 
     #[tokio::test]
     async fn test_wallet_register_success() {
@@ -265,6 +345,99 @@ mod tests {
         assert_eq!(stored_registration.wallet_certificate.0, cert.0);
     }
 
+    #[tokio::test]
+    async fn test_wallet_register_with_backup_success() {
+        // Prepare an unregistered wallet.
+        let mut wallet = WalletWithMocks::new_unregistered().await;
+
+        // Create a backup containing a history event, made by a wallet with another PIN salt.
+        let old_pin_salt = pin_key::new_pin_salt();
+        let old_cert = WalletWithMocks::valid_certificate().await;
+        let event = WalletEvent::new_issuance(Default::default());
+        let payload = BackupPayload {
+            registration: RegistrationData {
+                pin_salt: old_pin_salt.clone(),
+                wallet_certificate: old_cert.clone(),
+            },
+            contents: BackupContents {
+                events: vec![event.clone()],
+                ..Default::default()
+            },
+        };
+        let backup = WalletBackup::encrypt(&payload, RECOVERY_SECRET).unwrap();
+
+        let challenge = utils::random_bytes(32);
+        let challenge_response = challenge.clone();
+
+        wallet
+            .account_provider_client
+            .expect_registration_challenge()
+            .return_once(|_| Ok(challenge_response));
+
+        // The account server should receive the old certificate, signed using the PIN key of the old registration.
+        let cert = WalletWithMocks::valid_certificate().await;
+        let cert_response = cert.clone();
+        let old_pin_pubkey = PinKey::new(PIN, &old_pin_salt).verifying_key().unwrap();
+
+        wallet
+            .account_provider_client
+            .expect_register()
+            .return_once(move |_, registration_signed| {
+                let registration = registration_signed
+                    .dangerous_parse_unverified()
+                    .expect("Could not parse registration message");
+
+                let restore = registration
+                    .payload
+                    .restore
+                    .expect("Registration message should contain restore");
+
+                assert_eq!(restore.certificate.0, old_cert.0);
+                restore
+                    .verify(&registration.challenge, &old_pin_pubkey)
+                    .expect("Could not verify restore signature");
+
+                Ok(cert_response)
+            });
+
+        wallet
+            .register_with_backup(PIN.to_string(), &backup, RECOVERY_SECRET)
+            .await
+            .expect("Could not register wallet with backup");
+
+        // The wallet should now be registered and unlocked, using the new certificate.
+        assert!(wallet.has_registration());
+        assert!(!wallet.is_locked());
+        assert_eq!(wallet.registration.as_ref().unwrap().data.wallet_certificate.0, cert.0);
+
+        // The contents of the backup should be imported.
+        let storage = wallet.storage.get_mut();
+        assert_eq!(storage.event_log, vec![event]);
+        assert!(storage.fetch_data::<RestoreData>().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wallet_register_with_backup_error_decryption() {
+        let mut wallet = WalletWithMocks::new_unregistered().await;
+
+        let payload = BackupPayload {
+            registration: RegistrationData {
+                pin_salt: pin_key::new_pin_salt(),
+                wallet_certificate: "thisisjwt".to_string().into(),
+            },
+            contents: Default::default(),
+        };
+        let backup = WalletBackup::encrypt(&payload, RECOVERY_SECRET).unwrap();
+
+        let error = wallet
+            .register_with_backup(PIN.to_string(), &backup, "some_other_recovery_secret")
+            .await
+            .expect_err("Registering with backup should have resulted in error");
+
+        assert_matches!(error, WalletRegistrationError::Backup(BackupError::Decryption));
+        assert!(!wallet.has_registration());
+    }
+
     #[tokio::test]
     async fn test_wallet_register_error_already_registered() {
         let mut wallet = WalletWithMocks::new_registered_and_unlocked().await;
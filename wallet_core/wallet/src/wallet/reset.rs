@@ -33,6 +33,7 @@ where
             };
 
             self.issuance_session.take();
+            self.pin_recovery_session.take();
             self.disclosure_session.take();
 
             // The wallet should be locked in its initial state.
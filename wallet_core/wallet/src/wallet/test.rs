@@ -9,9 +9,9 @@ use nl_wallet_mdoc::{
     holder::Mdoc, server_keys::KeyPair, unsigned::UnsignedMdoc, utils::issuer_auth::IssuerRegistration, IssuerSigned,
 };
 use openid4vc::mock::MockIssuanceSession;
-use platform_support::hw_keystore::PlatformEcdsaKey;
+use platform_support::hw_keystore::{AttestedEcdsaKey, PlatformEcdsaKey};
 use wallet_common::{
-    account::messages::auth::{WalletCertificate, WalletCertificateClaims},
+    account::messages::auth::{PlatformAttestation, WalletCertificate, WalletCertificateClaims},
     generator::TimeGenerator,
     jwt::Jwt,
     keys::{software::SoftwareEcdsaKey, EcdsaKey, SecureEcdsaKey, StoredByIdentifier, WithIdentifier},
@@ -116,12 +116,22 @@ pub async fn create_full_pid_mdoc_unauthenticated() -> Mdoc {
 /// Generates a valid `Mdoc`, based on an `UnsignedMdoc` and issuer key.
 pub async fn mdoc_from_unsigned(unsigned_mdoc: UnsignedMdoc, issuer_key: &IssuerKey) -> Mdoc {
     let private_key_id = utils::random_string(16);
-    let mdoc_public_key = (&SoftwareEcdsaKey::new_random(private_key_id.clone())
+    let public_key = SoftwareEcdsaKey::new_random(private_key_id.clone())
         .verifying_key()
         .await
-        .unwrap())
-        .try_into()
         .unwrap();
+
+    mdoc_from_unsigned_with_key(unsigned_mdoc, issuer_key, private_key_id, &public_key).await
+}
+
+/// Generates a valid `Mdoc`, based on an `UnsignedMdoc` and issuer key, that is bound to an existing key.
+pub async fn mdoc_from_unsigned_with_key(
+    unsigned_mdoc: UnsignedMdoc,
+    issuer_key: &IssuerKey,
+    private_key_id: String,
+    public_key: &VerifyingKey,
+) -> Mdoc {
+    let mdoc_public_key = public_key.try_into().unwrap();
     let issuer_signed = IssuerSigned::sign(unsigned_mdoc, mdoc_public_key, &issuer_key.issuance_key)
         .await
         .unwrap();
@@ -131,6 +141,7 @@ pub async fn mdoc_from_unsigned(unsigned_mdoc: UnsignedMdoc, issuer_key: &Issuer
         issuer_signed,
         &TimeGenerator,
         &[(&issuer_key.trust_anchor.owned_trust_anchor).into()],
+        &[],
     )
     .unwrap()
 }
@@ -174,6 +185,23 @@ impl From<SoftwareEcdsaKey> for FallibleSoftwareEcdsaKey {
 
 impl PlatformEcdsaKey for FallibleSoftwareEcdsaKey {}
 
+impl AttestedEcdsaKey for FallibleSoftwareEcdsaKey {
+    type AttestationError = <SoftwareEcdsaKey as EcdsaKey>::Error;
+
+    /// Returns a fake Android attestation, of which the certificate chain
+    /// only contains the hash of the challenge, so that tests can check it.
+    async fn attest(&self, challenge: &[u8]) -> Result<PlatformAttestation, Self::AttestationError> {
+        let next_error = self.next_private_key_error.lock().take();
+
+        match next_error {
+            None => Ok(PlatformAttestation::Android {
+                certificate_chain: vec![utils::sha256(challenge)],
+            }),
+            Some(error) => Err(error),
+        }
+    }
+}
+
 impl StoredByIdentifier for FallibleSoftwareEcdsaKey {
     type Error = <SoftwareEcdsaKey as StoredByIdentifier>::Error;
 
@@ -274,7 +302,7 @@ impl WalletWithMocks {
         // on `Wallet` and set the wallet to unlocked.
         wallet.storage.get_mut().state = StorageState::Opened;
         wallet.storage.get_mut().data.insert(
-            <RegistrationData as KeyedData>::KEY,
+            <RegistrationData as KeyedData>::KEY.to_string(),
             serde_json::to_string(&registration_data).unwrap(),
         );
         wallet.registration = WalletRegistration {
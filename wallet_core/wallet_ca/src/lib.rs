@@ -1,16 +1,81 @@
 use std::{fs, io, path::Path};
 
 use anyhow::{anyhow, Result};
+use clap::ValueEnum;
 use clio::CachedInput;
+use indexmap::IndexMap;
 use p256::{
     ecdsa::SigningKey,
     pkcs8::{DecodePrivateKey, EncodePrivateKey},
 };
 use pem::{EncodeConfig, LineEnding, Pem};
 
-use nl_wallet_mdoc::{server_keys::KeyPair, utils::x509::Certificate};
+use nl_wallet_mdoc::{
+    server_keys::KeyPair,
+    utils::{
+        reader_auth::{AuthorizedAttribute, AuthorizedMdoc, AuthorizedNamespace, ReaderRegistration},
+        x509::Certificate,
+    },
+    MDL_DATA_ELEMENTS, MDL_DOCTYPE, MDL_NAMESPACE,
+};
+use wallet_common::revocation_list::DerCertificateRevocationList;
+
+/// Predefined sets of attributes that a reader can be authorized to request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AuthorizedMdocTemplate {
+    /// All data elements of the ISO mDL
+    Mdl,
+    /// Only the data elements of the ISO mDL needed for age verification
+    #[value(name = "mdl-age-over-18")]
+    MdlAgeOver18,
+}
+
+impl AuthorizedMdocTemplate {
+    pub fn doc_type(&self) -> &'static str {
+        match self {
+            Self::Mdl | Self::MdlAgeOver18 => MDL_DOCTYPE,
+        }
+    }
+
+    pub fn authorized_mdoc(&self) -> AuthorizedMdoc {
+        let attributes = match self {
+            Self::Mdl => MDL_DATA_ELEMENTS
+                .iter()
+                .map(|element| element.identifier)
+                .chain(["age_over_18"])
+                .collect::<Vec<_>>(),
+            Self::MdlAgeOver18 => vec!["portrait", "age_over_18"],
+        };
+
+        AuthorizedMdoc(IndexMap::from([(
+            MDL_NAMESPACE.to_string(),
+            AuthorizedNamespace(
+                attributes
+                    .into_iter()
+                    .map(|attribute| (attribute.to_string(), AuthorizedAttribute {}))
+                    .collect(),
+            ),
+        )]))
+    }
+
+    /// Add the attributes of this template to those already authorized in `reader_registration`.
+    pub fn apply(&self, reader_registration: &mut ReaderRegistration) {
+        let AuthorizedMdoc(namespaces) = reader_registration
+            .attributes
+            .entry(self.doc_type().to_string())
+            .or_insert_with(|| AuthorizedMdoc(IndexMap::new()));
 
-fn read_certificate(input: CachedInput) -> Result<Certificate> {
+        let AuthorizedMdoc(template_namespaces) = self.authorized_mdoc();
+        for (namespace, AuthorizedNamespace(template_attributes)) in template_namespaces {
+            let AuthorizedNamespace(attributes) = namespaces
+                .entry(namespace)
+                .or_insert_with(|| AuthorizedNamespace(IndexMap::new()));
+            attributes.extend(template_attributes);
+        }
+    }
+}
+
+pub fn read_certificate(input: CachedInput) -> Result<Certificate> {
     let input_string = io::read_to_string(input)?;
     let crt = Certificate::from_pem(&input_string)?;
     Ok(crt)
@@ -45,6 +110,20 @@ pub fn write_key_pair(key_pair: KeyPair, file_prefix: &str, force: bool) -> Resu
     Ok(())
 }
 
+pub fn write_crl(crl: &DerCertificateRevocationList, file_prefix: &str, force: bool) -> Result<()> {
+    let crl_file = format!("{}.crl.pem", file_prefix);
+    let crl_path = Path::new(&crl_file);
+    assert_not_exists(crl_path, force)?;
+
+    let crl_pem = Pem::new("X509 CRL", crl.der_bytes.as_slice());
+    fs::write(
+        crl_path,
+        pem::encode_config(&crl_pem, EncodeConfig::new().set_line_ending(LineEnding::LF)),
+    )?;
+    eprintln!("CRL stored in '{}'", crl_path.display());
+    Ok(())
+}
+
 fn assert_not_exists(file_path: &Path, force: bool) -> Result<()> {
     if file_path.exists() && !force {
         return Err(anyhow!("Target file '{}' already exists", file_path.display()));
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use clap::{Parser, Subcommand};
 use clio::CachedInput;
 
@@ -7,12 +7,12 @@ use nl_wallet_mdoc::{
     server_keys::KeyPair,
     utils::{issuer_auth::IssuerRegistration, reader_auth::ReaderRegistration, x509::CertificateConfiguration},
 };
-use wallet_ca::{read_key_pair, write_key_pair};
+use wallet_ca::{read_certificate, read_key_pair, write_crl, write_key_pair, AuthorizedMdocTemplate};
 
-/// Generate private keys and certificates
+/// Generate private keys, certificates and certificate revocation lists
 ///
-/// NOTE: Do NOT use in production environments, as the certificates lifetime is incredibly large, and no revocation is
-/// implemented.
+/// NOTE: Do NOT use in production environments, as the certificates lifetime is incredibly large, and the CRLs are
+/// only meant for testing revocation.
 #[derive(Parser)]
 #[command(author, version, about, long_about)]
 struct Cli {
@@ -54,6 +54,9 @@ enum Command {
         /// Prefix to use for the generated files: <FILE_PREFIX>.key.pem and <FILE_PREFIX>.crt.pem
         #[arg(short, long)]
         file_prefix: String,
+        /// URI of a CRL distribution point to include in the certificate, can be specified multiple times
+        #[arg(long)]
+        crl_distribution_point: Vec<String>,
         /// Duration for which the certificate will be valid
         #[arg(short, long, default_value = "365")]
         days: u32,
@@ -75,9 +78,15 @@ enum Command {
         /// Path to Reader Authentication file in JSON format
         #[arg(short, long, value_parser)]
         reader_auth_file: CachedInput,
+        /// Template of attributes to add to those in the Reader Authentication file, can be specified multiple times
+        #[arg(short = 't', long, value_enum)]
+        attributes_template: Vec<AuthorizedMdocTemplate>,
         /// Prefix to use for the generated files: <FILE_PREFIX>.key.pem and <FILE_PREFIX>.crt.pem
         #[arg(short, long)]
         file_prefix: String,
+        /// URI of a CRL distribution point to include in the certificate, can be specified multiple times
+        #[arg(long)]
+        crl_distribution_point: Vec<String>,
         /// Duration for which the certificate will be valid
         #[arg(short, long, default_value = "365")]
         days: u32,
@@ -85,10 +94,31 @@ enum Command {
         #[arg(long, default_value = "false")]
         force: bool,
     },
+    /// Generate a Certificate Revocation List (CRL), signed by the CA, that revokes the specified certificates
+    Crl {
+        /// Path to the CA key file in PEM format
+        #[arg(short = 'k', long, value_parser)]
+        ca_key_file: CachedInput,
+        /// Path to the CA certificate file in PEM format
+        #[arg(short = 'c', long, value_parser)]
+        ca_crt_file: CachedInput,
+        /// Path to a certificate file in PEM format to revoke, can be specified multiple times
+        #[arg(short, long, value_parser)]
+        revoked_crt_file: Vec<CachedInput>,
+        /// Prefix to use for the generated file: <FILE_PREFIX>.crl.pem
+        #[arg(short, long)]
+        file_prefix: String,
+        /// Duration after which the next CRL will be issued
+        #[arg(short, long, default_value = "7")]
+        days: u32,
+        /// Overwrite existing files
+        #[arg(long, default_value = "false")]
+        force: bool,
+    },
 }
 
 impl Command {
-    fn get_certificate_configuration(days: u32) -> Result<CertificateConfiguration> {
+    fn get_validity(days: u32) -> (DateTime<Utc>, DateTime<Utc>) {
         let not_before = Utc::now();
         let not_after = not_before
             .checked_add_signed(Duration::days(days as i64))
@@ -96,9 +126,18 @@ impl Command {
         if not_after <= not_before {
             panic!("`valid_for` must be a positive duration");
         }
+        (not_before, not_after)
+    }
+
+    fn get_certificate_configuration(
+        days: u32,
+        crl_distribution_points: Vec<String>,
+    ) -> Result<CertificateConfiguration> {
+        let (not_before, not_after) = Self::get_validity(days);
         let configuration = CertificateConfiguration {
             not_before: Some(not_before),
             not_after: Some(not_after),
+            crl_distribution_points,
         };
         Ok(configuration)
     }
@@ -112,7 +151,7 @@ impl Command {
                 days,
                 force,
             } => {
-                let configuration = Self::get_certificate_configuration(days)?;
+                let configuration = Self::get_certificate_configuration(days, vec![])?;
                 let ca = KeyPair::generate_ca(&common_name, configuration)?;
                 write_key_pair(ca, &file_prefix, force)?;
                 Ok(())
@@ -123,6 +162,7 @@ impl Command {
                 common_name,
                 issuer_auth_file,
                 file_prefix,
+                crl_distribution_point,
                 days,
                 force,
             } => {
@@ -131,7 +171,7 @@ impl Command {
                 let key_pair = ca.generate(
                     &common_name,
                     issuer_registration.into(),
-                    Self::get_certificate_configuration(days)?,
+                    Self::get_certificate_configuration(days, crl_distribution_point)?,
                 )?;
                 write_key_pair(key_pair, &file_prefix, force)?;
                 Ok(())
@@ -141,20 +181,43 @@ impl Command {
                 ca_crt_file,
                 common_name,
                 reader_auth_file,
+                attributes_template,
                 file_prefix,
+                crl_distribution_point,
                 days,
                 force,
             } => {
                 let ca = read_key_pair(ca_key_file, ca_crt_file)?;
-                let reader_registration: ReaderRegistration = serde_json::from_reader(reader_auth_file)?;
+                let mut reader_registration: ReaderRegistration = serde_json::from_reader(reader_auth_file)?;
+                attributes_template
+                    .iter()
+                    .for_each(|template| template.apply(&mut reader_registration));
                 let key_pair = ca.generate(
                     &common_name,
                     reader_registration.into(),
-                    Self::get_certificate_configuration(days)?,
+                    Self::get_certificate_configuration(days, crl_distribution_point)?,
                 )?;
                 write_key_pair(key_pair, &file_prefix, force)?;
                 Ok(())
             }
+            Crl {
+                ca_key_file,
+                ca_crt_file,
+                revoked_crt_file,
+                file_prefix,
+                days,
+                force,
+            } => {
+                let ca = read_key_pair(ca_key_file, ca_crt_file)?;
+                let revoked = revoked_crt_file
+                    .into_iter()
+                    .map(read_certificate)
+                    .collect::<Result<Vec<_>>>()?;
+                let (this_update, next_update) = Self::get_validity(days);
+                let crl = ca.generate_crl(&revoked, this_update, next_update)?;
+                write_crl(&crl, &file_prefix, force)?;
+                Ok(())
+            }
         }
     }
 }
@@ -22,7 +22,15 @@ use predicates::{
 use time::{Duration, OffsetDateTime};
 use x509_parser::oid_registry::OID_KEY_TYPE_EC_PUBLIC_KEY;
 
-use nl_wallet_mdoc::utils::{issuer_auth::IssuerRegistration, reader_auth::ReaderRegistration};
+use nl_wallet_mdoc::{
+    utils::{
+        issuer_auth::IssuerRegistration,
+        reader_auth::ReaderRegistration,
+        x509::{Certificate, MdocCertificateExtension},
+    },
+    MDL_DOCTYPE,
+};
+use wallet_ca::AuthorizedMdocTemplate;
 
 trait RangeCompare<Offset> {
     /// Compare [`self`] to the range of [`other`] +/- the [`offset`].
@@ -59,6 +67,11 @@ Key stored in '{}'"#,
     Ok(result)
 }
 
+fn predicate_successfully_generated_crl(crl: &Path) -> Result<RegexPredicate> {
+    let result = predicate::str::is_match(format!("CRL stored in '{}'", crl.display()))?;
+    Ok(result)
+}
+
 fn predicate_file_already_exists(path: &Path) -> Result<RegexPredicate> {
     let result = predicate::str::is_match(format!("Error: Target file '{}' already exists\n", path.display()))?;
     Ok(result)
@@ -137,6 +150,7 @@ trait CommandExtension {
         rp_auth_json: &Path,
         file_prefix: &Path,
     ) -> &mut Self;
+    fn generate_crl(&mut self, ca_crt: &Path, ca_key: &Path, revoked_crts: &[&Path], file_prefix: &Path) -> &mut Self;
 }
 
 impl CommandExtension for Command {
@@ -187,6 +201,20 @@ impl CommandExtension for Command {
             .arg("--reader-auth-file")
             .arg(rp_auth_json)
     }
+
+    fn generate_crl(&mut self, ca_crt: &Path, ca_key: &Path, revoked_crts: &[&Path], file_prefix: &Path) -> &mut Self {
+        self.arg("crl")
+            .arg("--ca-key-file")
+            .arg(ca_key)
+            .arg("--ca-crt-file")
+            .arg(ca_crt)
+            .arg("--file-prefix")
+            .arg(file_prefix);
+        for revoked_crt in revoked_crts {
+            self.arg("--revoked-crt-file").arg(revoked_crt);
+        }
+        self
+    }
 }
 
 fn keypair_paths(temp: &TempDir, prefix: &str) -> (ChildPath, ChildPath, ChildPath) {
@@ -573,3 +601,125 @@ fn missing_input_files() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn generating_crl() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    let (ca_prefix, ca_crt, ca_key) = keypair_paths(&temp, "test-ca");
+
+    Command::cargo_bin("wallet_ca")?
+        .generate_ca(&ca_prefix)
+        .assert()
+        .success();
+
+    // Generate an mdl certificate that refers to a CRL distribution point
+    let (mdl_prefix, mdl_crt, _mdl_key) = keypair_paths(&temp, "test-mdl");
+    let issuer_auth_json = temp.child("test-issuer-auth.json");
+    issuer_auth_json.write_str(&serde_json::to_string(&IssuerRegistration::new_mock())?)?;
+
+    Command::cargo_bin("wallet_ca")?
+        .generate_mdl_crt(&ca_crt, &ca_key, &issuer_auth_json, &mdl_prefix)
+        .arg("--crl-distribution-point")
+        .arg("https://example.com/test-ca.crl")
+        .assert()
+        .success();
+
+    let mdl_pem_bytes = std::fs::read(&mdl_crt)?;
+    let (_, mdl_pem) = x509_parser::pem::parse_x509_pem(&mdl_pem_bytes)?;
+    let mdl_serial = mdl_pem.parse_x509()?.raw_serial().to_vec();
+    assert_eq!(
+        Certificate::from(mdl_pem.contents.as_slice()).crl_distribution_points()?,
+        vec!["https://example.com/test-ca.crl".to_string()]
+    );
+
+    // Generate a CRL that revokes the mdl certificate
+    let crl_prefix = temp.child("test-ca");
+    let crl_file = temp.child("test-ca.crl.pem");
+
+    Command::cargo_bin("wallet_ca")?
+        .generate_crl(&ca_crt, &ca_key, &[&mdl_crt], &crl_prefix)
+        .arg("--days=3")
+        .assert()
+        .success()
+        .stderr(predicate_successfully_generated_crl(&crl_file)?);
+
+    // Read the CRL and verify its PEM label, issuer, next update and revoked certificate
+    let crl_pem_bytes = std::fs::read(&crl_file)?;
+    let (_, crl_pem) = x509_parser::pem::parse_x509_pem(&crl_pem_bytes)?;
+    assert_eq!(crl_pem.label, "X509 CRL");
+    let (_, crl) = x509_parser::parse_x509_crl(&crl_pem.contents)?;
+
+    let ca_pem_bytes = std::fs::read(&ca_crt)?;
+    let (_, ca_pem) = x509_parser::pem::parse_x509_pem(&ca_pem_bytes)?;
+    assert_eq!(crl.issuer(), ca_pem.parse_x509()?.subject());
+
+    let next_update = crl.next_update().unwrap().to_datetime();
+    assert_eq!(
+        next_update.cmp_range(&(OffsetDateTime::now_utc() + Duration::days(3)), Duration::minutes(1)),
+        Ordering::Equal
+    );
+
+    itertools::assert_equal(
+        crl.iter_revoked_certificates().map(|revoked| revoked.raw_serial()),
+        vec![mdl_serial.as_slice()],
+    );
+
+    // Regenerating the CRL should fail, unless forced
+    Command::cargo_bin("wallet_ca")?
+        .generate_crl(&ca_crt, &ca_key, &[], &crl_prefix)
+        .assert()
+        .failure()
+        .stderr(predicate_file_already_exists(&crl_file)?);
+
+    Command::cargo_bin("wallet_ca")?
+        .generate_crl(&ca_crt, &ca_key, &[], &crl_prefix)
+        .arg("--force")
+        .assert()
+        .success();
+
+    // Explicitly close the temp folder, for better error reporting
+    temp.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn generating_rp_auth_with_attributes_template() -> Result<()> {
+    let temp = assert_fs::TempDir::new()?;
+    let (ca_prefix, ca_crt, ca_key) = keypair_paths(&temp, "test-ca");
+    let (rp_auth_prefix, rp_auth_crt, rp_auth_key) = keypair_paths(&temp, "test-reader-auth");
+    let rp_auth_json = temp.child("test-reader-auth.json");
+
+    Command::cargo_bin("wallet_ca")?
+        .generate_ca(&ca_prefix)
+        .assert()
+        .success();
+
+    // Generate reader-auth JSON input file, which already contains attributes of other doctypes
+    let reader_registration = ReaderRegistration::new_mock();
+    rp_auth_json.write_str(&serde_json::to_string(&reader_registration)?)?;
+
+    Command::cargo_bin("wallet_ca")?
+        .generate_rp_auth_crt(&ca_crt, &ca_key, &rp_auth_json, &rp_auth_prefix)
+        .arg("--attributes-template")
+        .arg("mdl-age-over-18")
+        .assert()
+        .success()
+        .stderr(predicate_successfully_generated(&rp_auth_crt, &rp_auth_key)?);
+
+    // Read the reader registration from the certificate and verify that the template was added to it
+    let rp_auth_pem = std::fs::read_to_string(&rp_auth_crt)?;
+    let generated_registration = ReaderRegistration::from_certificate(&Certificate::from_pem(&rp_auth_pem)?)?.unwrap();
+
+    let mut expected_registration = reader_registration;
+    expected_registration.attributes.insert(
+        MDL_DOCTYPE.to_string(),
+        AuthorizedMdocTemplate::MdlAgeOver18.authorized_mdoc(),
+    );
+    assert_eq!(generated_registration, expected_registration);
+
+    // Explicitly close the temp folder, for better error reporting
+    temp.close()?;
+
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use futures::{try_join, TryFutureExt};
+use p256::ecdsa::{signature::Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use serde_with::{base64::Base64, serde_as};
 
 use crate::{
     account::{
         errors::{Error, Result},
-        serialization::DerVerifyingKey,
+        serialization::{DerSignature, DerVerifyingKey},
         signed::SignedDouble,
     },
     jwt::{Jwt, JwtSubject},
@@ -26,6 +27,41 @@ pub struct Challenge {
 pub struct Registration {
     pub pin_pubkey: DerVerifyingKey,
     pub hw_pubkey: DerVerifyingKey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restore: Option<RegistrationRestore>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_attestation: Option<PlatformAttestation>,
+}
+
+/// Attestation by the platform of the device that the hardware key was generated in secure hardware, by a genuine
+/// instance of the Wallet app. This is optionally included in a [`Registration`] and always in a [`DeviceLink`], in
+/// which case the challenge referred to below is the device link challenge.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "platform", rename_all = "snake_case")]
+pub enum PlatformAttestation {
+    /// An Android Key Attestation certificate chain of the hardware key, starting with the leaf certificate.
+    /// The attestation challenge of the leaf certificate should be the SHA-256 hash of the registration challenge.
+    Android {
+        #[serde_as(as = "Vec<Base64>")]
+        certificate_chain: Vec<Vec<u8>>,
+    },
+    /// An Apple App Attest attestation object. The client data hash it was generated with should be the SHA-256 hash
+    /// of the registration challenge, concatenated with the DER encoded hardware public key.
+    Apple {
+        #[serde_as(as = "Base64")]
+        attestation_object: Vec<u8>,
+    },
+}
+
+/// Included in a [`Registration`] when the wallet is restored from a backup. This proves ownership of the account
+/// the backup was made with, so that the Wallet Provider can migrate the keys of that account to the new one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistrationRestore {
+    /// The wallet certificate of the account the backup was made with.
+    pub certificate: WalletCertificate,
+    /// Signature over the registration challenge, made with the PIN key of the account the backup was made with.
+    pub pin_signature: DerSignature,
 }
 
 impl Registration {
@@ -33,6 +69,25 @@ impl Registration {
         hw_privkey: &impl SecureEcdsaKey,
         pin_privkey: &impl EphemeralEcdsaKey,
         challenge: &[u8],
+    ) -> Result<SignedDouble<Registration>> {
+        Self::new_signed_with_restore(hw_privkey, pin_privkey, challenge, None).await
+    }
+
+    pub async fn new_signed_with_restore(
+        hw_privkey: &impl SecureEcdsaKey,
+        pin_privkey: &impl EphemeralEcdsaKey,
+        challenge: &[u8],
+        restore: Option<RegistrationRestore>,
+    ) -> Result<SignedDouble<Registration>> {
+        Self::new_signed_with_platform_attestation(hw_privkey, pin_privkey, challenge, restore, None).await
+    }
+
+    pub async fn new_signed_with_platform_attestation(
+        hw_privkey: &impl SecureEcdsaKey,
+        pin_privkey: &impl EphemeralEcdsaKey,
+        challenge: &[u8],
+        restore: Option<RegistrationRestore>,
+        platform_attestation: Option<PlatformAttestation>,
     ) -> Result<SignedDouble<Registration>> {
         let (pin_pubkey, hw_pubkey) = try_join!(
             pin_privkey.verifying_key().map_err(|e| Error::VerifyingKey(e.into())),
@@ -43,6 +98,75 @@ impl Registration {
             Registration {
                 pin_pubkey: pin_pubkey.into(),
                 hw_pubkey: hw_pubkey.into(),
+                restore,
+                platform_attestation,
+            },
+            challenge,
+            0,
+            hw_privkey,
+            pin_privkey,
+        )
+        .await
+    }
+}
+
+impl RegistrationRestore {
+    pub async fn new_signed(
+        certificate: WalletCertificate,
+        old_pin_privkey: &impl EphemeralEcdsaKey,
+        challenge: &[u8],
+    ) -> Result<Self> {
+        let pin_signature = old_pin_privkey
+            .try_sign(challenge)
+            .await
+            .map_err(|err| Error::Signing(Box::new(err)))?
+            .into();
+
+        Ok(RegistrationRestore {
+            certificate,
+            pin_signature,
+        })
+    }
+
+    /// Verify that the challenge was signed with the PIN key of the account the backup was made with.
+    pub fn verify(&self, challenge: &[u8], old_pin_pubkey: &VerifyingKey) -> Result<()> {
+        old_pin_pubkey.verify(challenge, &self.pin_signature.0)?;
+
+        Ok(())
+    }
+}
+
+/// Sent by a new device to link itself to an existing wallet account. The challenge is obtained from a device that is
+/// already linked to the account, by scanning a QR code that contains the challenge and the wallet certificate of that
+/// device. The message is signed with the hardware key of the new device and the PIN key of the account.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeviceLink {
+    pub hw_pubkey: DerVerifyingKey,
+    /// The wallet certificate of the device that started linking.
+    pub certificate: WalletCertificate,
+    /// Attestation of the hardware key of the new device, bound to the device link challenge. Contrary to a
+    /// [`Registration`], this is mandatory, as the new device gets access to the keys of the existing wallet user.
+    pub platform_attestation: PlatformAttestation,
+}
+
+impl DeviceLink {
+    pub async fn new_signed(
+        certificate: WalletCertificate,
+        platform_attestation: PlatformAttestation,
+        hw_privkey: &impl SecureEcdsaKey,
+        pin_privkey: &impl EphemeralEcdsaKey,
+        challenge: &[u8],
+    ) -> Result<SignedDouble<DeviceLink>> {
+        let hw_pubkey = hw_privkey
+            .verifying_key()
+            .await
+            .map_err(|e| Error::VerifyingKey(e.into()))?;
+
+        SignedDouble::sign(
+            DeviceLink {
+                hw_pubkey: hw_pubkey.into(),
+                certificate,
+                platform_attestation,
             },
             challenge,
             0,
@@ -109,4 +233,110 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn registration_restore() -> Result<()> {
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+        let old_pin_privkey = SigningKey::random(&mut OsRng);
+
+        let challenge = b"challenge";
+
+        // wallet includes proof of ownership of the old account in the registration message
+        let restore =
+            RegistrationRestore::new_signed("old_certificate".to_string().into(), &old_pin_privkey, challenge).await?;
+        let msg = Registration::new_signed_with_restore(&hw_privkey, &pin_privkey, challenge, Some(restore)).await?;
+
+        let unverified = msg.dangerous_parse_unverified()?;
+        let restore = unverified.payload.restore.expect("registration should contain restore");
+
+        // wallet provider verifies the challenge signature using the PIN public key of the old account
+        restore.verify(challenge, old_pin_privkey.verifying_key())?;
+        restore
+            .verify(challenge, pin_privkey.verifying_key())
+            .expect_err("restore should not verify against a different PIN key");
+        restore
+            .verify(b"other_challenge", old_pin_privkey.verifying_key())
+            .expect_err("restore should not verify against a different challenge");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn device_link() -> Result<()> {
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let challenge = b"challenge";
+
+        // the new device signs the challenge it scanned with its own hardware key and the PIN key of the account
+        let platform_attestation = PlatformAttestation::Apple {
+            attestation_object: b"attestation".to_vec(),
+        };
+        let msg = DeviceLink::new_signed(
+            "certificate".to_string().into(),
+            platform_attestation,
+            &hw_privkey,
+            &pin_privkey,
+            challenge,
+        )
+        .await?;
+
+        let unverified = msg.dangerous_parse_unverified()?;
+        assert_eq!(unverified.payload.hw_pubkey.0, *hw_privkey.verifying_key());
+        assert!(matches!(
+            unverified.payload.platform_attestation,
+            PlatformAttestation::Apple { attestation_object } if attestation_object == b"attestation"
+        ));
+
+        // wallet provider verifies the message using the new hardware key and the stored PIN key
+        msg.parse_and_verify(
+            challenge,
+            SequenceNumberComparison::EqualTo(0),
+            &unverified.payload.hw_pubkey.0,
+            pin_privkey.verifying_key(),
+        )?;
+        msg.parse_and_verify(
+            b"other_challenge",
+            SequenceNumberComparison::EqualTo(0),
+            &unverified.payload.hw_pubkey.0,
+            pin_privkey.verifying_key(),
+        )
+        .expect_err("device link should not verify against a different challenge");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn registration_platform_attestation() -> Result<()> {
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let challenge = b"challenge";
+
+        let platform_attestation = PlatformAttestation::Android {
+            certificate_chain: vec![b"leaf".to_vec(), b"root".to_vec()],
+        };
+        let msg = Registration::new_signed_with_platform_attestation(
+            &hw_privkey,
+            &pin_privkey,
+            challenge,
+            None,
+            Some(platform_attestation),
+        )
+        .await?;
+
+        let unverified = msg.dangerous_parse_unverified()?;
+        let platform_attestation = unverified
+            .payload
+            .platform_attestation
+            .expect("registration should contain platform attestation");
+
+        assert!(matches!(
+            platform_attestation,
+            PlatformAttestation::Android { certificate_chain } if certificate_chain == [b"leaf".to_vec(), b"root".to_vec()]
+        ));
+
+        Ok(())
+    }
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
@@ -16,7 +17,12 @@ pub enum AccountError {
     IncorrectPin(IncorrectPinData),
     PinTimeout(PinTimeoutData),
     AccountBlocked,
+    AccountSuspended,
+    AccountRevoked,
     InstructionValidation,
+    PinRecoveryValidation,
+    DeviceNotLinked,
+    DeviceLinkValidation,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -28,6 +34,8 @@ pub struct IncorrectPinData {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PinTimeoutData {
     pub time_left_in_ms: u64,
+    /// The exact moment at which the next PIN entry is allowed.
+    pub next_attempt_at: DateTime<Utc>,
 }
 
 // Allow conversion from `AccountError` to `Map<String, Value>`, which
@@ -68,7 +76,12 @@ impl AccountError {
             AccountErrorType::IncorrectPin => Self::IncorrectPin(serde_json::from_value(data)?),
             AccountErrorType::PinTimeout => Self::PinTimeout(serde_json::from_value(data)?),
             AccountErrorType::AccountBlocked => Self::AccountBlocked,
+            AccountErrorType::AccountSuspended => Self::AccountSuspended,
+            AccountErrorType::AccountRevoked => Self::AccountRevoked,
             AccountErrorType::InstructionValidation => Self::InstructionValidation,
+            AccountErrorType::PinRecoveryValidation => Self::PinRecoveryValidation,
+            AccountErrorType::DeviceNotLinked => Self::DeviceNotLinked,
+            AccountErrorType::DeviceLinkValidation => Self::DeviceLinkValidation,
         };
 
         Ok(account_error)
@@ -102,4 +115,41 @@ mod tests {
             }) if attempts_left_in_round == 2 && !is_final_round
         )
     }
+
+    #[test]
+    fn test_account_error_conversion_pin_timeout() {
+        let next_attempt_at = Utc::now();
+        let error = AccountError::PinTimeout(PinTimeoutData {
+            time_left_in_ms: 5000,
+            next_attempt_at,
+        });
+
+        let error_type = AccountErrorType::from(&error);
+        let error_data = error.into();
+
+        let parsed_error =
+            AccountError::try_from_type_and_data(error_type, error_data).expect("should parse successfully");
+
+        assert_eq!(parsed_error, error);
+    }
+
+    #[test]
+    fn test_account_error_conversion_without_data() {
+        for error in [
+            AccountError::AccountSuspended,
+            AccountError::AccountRevoked,
+            AccountError::PinRecoveryValidation,
+            AccountError::DeviceNotLinked,
+        ] {
+            let error_type = AccountErrorType::from(&error);
+            let error_data: Map<String, Value> = error.into();
+
+            assert!(error_data.is_empty());
+
+            let parsed_error =
+                AccountError::try_from_type_and_data(error_type, error_data).expect("should parse successfully");
+
+            assert_eq!(parsed_error, error);
+        }
+    }
 }
@@ -1,4 +1,7 @@
+use chrono::{DateTime, Utc};
+use p256::ecdsa::VerifyingKey;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::{base64::Base64, serde_as};
 
 use crate::{
     account::{
@@ -41,6 +44,135 @@ pub struct SignResult {
     pub signatures: Vec<Vec<DerSignature>>,
 }
 
+/// Performs an ECDH key agreement between each of the identified keys and `public_key`, which the wallet uses to derive
+/// the MAC key with which it authenticates disclosed mdocs to a verifier.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiffieHellman {
+    pub identifiers: Vec<String>,
+    pub public_key: DerVerifyingKey,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiffieHellmanResult {
+    /// The raw shared secrets, in the same order as the identifiers in the [`DiffieHellman`] instruction.
+    #[serde_as(as = "Vec<Base64>")]
+    pub shared_secrets: Vec<Vec<u8>>,
+}
+
+/// Requests the audit log of all instructions the Wallet Provider received for this wallet.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetInstructionAuditLog;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetInstructionAuditLogResult {
+    pub entries: Vec<AuditedInstruction>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuditedInstruction {
+    pub date_time: DateTime<Utc>,
+    pub instruction_type: InstructionType,
+    pub outcome: InstructionOutcome,
+    /// The number of keys generated, signatures made or key agreements performed.
+    pub operation_count: u32,
+    /// The SHA-256 hash of the SHA-256 hashes of all signed payloads, for a [`Sign`] instruction.
+    #[serde_as(as = "Option<Base64>")]
+    pub payload_hash: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display, strum::EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum InstructionType {
+    CheckPin,
+    GenerateKey,
+    Sign,
+    DiffieHellman,
+    GetInstructionAuditLog,
+    StartDeviceLink,
+    /// Linking a new device, which is not an instruction itself but is authorized by the PIN in the same way.
+    LinkDevice,
+    GetDevices,
+    RemoveDevice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display, strum::EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum InstructionOutcome {
+    Success,
+    IncorrectPin,
+    /// The PIN was entered incorrectly too often, resulting in a timeout or the wallet being blocked.
+    Blocked,
+    ValidationError,
+    /// The instruction was valid, but could not be executed.
+    Failed,
+}
+
+/// Starts linking an additional device to this wallet account. The resulting challenge is presented to the new device
+/// in a QR code, together with the wallet certificate of this device, after which the new device links itself.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StartDeviceLink;
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StartDeviceLinkResult {
+    #[serde_as(as = "Base64")]
+    pub challenge: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetDevices;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetDevicesResult {
+    pub devices: Vec<LinkedDevice>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LinkedDevice {
+    pub device_id: String,
+    pub hw_pubkey: DerVerifyingKey,
+    pub linked_date_time: DateTime<Utc>,
+    /// Whether this is the device that sent the [`GetDevices`] instruction.
+    pub is_current: bool,
+}
+
+/// Removes another device from this wallet account, e.g. because it was lost. A device cannot remove itself.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoveDevice {
+    pub device_id: String,
+}
+
+/// Sent by a wallet whose PIN is blocked, after the user re-identified with DigiD, in order to register a new PIN
+/// key. Contrary to the other instructions, this instruction is signed using the new PIN key instead of the old one.
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecoverPin {
+    pub pin_pubkey: DerVerifyingKey,
+    /// The identifier of the Wallet Provider key that the PID currently held by the wallet is bound to.
+    pub pid_key_identifier: String,
+    /// The CBOR encoded `IssuerSigned` of the PID currently held by the wallet.
+    #[serde_as(as = "Base64")]
+    pub pid_issuer_signed: Vec<u8>,
+    /// The CBOR encoded `IssuerSigned` of the PID that was issued after the user re-identified with DigiD.
+    #[serde_as(as = "Base64")]
+    pub recovery_pid_issuer_signed: Vec<u8>,
+    /// Signature over [`RecoverPin::recovery_pid_message`] by the private key of the recovery PID, which proves that
+    /// the recovery PID was issued to this wallet.
+    pub recovery_pid_signature: DerSignature,
+}
+
+impl RecoverPin {
+    /// The message signed by the private key of the recovery PID, which is the SEC1 encoded new PIN public key.
+    /// This binds the recovery PID to this particular PIN recovery.
+    pub fn recovery_pid_message(pin_pubkey: &VerifyingKey) -> Vec<u8> {
+        pin_pubkey.to_encoded_point(false).as_bytes().to_vec()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstructionResultClaims<R> {
     pub result: R,
@@ -120,6 +252,42 @@ impl InstructionEndpoint for Sign {
     type Result = SignResult;
 }
 
+impl InstructionEndpoint for DiffieHellman {
+    const ENDPOINT: &'static str = "diffie_hellman";
+
+    type Result = DiffieHellmanResult;
+}
+
+impl InstructionEndpoint for GetInstructionAuditLog {
+    const ENDPOINT: &'static str = "get_instruction_audit_log";
+
+    type Result = GetInstructionAuditLogResult;
+}
+
+impl InstructionEndpoint for StartDeviceLink {
+    const ENDPOINT: &'static str = "start_device_link";
+
+    type Result = StartDeviceLinkResult;
+}
+
+impl InstructionEndpoint for GetDevices {
+    const ENDPOINT: &'static str = "get_devices";
+
+    type Result = GetDevicesResult;
+}
+
+impl InstructionEndpoint for RemoveDevice {
+    const ENDPOINT: &'static str = "remove_device";
+
+    type Result = ();
+}
+
+impl InstructionEndpoint for RecoverPin {
+    const ENDPOINT: &'static str = "recover_pin";
+
+    type Result = WalletCertificate;
+}
+
 impl<T> Instruction<T>
 where
     T: Serialize + DeserializeOwned,
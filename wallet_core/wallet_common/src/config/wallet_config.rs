@@ -11,7 +11,8 @@ use url::Url;
 use webpki::TrustAnchor;
 
 use crate::{
-    account::serialization::DerVerifyingKey, config::digid::DigidApp2AppConfiguration, trust_anchor::DerTrustAnchor,
+    account::serialization::DerVerifyingKey, config::digid::DigidApp2AppConfiguration,
+    revocation_list::DerCertificateRevocationList, trust_anchor::DerTrustAnchor,
 };
 
 #[nutype(
@@ -49,6 +50,9 @@ pub struct WalletConfiguration {
     pub pid_issuance: PidIssuanceConfiguration,
     pub disclosure: DisclosureConfiguration,
     pub mdoc_trust_anchors: Vec<DerTrustAnchor>,
+    /// CRLs against which the certificates of issued and disclosed mdocs are checked for revocation.
+    #[serde(default)]
+    pub mdoc_crls: Vec<DerCertificateRevocationList>,
     pub version: u64,
 }
 
@@ -123,6 +127,9 @@ pub struct PidIssuanceConfiguration {
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub struct DisclosureConfiguration {
     pub rp_trust_anchors: Vec<DerTrustAnchor>,
+    /// CRLs against which the certificates of relying parties are checked for revocation.
+    #[serde(default)]
+    pub rp_crls: Vec<DerCertificateRevocationList>,
 }
 
 impl Debug for AccountServerConfiguration {
@@ -7,6 +7,7 @@ pub mod jwt;
 pub mod keys;
 pub mod nonempty;
 pub mod reqwest;
+pub mod revocation_list;
 #[cfg(feature = "sentry")]
 pub mod sentry;
 pub mod spawn;
@@ -0,0 +1,74 @@
+use std::{
+    fmt::{Debug, Formatter},
+    hash::{Hash, Hasher},
+};
+
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use webpki::{BorrowedCertRevocationList, CertRevocationList, Error, OwnedCertRevocationList};
+
+/// A parsed X509 Certificate Revocation List (CRL) that retains its DER encoding, so that it can be (de)serialized
+/// and used as a field in another struct. This is the CRL counterpart of
+/// [`DerTrustAnchor`](crate::trust_anchor::DerTrustAnchor).
+#[derive(Clone)]
+pub struct DerCertificateRevocationList {
+    pub owned_crl: OwnedCertRevocationList,
+    pub der_bytes: Vec<u8>,
+}
+
+impl Debug for DerCertificateRevocationList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.der_bytes.fmt(f)
+    }
+}
+
+impl PartialEq for DerCertificateRevocationList {
+    fn eq(&self, other: &Self) -> bool {
+        self.der_bytes == other.der_bytes
+    }
+}
+
+impl Eq for DerCertificateRevocationList {}
+
+impl Hash for DerCertificateRevocationList {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.der_bytes.hash(state)
+    }
+}
+
+impl DerCertificateRevocationList {
+    pub fn from_der(der_bytes: Vec<u8>) -> Result<Self, Error> {
+        let crl = Self {
+            owned_crl: BorrowedCertRevocationList::from_der(&der_bytes)?.to_owned()?,
+            der_bytes,
+        };
+        Ok(crl)
+    }
+
+    /// The DER encoded distinguished name of the CA that issued this CRL.
+    pub fn issuer(&self) -> &[u8] {
+        self.owned_crl.issuer()
+    }
+
+    /// Convert a list of CRLs to the form that is accepted by `webpki` when verifying certificates.
+    pub fn to_webpki(crls: &[Self]) -> Vec<&dyn CertRevocationList> {
+        crls.iter()
+            .map(|crl| &crl.owned_crl as &dyn CertRevocationList)
+            .collect()
+    }
+}
+
+impl Serialize for DerCertificateRevocationList {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BASE64_STANDARD.encode(&self.der_bytes).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DerCertificateRevocationList {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let der_bytes = BASE64_STANDARD
+            .decode(String::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)?;
+        DerCertificateRevocationList::from_der(der_bytes).map_err(serde::de::Error::custom)
+    }
+}
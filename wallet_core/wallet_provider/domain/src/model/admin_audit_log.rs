@@ -0,0 +1,34 @@
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::model::wallet_user::{WalletId, WalletUserState};
+
+/// An action performed by an operator through the admin API of the Wallet Provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "action", content = "state")]
+pub enum AdminAction {
+    LookupWalletUser,
+    LookupInstructionAuditLog,
+    UnblockWalletUser,
+    UpdateWalletUserState(WalletUserState),
+}
+
+/// The operator on whose behalf an admin action is performed, as authenticated by their API key, together with the
+/// reason they optionally gave for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminActor {
+    pub operator: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminAuditLogEntry {
+    pub id: Uuid,
+    pub date_time: DateTime<Local>,
+    pub wallet_id: WalletId,
+    #[serde(flatten)]
+    pub action: AdminAction,
+    pub operator: String,
+    pub reason: Option<String>,
+}
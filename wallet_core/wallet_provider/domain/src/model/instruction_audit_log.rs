@@ -0,0 +1,184 @@
+use std::{error::Error, sync::Arc};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use serde_with::{base64::Base64, serde_as};
+use uuid::Uuid;
+
+pub use wallet_common::account::messages::instructions::{InstructionOutcome, InstructionType};
+
+use crate::model::hsm::Hsm;
+
+/// What an instruction did, as far as it is relevant for auditing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructionAuditDetails {
+    /// The number of keys generated or signatures made.
+    pub operation_count: u32,
+    /// A hash over all payloads that were signed.
+    pub payload_hash: Option<Vec<u8>>,
+}
+
+/// An entry in the append-only audit log of the instructions received for a single wallet user. The entries of a
+/// wallet user form a hash chain: the hash of each entry covers both its contents and the hash of the entry before it,
+/// so that changing or removing an entry breaks the chain from that point onwards. The hash is an HMAC with a key in
+/// the HSM, so that a valid chain cannot be recomputed by someone who can only modify the database.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstructionAuditLogEntry {
+    pub id: Uuid,
+    pub wallet_user_id: Uuid,
+    /// The position of this entry in the hash chain of the wallet user, starting at 0.
+    pub chain_index: u64,
+    pub date_time: DateTime<Local>,
+    pub instruction_type: InstructionType,
+    pub outcome: InstructionOutcome,
+    pub operation_count: u32,
+    #[serde_as(as = "Option<Base64>")]
+    pub payload_hash: Option<Vec<u8>>,
+    #[serde_as(as = "Base64")]
+    pub hash: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InstructionAuditLogChainError<E: Error> {
+    #[error("instruction audit log entry {0} is missing")]
+    MissingEntry(u64),
+    #[error("instruction audit log entry {0} belongs to another wallet user")]
+    WalletUserMismatch(u64),
+    #[error("hash of instruction audit log entry {0} does not match its contents")]
+    HashMismatch(u64),
+    #[error("could not compute hash of instruction audit log entry: {0}")]
+    Hsm(#[source] E),
+}
+
+impl InstructionAuditLogEntry {
+    /// Create the entry that follows `previous` in the audit log of a wallet user, where `previous` is `None` for the
+    /// very first entry. The hash is computed by the HSM using the key with the identifier `key_identifier`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_chained<H: Hsm>(
+        previous: Option<&InstructionAuditLogEntry>,
+        id: Uuid,
+        wallet_user_id: Uuid,
+        date_time: DateTime<Local>,
+        instruction_type: InstructionType,
+        outcome: InstructionOutcome,
+        details: InstructionAuditDetails,
+        hsm: &H,
+        key_identifier: &str,
+    ) -> Result<Self, H::Error> {
+        let mut entry = InstructionAuditLogEntry {
+            id,
+            wallet_user_id,
+            chain_index: previous.map(|previous| previous.chain_index + 1).unwrap_or_default(),
+            date_time,
+            instruction_type,
+            outcome,
+            operation_count: details.operation_count,
+            payload_hash: details.payload_hash,
+            hash: Vec::new(),
+        };
+        entry.hash = hsm
+            .sign_hmac(
+                key_identifier,
+                Arc::new(entry.hash_input(previous.map(|previous| previous.hash.as_slice()))),
+            )
+            .await?;
+
+        Ok(entry)
+    }
+
+    fn hash_input(&self, previous_hash: Option<&[u8]>) -> Vec<u8> {
+        let mut input = Vec::new();
+
+        // Every variable length field is prefixed with its length, so that the input of the hash is unambiguous.
+        // Note that the timestamp is truncated to microseconds, which is the precision in which it is persisted.
+        append_optional(&mut input, previous_hash);
+        input.extend_from_slice(self.id.as_bytes());
+        input.extend_from_slice(self.wallet_user_id.as_bytes());
+        input.extend_from_slice(&self.chain_index.to_be_bytes());
+        input.extend_from_slice(&self.date_time.timestamp_micros().to_be_bytes());
+        append_bytes(&mut input, self.instruction_type.to_string().as_bytes());
+        append_bytes(&mut input, self.outcome.to_string().as_bytes());
+        input.extend_from_slice(&self.operation_count.to_be_bytes());
+        append_optional(&mut input, self.payload_hash.as_deref());
+
+        input
+    }
+}
+
+fn append_bytes(input: &mut Vec<u8>, bytes: &[u8]) {
+    input.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+    input.extend_from_slice(bytes);
+}
+
+fn append_optional(input: &mut Vec<u8>, bytes: Option<&[u8]>) {
+    match bytes {
+        Some(bytes) => {
+            input.push(1);
+            append_bytes(input, bytes);
+        }
+        None => input.push(0),
+    }
+}
+
+/// Verify that `entries`, ordered by chain index, form the complete and unaltered hash chain of a single wallet user,
+/// using the HSM key with the identifier `key_identifier`. Note that removing the most recent entries cannot be
+/// detected this way.
+pub async fn verify_chain<H: Hsm>(
+    entries: &[InstructionAuditLogEntry],
+    hsm: &H,
+    key_identifier: &str,
+) -> Result<(), InstructionAuditLogChainError<H::Error>> {
+    let mut previous: Option<&InstructionAuditLogEntry> = None;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let index = index as u64;
+
+        if entry.chain_index != index {
+            return Err(InstructionAuditLogChainError::MissingEntry(index));
+        }
+        if previous.is_some_and(|previous| previous.wallet_user_id != entry.wallet_user_id) {
+            return Err(InstructionAuditLogChainError::WalletUserMismatch(index));
+        }
+
+        let hash = hsm
+            .sign_hmac(
+                key_identifier,
+                Arc::new(entry.hash_input(previous.map(|previous| previous.hash.as_slice()))),
+            )
+            .await
+            .map_err(InstructionAuditLogChainError::Hsm)?;
+        if hash != entry.hash {
+            return Err(InstructionAuditLogChainError::HashMismatch(index));
+        }
+
+        previous = Some(entry);
+    }
+
+    Ok(())
+}
+
+/// The complete instruction audit log of a wallet user, together with the result of verifying its hash chain.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstructionAuditLog {
+    pub entries: Vec<InstructionAuditLogEntry>,
+    /// Describes where the hash chain is broken, if it is.
+    pub chain_error: Option<String>,
+}
+
+impl InstructionAuditLog {
+    /// Verify the hash chain of `entries`, which only fails if the HSM could not be used to do so.
+    pub async fn verify<H: Hsm>(
+        entries: Vec<InstructionAuditLogEntry>,
+        hsm: &H,
+        key_identifier: &str,
+    ) -> Result<Self, H::Error> {
+        let chain_error = match verify_chain(&entries, hsm, key_identifier).await {
+            Ok(()) => None,
+            Err(InstructionAuditLogChainError::Hsm(error)) => return Err(error),
+            Err(error) => Some(error.to_string()),
+        };
+
+        Ok(InstructionAuditLog { entries, chain_error })
+    }
+}
@@ -1,9 +1,9 @@
-pub mod encrypted;
-pub mod encrypter;
-pub mod hsm;
+pub mod admin_audit_log;
+pub mod instruction_audit_log;
 pub mod pin_policy;
 pub mod wallet_user;
-pub mod wrapped_key;
+
+pub use ::hsm::model::{encrypted, encrypter, hsm, versioned_key, wrapped_key};
 
 #[cfg(feature = "mock")]
 pub use self::pin_policy::mock::{FailingPinPolicy, TimeoutPinPolicy};
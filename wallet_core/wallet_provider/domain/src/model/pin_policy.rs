@@ -7,6 +7,17 @@ pub trait PinPolicyEvaluator {
         last_failed_pin: Option<DateTime<Local>>,
         current_datetime: DateTime<Local>,
     ) -> PinPolicyEvaluation;
+
+    /// Returns the number of earlier unsuccessful attempts that still count towards the policy at `current_datetime`.
+    /// Policies that forgive unsuccessful attempts over time override this, by default attempts never decay.
+    fn decayed_attempts(
+        &self,
+        attempts: u8,
+        _last_failed_pin: Option<DateTime<Local>>,
+        _current_datetime: DateTime<Local>,
+    ) -> u8 {
+        attempts
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -17,9 +28,11 @@ pub enum PinPolicyEvaluation {
     },
     Timeout {
         timeout: Duration,
+        next_attempt_at: DateTime<Local>,
     },
     InTimeout {
         timeout: Duration,
+        next_attempt_at: DateTime<Local>,
     },
     BlockedPermanently,
 }
@@ -50,10 +63,13 @@ pub mod mock {
             &self,
             _attempts: u8,
             _last_failed_pin: Option<DateTime<Local>>,
-            _current_datetime: DateTime<Local>,
+            current_datetime: DateTime<Local>,
         ) -> PinPolicyEvaluation {
+            let timeout = Duration::seconds(60);
+
             PinPolicyEvaluation::Timeout {
-                timeout: Duration::seconds(60),
+                timeout,
+                next_attempt_at: current_datetime + timeout,
             }
         }
     }
@@ -2,7 +2,7 @@ use std::fmt::{Debug, Formatter};
 
 use chrono::{DateTime, Local};
 use p256::ecdsa::VerifyingKey;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use wallet_common::account::serialization::DerVerifyingKey;
@@ -11,15 +11,22 @@ use crate::model::{encrypted::Encrypted, wrapped_key::WrappedKey};
 
 pub type WalletId = String;
 
+/// A wallet user, as seen from one of the devices linked to it. The hardware public key, instruction challenge and
+/// instruction sequence number are those of that device.
 pub struct WalletUser {
     pub id: Uuid,
     pub wallet_id: WalletId,
+    pub device_id: Uuid,
     pub hw_pubkey: DerVerifyingKey,
     pub encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+    pub pin_pubkey_encryption_key_version: u32,
     pub unsuccessful_pin_entries: u8,
     pub last_unsuccessful_pin_entry: Option<DateTime<Local>>,
     pub instruction_challenge: Option<InstructionChallenge>,
     pub instruction_sequence_number: u64,
+    /// The challenge a new device should sign to be linked to the wallet user, if this device started linking one.
+    pub device_link_challenge: Option<InstructionChallenge>,
+    pub platform_attestation: Option<WalletUserPlatformAttestation>,
 }
 
 impl Debug for WalletUser {
@@ -38,14 +45,79 @@ pub struct InstructionChallenge {
 pub enum WalletUserQueryResult {
     Found(Box<WalletUser>),
     NotFound,
-    Blocked,
+    /// The wallet user is blocked after too many unsuccessful PIN entries. It can still be unblocked through PIN
+    /// recovery, which is why the wallet user is included.
+    Blocked(Box<WalletUser>),
+    Suspended,
+    Revoked,
+    /// The wallet user exists, but the hardware key is not that of a device linked to it, e.g. because the device
+    /// was removed.
+    DeviceNotLinked,
+}
+
+/// The lifecycle state of a wallet user, as managed by the operator of the Wallet Provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletUserState {
+    /// The wallet can be used normally.
+    Active,
+    /// The wallet is temporarily suspended, e.g. because the user reported their phone as lost.
+    Suspended,
+    /// The wallet is permanently revoked and can no longer be used. This state is final.
+    Revoked,
+}
+
+/// The administrative details of a wallet user, which are available regardless of its state.
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletUserDetails {
+    pub id: Uuid,
+    pub wallet_id: WalletId,
+    pub state: WalletUserState,
+    pub is_blocked: bool,
+    pub unsuccessful_pin_entries: u8,
+    pub last_unsuccessful_pin_entry: Option<DateTime<Local>>,
+    pub platform_attestation: Option<WalletUserPlatformAttestation>,
+    pub key_count: u64,
 }
 
 pub struct WalletUserCreate {
     pub id: Uuid,
     pub wallet_id: String,
+    /// The id of the device the wallet user registers with, which is linked to the wallet user on creation.
+    pub device_id: Uuid,
     pub hw_pubkey: VerifyingKey,
     pub encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+    pub pin_pubkey_encryption_key_version: u32,
+    pub platform_attestation: Option<WalletUserPlatformAttestation>,
+}
+
+/// The outcome of a successfully verified platform attestation of the hardware key, provided during registration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletUserPlatformAttestation {
+    /// The key was attested by Android Key Attestation to reside in a Trusted Execution Environment.
+    AndroidTrustedEnvironment,
+    /// The key was attested by Android Key Attestation to reside in a StrongBox secure element.
+    AndroidStrongBox,
+    /// The app instance was attested by Apple App Attest.
+    AppleAppAttest,
+}
+
+/// A device linked to a wallet user, each of which has its own hardware key.
+#[derive(Debug, Clone)]
+pub struct WalletUserDevice {
+    pub id: Uuid,
+    pub hw_pubkey: DerVerifyingKey,
+    pub linked_date_time: DateTime<Local>,
+    pub platform_attestation: Option<WalletUserPlatformAttestation>,
+}
+
+pub struct WalletUserDeviceCreate {
+    pub id: Uuid,
+    pub wallet_user_id: Uuid,
+    pub hw_pubkey: VerifyingKey,
+    pub linked_date_time: DateTime<Local>,
+    pub platform_attestation: Option<WalletUserPlatformAttestation>,
 }
 
 #[derive(Clone)]
@@ -79,6 +151,7 @@ pub mod mock {
         WalletUser {
             id: uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08"),
             wallet_id: "wallet_123".to_string(),
+            device_id: uuid!("e1b2f6a5-1e0d-4c36-9d7b-5f3a4c1e8a21"),
             hw_pubkey: DerVerifyingKey(
                 VerifyingKey::from_str(
                     r#"-----BEGIN PUBLIC KEY-----
@@ -90,10 +163,13 @@ SssTb0eI53lvfdvG/xkNcktwsXEIPL1y3lUKn1u1ZhFTnQn4QKmnvaN4uQ==
                 .unwrap(),
             ),
             encrypted_pin_pubkey: Encrypted::new(random_bytes(32), InitializationVector(random_bytes(32))),
+            pin_pubkey_encryption_key_version: 0,
             unsuccessful_pin_entries: 0,
             last_unsuccessful_pin_entry: None,
             instruction_challenge: None,
             instruction_sequence_number: 0,
+            device_link_challenge: None,
+            platform_attestation: None,
         }
     }
 }
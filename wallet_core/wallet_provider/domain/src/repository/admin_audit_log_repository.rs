@@ -0,0 +1,22 @@
+use crate::model::admin_audit_log::AdminAuditLogEntry;
+
+use super::{errors::PersistenceError, transaction::Committable};
+
+type Result<T> = std::result::Result<T, PersistenceError>;
+
+pub trait AdminAuditLogRepository {
+    type TransactionType: Committable;
+
+    async fn create_admin_audit_log_entry(
+        &self,
+        transaction: &Self::TransactionType,
+        entry: AdminAuditLogEntry,
+    ) -> Result<()>;
+
+    /// Find all audit log entries, optionally only those pertaining to a single wallet, ordered by date and time.
+    async fn find_admin_audit_log_entries(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: Option<&str>,
+    ) -> Result<Vec<AdminAuditLogEntry>>;
+}
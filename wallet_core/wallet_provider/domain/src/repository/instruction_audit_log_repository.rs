@@ -0,0 +1,31 @@
+use uuid::Uuid;
+
+use crate::model::instruction_audit_log::InstructionAuditLogEntry;
+
+use super::{errors::PersistenceError, transaction::Committable};
+
+type Result<T> = std::result::Result<T, PersistenceError>;
+
+pub trait InstructionAuditLogRepository {
+    type TransactionType: Committable;
+
+    async fn create_instruction_audit_log_entry(
+        &self,
+        transaction: &Self::TransactionType,
+        entry: InstructionAuditLogEntry,
+    ) -> Result<()>;
+
+    /// Find the most recent audit log entry of a wallet user, which is the head of its hash chain.
+    async fn find_last_instruction_audit_log_entry(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+    ) -> Result<Option<InstructionAuditLogEntry>>;
+
+    /// Find all audit log entries of a wallet user, ordered by their index in the hash chain.
+    async fn find_instruction_audit_log_entries(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+    ) -> Result<Vec<InstructionAuditLogEntry>>;
+}
@@ -0,0 +1,46 @@
+use p256::ecdsa::VerifyingKey;
+use uuid::Uuid;
+
+use crate::model::{encrypted::Encrypted, wrapped_key::WrappedKey};
+
+use super::{errors::PersistenceError, transaction::Committable};
+
+type Result<T> = std::result::Result<T, PersistenceError>;
+
+/// Access to all keys that are wrapped or encrypted by a key in the HSM, in order to migrate them to the current
+/// version of that key after it has been rotated.
+pub trait KeyRotationRepository {
+    type TransactionType: Committable;
+
+    /// Find at most `limit` wallet user keys that are not wrapped with `current_version` of the wrapping key.
+    async fn find_wallet_user_keys_to_rewrap(
+        &self,
+        transaction: &Self::TransactionType,
+        current_version: u32,
+        limit: u64,
+    ) -> Result<Vec<(Uuid, WrappedKey)>>;
+
+    async fn update_wallet_user_key(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_key_id: Uuid,
+        key: WrappedKey,
+    ) -> Result<()>;
+
+    /// Find at most `limit` PIN public keys, together with the version of the key they are encrypted with, that are
+    /// not encrypted with `current_version` of the encryption key.
+    async fn find_pin_pubkeys_to_reencrypt(
+        &self,
+        transaction: &Self::TransactionType,
+        current_version: u32,
+        limit: u64,
+    ) -> Result<Vec<(Uuid, Encrypted<VerifyingKey>, u32)>>;
+
+    async fn update_encrypted_pin_pubkey(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+        encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+        pin_pubkey_encryption_key_version: u32,
+    ) -> Result<()>;
+}
@@ -1,10 +1,18 @@
+mod admin_audit_log_repository;
 mod errors;
+mod instruction_audit_log_repository;
+mod key_rotation_repository;
 mod transaction;
+mod wallet_user_device_repository;
 mod wallet_user_repository;
 
 pub use self::{
+    admin_audit_log_repository::AdminAuditLogRepository,
     errors::PersistenceError,
+    instruction_audit_log_repository::InstructionAuditLogRepository,
+    key_rotation_repository::KeyRotationRepository,
     transaction::{Committable, TransactionStarter},
+    wallet_user_device_repository::WalletUserDeviceRepository,
     wallet_user_repository::WalletUserRepository,
 };
 
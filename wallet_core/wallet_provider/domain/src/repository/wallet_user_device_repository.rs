@@ -0,0 +1,41 @@
+use uuid::Uuid;
+
+use crate::model::wallet_user::{InstructionChallenge, WalletUserDevice, WalletUserDeviceCreate};
+
+use super::{errors::PersistenceError, transaction::Committable};
+
+type Result<T> = std::result::Result<T, PersistenceError>;
+
+pub trait WalletUserDeviceRepository {
+    type TransactionType: Committable;
+
+    async fn create_wallet_user_device(
+        &self,
+        transaction: &Self::TransactionType,
+        device: WalletUserDeviceCreate,
+    ) -> Result<()>;
+
+    /// Find all devices linked to a wallet user, ordered by the moment they were linked.
+    async fn find_wallet_user_devices(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+    ) -> Result<Vec<WalletUserDevice>>;
+
+    /// Remove a device from a wallet user, returning [`PersistenceError::NotFound`] when the wallet user has no
+    /// such device.
+    async fn delete_wallet_user_device(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+        device_id: Uuid,
+    ) -> Result<()>;
+
+    /// Set or clear the challenge a new device should sign to be linked by the device with `device_id`.
+    async fn update_device_link_challenge(
+        &self,
+        transaction: &Self::TransactionType,
+        device_id: Uuid,
+        challenge: Option<InstructionChallenge>,
+    ) -> Result<()>;
+}
@@ -1,8 +1,14 @@
 use chrono::{DateTime, Local};
+use p256::ecdsa::VerifyingKey;
 use std::collections::HashMap;
+use uuid::Uuid;
 
 use crate::model::{
-    wallet_user::{InstructionChallenge, WalletUserCreate, WalletUserKeys, WalletUserQueryResult},
+    encrypted::Encrypted,
+    wallet_user::{
+        InstructionChallenge, WalletUserCreate, WalletUserDetails, WalletUserKeys, WalletUserQueryResult,
+        WalletUserState,
+    },
     wrapped_key::WrappedKey,
 };
 
@@ -15,18 +21,28 @@ pub trait WalletUserRepository {
 
     async fn create_wallet_user(&self, transaction: &Self::TransactionType, user: WalletUserCreate) -> Result<()>;
 
+    /// Find a wallet user by its wallet id, as seen from the linked device with the hardware public key `hw_pubkey`.
+    /// The wallet user is locked until `transaction` ends, so that its unsuccessful PIN entries can be updated based
+    /// on the values returned.
     async fn find_wallet_user_by_wallet_id(
         &self,
         transaction: &Self::TransactionType,
         wallet_id: &str,
+        hw_pubkey: &VerifyingKey,
     ) -> Result<WalletUserQueryResult>;
 
-    async fn clear_instruction_challenge(&self, transaction: &Self::TransactionType, wallet_id: &str) -> Result<()>;
+    async fn find_wallet_user_details_by_wallet_id(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: &str,
+    ) -> Result<Option<WalletUserDetails>>;
+
+    async fn clear_instruction_challenge(&self, transaction: &Self::TransactionType, device_id: Uuid) -> Result<()>;
 
     async fn update_instruction_challenge_and_sequence_number(
         &self,
         transaction: &Self::TransactionType,
-        wallet_id: &str,
+        device_id: Uuid,
         challenge: InstructionChallenge,
         instruction_sequence_number: u64,
     ) -> Result<()>;
@@ -34,20 +50,39 @@ pub trait WalletUserRepository {
     async fn update_instruction_sequence_number(
         &self,
         transaction: &Self::TransactionType,
-        wallet_id: &str,
+        device_id: Uuid,
         instruction_sequence_number: u64,
     ) -> Result<()>;
 
+    /// Register an unsuccessful PIN entry, which results in `pin_entries` unsuccessful PIN entries in total.
     async fn register_unsuccessful_pin_entry(
         &self,
         transaction: &Self::TransactionType,
         wallet_id: &str,
+        pin_entries: u8,
         is_blocked: bool,
         datetime: DateTime<Local>,
     ) -> Result<()>;
 
     async fn reset_unsuccessful_pin_entries(&self, transaction: &Self::TransactionType, wallet_id: &str) -> Result<()>;
 
+    async fn block_wallet_user(&self, transaction: &Self::TransactionType, wallet_id: &str) -> Result<()>;
+
+    async fn update_wallet_user_pin_pubkey(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: &str,
+        encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+        pin_pubkey_encryption_key_version: u32,
+    ) -> Result<()>;
+
+    async fn update_wallet_user_state(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: &str,
+        state: WalletUserState,
+    ) -> Result<()>;
+
     async fn save_keys(&self, transaction: &Self::TransactionType, keys: WalletUserKeys) -> Result<()>;
 
     async fn find_keys_by_identifiers(
@@ -56,6 +91,13 @@ pub trait WalletUserRepository {
         wallet_user_id: uuid::Uuid,
         key_identifiers: &[String],
     ) -> Result<HashMap<String, WrappedKey>>;
+
+    async fn transfer_keys(
+        &self,
+        transaction: &Self::TransactionType,
+        source_wallet_user_id: uuid::Uuid,
+        destination_wallet_user_id: uuid::Uuid,
+    ) -> Result<()>;
 }
 
 #[cfg(feature = "mock")]
@@ -83,16 +125,25 @@ pub mod mock {
             &self,
             _transaction: &Self::TransactionType,
             _wallet_id: &str,
+            _hw_pubkey: &VerifyingKey,
         ) -> Result<WalletUserQueryResult> {
             Ok(WalletUserQueryResult::Found(Box::new(
                 wallet_user::mock::wallet_user_1(),
             )))
         }
 
-        async fn update_instruction_challenge_and_sequence_number(
+        async fn find_wallet_user_details_by_wallet_id(
             &self,
             _transaction: &Self::TransactionType,
             _wallet_id: &str,
+        ) -> Result<Option<WalletUserDetails>> {
+            Ok(None)
+        }
+
+        async fn update_instruction_challenge_and_sequence_number(
+            &self,
+            _transaction: &Self::TransactionType,
+            _device_id: Uuid,
             _challenge: InstructionChallenge,
             _instruction_sequence_number: u64,
         ) -> Result<()> {
@@ -102,7 +153,7 @@ pub mod mock {
         async fn update_instruction_sequence_number(
             &self,
             _transaction: &Self::TransactionType,
-            _wallet_id: &str,
+            _device_id: Uuid,
             _instruction_sequence_number: u64,
         ) -> Result<()> {
             Ok(())
@@ -111,7 +162,7 @@ pub mod mock {
         async fn clear_instruction_challenge(
             &self,
             _transaction: &Self::TransactionType,
-            _wallet_id: &str,
+            _device_id: Uuid,
         ) -> Result<()> {
             Ok(())
         }
@@ -120,6 +171,7 @@ pub mod mock {
             &self,
             _transaction: &Self::TransactionType,
             _wallet_id: &str,
+            _pin_entries: u8,
             _is_blocked: bool,
             _datetime: DateTime<Local>,
         ) -> Result<()> {
@@ -134,6 +186,29 @@ pub mod mock {
             Ok(())
         }
 
+        async fn block_wallet_user(&self, _transaction: &Self::TransactionType, _wallet_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_wallet_user_pin_pubkey(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_id: &str,
+            _encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+            _pin_pubkey_encryption_key_version: u32,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_wallet_user_state(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_id: &str,
+            _state: WalletUserState,
+        ) -> Result<()> {
+            Ok(())
+        }
+
         async fn save_keys(&self, _transaction: &Self::TransactionType, _keys: WalletUserKeys) -> Result<()> {
             Ok(())
         }
@@ -146,5 +221,14 @@ pub mod mock {
         ) -> Result<HashMap<String, WrappedKey>> {
             Ok(HashMap::new())
         }
+
+        async fn transfer_keys(
+            &self,
+            _transaction: &Self::TransactionType,
+            _source_wallet_user_id: Uuid,
+            _destination_wallet_user_id: Uuid,
+        ) -> Result<()> {
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // This table deliberately has no foreign key to wallet_user, as lookups of unknown wallets are audited as well.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AdminAuditLog::Table)
+                    .col(ColumnDef::new(AdminAuditLog::Id).uuid().not_null().primary_key())
+                    .col(
+                        ColumnDef::new(AdminAuditLog::DateTime)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(AdminAuditLog::WalletId).string().not_null())
+                    .col(ColumnDef::new(AdminAuditLog::Action).string().not_null())
+                    .col(ColumnDef::new(AdminAuditLog::WalletUserState).string().null())
+                    .col(ColumnDef::new(AdminAuditLog::Operator).string().not_null())
+                    .col(ColumnDef::new(AdminAuditLog::Reason).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ix_admin_audit_log_wallet_id")
+                    .table(AdminAuditLog::Table)
+                    .col(AdminAuditLog::WalletId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum AdminAuditLog {
+    Table,
+    Id,
+    DateTime,
+    WalletId,
+    Action,
+    WalletUserState,
+    Operator,
+    Reason,
+}
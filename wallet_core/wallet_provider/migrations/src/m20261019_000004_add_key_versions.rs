@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletUser::Table)
+                    .add_column(
+                        ColumnDef::new(WalletUser::PinPubkeyEncryptionKeyVersion)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletUserKey::Table)
+                    .add_column(
+                        ColumnDef::new(WalletUserKey::WrappingKeyVersion)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum WalletUser {
+    Table,
+    PinPubkeyEncryptionKeyVersion,
+}
+
+#[derive(Iden)]
+enum WalletUserKey {
+    Table,
+    WrappingKeyVersion,
+}
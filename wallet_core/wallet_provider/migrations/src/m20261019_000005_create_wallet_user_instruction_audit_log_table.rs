@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The unique index on the chain index prevents concurrent instructions from forking the hash chain.
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletUserInstructionAuditLog::Table)
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::WalletUserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::ChainIndex)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::DateTime)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::InstructionType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::Outcome)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::OperationCount)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserInstructionAuditLog::PayloadHash)
+                            .binary()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(WalletUserInstructionAuditLog::Hash).binary().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_instruction_audit_log_wallet_user_id")
+                            .from(
+                                WalletUserInstructionAuditLog::Table,
+                                WalletUserInstructionAuditLog::WalletUserId,
+                            )
+                            .to(WalletUser::Table, WalletUser::Id)
+                            .on_delete(ForeignKeyAction::NoAction),
+                    )
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uk_instruction_audit_log_wallet_user_id_chain_index")
+                            .col(WalletUserInstructionAuditLog::WalletUserId)
+                            .col(WalletUserInstructionAuditLog::ChainIndex),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The audit log is append-only, so any attempt to change or remove its entries is rejected by the database.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE OR REPLACE FUNCTION reject_instruction_audit_log_modification() RETURNS trigger AS $$ \
+                 BEGIN RAISE EXCEPTION 'wallet_user_instruction_audit_log is append-only'; END; $$ LANGUAGE plpgsql; \
+                 CREATE TRIGGER instruction_audit_log_append_only BEFORE UPDATE OR DELETE \
+                 ON wallet_user_instruction_audit_log \
+                 FOR EACH ROW EXECUTE FUNCTION reject_instruction_audit_log_modification(); \
+                 CREATE TRIGGER instruction_audit_log_no_truncate BEFORE TRUNCATE \
+                 ON wallet_user_instruction_audit_log \
+                 FOR EACH STATEMENT EXECUTE FUNCTION reject_instruction_audit_log_modification()",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum WalletUserInstructionAuditLog {
+    Table,
+    Id,
+    WalletUserId,
+    ChainIndex,
+    DateTime,
+    InstructionType,
+    Outcome,
+    OperationCount,
+    PayloadHash,
+    Hash,
+}
+
+#[derive(Iden)]
+enum WalletUser {
+    Table,
+    Id,
+}
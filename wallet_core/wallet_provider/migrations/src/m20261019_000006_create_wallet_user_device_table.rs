@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletUserDevice::Table)
+                    .col(ColumnDef::new(WalletUserDevice::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(WalletUserDevice::WalletUserId).uuid().not_null())
+                    .col(ColumnDef::new(WalletUserDevice::HwPubkeyDer).binary().not_null())
+                    .col(
+                        ColumnDef::new(WalletUserDevice::InstructionSequenceNumber)
+                            .unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(WalletUserDevice::DeviceLinkChallenge).binary().null())
+                    .col(
+                        ColumnDef::new(WalletUserDevice::DeviceLinkChallengeExpirationDateTime)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletUserDevice::LinkedDateTime)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WalletUserDevice::PlatformAttestation).string().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_device_wallet_user_id")
+                            .from(WalletUserDevice::Table, WalletUserDevice::WalletUserId)
+                            .to(WalletUser::Table, WalletUser::Id)
+                            .on_delete(ForeignKeyAction::NoAction),
+                    )
+                    .index(
+                        Index::create()
+                            .unique()
+                            .name("uk_device_wallet_user_id_hw_pubkey_der")
+                            .col(WalletUserDevice::WalletUserId)
+                            .col(WalletUserDevice::HwPubkeyDer),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every existing wallet user becomes a wallet user with a single device, which gets the id of the wallet user.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO wallet_user_device (id, wallet_user_id, hw_pubkey_der, instruction_sequence_number, \
+                 linked_date_time, platform_attestation) SELECT id, id, hw_pubkey_der, instruction_sequence_number, \
+                 now(), platform_attestation FROM wallet_user",
+            )
+            .await?;
+
+        // Instruction challenges are now stored per device instead of per wallet user.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletUserInstructionChallenge::Table)
+                    .add_column(
+                        ColumnDef::new(WalletUserInstructionChallenge::WalletUserDeviceId)
+                            .uuid()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE wallet_user_instruction_challenge SET wallet_user_device_id = wallet_user_id; \
+                 ALTER TABLE wallet_user_instruction_challenge DROP CONSTRAINT uk_wallet_user_id",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletUserInstructionChallenge::Table)
+                    .modify_column(
+                        ColumnDef::new(WalletUserInstructionChallenge::WalletUserDeviceId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_instruction_challenge_wallet_user_device_id")
+                            .from_tbl(WalletUserInstructionChallenge::Table)
+                            .from_col(WalletUserInstructionChallenge::WalletUserDeviceId)
+                            .to_tbl(WalletUserDevice::Table)
+                            .to_col(WalletUserDevice::Id)
+                            .on_delete(ForeignKeyAction::NoAction),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .unique()
+                    .name("uk_instruction_challenge_wallet_user_device_id")
+                    .table(WalletUserInstructionChallenge::Table)
+                    .col(WalletUserInstructionChallenge::WalletUserDeviceId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WalletUser::Table)
+                    .drop_column(WalletUser::HwPubkeyDer)
+                    .drop_column(WalletUser::InstructionSequenceNumber)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum WalletUserDevice {
+    Table,
+    Id,
+    WalletUserId,
+    HwPubkeyDer,
+    InstructionSequenceNumber,
+    DeviceLinkChallenge,
+    DeviceLinkChallengeExpirationDateTime,
+    LinkedDateTime,
+    PlatformAttestation,
+}
+
+#[derive(Iden)]
+enum WalletUserInstructionChallenge {
+    Table,
+    WalletUserDeviceId,
+}
+
+#[derive(Iden)]
+enum WalletUser {
+    Table,
+    Id,
+    HwPubkeyDer,
+    InstructionSequenceNumber,
+}
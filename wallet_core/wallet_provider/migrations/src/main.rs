@@ -7,6 +7,12 @@ use wallet_provider_database_settings::Settings;
 mod m20230616_000001_create_wallet_user_table;
 mod m20230908_000001_create_wallet_user_key_table;
 mod m20230926_000001_create_wallet_user_challenge_instruction;
+mod m20261019_000001_add_wallet_user_platform_attestation;
+mod m20261019_000002_add_wallet_user_state;
+mod m20261019_000003_create_admin_audit_log_table;
+mod m20261019_000004_add_key_versions;
+mod m20261019_000005_create_wallet_user_instruction_audit_log_table;
+mod m20261019_000006_create_wallet_user_device_table;
 
 pub struct Migrator;
 
@@ -16,6 +22,12 @@ impl MigratorTrait for Migrator {
             Box::new(m20230616_000001_create_wallet_user_table::Migration),
             Box::new(m20230908_000001_create_wallet_user_key_table::Migration),
             Box::new(m20230926_000001_create_wallet_user_challenge_instruction::Migration),
+            Box::new(m20261019_000001_add_wallet_user_platform_attestation::Migration),
+            Box::new(m20261019_000002_add_wallet_user_state::Migration),
+            Box::new(m20261019_000003_create_admin_audit_log_table::Migration),
+            Box::new(m20261019_000004_add_key_versions::Migration),
+            Box::new(m20261019_000005_create_wallet_user_instruction_audit_log_table::Migration),
+            Box::new(m20261019_000006_create_wallet_user_device_table::Migration),
         ]
     }
 }
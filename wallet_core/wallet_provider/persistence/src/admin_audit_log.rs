@@ -0,0 +1,93 @@
+use chrono::{DateTime, Local};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use wallet_provider_domain::{
+    model::admin_audit_log::{AdminAction, AdminAuditLogEntry},
+    repository::PersistenceError,
+};
+
+use crate::{
+    entity::admin_audit_log,
+    wallet_user::{wallet_user_state_from_str, wallet_user_state_to_str},
+    PersistenceConnection,
+};
+
+type Result<T> = std::result::Result<T, PersistenceError>;
+
+const LOOKUP_WALLET_USER: &str = "lookup_wallet_user";
+const LOOKUP_INSTRUCTION_AUDIT_LOG: &str = "lookup_instruction_audit_log";
+const UNBLOCK_WALLET_USER: &str = "unblock_wallet_user";
+const UPDATE_WALLET_USER_STATE: &str = "update_wallet_user_state";
+
+pub async fn create_admin_audit_log_entry<S, T>(db: &T, entry: AdminAuditLogEntry) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    let (action, wallet_user_state) = match entry.action {
+        AdminAction::LookupWalletUser => (LOOKUP_WALLET_USER, None),
+        AdminAction::LookupInstructionAuditLog => (LOOKUP_INSTRUCTION_AUDIT_LOG, None),
+        AdminAction::UnblockWalletUser => (UNBLOCK_WALLET_USER, None),
+        AdminAction::UpdateWalletUserState(state) => (UPDATE_WALLET_USER_STATE, Some(wallet_user_state_to_str(state))),
+    };
+
+    admin_audit_log::ActiveModel {
+        id: Set(entry.id),
+        date_time: Set(entry.date_time.into()),
+        wallet_id: Set(entry.wallet_id),
+        action: Set(action.to_string()),
+        wallet_user_state: Set(wallet_user_state.map(str::to_string)),
+        operator: Set(entry.operator),
+        reason: Set(entry.reason),
+    }
+    .insert(db.connection())
+    .await
+    .map(|_| ())
+    .map_err(|e| PersistenceError::Execution(e.into()))
+}
+
+pub async fn find_admin_audit_log_entries<S, T>(db: &T, wallet_id: Option<&str>) -> Result<Vec<AdminAuditLogEntry>>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    let mut query = admin_audit_log::Entity::find();
+
+    if let Some(wallet_id) = wallet_id {
+        query = query.filter(admin_audit_log::Column::WalletId.eq(wallet_id));
+    }
+
+    let models = query
+        .order_by_asc(admin_audit_log::Column::DateTime)
+        .all(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?;
+
+    models
+        .into_iter()
+        .map(|model| {
+            let action = match (model.action.as_str(), model.wallet_user_state.as_deref()) {
+                (LOOKUP_WALLET_USER, None) => AdminAction::LookupWalletUser,
+                (LOOKUP_INSTRUCTION_AUDIT_LOG, None) => AdminAction::LookupInstructionAuditLog,
+                (UNBLOCK_WALLET_USER, None) => AdminAction::UnblockWalletUser,
+                (UPDATE_WALLET_USER_STATE, Some(state)) => {
+                    AdminAction::UpdateWalletUserState(wallet_user_state_from_str(state))
+                }
+                (action, _) => {
+                    return Err(PersistenceError::Execution(
+                        format!("unknown admin audit log action: {action}").into(),
+                    ))
+                }
+            };
+
+            Ok(AdminAuditLogEntry {
+                id: model.id,
+                date_time: DateTime::<Local>::from(model.date_time),
+                wallet_id: model.wallet_id,
+                action,
+                operator: model.operator,
+                reason: model.reason,
+            })
+        })
+        .collect()
+}
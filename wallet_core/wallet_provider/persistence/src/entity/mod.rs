@@ -2,6 +2,9 @@
 
 pub mod prelude;
 
+pub mod admin_audit_log;
 pub mod wallet_user;
+pub mod wallet_user_device;
+pub mod wallet_user_instruction_audit_log;
 pub mod wallet_user_instruction_challenge;
 pub mod wallet_user_key;
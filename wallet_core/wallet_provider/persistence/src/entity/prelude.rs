@@ -1,5 +1,8 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.3
 
+pub use super::admin_audit_log::Entity as AdminAuditLog;
 pub use super::wallet_user::Entity as WalletUser;
+pub use super::wallet_user_device::Entity as WalletUserDevice;
+pub use super::wallet_user_instruction_audit_log::Entity as WalletUserInstructionAuditLog;
 pub use super::wallet_user_instruction_challenge::Entity as WalletUserInstructionChallenge;
 pub use super::wallet_user_key::Entity as WalletUserKey;
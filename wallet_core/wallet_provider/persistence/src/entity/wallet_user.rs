@@ -10,25 +10,41 @@ pub struct Model {
     #[sea_orm(unique)]
     pub wallet_id: String,
     #[sea_orm(column_type = "Binary(BlobSize::Blob(None))")]
-    pub hw_pubkey_der: Vec<u8>,
-    #[sea_orm(column_type = "Binary(BlobSize::Blob(None))")]
     pub encrypted_pin_pubkey_sec1: Vec<u8>,
     #[sea_orm(column_type = "Binary(BlobSize::Blob(None))")]
     pub pin_pubkey_iv: Vec<u8>,
-    pub instruction_sequence_number: i32,
     pub pin_entries: i16,
     pub last_unsuccessful_pin: Option<DateTimeWithTimeZone>,
     pub is_blocked: bool,
+    pub platform_attestation: Option<String>,
+    pub state: String,
+    pub pin_pubkey_encryption_key_version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
-    #[sea_orm(has_one = "super::wallet_user_instruction_challenge::Entity")]
+    #[sea_orm(has_many = "super::wallet_user_device::Entity")]
+    WalletUserDevice,
+    #[sea_orm(has_many = "super::wallet_user_instruction_audit_log::Entity")]
+    WalletUserInstructionAuditLog,
+    #[sea_orm(has_many = "super::wallet_user_instruction_challenge::Entity")]
     WalletUserInstructionChallenge,
     #[sea_orm(has_many = "super::wallet_user_key::Entity")]
     WalletUserKey,
 }
 
+impl Related<super::wallet_user_device::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WalletUserDevice.def()
+    }
+}
+
+impl Related<super::wallet_user_instruction_audit_log::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WalletUserInstructionAuditLog.def()
+    }
+}
+
 impl Related<super::wallet_user_instruction_challenge::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::WalletUserInstructionChallenge.def()
@@ -7,11 +7,12 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
-    #[sea_orm(unique)]
     pub wallet_user_id: Uuid,
     #[sea_orm(column_type = "Binary(BlobSize::Blob(None))")]
     pub instruction_challenge: Vec<u8>,
     pub expiration_date_time: DateTimeWithTimeZone,
+    #[sea_orm(unique)]
+    pub wallet_user_device_id: Uuid,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,6 +25,14 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     WalletUser,
+    #[sea_orm(
+        belongs_to = "super::wallet_user_device::Entity",
+        from = "Column::WalletUserDeviceId",
+        to = "super::wallet_user_device::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    WalletUserDevice,
 }
 
 impl Related<super::wallet_user::Entity> for Entity {
@@ -32,4 +41,10 @@ impl Related<super::wallet_user::Entity> for Entity {
     }
 }
 
+impl Related<super::wallet_user_device::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WalletUserDevice.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -11,6 +11,7 @@ pub struct Model {
     pub identifier: String,
     #[sea_orm(column_type = "Binary(BlobSize::Blob(None))")]
     pub encrypted_private_key: Vec<u8>,
+    pub wrapping_key_version: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Local};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use wallet_provider_domain::{
+    model::instruction_audit_log::{InstructionAuditLogEntry, InstructionOutcome, InstructionType},
+    repository::PersistenceError,
+};
+
+use crate::{entity::wallet_user_instruction_audit_log, PersistenceConnection};
+
+type Result<T> = std::result::Result<T, PersistenceError>;
+
+pub async fn create_instruction_audit_log_entry<S, T>(db: &T, entry: InstructionAuditLogEntry) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_instruction_audit_log::ActiveModel {
+        id: Set(entry.id),
+        wallet_user_id: Set(entry.wallet_user_id),
+        chain_index: Set(entry.chain_index as i64),
+        date_time: Set(entry.date_time.into()),
+        instruction_type: Set(entry.instruction_type.to_string()),
+        outcome: Set(entry.outcome.to_string()),
+        operation_count: Set(entry.operation_count as i32),
+        payload_hash: Set(entry.payload_hash),
+        hash: Set(entry.hash),
+    }
+    .insert(db.connection())
+    .await
+    .map(|_| ())
+    .map_err(|e| PersistenceError::Execution(e.into()))
+}
+
+pub async fn find_last_instruction_audit_log_entry<S, T>(
+    db: &T,
+    wallet_user_id: Uuid,
+) -> Result<Option<InstructionAuditLogEntry>>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_instruction_audit_log::Entity::find()
+        .filter(wallet_user_instruction_audit_log::Column::WalletUserId.eq(wallet_user_id))
+        .order_by_desc(wallet_user_instruction_audit_log::Column::ChainIndex)
+        .one(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?
+        .map(instruction_audit_log_entry)
+        .transpose()
+}
+
+pub async fn find_instruction_audit_log_entries<S, T>(
+    db: &T,
+    wallet_user_id: Uuid,
+) -> Result<Vec<InstructionAuditLogEntry>>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_instruction_audit_log::Entity::find()
+        .filter(wallet_user_instruction_audit_log::Column::WalletUserId.eq(wallet_user_id))
+        .order_by_asc(wallet_user_instruction_audit_log::Column::ChainIndex)
+        .all(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?
+        .into_iter()
+        .map(instruction_audit_log_entry)
+        .collect()
+}
+
+fn instruction_audit_log_entry(model: wallet_user_instruction_audit_log::Model) -> Result<InstructionAuditLogEntry> {
+    let instruction_type = InstructionType::from_str(&model.instruction_type).map_err(|_| {
+        PersistenceError::Execution(format!("unknown instruction type: {}", model.instruction_type).into())
+    })?;
+    let outcome = InstructionOutcome::from_str(&model.outcome)
+        .map_err(|_| PersistenceError::Execution(format!("unknown instruction outcome: {}", model.outcome).into()))?;
+
+    Ok(InstructionAuditLogEntry {
+        id: model.id,
+        wallet_user_id: model.wallet_user_id,
+        chain_index: model.chain_index as u64,
+        date_time: DateTime::<Local>::from(model.date_time),
+        instruction_type,
+        outcome,
+        operation_count: model.operation_count as u32,
+        payload_hash: model.payload_hash,
+        hash: model.hash,
+    })
+}
@@ -1,8 +1,11 @@
+pub mod admin_audit_log;
 pub mod database;
 pub mod entity;
+pub mod instruction_audit_log;
 pub mod repositories;
 pub mod transaction;
 pub mod wallet_user;
+pub mod wallet_user_device;
 pub mod wallet_user_key;
 
 pub trait PersistenceConnection<T> {
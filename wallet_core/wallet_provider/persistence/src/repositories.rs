@@ -1,17 +1,30 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Local};
+use p256::ecdsa::VerifyingKey;
 use uuid::{self, Uuid};
 
 use wallet_provider_domain::{
     model::{
-        wallet_user::{InstructionChallenge, WalletUserCreate, WalletUserKeys, WalletUserQueryResult},
+        admin_audit_log::AdminAuditLogEntry,
+        encrypted::Encrypted,
+        instruction_audit_log::InstructionAuditLogEntry,
+        wallet_user::{
+            InstructionChallenge, WalletUserCreate, WalletUserDetails, WalletUserDevice, WalletUserDeviceCreate,
+            WalletUserKeys, WalletUserQueryResult, WalletUserState,
+        },
         wrapped_key::WrappedKey,
     },
-    repository::{PersistenceError, TransactionStarter, WalletUserRepository},
+    repository::{
+        AdminAuditLogRepository, InstructionAuditLogRepository, KeyRotationRepository, PersistenceError,
+        TransactionStarter, WalletUserDeviceRepository, WalletUserRepository,
+    },
 };
 
-use crate::{database::Db, transaction, transaction::Transaction, wallet_user, wallet_user_key};
+use crate::{
+    admin_audit_log, database::Db, instruction_audit_log, transaction, transaction::Transaction, wallet_user,
+    wallet_user_device, wallet_user_key,
+};
 
 pub struct Repositories(Db);
 
@@ -44,37 +57,46 @@ impl WalletUserRepository for Repositories {
         &self,
         transaction: &Self::TransactionType,
         wallet_id: &str,
+        hw_pubkey: &VerifyingKey,
     ) -> Result<WalletUserQueryResult, PersistenceError> {
-        wallet_user::find_wallet_user_by_wallet_id(transaction, wallet_id).await
+        wallet_user::find_wallet_user_by_wallet_id(transaction, wallet_id, hw_pubkey).await
     }
 
-    async fn clear_instruction_challenge(
+    async fn find_wallet_user_details_by_wallet_id(
         &self,
         transaction: &Self::TransactionType,
         wallet_id: &str,
+    ) -> Result<Option<WalletUserDetails>, PersistenceError> {
+        wallet_user::find_wallet_user_details_by_wallet_id(transaction, wallet_id).await
+    }
+
+    async fn clear_instruction_challenge(
+        &self,
+        transaction: &Self::TransactionType,
+        device_id: Uuid,
     ) -> Result<(), PersistenceError> {
-        wallet_user::clear_instruction_challenge(transaction, wallet_id).await
+        wallet_user::clear_instruction_challenge(transaction, device_id).await
     }
 
     async fn update_instruction_sequence_number(
         &self,
         transaction: &Self::TransactionType,
-        wallet_id: &str,
+        device_id: Uuid,
         instruction_sequence_number: u64,
     ) -> Result<(), PersistenceError> {
-        wallet_user::update_instruction_sequence_number(transaction, wallet_id, instruction_sequence_number).await
+        wallet_user::update_instruction_sequence_number(transaction, device_id, instruction_sequence_number).await
     }
 
     async fn update_instruction_challenge_and_sequence_number(
         &self,
         transaction: &Self::TransactionType,
-        wallet_id: &str,
+        device_id: Uuid,
         challenge: InstructionChallenge,
         instruction_sequence_number: u64,
     ) -> Result<(), PersistenceError> {
         wallet_user::update_instruction_challenge_and_sequence_number(
             transaction,
-            wallet_id,
+            device_id,
             challenge,
             instruction_sequence_number,
         )
@@ -85,10 +107,11 @@ impl WalletUserRepository for Repositories {
         &self,
         transaction: &Self::TransactionType,
         wallet_id: &str,
+        pin_entries: u8,
         is_blocked: bool,
         datetime: DateTime<Local>,
     ) -> Result<(), PersistenceError> {
-        wallet_user::register_unsuccessful_pin_entry(transaction, wallet_id, is_blocked, datetime).await
+        wallet_user::register_unsuccessful_pin_entry(transaction, wallet_id, pin_entries, is_blocked, datetime).await
     }
 
     async fn reset_unsuccessful_pin_entries(
@@ -99,6 +122,39 @@ impl WalletUserRepository for Repositories {
         wallet_user::reset_unsuccessful_pin_entries(transaction, wallet_id).await
     }
 
+    async fn block_wallet_user(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: &str,
+    ) -> Result<(), PersistenceError> {
+        wallet_user::block_wallet_user(transaction, wallet_id).await
+    }
+
+    async fn update_wallet_user_pin_pubkey(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: &str,
+        encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+        pin_pubkey_encryption_key_version: u32,
+    ) -> Result<(), PersistenceError> {
+        wallet_user::update_wallet_user_pin_pubkey(
+            transaction,
+            wallet_id,
+            encrypted_pin_pubkey,
+            pin_pubkey_encryption_key_version,
+        )
+        .await
+    }
+
+    async fn update_wallet_user_state(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: &str,
+        state: WalletUserState,
+    ) -> Result<(), PersistenceError> {
+        wallet_user::update_wallet_user_state(transaction, wallet_id, state).await
+    }
+
     async fn save_keys(
         &self,
         transaction: &Self::TransactionType,
@@ -115,21 +171,173 @@ impl WalletUserRepository for Repositories {
     ) -> Result<HashMap<String, WrappedKey>, PersistenceError> {
         wallet_user_key::find_keys_by_identifiers(transaction, wallet_user_id, key_identifiers).await
     }
+
+    async fn transfer_keys(
+        &self,
+        transaction: &Self::TransactionType,
+        source_wallet_user_id: Uuid,
+        destination_wallet_user_id: Uuid,
+    ) -> Result<(), PersistenceError> {
+        wallet_user_key::transfer_keys(transaction, source_wallet_user_id, destination_wallet_user_id).await
+    }
+}
+
+impl AdminAuditLogRepository for Repositories {
+    type TransactionType = Transaction;
+
+    async fn create_admin_audit_log_entry(
+        &self,
+        transaction: &Self::TransactionType,
+        entry: AdminAuditLogEntry,
+    ) -> Result<(), PersistenceError> {
+        admin_audit_log::create_admin_audit_log_entry(transaction, entry).await
+    }
+
+    async fn find_admin_audit_log_entries(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_id: Option<&str>,
+    ) -> Result<Vec<AdminAuditLogEntry>, PersistenceError> {
+        admin_audit_log::find_admin_audit_log_entries(transaction, wallet_id).await
+    }
+}
+
+impl InstructionAuditLogRepository for Repositories {
+    type TransactionType = Transaction;
+
+    async fn create_instruction_audit_log_entry(
+        &self,
+        transaction: &Self::TransactionType,
+        entry: InstructionAuditLogEntry,
+    ) -> Result<(), PersistenceError> {
+        instruction_audit_log::create_instruction_audit_log_entry(transaction, entry).await
+    }
+
+    async fn find_last_instruction_audit_log_entry(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+    ) -> Result<Option<InstructionAuditLogEntry>, PersistenceError> {
+        instruction_audit_log::find_last_instruction_audit_log_entry(transaction, wallet_user_id).await
+    }
+
+    async fn find_instruction_audit_log_entries(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+    ) -> Result<Vec<InstructionAuditLogEntry>, PersistenceError> {
+        instruction_audit_log::find_instruction_audit_log_entries(transaction, wallet_user_id).await
+    }
+}
+
+impl WalletUserDeviceRepository for Repositories {
+    type TransactionType = Transaction;
+
+    async fn create_wallet_user_device(
+        &self,
+        transaction: &Self::TransactionType,
+        device: WalletUserDeviceCreate,
+    ) -> Result<(), PersistenceError> {
+        wallet_user_device::create_wallet_user_device(transaction, device).await
+    }
+
+    async fn find_wallet_user_devices(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+    ) -> Result<Vec<WalletUserDevice>, PersistenceError> {
+        wallet_user_device::find_wallet_user_devices(transaction, wallet_user_id).await
+    }
+
+    async fn delete_wallet_user_device(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+        device_id: Uuid,
+    ) -> Result<(), PersistenceError> {
+        wallet_user_device::delete_wallet_user_device(transaction, wallet_user_id, device_id).await
+    }
+
+    async fn update_device_link_challenge(
+        &self,
+        transaction: &Self::TransactionType,
+        device_id: Uuid,
+        challenge: Option<InstructionChallenge>,
+    ) -> Result<(), PersistenceError> {
+        wallet_user_device::update_device_link_challenge(transaction, device_id, challenge).await
+    }
+}
+
+impl KeyRotationRepository for Repositories {
+    type TransactionType = Transaction;
+
+    async fn find_wallet_user_keys_to_rewrap(
+        &self,
+        transaction: &Self::TransactionType,
+        current_version: u32,
+        limit: u64,
+    ) -> Result<Vec<(Uuid, WrappedKey)>, PersistenceError> {
+        wallet_user_key::find_keys_to_rewrap(transaction, current_version, limit).await
+    }
+
+    async fn update_wallet_user_key(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_key_id: Uuid,
+        key: WrappedKey,
+    ) -> Result<(), PersistenceError> {
+        wallet_user_key::update_key(transaction, wallet_user_key_id, key).await
+    }
+
+    async fn find_pin_pubkeys_to_reencrypt(
+        &self,
+        transaction: &Self::TransactionType,
+        current_version: u32,
+        limit: u64,
+    ) -> Result<Vec<(Uuid, Encrypted<VerifyingKey>, u32)>, PersistenceError> {
+        wallet_user::find_pin_pubkeys_to_reencrypt(transaction, current_version, limit).await
+    }
+
+    async fn update_encrypted_pin_pubkey(
+        &self,
+        transaction: &Self::TransactionType,
+        wallet_user_id: Uuid,
+        encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+        pin_pubkey_encryption_key_version: u32,
+    ) -> Result<(), PersistenceError> {
+        wallet_user::update_encrypted_pin_pubkey(
+            transaction,
+            wallet_user_id,
+            encrypted_pin_pubkey,
+            pin_pubkey_encryption_key_version,
+        )
+        .await
+    }
 }
 
 #[cfg(feature = "mock")]
 pub mod mock {
     use chrono::{DateTime, Local};
     use mockall;
+    use p256::ecdsa::VerifyingKey;
     use std::collections::HashMap;
     use uuid::Uuid;
 
     use wallet_provider_domain::{
         model::{
-            wallet_user::{InstructionChallenge, WalletUserCreate, WalletUserKeys, WalletUserQueryResult},
+            admin_audit_log::AdminAuditLogEntry,
+            encrypted::Encrypted,
+            instruction_audit_log::InstructionAuditLogEntry,
+            wallet_user::{
+                InstructionChallenge, WalletUserCreate, WalletUserDetails, WalletUserDevice, WalletUserDeviceCreate,
+                WalletUserKeys, WalletUserQueryResult, WalletUserState,
+            },
             wrapped_key::WrappedKey,
         },
-        repository::{MockTransaction, PersistenceError, TransactionStarter, WalletUserRepository},
+        repository::{
+            AdminAuditLogRepository, InstructionAuditLogRepository, KeyRotationRepository, MockTransaction,
+            PersistenceError, TransactionStarter, WalletUserDeviceRepository, WalletUserRepository,
+        },
     };
 
     mockall::mock! {
@@ -148,12 +356,20 @@ pub mod mock {
                 &self,
                 _transaction: &MockTransaction,
                 wallet_id: &str,
+                hw_pubkey: &VerifyingKey,
             ) -> Result<WalletUserQueryResult, PersistenceError>;
 
+            async fn find_wallet_user_details_by_wallet_id(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_id: &str,
+            ) -> Result<Option<WalletUserDetails>, PersistenceError>;
+
             async fn register_unsuccessful_pin_entry(
                 &self,
                 _transaction: &MockTransaction,
                 _wallet_id: &str,
+                _pin_entries: u8,
                 _is_blocked: bool,
                 _datetime: DateTime<Local>,
             ) -> Result<(), PersistenceError>;
@@ -164,16 +380,37 @@ pub mod mock {
                 _wallet_id: &str,
             ) -> Result<(), PersistenceError>;
 
-            async fn clear_instruction_challenge(
+            async fn block_wallet_user(
                 &self,
                 _transaction: &MockTransaction,
                 _wallet_id: &str,
             ) -> Result<(), PersistenceError>;
 
-            async fn update_instruction_challenge_and_sequence_number(
+            async fn update_wallet_user_pin_pubkey(
+                &self,
+                _transaction: &MockTransaction,
+                _wallet_id: &str,
+                _encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+                _pin_pubkey_encryption_key_version: u32,
+            ) -> Result<(), PersistenceError>;
+
+            async fn update_wallet_user_state(
                 &self,
                 _transaction: &MockTransaction,
                 _wallet_id: &str,
+                _state: WalletUserState,
+            ) -> Result<(), PersistenceError>;
+
+            async fn clear_instruction_challenge(
+                &self,
+                _transaction: &MockTransaction,
+                _device_id: Uuid,
+            ) -> Result<(), PersistenceError>;
+
+            async fn update_instruction_challenge_and_sequence_number(
+                &self,
+                _transaction: &MockTransaction,
+                _device_id: Uuid,
                 _challenge: InstructionChallenge,
                 _instruction_sequence_number: u64,
             ) -> Result<(), PersistenceError>;
@@ -181,7 +418,7 @@ pub mod mock {
             async fn update_instruction_sequence_number(
                 &self,
                 _transaction: &MockTransaction,
-                _wallet_id: &str,
+                _device_id: Uuid,
                 _instruction_sequence_number: u64,
             ) -> Result<(), PersistenceError>;
 
@@ -197,6 +434,114 @@ pub mod mock {
                 wallet_user_id: Uuid,
                 key_identifiers: &[String],
             ) -> Result<HashMap<String, WrappedKey>, PersistenceError>;
+
+            async fn transfer_keys(
+                &self,
+                _transaction: &MockTransaction,
+                source_wallet_user_id: Uuid,
+                destination_wallet_user_id: Uuid,
+            ) -> Result<(), PersistenceError>;
+        }
+
+        impl AdminAuditLogRepository for TransactionalWalletUserRepository {
+            type TransactionType = MockTransaction;
+
+            async fn create_admin_audit_log_entry(
+                &self,
+                _transaction: &MockTransaction,
+                entry: AdminAuditLogEntry,
+            ) -> Result<(), PersistenceError>;
+
+            async fn find_admin_audit_log_entries<'a>(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_id: Option<&'a str>,
+            ) -> Result<Vec<AdminAuditLogEntry>, PersistenceError>;
+        }
+
+        impl InstructionAuditLogRepository for TransactionalWalletUserRepository {
+            type TransactionType = MockTransaction;
+
+            async fn create_instruction_audit_log_entry(
+                &self,
+                _transaction: &MockTransaction,
+                entry: InstructionAuditLogEntry,
+            ) -> Result<(), PersistenceError>;
+
+            async fn find_last_instruction_audit_log_entry(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_user_id: Uuid,
+            ) -> Result<Option<InstructionAuditLogEntry>, PersistenceError>;
+
+            async fn find_instruction_audit_log_entries(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_user_id: Uuid,
+            ) -> Result<Vec<InstructionAuditLogEntry>, PersistenceError>;
+        }
+
+        impl WalletUserDeviceRepository for TransactionalWalletUserRepository {
+            type TransactionType = MockTransaction;
+
+            async fn create_wallet_user_device(
+                &self,
+                _transaction: &MockTransaction,
+                device: WalletUserDeviceCreate,
+            ) -> Result<(), PersistenceError>;
+
+            async fn find_wallet_user_devices(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_user_id: Uuid,
+            ) -> Result<Vec<WalletUserDevice>, PersistenceError>;
+
+            async fn delete_wallet_user_device(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_user_id: Uuid,
+                device_id: Uuid,
+            ) -> Result<(), PersistenceError>;
+
+            async fn update_device_link_challenge(
+                &self,
+                _transaction: &MockTransaction,
+                device_id: Uuid,
+                challenge: Option<InstructionChallenge>,
+            ) -> Result<(), PersistenceError>;
+        }
+
+        impl KeyRotationRepository for TransactionalWalletUserRepository {
+            type TransactionType = MockTransaction;
+
+            async fn find_wallet_user_keys_to_rewrap(
+                &self,
+                _transaction: &MockTransaction,
+                current_version: u32,
+                limit: u64,
+            ) -> Result<Vec<(Uuid, WrappedKey)>, PersistenceError>;
+
+            async fn update_wallet_user_key(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_user_key_id: Uuid,
+                key: WrappedKey,
+            ) -> Result<(), PersistenceError>;
+
+            async fn find_pin_pubkeys_to_reencrypt(
+                &self,
+                _transaction: &MockTransaction,
+                current_version: u32,
+                limit: u64,
+            ) -> Result<Vec<(Uuid, Encrypted<VerifyingKey>, u32)>, PersistenceError>;
+
+            async fn update_encrypted_pin_pubkey(
+                &self,
+                _transaction: &MockTransaction,
+                wallet_user_id: Uuid,
+                encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+                pin_pubkey_encryption_key_version: u32,
+            ) -> Result<(), PersistenceError>;
         }
 
         impl TransactionStarter for TransactionalWalletUserRepository {
@@ -7,7 +7,7 @@ use sea_orm::{
     sea_query::{Expr, IntoIden, OnConflict, Query, SimpleExpr},
     ActiveModelTrait,
     ActiveValue::Set,
-    ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
 };
 
 use uuid::Uuid;
@@ -15,13 +15,17 @@ use wallet_common::account::serialization::DerVerifyingKey;
 use wallet_provider_domain::{
     model::{
         encrypted::{Encrypted, InitializationVector},
-        wallet_user::{InstructionChallenge, WalletUser, WalletUserCreate, WalletUserQueryResult},
+        wallet_user::{
+            InstructionChallenge, WalletUser, WalletUserCreate, WalletUserDetails, WalletUserDeviceCreate,
+            WalletUserPlatformAttestation, WalletUserQueryResult, WalletUserState,
+        },
     },
     repository::PersistenceError,
 };
 
 use crate::{
-    entity::{wallet_user, wallet_user_instruction_challenge},
+    entity::{wallet_user, wallet_user_device, wallet_user_instruction_challenge, wallet_user_key},
+    wallet_user_device::create_wallet_user_device,
     PersistenceConnection,
 };
 
@@ -35,87 +39,199 @@ where
     wallet_user::ActiveModel {
         id: Set(user.id),
         wallet_id: Set(user.wallet_id),
-        hw_pubkey_der: Set(user.hw_pubkey.to_public_key_der()?.to_vec()),
         encrypted_pin_pubkey_sec1: Set(user.encrypted_pin_pubkey.data),
         pin_pubkey_iv: Set(user.encrypted_pin_pubkey.iv.0),
-        instruction_sequence_number: Set(0),
+        pin_pubkey_encryption_key_version: Set(i32::try_from(user.pin_pubkey_encryption_key_version).unwrap()),
         pin_entries: Set(0),
         last_unsuccessful_pin: Set(None),
         is_blocked: Set(false),
+        platform_attestation: Set(user
+            .platform_attestation
+            .map(|platform_attestation| platform_attestation_to_str(platform_attestation).to_string())),
+        state: Set(wallet_user_state_to_str(WalletUserState::Active).to_string()),
     }
     .insert(db.connection())
     .await
-    .map(|_| ())
-    .map_err(|e| PersistenceError::Execution(e.into()))
+    .map_err(|e| PersistenceError::Execution(e.into()))?;
+
+    // The device the wallet user registers with is the first device linked to it.
+    create_wallet_user_device(
+        db,
+        WalletUserDeviceCreate {
+            id: user.device_id,
+            wallet_user_id: user.id,
+            hw_pubkey: user.hw_pubkey,
+            linked_date_time: Local::now(),
+            platform_attestation: user.platform_attestation,
+        },
+    )
+    .await
 }
 
-pub async fn find_wallet_user_by_wallet_id<S, T>(db: &T, wallet_id: &str) -> Result<WalletUserQueryResult>
+/// Find a wallet user by its wallet id, as seen from the device with the hardware public key `hw_pubkey`. When the
+/// wallet user is active but no such device is linked to it, [`WalletUserQueryResult::DeviceNotLinked`] is returned.
+///
+/// The row of the wallet user is locked until the end of the transaction, so that concurrent transactions cannot
+/// both register an unsuccessful PIN entry based on the same number of previous PIN entries.
+pub async fn find_wallet_user_by_wallet_id<S, T>(
+    db: &T,
+    wallet_id: &str,
+    hw_pubkey: &VerifyingKey,
+) -> Result<WalletUserQueryResult>
 where
     S: ConnectionTrait,
     T: PersistenceConnection<S>,
 {
-    let user_challenge = wallet_user::Entity::find()
-        .find_also_related(wallet_user_instruction_challenge::Entity)
+    let Some(wallet_user) = wallet_user::Entity::find()
         .filter(wallet_user::Column::WalletId.eq(wallet_id))
+        .lock_exclusive()
         .one(db.connection())
         .await
-        .map_err(|e| PersistenceError::Execution(e.into()))?;
+        .map_err(|e| PersistenceError::Execution(e.into()))?
+    else {
+        return Ok(WalletUserQueryResult::NotFound);
+    };
 
-    Ok(user_challenge
-        .map(|(wallet_user, challenge)| {
-            if wallet_user.is_blocked {
-                WalletUserQueryResult::Blocked
-            } else {
-                WalletUserQueryResult::Found(Box::new(WalletUser {
-                    id: wallet_user.id,
-                    wallet_id: wallet_user.wallet_id,
-                    encrypted_pin_pubkey: Encrypted::new(
-                        wallet_user.encrypted_pin_pubkey_sec1,
-                        InitializationVector(wallet_user.pin_pubkey_iv),
-                    ),
-                    hw_pubkey: DerVerifyingKey(VerifyingKey::from_public_key_der(&wallet_user.hw_pubkey_der).unwrap()),
-                    unsuccessful_pin_entries: wallet_user.pin_entries.try_into().ok().unwrap_or(u8::MAX),
-                    last_unsuccessful_pin_entry: wallet_user.last_unsuccessful_pin.map(DateTime::<Local>::from),
-                    instruction_challenge: challenge.map(|c| InstructionChallenge {
-                        bytes: c.instruction_challenge,
-                        expiration_date_time: DateTime::<Local>::from(c.expiration_date_time),
-                    }),
-                    instruction_sequence_number: u64::try_from(wallet_user.instruction_sequence_number).unwrap(),
-                }))
-            }
-        })
-        .unwrap_or(WalletUserQueryResult::NotFound))
+    match wallet_user_state_from_str(&wallet_user.state) {
+        WalletUserState::Revoked => return Ok(WalletUserQueryResult::Revoked),
+        WalletUserState::Suspended => return Ok(WalletUserQueryResult::Suspended),
+        WalletUserState::Active => {}
+    }
+
+    let Some((device, challenge)) = wallet_user_device::Entity::find()
+        .find_also_related(wallet_user_instruction_challenge::Entity)
+        .filter(wallet_user_device::Column::WalletUserId.eq(wallet_user.id))
+        .filter(wallet_user_device::Column::HwPubkeyDer.eq(hw_pubkey.to_public_key_der()?.to_vec()))
+        .one(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?
+    else {
+        return Ok(WalletUserQueryResult::DeviceNotLinked);
+    };
+
+    let is_blocked = wallet_user.is_blocked;
+    let user = Box::new(WalletUser {
+        id: wallet_user.id,
+        wallet_id: wallet_user.wallet_id,
+        device_id: device.id,
+        encrypted_pin_pubkey: Encrypted::new(
+            wallet_user.encrypted_pin_pubkey_sec1,
+            InitializationVector(wallet_user.pin_pubkey_iv),
+        ),
+        pin_pubkey_encryption_key_version: u32::try_from(wallet_user.pin_pubkey_encryption_key_version).unwrap(),
+        hw_pubkey: DerVerifyingKey(VerifyingKey::from_public_key_der(&device.hw_pubkey_der).unwrap()),
+        unsuccessful_pin_entries: wallet_user.pin_entries.try_into().ok().unwrap_or(u8::MAX),
+        last_unsuccessful_pin_entry: wallet_user.last_unsuccessful_pin.map(DateTime::<Local>::from),
+        instruction_challenge: challenge.map(|c| InstructionChallenge {
+            bytes: c.instruction_challenge,
+            expiration_date_time: DateTime::<Local>::from(c.expiration_date_time),
+        }),
+        instruction_sequence_number: u64::try_from(device.instruction_sequence_number).unwrap(),
+        device_link_challenge: device
+            .device_link_challenge
+            .zip(device.device_link_challenge_expiration_date_time)
+            .map(|(bytes, expiration_date_time)| InstructionChallenge {
+                bytes,
+                expiration_date_time: DateTime::<Local>::from(expiration_date_time),
+            }),
+        platform_attestation: wallet_user
+            .platform_attestation
+            .as_deref()
+            .and_then(platform_attestation_from_str),
+    });
+
+    if is_blocked {
+        Ok(WalletUserQueryResult::Blocked(user))
+    } else {
+        Ok(WalletUserQueryResult::Found(user))
+    }
 }
-pub async fn clear_instruction_challenge<S, T>(db: &T, wallet_id: &str) -> Result<()>
+
+pub async fn find_wallet_user_details_by_wallet_id<S, T>(db: &T, wallet_id: &str) -> Result<Option<WalletUserDetails>>
 where
     S: ConnectionTrait,
     T: PersistenceConnection<S>,
 {
-    let stmt = Query::delete()
-        .from_table(wallet_user_instruction_challenge::Entity)
-        .and_where(
-            wallet_user_instruction_challenge::Column::WalletUserId.in_subquery(
-                Query::select()
-                    .column(wallet_user::Column::Id)
-                    .from(wallet_user::Entity)
-                    .and_where(Expr::col(wallet_user::Column::WalletId).eq(wallet_id))
-                    .to_owned(),
-            ),
-        )
-        .to_owned();
+    let Some(wallet_user) = wallet_user::Entity::find()
+        .filter(wallet_user::Column::WalletId.eq(wallet_id))
+        .one(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?
+    else {
+        return Ok(None);
+    };
 
-    let conn = db.connection();
-    let builder = conn.get_database_backend();
-    conn.execute(builder.build(&stmt))
+    let key_count = wallet_user_key::Entity::find()
+        .filter(wallet_user_key::Column::WalletUserId.eq(wallet_user.id))
+        .count(db.connection())
         .await
         .map_err(|e| PersistenceError::Execution(e.into()))?;
 
-    Ok(())
+    Ok(Some(WalletUserDetails {
+        id: wallet_user.id,
+        wallet_id: wallet_user.wallet_id,
+        state: wallet_user_state_from_str(&wallet_user.state),
+        is_blocked: wallet_user.is_blocked,
+        unsuccessful_pin_entries: wallet_user.pin_entries.try_into().ok().unwrap_or(u8::MAX),
+        last_unsuccessful_pin_entry: wallet_user.last_unsuccessful_pin.map(DateTime::<Local>::from),
+        platform_attestation: wallet_user
+            .platform_attestation
+            .as_deref()
+            .and_then(platform_attestation_from_str),
+        key_count,
+    }))
+}
+
+pub(crate) fn wallet_user_state_to_str(state: WalletUserState) -> &'static str {
+    match state {
+        WalletUserState::Active => "active",
+        WalletUserState::Suspended => "suspended",
+        WalletUserState::Revoked => "revoked",
+    }
+}
+
+// Any unknown state is treated as revoked, so that a corrupt value never grants access to a wallet.
+pub(crate) fn wallet_user_state_from_str(state: &str) -> WalletUserState {
+    match state {
+        "active" => WalletUserState::Active,
+        "suspended" => WalletUserState::Suspended,
+        _ => WalletUserState::Revoked,
+    }
+}
+
+pub(crate) fn platform_attestation_to_str(platform_attestation: WalletUserPlatformAttestation) -> &'static str {
+    match platform_attestation {
+        WalletUserPlatformAttestation::AndroidTrustedEnvironment => "android_trusted_environment",
+        WalletUserPlatformAttestation::AndroidStrongBox => "android_strong_box",
+        WalletUserPlatformAttestation::AppleAppAttest => "apple_app_attest",
+    }
+}
+
+pub(crate) fn platform_attestation_from_str(platform_attestation: &str) -> Option<WalletUserPlatformAttestation> {
+    match platform_attestation {
+        "android_trusted_environment" => Some(WalletUserPlatformAttestation::AndroidTrustedEnvironment),
+        "android_strong_box" => Some(WalletUserPlatformAttestation::AndroidStrongBox),
+        "apple_app_attest" => Some(WalletUserPlatformAttestation::AppleAppAttest),
+        _ => None,
+    }
+}
+
+pub async fn clear_instruction_challenge<S, T>(db: &T, device_id: Uuid) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_instruction_challenge::Entity::delete_many()
+        .filter(wallet_user_instruction_challenge::Column::WalletUserDeviceId.eq(device_id))
+        .exec(db.connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| PersistenceError::Execution(e.into()))
 }
 
 pub async fn update_instruction_challenge_and_sequence_number<S, T>(
     db: &T,
-    wallet_id: &str,
+    device_id: Uuid,
     instruction_challenge: InstructionChallenge,
     instruction_sequence_number: u64,
 ) -> Result<()>
@@ -123,30 +239,32 @@ where
     S: ConnectionTrait,
     T: PersistenceConnection<S>,
 {
-    update_instruction_sequence_number(db, wallet_id, instruction_sequence_number).await?;
+    update_instruction_sequence_number(db, device_id, instruction_sequence_number).await?;
 
-    // insert a new instruction challenge, or update if one already exists with this wallet.id
+    // insert a new instruction challenge, or update if one already exists for this device
     let stmt = Query::insert()
         .into_table(wallet_user_instruction_challenge::Entity)
         .columns([
             wallet_user_instruction_challenge::Column::Id,
             wallet_user_instruction_challenge::Column::WalletUserId,
+            wallet_user_instruction_challenge::Column::WalletUserDeviceId,
             wallet_user_instruction_challenge::Column::InstructionChallenge,
             wallet_user_instruction_challenge::Column::ExpirationDateTime,
         ])
         .select_from(
             Query::select()
                 .expr(Expr::value(Uuid::new_v4()))
-                .column(wallet_user::Column::Id)
+                .column(wallet_user_device::Column::WalletUserId)
+                .column(wallet_user_device::Column::Id)
                 .expr(Expr::value(instruction_challenge.bytes))
                 .expr(Expr::value(instruction_challenge.expiration_date_time))
-                .from(wallet_user::Entity)
-                .and_where(Expr::col(wallet_user::Column::WalletId).eq(wallet_id))
+                .from(wallet_user_device::Entity)
+                .and_where(Expr::col(wallet_user_device::Column::Id).eq(device_id))
                 .to_owned(),
         )
         .map_err(|e| PersistenceError::Execution(e.into()))?
         .on_conflict(
-            OnConflict::column(wallet_user_instruction_challenge::Column::WalletUserId)
+            OnConflict::column(wallet_user_instruction_challenge::Column::WalletUserDeviceId)
                 .update_columns([
                     wallet_user_instruction_challenge::Column::InstructionChallenge,
                     wallet_user_instruction_challenge::Column::ExpirationDateTime,
@@ -166,27 +284,29 @@ where
 
 pub async fn update_instruction_sequence_number<S, T>(
     db: &T,
-    wallet_id: &str,
+    device_id: Uuid,
     instruction_sequence_number: u64,
 ) -> Result<()>
 where
     S: ConnectionTrait,
     T: PersistenceConnection<S>,
 {
-    update_fields(
-        db,
-        wallet_id,
-        vec![(
-            wallet_user::Column::InstructionSequenceNumber,
+    wallet_user_device::Entity::update_many()
+        .col_expr(
+            wallet_user_device::Column::InstructionSequenceNumber,
             Expr::value(instruction_sequence_number),
-        )],
-    )
-    .await
+        )
+        .filter(wallet_user_device::Column::Id.eq(device_id))
+        .exec(db.connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| PersistenceError::Execution(e.into()))
 }
 
 pub async fn register_unsuccessful_pin_entry<S, T>(
     db: &T,
     wallet_id: &str,
+    pin_entries: u8,
     is_blocked: bool,
     datetime: DateTime<Local>,
 ) -> Result<()>
@@ -197,11 +317,7 @@ where
     update_pin_entries(
         db,
         wallet_id,
-        // make sure the pin_entries column doesn't overflow
-        Expr::cust_with_exprs(
-            "least($1, $2)",
-            vec![Expr::col(wallet_user::Column::PinEntries).add(1), Expr::value(u8::MAX)],
-        ),
+        Expr::value(pin_entries),
         Some(datetime.into()),
         is_blocked,
     )
@@ -217,6 +333,143 @@ where
     update_pin_entries(db, wallet_id, Expr::value(0), datetime, false).await
 }
 
+pub async fn block_wallet_user<S, T>(db: &T, wallet_id: &str) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    update_fields(db, wallet_id, vec![(wallet_user::Column::IsBlocked, Expr::value(true))]).await
+}
+
+/// Replace the PIN public key of a wallet user after a successful PIN recovery, which also unblocks the wallet user.
+pub async fn update_wallet_user_pin_pubkey<S, T>(
+    db: &T,
+    wallet_id: &str,
+    encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+    pin_pubkey_encryption_key_version: u32,
+) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    let datetime: Option<DateTime<Utc>> = None;
+
+    update_fields(
+        db,
+        wallet_id,
+        vec![
+            (
+                wallet_user::Column::EncryptedPinPubkeySec1,
+                Expr::value(encrypted_pin_pubkey.data),
+            ),
+            (wallet_user::Column::PinPubkeyIv, Expr::value(encrypted_pin_pubkey.iv.0)),
+            (
+                wallet_user::Column::PinPubkeyEncryptionKeyVersion,
+                Expr::value(pin_pubkey_encryption_key_version),
+            ),
+            (wallet_user::Column::PinEntries, Expr::value(0)),
+            (wallet_user::Column::LastUnsuccessfulPin, Expr::value(datetime)),
+            (wallet_user::Column::IsBlocked, Expr::value(false)),
+        ],
+    )
+    .await
+}
+
+/// Update the lifecycle state of a wallet user. As revocation is final, a revoked wallet user cannot be updated,
+/// in which case [`PersistenceError::NotFound`] is returned, as it is for an unknown wallet user.
+pub async fn update_wallet_user_state<S, T>(db: &T, wallet_id: &str, state: WalletUserState) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    let revoked = wallet_user_state_to_str(WalletUserState::Revoked);
+
+    let result = wallet_user::Entity::update_many()
+        .col_expr(wallet_user::Column::State, Expr::value(wallet_user_state_to_str(state)))
+        .filter(wallet_user::Column::WalletId.eq(wallet_id))
+        .filter(wallet_user::Column::State.ne(revoked))
+        .exec(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?;
+
+    if result.rows_affected == 0 {
+        return Err(PersistenceError::NotFound(format!(
+            "wallet user with wallet_id {wallet_id} that is not revoked"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Find at most `limit` PIN public keys, together with the version of the key they are encrypted with, that are not
+/// encrypted with `current_version` of the encryption key, ordered by the id of the wallet user. The rows are locked
+/// until the end of the transaction, so that a concurrent PIN recovery cannot be overwritten.
+pub async fn find_pin_pubkeys_to_reencrypt<S, T>(
+    db: &T,
+    current_version: u32,
+    limit: u64,
+) -> Result<Vec<(Uuid, Encrypted<VerifyingKey>, u32)>>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user::Entity::find()
+        .select_only()
+        .column(wallet_user::Column::Id)
+        .column(wallet_user::Column::EncryptedPinPubkeySec1)
+        .column(wallet_user::Column::PinPubkeyIv)
+        .column(wallet_user::Column::PinPubkeyEncryptionKeyVersion)
+        .filter(wallet_user::Column::PinPubkeyEncryptionKeyVersion.ne(current_version))
+        .order_by_asc(wallet_user::Column::Id)
+        .limit(limit)
+        .lock_exclusive()
+        .into_tuple::<(Uuid, Vec<u8>, Vec<u8>, i32)>()
+        .all(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))
+        .map(|result| {
+            result
+                .into_iter()
+                .map(|(id, data, iv, version)| {
+                    (
+                        id,
+                        Encrypted::new(data, InitializationVector(iv)),
+                        u32::try_from(version).unwrap(),
+                    )
+                })
+                .collect()
+        })
+}
+
+/// Replace the encrypted PIN public key of a wallet user with one that is encrypted with another version of the
+/// encryption key. Contrary to [`update_wallet_user_pin_pubkey`], this leaves the PIN entries of the wallet user as is.
+pub async fn update_encrypted_pin_pubkey<S, T>(
+    db: &T,
+    wallet_user_id: Uuid,
+    encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+    pin_pubkey_encryption_key_version: u32,
+) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user::Entity::update_many()
+        .col_expr(
+            wallet_user::Column::EncryptedPinPubkeySec1,
+            Expr::value(encrypted_pin_pubkey.data),
+        )
+        .col_expr(wallet_user::Column::PinPubkeyIv, Expr::value(encrypted_pin_pubkey.iv.0))
+        .col_expr(
+            wallet_user::Column::PinPubkeyEncryptionKeyVersion,
+            Expr::value(pin_pubkey_encryption_key_version),
+        )
+        .filter(wallet_user::Column::Id.eq(wallet_user_id))
+        .exec(db.connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| PersistenceError::Execution(e.into()))
+}
+
 async fn update_fields<S, T, C>(db: &T, wallet_id: &str, col_values: Vec<(C, SimpleExpr)>) -> Result<()>
 where
     S: ConnectionTrait,
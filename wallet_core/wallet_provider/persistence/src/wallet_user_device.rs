@@ -0,0 +1,128 @@
+use chrono::{DateTime, Local};
+use p256::{
+    ecdsa::VerifyingKey,
+    pkcs8::{DecodePublicKey, EncodePublicKey},
+};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use uuid::Uuid;
+
+use wallet_common::account::serialization::DerVerifyingKey;
+use wallet_provider_domain::{
+    model::wallet_user::{InstructionChallenge, WalletUserDevice, WalletUserDeviceCreate},
+    repository::PersistenceError,
+};
+
+use crate::{
+    entity::{wallet_user_device, wallet_user_instruction_challenge},
+    wallet_user::{platform_attestation_from_str, platform_attestation_to_str},
+    PersistenceConnection,
+};
+
+type Result<T> = std::result::Result<T, PersistenceError>;
+
+pub async fn create_wallet_user_device<S, T>(db: &T, device: WalletUserDeviceCreate) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_device::ActiveModel {
+        id: Set(device.id),
+        wallet_user_id: Set(device.wallet_user_id),
+        hw_pubkey_der: Set(device.hw_pubkey.to_public_key_der()?.to_vec()),
+        instruction_sequence_number: Set(0),
+        device_link_challenge: Set(None),
+        device_link_challenge_expiration_date_time: Set(None),
+        linked_date_time: Set(device.linked_date_time.into()),
+        platform_attestation: Set(device
+            .platform_attestation
+            .map(|platform_attestation| platform_attestation_to_str(platform_attestation).to_string())),
+    }
+    .insert(db.connection())
+    .await
+    .map(|_| ())
+    .map_err(|e| PersistenceError::Execution(e.into()))
+}
+
+pub async fn find_wallet_user_devices<S, T>(db: &T, wallet_user_id: Uuid) -> Result<Vec<WalletUserDevice>>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_device::Entity::find()
+        .filter(wallet_user_device::Column::WalletUserId.eq(wallet_user_id))
+        .order_by_asc(wallet_user_device::Column::LinkedDateTime)
+        .all(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?
+        .into_iter()
+        .map(|device| {
+            Ok(WalletUserDevice {
+                id: device.id,
+                hw_pubkey: DerVerifyingKey(VerifyingKey::from_public_key_der(&device.hw_pubkey_der)?),
+                linked_date_time: DateTime::<Local>::from(device.linked_date_time),
+                platform_attestation: device
+                    .platform_attestation
+                    .as_deref()
+                    .and_then(platform_attestation_from_str),
+            })
+        })
+        .collect()
+}
+
+/// Delete a device of a wallet user, together with its pending instruction challenge.
+pub async fn delete_wallet_user_device<S, T>(db: &T, wallet_user_id: Uuid, device_id: Uuid) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_instruction_challenge::Entity::delete_many()
+        .filter(wallet_user_instruction_challenge::Column::WalletUserId.eq(wallet_user_id))
+        .filter(wallet_user_instruction_challenge::Column::WalletUserDeviceId.eq(device_id))
+        .exec(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?;
+
+    let result = wallet_user_device::Entity::delete_many()
+        .filter(wallet_user_device::Column::WalletUserId.eq(wallet_user_id))
+        .filter(wallet_user_device::Column::Id.eq(device_id))
+        .exec(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))?;
+
+    if result.rows_affected == 0 {
+        return Err(PersistenceError::NotFound(format!(
+            "device {device_id} of wallet user {wallet_user_id}"
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn update_device_link_challenge<S, T>(
+    db: &T,
+    device_id: Uuid,
+    challenge: Option<InstructionChallenge>,
+) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    let (bytes, expiration_date_time) = challenge
+        .map(|challenge| (challenge.bytes, challenge.expiration_date_time))
+        .unzip();
+
+    wallet_user_device::Entity::update_many()
+        .col_expr(wallet_user_device::Column::DeviceLinkChallenge, Expr::value(bytes))
+        .col_expr(
+            wallet_user_device::Column::DeviceLinkChallengeExpirationDateTime,
+            Expr::value(expiration_date_time),
+        )
+        .filter(wallet_user_device::Column::Id.eq(device_id))
+        .exec(db.connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| PersistenceError::Execution(e.into()))
+}
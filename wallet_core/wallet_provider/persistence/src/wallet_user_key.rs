@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, Set};
+use sea_orm::{sea_query::Expr, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use uuid::Uuid;
 
 use wallet_provider_domain::{
     model::{wallet_user::WalletUserKeys, wrapped_key::WrappedKey},
@@ -23,6 +24,7 @@ where
             id: Set(key_create.wallet_user_key_id),
             wallet_user_id: Set(create.wallet_user_id),
             identifier: Set(key_create.key_identifier),
+            wrapping_key_version: Set(i32::try_from(key_create.key.wrapping_key_version()).unwrap()),
             encrypted_private_key: Set(key_create.key.into()),
         })
         .collect::<Vec<_>>();
@@ -47,19 +49,95 @@ where
         .select_only()
         .column(wallet_user_key::Column::Identifier)
         .column(wallet_user_key::Column::EncryptedPrivateKey)
+        .column(wallet_user_key::Column::WrappingKeyVersion)
         .filter(
             wallet_user_key::Column::WalletUserId
                 .eq(wallet_user_id)
                 .and(wallet_user_key::Column::Identifier.is_in(identifiers)),
         )
-        .into_tuple::<(String, Vec<u8>)>()
+        .into_tuple::<(String, Vec<u8>, i32)>()
         .all(db.connection())
         .await
         .map_err(|e| PersistenceError::Execution(e.into()))
         .map(|result| {
             result
                 .into_iter()
-                .map(|(id, key_data)| (id, WrappedKey::new(key_data)))
+                .map(|(id, key_data, version)| (id, wrapped_key(key_data, version)))
                 .collect()
         })
 }
+
+/// Moves all keys of one wallet user to another, which is used when a wallet is restored from a backup.
+pub async fn transfer_keys<S, T>(
+    db: &T,
+    source_wallet_user_id: uuid::Uuid,
+    destination_wallet_user_id: uuid::Uuid,
+) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_key::Entity::update_many()
+        .col_expr(
+            wallet_user_key::Column::WalletUserId,
+            Expr::value(destination_wallet_user_id),
+        )
+        .filter(wallet_user_key::Column::WalletUserId.eq(source_wallet_user_id))
+        .exec(db.connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| PersistenceError::Execution(e.into()))
+}
+
+/// Find at most `limit` keys that are not wrapped with `current_version` of the wrapping key, ordered by their id. The
+/// rows are locked until the end of the transaction.
+pub async fn find_keys_to_rewrap<S, T>(db: &T, current_version: u32, limit: u64) -> Result<Vec<(Uuid, WrappedKey)>>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_key::Entity::find()
+        .select_only()
+        .column(wallet_user_key::Column::Id)
+        .column(wallet_user_key::Column::EncryptedPrivateKey)
+        .column(wallet_user_key::Column::WrappingKeyVersion)
+        .filter(wallet_user_key::Column::WrappingKeyVersion.ne(current_version))
+        .order_by_asc(wallet_user_key::Column::Id)
+        .limit(limit)
+        .lock_exclusive()
+        .into_tuple::<(Uuid, Vec<u8>, i32)>()
+        .all(db.connection())
+        .await
+        .map_err(|e| PersistenceError::Execution(e.into()))
+        .map(|result| {
+            result
+                .into_iter()
+                .map(|(id, key_data, version)| (id, wrapped_key(key_data, version)))
+                .collect()
+        })
+}
+
+pub async fn update_key<S, T>(db: &T, wallet_user_key_id: Uuid, key: WrappedKey) -> Result<()>
+where
+    S: ConnectionTrait,
+    T: PersistenceConnection<S>,
+{
+    wallet_user_key::Entity::update_many()
+        .col_expr(
+            wallet_user_key::Column::WrappingKeyVersion,
+            Expr::value(key.wrapping_key_version()),
+        )
+        .col_expr(
+            wallet_user_key::Column::EncryptedPrivateKey,
+            Expr::value(Vec::<u8>::from(key)),
+        )
+        .filter(wallet_user_key::Column::Id.eq(wallet_user_key_id))
+        .exec(db.connection())
+        .await
+        .map(|_| ())
+        .map_err(|e| PersistenceError::Execution(e.into()))
+}
+
+fn wrapped_key(key_data: Vec<u8>, wrapping_key_version: i32) -> WrappedKey {
+    WrappedKey::new(key_data, u32::try_from(wrapping_key_version).unwrap())
+}
@@ -0,0 +1,57 @@
+use uuid::Uuid;
+
+use wallet_common::{generator::Generator, utils::random_string};
+use wallet_provider_domain::{
+    model::{
+        admin_audit_log::{AdminAction, AdminAuditLogEntry},
+        wallet_user::WalletUserState,
+    },
+    EpochGenerator,
+};
+use wallet_provider_persistence::admin_audit_log::{create_admin_audit_log_entry, find_admin_audit_log_entries};
+
+pub mod common;
+
+#[tokio::test]
+async fn test_create_and_find_admin_audit_log_entries() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_id = random_string(32);
+    let other_wallet_id = random_string(32);
+
+    for (wallet_id, action) in [
+        (&wallet_id, AdminAction::LookupWalletUser),
+        (&other_wallet_id, AdminAction::UnblockWalletUser),
+        (
+            &wallet_id,
+            AdminAction::UpdateWalletUserState(WalletUserState::Suspended),
+        ),
+    ] {
+        create_admin_audit_log_entry(
+            &db,
+            AdminAuditLogEntry {
+                id: Uuid::new_v4(),
+                date_time: EpochGenerator.generate(),
+                wallet_id: wallet_id.clone(),
+                action,
+                operator: "helpdesk".to_string(),
+                reason: Some("reported stolen".to_string()),
+            },
+        )
+        .await
+        .expect("Could not create admin audit log entry");
+    }
+
+    let entries = find_admin_audit_log_entries(&db, Some(&wallet_id))
+        .await
+        .expect("Could not find admin audit log entries");
+
+    assert!(entries
+        .iter()
+        .all(|entry| entry.operator == "helpdesk" && entry.reason.as_deref() == Some("reported stolen")));
+
+    let actions = entries.into_iter().map(|entry| entry.action).collect::<Vec<_>>();
+    assert_eq!(actions.len(), 2);
+    assert!(actions.contains(&AdminAction::LookupWalletUser));
+    assert!(actions.contains(&AdminAction::UpdateWalletUserState(WalletUserState::Suspended)));
+}
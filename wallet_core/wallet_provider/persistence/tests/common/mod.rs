@@ -43,17 +43,22 @@ pub async fn db_from_env() -> Result<Db, PersistenceError> {
 #[derive(Debug, thiserror::Error)]
 enum EncrypterError {}
 
-pub async fn create_wallet_user_with_random_keys<S, T>(db: &T, id: Uuid, wallet_id: String)
+/// Create a wallet user with a single device, which gets the same id as the wallet user. Returns the hardware public key
+/// of that device.
+pub async fn create_wallet_user_with_random_keys<S, T>(db: &T, id: Uuid, wallet_id: String) -> VerifyingKey
 where
     S: ConnectionTrait,
     T: PersistenceConnection<S>,
 {
+    let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+
     create_wallet_user(
         db,
         WalletUserCreate {
             id,
             wallet_id,
-            hw_pubkey: *SigningKey::random(&mut OsRng).verifying_key(),
+            device_id: id,
+            hw_pubkey,
             encrypted_pin_pubkey: Encrypter::<VerifyingKey>::encrypt(
                 &MockPkcs11Client::<EncrypterError>::default(),
                 "key1",
@@ -61,10 +66,14 @@ where
             )
             .await
             .unwrap(),
+            pin_pubkey_encryption_key_version: 0,
+            platform_attestation: None,
         },
     )
     .await
     .expect("Could not create wallet user");
+
+    hw_pubkey
 }
 
 pub async fn find_wallet_user<S, T>(db: &T, id: Uuid) -> Option<wallet_user::Model>
@@ -79,14 +88,14 @@ where
         .expect("Could not fetch wallet user")
 }
 
-pub async fn create_instruction_challenge_with_random_data<S, T>(db: &T, wallet_id: String)
+pub async fn create_instruction_challenge_with_random_data<S, T>(db: &T, device_id: Uuid)
 where
     S: ConnectionTrait,
     T: PersistenceConnection<S>,
 {
     update_instruction_challenge_and_sequence_number(
         db,
-        &wallet_id,
+        device_id,
         InstructionChallenge {
             expiration_date_time: Local::now(), // irrelevant for these tests
             bytes: random_bytes(32),
@@ -0,0 +1,188 @@
+use chrono::Local;
+use p256::ecdsa::signature::digest::MacError;
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use wallet_provider_domain::model::{
+    hsm::{mock::MockPkcs11Client, Hsm},
+    instruction_audit_log::{
+        verify_chain, InstructionAuditDetails, InstructionAuditLogEntry, InstructionOutcome, InstructionType,
+    },
+};
+use wallet_provider_persistence::{
+    entity::wallet_user_instruction_audit_log,
+    instruction_audit_log::{
+        create_instruction_audit_log_entry, find_instruction_audit_log_entries, find_last_instruction_audit_log_entry,
+    },
+    PersistenceConnection,
+};
+
+pub mod common;
+
+const KEY_IDENTIFIER: &str = "instruction_audit_log_key";
+
+#[derive(Debug, thiserror::Error)]
+#[error("hmac error: {0}")]
+struct HsmError(#[from] MacError);
+
+async fn hsm() -> MockPkcs11Client<HsmError> {
+    let hsm = MockPkcs11Client::default();
+    hsm.generate_generic_secret_key(KEY_IDENTIFIER).await.unwrap();
+
+    hsm
+}
+
+async fn new_entry(
+    hsm: &MockPkcs11Client<HsmError>,
+    previous: Option<&InstructionAuditLogEntry>,
+    wallet_user_id: Uuid,
+    instruction_type: InstructionType,
+    outcome: InstructionOutcome,
+    details: InstructionAuditDetails,
+) -> InstructionAuditLogEntry {
+    InstructionAuditLogEntry::new_chained(
+        previous,
+        Uuid::new_v4(),
+        wallet_user_id,
+        Local::now(),
+        instruction_type,
+        outcome,
+        details,
+        hsm,
+        KEY_IDENTIFIER,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_create_and_find_instruction_audit_log_entries() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+    let hsm = hsm().await;
+
+    let wallet_user_id = Uuid::new_v4();
+    common::create_wallet_user_with_random_keys(&db, wallet_user_id, Uuid::new_v4().to_string()).await;
+
+    assert!(find_last_instruction_audit_log_entry(&db, wallet_user_id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let mut previous = None;
+    for (instruction_type, outcome, details) in [
+        (
+            InstructionType::CheckPin,
+            InstructionOutcome::IncorrectPin,
+            InstructionAuditDetails::default(),
+        ),
+        (
+            InstructionType::Sign,
+            InstructionOutcome::Success,
+            InstructionAuditDetails {
+                operation_count: 2,
+                payload_hash: Some(vec![1, 2, 3]),
+            },
+        ),
+    ] {
+        let entry = new_entry(
+            &hsm,
+            previous.as_ref(),
+            wallet_user_id,
+            instruction_type,
+            outcome,
+            details,
+        )
+        .await;
+        create_instruction_audit_log_entry(&db, entry.clone())
+            .await
+            .expect("Could not create instruction audit log entry");
+
+        previous = Some(entry);
+    }
+
+    let last = find_last_instruction_audit_log_entry(&db, wallet_user_id)
+        .await
+        .unwrap()
+        .expect("last entry should exist");
+    assert_eq!(last.chain_index, 1);
+    assert_eq!(last.hash, previous.unwrap().hash);
+
+    let entries = find_instruction_audit_log_entries(&db, wallet_user_id)
+        .await
+        .expect("Could not find instruction audit log entries");
+
+    assert_eq!(
+        entries.iter().map(|entry| entry.instruction_type).collect::<Vec<_>>(),
+        vec![InstructionType::CheckPin, InstructionType::Sign]
+    );
+    verify_chain(&entries, &hsm, KEY_IDENTIFIER)
+        .await
+        .expect("persisted entries should form a valid hash chain");
+}
+
+#[tokio::test]
+async fn test_create_instruction_audit_log_entry_with_existing_chain_index_should_fail() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+    let hsm = hsm().await;
+
+    let wallet_user_id = Uuid::new_v4();
+    common::create_wallet_user_with_random_keys(&db, wallet_user_id, Uuid::new_v4().to_string()).await;
+
+    let new_entry = || {
+        new_entry(
+            &hsm,
+            None,
+            wallet_user_id,
+            InstructionType::CheckPin,
+            InstructionOutcome::Success,
+            InstructionAuditDetails::default(),
+        )
+    };
+
+    create_instruction_audit_log_entry(&db, new_entry().await)
+        .await
+        .unwrap();
+    create_instruction_audit_log_entry(&db, new_entry().await)
+        .await
+        .expect_err("forking the hash chain should fail");
+}
+
+#[tokio::test]
+async fn test_instruction_audit_log_entries_cannot_be_changed() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+    let hsm = hsm().await;
+
+    let wallet_user_id = Uuid::new_v4();
+    common::create_wallet_user_with_random_keys(&db, wallet_user_id, Uuid::new_v4().to_string()).await;
+
+    let entry = new_entry(
+        &hsm,
+        None,
+        wallet_user_id,
+        InstructionType::CheckPin,
+        InstructionOutcome::IncorrectPin,
+        InstructionAuditDetails::default(),
+    )
+    .await;
+    create_instruction_audit_log_entry(&db, entry.clone()).await.unwrap();
+
+    wallet_user_instruction_audit_log::Entity::update_many()
+        .col_expr(
+            wallet_user_instruction_audit_log::Column::Outcome,
+            Expr::value(InstructionOutcome::Success.to_string()),
+        )
+        .filter(wallet_user_instruction_audit_log::Column::Id.eq(entry.id))
+        .exec(db.connection())
+        .await
+        .expect_err("updating an instruction audit log entry should fail");
+
+    wallet_user_instruction_audit_log::Entity::delete_by_id(entry.id)
+        .exec(db.connection())
+        .await
+        .expect_err("deleting an instruction audit log entry should fail");
+
+    let entries = find_instruction_audit_log_entries(&db, wallet_user_id).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].outcome, InstructionOutcome::IncorrectPin);
+    assert_eq!(entries[0].hash, entry.hash);
+}
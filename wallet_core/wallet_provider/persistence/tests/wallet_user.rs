@@ -1,10 +1,23 @@
+use std::time::Duration;
+
 use uuid::Uuid;
 
 use wallet_common::{generator::Generator, utils::random_string};
-use wallet_provider_domain::{repository::Committable, EpochGenerator};
+use wallet_provider_domain::{
+    model::{
+        encrypted::{Encrypted, InitializationVector},
+        wallet_user::{WalletUserQueryResult, WalletUserState},
+    },
+    repository::{Committable, PersistenceError},
+    EpochGenerator,
+};
 use wallet_provider_persistence::{
     transaction,
-    wallet_user::{clear_instruction_challenge, register_unsuccessful_pin_entry},
+    wallet_user::{
+        block_wallet_user, clear_instruction_challenge, find_wallet_user_by_wallet_id,
+        find_wallet_user_details_by_wallet_id, register_unsuccessful_pin_entry, update_encrypted_pin_pubkey,
+        update_wallet_user_pin_pubkey, update_wallet_user_state,
+    },
 };
 
 pub mod common;
@@ -85,13 +98,13 @@ async fn test_insert_instruction_challenge_on_conflict() {
     assert!(challenges.is_empty());
 
     // insert an instruction challenge for the first time, we should only find that one afterwards
-    common::create_instruction_challenge_with_random_data(&db, wallet_id.clone()).await;
+    common::create_instruction_challenge_with_random_data(&db, wallet_user_id).await;
     let challenges = common::find_instruction_challenges_by_wallet_id(&db, wallet_id.clone()).await;
     assert_eq!(challenges.len(), 1);
     assert_eq!(challenges[0].wallet_user_id, wallet_user_id.clone());
 
     let og_id = challenges[0].id;
-    common::create_instruction_challenge_with_random_data(&db, wallet_id.clone()).await;
+    common::create_instruction_challenge_with_random_data(&db, wallet_user_id).await;
 
     // insert another instruction challenge, this should update the bytes and expiration date in the first one
     let challenges = common::find_instruction_challenges_by_wallet_id(&db, wallet_id.clone()).await;
@@ -100,7 +113,7 @@ async fn test_insert_instruction_challenge_on_conflict() {
     // as the challenge should be updated, its ID stays the same
     assert_eq!(challenges[0].id, og_id);
 
-    clear_instruction_challenge(&db, &wallet_id)
+    clear_instruction_challenge(&db, wallet_user_id)
         .await
         .expect("Could not clear instruction challenges");
 
@@ -108,7 +121,7 @@ async fn test_insert_instruction_challenge_on_conflict() {
     let challenges = common::find_instruction_challenges_by_wallet_id(&db, wallet_id.clone()).await;
     assert_eq!(challenges.len(), 0);
 
-    common::create_instruction_challenge_with_random_data(&db, wallet_id.clone()).await;
+    common::create_instruction_challenge_with_random_data(&db, wallet_user_id).await;
 
     // insert an instruction challenge for the second time, we should only find that one afterwards
     let challenges = common::find_instruction_challenges_by_wallet_id(&db, wallet_id.clone()).await;
@@ -130,7 +143,7 @@ async fn test_insert_instruction_challenge_on_conflict() {
     assert!(challenges.is_empty());
 
     // insert an instruction challenge for our second wallet, we should only find one per wallet
-    common::create_instruction_challenge_with_random_data(&db, wallet_id2.clone()).await;
+    common::create_instruction_challenge_with_random_data(&db, wallet_user_id2).await;
     let challenges = common::find_instruction_challenges_by_wallet_id(&db, wallet_id2.clone()).await;
     assert_eq!(challenges.len(), 1);
     assert_eq!(challenges[0].wallet_user_id, wallet_user_id2.clone());
@@ -152,12 +165,233 @@ async fn test_register_unsuccessful_pin_entry() {
     let before = common::find_wallet_user(&db, wallet_user_id).await.unwrap();
     assert!(before.last_unsuccessful_pin.is_none());
 
-    register_unsuccessful_pin_entry(&db, &wallet_id, false, EpochGenerator.generate())
-        .await
-        .expect("Could register unsuccessful pin entry");
+    register_unsuccessful_pin_entry(
+        &db,
+        &wallet_id,
+        u8::try_from(before.pin_entries + 1).unwrap(),
+        false,
+        EpochGenerator.generate(),
+    )
+    .await
+    .expect("Could register unsuccessful pin entry");
 
     let after = common::find_wallet_user(&db, wallet_user_id).await.unwrap();
 
     assert_eq!(before.pin_entries + 1, after.pin_entries);
     assert_eq!(EpochGenerator.generate(), after.last_unsuccessful_pin.unwrap());
 }
+
+#[tokio::test]
+async fn test_find_wallet_user_by_wallet_id_locks_wallet_user() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = random_string(32);
+
+    let hw_pubkey = common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+
+    let transaction = transaction::begin_transaction(&db)
+        .await
+        .expect("Could not begin transaction");
+    let WalletUserQueryResult::Found(wallet_user) = find_wallet_user_by_wallet_id(&transaction, &wallet_id, &hw_pubkey)
+        .await
+        .expect("Could not find wallet user")
+    else {
+        panic!("Could not find wallet user");
+    };
+
+    // A concurrent transaction should not be able to retrieve the wallet user, until the unsuccessful PIN entry is
+    // registered and the first transaction is committed.
+    let concurrent_transaction = transaction::begin_transaction(&db)
+        .await
+        .expect("Could not begin concurrent transaction");
+    let concurrent_find = find_wallet_user_by_wallet_id(&concurrent_transaction, &wallet_id, &hw_pubkey);
+    tokio::pin!(concurrent_find);
+
+    assert!(tokio::time::timeout(Duration::from_millis(500), &mut concurrent_find)
+        .await
+        .is_err());
+
+    register_unsuccessful_pin_entry(
+        &transaction,
+        &wallet_id,
+        wallet_user.unsuccessful_pin_entries + 1,
+        false,
+        EpochGenerator.generate(),
+    )
+    .await
+    .expect("Could register unsuccessful pin entry");
+    transaction.commit().await.expect("Could not commit transaction");
+
+    let WalletUserQueryResult::Found(concurrent_wallet_user) =
+        concurrent_find.await.expect("Could not find wallet user")
+    else {
+        panic!("Could not find wallet user");
+    };
+
+    assert_eq!(
+        concurrent_wallet_user.unsuccessful_pin_entries,
+        wallet_user.unsuccessful_pin_entries + 1
+    );
+}
+
+#[tokio::test]
+async fn test_block_wallet_user() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = random_string(32);
+
+    common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+
+    let before = common::find_wallet_user(&db, wallet_user_id).await.unwrap();
+    assert!(!before.is_blocked);
+
+    block_wallet_user(&db, &wallet_id)
+        .await
+        .expect("Could not block wallet user");
+
+    let after = common::find_wallet_user(&db, wallet_user_id).await.unwrap();
+    assert!(after.is_blocked);
+}
+
+#[tokio::test]
+async fn test_update_wallet_user_pin_pubkey() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = random_string(32);
+
+    let hw_pubkey = common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+    register_unsuccessful_pin_entry(&db, &wallet_id, 1, true, EpochGenerator.generate())
+        .await
+        .expect("Could register unsuccessful pin entry");
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Blocked(_)));
+
+    update_wallet_user_pin_pubkey(
+        &db,
+        &wallet_id,
+        Encrypted::new(vec![1, 2, 3], InitializationVector(vec![4, 5, 6])),
+        1,
+    )
+    .await
+    .expect("Could not update pin pubkey of wallet user");
+
+    let after = common::find_wallet_user(&db, wallet_user_id).await.unwrap();
+    assert_eq!(after.encrypted_pin_pubkey_sec1, vec![1, 2, 3]);
+    assert_eq!(after.pin_pubkey_iv, vec![4, 5, 6]);
+    assert_eq!(after.pin_pubkey_encryption_key_version, 1);
+    assert_eq!(after.pin_entries, 0);
+    assert!(after.last_unsuccessful_pin.is_none());
+    assert!(!after.is_blocked);
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Found(_)));
+}
+
+#[tokio::test]
+async fn test_update_encrypted_pin_pubkey() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = random_string(32);
+
+    common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+    register_unsuccessful_pin_entry(&db, &wallet_id, 1, false, EpochGenerator.generate())
+        .await
+        .expect("Could register unsuccessful pin entry");
+
+    update_encrypted_pin_pubkey(
+        &db,
+        wallet_user_id,
+        Encrypted::new(vec![1, 2, 3], InitializationVector(vec![4, 5, 6])),
+        2,
+    )
+    .await
+    .expect("Could not update encrypted pin pubkey of wallet user");
+
+    // Re-encrypting the PIN public key should not affect the PIN entries of the wallet user.
+    let after = common::find_wallet_user(&db, wallet_user_id).await.unwrap();
+    assert_eq!(after.encrypted_pin_pubkey_sec1, vec![1, 2, 3]);
+    assert_eq!(after.pin_pubkey_iv, vec![4, 5, 6]);
+    assert_eq!(after.pin_pubkey_encryption_key_version, 2);
+    assert_eq!(after.pin_entries, 1);
+}
+
+#[tokio::test]
+async fn test_update_wallet_user_state() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = random_string(32);
+
+    let hw_pubkey = common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+
+    let before = common::find_wallet_user(&db, wallet_user_id).await.unwrap();
+    assert_eq!(before.state, "active");
+
+    update_wallet_user_state(&db, &wallet_id, WalletUserState::Suspended)
+        .await
+        .expect("Could not suspend wallet user");
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Suspended));
+
+    update_wallet_user_state(&db, &wallet_id, WalletUserState::Active)
+        .await
+        .expect("Could not reactivate wallet user");
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Found(_)));
+
+    update_wallet_user_state(&db, &wallet_id, WalletUserState::Revoked)
+        .await
+        .expect("Could not revoke wallet user");
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Revoked));
+
+    // Revocation is final, so the wallet user cannot be reactivated.
+    let error = update_wallet_user_state(&db, &wallet_id, WalletUserState::Active)
+        .await
+        .expect_err("Reactivating a revoked wallet user should fail");
+    assert!(matches!(error, PersistenceError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn test_find_wallet_user_details_by_wallet_id() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = random_string(32);
+
+    let details = find_wallet_user_details_by_wallet_id(&db, &wallet_id).await.unwrap();
+    assert!(details.is_none());
+
+    common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+    block_wallet_user(&db, &wallet_id)
+        .await
+        .expect("Could not block wallet user");
+
+    let details = find_wallet_user_details_by_wallet_id(&db, &wallet_id)
+        .await
+        .unwrap()
+        .expect("Wallet user details not found");
+
+    assert_eq!(details.id, wallet_user_id);
+    assert_eq!(details.state, WalletUserState::Active);
+    assert!(details.is_blocked);
+    assert_eq!(details.key_count, 0);
+}
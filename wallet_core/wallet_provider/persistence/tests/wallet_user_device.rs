@@ -0,0 +1,137 @@
+use chrono::Local;
+use p256::ecdsa::SigningKey;
+use rand_core::OsRng;
+use uuid::Uuid;
+
+use wallet_common::utils::random_bytes;
+use wallet_provider_domain::{
+    model::wallet_user::{
+        InstructionChallenge, WalletUserDeviceCreate, WalletUserPlatformAttestation, WalletUserQueryResult,
+    },
+    repository::PersistenceError,
+};
+use wallet_provider_persistence::{
+    wallet_user::{find_wallet_user_by_wallet_id, update_instruction_challenge_and_sequence_number},
+    wallet_user_device::{
+        create_wallet_user_device, delete_wallet_user_device, find_wallet_user_devices, update_device_link_challenge,
+    },
+};
+
+pub mod common;
+
+#[tokio::test]
+async fn test_link_and_remove_device() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = Uuid::new_v4().to_string();
+    let hw_pubkey = common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+
+    let device_id = Uuid::new_v4();
+    let device_hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+    create_wallet_user_device(
+        &db,
+        WalletUserDeviceCreate {
+            id: device_id,
+            wallet_user_id,
+            hw_pubkey: device_hw_pubkey,
+            linked_date_time: Local::now(),
+            platform_attestation: Some(WalletUserPlatformAttestation::AndroidStrongBox),
+        },
+    )
+    .await
+    .expect("Could not create wallet user device");
+
+    let devices = find_wallet_user_devices(&db, wallet_user_id).await.unwrap();
+    assert_eq!(
+        devices.iter().map(|device| device.id).collect::<Vec<_>>(),
+        vec![wallet_user_id, device_id]
+    );
+    assert_eq!(devices[1].hw_pubkey.0, device_hw_pubkey);
+    assert_eq!(
+        devices[1].platform_attestation,
+        Some(WalletUserPlatformAttestation::AndroidStrongBox)
+    );
+
+    // both devices see the same wallet user, but each with its own sequence number
+    update_instruction_challenge_and_sequence_number(
+        &db,
+        device_id,
+        InstructionChallenge {
+            bytes: random_bytes(32),
+            expiration_date_time: Local::now(),
+        },
+        5,
+    )
+    .await
+    .unwrap();
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Found(user)
+        if user.device_id == wallet_user_id && user.instruction_sequence_number == 0 && user.instruction_challenge.is_none()));
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &device_hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Found(user)
+        if user.id == wallet_user_id && user.device_id == device_id && user.instruction_sequence_number == 5
+            && user.instruction_challenge.is_some()));
+
+    let unknown_hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &unknown_hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::DeviceNotLinked));
+
+    // removing the device also removes its pending instruction challenge
+    delete_wallet_user_device(&db, wallet_user_id, device_id)
+        .await
+        .expect("Could not delete wallet user device");
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &device_hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::DeviceNotLinked));
+    assert_eq!(find_wallet_user_devices(&db, wallet_user_id).await.unwrap().len(), 1);
+
+    let error = delete_wallet_user_device(&db, wallet_user_id, device_id)
+        .await
+        .expect_err("Deleting a removed device should fail");
+    assert!(matches!(error, PersistenceError::NotFound(_)));
+}
+
+#[tokio::test]
+async fn test_update_device_link_challenge() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let wallet_user_id = Uuid::new_v4();
+    let wallet_id = Uuid::new_v4().to_string();
+    let hw_pubkey = common::create_wallet_user_with_random_keys(&db, wallet_user_id, wallet_id.clone()).await;
+
+    let challenge = random_bytes(32);
+    update_device_link_challenge(
+        &db,
+        wallet_user_id,
+        Some(InstructionChallenge {
+            bytes: challenge.clone(),
+            expiration_date_time: Local::now(),
+        }),
+    )
+    .await
+    .unwrap();
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Found(user)
+        if user.device_link_challenge.as_ref().map(|challenge| &challenge.bytes) == Some(&challenge)));
+
+    update_device_link_challenge(&db, wallet_user_id, None).await.unwrap();
+
+    let query_result = find_wallet_user_by_wallet_id(&db, &wallet_id, &hw_pubkey)
+        .await
+        .unwrap();
+    assert!(matches!(query_result, WalletUserQueryResult::Found(user) if user.device_link_challenge.is_none()));
+}
@@ -6,7 +6,7 @@ use wallet_provider_domain::model::{
     wallet_user::{WalletUserKey, WalletUserKeys},
     wrapped_key::WrappedKey,
 };
-use wallet_provider_persistence::wallet_user_key::{create_keys, find_keys_by_identifiers};
+use wallet_provider_persistence::wallet_user_key::{create_keys, find_keys_by_identifiers, transfer_keys, update_key};
 
 pub mod common;
 
@@ -17,12 +17,12 @@ async fn test_create_keys() {
     let key1 = WalletUserKey {
         wallet_user_key_id: Uuid::new_v4(),
         key_identifier: "key1".to_string(),
-        key: WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec()),
+        key: WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec(), 0),
     };
     let key2 = WalletUserKey {
         wallet_user_key_id: Uuid::new_v4(),
         key_identifier: "key2".to_string(),
-        key: WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec()),
+        key: WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec(), 0),
     };
 
     let wallet_user_id = Uuid::new_v4();
@@ -55,3 +55,87 @@ async fn test_create_keys() {
     let key2: Vec<u8> = key2.key.into();
     assert_eq!(vec![key1, key2], keys);
 }
+
+#[tokio::test]
+async fn test_transfer_keys() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let key = WalletUserKey {
+        wallet_user_key_id: Uuid::new_v4(),
+        key_identifier: "key1".to_string(),
+        key: WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec(), 0),
+    };
+
+    let source_wallet_user_id = Uuid::new_v4();
+    let destination_wallet_user_id = Uuid::new_v4();
+
+    common::create_wallet_user_with_random_keys(&db, source_wallet_user_id, Uuid::new_v4().to_string()).await;
+    common::create_wallet_user_with_random_keys(&db, destination_wallet_user_id, Uuid::new_v4().to_string()).await;
+
+    create_keys(
+        &db,
+        WalletUserKeys {
+            wallet_user_id: source_wallet_user_id,
+            keys: vec![key.clone()],
+        },
+    )
+    .await
+    .unwrap();
+
+    transfer_keys(&db, source_wallet_user_id, destination_wallet_user_id)
+        .await
+        .unwrap();
+
+    let identifiers = ["key1".to_string()];
+    assert!(find_keys_by_identifiers(&db, source_wallet_user_id, &identifiers)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let persisted_key: Vec<u8> = find_keys_by_identifiers(&db, destination_wallet_user_id, &identifiers)
+        .await
+        .unwrap()
+        .remove("key1")
+        .expect("key should be transferred to the destination wallet user")
+        .into();
+    let key: Vec<u8> = key.key.into();
+    assert_eq!(persisted_key, key);
+}
+
+#[tokio::test]
+async fn test_update_key() {
+    let db = common::db_from_env().await.expect("Could not connect to database");
+
+    let key = WalletUserKey {
+        wallet_user_key_id: Uuid::new_v4(),
+        key_identifier: "key1".to_string(),
+        key: WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec(), 0),
+    };
+
+    let wallet_user_id = Uuid::new_v4();
+    common::create_wallet_user_with_random_keys(&db, wallet_user_id, Uuid::new_v4().to_string()).await;
+
+    create_keys(
+        &db,
+        WalletUserKeys {
+            wallet_user_id,
+            keys: vec![key.clone()],
+        },
+    )
+    .await
+    .unwrap();
+
+    let rewrapped_key = WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec(), 1);
+    update_key(&db, key.wallet_user_key_id, rewrapped_key.clone())
+        .await
+        .unwrap();
+
+    let persisted_key = find_keys_by_identifiers(&db, wallet_user_id, &["key1".to_string()])
+        .await
+        .unwrap()
+        .remove("key1")
+        .expect("key should still exist");
+
+    assert_eq!(persisted_key.wrapping_key_version(), 1);
+    assert_eq!(Vec::<u8>::from(persisted_key), Vec::<u8>::from(rewrapped_key));
+}
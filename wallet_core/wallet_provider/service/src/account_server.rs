@@ -1,20 +1,25 @@
 use std::{sync::Arc, time::Duration};
 
 use chrono::{DateTime, Local};
-use p256::{ecdsa::VerifyingKey, pkcs8::EncodePublicKey};
+use p256::{
+    ecdsa::{signature::Verifier, VerifyingKey},
+    pkcs8::EncodePublicKey,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::{base64::Base64, serde_as};
-use tracing::debug;
+use tracing::{debug, error};
 use uuid::Uuid;
 
+use hsm::service::HsmError;
 use wallet_common::{
     account::{
         errors::Error as AccountError,
         messages::{
-            auth::{Registration, WalletCertificate, WalletCertificateClaims},
+            auth::{DeviceLink, Registration, WalletCertificate, WalletCertificateClaims},
             errors::{IncorrectPinData, PinTimeoutData},
             instructions::{
                 Instruction, InstructionChallengeRequestMessage, InstructionResult, InstructionResultClaims,
+                InstructionType, RecoverPin,
             },
         },
         signed::{ChallengeResponsePayload, SequenceNumberComparison, SignedDouble},
@@ -27,16 +32,25 @@ use wallet_provider_domain::{
     model::{
         encrypter::{Decrypter, Encrypter},
         hsm::{Hsm, WalletUserHsm},
+        instruction_audit_log::{InstructionAuditDetails, InstructionAuditLogEntry, InstructionOutcome},
         pin_policy::{PinPolicyEvaluation, PinPolicyEvaluator},
-        wallet_user::{InstructionChallenge, WalletUser, WalletUserCreate, WalletUserQueryResult},
+        versioned_key::VersionedKey,
+        wallet_user::{
+            InstructionChallenge, WalletUser, WalletUserCreate, WalletUserDeviceCreate, WalletUserPlatformAttestation,
+            WalletUserQueryResult, WalletUserState,
+        },
+    },
+    repository::{
+        Committable, InstructionAuditLogRepository, PersistenceError, TransactionStarter, WalletUserDeviceRepository,
+        WalletUserRepository,
     },
-    repository::{Committable, PersistenceError, TransactionStarter, WalletUserRepository},
 };
 
 use crate::{
-    hsm::HsmError,
     instructions::HandleInstruction,
     keys::{CertificateSigningKey, InstructionResultSigningKey},
+    pin_recovery::{PinRecoveryError, PinRecoveryVerifier},
+    platform_attestation::{PlatformAttestationError, PlatformAttestationVerifier},
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -78,6 +92,12 @@ pub enum WalletCertificateError {
     UserNotRegistered,
     #[error("registered wallet user blocked")]
     UserBlocked,
+    #[error("registered wallet user suspended")]
+    UserSuspended,
+    #[error("registered wallet user revoked")]
+    UserRevoked,
+    #[error("device is not linked to registered wallet user")]
+    DeviceNotLinked,
     #[error("could not retrieve registered wallet user: {0}")]
     Persistence(#[from] PersistenceError),
     #[error("hsm error: {0}")]
@@ -104,6 +124,12 @@ pub enum RegistrationError {
     PinPubKeyEncoding(#[source] der::Error),
     #[error("wallet certificate validation error: {0}")]
     WalletCertificate(#[from] WalletCertificateError),
+    #[error("wallet certificate of restored account validation error: {0}")]
+    RestoreWalletCertificate(#[source] WalletCertificateError),
+    #[error("restore pin validation error ({0:?})")]
+    RestorePin(PinPolicyEvaluation),
+    #[error("platform attestation validation error: {0}")]
+    PlatformAttestation(#[from] PlatformAttestationError),
     #[error("hsm error: {0}")]
     HsmError(#[from] HsmError),
 }
@@ -120,6 +146,12 @@ pub enum InstructionError {
     PinTimeout(PinTimeoutData),
     #[error("account is blocked")]
     AccountBlocked,
+    #[error("PIN recovery error: {0}")]
+    PinRecovery(#[from] PinRecoveryError),
+    #[error("device error: {0}")]
+    Device(#[from] DeviceError),
+    #[error("no key found with identifier: {0}")]
+    KeyNotFound(String),
     #[error("instruction result signing error: {0}")]
     Signing(#[source] JwtError),
     #[error("persistence error: {0}")]
@@ -142,6 +174,22 @@ pub enum InstructionValidationError {
     HsmError(#[from] HsmError),
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum DeviceError {
+    #[error("device link challenge mismatch")]
+    LinkChallengeMismatch,
+    #[error("device link challenge timeout")]
+    LinkChallengeTimeout,
+    #[error("device link message validation error: {0}")]
+    LinkValidation(#[source] AccountError),
+    #[error("device link platform attestation validation error: {0}")]
+    LinkPlatformAttestation(#[source] PlatformAttestationError),
+    #[error("a device cannot remove itself")]
+    RemoveCurrentDevice,
+    #[error("no device with id {0} is linked to the wallet user")]
+    UnknownDevice(String),
+}
+
 impl From<PinPolicyEvaluation> for InstructionError {
     fn from(value: PinPolicyEvaluation) -> Self {
         match value {
@@ -152,12 +200,18 @@ impl From<PinPolicyEvaluation> for InstructionError {
                 attempts_left_in_round,
                 is_final_round,
             }),
-            PinPolicyEvaluation::Timeout { timeout } | PinPolicyEvaluation::InTimeout { timeout } => {
-                InstructionError::PinTimeout(PinTimeoutData {
-                    time_left_in_ms: u64::try_from(timeout.num_milliseconds())
-                        .expect("number of milliseconds in timeout cannot be negative"),
-                })
+            PinPolicyEvaluation::Timeout {
+                timeout,
+                next_attempt_at,
             }
+            | PinPolicyEvaluation::InTimeout {
+                timeout,
+                next_attempt_at,
+            } => InstructionError::PinTimeout(PinTimeoutData {
+                time_left_in_ms: u64::try_from(timeout.num_milliseconds())
+                    .expect("number of milliseconds in timeout cannot be negative"),
+                next_attempt_at: next_attempt_at.to_utc(),
+            }),
             PinPolicyEvaluation::BlockedPermanently => InstructionError::AccountBlocked,
         }
     }
@@ -181,14 +235,24 @@ impl JwtSubject for RegistrationChallengeClaims {
     const SUB: &'static str = "registration_challenge";
 }
 
+/// The keys in the HSM that the account server uses, apart from the keys it signs certificates and results with.
+pub struct AccountServerKeys {
+    pub pin_pubkey_encryption_key: VersionedKey,
+    pub pin_public_disclosure_protection_key_identifier: String,
+    pub instruction_audit_log_key_identifier: String,
+}
+
 pub struct AccountServer {
     instruction_challenge_timeout: Duration,
 
     pub name: String,
 
     certificate_signing_pubkey: EcdsaDecodingKey,
-    encryption_key_identifier: String,
+    pin_pubkey_encryption_key: VersionedKey,
     pin_public_disclosure_protection_key_identifier: String,
+    instruction_audit_log_key_identifier: String,
+    platform_attestation_verifier: PlatformAttestationVerifier,
+    pin_recovery_verifier: PinRecoveryVerifier,
 }
 
 impl AccountServer {
@@ -196,18 +260,43 @@ impl AccountServer {
         instruction_challenge_timeout: Duration,
         name: String,
         certificate_signing_pubkey: EcdsaDecodingKey,
-        encryption_key_identifier: String,
-        pin_public_disclosure_protection_key_identifier: String,
+        keys: AccountServerKeys,
+        platform_attestation_verifier: PlatformAttestationVerifier,
+        pin_recovery_verifier: PinRecoveryVerifier,
     ) -> Result<Self, AccountServerInitError> {
+        let AccountServerKeys {
+            pin_pubkey_encryption_key,
+            pin_public_disclosure_protection_key_identifier,
+            instruction_audit_log_key_identifier,
+        } = keys;
+
         Ok(AccountServer {
             instruction_challenge_timeout,
             name,
             certificate_signing_pubkey,
-            encryption_key_identifier,
+            pin_pubkey_encryption_key,
             pin_public_disclosure_protection_key_identifier,
+            instruction_audit_log_key_identifier,
+            platform_attestation_verifier,
+            pin_recovery_verifier,
         })
     }
 
+    /// The identifier of the HSM key that the hash chain of the instruction audit log is computed with.
+    pub fn instruction_audit_log_key_identifier(&self) -> &str {
+        &self.instruction_audit_log_key_identifier
+    }
+
+    /// The identifier of the version of the PIN public key encryption key that the PIN public key of `wallet_user` is
+    /// encrypted with.
+    fn pin_pubkey_encryption_key_identifier(&self, wallet_user: &WalletUser) -> Result<String, HsmError> {
+        let version = wallet_user.pin_pubkey_encryption_key_version;
+
+        self.pin_pubkey_encryption_key
+            .identifier(version)
+            .ok_or(HsmError::UnavailableKeyVersion(version))
+    }
+
     // Only used for registration. When a registered user sends an instruction, we should store
     // the challenge per user, instead globally.
     pub async fn registration_challenge(
@@ -248,8 +337,10 @@ impl AccountServer {
 
         debug!("Verifying certificate and retrieving wallet user");
 
-        let user = self
-            .verify_wallet_certificate(&challenge_request.certificate, repositories, hsm)
+        // A blocked wallet user can still request a challenge, as it needs one to recover its PIN. This does not
+        // allow the wallet user to perform any other instruction, as those verify that the wallet user is not blocked.
+        let (user, _) = self
+            .verify_wallet_certificate_allowing_blocked(&challenge_request.certificate, repositories, hsm)
             .await?;
 
         debug!("Parsing and verifying challenge request for user {}", user.id);
@@ -278,7 +369,7 @@ impl AccountServer {
         repositories
             .update_instruction_challenge_and_sequence_number(
                 &tx,
-                &user.wallet_id,
+                user.device_id,
                 challenge.clone(),
                 parsed.sequence_number,
             )
@@ -301,42 +392,55 @@ impl AccountServer {
     ) -> Result<InstructionResult<IR>, InstructionError>
     where
         T: Committable,
-        R: TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
         I: HandleInstruction<Result = IR> + Serialize + DeserializeOwned,
         IR: Serialize + DeserializeOwned,
         G: Generator<Uuid> + Generator<DateTime<Local>>,
         H: WalletUserHsm<Error = HsmError> + Hsm<Error = HsmError> + Decrypter<VerifyingKey, Error = HsmError>,
     {
+        debug!("Starting database transaction");
+
+        let tx = repositories.begin_transaction().await?;
+
         debug!("Verifying certificate and retrieving wallet user");
 
+        // The wallet user is retrieved within the transaction that registers the outcome of the PIN entry, which locks
+        // the wallet user until this transaction ends. This way, concurrent instructions are evaluated one by one.
         let wallet_user = self
-            .verify_wallet_certificate(&instruction.certificate, repositories, wallet_user_hsm)
-            .await?;
-
-        debug!(
-            "Starting database transaction and instruction handling process for user {}",
-            &wallet_user.id
-        );
+            .verify_wallet_certificate_in_transaction(&instruction.certificate, repositories, &tx, wallet_user_hsm)
+            .await
+            .and_then(Self::reject_blocked)?;
 
-        let tx = repositories.begin_transaction().await?;
+        debug!("Starting instruction handling process for user {}", &wallet_user.id);
 
         debug!("Clearing instruction challenge");
 
         repositories
-            .clear_instruction_challenge(&tx, &wallet_user.wallet_id)
+            .clear_instruction_challenge(&tx, wallet_user.device_id)
             .await?;
 
         debug!("Evaluating pin policy state");
 
-        let pin_eval = pin_policy.evaluate(
-            wallet_user.unsuccessful_pin_entries + 1,
-            wallet_user.last_unsuccessful_pin_entry,
-            generators.generate(),
-        );
+        let (pin_entries, pin_eval) = Self::evaluate_pin_policy(&wallet_user, pin_policy, generators.generate());
 
         // An evaluation result of blocked permanently can only occur once. This fact is stored in the database
         // for the wallet_user. Subsequent calls will verify if the user is blocked against the database.
-        if matches!(pin_eval, PinPolicyEvaluation::InTimeout { timeout: _ }) {
+        if matches!(pin_eval, PinPolicyEvaluation::InTimeout { .. }) {
+            self.audit_instruction(
+                repositories,
+                &tx,
+                generators,
+                wallet_user_hsm,
+                &wallet_user,
+                I::INSTRUCTION_TYPE,
+                InstructionOutcome::Blocked,
+                InstructionAuditDetails::default(),
+            )
+            .await?;
+
             tx.commit().await?;
             return Err(pin_eval.into());
         }
@@ -357,53 +461,197 @@ impl AccountServer {
                 debug!("Updating instruction sequence number to {}", payload.sequence_number);
 
                 repositories
-                    .update_instruction_sequence_number(&tx, &wallet_user.wallet_id, payload.sequence_number)
+                    .update_instruction_sequence_number(&tx, wallet_user.device_id, payload.sequence_number)
                     .await?;
 
                 tx.commit().await?;
 
+                let details = payload.payload.audit_details();
                 let instruction_result = payload
                     .payload
                     .handle(&wallet_user, generators, repositories, wallet_user_hsm)
-                    .await?;
-                self.sign_instruction_result(instruction_result_signing_key, instruction_result)
+                    .await;
+
+                // The outcome of the instruction is only known after handling it, which happens after the transaction
+                // above has ended, as handling an instruction may use transactions of its own. By then its effects have
+                // been committed, so a failure to record it is logged rather than returned, which would discard the
+                // result.
+                let outcome = match instruction_result {
+                    Ok(_) => InstructionOutcome::Success,
+                    Err(_) => InstructionOutcome::Failed,
+                };
+
+                if let Err(error) = self
+                    .audit_handled_instruction(
+                        repositories,
+                        generators,
+                        wallet_user_hsm,
+                        &wallet_user,
+                        I::INSTRUCTION_TYPE,
+                        outcome,
+                        details,
+                    )
+                    .await
+                {
+                    error!(
+                        "Could not record handled {} instruction with outcome {} for wallet user {} in audit log: {}",
+                        I::INSTRUCTION_TYPE,
+                        outcome,
+                        wallet_user.id,
+                        error
+                    );
+                }
+
+                self.sign_instruction_result(instruction_result_signing_key, instruction_result?)
                     .await
             }
             Err(validation_error) => {
-                let error = if matches!(validation_error, InstructionValidationError::VerificationFailed(_)) {
+                let (outcome, error) = if matches!(validation_error, InstructionValidationError::VerificationFailed(_))
+                {
                     debug!("Instruction validation failed, registering unsuccessful pin entry");
 
                     repositories
                         .register_unsuccessful_pin_entry(
                             &tx,
                             &wallet_user.wallet_id,
+                            pin_entries,
                             matches!(pin_eval, PinPolicyEvaluation::BlockedPermanently),
                             generators.generate(),
                         )
                         .await?;
-                    Err(pin_eval.into())
+
+                    let outcome = match pin_eval {
+                        PinPolicyEvaluation::Failed { .. } => InstructionOutcome::IncorrectPin,
+                        _ => InstructionOutcome::Blocked,
+                    };
+                    (outcome, pin_eval.into())
                 } else {
-                    Err(validation_error)?
+                    (InstructionOutcome::ValidationError, validation_error.into())
                 };
 
+                self.audit_instruction(
+                    repositories,
+                    &tx,
+                    generators,
+                    wallet_user_hsm,
+                    &wallet_user,
+                    I::INSTRUCTION_TYPE,
+                    outcome,
+                    InstructionAuditDetails::default(),
+                )
+                .await?;
+
                 tx.commit().await?;
-                error
+                Err(error)
             }
         }
     }
 
-    pub async fn register<T, R, H>(
+    /// Append an entry for an instruction to the audit log of the wallet user, chained to the last entry. Concurrent
+    /// instructions cannot fork the hash chain, as the persistence layer only accepts a single entry per chain index.
+    #[allow(clippy::too_many_arguments)]
+    async fn audit_instruction<T, R, G, H>(
+        &self,
+        repositories: &R,
+        tx: &T,
+        generators: &G,
+        hsm: &H,
+        wallet_user: &WalletUser,
+        instruction_type: InstructionType,
+        outcome: InstructionOutcome,
+        details: InstructionAuditDetails,
+    ) -> Result<(), InstructionError>
+    where
+        T: Committable,
+        R: InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+        H: Hsm<Error = HsmError>,
+    {
+        debug!(
+            "Recording {} instruction in audit log with outcome {}",
+            instruction_type, outcome
+        );
+
+        let previous = repositories
+            .find_last_instruction_audit_log_entry(tx, wallet_user.id)
+            .await?;
+        let entry = InstructionAuditLogEntry::new_chained(
+            previous.as_ref(),
+            Generator::<Uuid>::generate(generators),
+            wallet_user.id,
+            Generator::<DateTime<Local>>::generate(generators),
+            instruction_type,
+            outcome,
+            details,
+            hsm,
+            &self.instruction_audit_log_key_identifier,
+        )
+        .await?;
+
+        repositories.create_instruction_audit_log_entry(tx, entry).await?;
+
+        Ok(())
+    }
+
+    /// Record an instruction in the audit log after it has been handled, in a transaction of its own.
+    #[allow(clippy::too_many_arguments)]
+    async fn audit_handled_instruction<T, R, G, H>(
+        &self,
+        repositories: &R,
+        generators: &G,
+        hsm: &H,
+        wallet_user: &WalletUser,
+        instruction_type: InstructionType,
+        outcome: InstructionOutcome,
+        details: InstructionAuditDetails,
+    ) -> Result<(), InstructionError>
+    where
+        T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+        H: Hsm<Error = HsmError>,
+    {
+        let tx = repositories.begin_transaction().await?;
+
+        // Lock the wallet user again, so that the entries of concurrent instructions are appended one by one.
+        repositories
+            .find_wallet_user_by_wallet_id(&tx, &wallet_user.wallet_id, &wallet_user.hw_pubkey.0)
+            .await?;
+        self.audit_instruction(
+            repositories,
+            &tx,
+            generators,
+            hsm,
+            wallet_user,
+            instruction_type,
+            outcome,
+            details,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn register<T, R, G, H>(
         &self,
         certificate_signing_key: &impl CertificateSigningKey,
-        uuid_generator: &impl Generator<Uuid>,
+        generators: &G,
         repositories: &R,
+        pin_policy: &impl PinPolicyEvaluator,
         hsm: &H,
         registration_message: SignedDouble<Registration>,
     ) -> Result<WalletCertificate, RegistrationError>
     where
         T: Committable,
         R: TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>,
-        H: Encrypter<VerifyingKey, Error = HsmError> + Hsm<Error = HsmError>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+        H: Encrypter<VerifyingKey, Error = HsmError>
+            + Decrypter<VerifyingKey, Error = HsmError>
+            + Hsm<Error = HsmError>,
     {
         debug!("Parsing message to lookup public keys");
 
@@ -429,27 +677,57 @@ impl AccountServer {
             .parse_and_verify(challenge, SequenceNumberComparison::EqualTo(0), &hw_pubkey, &pin_pubkey)
             .map_err(RegistrationError::MessageValidation)?;
 
-        debug!("Starting database transaction");
+        debug!("Verifying platform attestation of the hw pubkey");
+
+        let platform_attestation = self.platform_attestation_verifier.verify(
+            unverified.payload.platform_attestation.as_ref(),
+            challenge,
+            &hw_pubkey,
+            generators,
+        )?;
 
-        let encrypted_pin_pubkey = Encrypter::encrypt(hsm, &self.encryption_key_identifier, pin_pubkey).await?;
+        let encrypted_pin_pubkey =
+            Encrypter::encrypt(hsm, &self.pin_pubkey_encryption_key.current_identifier(), pin_pubkey).await?;
+
+        debug!("Starting database transaction");
 
         let tx = repositories.begin_transaction().await?;
 
+        // When restoring a backup, the wallet proves that it owns the account the backup was made with.
+        let (restored_wallet_user, tx) = self
+            .verify_registration_restore(&unverified, generators, repositories, tx, pin_policy, hsm)
+            .await?;
+
         debug!("Creating new wallet user");
 
-        let uuid = uuid_generator.generate();
+        let uuid = Generator::<Uuid>::generate(generators);
         repositories
             .create_wallet_user(
                 &tx,
                 WalletUserCreate {
                     id: uuid,
                     wallet_id: wallet_id.clone(),
+                    device_id: Generator::<Uuid>::generate(generators),
                     hw_pubkey,
                     encrypted_pin_pubkey,
+                    pin_pubkey_encryption_key_version: self.pin_pubkey_encryption_key.current_version(),
+                    platform_attestation,
                 },
             )
             .await?;
 
+        if let Some(restored_wallet_user) = restored_wallet_user {
+            debug!(
+                "Transferring keys from restored wallet user {} to {} and revoking the restored wallet user",
+                restored_wallet_user.id, uuid
+            );
+
+            repositories.transfer_keys(&tx, restored_wallet_user.id, uuid).await?;
+            repositories
+                .update_wallet_user_state(&tx, &restored_wallet_user.wallet_id, WalletUserState::Revoked)
+                .await?;
+        }
+
         debug!("Generating new wallet certificate for user {}", uuid);
 
         let cert_result = self
@@ -461,140 +739,681 @@ impl AccountServer {
         Ok(cert_result)
     }
 
-    async fn new_wallet_certificate<H>(
+    /// Let a wallet user that is blocked after too many unsuccessful PIN entries register a new PIN key, after the
+    /// user re-identified with DigiD. The instruction is signed with the new PIN key and contains both the PID held
+    /// by the wallet and the PID that was issued during re-identification. The keys of the wallet user are preserved.
+    pub async fn recover_pin<T, R, G, H>(
         &self,
+        instruction: Instruction<RecoverPin>,
         certificate_signing_key: &impl CertificateSigningKey,
-        wallet_id: String,
-        wallet_hw_pubkey: VerifyingKey,
-        wallet_pin_pubkey: VerifyingKey,
+        instruction_result_signing_key: &impl InstructionResultSigningKey,
+        generators: &G,
+        repositories: &R,
         hsm: &H,
-    ) -> Result<WalletCertificate, RegistrationError>
+    ) -> Result<InstructionResult<WalletCertificate>, InstructionError>
     where
-        H: Hsm<Error = HsmError>,
+        T: Committable,
+        R: TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>,
+        G: Generator<DateTime<Local>>,
+        H: WalletUserHsm<Error = HsmError>
+            + Hsm<Error = HsmError>
+            + Encrypter<VerifyingKey, Error = HsmError>
+            + Decrypter<VerifyingKey, Error = HsmError>,
     {
-        let pin_pubkey_hash = sign_pin_pubkey(
-            wallet_pin_pubkey,
-            &self.pin_public_disclosure_protection_key_identifier,
+        debug!("Verifying certificate and retrieving wallet user");
+
+        let (wallet_user, is_blocked) = self
+            .verify_wallet_certificate_allowing_blocked(&instruction.certificate, repositories, hsm)
+            .await?;
+
+        // Only a wallet user that got blocked by the PIN policy has registered the moment it got blocked, after which
+        // the user should have re-identified.
+        let blocked_at = match (is_blocked, wallet_user.last_unsuccessful_pin_entry) {
+            (true, Some(blocked_at)) => blocked_at,
+            _ => return Err(PinRecoveryError::NotBlocked.into()),
+        };
+
+        debug!("Clearing instruction challenge of user {}", &wallet_user.id);
+
+        // The challenge is cleared regardless of the outcome of the PIN recovery, so that it cannot be reused.
+        let tx = repositories.begin_transaction().await?;
+        repositories
+            .clear_instruction_challenge(&tx, wallet_user.device_id)
+            .await?;
+        tx.commit().await?;
+
+        debug!("Verifying PIN recovery instruction with the new pin key");
+
+        let payload = self.verify_pin_recovery_instruction(instruction, &wallet_user, generators)?;
+        let recover_pin = payload.payload;
+
+        debug!("Verifying the PIDs and the keys of both PIDs");
+
+        let pid_public_key = self
+            .pin_recovery_verifier
+            .verify(&recover_pin, blocked_at, generators)?;
+
+        debug!("Starting database transaction");
+
+        let tx = repositories.begin_transaction().await?;
+
+        self.verify_pid_key(
+            &wallet_user,
+            &recover_pin.pid_key_identifier,
+            &pid_public_key,
+            repositories,
+            &tx,
             hsm,
         )
         .await?;
 
-        let cert = WalletCertificateClaims {
-            wallet_id,
-            hw_pubkey: wallet_hw_pubkey.into(),
-            pin_pubkey_hash,
-            version: WALLET_CERTIFICATE_VERSION,
+        debug!("PIN recovery successful, storing new pin public key and unblocking user");
 
-            iss: self.name.clone(),
-            iat: jsonwebtoken::get_current_timestamp(),
-        };
+        let pin_pubkey = recover_pin.pin_pubkey.0;
+        let encrypted_pin_pubkey =
+            Encrypter::encrypt(hsm, &self.pin_pubkey_encryption_key.current_identifier(), pin_pubkey).await?;
 
-        Jwt::sign_with_sub(&cert, certificate_signing_key)
+        repositories
+            .update_wallet_user_pin_pubkey(
+                &tx,
+                &wallet_user.wallet_id,
+                encrypted_pin_pubkey,
+                self.pin_pubkey_encryption_key.current_version(),
+            )
+            .await?;
+        repositories
+            .update_instruction_sequence_number(&tx, wallet_user.device_id, payload.sequence_number)
+            .await?;
+
+        debug!("Generating new wallet certificate for user {}", wallet_user.id);
+
+        let cert = self
+            .new_wallet_certificate_claims(wallet_user.wallet_id, wallet_user.hw_pubkey.0, pin_pubkey, hsm)
+            .await?;
+        let wallet_certificate = Jwt::sign_with_sub(&cert, certificate_signing_key)
             .await
-            .map_err(RegistrationError::JwtSigning)
-    }
+            .map_err(InstructionError::Signing)?;
 
-    fn verify_registration_challenge(
-        &self,
-        certificate_signing_pubkey: &EcdsaDecodingKey,
-        challenge: &[u8],
-    ) -> Result<RegistrationChallengeClaims, RegistrationError> {
-        Jwt::parse_and_verify_with_sub(
-            &String::from_utf8(challenge.to_owned())
-                .map_err(RegistrationError::ChallengeDecoding)?
-                .into(),
-            certificate_signing_pubkey,
-        )
-        .map_err(RegistrationError::ChallengeValidation)
+        tx.commit().await?;
+
+        self.sign_instruction_result(instruction_result_signing_key, wallet_certificate)
+            .await
     }
 
-    async fn verify_wallet_certificate<T, R, H>(
+    /// Link a new device to the wallet user of an already linked device. The new device scanned a QR code containing
+    /// the wallet certificate and a device link challenge of the linked device, and signed that challenge with both
+    /// its own hardware key and the PIN key of the wallet user. As the new device gets access to the keys of the wallet
+    /// user, which are shared by all of its devices, the platform attestation of its hardware key is always required.
+    /// The new device gets its own wallet certificate.
+    pub async fn link_device<T, R, G, H>(
         &self,
-        certificate: &WalletCertificate,
-        wallet_user_repository: &R,
+        certificate_signing_key: &impl CertificateSigningKey,
+        generators: &G,
+        repositories: &R,
+        pin_policy: &impl PinPolicyEvaluator,
         hsm: &H,
-    ) -> Result<WalletUser, WalletCertificateError>
+        link_message: SignedDouble<DeviceLink>,
+    ) -> Result<WalletCertificate, InstructionError>
     where
         T: Committable,
-        R: TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
         H: Decrypter<VerifyingKey, Error = HsmError> + Hsm<Error = HsmError>,
     {
-        debug!("Parsing and verifying the provided certificate");
+        debug!("Parsing message to lookup the certificate of the linked device and the new hw pubkey");
 
-        let cert_data = certificate.parse_and_verify_with_sub(&self.certificate_signing_pubkey)?;
+        let unverified = link_message
+            .dangerous_parse_unverified()
+            .map_err(DeviceError::LinkValidation)?;
 
         debug!("Starting database transaction");
 
-        let tx = wallet_user_repository.begin_transaction().await?;
+        let tx = repositories.begin_transaction().await?;
 
-        debug!("Fetching the user associated to the provided certificate");
+        debug!("Verifying certificate and retrieving wallet user of the linked device");
 
-        let user_result = wallet_user_repository
-            .find_wallet_user_by_wallet_id(&tx, &cert_data.wallet_id)
+        // Like when handling an instruction, the wallet user stays locked until the PIN entry is registered.
+        let wallet_user = self
+            .verify_wallet_certificate_in_transaction(&unverified.payload.certificate, repositories, &tx, hsm)
+            .await
+            .and_then(Self::reject_blocked)?;
+
+        debug!("Clearing device link challenge of device {}", wallet_user.device_id);
+
+        // The challenge is cleared regardless of the outcome, so that it can only be used once.
+        repositories
+            .update_device_link_challenge(&tx, wallet_user.device_id, None)
             .await?;
-        tx.commit().await?;
 
-        match user_result {
-            WalletUserQueryResult::NotFound => {
-                debug!("No user found for the provided certificate: {}", &cert_data.wallet_id);
-                Err(WalletCertificateError::UserNotRegistered)
-            }
-            WalletUserQueryResult::Blocked => {
-                debug!("User found for the provided certificate is blocked");
-                Err(WalletCertificateError::UserBlocked)
-            }
-            WalletUserQueryResult::Found(user_boxed) => {
-                debug!("Generating pin public key hash");
+        debug!("Evaluating pin policy state");
+
+        let (pin_entries, pin_eval) = Self::evaluate_pin_policy(&wallet_user, pin_policy, generators.generate());
+
+        if matches!(pin_eval, PinPolicyEvaluation::InTimeout { .. }) {
+            self.audit_instruction(
+                repositories,
+                &tx,
+                generators,
+                hsm,
+                &wallet_user,
+                InstructionType::LinkDevice,
+                InstructionOutcome::Blocked,
+                InstructionAuditDetails::default(),
+            )
+            .await?;
+
+            tx.commit().await?;
+            return Err(pin_eval.into());
+        }
+
+        debug!("Verifying device link message");
+
+        let hw_pubkey = unverified.payload.hw_pubkey.0;
 
-                let user = *user_boxed;
+        match self
+            .verify_device_link(&link_message, &hw_pubkey, &wallet_user, generators, hsm)
+            .await
+        {
+            Ok((pin_pubkey, platform_attestation)) => {
+                debug!("Device link message successfully verified, resetting pin retries");
+
+                repositories
+                    .reset_unsuccessful_pin_entries(&tx, &wallet_user.wallet_id)
+                    .await?;
+
+                let device_id = Generator::<Uuid>::generate(generators);
 
-                let pin_pubkey =
-                    Decrypter::decrypt(hsm, &self.encryption_key_identifier, user.encrypted_pin_pubkey.clone()).await?;
+                debug!("Linking device {} to user {}", device_id, wallet_user.id);
 
-                let pin_hash_verification = verify_pin_pubkey(
-                    pin_pubkey,
-                    cert_data.pin_pubkey_hash,
-                    &self.pin_public_disclosure_protection_key_identifier,
+                repositories
+                    .create_wallet_user_device(
+                        &tx,
+                        WalletUserDeviceCreate {
+                            id: device_id,
+                            wallet_user_id: wallet_user.id,
+                            hw_pubkey,
+                            linked_date_time: generators.generate(),
+                            platform_attestation: Some(platform_attestation),
+                        },
+                    )
+                    .await?;
+
+                self.audit_instruction(
+                    repositories,
+                    &tx,
+                    generators,
                     hsm,
+                    &wallet_user,
+                    InstructionType::LinkDevice,
+                    InstructionOutcome::Success,
+                    InstructionAuditDetails::default(),
                 )
-                .await;
+                .await?;
+
+                debug!("Generating new wallet certificate for device {}", device_id);
+
+                let cert = self
+                    .new_wallet_certificate_claims(wallet_user.wallet_id.clone(), hw_pubkey, pin_pubkey, hsm)
+                    .await?;
+                let wallet_certificate = Jwt::sign_with_sub(&cert, certificate_signing_key)
+                    .await
+                    .map_err(InstructionError::Signing)?;
+
+                tx.commit().await?;
+
+                Ok(wallet_certificate)
+            }
+            Err(error) => {
+                let (outcome, error) = if matches!(error, InstructionError::Device(DeviceError::LinkValidation(_))) {
+                    debug!("Device link validation failed, registering unsuccessful pin entry");
 
-                debug!("Verifying user matches the provided certificate");
+                    repositories
+                        .register_unsuccessful_pin_entry(
+                            &tx,
+                            &wallet_user.wallet_id,
+                            pin_entries,
+                            matches!(pin_eval, PinPolicyEvaluation::BlockedPermanently),
+                            generators.generate(),
+                        )
+                        .await?;
 
-                if pin_hash_verification.is_err() {
-                    Err(WalletCertificateError::PinPubKeyMismatch)
-                } else if user.hw_pubkey != cert_data.hw_pubkey {
-                    Err(WalletCertificateError::HwPubKeyMismatch)
+                    let outcome = match pin_eval {
+                        PinPolicyEvaluation::Failed { .. } => InstructionOutcome::IncorrectPin,
+                        _ => InstructionOutcome::Blocked,
+                    };
+                    (outcome, pin_eval.into())
                 } else {
-                    Ok(user)
-                }
+                    (InstructionOutcome::ValidationError, error)
+                };
+
+                self.audit_instruction(
+                    repositories,
+                    &tx,
+                    generators,
+                    hsm,
+                    &wallet_user,
+                    InstructionType::LinkDevice,
+                    outcome,
+                    InstructionAuditDetails::default(),
+                )
+                .await?;
+
+                tx.commit().await?;
+                Err(error)
             }
         }
     }
 
-    async fn verify_instruction<I, R, D>(
+    /// Verify the device link message and the platform attestation it contains against the device link challenge of
+    /// the linked device, returning the PIN public key of the wallet user it was signed with and the outcome of the
+    /// platform attestation.
+    async fn verify_device_link<H>(
         &self,
-        instruction: Instruction<I>,
+        link_message: &SignedDouble<DeviceLink>,
+        hw_pubkey: &VerifyingKey,
         wallet_user: &WalletUser,
         time_generator: &impl Generator<DateTime<Local>>,
-        verifying_key_decrypter: &D,
-    ) -> Result<ChallengeResponsePayload<I>, InstructionValidationError>
+        hsm: &H,
+    ) -> Result<(VerifyingKey, WalletUserPlatformAttestation), InstructionError>
     where
-        I: HandleInstruction<Result = R> + Serialize + DeserializeOwned,
-        D: Decrypter<VerifyingKey, Error = HsmError>,
+        H: Decrypter<VerifyingKey, Error = HsmError>,
     {
         let challenge = wallet_user
-            .instruction_challenge
+            .device_link_challenge
             .as_ref()
-            .ok_or(InstructionValidationError::ChallengeMismatch)?;
+            .ok_or(DeviceError::LinkChallengeMismatch)?;
 
         if challenge.expiration_date_time < time_generator.generate() {
-            return Err(InstructionValidationError::ChallengeTimeout);
+            return Err(DeviceError::LinkChallengeTimeout.into());
         }
 
-        let pin_pubkey = verifying_key_decrypter
-            .decrypt(
-                &self.encryption_key_identifier,
+        let pin_pubkey = Decrypter::decrypt(
+            hsm,
+            &self.pin_pubkey_encryption_key_identifier(wallet_user)?,
+            wallet_user.encrypted_pin_pubkey.clone(),
+        )
+        .await?;
+
+        let link = link_message
+            .parse_and_verify(
+                &challenge.bytes,
+                SequenceNumberComparison::EqualTo(0),
+                hw_pubkey,
+                &pin_pubkey,
+            )
+            .map_err(DeviceError::LinkValidation)?;
+
+        // Contrary to registration, the platform attestation is required regardless of the configuration.
+        let platform_attestation = self
+            .platform_attestation_verifier
+            .verify(
+                Some(&link.payload.platform_attestation),
+                &challenge.bytes,
+                hw_pubkey,
+                time_generator,
+            )
+            .map_err(DeviceError::LinkPlatformAttestation)?
+            .ok_or(DeviceError::LinkPlatformAttestation(PlatformAttestationError::Missing))?;
+
+        Ok((pin_pubkey, platform_attestation))
+    }
+
+    fn verify_pin_recovery_instruction(
+        &self,
+        instruction: Instruction<RecoverPin>,
+        wallet_user: &WalletUser,
+        time_generator: &impl Generator<DateTime<Local>>,
+    ) -> Result<ChallengeResponsePayload<RecoverPin>, InstructionValidationError> {
+        let challenge = wallet_user
+            .instruction_challenge
+            .as_ref()
+            .ok_or(InstructionValidationError::ChallengeMismatch)?;
+
+        if challenge.expiration_date_time < time_generator.generate() {
+            return Err(InstructionValidationError::ChallengeTimeout);
+        }
+
+        // The new pin public key is contained within the instruction, so parse it before verifying the instruction.
+        let pin_pubkey = instruction
+            .instruction
+            .dangerous_parse_unverified()
+            .map_err(InstructionValidationError::VerificationFailed)?
+            .payload
+            .pin_pubkey
+            .0;
+
+        instruction
+            .instruction
+            .parse_and_verify(
+                &challenge.bytes,
+                SequenceNumberComparison::LargerThan(wallet_user.instruction_sequence_number),
+                &wallet_user.hw_pubkey.0,
+                &pin_pubkey,
+            )
+            .map_err(InstructionValidationError::VerificationFailed)
+    }
+
+    /// Verify that the PID held by the wallet is bound to a key of the wallet user, by signing a random message with
+    /// that key and verifying the signature against the public key contained in the PID.
+    async fn verify_pid_key<T, R, H>(
+        &self,
+        wallet_user: &WalletUser,
+        key_identifier: &str,
+        pid_public_key: &VerifyingKey,
+        repositories: &R,
+        tx: &T,
+        hsm: &H,
+    ) -> Result<(), InstructionError>
+    where
+        T: Committable,
+        R: WalletUserRepository<TransactionType = T>,
+        H: WalletUserHsm<Error = HsmError>,
+    {
+        let wrapped_key = repositories
+            .find_keys_by_identifiers(tx, wallet_user.id, &[key_identifier.to_string()])
+            .await?
+            .remove(key_identifier)
+            .ok_or_else(|| PinRecoveryError::PidKeyNotFound(key_identifier.to_string()))?;
+
+        let message = random_bytes(32);
+        let signature = hsm.sign_wrapped(wrapped_key, Arc::new(message.clone())).await?;
+
+        pid_public_key
+            .verify(&message, &signature)
+            .map_err(|_| PinRecoveryError::PidKeyMismatch)?;
+
+        Ok(())
+    }
+
+    async fn new_wallet_certificate<H>(
+        &self,
+        certificate_signing_key: &impl CertificateSigningKey,
+        wallet_id: String,
+        wallet_hw_pubkey: VerifyingKey,
+        wallet_pin_pubkey: VerifyingKey,
+        hsm: &H,
+    ) -> Result<WalletCertificate, RegistrationError>
+    where
+        H: Hsm<Error = HsmError>,
+    {
+        let cert = self
+            .new_wallet_certificate_claims(wallet_id, wallet_hw_pubkey, wallet_pin_pubkey, hsm)
+            .await?;
+
+        Jwt::sign_with_sub(&cert, certificate_signing_key)
+            .await
+            .map_err(RegistrationError::JwtSigning)
+    }
+
+    async fn new_wallet_certificate_claims<H>(
+        &self,
+        wallet_id: String,
+        wallet_hw_pubkey: VerifyingKey,
+        wallet_pin_pubkey: VerifyingKey,
+        hsm: &H,
+    ) -> Result<WalletCertificateClaims, WalletCertificateError>
+    where
+        H: Hsm<Error = HsmError>,
+    {
+        let pin_pubkey_hash = sign_pin_pubkey(
+            wallet_pin_pubkey,
+            &self.pin_public_disclosure_protection_key_identifier,
+            hsm,
+        )
+        .await?;
+
+        let cert = WalletCertificateClaims {
+            wallet_id,
+            hw_pubkey: wallet_hw_pubkey.into(),
+            pin_pubkey_hash,
+            version: WALLET_CERTIFICATE_VERSION,
+
+            iss: self.name.clone(),
+            iat: jsonwebtoken::get_current_timestamp(),
+        };
+
+        Ok(cert)
+    }
+
+    fn verify_registration_challenge(
+        &self,
+        certificate_signing_pubkey: &EcdsaDecodingKey,
+        challenge: &[u8],
+    ) -> Result<RegistrationChallengeClaims, RegistrationError> {
+        Jwt::parse_and_verify_with_sub(
+            &String::from_utf8(challenge.to_owned())
+                .map_err(RegistrationError::ChallengeDecoding)?
+                .into(),
+            certificate_signing_pubkey,
+        )
+        .map_err(RegistrationError::ChallengeValidation)
+    }
+
+    /// If the registration restores a backup, verify that the wallet owns the account to restore. This happens within
+    /// the transaction that creates the new account. When the PIN is incorrect, the unsuccessful PIN entry is
+    /// registered and the transaction is committed.
+    async fn verify_registration_restore<T, R, G, H>(
+        &self,
+        registration: &ChallengeResponsePayload<Registration>,
+        generators: &G,
+        repositories: &R,
+        tx: T,
+        pin_policy: &impl PinPolicyEvaluator,
+        hsm: &H,
+    ) -> Result<(Option<WalletUser>, T), RegistrationError>
+    where
+        T: Committable,
+        R: WalletUserRepository<TransactionType = T>,
+        G: Generator<DateTime<Local>>,
+        H: Decrypter<VerifyingKey, Error = HsmError> + Hsm<Error = HsmError>,
+    {
+        let Some(restore) = registration.payload.restore.as_ref() else {
+            return Ok((None, tx));
+        };
+
+        debug!("Verifying ownership of the account to restore");
+
+        let wallet_user = match self
+            .verify_wallet_certificate_in_transaction(&restore.certificate, repositories, &tx, hsm)
+            .await
+            .map_err(RegistrationError::RestoreWalletCertificate)?
+        {
+            (_, true) => {
+                debug!("User found for the account to restore is blocked");
+                return Err(RegistrationError::RestoreWalletCertificate(
+                    WalletCertificateError::UserBlocked,
+                ));
+            }
+            (user, false) => user,
+        };
+
+        debug!("Evaluating pin policy state of the account to restore");
+
+        let (pin_entries, pin_eval) = Self::evaluate_pin_policy(&wallet_user, pin_policy, generators.generate());
+
+        if matches!(pin_eval, PinPolicyEvaluation::InTimeout { .. }) {
+            return Err(RegistrationError::RestorePin(pin_eval));
+        }
+
+        debug!("Checking if challenge is signed with the pin key of the account to restore");
+
+        let pin_pubkey = Decrypter::decrypt(
+            hsm,
+            &self.pin_pubkey_encryption_key_identifier(&wallet_user)?,
+            wallet_user.encrypted_pin_pubkey.clone(),
+        )
+        .await?;
+
+        if restore.verify(&registration.challenge, &pin_pubkey).is_err() {
+            debug!("Restore pin validation failed, registering unsuccessful pin entry");
+
+            repositories
+                .register_unsuccessful_pin_entry(
+                    &tx,
+                    &wallet_user.wallet_id,
+                    pin_entries,
+                    matches!(pin_eval, PinPolicyEvaluation::BlockedPermanently),
+                    generators.generate(),
+                )
+                .await?;
+            tx.commit().await?;
+
+            return Err(RegistrationError::RestorePin(pin_eval));
+        }
+
+        Ok((Some(wallet_user), tx))
+    }
+
+    /// Evaluate the PIN policy for the next PIN entry of the wallet user, returning the total number of unsuccessful
+    /// PIN entries to register when this entry turns out to be unsuccessful.
+    fn evaluate_pin_policy(
+        wallet_user: &WalletUser,
+        pin_policy: &impl PinPolicyEvaluator,
+        current_datetime: DateTime<Local>,
+    ) -> (u8, PinPolicyEvaluation) {
+        let pin_entries = pin_policy
+            .decayed_attempts(
+                wallet_user.unsuccessful_pin_entries,
+                wallet_user.last_unsuccessful_pin_entry,
+                current_datetime,
+            )
+            .saturating_add(1);
+        let pin_eval = pin_policy.evaluate(pin_entries, wallet_user.last_unsuccessful_pin_entry, current_datetime);
+
+        (pin_entries, pin_eval)
+    }
+
+    fn reject_blocked((user, is_blocked): (WalletUser, bool)) -> Result<WalletUser, WalletCertificateError> {
+        if is_blocked {
+            debug!("User found for the provided certificate is blocked");
+            return Err(WalletCertificateError::UserBlocked);
+        }
+
+        Ok(user)
+    }
+
+    /// Verify the wallet certificate and retrieve the wallet user it belongs to in a separate transaction, accepting a
+    /// wallet user that is blocked. Returns the wallet user and whether it is blocked.
+    async fn verify_wallet_certificate_allowing_blocked<T, R, H>(
+        &self,
+        certificate: &WalletCertificate,
+        wallet_user_repository: &R,
+        hsm: &H,
+    ) -> Result<(WalletUser, bool), WalletCertificateError>
+    where
+        T: Committable,
+        R: TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>,
+        H: Decrypter<VerifyingKey, Error = HsmError> + Hsm<Error = HsmError>,
+    {
+        debug!("Starting database transaction");
+
+        let tx = wallet_user_repository.begin_transaction().await?;
+        let result = self
+            .verify_wallet_certificate_in_transaction(certificate, wallet_user_repository, &tx, hsm)
+            .await;
+        tx.commit().await?;
+
+        result
+    }
+
+    /// Verify the wallet certificate like [`Self::verify_wallet_certificate_allowing_blocked`] does, but look up the
+    /// wallet user within the provided transaction.
+    async fn verify_wallet_certificate_in_transaction<T, R, H>(
+        &self,
+        certificate: &WalletCertificate,
+        wallet_user_repository: &R,
+        tx: &T,
+        hsm: &H,
+    ) -> Result<(WalletUser, bool), WalletCertificateError>
+    where
+        R: WalletUserRepository<TransactionType = T>,
+        H: Decrypter<VerifyingKey, Error = HsmError> + Hsm<Error = HsmError>,
+    {
+        debug!("Parsing and verifying the provided certificate");
+
+        let cert_data = certificate.parse_and_verify_with_sub(&self.certificate_signing_pubkey)?;
+
+        debug!("Fetching the user associated to the provided certificate");
+
+        let user_result = wallet_user_repository
+            .find_wallet_user_by_wallet_id(tx, &cert_data.wallet_id, &cert_data.hw_pubkey.0)
+            .await?;
+
+        let (user, is_blocked) = match user_result {
+            WalletUserQueryResult::NotFound => {
+                debug!("No user found for the provided certificate: {}", &cert_data.wallet_id);
+                return Err(WalletCertificateError::UserNotRegistered);
+            }
+            WalletUserQueryResult::Suspended => {
+                debug!("User found for the provided certificate is suspended");
+                return Err(WalletCertificateError::UserSuspended);
+            }
+            WalletUserQueryResult::Revoked => {
+                debug!("User found for the provided certificate is revoked");
+                return Err(WalletCertificateError::UserRevoked);
+            }
+            WalletUserQueryResult::DeviceNotLinked => {
+                debug!("Device of the provided certificate is not linked to the user");
+                return Err(WalletCertificateError::DeviceNotLinked);
+            }
+            WalletUserQueryResult::Blocked(user_boxed) => (*user_boxed, true),
+            WalletUserQueryResult::Found(user_boxed) => (*user_boxed, false),
+        };
+
+        debug!("Generating pin public key hash");
+
+        let pin_pubkey = Decrypter::decrypt(
+            hsm,
+            &self.pin_pubkey_encryption_key_identifier(&user)?,
+            user.encrypted_pin_pubkey.clone(),
+        )
+        .await?;
+
+        let pin_hash_verification = verify_pin_pubkey(
+            pin_pubkey,
+            cert_data.pin_pubkey_hash,
+            &self.pin_public_disclosure_protection_key_identifier,
+            hsm,
+        )
+        .await;
+
+        debug!("Verifying user matches the provided certificate");
+
+        if pin_hash_verification.is_err() {
+            Err(WalletCertificateError::PinPubKeyMismatch)
+        } else if user.hw_pubkey != cert_data.hw_pubkey {
+            Err(WalletCertificateError::HwPubKeyMismatch)
+        } else {
+            Ok((user, is_blocked))
+        }
+    }
+
+    async fn verify_instruction<I, R, D>(
+        &self,
+        instruction: Instruction<I>,
+        wallet_user: &WalletUser,
+        time_generator: &impl Generator<DateTime<Local>>,
+        verifying_key_decrypter: &D,
+    ) -> Result<ChallengeResponsePayload<I>, InstructionValidationError>
+    where
+        I: HandleInstruction<Result = R> + Serialize + DeserializeOwned,
+        D: Decrypter<VerifyingKey, Error = HsmError>,
+    {
+        let challenge = wallet_user
+            .instruction_challenge
+            .as_ref()
+            .ok_or(InstructionValidationError::ChallengeMismatch)?;
+
+        if challenge.expiration_date_time < time_generator.generate() {
+            return Err(InstructionValidationError::ChallengeTimeout);
+        }
+
+        let pin_pubkey = verifying_key_decrypter
+            .decrypt(
+                &self.pin_pubkey_encryption_key_identifier(wallet_user)?,
                 wallet_user.encrypted_pin_pubkey.clone(),
             )
             .await?;
@@ -683,14 +1502,22 @@ pub mod mock {
             Duration::from_millis(15000),
             "mock_account_server".into(),
             certificate_signing_pubkey,
-            "encryption_key_1".into(),
-            "signing_key_2".into(),
+            AccountServerKeys {
+                pin_pubkey_encryption_key: "encryption_key_1".to_string().into(),
+                pin_public_disclosure_protection_key_identifier: "signing_key_2".into(),
+                instruction_audit_log_key_identifier: "instruction_audit_log_key".into(),
+            },
+            PlatformAttestationVerifier::default(),
+            PinRecoveryVerifier::default(),
         )
         .await
         .unwrap();
 
         let hsm = MockPkcs11Client::default();
         hsm.generate_generic_secret_key("signing_key_2").await.unwrap();
+        hsm.generate_generic_secret_key("instruction_audit_log_key")
+            .await
+            .unwrap();
 
         (account_server, hsm)
     }
@@ -698,17 +1525,28 @@ pub mod mock {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, num::NonZeroU8, sync::Mutex};
 
     use assert_matches::assert_matches;
-    use chrono::TimeZone;
-    use p256::ecdsa::SigningKey;
+    use chrono::{Days, TimeDelta, TimeZone, Utc};
+    use indexmap::IndexMap;
+    use p256::ecdsa::{signature::Signer, Signature, SigningKey};
     use rand::rngs::OsRng;
+    use rstest::rstest;
     use uuid::uuid;
 
+    use nl_wallet_mdoc::{
+        server_keys::KeyPair,
+        unsigned::{Entry, UnsignedMdoc},
+        utils::serialization::cbor_serialize,
+        IssuerSigned, Tdate,
+    };
     use wallet_common::{
         account::{
-            messages::instructions::{CheckPin, InstructionChallengeRequest},
+            messages::{
+                auth::{PlatformAttestation, RegistrationRestore},
+                instructions::{CheckPin, GenerateKey, InstructionChallengeRequest, Sign},
+            },
             serialization::DerVerifyingKey,
         },
         keys::{software::SoftwareEcdsaKey, EcdsaKey},
@@ -716,14 +1554,28 @@ mod tests {
     use wallet_provider_domain::{
         generator::mock::MockGenerators,
         model::{
-            hsm::mock::MockPkcs11Client, wallet_user::WalletUserKeys, wrapped_key::WrappedKey, FailingPinPolicy,
-            TimeoutPinPolicy,
+            encrypted::Encrypted,
+            hsm::mock::MockPkcs11Client,
+            instruction_audit_log::{verify_chain, InstructionType},
+            wallet_user::{
+                WalletUserDetails, WalletUserDevice, WalletUserKeys, WalletUserPlatformAttestation, WalletUserState,
+            },
+            wrapped_key::WrappedKey,
+            FailingPinPolicy, TimeoutPinPolicy,
         },
         repository::{MockTransaction, MockTransactionStarter},
-        EpochGenerator, FixedUuidGenerator,
+        EpochGenerator,
     };
     use wallet_provider_persistence::repositories::mock::MockTransactionalWalletUserRepository;
 
+    use crate::platform_attestation::{
+        mock::{
+            android_certificate_chain, MockAttestationCa, MockKeyDescription, ANDROID_PACKAGE_NAME,
+            ANDROID_SIGNING_CERTIFICATE_DIGEST,
+        },
+        AndroidAttestationConfig, AndroidSecurityLevel,
+    };
+
     use super::*;
 
     async fn do_registration(
@@ -751,8 +1603,9 @@ mod tests {
         account_server
             .register(
                 certificate_signing_key,
-                &FixedUuidGenerator,
+                &MockGenerators,
                 &wallet_user_repo,
+                &FailingPinPolicy,
                 hsm,
                 registration_message,
             )
@@ -786,19 +1639,288 @@ mod tests {
         assert_eq!(cert_data.hw_pubkey.0, *hw_privkey.verifying_key());
     }
 
-    struct WalletUserTestRepo {
-        hw: VerifyingKey,
-        pin: VerifyingKey,
-        challenge: Option<Vec<u8>>,
-        instruction_sequence_number: u64,
-    }
+    #[tokio::test]
+    async fn test_register_platform_attestation() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
 
-    impl WalletUserRepository for WalletUserTestRepo {
-        type TransactionType = MockTransaction;
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let ca = MockAttestationCa::generate();
+        let account_server = with_android_attestation(account_server, &ca);
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
 
-        async fn create_wallet_user(
-            &self,
-            _transaction: &Self::TransactionType,
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_create_wallet_user()
+            .withf(|_, user| {
+                user.platform_attestation == Some(WalletUserPlatformAttestation::AndroidTrustedEnvironment)
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        // The wallet includes a key attestation of its hardware key, bound to the registration challenge.
+        let challenge = account_server
+            .registration_challenge(&certificate_signing_key)
+            .await
+            .expect("Could not get registration challenge");
+        let certificate_chain = android_certificate_chain(&ca, &hw_privkey, &MockKeyDescription::new(&challenge));
+        let registration_message = Registration::new_signed_with_platform_attestation(
+            &hw_privkey,
+            &pin_privkey,
+            &challenge,
+            None,
+            Some(PlatformAttestation::Android { certificate_chain }),
+        )
+        .await
+        .expect("Could not sign new registration");
+
+        account_server
+            .register(
+                &certificate_signing_key,
+                &MockGenerators,
+                &wallet_user_repo,
+                &FailingPinPolicy,
+                &hsm,
+                registration_message,
+            )
+            .await
+            .expect("Could not process registration message at account server");
+
+        // As a platform attestation is required, a registration without one should be rejected.
+        let challenge = account_server
+            .registration_challenge(&certificate_signing_key)
+            .await
+            .expect("Could not get registration challenge");
+        let registration_message = Registration::new_signed(&hw_privkey, &pin_privkey, &challenge)
+            .await
+            .expect("Could not sign new registration");
+
+        let error = account_server
+            .register(
+                &certificate_signing_key,
+                &MockGenerators,
+                &wallet_user_repo,
+                &FailingPinPolicy,
+                &hsm,
+                registration_message,
+            )
+            .await
+            .expect_err("Registration without platform attestation should fail");
+
+        assert_matches!(
+            error,
+            RegistrationError::PlatformAttestation(PlatformAttestationError::Missing)
+        );
+    }
+
+    /// Require Android key attestations signed by `ca` for the account server.
+    fn with_android_attestation(account_server: AccountServer, ca: &MockAttestationCa) -> AccountServer {
+        AccountServer {
+            platform_attestation_verifier: PlatformAttestationVerifier {
+                required: true,
+                android: Some(AndroidAttestationConfig {
+                    root_certificates: vec![ca.trust_anchor()],
+                    package_names: vec![ANDROID_PACKAGE_NAME.to_string()],
+                    signing_certificate_digests: vec![ANDROID_SIGNING_CERTIFICATE_DIGEST.to_vec()],
+                    security_levels: vec![AndroidSecurityLevel::TrustedEnvironment],
+                }),
+                apple: None,
+            },
+            ..account_server
+        }
+    }
+
+    async fn restore_registration_message(
+        account_server: &AccountServer,
+        certificate_signing_key: &impl CertificateSigningKey,
+        old_certificate: WalletCertificate,
+        old_pin_privkey: &SigningKey,
+    ) -> SignedDouble<Registration> {
+        let challenge = account_server
+            .registration_challenge(certificate_signing_key)
+            .await
+            .expect("Could not get registration challenge");
+
+        let restore = RegistrationRestore::new_signed(old_certificate, old_pin_privkey, &challenge)
+            .await
+            .expect("Could not sign registration restore");
+
+        Registration::new_signed_with_restore(
+            &SigningKey::random(&mut OsRng),
+            &SigningKey::random(&mut OsRng),
+            &challenge,
+            Some(restore),
+        )
+        .await
+        .expect("Could not sign new registration")
+    }
+
+    async fn restore_wallet_user_repo(
+        hsm: &MockPkcs11Client<HsmError>,
+        old_hw_pubkey: VerifyingKey,
+        old_pin_pubkey: VerifyingKey,
+    ) -> MockTransactionalWalletUserRepository {
+        let encrypted_pin_pubkey = Encrypter::<VerifyingKey>::encrypt(hsm, "encryption_key_1", old_pin_pubkey)
+            .await
+            .unwrap();
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_find_wallet_user_by_wallet_id()
+            .returning(move |_, wallet_id, _| {
+                Ok(WalletUserQueryResult::Found(Box::new(WalletUser {
+                    id: uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08"),
+                    wallet_id: wallet_id.to_string(),
+                    device_id: uuid!("e1b2f6a5-1e0d-4c36-9d7b-5f3a4c1e8a21"),
+                    hw_pubkey: DerVerifyingKey(old_hw_pubkey),
+                    encrypted_pin_pubkey: encrypted_pin_pubkey.clone(),
+                    pin_pubkey_encryption_key_version: 0,
+                    unsuccessful_pin_entries: 0,
+                    last_unsuccessful_pin_entry: None,
+                    instruction_challenge: None,
+                    instruction_sequence_number: 0,
+                    device_link_challenge: None,
+                    platform_attestation: None,
+                })))
+            });
+
+        wallet_user_repo
+    }
+
+    #[tokio::test]
+    async fn test_register_restore() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let old_certificate = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+        let old_wallet_id = old_certificate
+            .parse_and_verify_with_sub(&(&certificate_signing_pubkey).into())
+            .unwrap()
+            .wallet_id;
+
+        let registration_message =
+            restore_registration_message(&account_server, &certificate_signing_key, old_certificate, &pin_privkey)
+                .await;
+
+        // The keys of the old wallet user should be transferred to the new one, after which it is revoked.
+        let mut wallet_user_repo =
+            restore_wallet_user_repo(&hsm, *hw_privkey.verifying_key(), *pin_privkey.verifying_key()).await;
+        wallet_user_repo
+            .expect_create_wallet_user()
+            .times(1)
+            .returning(|_, _| Ok(()));
+        wallet_user_repo
+            .expect_transfer_keys()
+            .withf(|_, source, destination| {
+                *source == uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08")
+                    && *destination == uuid!("c9723aef-022b-4ab7-9cc3-ff4227ec1cc9")
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        wallet_user_repo
+            .expect_update_wallet_user_state()
+            .withf(move |_, wallet_id, state| wallet_id == old_wallet_id && *state == WalletUserState::Revoked)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        account_server
+            .register(
+                &certificate_signing_key,
+                &MockGenerators,
+                &wallet_user_repo,
+                &FailingPinPolicy,
+                &hsm,
+                registration_message,
+            )
+            .await
+            .expect("Could not process restore registration message at account server");
+    }
+
+    #[tokio::test]
+    async fn test_register_restore_wrong_pin() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let old_certificate = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        // Sign the challenge with a PIN key that does not belong to the old account.
+        let registration_message = restore_registration_message(
+            &account_server,
+            &certificate_signing_key,
+            old_certificate,
+            &SigningKey::random(&mut OsRng),
+        )
+        .await;
+
+        // An unsuccessful PIN entry should be registered and no new wallet user should be created.
+        let mut wallet_user_repo =
+            restore_wallet_user_repo(&hsm, *hw_privkey.verifying_key(), *pin_privkey.verifying_key()).await;
+        wallet_user_repo
+            .expect_register_unsuccessful_pin_entry()
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        wallet_user_repo.expect_create_wallet_user().never();
+
+        let error = account_server
+            .register(
+                &certificate_signing_key,
+                &MockGenerators,
+                &wallet_user_repo,
+                &FailingPinPolicy,
+                &hsm,
+                registration_message,
+            )
+            .await
+            .expect_err("restore registration with the wrong PIN should fail");
+
+        assert_matches!(error, RegistrationError::RestorePin(PinPolicyEvaluation::Failed { .. }));
+    }
+
+    struct WalletUserTestRepo {
+        hw: VerifyingKey,
+        pin: VerifyingKey,
+        challenge: Option<Vec<u8>>,
+        instruction_sequence_number: u64,
+        instruction_audit_log: Mutex<Vec<InstructionAuditLogEntry>>,
+        instruction_audit_log_unavailable: bool,
+        device_link_challenge: Mutex<Option<InstructionChallenge>>,
+    }
+
+    impl WalletUserRepository for WalletUserTestRepo {
+        type TransactionType = MockTransaction;
+
+        async fn create_wallet_user(
+            &self,
+            _transaction: &Self::TransactionType,
             _user: WalletUserCreate,
         ) -> Result<(), PersistenceError> {
             Ok(())
@@ -807,10 +1929,12 @@ mod tests {
             &self,
             _transaction: &Self::TransactionType,
             wallet_id: &str,
+            _hw_pubkey: &VerifyingKey,
         ) -> Result<WalletUserQueryResult, PersistenceError> {
             Ok(WalletUserQueryResult::Found(Box::new(WalletUser {
                 id: uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08"),
                 wallet_id: wallet_id.to_string(),
+                device_id: uuid!("e1b2f6a5-1e0d-4c36-9d7b-5f3a4c1e8a21"),
                 hw_pubkey: DerVerifyingKey(self.hw),
                 encrypted_pin_pubkey: Encrypter::<VerifyingKey>::encrypt(
                     &MockPkcs11Client::<HsmError>::default(),
@@ -819,6 +1943,7 @@ mod tests {
                 )
                 .await
                 .unwrap(),
+                pin_pubkey_encryption_key_version: 0,
                 unsuccessful_pin_entries: 0,
                 last_unsuccessful_pin_entry: None,
                 instruction_challenge: self.challenge.clone().map(|c| InstructionChallenge {
@@ -826,12 +1951,22 @@ mod tests {
                     expiration_date_time: Local::now() + Duration::from_millis(15000),
                 }),
                 instruction_sequence_number: self.instruction_sequence_number,
+                device_link_challenge: self.device_link_challenge.lock().unwrap().clone(),
+                platform_attestation: None,
             })))
         }
+        async fn find_wallet_user_details_by_wallet_id(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_id: &str,
+        ) -> Result<Option<WalletUserDetails>, PersistenceError> {
+            Ok(None)
+        }
         async fn register_unsuccessful_pin_entry(
             &self,
             _transaction: &Self::TransactionType,
             _wallet_id: &str,
+            _pin_entries: u8,
             _is_blocked: bool,
             _datetime: DateTime<Local>,
         ) -> Result<(), PersistenceError> {
@@ -844,17 +1979,41 @@ mod tests {
         ) -> Result<(), PersistenceError> {
             Ok(())
         }
-        async fn clear_instruction_challenge(
+        async fn block_wallet_user(
             &self,
             _transaction: &Self::TransactionType,
             _wallet_id: &str,
         ) -> Result<(), PersistenceError> {
             Ok(())
         }
-        async fn update_instruction_challenge_and_sequence_number(
+        async fn update_wallet_user_pin_pubkey(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_id: &str,
+            _encrypted_pin_pubkey: Encrypted<VerifyingKey>,
+            _pin_pubkey_encryption_key_version: u32,
+        ) -> Result<(), PersistenceError> {
+            Ok(())
+        }
+        async fn update_wallet_user_state(
             &self,
             _transaction: &Self::TransactionType,
             _wallet_id: &str,
+            _state: WalletUserState,
+        ) -> Result<(), PersistenceError> {
+            Ok(())
+        }
+        async fn clear_instruction_challenge(
+            &self,
+            _transaction: &Self::TransactionType,
+            _device_id: Uuid,
+        ) -> Result<(), PersistenceError> {
+            Ok(())
+        }
+        async fn update_instruction_challenge_and_sequence_number(
+            &self,
+            _transaction: &Self::TransactionType,
+            _device_id: Uuid,
             _challenge: InstructionChallenge,
             _instruction_sequence_number: u64,
         ) -> Result<(), PersistenceError> {
@@ -863,7 +2022,7 @@ mod tests {
         async fn update_instruction_sequence_number(
             &self,
             _transaction: &Self::TransactionType,
-            _wallet_id: &str,
+            _device_id: Uuid,
             _instruction_sequence_number: u64,
         ) -> Result<(), PersistenceError> {
             Ok(())
@@ -886,34 +2045,601 @@ mod tests {
                 .map(|id| {
                     (
                         id.clone(),
-                        WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec()),
+                        WrappedKey::new(SigningKey::random(&mut OsRng).to_bytes().to_vec(), 0),
                     )
                 })
                 .collect())
         }
+        async fn transfer_keys(
+            &self,
+            _transaction: &Self::TransactionType,
+            _source_wallet_user_id: Uuid,
+            _destination_wallet_user_id: Uuid,
+        ) -> Result<(), PersistenceError> {
+            Ok(())
+        }
+    }
+
+    impl InstructionAuditLogRepository for WalletUserTestRepo {
+        type TransactionType = MockTransaction;
+
+        async fn create_instruction_audit_log_entry(
+            &self,
+            _transaction: &Self::TransactionType,
+            entry: InstructionAuditLogEntry,
+        ) -> Result<(), PersistenceError> {
+            if self.instruction_audit_log_unavailable {
+                return Err(PersistenceError::Execution("instruction audit log unavailable".into()));
+            }
+            self.instruction_audit_log.lock().unwrap().push(entry);
+            Ok(())
+        }
+
+        async fn find_last_instruction_audit_log_entry(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_user_id: Uuid,
+        ) -> Result<Option<InstructionAuditLogEntry>, PersistenceError> {
+            Ok(self.instruction_audit_log.lock().unwrap().last().cloned())
+        }
+
+        async fn find_instruction_audit_log_entries(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_user_id: Uuid,
+        ) -> Result<Vec<InstructionAuditLogEntry>, PersistenceError> {
+            Ok(self.instruction_audit_log.lock().unwrap().clone())
+        }
+    }
+
+    impl WalletUserDeviceRepository for WalletUserTestRepo {
+        type TransactionType = MockTransaction;
+
+        async fn create_wallet_user_device(
+            &self,
+            _transaction: &Self::TransactionType,
+            _device: WalletUserDeviceCreate,
+        ) -> Result<(), PersistenceError> {
+            Ok(())
+        }
+
+        async fn find_wallet_user_devices(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_user_id: Uuid,
+        ) -> Result<Vec<WalletUserDevice>, PersistenceError> {
+            Ok(vec![])
+        }
+
+        async fn delete_wallet_user_device(
+            &self,
+            _transaction: &Self::TransactionType,
+            _wallet_user_id: Uuid,
+            _device_id: Uuid,
+        ) -> Result<(), PersistenceError> {
+            Ok(())
+        }
+
+        async fn update_device_link_challenge(
+            &self,
+            _transaction: &Self::TransactionType,
+            _device_id: Uuid,
+            challenge: Option<InstructionChallenge>,
+        ) -> Result<(), PersistenceError> {
+            *self.device_link_challenge.lock().unwrap() = challenge;
+            Ok(())
+        }
     }
 
     impl TransactionStarter for WalletUserTestRepo {
         type TransactionType = <MockTransactionStarter as TransactionStarter>::TransactionType;
 
-        async fn begin_transaction(&self) -> Result<Self::TransactionType, PersistenceError> {
-            MockTransactionStarter.begin_transaction().await
-        }
+        async fn begin_transaction(&self) -> Result<Self::TransactionType, PersistenceError> {
+            MockTransactionStarter.begin_transaction().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_pin() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+        let instruction_result_signing_key = SoftwareEcdsaKey::new_random("instruction_result_signing_key".to_string());
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let hw_pubkey = *hw_privkey.verifying_key();
+        let pin_pubkey = *pin_privkey.verifying_key();
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let deps = WalletUserTestRepo {
+            hw: hw_pubkey,
+            pin: pin_pubkey,
+            challenge: None,
+            instruction_sequence_number: 42,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Default::default(),
+        };
+
+        assert_matches!(
+            account_server
+                .instruction_challenge(
+                    InstructionChallengeRequestMessage {
+                        message: InstructionChallengeRequest::new_signed(9, "wallet", &hw_privkey)
+                            .await
+                            .unwrap(),
+                        certificate: cert.clone(),
+                    },
+                    &deps,
+                    &EpochGenerator,
+                    &hsm,
+                )
+                .await
+                .expect_err("should return instruction sequence number mismatch error"),
+            ChallengeError::SequenceNumberValidation
+        );
+
+        let challenge = account_server
+            .instruction_challenge(
+                InstructionChallengeRequestMessage {
+                    message: InstructionChallengeRequest::new_signed(43, "wallet", &hw_privkey)
+                        .await
+                        .unwrap(),
+                    certificate: cert.clone(),
+                },
+                &deps,
+                &EpochGenerator,
+                &hsm,
+            )
+            .await
+            .unwrap();
+
+        assert_matches!(
+            account_server
+                .handle_instruction(
+                    Instruction::new_signed(
+                        CheckPin,
+                        43,
+                        &hw_privkey,
+                        &pin_privkey,
+                        &challenge.clone(),
+                        cert.clone(),
+                    )
+                    .await
+                    .unwrap(),
+                    &instruction_result_signing_key,
+                    &MockGenerators,
+                    &WalletUserTestRepo {
+                        hw: hw_pubkey,
+                        pin: pin_pubkey,
+                        challenge: Some(challenge.clone()),
+                        instruction_sequence_number: 43,
+                        instruction_audit_log: Default::default(),
+                        instruction_audit_log_unavailable: false,
+                        device_link_challenge: Default::default(),
+                    },
+                    &FailingPinPolicy,
+                    &hsm,
+                )
+                .await
+                .expect_err("sequence number mismatch error should result in IncorrectPin error"),
+            InstructionError::IncorrectPin(IncorrectPinData {
+                attempts_left_in_round: _,
+                is_final_round: _
+            })
+        );
+
+        account_server
+            .handle_instruction(
+                Instruction::new_signed(CheckPin, 44, &hw_privkey, &pin_privkey, &challenge, cert.clone())
+                    .await
+                    .unwrap(),
+                &instruction_result_signing_key,
+                &MockGenerators,
+                &WalletUserTestRepo {
+                    hw: hw_pubkey,
+                    pin: pin_pubkey,
+                    challenge: Some(challenge),
+                    instruction_sequence_number: 2,
+                    instruction_audit_log: Default::default(),
+                    instruction_audit_log_unavailable: false,
+                    device_link_challenge: Default::default(),
+                },
+                &TimeoutPinPolicy,
+                &hsm,
+            )
+            .await
+            .expect("should return instruction result");
+    }
+
+    #[tokio::test]
+    async fn test_handle_instruction_audit_log() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+        let instruction_result_signing_key = SoftwareEcdsaKey::new_random("instruction_result_signing_key".to_string());
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let challenge = random_bytes(32);
+        let repo = WalletUserTestRepo {
+            hw: *hw_privkey.verifying_key(),
+            pin: *pin_privkey.verifying_key(),
+            challenge: Some(challenge.clone()),
+            instruction_sequence_number: 43,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Default::default(),
+        };
+
+        // An instruction with an old sequence number fails verification, which counts as an incorrect PIN.
+        account_server
+            .handle_instruction(
+                Instruction::new_signed(CheckPin, 43, &hw_privkey, &pin_privkey, &challenge, cert.clone())
+                    .await
+                    .unwrap(),
+                &instruction_result_signing_key,
+                &MockGenerators,
+                &repo,
+                &FailingPinPolicy,
+                &hsm,
+            )
+            .await
+            .expect_err("sequence number mismatch should result in an error");
+
+        let messages_with_identifiers = vec![
+            (random_bytes(32), vec!["key1".to_string(), "key2".to_string()]),
+            (random_bytes(32), vec!["key1".to_string()]),
+        ];
+        account_server
+            .handle_instruction(
+                Instruction::new_signed(
+                    Sign {
+                        messages_with_identifiers: messages_with_identifiers.clone(),
+                    },
+                    44,
+                    &hw_privkey,
+                    &pin_privkey,
+                    &challenge,
+                    cert.clone(),
+                )
+                .await
+                .unwrap(),
+                &instruction_result_signing_key,
+                &MockGenerators,
+                &repo,
+                &TimeoutPinPolicy,
+                &hsm,
+            )
+            .await
+            .expect("should return instruction result");
+
+        let entries = repo.instruction_audit_log.into_inner().unwrap();
+        verify_chain(&entries, &hsm, account_server.instruction_audit_log_key_identifier())
+            .await
+            .expect("audit log entries should form a valid hash chain");
+
+        assert_matches!(
+            entries.as_slice(),
+            [
+                InstructionAuditLogEntry {
+                    instruction_type: InstructionType::CheckPin,
+                    outcome: InstructionOutcome::IncorrectPin,
+                    operation_count: 0,
+                    payload_hash: None,
+                    ..
+                },
+                InstructionAuditLogEntry {
+                    instruction_type: InstructionType::Sign,
+                    outcome: InstructionOutcome::Success,
+                    operation_count: 3,
+                    payload_hash: Some(_),
+                    ..
+                },
+            ]
+        );
+        assert_eq!(
+            entries[1].payload_hash,
+            Sign {
+                messages_with_identifiers
+            }
+            .audit_details()
+            .payload_hash
+        );
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct FailingInstruction;
+
+    impl HandleInstruction for FailingInstruction {
+        type Result = ();
+
+        const INSTRUCTION_TYPE: InstructionType = InstructionType::GenerateKey;
+
+        fn audit_details(&self) -> InstructionAuditDetails {
+            InstructionAuditDetails {
+                operation_count: 2,
+                payload_hash: None,
+            }
+        }
+
+        async fn handle<T, R, G>(
+            self,
+            _wallet_user: &WalletUser,
+            _generators: &G,
+            _wallet_user_repository: &R,
+            _wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
+        ) -> Result<(), InstructionError>
+        where
+            T: Committable,
+            R: TransactionStarter<TransactionType = T>
+                + WalletUserRepository<TransactionType = T>
+                + WalletUserDeviceRepository<TransactionType = T>
+                + InstructionAuditLogRepository<TransactionType = T>,
+            G: Generator<Uuid> + Generator<DateTime<Local>>,
+        {
+            Err(HsmError::KeyNotFound("key".to_string()).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_instruction_audit_log_failed() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+        let instruction_result_signing_key = SoftwareEcdsaKey::new_random("instruction_result_signing_key".to_string());
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let challenge = random_bytes(32);
+        let repo = WalletUserTestRepo {
+            hw: *hw_privkey.verifying_key(),
+            pin: *pin_privkey.verifying_key(),
+            challenge: Some(challenge.clone()),
+            instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Default::default(),
+        };
+
+        // An instruction that is valid but cannot be executed should be recorded as such, after it has been handled.
+        assert_matches!(
+            account_server
+                .handle_instruction(
+                    Instruction::new_signed(FailingInstruction, 1, &hw_privkey, &pin_privkey, &challenge, cert)
+                        .await
+                        .unwrap(),
+                    &instruction_result_signing_key,
+                    &MockGenerators,
+                    &repo,
+                    &FailingPinPolicy,
+                    &hsm,
+                )
+                .await
+                .expect_err("handling the instruction should fail"),
+            InstructionError::HsmError(HsmError::KeyNotFound(_))
+        );
+
+        let entries = repo.instruction_audit_log.into_inner().unwrap();
+        assert_matches!(
+            entries.as_slice(),
+            [InstructionAuditLogEntry {
+                instruction_type: InstructionType::GenerateKey,
+                outcome: InstructionOutcome::Failed,
+                operation_count: 2,
+                payload_hash: None,
+                ..
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_instruction_audit_log_unavailable() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+        let instruction_result_signing_key = SoftwareEcdsaKey::new_random("instruction_result_signing_key".to_string());
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let challenge = random_bytes(32);
+        let repo = WalletUserTestRepo {
+            hw: *hw_privkey.verifying_key(),
+            pin: *pin_privkey.verifying_key(),
+            challenge: Some(challenge.clone()),
+            instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: true,
+            device_link_challenge: Default::default(),
+        };
+
+        // The keys have already been generated when recording the instruction fails, so its result is still returned.
+        let result = account_server
+            .handle_instruction(
+                Instruction::new_signed(
+                    GenerateKey {
+                        identifiers: vec!["key1".to_string()],
+                    },
+                    1,
+                    &hw_privkey,
+                    &pin_privkey,
+                    &challenge,
+                    cert,
+                )
+                .await
+                .unwrap(),
+                &instruction_result_signing_key,
+                &MockGenerators,
+                &repo,
+                &TimeoutPinPolicy,
+                &hsm,
+            )
+            .await
+            .expect("should return instruction result");
+
+        let result = result
+            .parse_and_verify_with_sub(&(&instruction_result_signing_key.verifying_key().await.unwrap()).into())
+            .expect("could not verify instruction result");
+        assert_eq!(result.result.public_keys.len(), 1);
+
+        assert!(repo.instruction_audit_log.into_inner().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_link_device() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let ca = MockAttestationCa::generate();
+        let account_server = with_android_attestation(account_server, &ca);
+
+        let challenge = random_bytes(32);
+        let repo = WalletUserTestRepo {
+            hw: *hw_privkey.verifying_key(),
+            pin: *pin_privkey.verifying_key(),
+            challenge: None,
+            instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Mutex::new(Some(InstructionChallenge {
+                bytes: challenge.clone(),
+                expiration_date_time: Local::now() + Duration::from_millis(15000),
+            })),
+        };
+
+        // The new device includes a key attestation of its hardware key, bound to the device link challenge.
+        let new_hw_privkey = SigningKey::random(&mut OsRng);
+        let platform_attestation = PlatformAttestation::Android {
+            certificate_chain: android_certificate_chain(&ca, &new_hw_privkey, &MockKeyDescription::new(&challenge)),
+        };
+        let link_message = DeviceLink::new_signed(
+            cert.clone(),
+            platform_attestation.clone(),
+            &new_hw_privkey,
+            &pin_privkey,
+            &challenge,
+        )
+        .await
+        .unwrap();
+
+        let new_cert = account_server
+            .link_device(
+                &certificate_signing_key,
+                &MockGenerators,
+                &repo,
+                &FailingPinPolicy,
+                &hsm,
+                link_message,
+            )
+            .await
+            .expect("should link device");
+
+        let cert_data = new_cert
+            .parse_and_verify_with_sub(&(&certificate_signing_pubkey).into())
+            .expect("should issue a valid wallet certificate");
+        assert_eq!(cert_data.hw_pubkey.0, *new_hw_privkey.verifying_key());
+        assert!(repo.device_link_challenge.lock().unwrap().is_none());
+
+        // The device link challenge can only be used once.
+        let link_message =
+            DeviceLink::new_signed(cert, platform_attestation, &new_hw_privkey, &pin_privkey, &challenge)
+                .await
+                .unwrap();
+        assert_matches!(
+            account_server
+                .link_device(
+                    &certificate_signing_key,
+                    &MockGenerators,
+                    &repo,
+                    &FailingPinPolicy,
+                    &hsm,
+                    link_message,
+                )
+                .await
+                .expect_err("reusing the device link challenge should fail"),
+            InstructionError::Device(DeviceError::LinkChallengeMismatch)
+        );
+
+        let entries = repo.instruction_audit_log.into_inner().unwrap();
+        assert_matches!(
+            entries.as_slice(),
+            [
+                InstructionAuditLogEntry {
+                    instruction_type: InstructionType::LinkDevice,
+                    outcome: InstructionOutcome::Success,
+                    ..
+                },
+                InstructionAuditLogEntry {
+                    instruction_type: InstructionType::LinkDevice,
+                    outcome: InstructionOutcome::ValidationError,
+                    ..
+                },
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn test_check_pin() {
+    async fn test_link_device_invalid_platform_attestation() {
         let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
         let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
-        let instruction_result_signing_key = SoftwareEcdsaKey::new_random("instruction_result_signing_key".to_string());
 
         let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
         let hw_privkey = SigningKey::random(&mut OsRng);
         let pin_privkey = SigningKey::random(&mut OsRng);
 
-        let hw_pubkey = *hw_privkey.verifying_key();
-        let pin_pubkey = *pin_privkey.verifying_key();
-
         let cert = do_registration(
             &account_server,
             &hsm,
@@ -923,96 +2649,177 @@ mod tests {
         )
         .await;
 
-        let deps = WalletUserTestRepo {
-            hw: hw_pubkey,
-            pin: pin_pubkey,
+        let ca = MockAttestationCa::generate();
+        let account_server = with_android_attestation(account_server, &ca);
+
+        let challenge = random_bytes(32);
+        let new_repo = || WalletUserTestRepo {
+            hw: *hw_privkey.verifying_key(),
+            pin: *pin_privkey.verifying_key(),
             challenge: None,
-            instruction_sequence_number: 42,
+            instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Mutex::new(Some(InstructionChallenge {
+                bytes: challenge.clone(),
+                expiration_date_time: Local::now() + Duration::from_millis(15000),
+            })),
         };
 
+        // The key attestation is bound to another challenge than the device link challenge.
+        let new_hw_privkey = SigningKey::random(&mut OsRng);
+        let other_challenge = random_bytes(32);
+        let platform_attestation = PlatformAttestation::Android {
+            certificate_chain: android_certificate_chain(
+                &ca,
+                &new_hw_privkey,
+                &MockKeyDescription::new(&other_challenge),
+            ),
+        };
+        let link_message = DeviceLink::new_signed(
+            cert.clone(),
+            platform_attestation,
+            &new_hw_privkey,
+            &pin_privkey,
+            &challenge,
+        )
+        .await
+        .unwrap();
+
+        let repo = new_repo();
         assert_matches!(
             account_server
-                .instruction_challenge(
-                    InstructionChallengeRequestMessage {
-                        message: InstructionChallengeRequest::new_signed(9, "wallet", &hw_privkey)
-                            .await
-                            .unwrap(),
-                        certificate: cert.clone(),
-                    },
-                    &deps,
-                    &EpochGenerator,
+                .link_device(
+                    &certificate_signing_key,
+                    &MockGenerators,
+                    &repo,
+                    &FailingPinPolicy,
                     &hsm,
+                    link_message,
                 )
                 .await
-                .expect_err("should return instruction sequence number mismatch error"),
-            ChallengeError::SequenceNumberValidation
+                .expect_err("a key attestation bound to another challenge should fail"),
+            InstructionError::Device(DeviceError::LinkPlatformAttestation(PlatformAttestationError::Android(
+                _
+            )))
         );
 
-        let challenge = account_server
-            .instruction_challenge(
-                InstructionChallengeRequestMessage {
-                    message: InstructionChallengeRequest::new_signed(43, "wallet", &hw_privkey)
-                        .await
-                        .unwrap(),
-                    certificate: cert.clone(),
-                },
-                &deps,
-                &EpochGenerator,
-                &hsm,
-            )
-            .await
-            .unwrap();
+        // Apple app attestation is not configured.
+        let link_message = DeviceLink::new_signed(
+            cert,
+            PlatformAttestation::Apple {
+                attestation_object: b"attestation".to_vec(),
+            },
+            &new_hw_privkey,
+            &pin_privkey,
+            &challenge,
+        )
+        .await
+        .unwrap();
 
+        let repo = new_repo();
         assert_matches!(
             account_server
-                .handle_instruction(
-                    Instruction::new_signed(
-                        CheckPin,
-                        43,
-                        &hw_privkey,
-                        &pin_privkey,
-                        &challenge.clone(),
-                        cert.clone(),
-                    )
-                    .await
-                    .unwrap(),
-                    &instruction_result_signing_key,
+                .link_device(
+                    &certificate_signing_key,
                     &MockGenerators,
-                    &WalletUserTestRepo {
-                        hw: hw_pubkey,
-                        pin: pin_pubkey,
-                        challenge: Some(challenge.clone()),
-                        instruction_sequence_number: 43,
-                    },
+                    &repo,
                     &FailingPinPolicy,
                     &hsm,
+                    link_message,
                 )
                 .await
-                .expect_err("sequence number mismatch error should result in IncorrectPin error"),
-            InstructionError::IncorrectPin(IncorrectPinData {
-                attempts_left_in_round: _,
-                is_final_round: _
-            })
+                .expect_err("an attestation for a platform that is not configured should fail"),
+            InstructionError::Device(DeviceError::LinkPlatformAttestation(
+                PlatformAttestationError::NotConfigured(_)
+            ))
         );
 
-        account_server
-            .handle_instruction(
-                Instruction::new_signed(CheckPin, 44, &hw_privkey, &pin_privkey, &challenge, cert.clone())
-                    .await
-                    .unwrap(),
-                &instruction_result_signing_key,
-                &MockGenerators,
-                &WalletUserTestRepo {
-                    hw: hw_pubkey,
-                    pin: pin_pubkey,
-                    challenge: Some(challenge),
-                    instruction_sequence_number: 2,
-                },
-                &TimeoutPinPolicy,
-                &hsm,
-            )
-            .await
-            .expect("should return instruction result");
+        // The PIN was correct, so this is not registered as an unsuccessful PIN entry.
+        let entries = repo.instruction_audit_log.into_inner().unwrap();
+        assert_matches!(
+            entries.as_slice(),
+            [InstructionAuditLogEntry {
+                instruction_type: InstructionType::LinkDevice,
+                outcome: InstructionOutcome::ValidationError,
+                ..
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_link_device_wrong_pin() {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let ca = MockAttestationCa::generate();
+        let account_server = with_android_attestation(account_server, &ca);
+
+        let challenge = random_bytes(32);
+        let repo = WalletUserTestRepo {
+            hw: *hw_privkey.verifying_key(),
+            pin: *pin_privkey.verifying_key(),
+            challenge: None,
+            instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Mutex::new(Some(InstructionChallenge {
+                bytes: challenge.clone(),
+                expiration_date_time: Local::now() + Duration::from_millis(15000),
+            })),
+        };
+
+        let new_hw_privkey = SigningKey::random(&mut OsRng);
+        let platform_attestation = PlatformAttestation::Android {
+            certificate_chain: android_certificate_chain(&ca, &new_hw_privkey, &MockKeyDescription::new(&challenge)),
+        };
+        let link_message = DeviceLink::new_signed(
+            cert,
+            platform_attestation,
+            &new_hw_privkey,
+            &SigningKey::random(&mut OsRng),
+            &challenge,
+        )
+        .await
+        .unwrap();
+
+        assert_matches!(
+            account_server
+                .link_device(
+                    &certificate_signing_key,
+                    &MockGenerators,
+                    &repo,
+                    &FailingPinPolicy,
+                    &hsm,
+                    link_message,
+                )
+                .await
+                .expect_err("signing with the wrong PIN key should fail"),
+            InstructionError::IncorrectPin(_)
+        );
+
+        let entries = repo.instruction_audit_log.into_inner().unwrap();
+        assert_matches!(
+            entries.as_slice(),
+            [InstructionAuditLogEntry {
+                instruction_type: InstructionType::LinkDevice,
+                outcome: InstructionOutcome::IncorrectPin,
+                ..
+            }]
+        );
     }
 
     #[tokio::test]
@@ -1051,6 +2858,9 @@ mod tests {
                     pin: pin_pubkey,
                     challenge: None,
                     instruction_sequence_number: 0,
+                    instruction_audit_log: Default::default(),
+                    instruction_audit_log_unavailable: false,
+                    device_link_challenge: Default::default(),
                 },
                 &EpochGenerator,
                 &hsm,
@@ -1059,13 +2869,16 @@ mod tests {
             .unwrap();
 
         account_server
-            .verify_wallet_certificate(
+            .verify_wallet_certificate_allowing_blocked(
                 &cert,
                 &WalletUserTestRepo {
                     hw: hw_pubkey,
                     pin: pin_pubkey,
                     challenge: Some(challenge),
                     instruction_sequence_number: 0,
+                    instruction_audit_log: Default::default(),
+                    instruction_audit_log_unavailable: false,
+                    device_link_challenge: Default::default(),
                 },
                 &hsm,
             )
@@ -1094,13 +2907,16 @@ mod tests {
         .await;
 
         account_server
-            .verify_wallet_certificate(
+            .verify_wallet_certificate_allowing_blocked(
                 &cert,
                 &WalletUserTestRepo {
                     hw: *SigningKey::random(&mut OsRng).verifying_key(),
                     pin: pin_pubkey,
                     challenge: None,
                     instruction_sequence_number: 0,
+                    instruction_audit_log: Default::default(),
+                    instruction_audit_log_unavailable: false,
+                    device_link_challenge: Default::default(),
                 },
                 &hsm,
             )
@@ -1128,13 +2944,16 @@ mod tests {
         .await;
 
         account_server
-            .verify_wallet_certificate(
+            .verify_wallet_certificate_allowing_blocked(
                 &cert,
                 &WalletUserTestRepo {
                     hw: hw_pubkey,
                     pin: *SigningKey::random(&mut OsRng).verifying_key(),
                     challenge: None,
                     instruction_sequence_number: 0,
+                    instruction_audit_log: Default::default(),
+                    instruction_audit_log_unavailable: false,
+                    device_link_challenge: Default::default(),
                 },
                 &hsm,
             )
@@ -1142,6 +2961,53 @@ mod tests {
             .expect_err("Should not validate");
     }
 
+    #[rstest]
+    #[case(WalletUserQueryResult::Suspended, |error: &WalletCertificateError| matches!(error, WalletCertificateError::UserSuspended))]
+    #[case(WalletUserQueryResult::Revoked, |error: &WalletCertificateError| matches!(error, WalletCertificateError::UserRevoked))]
+    #[tokio::test]
+    async fn suspended_or_revoked_wallet_user_should_not_validate(
+        #[case] query_result: WalletUserQueryResult,
+        #[case] error_matches: impl Fn(&WalletCertificateError) -> bool,
+    ) {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+
+        let (account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_find_wallet_user_by_wallet_id()
+            .return_once(move |_, _, _| Ok(query_result));
+
+        let challenge_request = InstructionChallengeRequestMessage {
+            message: InstructionChallengeRequest::new_signed(1, "wallet", &hw_privkey)
+                .await
+                .unwrap(),
+            certificate: cert,
+        };
+
+        let error = account_server
+            .instruction_challenge(challenge_request, &wallet_user_repo, &EpochGenerator, &hsm)
+            .await
+            .expect_err("challenge for a suspended or revoked wallet user should fail");
+
+        assert_matches!(error, ChallengeError::WalletCertificate(error) if error_matches(&error));
+    }
+
     #[tokio::test]
     async fn valid_challenge_should_verify() {
         let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
@@ -1168,6 +3034,9 @@ mod tests {
             pin: pin_pubkey,
             challenge: None,
             instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Default::default(),
         };
 
         let challenge_request = InstructionChallengeRequestMessage {
@@ -1185,7 +3054,7 @@ mod tests {
         repo.challenge = Some(challenge.clone());
 
         let tx = repo.begin_transaction().await.unwrap();
-        let wallet_user = repo.find_wallet_user_by_wallet_id(&tx, "0").await.unwrap();
+        let wallet_user = repo.find_wallet_user_by_wallet_id(&tx, "0", &hw_pubkey).await.unwrap();
         tx.commit().await.unwrap();
 
         assert_matches!(
@@ -1230,6 +3099,9 @@ mod tests {
             pin: pin_pubkey,
             challenge: None,
             instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Default::default(),
         };
 
         let challenge_request = InstructionChallengeRequestMessage {
@@ -1247,7 +3119,7 @@ mod tests {
         repo.challenge = Some(random_bytes(32));
 
         let tx = repo.begin_transaction().await.unwrap();
-        let wallet_user = repo.find_wallet_user_by_wallet_id(&tx, "0").await.unwrap();
+        let wallet_user = repo.find_wallet_user_by_wallet_id(&tx, "0", &hw_pubkey).await.unwrap();
         tx.commit().await.unwrap();
 
         assert_matches!(
@@ -1302,6 +3174,9 @@ mod tests {
             pin: pin_pubkey,
             challenge: None,
             instruction_sequence_number: 0,
+            instruction_audit_log: Default::default(),
+            instruction_audit_log_unavailable: false,
+            device_link_challenge: Default::default(),
         };
 
         let challenge_request = InstructionChallengeRequestMessage {
@@ -1317,7 +3192,7 @@ mod tests {
             .unwrap();
 
         let tx = repo.begin_transaction().await.unwrap();
-        let wallet_user = repo.find_wallet_user_by_wallet_id(&tx, "0").await.unwrap();
+        let wallet_user = repo.find_wallet_user_by_wallet_id(&tx, "0", &hw_pubkey).await.unwrap();
         assert_matches!(wallet_user, WalletUserQueryResult::Found(_));
 
         if let WalletUserQueryResult::Found(mut user) = wallet_user {
@@ -1341,4 +3216,241 @@ mod tests {
             );
         }
     }
+
+    struct NowGenerator;
+
+    impl Generator<DateTime<Local>> for NowGenerator {
+        fn generate(&self) -> DateTime<Local> {
+            Local::now()
+        }
+    }
+
+    const PID_KEY_IDENTIFIER: &str = "pid_key";
+    const BSN: &str = "999991772";
+
+    async fn issue_pid(issuer_key: &KeyPair, bsn: &str, device_key: &VerifyingKey) -> Vec<u8> {
+        let unsigned_mdoc = UnsignedMdoc {
+            doc_type: "com.example.pid".to_string(),
+            copy_count: NonZeroU8::new(1).unwrap(),
+            valid_from: Tdate::now(),
+            valid_until: (Utc::now() + Days::new(365)).into(),
+            attributes: IndexMap::from([(
+                "com.example.pid".to_string(),
+                vec![Entry {
+                    name: "bsn".to_string(),
+                    value: ciborium::Value::Text(bsn.to_string()),
+                }],
+            )])
+            .try_into()
+            .unwrap(),
+        };
+
+        let issuer_signed = IssuerSigned::sign(unsigned_mdoc, device_key.try_into().unwrap(), issuer_key)
+            .await
+            .unwrap();
+
+        cbor_serialize(&issuer_signed).unwrap()
+    }
+
+    /// Perform PIN recovery for a wallet user, of which the PID is bound to a key stored with the wallet user only
+    /// when `pid_key_matches` is set, and the recovery PID is bound to the key that signs the new PIN public key only
+    /// when `recovery_pid_key_matches` is set. Returns the new PIN key and the claims of the new wallet certificate.
+    async fn do_pin_recovery(
+        is_blocked: bool,
+        blocked_at: DateTime<Local>,
+        recovery_bsn: &str,
+        pid_key_matches: bool,
+        recovery_pid_key_matches: bool,
+    ) -> Result<(VerifyingKey, WalletCertificateClaims), InstructionError> {
+        let certificate_signing_key = SoftwareEcdsaKey::new_random("certificate_signing_key".to_string());
+        let certificate_signing_pubkey = certificate_signing_key.verifying_key().await.unwrap();
+        let instruction_result_signing_key = SoftwareEcdsaKey::new_random("instruction_result_signing_key".to_string());
+        let instruction_result_signing_pubkey = instruction_result_signing_key.verifying_key().await.unwrap();
+
+        let (mut account_server, hsm) = mock::account_server_and_hsm((&certificate_signing_pubkey).into()).await;
+
+        let issuer_ca = KeyPair::generate_issuer_mock_ca().unwrap();
+        let issuer_key = issuer_ca.generate_issuer_mock(None).unwrap();
+        account_server.pin_recovery_verifier = PinRecoveryVerifier {
+            pid_trust_anchors: vec![issuer_ca.certificate().try_into().unwrap()],
+            pid_crls: vec![],
+        };
+
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let pin_privkey = SigningKey::random(&mut OsRng);
+        let new_pin_privkey = SigningKey::random(&mut OsRng);
+        let hw_pubkey = *hw_privkey.verifying_key();
+        let new_pin_pubkey = *new_pin_privkey.verifying_key();
+
+        let cert = do_registration(
+            &account_server,
+            &hsm,
+            &certificate_signing_key,
+            &hw_privkey,
+            &pin_privkey,
+        )
+        .await;
+
+        let (pid_pubkey, pid_wrapped_key) = hsm.generate_wrapped_key().await.unwrap();
+        let stored_wrapped_key = if pid_key_matches {
+            pid_wrapped_key
+        } else {
+            hsm.generate_wrapped_key().await.unwrap().1
+        };
+
+        let pid = issue_pid(&issuer_key, BSN, &pid_pubkey).await;
+        let recovery_pid_privkey = SigningKey::random(&mut OsRng);
+        let recovery_pid = issue_pid(&issuer_key, recovery_bsn, recovery_pid_privkey.verifying_key()).await;
+        let recovery_pid_signature: Signature = if recovery_pid_key_matches {
+            recovery_pid_privkey.sign(&RecoverPin::recovery_pid_message(&new_pin_pubkey))
+        } else {
+            SigningKey::random(&mut OsRng).sign(&RecoverPin::recovery_pid_message(&new_pin_pubkey))
+        };
+
+        let challenge = random_bytes(32);
+        let encrypted_pin_pubkey =
+            Encrypter::<VerifyingKey>::encrypt(&hsm, "encryption_key_1", *pin_privkey.verifying_key())
+                .await
+                .unwrap();
+
+        // The PIN public key and sequence number should only be updated when PIN recovery succeeds.
+        let expected_updates = usize::from(
+            is_blocked
+                && recovery_bsn == BSN
+                && blocked_at <= Local::now()
+                && pid_key_matches
+                && recovery_pid_key_matches,
+        );
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        let user_challenge = challenge.clone();
+        wallet_user_repo
+            .expect_find_wallet_user_by_wallet_id()
+            .returning(move |_, wallet_id, _| {
+                let user = Box::new(WalletUser {
+                    id: uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08"),
+                    wallet_id: wallet_id.to_string(),
+                    device_id: uuid!("e1b2f6a5-1e0d-4c36-9d7b-5f3a4c1e8a21"),
+                    hw_pubkey: DerVerifyingKey(hw_pubkey),
+                    encrypted_pin_pubkey: encrypted_pin_pubkey.clone(),
+                    pin_pubkey_encryption_key_version: 0,
+                    unsuccessful_pin_entries: 16,
+                    last_unsuccessful_pin_entry: Some(blocked_at),
+                    instruction_challenge: Some(InstructionChallenge {
+                        bytes: user_challenge.clone(),
+                        expiration_date_time: Local::now() + Duration::from_millis(15000),
+                    }),
+                    instruction_sequence_number: 5,
+                    device_link_challenge: None,
+                    platform_attestation: None,
+                });
+
+                if is_blocked {
+                    Ok(WalletUserQueryResult::Blocked(user))
+                } else {
+                    Ok(WalletUserQueryResult::Found(user))
+                }
+            });
+        wallet_user_repo
+            .expect_clear_instruction_challenge()
+            .returning(|_, _| Ok(()));
+        wallet_user_repo
+            .expect_find_keys_by_identifiers()
+            .withf(|_, _, identifiers| identifiers == [PID_KEY_IDENTIFIER])
+            .return_once(move |_, _, _| Ok(HashMap::from([(PID_KEY_IDENTIFIER.to_string(), stored_wrapped_key)])));
+        wallet_user_repo
+            .expect_update_wallet_user_pin_pubkey()
+            .withf(move |_, _, encrypted, version| {
+                encrypted.data == new_pin_pubkey.to_sec1_bytes().to_vec() && *version == 0
+            })
+            .times(expected_updates)
+            .returning(|_, _, _, _| Ok(()));
+        wallet_user_repo
+            .expect_update_instruction_sequence_number()
+            .withf(|_, _, sequence_number| *sequence_number == 6)
+            .times(expected_updates)
+            .returning(|_, _, _| Ok(()));
+
+        let instruction = Instruction::new_signed(
+            RecoverPin {
+                pin_pubkey: new_pin_pubkey.into(),
+                pid_key_identifier: PID_KEY_IDENTIFIER.to_string(),
+                pid_issuer_signed: pid,
+                recovery_pid_issuer_signed: recovery_pid,
+                recovery_pid_signature: recovery_pid_signature.into(),
+            },
+            6,
+            &hw_privkey,
+            &new_pin_privkey,
+            &challenge,
+            cert,
+        )
+        .await
+        .unwrap();
+
+        let result = account_server
+            .recover_pin(
+                instruction,
+                &certificate_signing_key,
+                &instruction_result_signing_key,
+                &NowGenerator,
+                &wallet_user_repo,
+                &hsm,
+            )
+            .await?;
+
+        let wallet_certificate = result
+            .parse_and_verify_with_sub(&(&instruction_result_signing_pubkey).into())
+            .expect("instruction result should verify")
+            .result;
+        let claims = wallet_certificate
+            .parse_and_verify_with_sub(&(&certificate_signing_pubkey).into())
+            .expect("new wallet certificate should verify");
+
+        verify_pin_pubkey(new_pin_pubkey, claims.pin_pubkey_hash.clone(), "signing_key_2", &hsm)
+            .await
+            .expect("new wallet certificate should contain the new pin public key");
+
+        Ok((new_pin_pubkey, claims))
+    }
+
+    #[tokio::test]
+    async fn test_recover_pin() {
+        let (_, claims) = do_pin_recovery(true, Local::now() - TimeDelta::hours(1), BSN, true, true)
+            .await
+            .expect("PIN recovery should succeed");
+
+        assert_eq!(claims.version, WALLET_CERTIFICATE_VERSION);
+    }
+
+    #[rstest]
+    #[case::not_blocked(false, TimeDelta::hours(-1), BSN, true, true, |error: &PinRecoveryError| matches!(error, PinRecoveryError::NotBlocked))]
+    #[case::bsn_mismatch(true, TimeDelta::hours(-1), "999991773", true, true, |error: &PinRecoveryError| matches!(error, PinRecoveryError::BsnMismatch))]
+    #[case::recovery_pid_not_fresh(true, TimeDelta::hours(1), BSN, true, true, |error: &PinRecoveryError| matches!(error, PinRecoveryError::RecoveryPidNotFresh))]
+    #[case::pid_key_mismatch(true, TimeDelta::hours(-1), BSN, false, true, |error: &PinRecoveryError| matches!(error, PinRecoveryError::PidKeyMismatch))]
+    #[case::recovery_pid_key_mismatch(true, TimeDelta::hours(-1), BSN, true, false, |error: &PinRecoveryError| matches!(error, PinRecoveryError::RecoveryPidSignature(_)))]
+    #[tokio::test]
+    async fn test_recover_pin_error(
+        #[case] is_blocked: bool,
+        #[case] blocked_since: TimeDelta,
+        #[case] recovery_bsn: &str,
+        #[case] pid_key_matches: bool,
+        #[case] recovery_pid_key_matches: bool,
+        #[case] expected_error: impl Fn(&PinRecoveryError) -> bool,
+    ) {
+        let error = do_pin_recovery(
+            is_blocked,
+            Local::now() + blocked_since,
+            recovery_bsn,
+            pid_key_matches,
+            recovery_pid_key_matches,
+        )
+        .await
+        .expect_err("PIN recovery should fail");
+
+        assert_matches!(error, InstructionError::PinRecovery(error) if expected_error(&error));
+    }
 }
@@ -0,0 +1,535 @@
+use chrono::{DateTime, Local};
+use tracing::info;
+use uuid::Uuid;
+
+use hsm::service::HsmError;
+use wallet_common::generator::Generator;
+use wallet_provider_domain::{
+    model::{
+        admin_audit_log::{AdminAction, AdminActor, AdminAuditLogEntry},
+        hsm::Hsm,
+        instruction_audit_log::InstructionAuditLog,
+        wallet_user::{WalletUserDetails, WalletUserState},
+    },
+    repository::{
+        AdminAuditLogRepository, Committable, InstructionAuditLogRepository, PersistenceError, TransactionStarter,
+        WalletUserRepository,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AdminError {
+    #[error("no wallet user found with wallet_id: {0}")]
+    WalletUserNotFound(String),
+    #[error("wallet user is revoked, its state can no longer be changed: {0}")]
+    WalletUserRevoked(String),
+    #[error("persistence error: {0}")]
+    Persistence(#[from] PersistenceError),
+    #[error("hsm error: {0}")]
+    Hsm(#[from] HsmError),
+}
+
+/// Look up the administrative details of a wallet user. Note that the lookup itself is audited as well, even when
+/// no wallet user is found.
+pub async fn wallet_user_details<T, R, G>(
+    repositories: &R,
+    generators: &G,
+    actor: &AdminActor,
+    wallet_id: &str,
+) -> Result<WalletUserDetails, AdminError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T>
+        + WalletUserRepository<TransactionType = T>
+        + AdminAuditLogRepository<TransactionType = T>,
+    G: Generator<Uuid> + Generator<DateTime<Local>>,
+{
+    let tx = repositories.begin_transaction().await?;
+
+    audit(
+        repositories,
+        &tx,
+        generators,
+        actor,
+        wallet_id,
+        AdminAction::LookupWalletUser,
+    )
+    .await?;
+    let details = repositories
+        .find_wallet_user_details_by_wallet_id(&tx, wallet_id)
+        .await?;
+
+    tx.commit().await?;
+
+    details.ok_or_else(|| AdminError::WalletUserNotFound(wallet_id.to_string()))
+}
+
+/// Look up the instruction audit log of a wallet user and verify its hash chain using the HSM key with the identifier
+/// `key_identifier`, e.g. when investigating fraud involving a stolen PIN. Like the lookup of the wallet user itself,
+/// this lookup is audited.
+pub async fn instruction_audit_log<T, R, G, H>(
+    repositories: &R,
+    generators: &G,
+    actor: &AdminActor,
+    hsm: &H,
+    key_identifier: &str,
+    wallet_id: &str,
+) -> Result<InstructionAuditLog, AdminError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T>
+        + WalletUserRepository<TransactionType = T>
+        + AdminAuditLogRepository<TransactionType = T>
+        + InstructionAuditLogRepository<TransactionType = T>,
+    G: Generator<Uuid> + Generator<DateTime<Local>>,
+    H: Hsm<Error = HsmError>,
+{
+    let tx = repositories.begin_transaction().await?;
+
+    audit(
+        repositories,
+        &tx,
+        generators,
+        actor,
+        wallet_id,
+        AdminAction::LookupInstructionAuditLog,
+    )
+    .await?;
+    let details = repositories
+        .find_wallet_user_details_by_wallet_id(&tx, wallet_id)
+        .await?;
+    let entries = match &details {
+        Some(details) => repositories.find_instruction_audit_log_entries(&tx, details.id).await?,
+        None => Vec::new(),
+    };
+
+    tx.commit().await?;
+
+    details.ok_or_else(|| AdminError::WalletUserNotFound(wallet_id.to_string()))?;
+
+    let log = InstructionAuditLog::verify(entries, hsm, key_identifier).await?;
+
+    Ok(log)
+}
+
+/// Unblock a wallet user by resetting its unsuccessful PIN entries, e.g. after the user has been identified by the
+/// helpdesk.
+pub async fn unblock_wallet_user<T, R, G>(
+    repositories: &R,
+    generators: &G,
+    actor: &AdminActor,
+    wallet_id: &str,
+) -> Result<(), AdminError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T>
+        + WalletUserRepository<TransactionType = T>
+        + AdminAuditLogRepository<TransactionType = T>,
+    G: Generator<Uuid> + Generator<DateTime<Local>>,
+{
+    let tx = repositories.begin_transaction().await?;
+
+    find_existing_wallet_user(repositories, &tx, wallet_id).await?;
+
+    info!("Unblocking wallet user {} on behalf of {}", wallet_id, actor.operator);
+
+    repositories.reset_unsuccessful_pin_entries(&tx, wallet_id).await?;
+    audit(
+        repositories,
+        &tx,
+        generators,
+        actor,
+        wallet_id,
+        AdminAction::UnblockWalletUser,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Change the lifecycle state of a wallet user, e.g. to suspend the wallet after the user reported their phone as
+/// stolen. Once a wallet user is revoked, its state can no longer be changed.
+pub async fn update_wallet_user_state<T, R, G>(
+    repositories: &R,
+    generators: &G,
+    actor: &AdminActor,
+    wallet_id: &str,
+    state: WalletUserState,
+) -> Result<(), AdminError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T>
+        + WalletUserRepository<TransactionType = T>
+        + AdminAuditLogRepository<TransactionType = T>,
+    G: Generator<Uuid> + Generator<DateTime<Local>>,
+{
+    let tx = repositories.begin_transaction().await?;
+
+    let details = find_existing_wallet_user(repositories, &tx, wallet_id).await?;
+    if details.state == WalletUserState::Revoked {
+        return Err(AdminError::WalletUserRevoked(wallet_id.to_string()));
+    }
+
+    info!(
+        "Updating state of wallet user {} to {:?} on behalf of {}",
+        wallet_id, state, actor.operator
+    );
+
+    repositories.update_wallet_user_state(&tx, wallet_id, state).await?;
+    audit(
+        repositories,
+        &tx,
+        generators,
+        actor,
+        wallet_id,
+        AdminAction::UpdateWalletUserState(state),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn audit_log<T, R>(repositories: &R, wallet_id: Option<&str>) -> Result<Vec<AdminAuditLogEntry>, AdminError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T> + AdminAuditLogRepository<TransactionType = T>,
+{
+    let tx = repositories.begin_transaction().await?;
+    let entries = repositories.find_admin_audit_log_entries(&tx, wallet_id).await?;
+    tx.commit().await?;
+
+    Ok(entries)
+}
+
+async fn find_existing_wallet_user<T, R>(
+    repositories: &R,
+    tx: &T,
+    wallet_id: &str,
+) -> Result<WalletUserDetails, AdminError>
+where
+    R: WalletUserRepository<TransactionType = T>,
+    T: Committable,
+{
+    repositories
+        .find_wallet_user_details_by_wallet_id(tx, wallet_id)
+        .await?
+        .ok_or_else(|| AdminError::WalletUserNotFound(wallet_id.to_string()))
+}
+
+async fn audit<T, R, G>(
+    repositories: &R,
+    tx: &T,
+    generators: &G,
+    actor: &AdminActor,
+    wallet_id: &str,
+    action: AdminAction,
+) -> Result<(), PersistenceError>
+where
+    T: Committable,
+    R: AdminAuditLogRepository<TransactionType = T>,
+    G: Generator<Uuid> + Generator<DateTime<Local>>,
+{
+    repositories
+        .create_admin_audit_log_entry(
+            tx,
+            AdminAuditLogEntry {
+                id: Generator::<Uuid>::generate(generators),
+                date_time: Generator::<DateTime<Local>>::generate(generators),
+                wallet_id: wallet_id.to_string(),
+                action,
+                operator: actor.operator.clone(),
+                reason: actor.reason.clone(),
+            },
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use uuid::uuid;
+
+    use wallet_provider_domain::{
+        generator::mock::MockGenerators,
+        model::{
+            hsm::mock::MockPkcs11Client,
+            instruction_audit_log::{
+                InstructionAuditDetails, InstructionAuditLogEntry, InstructionOutcome, InstructionType,
+            },
+        },
+        repository::MockTransaction,
+        EpochGenerator,
+    };
+    use wallet_provider_persistence::repositories::mock::MockTransactionalWalletUserRepository;
+
+    use super::*;
+
+    fn wallet_user_details(state: WalletUserState) -> WalletUserDetails {
+        WalletUserDetails {
+            id: uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08"),
+            wallet_id: "wallet_123".to_string(),
+            state,
+            is_blocked: true,
+            unsuccessful_pin_entries: 16,
+            last_unsuccessful_pin_entry: None,
+            platform_attestation: None,
+            key_count: 2,
+        }
+    }
+
+    fn wallet_user_repo(details: Option<WalletUserDetails>) -> MockTransactionalWalletUserRepository {
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_find_wallet_user_details_by_wallet_id()
+            .return_once(|_, _| Ok(details));
+
+        wallet_user_repo
+    }
+
+    fn actor() -> AdminActor {
+        AdminActor {
+            operator: "helpdesk".to_string(),
+            reason: Some("reported stolen".to_string()),
+        }
+    }
+
+    fn expect_audit(wallet_user_repo: &mut MockTransactionalWalletUserRepository, action: AdminAction) {
+        wallet_user_repo
+            .expect_create_admin_audit_log_entry()
+            .withf(move |_, entry| {
+                entry.id == uuid!("c9723aef-022b-4ab7-9cc3-ff4227ec1cc9")
+                    && entry.wallet_id == "wallet_123"
+                    && entry.action == action
+                    && entry.operator == "helpdesk"
+                    && entry.reason.as_deref() == Some("reported stolen")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_wallet_user_details() {
+        let mut wallet_user_repo = wallet_user_repo(Some(wallet_user_details(WalletUserState::Active)));
+        expect_audit(&mut wallet_user_repo, AdminAction::LookupWalletUser);
+
+        let details = super::wallet_user_details(&wallet_user_repo, &MockGenerators, &actor(), "wallet_123")
+            .await
+            .expect("looking up the wallet user should succeed");
+
+        assert_eq!(details.key_count, 2);
+        assert_eq!(details.unsuccessful_pin_entries, 16);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_user_details_not_found() {
+        let mut wallet_user_repo = wallet_user_repo(None);
+        expect_audit(&mut wallet_user_repo, AdminAction::LookupWalletUser);
+
+        let error = super::wallet_user_details(&wallet_user_repo, &MockGenerators, &actor(), "wallet_123")
+            .await
+            .expect_err("looking up an unknown wallet user should fail");
+
+        assert_matches!(error, AdminError::WalletUserNotFound(wallet_id) if wallet_id == "wallet_123");
+    }
+
+    const INSTRUCTION_AUDIT_LOG_KEY_IDENTIFIER: &str = "instruction_audit_log_key";
+
+    async fn instruction_audit_log_hsm() -> MockPkcs11Client<HsmError> {
+        let hsm = MockPkcs11Client::default();
+        hsm.generate_generic_secret_key(INSTRUCTION_AUDIT_LOG_KEY_IDENTIFIER)
+            .await
+            .unwrap();
+
+        hsm
+    }
+
+    async fn instruction_audit_log_entries(hsm: &MockPkcs11Client<HsmError>) -> Vec<InstructionAuditLogEntry> {
+        let wallet_user_id = uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08");
+
+        let first = InstructionAuditLogEntry::new_chained(
+            None,
+            Uuid::new_v4(),
+            wallet_user_id,
+            EpochGenerator.generate(),
+            InstructionType::CheckPin,
+            InstructionOutcome::IncorrectPin,
+            InstructionAuditDetails::default(),
+            hsm,
+            INSTRUCTION_AUDIT_LOG_KEY_IDENTIFIER,
+        )
+        .await
+        .unwrap();
+        let second = InstructionAuditLogEntry::new_chained(
+            Some(&first),
+            Uuid::new_v4(),
+            wallet_user_id,
+            EpochGenerator.generate(),
+            InstructionType::Sign,
+            InstructionOutcome::Success,
+            InstructionAuditDetails {
+                operation_count: 1,
+                payload_hash: Some(vec![0; 32]),
+            },
+            hsm,
+            INSTRUCTION_AUDIT_LOG_KEY_IDENTIFIER,
+        )
+        .await
+        .unwrap();
+
+        vec![first, second]
+    }
+
+    async fn lookup_instruction_audit_log(
+        entries: Vec<InstructionAuditLogEntry>,
+        hsm: &MockPkcs11Client<HsmError>,
+    ) -> InstructionAuditLog {
+        let wallet_user_repo = instruction_audit_log_repo(entries);
+
+        instruction_audit_log(
+            &wallet_user_repo,
+            &MockGenerators,
+            &actor(),
+            hsm,
+            INSTRUCTION_AUDIT_LOG_KEY_IDENTIFIER,
+            "wallet_123",
+        )
+        .await
+        .expect("looking up the instruction audit log should succeed")
+    }
+
+    fn instruction_audit_log_repo(entries: Vec<InstructionAuditLogEntry>) -> MockTransactionalWalletUserRepository {
+        let mut wallet_user_repo = wallet_user_repo(Some(wallet_user_details(WalletUserState::Active)));
+        wallet_user_repo
+            .expect_find_instruction_audit_log_entries()
+            .withf(|_, wallet_user_id| *wallet_user_id == uuid!("d944f36e-ffbd-402f-b6f3-418cf4c49e08"))
+            .return_once(|_, _| Ok(entries));
+        expect_audit(&mut wallet_user_repo, AdminAction::LookupInstructionAuditLog);
+
+        wallet_user_repo
+    }
+
+    #[tokio::test]
+    async fn test_instruction_audit_log() {
+        let hsm = instruction_audit_log_hsm().await;
+        let log = lookup_instruction_audit_log(instruction_audit_log_entries(&hsm).await, &hsm).await;
+
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.chain_error, None);
+    }
+
+    #[tokio::test]
+    async fn test_instruction_audit_log_tampered() {
+        let hsm = instruction_audit_log_hsm().await;
+        let mut entries = instruction_audit_log_entries(&hsm).await;
+        entries[0].outcome = InstructionOutcome::Success;
+
+        let log = lookup_instruction_audit_log(entries, &hsm).await;
+
+        assert_eq!(
+            log.chain_error.as_deref(),
+            Some("hash of instruction audit log entry 0 does not match its contents")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_instruction_audit_log_forged() {
+        // A chain that is recomputed without access to the HSM key should not be accepted.
+        let hsm = instruction_audit_log_hsm().await;
+        let forged_entries = instruction_audit_log_entries(&instruction_audit_log_hsm().await).await;
+
+        let log = lookup_instruction_audit_log(forged_entries, &hsm).await;
+
+        assert_eq!(
+            log.chain_error.as_deref(),
+            Some("hash of instruction audit log entry 0 does not match its contents")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_instruction_audit_log_removed_entry() {
+        let hsm = instruction_audit_log_hsm().await;
+        let mut entries = instruction_audit_log_entries(&hsm).await;
+        entries.remove(0);
+
+        let log = lookup_instruction_audit_log(entries, &hsm).await;
+
+        assert_eq!(
+            log.chain_error.as_deref(),
+            Some("instruction audit log entry 0 is missing")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unblock_wallet_user() {
+        let mut wallet_user_repo = wallet_user_repo(Some(wallet_user_details(WalletUserState::Active)));
+        wallet_user_repo
+            .expect_reset_unsuccessful_pin_entries()
+            .withf(|_, wallet_id| wallet_id == "wallet_123")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        expect_audit(&mut wallet_user_repo, AdminAction::UnblockWalletUser);
+
+        unblock_wallet_user(&wallet_user_repo, &MockGenerators, &actor(), "wallet_123")
+            .await
+            .expect("unblocking the wallet user should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_unblock_wallet_user_not_found() {
+        let wallet_user_repo = wallet_user_repo(None);
+
+        let error = unblock_wallet_user(&wallet_user_repo, &MockGenerators, &actor(), "wallet_123")
+            .await
+            .expect_err("unblocking an unknown wallet user should fail");
+
+        assert_matches!(error, AdminError::WalletUserNotFound(_));
+    }
+
+    #[tokio::test]
+    async fn test_update_wallet_user_state() {
+        let mut wallet_user_repo = wallet_user_repo(Some(wallet_user_details(WalletUserState::Active)));
+        wallet_user_repo
+            .expect_update_wallet_user_state()
+            .withf(|_, wallet_id, state| wallet_id == "wallet_123" && *state == WalletUserState::Suspended)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        expect_audit(
+            &mut wallet_user_repo,
+            AdminAction::UpdateWalletUserState(WalletUserState::Suspended),
+        );
+
+        update_wallet_user_state(
+            &wallet_user_repo,
+            &MockGenerators,
+            &actor(),
+            "wallet_123",
+            WalletUserState::Suspended,
+        )
+        .await
+        .expect("suspending the wallet user should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_update_wallet_user_state_revoked() {
+        let wallet_user_repo = wallet_user_repo(Some(wallet_user_details(WalletUserState::Revoked)));
+
+        let error = update_wallet_user_state(
+            &wallet_user_repo,
+            &MockGenerators,
+            &actor(),
+            "wallet_123",
+            WalletUserState::Active,
+        )
+        .await
+        .expect_err("reactivating a revoked wallet user should fail");
+
+        assert_matches!(error, AdminError::WalletUserRevoked(_));
+    }
+}
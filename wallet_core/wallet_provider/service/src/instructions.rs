@@ -1,52 +1,85 @@
 use std::sync::Arc;
 
-use futures::future;
+use chrono::{DateTime, Duration, Local};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+use hsm::service::HsmError;
 use wallet_common::{
     account::{
-        messages::instructions::{CheckPin, GenerateKey, GenerateKeyResult, Sign, SignResult},
+        messages::instructions::{
+            AuditedInstruction, CheckPin, DiffieHellman, DiffieHellmanResult, GenerateKey, GenerateKeyResult,
+            GetDevices, GetDevicesResult, GetInstructionAuditLog, GetInstructionAuditLogResult, LinkedDevice,
+            RemoveDevice, Sign, SignResult, StartDeviceLink, StartDeviceLinkResult,
+        },
         serialization::{DerSignature, DerVerifyingKey},
     },
     generator::Generator,
+    utils::random_bytes,
 };
 use wallet_provider_domain::{
     model::{
         hsm::WalletUserHsm,
-        wallet_user::{WalletUser, WalletUserKey, WalletUserKeys},
+        instruction_audit_log::{InstructionAuditDetails, InstructionType},
+        wallet_user::{InstructionChallenge, WalletUser, WalletUserKey, WalletUserKeys},
+    },
+    repository::{
+        Committable, InstructionAuditLogRepository, PersistenceError, TransactionStarter, WalletUserDeviceRepository,
+        WalletUserRepository,
     },
-    repository::{Committable, TransactionStarter, WalletUserRepository},
 };
 
-use crate::{account_server::InstructionError, hsm::HsmError};
+use crate::account_server::{DeviceError, InstructionError};
+
+/// How long a new device has to scan the QR code and link itself, after a linked device started linking.
+const DEVICE_LINK_CHALLENGE_TIMEOUT: Duration = Duration::minutes(5);
 
 pub trait HandleInstruction {
     type Result: Serialize;
 
-    async fn handle<T>(
+    const INSTRUCTION_TYPE: InstructionType;
+
+    /// The details of this instruction that are recorded in the instruction audit log when it is accepted.
+    fn audit_details(&self) -> InstructionAuditDetails {
+        InstructionAuditDetails::default()
+    }
+
+    async fn handle<T, R, G>(
         self,
         wallet_user: &WalletUser,
-        uuid_generator: &impl Generator<Uuid>,
-        wallet_user_repository: &(impl TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>),
+        generators: &G,
+        wallet_user_repository: &R,
         wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
     ) -> Result<Self::Result, InstructionError>
     where
-        T: Committable;
+        T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>;
 }
 
 impl HandleInstruction for CheckPin {
     type Result = ();
 
-    async fn handle<T>(
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::CheckPin;
+
+    async fn handle<T, R, G>(
         self,
         _wallet_user: &WalletUser,
-        _uuid_generator: &impl Generator<Uuid>,
-        _wallet_user_repository: &(impl TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>),
+        _generators: &G,
+        _wallet_user_repository: &R,
         _wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
     ) -> Result<(), InstructionError>
     where
         T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
     {
         Ok(())
     }
@@ -55,15 +88,29 @@ impl HandleInstruction for CheckPin {
 impl HandleInstruction for GenerateKey {
     type Result = GenerateKeyResult;
 
-    async fn handle<T>(
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::GenerateKey;
+
+    fn audit_details(&self) -> InstructionAuditDetails {
+        InstructionAuditDetails {
+            operation_count: self.identifiers.len() as u32,
+            payload_hash: None,
+        }
+    }
+
+    async fn handle<T, R, G>(
         self,
         wallet_user: &WalletUser,
-        uuid_generator: &impl Generator<Uuid>,
-        wallet_user_repository: &(impl TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>),
+        generators: &G,
+        wallet_user_repository: &R,
         wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
     ) -> Result<GenerateKeyResult, InstructionError>
     where
         T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
     {
         let identifiers: Vec<&str> = self.identifiers.iter().map(|i| i.as_str()).collect();
         let keys = wallet_user_hsm.generate_wrapped_keys(&identifiers).await?;
@@ -74,7 +121,7 @@ impl HandleInstruction for GenerateKey {
                 (
                     (identifier.clone(), DerVerifyingKey::from(public_key)),
                     WalletUserKey {
-                        wallet_user_key_id: uuid_generator.generate(),
+                        wallet_user_key_id: Generator::<Uuid>::generate(generators),
                         key_identifier: identifier,
                         key: wrapped_key,
                     },
@@ -101,15 +148,39 @@ impl HandleInstruction for GenerateKey {
 impl HandleInstruction for Sign {
     type Result = SignResult;
 
-    async fn handle<T>(
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::Sign;
+
+    fn audit_details(&self) -> InstructionAuditDetails {
+        let (operation_count, hasher) =
+            self.messages_with_identifiers
+                .iter()
+                .fold((0, Sha256::new()), |(count, hasher), (data, identifiers)| {
+                    (
+                        count + identifiers.len() as u32,
+                        hasher.chain_update(Sha256::digest(data)),
+                    )
+                });
+
+        InstructionAuditDetails {
+            operation_count,
+            payload_hash: Some(hasher.finalize().to_vec()),
+        }
+    }
+
+    async fn handle<T, R, G>(
         self,
         wallet_user: &WalletUser,
-        _uuid_generator: &impl Generator<Uuid>,
-        wallet_user_repository: &(impl TransactionStarter<TransactionType = T> + WalletUserRepository<TransactionType = T>),
+        _generators: &G,
+        wallet_user_repository: &R,
         wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
     ) -> Result<SignResult, InstructionError>
     where
         T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
     {
         let (data, identifiers): (Vec<_>, Vec<_>) = self.messages_with_identifiers.into_iter().unzip();
 
@@ -123,42 +194,281 @@ impl HandleInstruction for Sign {
             .await?;
         tx.commit().await?;
 
-        let signatures = future::try_join_all(identifiers.iter().zip(data).map(|(identifiers, data)| async {
-            let data = Arc::new(data);
-            future::try_join_all(identifiers.iter().map(|identifier| async {
-                let wrapped_key = found_keys.get(identifier).cloned().unwrap();
-                wallet_user_hsm
-                    .sign_wrapped(wrapped_key, Arc::clone(&data))
-                    .await
-                    .map(DerSignature::from)
-            }))
-            .await
-        }))
-        .await?;
+        // Sign all messages in a single batch and regroup the signatures per message afterwards.
+        let found_keys = &found_keys;
+        let wrapped_keys = identifiers
+            .iter()
+            .zip(data)
+            .flat_map(|(identifiers, data)| {
+                let data = Arc::new(data);
+                identifiers
+                    .iter()
+                    .map(move |identifier| (found_keys.get(identifier).cloned().unwrap(), Arc::clone(&data)))
+            })
+            .collect();
+
+        let mut signatures = wallet_user_hsm
+            .sign_wrapped_multiple(wrapped_keys)
+            .await?
+            .into_iter()
+            .map(DerSignature::from);
+
+        let signatures = identifiers
+            .iter()
+            .map(|identifiers| signatures.by_ref().take(identifiers.len()).collect())
+            .collect();
 
         Ok(SignResult { signatures })
     }
 }
 
+impl HandleInstruction for DiffieHellman {
+    type Result = DiffieHellmanResult;
+
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::DiffieHellman;
+
+    fn audit_details(&self) -> InstructionAuditDetails {
+        InstructionAuditDetails {
+            operation_count: self.identifiers.len() as u32,
+            payload_hash: None,
+        }
+    }
+
+    async fn handle<T, R, G>(
+        self,
+        wallet_user: &WalletUser,
+        _generators: &G,
+        wallet_user_repository: &R,
+        wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
+    ) -> Result<DiffieHellmanResult, InstructionError>
+    where
+        T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+    {
+        let tx = wallet_user_repository.begin_transaction().await?;
+        let found_keys = wallet_user_repository
+            .find_keys_by_identifiers(&tx, wallet_user.id, &self.identifiers)
+            .await?;
+        tx.commit().await?;
+
+        let wrapped_keys = self
+            .identifiers
+            .iter()
+            .map(|identifier| {
+                found_keys
+                    .get(identifier)
+                    .cloned()
+                    .ok_or_else(|| InstructionError::KeyNotFound(identifier.clone()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let shared_secrets = wallet_user_hsm
+            .diffie_hellman_wrapped_multiple(wrapped_keys, self.public_key.0)
+            .await?;
+
+        Ok(DiffieHellmanResult { shared_secrets })
+    }
+}
+
+impl HandleInstruction for GetInstructionAuditLog {
+    type Result = GetInstructionAuditLogResult;
+
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::GetInstructionAuditLog;
+
+    async fn handle<T, R, G>(
+        self,
+        wallet_user: &WalletUser,
+        _generators: &G,
+        wallet_user_repository: &R,
+        _wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
+    ) -> Result<GetInstructionAuditLogResult, InstructionError>
+    where
+        T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+    {
+        let tx = wallet_user_repository.begin_transaction().await?;
+        let entries = wallet_user_repository
+            .find_instruction_audit_log_entries(&tx, wallet_user.id)
+            .await?;
+        tx.commit().await?;
+
+        let entries = entries
+            .into_iter()
+            .map(|entry| AuditedInstruction {
+                date_time: entry.date_time.to_utc(),
+                instruction_type: entry.instruction_type,
+                outcome: entry.outcome,
+                operation_count: entry.operation_count,
+                payload_hash: entry.payload_hash,
+            })
+            .collect();
+
+        Ok(GetInstructionAuditLogResult { entries })
+    }
+}
+
+impl HandleInstruction for StartDeviceLink {
+    type Result = StartDeviceLinkResult;
+
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::StartDeviceLink;
+
+    async fn handle<T, R, G>(
+        self,
+        wallet_user: &WalletUser,
+        generators: &G,
+        wallet_user_repository: &R,
+        _wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
+    ) -> Result<StartDeviceLinkResult, InstructionError>
+    where
+        T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+    {
+        // Any previous challenge of this device is replaced, so only the most recent QR code can be used.
+        let challenge = InstructionChallenge {
+            bytes: random_bytes(32),
+            expiration_date_time: Generator::<DateTime<Local>>::generate(generators) + DEVICE_LINK_CHALLENGE_TIMEOUT,
+        };
+
+        let tx = wallet_user_repository.begin_transaction().await?;
+        wallet_user_repository
+            .update_device_link_challenge(&tx, wallet_user.device_id, Some(challenge.clone()))
+            .await?;
+        tx.commit().await?;
+
+        Ok(StartDeviceLinkResult {
+            challenge: challenge.bytes,
+        })
+    }
+}
+
+impl HandleInstruction for GetDevices {
+    type Result = GetDevicesResult;
+
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::GetDevices;
+
+    async fn handle<T, R, G>(
+        self,
+        wallet_user: &WalletUser,
+        _generators: &G,
+        wallet_user_repository: &R,
+        _wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
+    ) -> Result<GetDevicesResult, InstructionError>
+    where
+        T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+    {
+        let tx = wallet_user_repository.begin_transaction().await?;
+        let devices = wallet_user_repository
+            .find_wallet_user_devices(&tx, wallet_user.id)
+            .await?;
+        tx.commit().await?;
+
+        let devices = devices
+            .into_iter()
+            .map(|device| LinkedDevice {
+                device_id: device.id.to_string(),
+                hw_pubkey: device.hw_pubkey,
+                linked_date_time: device.linked_date_time.to_utc(),
+                is_current: device.id == wallet_user.device_id,
+            })
+            .collect();
+
+        Ok(GetDevicesResult { devices })
+    }
+}
+
+impl HandleInstruction for RemoveDevice {
+    type Result = ();
+
+    const INSTRUCTION_TYPE: InstructionType = InstructionType::RemoveDevice;
+
+    async fn handle<T, R, G>(
+        self,
+        wallet_user: &WalletUser,
+        _generators: &G,
+        wallet_user_repository: &R,
+        _wallet_user_hsm: &impl WalletUserHsm<Error = HsmError>,
+    ) -> Result<(), InstructionError>
+    where
+        T: Committable,
+        R: TransactionStarter<TransactionType = T>
+            + WalletUserRepository<TransactionType = T>
+            + WalletUserDeviceRepository<TransactionType = T>
+            + InstructionAuditLogRepository<TransactionType = T>,
+        G: Generator<Uuid> + Generator<DateTime<Local>>,
+    {
+        let device_id = Uuid::parse_str(&self.device_id).map_err(|_| DeviceError::UnknownDevice(self.device_id))?;
+
+        if device_id == wallet_user.device_id {
+            return Err(DeviceError::RemoveCurrentDevice.into());
+        }
+
+        let tx = wallet_user_repository.begin_transaction().await?;
+        wallet_user_repository
+            .delete_wallet_user_device(&tx, wallet_user.id, device_id)
+            .await
+            .map_err(|error| match error {
+                PersistenceError::NotFound(_) => DeviceError::UnknownDevice(device_id.to_string()).into(),
+                error => InstructionError::from(error),
+            })?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use p256::ecdsa::{signature::Verifier, SigningKey};
+    use assert_matches::assert_matches;
+    use chrono::Local;
+    use p256::{
+        ecdh,
+        ecdsa::{signature::Verifier, SigningKey},
+        SecretKey,
+    };
     use rand::rngs::OsRng;
+    use uuid::uuid;
 
     use wallet_common::{
-        account::messages::instructions::{CheckPin, GenerateKey, Sign},
+        account::{
+            messages::instructions::{CheckPin, DiffieHellman, GenerateKey, GetDevices, RemoveDevice, Sign},
+            serialization::DerVerifyingKey,
+        },
         utils::random_bytes,
     };
     use wallet_provider_domain::{
-        model::{hsm::mock::MockPkcs11Client, wallet_user, wrapped_key::WrappedKey},
-        repository::MockTransaction,
-        FixedUuidGenerator,
+        generator::mock::MockGenerators,
+        model::{
+            hsm::mock::MockPkcs11Client,
+            wallet_user::{self, WalletUserDevice},
+            wrapped_key::WrappedKey,
+        },
+        repository::{MockTransaction, PersistenceError},
     };
     use wallet_provider_persistence::repositories::mock::MockTransactionalWalletUserRepository;
 
-    use crate::instructions::HandleInstruction;
+    use crate::{
+        account_server::{DeviceError, InstructionError},
+        instructions::HandleInstruction,
+    };
 
     #[tokio::test]
     async fn should_handle_checkpin() {
@@ -168,7 +478,7 @@ mod tests {
         instruction
             .handle(
                 &wallet_user,
-                &FixedUuidGenerator,
+                &MockGenerators,
                 &MockTransactionalWalletUserRepository::new(),
                 &MockPkcs11Client::default(),
             )
@@ -193,7 +503,7 @@ mod tests {
         let result = instruction
             .handle(
                 &wallet_user,
-                &FixedUuidGenerator,
+                &MockGenerators,
                 &wallet_user_repo,
                 &MockPkcs11Client::default(),
             )
@@ -238,13 +548,13 @@ mod tests {
             .withf(|_, _, key_identifiers| key_identifiers.contains(&"key1".to_string()))
             .return_once(move |_, _, _| {
                 Ok(HashMap::from([
-                    ("key1".to_string(), WrappedKey::new(signing_key_1_bytes)),
-                    ("key2".to_string(), WrappedKey::new(signing_key_2_bytes)),
+                    ("key1".to_string(), WrappedKey::new(signing_key_1_bytes, 0)),
+                    ("key2".to_string(), WrappedKey::new(signing_key_2_bytes, 0)),
                 ]))
             });
 
         let result = instruction
-            .handle(&wallet_user, &FixedUuidGenerator, &wallet_user_repo, &pkcs11_client)
+            .handle(&wallet_user, &MockGenerators, &wallet_user_repo, &pkcs11_client)
             .await
             .unwrap();
 
@@ -261,4 +571,201 @@ mod tests {
             .verify(&random_msg_2, &result.signatures[1][0].0)
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn should_handle_diffie_hellman() {
+        let wallet_user = wallet_user::mock::wallet_user_1();
+
+        let signing_key_1 = SigningKey::random(&mut OsRng);
+        let signing_key_2 = SigningKey::random(&mut OsRng);
+        let signing_key_1_bytes = signing_key_1.to_bytes().to_vec();
+        let signing_key_2_bytes = signing_key_2.to_bytes().to_vec();
+        let reader_key = SecretKey::random(&mut OsRng);
+
+        let instruction = DiffieHellman {
+            identifiers: vec!["key2".to_string(), "key1".to_string()],
+            public_key: DerVerifyingKey(reader_key.public_key().into()),
+        };
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_find_keys_by_identifiers()
+            .return_once(move |_, _, _| {
+                Ok(HashMap::from([
+                    ("key1".to_string(), WrappedKey::new(signing_key_1_bytes, 0)),
+                    ("key2".to_string(), WrappedKey::new(signing_key_2_bytes, 0)),
+                ]))
+            });
+
+        let result = instruction
+            .handle(
+                &wallet_user,
+                &MockGenerators,
+                &wallet_user_repo,
+                &MockPkcs11Client::default(),
+            )
+            .await
+            .unwrap();
+
+        let expected_shared_secrets: Vec<_> = [signing_key_2, signing_key_1]
+            .iter()
+            .map(|key| {
+                ecdh::diffie_hellman(reader_key.to_nonzero_scalar(), key.verifying_key().as_affine())
+                    .raw_secret_bytes()
+                    .to_vec()
+            })
+            .collect();
+        assert_eq!(expected_shared_secrets, result.shared_secrets);
+    }
+
+    #[tokio::test]
+    async fn should_not_handle_diffie_hellman_for_unknown_key() {
+        let wallet_user = wallet_user::mock::wallet_user_1();
+
+        let signing_key_bytes = SigningKey::random(&mut OsRng).to_bytes().to_vec();
+        let reader_key = SecretKey::random(&mut OsRng);
+
+        let instruction = DiffieHellman {
+            identifiers: vec!["key1".to_string(), "unknown_key".to_string()],
+            public_key: DerVerifyingKey(reader_key.public_key().into()),
+        };
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_find_keys_by_identifiers()
+            .return_once(move |_, _, _| {
+                Ok(HashMap::from([(
+                    "key1".to_string(),
+                    WrappedKey::new(signing_key_bytes, 0),
+                )]))
+            });
+
+        let error = instruction
+            .handle(
+                &wallet_user,
+                &MockGenerators,
+                &wallet_user_repo,
+                &MockPkcs11Client::default(),
+            )
+            .await
+            .expect_err("handling a Diffie-Hellman instruction for an unknown key should fail");
+
+        assert_matches!(error, InstructionError::KeyNotFound(identifier) if identifier == "unknown_key");
+    }
+
+    #[tokio::test]
+    async fn should_handle_get_devices() {
+        let wallet_user = wallet_user::mock::wallet_user_1();
+        let current_device_id = wallet_user.device_id;
+        let other_device_id = uuid!("5d1e2c3b-4a59-4f68-8b7a-9c0d1e2f3a4b");
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_find_wallet_user_devices()
+            .return_once(move |_, _| {
+                Ok([current_device_id, other_device_id]
+                    .into_iter()
+                    .map(|id| WalletUserDevice {
+                        id,
+                        hw_pubkey: (*SigningKey::random(&mut OsRng).verifying_key()).into(),
+                        linked_date_time: Local::now(),
+                        platform_attestation: None,
+                    })
+                    .collect())
+            });
+
+        let result = GetDevices
+            .handle(
+                &wallet_user,
+                &MockGenerators,
+                &wallet_user_repo,
+                &MockPkcs11Client::default(),
+            )
+            .await
+            .unwrap();
+
+        let devices: Vec<_> = result
+            .devices
+            .into_iter()
+            .map(|device| (device.device_id, device.is_current))
+            .collect();
+        assert_eq!(
+            vec![
+                (current_device_id.to_string(), true),
+                (other_device_id.to_string(), false)
+            ],
+            devices
+        );
+    }
+
+    #[tokio::test]
+    async fn should_handle_remove_device() {
+        let wallet_user = wallet_user::mock::wallet_user_1();
+        let other_device_id = uuid!("5d1e2c3b-4a59-4f68-8b7a-9c0d1e2f3a4b");
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_delete_wallet_user_device()
+            .withf(move |_, _, device_id| *device_id == other_device_id)
+            .return_once(|_, _, _| Ok(()));
+
+        RemoveDevice {
+            device_id: other_device_id.to_string(),
+        }
+        .handle(
+            &wallet_user,
+            &MockGenerators,
+            &wallet_user_repo,
+            &MockPkcs11Client::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_not_remove_current_or_unknown_device() {
+        let wallet_user = wallet_user::mock::wallet_user_1();
+
+        let mut wallet_user_repo = MockTransactionalWalletUserRepository::new();
+        wallet_user_repo
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        wallet_user_repo
+            .expect_delete_wallet_user_device()
+            .returning(|_, _, _| Err(PersistenceError::NotFound("device".to_string())));
+
+        for (device_id, expected_current) in [
+            (wallet_user.device_id.to_string(), true),
+            ("not a uuid".to_string(), false),
+            ("5d1e2c3b-4a59-4f68-8b7a-9c0d1e2f3a4b".to_string(), false),
+        ] {
+            let error = RemoveDevice { device_id }
+                .handle(
+                    &wallet_user,
+                    &MockGenerators,
+                    &wallet_user_repo,
+                    &MockPkcs11Client::default(),
+                )
+                .await
+                .expect_err("removing the device should fail");
+
+            if expected_current {
+                assert_matches!(error, InstructionError::Device(DeviceError::RemoveCurrentDevice));
+            } else {
+                assert_matches!(error, InstructionError::Device(DeviceError::UnknownDevice(_)));
+            }
+        }
+    }
 }
@@ -0,0 +1,310 @@
+use p256::ecdsa::VerifyingKey;
+use tracing::info;
+
+use hsm::service::HsmError;
+use wallet_provider_domain::{
+    model::{
+        encrypter::{Decrypter, Encrypter},
+        hsm::WalletUserHsm,
+        versioned_key::VersionedKey,
+    },
+    repository::{Committable, KeyRotationRepository, PersistenceError, TransactionStarter},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeyRotationError {
+    #[error("persistence error: {0}")]
+    Persistence(#[from] PersistenceError),
+    #[error("hsm error: {0}")]
+    Hsm(#[from] HsmError),
+}
+
+/// Migrate all data that is wrapped or encrypted with a previous version of a key in the HSM to the current version of
+/// that key, after which the previous versions can be retired.
+pub async fn rotate_keys<T, R, H>(
+    repositories: &R,
+    hsm: &H,
+    pin_pubkey_encryption_key: &VersionedKey,
+    batch_size: u64,
+) -> Result<(), KeyRotationError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T> + KeyRotationRepository<TransactionType = T>,
+    H: WalletUserHsm<Error = HsmError>
+        + Encrypter<VerifyingKey, Error = HsmError>
+        + Decrypter<VerifyingKey, Error = HsmError>,
+{
+    let rewrapped = rewrap_wallet_user_keys(repositories, hsm, batch_size).await?;
+    info!("Rewrapped {} wallet user keys", rewrapped);
+
+    let reencrypted = reencrypt_pin_pubkeys(repositories, hsm, pin_pubkey_encryption_key, batch_size).await?;
+    info!("Re-encrypted {} PIN public keys", reencrypted);
+
+    Ok(())
+}
+
+/// Rewrap all wallet user keys that are not wrapped with the current version of the wrapping key, returning the number
+/// of rewrapped keys. Every batch is committed separately, so that an interrupted rotation can simply be resumed.
+pub async fn rewrap_wallet_user_keys<T, R, H>(
+    repositories: &R,
+    hsm: &H,
+    batch_size: u64,
+) -> Result<u64, KeyRotationError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T> + KeyRotationRepository<TransactionType = T>,
+    H: WalletUserHsm<Error = HsmError>,
+{
+    let current_version = hsm.wrapping_key_version();
+    let mut rewrapped_count = 0;
+
+    loop {
+        let tx = repositories.begin_transaction().await?;
+
+        let keys = repositories
+            .find_wallet_user_keys_to_rewrap(&tx, current_version, batch_size)
+            .await?;
+        if keys.is_empty() {
+            return Ok(rewrapped_count);
+        }
+
+        let (ids, wrapped_keys): (Vec<_>, Vec<_>) = keys.into_iter().unzip();
+        let rewrapped_keys = hsm.rewrap_multiple(wrapped_keys).await?;
+
+        for (id, rewrapped_key) in ids.into_iter().zip(rewrapped_keys) {
+            repositories.update_wallet_user_key(&tx, id, rewrapped_key).await?;
+            rewrapped_count += 1;
+        }
+
+        tx.commit().await?;
+
+        info!("Rewrapped {} wallet user keys so far", rewrapped_count);
+    }
+}
+
+/// Re-encrypt all PIN public keys that are not encrypted with the current version of the encryption key, returning the
+/// number of re-encrypted keys. Every batch is committed separately, so that an interrupted rotation can simply be
+/// resumed.
+pub async fn reencrypt_pin_pubkeys<T, R, H>(
+    repositories: &R,
+    hsm: &H,
+    pin_pubkey_encryption_key: &VersionedKey,
+    batch_size: u64,
+) -> Result<u64, KeyRotationError>
+where
+    T: Committable,
+    R: TransactionStarter<TransactionType = T> + KeyRotationRepository<TransactionType = T>,
+    H: Encrypter<VerifyingKey, Error = HsmError> + Decrypter<VerifyingKey, Error = HsmError>,
+{
+    let current_version = pin_pubkey_encryption_key.current_version();
+    let current_identifier = pin_pubkey_encryption_key.current_identifier();
+    let mut reencrypted_count = 0;
+
+    loop {
+        let tx = repositories.begin_transaction().await?;
+
+        let pin_pubkeys = repositories
+            .find_pin_pubkeys_to_reencrypt(&tx, current_version, batch_size)
+            .await?;
+        if pin_pubkeys.is_empty() {
+            return Ok(reencrypted_count);
+        }
+
+        for (wallet_user_id, encrypted_pin_pubkey, version) in pin_pubkeys {
+            let identifier = pin_pubkey_encryption_key
+                .identifier(version)
+                .ok_or(HsmError::UnavailableKeyVersion(version))?;
+
+            let pin_pubkey = Decrypter::decrypt(hsm, &identifier, encrypted_pin_pubkey).await?;
+            let reencrypted_pin_pubkey = Encrypter::encrypt(hsm, &current_identifier, pin_pubkey).await?;
+
+            repositories
+                .update_encrypted_pin_pubkey(&tx, wallet_user_id, reencrypted_pin_pubkey, current_version)
+                .await?;
+            reencrypted_count += 1;
+        }
+
+        tx.commit().await?;
+
+        info!("Re-encrypted {} PIN public keys so far", reencrypted_count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashSet, VecDeque},
+        sync::{Arc, Mutex},
+    };
+
+    use assert_matches::assert_matches;
+    use p256::ecdsa::{signature::Verifier, SigningKey};
+    use rand::rngs::OsRng;
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    use hsm::service::SoftwareHsm;
+    use wallet_provider_domain::{
+        model::{encrypted::Encrypted, hsm::Hsm},
+        repository::MockTransaction,
+    };
+    use wallet_provider_persistence::repositories::mock::MockTransactionalWalletUserRepository;
+
+    use super::*;
+
+    const WRAPPING_KEY_IDENTIFIER: &str = "attestation_wrapping_key";
+    const ENCRYPTION_KEY_IDENTIFIER: &str = "pin_pubkey_encryption_key";
+
+    fn open_hsm(dir: &TempDir, wrapping_key_version: u32) -> SoftwareHsm {
+        let hsm = SoftwareHsm::open(
+            dir.path().join("keys.json"),
+            "passphrase",
+            VersionedKey::new(
+                WRAPPING_KEY_IDENTIFIER.to_string(),
+                wrapping_key_version,
+                HashSet::new(),
+            ),
+            false,
+        )
+        .unwrap();
+        hsm.ensure_secret_key(ENCRYPTION_KEY_IDENTIFIER).unwrap();
+        hsm.ensure_secret_key(&format!("{ENCRYPTION_KEY_IDENTIFIER}_v1"))
+            .unwrap();
+
+        hsm
+    }
+
+    /// Returns the batches in order on every find, followed by an empty batch.
+    fn batches<T: Send + 'static>(
+        batches: Vec<Vec<T>>,
+    ) -> impl FnMut(&MockTransaction, u32, u64) -> Result<Vec<T>, PersistenceError> {
+        let batches = Arc::new(Mutex::new(VecDeque::from(batches)));
+        move |_, _, _| Ok(batches.lock().unwrap().pop_front().unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_rewrap_wallet_user_keys() {
+        let dir = TempDir::new().unwrap();
+        let hsm = open_hsm(&dir, 0);
+
+        let (verifying_key_1, wrapped_key_1) = hsm.generate_wrapped_key().await.unwrap();
+        let (verifying_key_2, wrapped_key_2) = hsm.generate_wrapped_key().await.unwrap();
+        let (verifying_key_3, wrapped_key_3) = hsm.generate_wrapped_key().await.unwrap();
+        let verifying_keys = [verifying_key_1, verifying_key_2, verifying_key_3];
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+
+        let hsm = open_hsm(&dir, 1);
+
+        let rewrapped_keys = Arc::new(Mutex::new(Vec::new()));
+
+        let mut repositories = MockTransactionalWalletUserRepository::new();
+        repositories
+            .expect_begin_transaction()
+            .times(3)
+            .returning(|| Ok(MockTransaction));
+        repositories
+            .expect_find_wallet_user_keys_to_rewrap()
+            .withf(|_, current_version, batch_size| *current_version == 1 && *batch_size == 2)
+            .times(3)
+            .returning(batches(vec![
+                vec![(ids[0], wrapped_key_1), (ids[1], wrapped_key_2)],
+                vec![(ids[2], wrapped_key_3)],
+            ]));
+        repositories.expect_update_wallet_user_key().times(3).returning({
+            let rewrapped_keys = Arc::clone(&rewrapped_keys);
+            move |_, id, key| {
+                rewrapped_keys.lock().unwrap().push((id, key));
+                Ok(())
+            }
+        });
+
+        let count = rewrap_wallet_user_keys(&repositories, &hsm, 2).await.unwrap();
+        assert_eq!(count, 3);
+
+        let data = Arc::new(b"data".to_vec());
+        let rewrapped_keys = rewrapped_keys.lock().unwrap().clone();
+        for ((id, key), (expected_id, verifying_key)) in
+            rewrapped_keys.into_iter().zip(ids.into_iter().zip(verifying_keys))
+        {
+            assert_eq!(id, expected_id);
+            assert_eq!(key.wrapping_key_version(), 1);
+
+            let signature = hsm.sign_wrapped(key, Arc::clone(&data)).await.unwrap();
+            verifying_key.verify(&data, &signature).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reencrypt_pin_pubkeys() {
+        let dir = TempDir::new().unwrap();
+        let hsm = open_hsm(&dir, 0);
+        let encryption_key = VersionedKey::new(ENCRYPTION_KEY_IDENTIFIER.to_string(), 1, HashSet::new());
+
+        let pin_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let encrypted_pin_pubkey = Encrypter::encrypt(&hsm, ENCRYPTION_KEY_IDENTIFIER, pin_pubkey)
+            .await
+            .unwrap();
+        let wallet_user_id = Uuid::new_v4();
+
+        let reencrypted = Arc::new(Mutex::new(None));
+
+        let mut repositories = MockTransactionalWalletUserRepository::new();
+        repositories
+            .expect_begin_transaction()
+            .times(2)
+            .returning(|| Ok(MockTransaction));
+        repositories
+            .expect_find_pin_pubkeys_to_reencrypt()
+            .withf(|_, current_version, _| *current_version == 1)
+            .times(2)
+            .returning(batches(vec![vec![(wallet_user_id, encrypted_pin_pubkey, 0)]]));
+        repositories
+            .expect_update_encrypted_pin_pubkey()
+            .withf(move |_, id, _, version| *id == wallet_user_id && *version == 1)
+            .times(1)
+            .returning({
+                let reencrypted = Arc::clone(&reencrypted);
+                move |_, _, encrypted, _| {
+                    reencrypted.lock().unwrap().replace(encrypted);
+                    Ok(())
+                }
+            });
+
+        let count = reencrypt_pin_pubkeys(&repositories, &hsm, &encryption_key, 10)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let reencrypted: Encrypted<VerifyingKey> = reencrypted.lock().unwrap().take().unwrap();
+        let decrypted = Decrypter::decrypt(&hsm, &encryption_key.current_identifier(), reencrypted)
+            .await
+            .unwrap();
+        assert_eq!(decrypted, pin_pubkey);
+    }
+
+    #[tokio::test]
+    async fn test_reencrypt_pin_pubkeys_retired_version() {
+        let dir = TempDir::new().unwrap();
+        let hsm = open_hsm(&dir, 0);
+        let encryption_key = VersionedKey::new(ENCRYPTION_KEY_IDENTIFIER.to_string(), 1, HashSet::from([0]));
+
+        let encrypted_pin_pubkey: Encrypted<VerifyingKey> =
+            Hsm::encrypt(&hsm, ENCRYPTION_KEY_IDENTIFIER, vec![1, 2, 3])
+                .await
+                .unwrap();
+
+        let mut repositories = MockTransactionalWalletUserRepository::new();
+        repositories
+            .expect_begin_transaction()
+            .returning(|| Ok(MockTransaction));
+        repositories
+            .expect_find_pin_pubkeys_to_reencrypt()
+            .returning(batches(vec![vec![(Uuid::new_v4(), encrypted_pin_pubkey, 0)]]));
+        repositories.expect_update_encrypted_pin_pubkey().never();
+
+        let error = reencrypt_pin_pubkeys(&repositories, &hsm, &encryption_key, 10)
+            .await
+            .expect_err("re-encrypting with a retired key version should fail");
+        assert_matches!(error, KeyRotationError::Hsm(HsmError::UnavailableKeyVersion(0)));
+    }
+}
@@ -1,17 +1,13 @@
-use std::sync::Arc;
-
 use p256::ecdsa::{Signature, VerifyingKey};
 
+use hsm::{keys::HsmEcdsaKey, service::HsmError};
 use wallet_common::keys::{EcdsaKey, SecureEcdsaKey, WithIdentifier};
-use wallet_provider_domain::model::hsm::Hsm;
-
-use crate::hsm::{HsmError, Pkcs11Hsm};
 
 pub trait CertificateSigningKey: SecureEcdsaKey + WithIdentifier {}
 pub trait InstructionResultSigningKey: SecureEcdsaKey + WithIdentifier {}
 
-pub struct CertificateSigning(pub WalletProviderEcdsaKey);
-pub struct InstructionResultSigning(pub WalletProviderEcdsaKey);
+pub struct CertificateSigning(pub HsmEcdsaKey);
+pub struct InstructionResultSigning(pub HsmEcdsaKey);
 
 impl EcdsaKey for CertificateSigning {
     type Error = HsmError;
@@ -40,51 +36,20 @@ impl EcdsaKey for InstructionResultSigning {
 impl SecureEcdsaKey for CertificateSigning {}
 impl WithIdentifier for CertificateSigning {
     fn identifier(&self) -> &str {
-        &self.0.identifier
+        self.0.identifier()
     }
 }
 
 impl SecureEcdsaKey for InstructionResultSigning {}
 impl WithIdentifier for InstructionResultSigning {
     fn identifier(&self) -> &str {
-        &self.0.identifier
+        self.0.identifier()
     }
 }
 
 impl CertificateSigningKey for CertificateSigning {}
 impl InstructionResultSigningKey for InstructionResultSigning {}
 
-pub struct WalletProviderEcdsaKey {
-    identifier: String,
-    hsm: Pkcs11Hsm,
-}
-
-impl WalletProviderEcdsaKey {
-    pub fn new(identifier: String, hsm: Pkcs11Hsm) -> Self {
-        Self { identifier, hsm }
-    }
-}
-
-impl EcdsaKey for WalletProviderEcdsaKey {
-    type Error = HsmError;
-
-    async fn verifying_key(&self) -> Result<VerifyingKey, Self::Error> {
-        self.hsm.get_verifying_key(&self.identifier).await
-    }
-
-    async fn try_sign(&self, msg: &[u8]) -> Result<Signature, Self::Error> {
-        Hsm::sign_ecdsa(&self.hsm, &self.identifier, Arc::new(msg.into())).await
-    }
-}
-
-impl WithIdentifier for WalletProviderEcdsaKey {
-    fn identifier(&self) -> &str {
-        &self.identifier
-    }
-}
-
-impl SecureEcdsaKey for WalletProviderEcdsaKey {}
-
 #[cfg(any(test, feature = "software_keys"))]
 pub mod mock {
     use wallet_common::keys::software::SoftwareEcdsaKey;
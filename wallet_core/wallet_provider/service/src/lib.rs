@@ -1,5 +1,8 @@
 pub mod account_server;
-pub mod hsm;
+pub mod admin;
 pub mod instructions;
+pub mod key_rotation;
 pub mod keys;
 pub mod pin_policy;
+pub mod pin_recovery;
+pub mod platform_attestation;
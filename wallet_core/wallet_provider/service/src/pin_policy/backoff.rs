@@ -0,0 +1,202 @@
+use chrono::{DateTime, Duration, Local};
+
+use wallet_provider_domain::model::pin_policy::{PinPolicyEvaluation, PinPolicyEvaluator};
+
+/// A PIN policy that allows a number of unsuccessful attempts without delay, after which every unsuccessful attempt
+/// results in a timeout that doubles with each attempt, up to a maximum. The wallet is blocked permanently after
+/// the maximum number of attempts.
+pub struct ExponentialBackoffPinPolicy {
+    attempts_before_timeout: u8,
+    base_timeout: Duration,
+    max_timeout: Duration,
+    max_attempts: u8,
+}
+
+impl ExponentialBackoffPinPolicy {
+    pub fn new(attempts_before_timeout: u8, base_timeout: Duration, max_timeout: Duration, max_attempts: u8) -> Self {
+        assert!(attempts_before_timeout > 0, "at least one attempt should be allowed");
+        assert!(max_attempts > 0, "at least one attempt should be allowed");
+        assert!(base_timeout > Duration::zero(), "base timeout should be positive");
+        assert!(
+            max_timeout >= base_timeout,
+            "maximum timeout should not be smaller than base timeout"
+        );
+
+        Self {
+            attempts_before_timeout,
+            base_timeout,
+            max_timeout,
+            max_attempts,
+        }
+    }
+
+    /// The timeout that results from the unsuccessful attempt with number `attempts`, if any.
+    fn timeout(&self, attempts: u8) -> Option<Duration> {
+        let exponent = attempts.checked_sub(self.attempts_before_timeout)?;
+
+        let timeout = 2i32
+            .checked_pow(u32::from(exponent))
+            .and_then(|factor| self.base_timeout.checked_mul(factor))
+            .map_or(self.max_timeout, |timeout| timeout.min(self.max_timeout));
+
+        Some(timeout)
+    }
+}
+
+impl PinPolicyEvaluator for ExponentialBackoffPinPolicy {
+    fn evaluate(
+        &self,
+        attempts: u8,
+        last_failed_pin: Option<DateTime<Local>>,
+        current_datetime: DateTime<Local>,
+    ) -> PinPolicyEvaluation {
+        assert!(attempts > 0);
+        assert!(
+            (attempts == 1) == last_failed_pin.is_none(),
+            "cannot evaluate pin policy for inconsistent starting point"
+        );
+
+        if attempts >= self.max_attempts {
+            return PinPolicyEvaluation::BlockedPermanently;
+        }
+
+        if let (Some(last_failed), Some(previous_timeout)) = (last_failed_pin, self.timeout(attempts - 1)) {
+            let timed_out_until = last_failed + previous_timeout;
+
+            if timed_out_until > current_datetime {
+                return PinPolicyEvaluation::InTimeout {
+                    timeout: timed_out_until - current_datetime,
+                    next_attempt_at: timed_out_until,
+                };
+            }
+        }
+
+        if let Some(timeout) = self.timeout(attempts) {
+            return PinPolicyEvaluation::Timeout {
+                timeout,
+                next_attempt_at: current_datetime + timeout,
+            };
+        }
+
+        // The first timeout is preceded by a single round of attempts, which is final if the wallet gets blocked
+        // before any timeout occurs.
+        PinPolicyEvaluation::Failed {
+            attempts_left_in_round: self.attempts_before_timeout.min(self.max_attempts) - attempts,
+            is_final_round: self.max_attempts <= self.attempts_before_timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::{Duration, Local};
+    use proptest::prelude::*;
+
+    use wallet_provider_domain::model::pin_policy::{PinPolicyEvaluation, PinPolicyEvaluator};
+
+    use super::ExponentialBackoffPinPolicy;
+
+    fn policy() -> ExponentialBackoffPinPolicy {
+        ExponentialBackoffPinPolicy::new(3, Duration::seconds(30), Duration::hours(1), 20)
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let policy = policy();
+        let now = Local::now();
+
+        assert_eq!(
+            policy.evaluate(1, None, now),
+            PinPolicyEvaluation::Failed {
+                attempts_left_in_round: 2,
+                is_final_round: false
+            }
+        );
+        assert_eq!(
+            policy.evaluate(3, Some(now), now),
+            PinPolicyEvaluation::Timeout {
+                timeout: Duration::seconds(30),
+                next_attempt_at: now + Duration::seconds(30),
+            }
+        );
+        assert_eq!(
+            policy.evaluate(4, Some(now - Duration::seconds(10)), now),
+            PinPolicyEvaluation::InTimeout {
+                timeout: Duration::seconds(20),
+                next_attempt_at: now + Duration::seconds(20),
+            }
+        );
+        assert_eq!(
+            policy.evaluate(4, Some(now - Duration::seconds(30)), now),
+            PinPolicyEvaluation::Timeout {
+                timeout: Duration::seconds(60),
+                next_attempt_at: now + Duration::seconds(60),
+            }
+        );
+        assert_matches!(
+            policy.evaluate(19, Some(now - Duration::hours(1)), now),
+            PinPolicyEvaluation::Timeout { timeout, .. } if timeout == Duration::hours(1)
+        );
+        assert_eq!(
+            policy.evaluate(20, Some(now), now),
+            PinPolicyEvaluation::BlockedPermanently
+        );
+    }
+
+    #[test]
+    fn test_evaluate_blocked_before_timeout() {
+        let policy = ExponentialBackoffPinPolicy::new(5, Duration::seconds(30), Duration::hours(1), 3);
+
+        assert_eq!(
+            policy.evaluate(2, Some(Local::now()), Local::now()),
+            PinPolicyEvaluation::Failed {
+                attempts_left_in_round: 1,
+                is_final_round: true
+            }
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn timeout_doubles_until_maximum(attempts in 3u8..=u8::MAX) {
+            let policy = policy();
+
+            let timeout = policy.timeout(attempts).unwrap();
+            let next_timeout = policy.timeout(attempts.saturating_add(1)).unwrap();
+
+            prop_assert!(timeout <= Duration::hours(1));
+            prop_assert!(next_timeout == (timeout * 2).min(Duration::hours(1)) || attempts == u8::MAX);
+        }
+
+        #[test]
+        fn in_timeout_exactly_until_previous_timeout_ends(attempts in 4u8..20, elapsed_ms in 0i64..7_200_000) {
+            let policy = policy();
+            let now = Local::now();
+            let elapsed = Duration::milliseconds(elapsed_ms);
+            let previous_timeout = policy.timeout(attempts - 1).unwrap();
+
+            match policy.evaluate(attempts, Some(now - elapsed), now) {
+                PinPolicyEvaluation::InTimeout { timeout, next_attempt_at } => {
+                    prop_assert!(elapsed < previous_timeout);
+                    prop_assert_eq!(timeout, previous_timeout - elapsed);
+                    prop_assert_eq!(next_attempt_at, now - elapsed + previous_timeout);
+                }
+                PinPolicyEvaluation::Timeout { timeout, next_attempt_at } => {
+                    prop_assert!(elapsed >= previous_timeout);
+                    prop_assert_eq!(Some(timeout), policy.timeout(attempts));
+                    prop_assert_eq!(next_attempt_at, now + timeout);
+                }
+                evaluation => prop_assert!(false, "unexpected evaluation: {:?}", evaluation),
+            }
+        }
+
+        #[test]
+        fn blocked_exactly_from_max_attempts(attempts in 2u8..=u8::MAX) {
+            let now = Local::now();
+            let evaluation = policy().evaluate(attempts, Some(now - Duration::days(1)), now);
+
+            prop_assert_eq!(evaluation == PinPolicyEvaluation::BlockedPermanently, attempts >= 20);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::{DateTime, Duration, Local};
+
+use wallet_provider_domain::model::pin_policy::{PinPolicyEvaluation, PinPolicyEvaluator};
+
+/// A PIN policy that results in a fixed timeout for every unsuccessful attempt after a number of attempts without
+/// delay, where the number of unsuccessful attempts decreases by one for every `decay_interval` that passes since the
+/// last unsuccessful attempt. The wallet is blocked permanently after the maximum number of attempts.
+pub struct DecayingPinPolicy {
+    attempts_before_timeout: u8,
+    timeout: Duration,
+    max_attempts: u8,
+    decay_interval: Duration,
+}
+
+impl DecayingPinPolicy {
+    pub fn new(attempts_before_timeout: u8, timeout: Duration, max_attempts: u8, decay_interval: Duration) -> Self {
+        assert!(attempts_before_timeout > 0, "at least one attempt should be allowed");
+        assert!(max_attempts > 0, "at least one attempt should be allowed");
+        assert!(timeout > Duration::zero(), "timeout should be positive");
+        assert!(
+            decay_interval > timeout,
+            "decay interval should be larger than the timeout"
+        );
+
+        Self {
+            attempts_before_timeout,
+            timeout,
+            max_attempts,
+            decay_interval,
+        }
+    }
+
+    fn timeout(&self, attempts: u8) -> Option<Duration> {
+        (attempts >= self.attempts_before_timeout).then_some(self.timeout)
+    }
+}
+
+impl PinPolicyEvaluator for DecayingPinPolicy {
+    fn decayed_attempts(
+        &self,
+        attempts: u8,
+        last_failed_pin: Option<DateTime<Local>>,
+        current_datetime: DateTime<Local>,
+    ) -> u8 {
+        let Some(last_failed) = last_failed_pin else {
+            return attempts;
+        };
+
+        let intervals =
+            (current_datetime - last_failed).num_milliseconds().max(0) / self.decay_interval.num_milliseconds();
+
+        attempts.saturating_sub(u8::try_from(intervals).unwrap_or(u8::MAX))
+    }
+
+    fn evaluate(
+        &self,
+        attempts: u8,
+        last_failed_pin: Option<DateTime<Local>>,
+        current_datetime: DateTime<Local>,
+    ) -> PinPolicyEvaluation {
+        assert!(attempts > 0);
+        // As the attempts decay, a first attempt can be preceded by an unsuccessful attempt.
+        assert!(
+            attempts == 1 || last_failed_pin.is_some(),
+            "cannot evaluate pin policy for inconsistent starting point"
+        );
+
+        if attempts >= self.max_attempts {
+            return PinPolicyEvaluation::BlockedPermanently;
+        }
+
+        if let (Some(last_failed), Some(previous_timeout)) = (last_failed_pin, self.timeout(attempts - 1)) {
+            let timed_out_until = last_failed + previous_timeout;
+
+            if timed_out_until > current_datetime {
+                return PinPolicyEvaluation::InTimeout {
+                    timeout: timed_out_until - current_datetime,
+                    next_attempt_at: timed_out_until,
+                };
+            }
+        }
+
+        if let Some(timeout) = self.timeout(attempts) {
+            return PinPolicyEvaluation::Timeout {
+                timeout,
+                next_attempt_at: current_datetime + timeout,
+            };
+        }
+
+        PinPolicyEvaluation::Failed {
+            attempts_left_in_round: self.attempts_before_timeout.min(self.max_attempts) - attempts,
+            is_final_round: self.max_attempts <= self.attempts_before_timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local};
+    use proptest::prelude::*;
+
+    use wallet_provider_domain::model::pin_policy::{PinPolicyEvaluation, PinPolicyEvaluator};
+
+    use super::DecayingPinPolicy;
+
+    fn policy() -> DecayingPinPolicy {
+        DecayingPinPolicy::new(3, Duration::minutes(1), 10, Duration::hours(1))
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let policy = policy();
+        let now = Local::now();
+
+        assert_eq!(
+            policy.evaluate(2, Some(now), now),
+            PinPolicyEvaluation::Failed {
+                attempts_left_in_round: 1,
+                is_final_round: false
+            }
+        );
+        assert_eq!(
+            policy.evaluate(3, Some(now), now),
+            PinPolicyEvaluation::Timeout {
+                timeout: Duration::minutes(1),
+                next_attempt_at: now + Duration::minutes(1),
+            }
+        );
+        assert_eq!(
+            policy.evaluate(4, Some(now - Duration::seconds(45)), now),
+            PinPolicyEvaluation::InTimeout {
+                timeout: Duration::seconds(15),
+                next_attempt_at: now + Duration::seconds(15),
+            }
+        );
+        assert_eq!(
+            policy.evaluate(10, Some(now), now),
+            PinPolicyEvaluation::BlockedPermanently
+        );
+    }
+
+    #[test]
+    fn test_decayed_attempts() {
+        let policy = policy();
+        let now = Local::now();
+
+        assert_eq!(policy.decayed_attempts(5, None, now), 5);
+        assert_eq!(policy.decayed_attempts(5, Some(now - Duration::minutes(59)), now), 5);
+        assert_eq!(policy.decayed_attempts(5, Some(now - Duration::hours(1)), now), 4);
+        assert_eq!(policy.decayed_attempts(5, Some(now - Duration::hours(3)), now), 2);
+        assert_eq!(policy.decayed_attempts(5, Some(now - Duration::days(1)), now), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn decayed_attempts_never_increase(attempts in 0u8..=u8::MAX, elapsed_ms in 0i64..1_000_000_000) {
+            let policy = policy();
+            let now = Local::now();
+            let elapsed = Duration::milliseconds(elapsed_ms);
+
+            let decayed = policy.decayed_attempts(attempts, Some(now - elapsed), now);
+
+            prop_assert!(decayed <= attempts);
+            prop_assert_eq!(
+                i64::from(attempts - decayed),
+                (elapsed_ms / Duration::hours(1).num_milliseconds()).min(i64::from(attempts))
+            );
+        }
+
+        #[test]
+        fn in_timeout_exactly_until_timeout_ends(attempts in 4u8..10, elapsed_ms in 0i64..120_000) {
+            let policy = policy();
+            let now = Local::now();
+            let elapsed = Duration::milliseconds(elapsed_ms);
+
+            match policy.evaluate(attempts, Some(now - elapsed), now) {
+                PinPolicyEvaluation::InTimeout { timeout, next_attempt_at } => {
+                    prop_assert!(elapsed < Duration::minutes(1));
+                    prop_assert_eq!(timeout, Duration::minutes(1) - elapsed);
+                    prop_assert_eq!(next_attempt_at, now - elapsed + Duration::minutes(1));
+                }
+                PinPolicyEvaluation::Timeout { timeout, next_attempt_at } => {
+                    prop_assert!(elapsed >= Duration::minutes(1));
+                    prop_assert_eq!(timeout, Duration::minutes(1));
+                    prop_assert_eq!(next_attempt_at, now + timeout);
+                }
+                evaluation => prop_assert!(false, "unexpected evaluation: {:?}", evaluation),
+            }
+        }
+
+        #[test]
+        fn blocked_exactly_from_max_attempts(attempts in 1u8..=u8::MAX) {
+            let now = Local::now();
+            let evaluation = policy().evaluate(attempts, Some(now - Duration::minutes(5)), now);
+
+            prop_assert_eq!(evaluation == PinPolicyEvaluation::BlockedPermanently, attempts >= 10);
+        }
+    }
+}
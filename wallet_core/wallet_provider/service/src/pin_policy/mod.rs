@@ -2,6 +2,11 @@ use chrono::{DateTime, Duration, Local};
 
 use wallet_provider_domain::model::pin_policy::{PinPolicyEvaluation, PinPolicyEvaluator};
 
+pub use self::{backoff::ExponentialBackoffPinPolicy, decay::DecayingPinPolicy};
+
+mod backoff;
+mod decay;
+
 pub struct PinPolicy {
     rounds: u8,
     attempts_per_round: u8,
@@ -93,12 +98,16 @@ impl PinPolicyEvaluator for PinPolicy {
             let start_of_next_round = self.attempts_per_round == self.attempts_left_in_round(attempts) + 1;
 
             if end_of_round {
-                return PinPolicyEvaluation::Timeout { timeout };
+                return PinPolicyEvaluation::Timeout {
+                    timeout,
+                    next_attempt_at: current_datetime + timeout,
+                };
             }
 
             if is_already_in_timeout && start_of_next_round {
                 return PinPolicyEvaluation::InTimeout {
                     timeout: timed_out_until - current_datetime,
+                    next_attempt_at: timed_out_until,
                 };
             }
         }
@@ -110,6 +119,41 @@ impl PinPolicyEvaluator for PinPolicy {
     }
 }
 
+/// The PIN policy that is selected in the configuration of the Wallet Provider.
+pub enum ConfiguredPinPolicy {
+    Rounds(PinPolicy),
+    ExponentialBackoff(ExponentialBackoffPinPolicy),
+    Decaying(DecayingPinPolicy),
+}
+
+impl PinPolicyEvaluator for ConfiguredPinPolicy {
+    fn decayed_attempts(
+        &self,
+        attempts: u8,
+        last_failed_pin: Option<DateTime<Local>>,
+        current_datetime: DateTime<Local>,
+    ) -> u8 {
+        match self {
+            Self::Rounds(policy) => policy.decayed_attempts(attempts, last_failed_pin, current_datetime),
+            Self::ExponentialBackoff(policy) => policy.decayed_attempts(attempts, last_failed_pin, current_datetime),
+            Self::Decaying(policy) => policy.decayed_attempts(attempts, last_failed_pin, current_datetime),
+        }
+    }
+
+    fn evaluate(
+        &self,
+        attempts: u8,
+        last_failed_pin: Option<DateTime<Local>>,
+        current_datetime: DateTime<Local>,
+    ) -> PinPolicyEvaluation {
+        match self {
+            Self::Rounds(policy) => policy.evaluate(attempts, last_failed_pin, current_datetime),
+            Self::ExponentialBackoff(policy) => policy.evaluate(attempts, last_failed_pin, current_datetime),
+            Self::Decaying(policy) => policy.evaluate(attempts, last_failed_pin, current_datetime),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
@@ -144,15 +188,16 @@ mod tests {
     #[test]
     fn test_evaluate() {
         let policy = PinPolicy::new(4, 4, (1..4).map(Duration::hours).collect());
+        let now = Local::now();
 
         assert_eq!(
             PinPolicyEvaluation::BlockedPermanently,
-            policy.evaluate(16, Some(Local::now()), Local::now()),
+            policy.evaluate(16, Some(now), now),
         );
 
         assert_eq!(
             PinPolicyEvaluation::BlockedPermanently,
-            policy.evaluate(100, Some(Local::now()), Local::now())
+            policy.evaluate(100, Some(now), now)
         );
 
         assert_eq!(
@@ -160,7 +205,7 @@ mod tests {
                 attempts_left_in_round: 3,
                 is_final_round: false
             },
-            policy.evaluate(1, None, Local::now())
+            policy.evaluate(1, None, now)
         );
 
         assert_eq!(
@@ -168,7 +213,7 @@ mod tests {
                 attempts_left_in_round: 2,
                 is_final_round: false
             },
-            policy.evaluate(2, Some(Local::now() - Duration::hours(1)), Local::now())
+            policy.evaluate(2, Some(now - Duration::hours(1)), now)
         );
 
         assert_eq!(
@@ -176,26 +221,28 @@ mod tests {
                 attempts_left_in_round: 1,
                 is_final_round: false
             },
-            policy.evaluate(3, Some(Local::now() - Duration::hours(1)), Local::now())
+            policy.evaluate(3, Some(now - Duration::hours(1)), now)
         );
 
         assert_eq!(
             PinPolicyEvaluation::Timeout {
                 timeout: Duration::hours(1),
+                next_attempt_at: now + Duration::hours(1),
             },
-            policy.evaluate(4, Some(Local::now() - Duration::hours(1)), Local::now())
+            policy.evaluate(4, Some(now - Duration::hours(1)), now)
         );
 
         assert_matches!(
-            policy.evaluate(5, Some(Local::now() - Duration::minutes(30)), Local::now()),
-            PinPolicyEvaluation::InTimeout { timeout: t } if t < Duration::hours(1)
+            policy.evaluate(5, Some(now - Duration::minutes(30)), now),
+            PinPolicyEvaluation::InTimeout { timeout: t, next_attempt_at } if t == Duration::minutes(30) && next_attempt_at == now + t
         );
 
         assert_eq!(
             PinPolicyEvaluation::Timeout {
                 timeout: Duration::hours(3),
+                next_attempt_at: now + Duration::hours(3),
             },
-            policy.evaluate(12, Some(Local::now() - Duration::hours(3)), Local::now())
+            policy.evaluate(12, Some(now - Duration::hours(3)), now)
         );
 
         assert_eq!(
@@ -203,35 +250,39 @@ mod tests {
                 attempts_left_in_round: 1,
                 is_final_round: true
             },
-            policy.evaluate(15, Some(Local::now() - Duration::hours(3)), Local::now())
+            policy.evaluate(15, Some(now - Duration::hours(3)), now)
         );
 
         assert_matches!(
-            policy.evaluate(4, Some(Local::now() - Duration::minutes(30)), Local::now()),
+            policy.evaluate(4, Some(now - Duration::minutes(30)), now),
             PinPolicyEvaluation::Timeout {
                 timeout: t,
+                ..
             } if t == Duration::hours(1)
         );
 
         assert_matches!(
-            policy.evaluate(8, Some(Local::now() - Duration::minutes(30)), Local::now()),
+            policy.evaluate(8, Some(now - Duration::minutes(30)), now),
             PinPolicyEvaluation::Timeout {
                 timeout: t,
+                ..
             } if t == Duration::hours(2)
         );
 
         assert_matches!(
-            policy.evaluate(12, Some(Local::now() - Duration::hours(1)), Local::now()),
+            policy.evaluate(12, Some(now - Duration::hours(1)), now),
             PinPolicyEvaluation::Timeout {
                 timeout: t,
+                ..
             } if t == Duration::hours(3)
         );
 
         assert_matches!(
-            policy.evaluate(13, Some(Local::now() - Duration::hours(1)), Local::now()),
+            policy.evaluate(13, Some(now - Duration::hours(1)), now),
             PinPolicyEvaluation::InTimeout {
                 timeout: t,
-            } if t < Duration::hours(3)
+                next_attempt_at,
+            } if t == Duration::hours(2) && next_attempt_at == now + t
         );
     }
 
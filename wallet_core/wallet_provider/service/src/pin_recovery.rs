@@ -0,0 +1,177 @@
+//! Verification of the proof that a wallet user, which is blocked after too many unsuccessful PIN entries, provides
+//! in order to recover its PIN. The user re-identifies with DigiD, which results in a freshly issued PID that should
+//! belong to the same person as the PID currently held by the wallet.
+
+use chrono::{DateTime, Local, Utc};
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use serde::Deserialize;
+use webpki::TrustAnchor;
+
+use nl_wallet_mdoc::{
+    utils::{
+        crypto::CryptoError,
+        serialization::{cbor_deserialize, CborError},
+    },
+    verifier::ValidityRequirement,
+    DataElementValue, IssuerSigned, MobileSecurityObject,
+};
+use wallet_common::{
+    account::messages::instructions::RecoverPin, generator::Generator, revocation_list::DerCertificateRevocationList,
+    trust_anchor::DerTrustAnchor,
+};
+
+const PID_DOCTYPE: &str = "com.example.pid";
+const PID_BSN: &str = "bsn";
+
+#[derive(Debug, thiserror::Error)]
+pub enum PinRecoveryError {
+    #[error("PIN recovery is not configured")]
+    NotConfigured,
+    #[error("wallet user is not blocked")]
+    NotBlocked,
+    #[error("could not decode PID: {0}")]
+    PidDecoding(#[source] CborError),
+    #[error("could not verify PID: {0}")]
+    PidVerification(#[source] nl_wallet_mdoc::Error),
+    #[error("unexpected doctype of PID: {0}")]
+    UnexpectedDocType(String),
+    #[error("PID does not contain a BSN")]
+    MissingBsn,
+    #[error("BSN of the recovery PID does not match the BSN of the PID held by the wallet")]
+    BsnMismatch,
+    #[error("could not parse signing date of recovery PID: {0}")]
+    SignedDateParsing(#[source] chrono::ParseError),
+    #[error("recovery PID was issued before the wallet user was blocked")]
+    RecoveryPidNotFresh,
+    #[error("could not read public key of PID: {0}")]
+    PidPublicKey(#[source] CryptoError),
+    #[error("recovery PID is not bound to a key possessed by the wallet: {0}")]
+    RecoveryPidSignature(#[source] p256::ecdsa::Error),
+    #[error("key of the PID held by the wallet not found: {0}")]
+    PidKeyNotFound(String),
+    #[error("PID held by the wallet is not bound to a key of the wallet user")]
+    PidKeyMismatch,
+}
+
+/// Configuration of PIN recovery, which is only possible when the trust anchors of the PID issuer are configured.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PinRecoveryVerifier {
+    #[serde(default)]
+    pub pid_trust_anchors: Vec<DerTrustAnchor>,
+    #[serde(default)]
+    pub pid_crls: Vec<DerCertificateRevocationList>,
+}
+
+struct VerifiedPid {
+    bsn: DataElementValue,
+    mso: MobileSecurityObject,
+}
+
+impl PinRecoveryVerifier {
+    /// Verify that both CBOR encoded PIDs are issued by a trusted PID issuer and contain the same BSN, that the
+    /// recovery PID was issued after the wallet user was blocked and that the wallet possesses the private key of the
+    /// recovery PID. Returns the public key of the PID held by the wallet, which the caller should check against the
+    /// keys of the wallet user.
+    pub fn verify(
+        &self,
+        recover_pin: &RecoverPin,
+        blocked_at: DateTime<Local>,
+        time: &impl Generator<DateTime<Local>>,
+    ) -> Result<VerifyingKey, PinRecoveryError> {
+        if self.pid_trust_anchors.is_empty() {
+            return Err(PinRecoveryError::NotConfigured);
+        }
+
+        let trust_anchors = self
+            .pid_trust_anchors
+            .iter()
+            .map(|anchor| (&anchor.owned_trust_anchor).into())
+            .collect::<Vec<TrustAnchor>>();
+        let time = UtcTimeGenerator(time);
+
+        let pid = Self::verify_pid(&recover_pin.pid_issuer_signed, &time, &trust_anchors, &self.pid_crls)?;
+        let recovery_pid = Self::verify_pid(
+            &recover_pin.recovery_pid_issuer_signed,
+            &time,
+            &trust_anchors,
+            &self.pid_crls,
+        )?;
+
+        if pid.bsn != recovery_pid.bsn {
+            return Err(PinRecoveryError::BsnMismatch);
+        }
+
+        // A PID that was issued before the wallet user got blocked may have been obtained from the wallet itself,
+        // which is why only a PID issued afterwards proves that the user re-identified with DigiD.
+        let recovery_pid_signed = DateTime::<Utc>::try_from(&recovery_pid.mso.validity_info.signed)
+            .map_err(PinRecoveryError::SignedDateParsing)?;
+        if recovery_pid_signed < blocked_at {
+            return Err(PinRecoveryError::RecoveryPidNotFresh);
+        }
+
+        // Without proof of possession of its private key, the recovery PID could have been issued to someone else.
+        Self::verify_recovery_pid_signature(recovery_pid.mso, recover_pin)?;
+
+        let public_key = pid
+            .mso
+            .device_key_info
+            .try_into()
+            .map_err(PinRecoveryError::PidPublicKey)?;
+
+        Ok(public_key)
+    }
+
+    fn verify_recovery_pid_signature(
+        recovery_pid_mso: MobileSecurityObject,
+        recover_pin: &RecoverPin,
+    ) -> Result<(), PinRecoveryError> {
+        let recovery_pid_public_key: VerifyingKey = recovery_pid_mso
+            .device_key_info
+            .try_into()
+            .map_err(PinRecoveryError::PidPublicKey)?;
+        let signature: &Signature = &recover_pin.recovery_pid_signature.0;
+
+        recovery_pid_public_key
+            .verify(&RecoverPin::recovery_pid_message(&recover_pin.pin_pubkey.0), signature)
+            .map_err(PinRecoveryError::RecoveryPidSignature)
+    }
+
+    fn verify_pid(
+        pid: &[u8],
+        time: &impl Generator<DateTime<Utc>>,
+        trust_anchors: &[TrustAnchor],
+        crls: &[DerCertificateRevocationList],
+    ) -> Result<VerifiedPid, PinRecoveryError> {
+        let issuer_signed: IssuerSigned = cbor_deserialize(pid).map_err(PinRecoveryError::PidDecoding)?;
+
+        // The PID held by the wallet may have been issued with a validity that starts in the future.
+        let (attributes, mso) = issuer_signed
+            .verify(ValidityRequirement::AllowNotYetValid, time, trust_anchors, crls)
+            .map_err(PinRecoveryError::PidVerification)?;
+
+        if mso.doc_type != PID_DOCTYPE {
+            return Err(PinRecoveryError::UnexpectedDocType(mso.doc_type));
+        }
+
+        let bsn = attributes
+            .attributes
+            .get(PID_DOCTYPE)
+            .and_then(|entries| entries.iter().find(|entry| entry.name == PID_BSN))
+            .map(|entry| entry.value.clone())
+            .ok_or(PinRecoveryError::MissingBsn)?;
+
+        Ok(VerifiedPid { bsn, mso })
+    }
+}
+
+/// Adapts the local time generator used throughout the Wallet Provider to the UTC time that mdoc verification uses.
+struct UtcTimeGenerator<'a, G>(&'a G);
+
+impl<G> Generator<DateTime<Utc>> for UtcTimeGenerator<'_, G>
+where
+    G: Generator<DateTime<Local>>,
+{
+    fn generate(&self) -> DateTime<Utc> {
+        self.0.generate().to_utc()
+    }
+}
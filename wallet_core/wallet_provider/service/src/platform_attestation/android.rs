@@ -0,0 +1,468 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Local};
+use p256::{ecdsa::VerifyingKey, pkcs8::DecodePublicKey};
+use serde::Deserialize;
+use serde_with::{hex::Hex, serde_as};
+use sha2::{Digest, Sha256};
+use x509_parser::{
+    certificate::X509Certificate,
+    der_parser::asn1_rs::{self, Any, Class, FromDer, Oid, Tag},
+    error::X509Error,
+};
+
+use wallet_common::{generator::Generator, trust_anchor::DerTrustAnchor};
+use wallet_provider_domain::model::wallet_user::WalletUserPlatformAttestation;
+
+use super::verify_certificate_chain;
+
+/// OID 1.3.6.1.4.1.11129.2.1.17
+const KEY_DESCRIPTION_OID: Oid = Oid::new(Cow::Borrowed(&[43, 6, 1, 4, 1, 214, 121, 2, 1, 17]));
+
+/// Tag of the `rootOfTrust` field of an `AuthorizationList`.
+const ROOT_OF_TRUST_TAG: u32 = 704;
+/// Tag of the `attestationApplicationId` field of an `AuthorizationList`.
+const ATTESTATION_APPLICATION_ID_TAG: u32 = 709;
+
+/// The `Verified` value of the `VerifiedBootState` enumeration.
+const VERIFIED_BOOT_STATE_VERIFIED: u32 = 0;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AndroidAttestationError {
+    #[error("certificate chain is empty")]
+    EmptyCertificateChain,
+    #[error("certificate chain verification failed: {0}")]
+    CertificateChain(#[source] webpki::Error),
+    #[error("could not parse certificate: {0}")]
+    CertificateParsing(#[from] X509Error),
+    #[error("could not decode public key of certificate: {0}")]
+    PublicKeyDecoding(#[source] p256::pkcs8::spki::Error),
+    #[error("public key of certificate does not match hardware public key")]
+    PublicKeyMismatch,
+    #[error("certificate does not contain a key description")]
+    MissingKeyDescription,
+    #[error("could not parse key description: {0}")]
+    KeyDescriptionParsing(#[from] asn1_rs::Error),
+    #[error("attestation challenge does not match registration challenge")]
+    ChallengeMismatch,
+    #[error("key is not stored in secure hardware")]
+    SoftwareSecurityLevel,
+    #[error("unknown security level: {0}")]
+    UnknownSecurityLevel(u32),
+    #[error("security level is not allowed: {0:?}")]
+    SecurityLevelNotAllowed(AndroidSecurityLevel),
+    #[error("key description does not contain a root of trust")]
+    MissingRootOfTrust,
+    #[error("device bootloader is not locked")]
+    DeviceNotLocked,
+    #[error("device verified boot state is not verified: {0}")]
+    BootStateNotVerified(u32),
+    #[error("key description does not contain an application id")]
+    MissingApplicationId,
+    #[error("package name is not allowed: {0:?}")]
+    PackageNameNotAllowed(Vec<String>),
+    #[error("signing certificate digest is not allowed: {0:?}")]
+    SigningCertificateDigestNotAllowed(Vec<String>),
+}
+
+/// The security level of secure hardware in which an Android key can be stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AndroidSecurityLevel {
+    TrustedEnvironment,
+    StrongBox,
+}
+
+/// Configuration for verifying Android Key Attestation certificate chains.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AndroidAttestationConfig {
+    /// The Google hardware attestation root certificates.
+    pub root_certificates: Vec<DerTrustAnchor>,
+    /// The package names of the Wallet app.
+    pub package_names: Vec<String>,
+    /// The hex encoded SHA-256 digests of the certificates with which the Wallet app is signed. As anyone can sign an
+    /// app with our package name, only these prove that the app is ours.
+    #[serde_as(as = "Vec<Hex>")]
+    pub signing_certificate_digests: Vec<Vec<u8>>,
+    /// The security levels that are accepted for both the attestation and the key itself.
+    #[serde(default = "default_security_levels")]
+    pub security_levels: Vec<AndroidSecurityLevel>,
+}
+
+fn default_security_levels() -> Vec<AndroidSecurityLevel> {
+    vec![
+        AndroidSecurityLevel::TrustedEnvironment,
+        AndroidSecurityLevel::StrongBox,
+    ]
+}
+
+/// The fields of the `KeyDescription` extension that are verified.
+struct KeyDescription<'a> {
+    attestation_security_level: u32,
+    key_mint_security_level: u32,
+    attestation_challenge: Cow<'a, [u8]>,
+    software_enforced: Vec<Any<'a>>,
+    hardware_enforced: Vec<Any<'a>>,
+}
+
+impl AndroidAttestationConfig {
+    pub(super) fn verify(
+        &self,
+        certificate_chain: &[Vec<u8>],
+        challenge: &[u8],
+        hw_pubkey: &VerifyingKey,
+        time: &impl Generator<DateTime<Local>>,
+    ) -> Result<WalletUserPlatformAttestation, AndroidAttestationError> {
+        let leaf = certificate_chain
+            .first()
+            .ok_or(AndroidAttestationError::EmptyCertificateChain)?;
+
+        verify_certificate_chain(certificate_chain, &self.root_certificates, time)
+            .map_err(AndroidAttestationError::CertificateChain)?;
+
+        let (_, leaf) = X509Certificate::from_der(leaf).map_err(X509Error::from)?;
+
+        // The attested key should be the hardware key that signed the registration.
+        let public_key = VerifyingKey::from_public_key_der(leaf.public_key().raw)
+            .map_err(AndroidAttestationError::PublicKeyDecoding)?;
+        if public_key != *hw_pubkey {
+            return Err(AndroidAttestationError::PublicKeyMismatch);
+        }
+
+        let extension = leaf
+            .iter_extensions()
+            .find(|extension| extension.oid == KEY_DESCRIPTION_OID)
+            .ok_or(AndroidAttestationError::MissingKeyDescription)?;
+        let key_description = KeyDescription::parse(extension.value)?;
+
+        // The registration challenge is hashed, as the attestation challenge is limited in size.
+        if *key_description.attestation_challenge != *Sha256::digest(challenge) {
+            return Err(AndroidAttestationError::ChallengeMismatch);
+        }
+
+        self.check_security_level(key_description.attestation_security_level)?;
+        let security_level = self.check_security_level(key_description.key_mint_security_level)?;
+
+        Self::check_root_of_trust(&key_description.hardware_enforced)?;
+        self.check_application_id(&key_description)?;
+
+        let outcome = match security_level {
+            AndroidSecurityLevel::TrustedEnvironment => WalletUserPlatformAttestation::AndroidTrustedEnvironment,
+            AndroidSecurityLevel::StrongBox => WalletUserPlatformAttestation::AndroidStrongBox,
+        };
+
+        Ok(outcome)
+    }
+
+    fn check_security_level(&self, security_level: u32) -> Result<AndroidSecurityLevel, AndroidAttestationError> {
+        let security_level = match security_level {
+            0 => return Err(AndroidAttestationError::SoftwareSecurityLevel),
+            1 => AndroidSecurityLevel::TrustedEnvironment,
+            2 => AndroidSecurityLevel::StrongBox,
+            _ => return Err(AndroidAttestationError::UnknownSecurityLevel(security_level)),
+        };
+
+        if !self.security_levels.contains(&security_level) {
+            return Err(AndroidAttestationError::SecurityLevelNotAllowed(security_level));
+        }
+
+        Ok(security_level)
+    }
+
+    fn check_root_of_trust(hardware_enforced: &[Any]) -> Result<(), AndroidAttestationError> {
+        let root_of_trust = find_authorization(hardware_enforced, ROOT_OF_TRUST_TAG)?
+            .ok_or(AndroidAttestationError::MissingRootOfTrust)?;
+
+        // RootOfTrust ::= SEQUENCE { verifiedBootKey, deviceLocked, verifiedBootState, ... }
+        let fields = elements(&root_of_trust, Tag::Sequence)?;
+        let device_locked = field(&fields, 1)?.as_bool()?;
+        let verified_boot_state = field(&fields, 2)?.clone().enumerated()?.0;
+
+        if !device_locked {
+            return Err(AndroidAttestationError::DeviceNotLocked);
+        }
+        if verified_boot_state != VERIFIED_BOOT_STATE_VERIFIED {
+            return Err(AndroidAttestationError::BootStateNotVerified(verified_boot_state));
+        }
+
+        Ok(())
+    }
+
+    fn check_application_id(&self, key_description: &KeyDescription) -> Result<(), AndroidAttestationError> {
+        let application_id =
+            match find_authorization(&key_description.software_enforced, ATTESTATION_APPLICATION_ID_TAG)? {
+                Some(application_id) => application_id,
+                None => find_authorization(&key_description.hardware_enforced, ATTESTATION_APPLICATION_ID_TAG)?
+                    .ok_or(AndroidAttestationError::MissingApplicationId)?,
+            };
+
+        // AttestationApplicationId ::= SEQUENCE {
+        //     packageInfos SET OF AttestationPackageInfo,
+        //     signatureDigests SET OF OCTET STRING,
+        // }
+        // AttestationPackageInfo ::= SEQUENCE { packageName OCTET STRING, version INTEGER }
+        let application_id = application_id.octetstring()?;
+        let (_, application_id) = Any::from_der(application_id.as_cow()).map_err(asn1_rs::Error::from)?;
+        let fields = elements(&application_id, Tag::Sequence)?;
+        let package_names = elements(field(&fields, 0)?, Tag::Set)?
+            .iter()
+            .map(|package_info| {
+                let package_info = elements(package_info, Tag::Sequence)?;
+                let package_name = field(&package_info, 0)?.clone().octetstring()?;
+                Ok(String::from_utf8_lossy(package_name.as_cow()).into_owned())
+            })
+            .collect::<Result<Vec<_>, asn1_rs::Error>>()?;
+
+        // All packages sharing the UID of the app are listed, all of which should be allowed.
+        if package_names.is_empty() || !package_names.iter().all(|name| self.package_names.contains(name)) {
+            return Err(AndroidAttestationError::PackageNameNotAllowed(package_names));
+        }
+
+        let signature_digests = elements(field(&fields, 1)?, Tag::Set)?
+            .into_iter()
+            .map(|signature_digest| Ok(signature_digest.octetstring()?.into_cow().into_owned()))
+            .collect::<Result<Vec<_>, asn1_rs::Error>>()?;
+
+        // Likewise, every certificate the app is signed with should be allowed.
+        if signature_digests.is_empty()
+            || !signature_digests
+                .iter()
+                .all(|digest| self.signing_certificate_digests.contains(digest))
+        {
+            return Err(AndroidAttestationError::SigningCertificateDigestNotAllowed(
+                signature_digests.iter().map(hex::encode).collect(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> KeyDescription<'a> {
+    fn parse(value: &'a [u8]) -> Result<Self, asn1_rs::Error> {
+        let (_, key_description) = Any::from_der(value)?;
+        let fields = elements(&key_description, Tag::Sequence)?;
+
+        let key_description = KeyDescription {
+            attestation_security_level: field(&fields, 1)?.clone().enumerated()?.0,
+            key_mint_security_level: field(&fields, 3)?.clone().enumerated()?.0,
+            attestation_challenge: field(&fields, 4)?.clone().octetstring()?.into_cow(),
+            software_enforced: elements(field(&fields, 6)?, Tag::Sequence)?,
+            hardware_enforced: elements(field(&fields, 7)?, Tag::Sequence)?,
+        };
+
+        Ok(key_description)
+    }
+}
+
+/// Return the elements of a SEQUENCE or SET with the expected `tag`.
+fn elements<'a>(value: &Any<'a>, tag: Tag) -> Result<Vec<Any<'a>>, asn1_rs::Error> {
+    value.header.tag().assert_eq(tag)?;
+
+    let mut content = value.data;
+    let mut elements = Vec::new();
+    while !content.is_empty() {
+        let (rest, element) = Any::from_der(content)?;
+        elements.push(element);
+        content = rest;
+    }
+
+    Ok(elements)
+}
+
+fn field<'a, 'b>(fields: &'b [Any<'a>], index: usize) -> Result<&'b Any<'a>, asn1_rs::Error> {
+    fields.get(index).ok_or(asn1_rs::Error::BerValueError)
+}
+
+/// Find the explicitly tagged field with `tag` in an `AuthorizationList` and return its inner value.
+fn find_authorization<'a>(authorizations: &[Any<'a>], tag: u32) -> Result<Option<Any<'a>>, asn1_rs::Error> {
+    let value = authorizations
+        .iter()
+        .find(|authorization| {
+            authorization.header.class() == Class::ContextSpecific && authorization.header.tag().0 == tag
+        })
+        .map(|authorization| Any::from_der(authorization.data))
+        .transpose()?
+        .map(|(_, value)| value);
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use p256::ecdsa::SigningKey;
+    use rand::rngs::OsRng;
+    use rstest::rstest;
+
+    use wallet_provider_domain::generator::mock::MockGenerators;
+
+    use super::{
+        super::mock::{
+            android_certificate_chain, MockAttestationCa, MockKeyDescription, ANDROID_PACKAGE_NAME,
+            ANDROID_SIGNING_CERTIFICATE_DIGEST,
+        },
+        *,
+    };
+
+    const CHALLENGE: &[u8] = b"registration_challenge";
+
+    fn config(ca: &MockAttestationCa) -> AndroidAttestationConfig {
+        AndroidAttestationConfig {
+            root_certificates: vec![ca.trust_anchor()],
+            package_names: vec![ANDROID_PACKAGE_NAME.to_string()],
+            signing_certificate_digests: vec![ANDROID_SIGNING_CERTIFICATE_DIGEST.to_vec()],
+            security_levels: default_security_levels(),
+        }
+    }
+
+    #[rstest]
+    #[case(1, WalletUserPlatformAttestation::AndroidTrustedEnvironment)]
+    #[case(2, WalletUserPlatformAttestation::AndroidStrongBox)]
+    fn test_android_attestation_verify(
+        #[case] security_level: i64,
+        #[case] expected_outcome: WalletUserPlatformAttestation,
+    ) {
+        let ca = MockAttestationCa::generate();
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let key_description = MockKeyDescription {
+            security_level,
+            ..MockKeyDescription::new(CHALLENGE)
+        };
+        let certificate_chain = android_certificate_chain(&ca, &hw_privkey, &key_description);
+
+        let outcome = config(&ca)
+            .verify(
+                &certificate_chain,
+                CHALLENGE,
+                hw_privkey.verifying_key(),
+                &MockGenerators,
+            )
+            .expect("Android key attestation should be valid");
+
+        assert_eq!(outcome, expected_outcome);
+    }
+
+    #[rstest]
+    #[case(
+        MockKeyDescription { attestation_challenge: b"other_challenge".to_vec(), ..MockKeyDescription::new(CHALLENGE) },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::ChallengeMismatch)
+    )]
+    #[case(
+        MockKeyDescription { security_level: 0, ..MockKeyDescription::new(CHALLENGE) },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::SoftwareSecurityLevel)
+    )]
+    #[case(
+        MockKeyDescription { device_locked: false, ..MockKeyDescription::new(CHALLENGE) },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::DeviceNotLocked)
+    )]
+    #[case(
+        MockKeyDescription { package_names: vec!["com.example.other".to_string()], ..MockKeyDescription::new(CHALLENGE) },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::PackageNameNotAllowed(_))
+    )]
+    #[case(
+        MockKeyDescription {
+            package_names: vec![ANDROID_PACKAGE_NAME.to_string(), "com.example.other".to_string()],
+            ..MockKeyDescription::new(CHALLENGE)
+        },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::PackageNameNotAllowed(_))
+    )]
+    #[case(
+        MockKeyDescription { signature_digests: vec![vec![1; 32]], ..MockKeyDescription::new(CHALLENGE) },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::SigningCertificateDigestNotAllowed(_))
+    )]
+    #[case(
+        MockKeyDescription {
+            signature_digests: vec![ANDROID_SIGNING_CERTIFICATE_DIGEST.to_vec(), vec![1; 32]],
+            ..MockKeyDescription::new(CHALLENGE)
+        },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::SigningCertificateDigestNotAllowed(_))
+    )]
+    #[case(
+        MockKeyDescription { signature_digests: vec![], ..MockKeyDescription::new(CHALLENGE) },
+        |error: &AndroidAttestationError| matches!(error, AndroidAttestationError::SigningCertificateDigestNotAllowed(_))
+    )]
+    fn test_android_attestation_verify_error_key_description(
+        #[case] key_description: MockKeyDescription,
+        #[case] is_expected_error: impl Fn(&AndroidAttestationError) -> bool,
+    ) {
+        let ca = MockAttestationCa::generate();
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let certificate_chain = android_certificate_chain(&ca, &hw_privkey, &key_description);
+
+        let error = config(&ca)
+            .verify(
+                &certificate_chain,
+                CHALLENGE,
+                hw_privkey.verifying_key(),
+                &MockGenerators,
+            )
+            .expect_err("Android key attestation should not be valid");
+
+        assert!(is_expected_error(&error), "unexpected error: {error:?}");
+    }
+
+    #[test]
+    fn test_android_attestation_verify_error_security_level_not_allowed() {
+        let ca = MockAttestationCa::generate();
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let certificate_chain = android_certificate_chain(&ca, &hw_privkey, &MockKeyDescription::new(CHALLENGE));
+
+        let config = AndroidAttestationConfig {
+            security_levels: vec![AndroidSecurityLevel::StrongBox],
+            ..config(&ca)
+        };
+        let error = config
+            .verify(
+                &certificate_chain,
+                CHALLENGE,
+                hw_privkey.verifying_key(),
+                &MockGenerators,
+            )
+            .expect_err("Android key attestation should not be valid");
+
+        assert_matches!(
+            error,
+            AndroidAttestationError::SecurityLevelNotAllowed(AndroidSecurityLevel::TrustedEnvironment)
+        );
+    }
+
+    #[test]
+    fn test_android_attestation_verify_error_public_key_mismatch() {
+        let ca = MockAttestationCa::generate();
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let certificate_chain = android_certificate_chain(&ca, &hw_privkey, &MockKeyDescription::new(CHALLENGE));
+
+        let other_hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let error = config(&ca)
+            .verify(&certificate_chain, CHALLENGE, &other_hw_pubkey, &MockGenerators)
+            .expect_err("Android key attestation should not be valid");
+
+        assert_matches!(error, AndroidAttestationError::PublicKeyMismatch);
+    }
+
+    #[test]
+    fn test_android_attestation_verify_error_untrusted_root() {
+        let ca = MockAttestationCa::generate();
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let certificate_chain = android_certificate_chain(&ca, &hw_privkey, &MockKeyDescription::new(CHALLENGE));
+
+        let error = config(&MockAttestationCa::generate())
+            .verify(
+                &certificate_chain,
+                CHALLENGE,
+                hw_privkey.verifying_key(),
+                &MockGenerators,
+            )
+            .expect_err("Android key attestation should not be valid");
+
+        assert_matches!(error, AndroidAttestationError::CertificateChain(_));
+
+        let error = config(&ca)
+            .verify(&[], CHALLENGE, hw_privkey.verifying_key(), &MockGenerators)
+            .expect_err("Android key attestation should not be valid");
+
+        assert_matches!(error, AndroidAttestationError::EmptyCertificateChain);
+    }
+}
@@ -0,0 +1,334 @@
+use std::borrow::Cow;
+
+use chrono::{DateTime, Local};
+use p256::{ecdsa::VerifyingKey, pkcs8::EncodePublicKey};
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+use sha2::{Digest, Sha256};
+use x509_parser::{
+    certificate::X509Certificate,
+    der_parser::asn1_rs::{self, Any, Class, FromDer, Oid, Tag},
+    error::X509Error,
+};
+
+use wallet_common::{generator::Generator, trust_anchor::DerTrustAnchor};
+use wallet_provider_domain::model::wallet_user::WalletUserPlatformAttestation;
+
+use super::verify_certificate_chain;
+
+/// OID 1.2.840.113635.100.8.2
+const NONCE_OID: Oid = Oid::new(Cow::Borrowed(&[42, 134, 72, 134, 247, 99, 100, 8, 2]));
+
+const ATTESTATION_FORMAT: &str = "apple-appattest";
+
+const AAGUID_PRODUCTION: &[u8; 16] = b"appattest\0\0\0\0\0\0\0";
+const AAGUID_DEVELOPMENT: &[u8; 16] = b"appattestdevelop";
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppleAttestationError {
+    #[error("could not decode attestation object: {0}")]
+    Decoding(#[from] ciborium::de::Error<std::io::Error>),
+    #[error("unsupported attestation format: {0}")]
+    UnsupportedFormat(String),
+    #[error("certificate chain verification failed: {0}")]
+    CertificateChain(#[source] webpki::Error),
+    #[error("could not parse certificate: {0}")]
+    CertificateParsing(#[from] X509Error),
+    #[error("could not parse nonce: {0}")]
+    NonceParsing(#[from] asn1_rs::Error),
+    #[error("certificate does not contain a nonce")]
+    MissingNonce,
+    #[error("nonce does not match registration challenge and hardware public key")]
+    NonceMismatch,
+    #[error("could not encode hardware public key: {0}")]
+    PublicKeyEncoding(#[source] p256::pkcs8::spki::Error),
+    #[error("authenticator data is too short")]
+    AuthenticatorDataLength,
+    #[error("relying party id does not match app id")]
+    AppIdMismatch,
+    #[error("counter is not zero: {0}")]
+    CounterNotZero(u32),
+    #[error("AAGUID does not match environment {0:?}")]
+    AaguidMismatch(AppleEnvironment),
+    #[error("credential id does not match public key of certificate")]
+    CredentialIdMismatch,
+}
+
+/// The App Attest environment in which the app runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppleEnvironment {
+    #[default]
+    Production,
+    Development,
+}
+
+/// Configuration for verifying Apple App Attest attestation objects.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppleAttestationConfig {
+    /// The Apple App Attestation root certificates.
+    pub root_certificates: Vec<DerTrustAnchor>,
+    /// The team id of the developer of the Wallet app.
+    pub team_id: String,
+    /// The bundle id of the Wallet app.
+    pub bundle_id: String,
+    #[serde(default)]
+    pub environment: AppleEnvironment,
+}
+
+#[derive(Deserialize)]
+struct AttestationObject {
+    fmt: String,
+    #[serde(rename = "attStmt")]
+    att_stmt: AttestationStatement,
+    #[serde(rename = "authData")]
+    auth_data: ByteBuf,
+}
+
+#[derive(Deserialize)]
+struct AttestationStatement {
+    x5c: Vec<ByteBuf>,
+}
+
+/// The fields of the authenticator data that are verified.
+struct AuthenticatorData<'a> {
+    rp_id_hash: &'a [u8],
+    counter: u32,
+    aaguid: &'a [u8],
+    credential_id: &'a [u8],
+}
+
+impl AppleAttestationConfig {
+    pub(super) fn verify(
+        &self,
+        attestation_object: &[u8],
+        challenge: &[u8],
+        hw_pubkey: &VerifyingKey,
+        time: &impl Generator<DateTime<Local>>,
+    ) -> Result<WalletUserPlatformAttestation, AppleAttestationError> {
+        let attestation_object: AttestationObject = ciborium::from_reader(attestation_object)?;
+        if attestation_object.fmt != ATTESTATION_FORMAT {
+            return Err(AppleAttestationError::UnsupportedFormat(attestation_object.fmt));
+        }
+
+        let certificate_chain = attestation_object
+            .att_stmt
+            .x5c
+            .into_iter()
+            .map(ByteBuf::into_vec)
+            .collect::<Vec<_>>();
+        verify_certificate_chain(&certificate_chain, &self.root_certificates, time)
+            .map_err(AppleAttestationError::CertificateChain)?;
+
+        // The chain cannot be empty at this point, as it was verified.
+        let (_, credential_certificate) = X509Certificate::from_der(&certificate_chain[0]).map_err(X509Error::from)?;
+
+        // The App Attest key cannot sign the registration, so instead the hardware public key is included in the
+        // client data, along with the registration challenge.
+        let hw_pubkey = hw_pubkey
+            .to_public_key_der()
+            .map_err(AppleAttestationError::PublicKeyEncoding)?;
+        let client_data_hash = Sha256::new()
+            .chain_update(challenge)
+            .chain_update(hw_pubkey.as_bytes())
+            .finalize();
+        let expected_nonce = Sha256::new()
+            .chain_update(&attestation_object.auth_data)
+            .chain_update(client_data_hash)
+            .finalize();
+        if *nonce(&credential_certificate)? != *expected_nonce {
+            return Err(AppleAttestationError::NonceMismatch);
+        }
+
+        let auth_data = AuthenticatorData::parse(&attestation_object.auth_data)?;
+
+        let app_id = format!("{}.{}", self.team_id, self.bundle_id);
+        if *auth_data.rp_id_hash != *Sha256::digest(app_id) {
+            return Err(AppleAttestationError::AppIdMismatch);
+        }
+
+        if auth_data.counter != 0 {
+            return Err(AppleAttestationError::CounterNotZero(auth_data.counter));
+        }
+
+        let expected_aaguid = match self.environment {
+            AppleEnvironment::Production => AAGUID_PRODUCTION,
+            AppleEnvironment::Development => AAGUID_DEVELOPMENT,
+        };
+        if auth_data.aaguid != expected_aaguid {
+            return Err(AppleAttestationError::AaguidMismatch(self.environment));
+        }
+
+        // The credential id is the hash of the uncompressed EC point of the App Attest public key.
+        let public_key = &credential_certificate.public_key().subject_public_key.data;
+        if *auth_data.credential_id != *Sha256::digest(public_key) {
+            return Err(AppleAttestationError::CredentialIdMismatch);
+        }
+
+        Ok(WalletUserPlatformAttestation::AppleAppAttest)
+    }
+}
+
+impl<'a> AuthenticatorData<'a> {
+    fn parse(auth_data: &'a [u8]) -> Result<Self, AppleAttestationError> {
+        // rpIdHash (32) || flags (1) || signCount (4) || aaguid (16) || credentialIdLength (2) || credentialId || ...
+        let bytes = |start: usize, length: usize| {
+            auth_data
+                .get(start..start + length)
+                .ok_or(AppleAttestationError::AuthenticatorDataLength)
+        };
+
+        let counter = u32::from_be_bytes(bytes(33, 4)?.try_into().unwrap());
+        let credential_id_length = u16::from_be_bytes(bytes(53, 2)?.try_into().unwrap());
+
+        let auth_data = AuthenticatorData {
+            rp_id_hash: bytes(0, 32)?,
+            counter,
+            aaguid: bytes(37, 16)?,
+            credential_id: bytes(55, credential_id_length.into())?,
+        };
+
+        Ok(auth_data)
+    }
+}
+
+/// Extract the nonce from the credential certificate, which is encoded as `SEQUENCE { [1] EXPLICIT OCTET STRING }`.
+fn nonce<'a>(certificate: &X509Certificate<'a>) -> Result<Cow<'a, [u8]>, AppleAttestationError> {
+    let extension = certificate
+        .iter_extensions()
+        .find(|extension| extension.oid == NONCE_OID)
+        .ok_or(AppleAttestationError::MissingNonce)?;
+
+    let (_, sequence) = Any::from_der(extension.value).map_err(asn1_rs::Error::from)?;
+    sequence.header.tag().assert_eq(Tag::Sequence)?;
+
+    let (_, tagged) = Any::from_der(sequence.data).map_err(asn1_rs::Error::from)?;
+    if tagged.header.class() != Class::ContextSpecific || tagged.header.tag() != Tag(1) {
+        return Err(AppleAttestationError::MissingNonce);
+    }
+
+    let (_, nonce) = Any::from_der(tagged.data).map_err(asn1_rs::Error::from)?;
+    let nonce = nonce.octetstring()?.into_cow();
+
+    Ok(nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use p256::ecdsa::SigningKey;
+    use rand::rngs::OsRng;
+    use rstest::rstest;
+
+    use wallet_provider_domain::generator::mock::MockGenerators;
+
+    use super::{
+        super::mock::{
+            apple_attestation_object, MockAppleAttestation, MockAttestationCa, APPLE_BUNDLE_ID, APPLE_TEAM_ID,
+        },
+        *,
+    };
+
+    const CHALLENGE: &[u8] = b"registration_challenge";
+
+    fn config(ca: &MockAttestationCa) -> AppleAttestationConfig {
+        AppleAttestationConfig {
+            root_certificates: vec![ca.trust_anchor()],
+            team_id: APPLE_TEAM_ID.to_string(),
+            bundle_id: APPLE_BUNDLE_ID.to_string(),
+            environment: AppleEnvironment::Production,
+        }
+    }
+
+    #[rstest]
+    #[case(AppleEnvironment::Production, *AAGUID_PRODUCTION)]
+    #[case(AppleEnvironment::Development, *AAGUID_DEVELOPMENT)]
+    fn test_apple_attestation_verify(#[case] environment: AppleEnvironment, #[case] aaguid: [u8; 16]) {
+        let ca = MockAttestationCa::generate();
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let attestation_object = apple_attestation_object(
+            &ca,
+            &MockAppleAttestation {
+                aaguid,
+                ..MockAppleAttestation::new(CHALLENGE, &hw_pubkey)
+            },
+        );
+
+        let config = AppleAttestationConfig {
+            environment,
+            ..config(&ca)
+        };
+        let outcome = config
+            .verify(&attestation_object, CHALLENGE, &hw_pubkey, &MockGenerators)
+            .expect("Apple app attestation should be valid");
+
+        assert_eq!(outcome, WalletUserPlatformAttestation::AppleAppAttest);
+    }
+
+    #[rstest]
+    #[case(
+        |attestation: &mut MockAppleAttestation| attestation.client_data_hash = b"other_client_data_hash".to_vec(),
+        |error: &AppleAttestationError| matches!(error, AppleAttestationError::NonceMismatch)
+    )]
+    #[case(
+        |attestation: &mut MockAppleAttestation| attestation.app_id = format!("{APPLE_TEAM_ID}.com.example.other"),
+        |error: &AppleAttestationError| matches!(error, AppleAttestationError::AppIdMismatch)
+    )]
+    #[case(
+        |attestation: &mut MockAppleAttestation| attestation.counter = 1,
+        |error: &AppleAttestationError| matches!(error, AppleAttestationError::CounterNotZero(1))
+    )]
+    #[case(
+        |attestation: &mut MockAppleAttestation| attestation.aaguid = *AAGUID_DEVELOPMENT,
+        |error: &AppleAttestationError| matches!(error, AppleAttestationError::AaguidMismatch(AppleEnvironment::Production))
+    )]
+    fn test_apple_attestation_verify_error_attestation(
+        #[case] modify_attestation: impl Fn(&mut MockAppleAttestation),
+        #[case] is_expected_error: impl Fn(&AppleAttestationError) -> bool,
+    ) {
+        let ca = MockAttestationCa::generate();
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let mut attestation = MockAppleAttestation::new(CHALLENGE, &hw_pubkey);
+        modify_attestation(&mut attestation);
+        let attestation_object = apple_attestation_object(&ca, &attestation);
+
+        let error = config(&ca)
+            .verify(&attestation_object, CHALLENGE, &hw_pubkey, &MockGenerators)
+            .expect_err("Apple app attestation should not be valid");
+
+        assert!(is_expected_error(&error), "unexpected error: {error:?}");
+    }
+
+    #[test]
+    fn test_apple_attestation_verify_error_hw_pubkey_mismatch() {
+        let ca = MockAttestationCa::generate();
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let attestation_object = apple_attestation_object(&ca, &MockAppleAttestation::new(CHALLENGE, &hw_pubkey));
+
+        let other_hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let error = config(&ca)
+            .verify(&attestation_object, CHALLENGE, &other_hw_pubkey, &MockGenerators)
+            .expect_err("Apple app attestation should not be valid");
+
+        assert_matches!(error, AppleAttestationError::NonceMismatch);
+    }
+
+    #[test]
+    fn test_apple_attestation_verify_error_untrusted_root() {
+        let ca = MockAttestationCa::generate();
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let attestation_object = apple_attestation_object(&ca, &MockAppleAttestation::new(CHALLENGE, &hw_pubkey));
+
+        let error = config(&MockAttestationCa::generate())
+            .verify(&attestation_object, CHALLENGE, &hw_pubkey, &MockGenerators)
+            .expect_err("Apple app attestation should not be valid");
+
+        assert_matches!(error, AppleAttestationError::CertificateChain(_));
+
+        let error = config(&ca)
+            .verify(b"not cbor", CHALLENGE, &hw_pubkey, &MockGenerators)
+            .expect_err("Apple app attestation should not be valid");
+
+        assert_matches!(error, AppleAttestationError::Decoding(_));
+    }
+}
@@ -0,0 +1,469 @@
+//! Verification of the platform attestation that a wallet may include in its registration, which proves that the
+//! hardware key was generated in secure hardware by a genuine instance of the Wallet app.
+
+mod android;
+mod apple;
+
+use chrono::{DateTime, Local};
+use p256::ecdsa::VerifyingKey;
+use serde::Deserialize;
+use webpki::{EndEntityCert, KeyUsage, SignatureAlgorithm, Time, TrustAnchor};
+
+use wallet_common::{account::messages::auth::PlatformAttestation, generator::Generator, trust_anchor::DerTrustAnchor};
+use wallet_provider_domain::model::wallet_user::WalletUserPlatformAttestation;
+
+pub use android::{AndroidAttestationConfig, AndroidAttestationError, AndroidSecurityLevel};
+pub use apple::{AppleAttestationConfig, AppleAttestationError, AppleEnvironment};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlatformAttestationError {
+    #[error("platform attestation is required, but none was provided")]
+    Missing,
+    #[error("platform attestation for {0} is not configured")]
+    NotConfigured(&'static str),
+    #[error("Android key attestation verification error: {0}")]
+    Android(#[from] AndroidAttestationError),
+    #[error("Apple app attestation verification error: {0}")]
+    Apple(#[from] AppleAttestationError),
+}
+
+/// Configuration of the platform attestations accepted during registration. A platform attestation for a platform
+/// that is not configured is rejected. When `required` is set, registrations without a platform attestation are
+/// rejected as well.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PlatformAttestationVerifier {
+    #[serde(default)]
+    pub required: bool,
+    pub android: Option<AndroidAttestationConfig>,
+    pub apple: Option<AppleAttestationConfig>,
+}
+
+impl PlatformAttestationVerifier {
+    /// Verify the platform attestation of `hw_pubkey`, which should be bound to the registration `challenge`.
+    /// Returns the outcome to be stored with the wallet user, if a platform attestation was provided.
+    pub fn verify(
+        &self,
+        platform_attestation: Option<&PlatformAttestation>,
+        challenge: &[u8],
+        hw_pubkey: &VerifyingKey,
+        time: &impl Generator<DateTime<Local>>,
+    ) -> Result<Option<WalletUserPlatformAttestation>, PlatformAttestationError> {
+        let outcome = match platform_attestation {
+            None if self.required => return Err(PlatformAttestationError::Missing),
+            None => return Ok(None),
+            Some(PlatformAttestation::Android { certificate_chain }) => self
+                .android
+                .as_ref()
+                .ok_or(PlatformAttestationError::NotConfigured("Android"))?
+                .verify(certificate_chain, challenge, hw_pubkey, time)?,
+            Some(PlatformAttestation::Apple { attestation_object }) => self
+                .apple
+                .as_ref()
+                .ok_or(PlatformAttestationError::NotConfigured("Apple"))?
+                .verify(attestation_object, challenge, hw_pubkey, time)?,
+        };
+
+        Ok(Some(outcome))
+    }
+}
+
+/// The signature algorithms used by the Google and Apple attestation certificate chains.
+static SUPPORTED_SIG_ALGS: &[&SignatureAlgorithm] = &[
+    &webpki::ECDSA_P256_SHA256,
+    &webpki::ECDSA_P256_SHA384,
+    &webpki::ECDSA_P384_SHA256,
+    &webpki::ECDSA_P384_SHA384,
+    &webpki::RSA_PKCS1_2048_8192_SHA256,
+    &webpki::RSA_PKCS1_2048_8192_SHA384,
+    &webpki::RSA_PKCS1_2048_8192_SHA512,
+];
+
+/// Verify a certificate chain, starting with the leaf certificate, against the configured root certificates.
+fn verify_certificate_chain(
+    certificate_chain: &[Vec<u8>],
+    root_certificates: &[DerTrustAnchor],
+    time: &impl Generator<DateTime<Local>>,
+) -> Result<(), webpki::Error> {
+    let (leaf, intermediates) = certificate_chain.split_first().ok_or(webpki::Error::BadDer)?;
+    let intermediates = intermediates.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let trust_anchors = root_certificates
+        .iter()
+        .map(|anchor| TrustAnchor::from(&anchor.owned_trust_anchor))
+        .collect::<Vec<_>>();
+
+    EndEntityCert::try_from(leaf.as_slice())?.verify_for_usage(
+        SUPPORTED_SIG_ALGS,
+        &trust_anchors,
+        &intermediates,
+        Time::from_seconds_since_unix_epoch(time.generate().timestamp() as u64),
+        KeyUsage::client_auth(),
+        &[],
+    )
+}
+
+/// Generates attestation fixtures that are signed by a generated CA, with the same structure as the ones produced by
+/// Android and iOS devices.
+#[cfg(test)]
+pub(crate) mod mock {
+    use ciborium::Value;
+    use p256::{
+        ecdsa::{SigningKey, VerifyingKey},
+        pkcs8::{EncodePrivateKey, EncodePublicKey},
+    };
+    use rcgen::{
+        BasicConstraints, Certificate, CertificateParams, CustomExtension, IsCa, KeyPair, PKCS_ECDSA_P256_SHA256,
+    };
+    use sha2::{Digest, Sha256};
+    use yasna::Tag;
+
+    use wallet_common::trust_anchor::DerTrustAnchor;
+
+    pub const ANDROID_PACKAGE_NAME: &str = "nl.ictu.edi.wallet.latest";
+    pub const ANDROID_SIGNING_CERTIFICATE_DIGEST: [u8; 32] = [0xab; 32];
+    pub const APPLE_TEAM_ID: &str = "XGL6UKBPLP";
+    pub const APPLE_BUNDLE_ID: &str = "nl.ictu.edi.wallet.latest";
+
+    /// The Google hardware attestation root certificate, as published by Google, which uses an RSA 4096 key.
+    pub const GOOGLE_HARDWARE_ATTESTATION_ROOT: &str = "MIIFYDCCA0igAwIBAgIJAOj6GWMU0voYMA0GCSqGSIb3DQEBCwUAMBsxGTAX\
+                                                        BgNVBAUTEGY5MjAwOWU4NTNiNmIwNDUwHhcNMTYwNTI2MTYyODUyWhcNMjYw\
+                                                        NTI0MTYyODUyWjAbMRkwFwYDVQQFExBmOTIwMDllODUzYjZiMDQ1MIICIjAN\
+                                                        BgkqhkiG9w0BAQEFAAOCAg8AMIICCgKCAgEAr7bHgiuxpwHsK7Qui8xUFmOr\
+                                                        75gvMsd/dTEDDJdSSxtf6An7xyqpRR90PL2abxM1dEqlXnf2tqw1Ne4Xwl5j\
+                                                        lRfdnJLmN0pTy/4lj4/7tv0Sk3iiKkypnEUtR6WfMgH0QZfKHM1+di+y9TFR\
+                                                        tv6y//0rb+T+W8a9nsNL/ggjnar86461qO0rOs2cXjp3kOG1FEJ5MVmFmBGt\
+                                                        nrKpa73XpXyTqRxB/M0n1n/W9nGqC4FSYa04T6N5RIZGBN2z2MT5IKGbFlbC\
+                                                        8UrW0DxW7AYImQQcHtGl/m00QLVWutHQoVJYnFPlXTcHYvASLu+RhhsbDmxM\
+                                                        gJJ0mcDpvsC4PjvB+TxywElgS70vE0XmLD+OJtvsBslHZvPBKCOdT0MS+tgS\
+                                                        OIfga+z1Z1g7+DVagf7quvmag8jfPioyKvxnK/EgsTUVi2ghzq8wm27ud/mI\
+                                                        M7AY2qEORR8Go3TVB4HzWQgpZrt3i5MIlCaY504LzSRiigHCzAPlHws+W0rB\
+                                                        5N+er5/2pJKnfBSDiCiFAVtCLOZ7gLiMm0jhO2B6tUXHI/+MRPjy02i59lIN\
+                                                        MRRev56GKtcd9qO/0kUJWdZTdA2XoS82ixPvZtXQpUpuL12ab+9EaDK8Z4RH\
+                                                        JYYfCT3Q5vNAXaiWQ+8PTWm2QgBR/bkwSWc+NpUFgNPN9PvQi8WEg5UmAGMC\
+                                                        AwEAAaOBpjCBozAdBgNVHQ4EFgQUNmHhAHyIBQlRi0RsR/8aTMnqTxIwHwYD\
+                                                        VR0jBBgwFoAUNmHhAHyIBQlRi0RsR/8aTMnqTxIwDwYDVR0TAQH/BAUwAwEB\
+                                                        /zAOBgNVHQ8BAf8EBAMCAYYwQAYDVR0fBDkwNzA1oDOgMYYvaHR0cHM6Ly9h\
+                                                        bmRyb2lkLmdvb2dsZWFwaXMuY29tL2F0dGVzdGF0aW9uL2NybC8wDQYJKoZI\
+                                                        hvcNAQELBQADggIBACDIw41L3KlXG0aMiS//cqrG+EShHUGo8HNsw30W1kJt\
+                                                        jn6UBwRM6jnmiwfBPb8VA91chb2vssAtX2zbTvqBJ9+LBPGCdw/E53Rbf86q\
+                                                        hxKaiAHOjpvAy5Y3m00mqC0w/Zwvju1twb4vhLaJ5NkUJYsUS7rmJKHHBnET\
+                                                        Li8GFqiEsqTWpG/6ibYCv7rYDBJDcR9W62BW9jfIoBQcxUCUJouMPH25lLNc\
+                                                        Dc1ssqvC2v7iUgI9LeoM1sNovqPmQUiG9rHli1vXxzCyaMTjwftkJLkf6724\
+                                                        DFhuKug2jITV0QkXvaJWF4nUaHOTNA4uJU9WDvZLI1j83A+/xnAJUucIv/zG\
+                                                        J1AMH2boHqF8CY16LpsYgBt6tKxxWH00XcyDCdW2KlBCeqbQPcsFmWyWugxd\
+                                                        cekhYsAWyoSf818NUsZdBWBaR/OukXrNLfkQ79IyZohZbvabO/X+MVT3rriA\
+                                                        oKc8oE2Uws6DF+60PV7/WIPjNvXySdqspImSN78mflxDqwLqRBYkA3I75qpp\
+                                                        LGG9rp7UCdRjxMl8ZDBld+7yvHVgt1cVzJx9xnyGCC23UaicMDSXYrB4I4WH\
+                                                        XPGjxhZuCuPBLTdOLU8YRvMYdEvYebWHMpvwGCF6bAx3JBpIeOQ1wDB5y0US\
+                                                        icV3YgYGmi+NZfhA4URSh77Yd6uuJOJENRaNVTzk";
+
+    /// The Apple App Attestation root certificate, as published by Apple, which uses a P-384 key.
+    pub const APPLE_APP_ATTESTATION_ROOT: &str = "MIICITCCAaegAwIBAgIQC/O+DvHN0uD7jG5yH2IXmDAKBggqhkjOPQQDAzBSMSYwJAYD\
+                                                  VQQDDB1BcHBsZSBBcHAgQXR0ZXN0YXRpb24gUm9vdCBDQTETMBEGA1UECgwKQXBwbGUg\
+                                                  SW5jLjETMBEGA1UECAwKQ2FsaWZvcm5pYTAeFw0yMDAzMTgxODMyNTNaFw00NTAzMTUw\
+                                                  MDAwMDBaMFIxJjAkBgNVBAMMHUFwcGxlIEFwcCBBdHRlc3RhdGlvbiBSb290IENBMRMw\
+                                                  EQYDVQQKDApBcHBsZSBJbmMuMRMwEQYDVQQIDApDYWxpZm9ybmlhMHYwEAYHKoZIzj0C\
+                                                  AQYFK4EEACIDYgAERTHhmLW07ATaFQIEVwTtT4dyctdhNbJhFs/Ii2FdCgAHGbpphY3+\
+                                                  d8qjuDngIN3WVhQUBHAoMeQ/cLiP1sOUtgjqK9auYen1mMEvRq9Sk3Jm5X8U62H+xTD3\
+                                                  FE9TgS41o0IwQDAPBgNVHRMBAf8EBTADAQH/MB0GA1UdDgQWBBSskRBTM72+aEH/pwyp\
+                                                  5frq5eWKoTAOBgNVHQ8BAf8EBAMCAQYwCgYIKoZIzj0EAwMDaAAwZQIwQgFGnByvsiVb\
+                                                  pTKwSga0kP0e8EeDS4+sQmTvb7vn53O5+FRXgeLhpJ06ysC5PrOyAjEAp5U4xDgEgllF\
+                                                  7En3VcE3iexZZtKeYnpqtijVoyFraWVIyd/dganmrduC1bmTBGwD";
+
+    pub struct MockAttestationCa(Certificate);
+
+    impl MockAttestationCa {
+        pub fn generate() -> Self {
+            let mut params = CertificateParams::new(vec![]);
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params.not_before = rcgen::date_time_ymd(1970, 1, 1);
+
+            MockAttestationCa(Certificate::from_params(params).unwrap())
+        }
+
+        pub fn certificate(&self) -> Vec<u8> {
+            self.0.serialize_der().unwrap()
+        }
+
+        pub fn trust_anchor(&self) -> DerTrustAnchor {
+            DerTrustAnchor::from_der(self.certificate()).unwrap()
+        }
+
+        fn issue(&self, key_pair: KeyPair, extension: CustomExtension) -> Vec<u8> {
+            let mut params = CertificateParams::new(vec![]);
+            params.alg = &PKCS_ECDSA_P256_SHA256;
+            params.key_pair = Some(key_pair);
+            params.not_before = rcgen::date_time_ymd(1970, 1, 1);
+            params.custom_extensions = vec![extension];
+
+            Certificate::from_params(params)
+                .unwrap()
+                .serialize_der_with_signer(&self.0)
+                .unwrap()
+        }
+    }
+
+    /// The values of an Android `KeyDescription` that are verified.
+    pub struct MockKeyDescription {
+        pub attestation_challenge: Vec<u8>,
+        pub security_level: i64,
+        pub package_names: Vec<String>,
+        pub signature_digests: Vec<Vec<u8>>,
+        pub device_locked: bool,
+    }
+
+    impl MockKeyDescription {
+        pub fn new(challenge: &[u8]) -> Self {
+            MockKeyDescription {
+                attestation_challenge: Sha256::digest(challenge).to_vec(),
+                security_level: 1,
+                package_names: vec![ANDROID_PACKAGE_NAME.to_string()],
+                signature_digests: vec![ANDROID_SIGNING_CERTIFICATE_DIGEST.to_vec()],
+                device_locked: true,
+            }
+        }
+
+        fn to_der(&self) -> Vec<u8> {
+            let application_id = yasna::construct_der(|writer| {
+                writer.write_sequence(|writer| {
+                    writer.next().write_set_of(|writer| {
+                        for package_name in &self.package_names {
+                            writer.next().write_sequence(|writer| {
+                                writer.next().write_bytes(package_name.as_bytes());
+                                writer.next().write_i64(1);
+                            });
+                        }
+                    });
+                    writer.next().write_set_of(|writer| {
+                        for signature_digest in &self.signature_digests {
+                            writer.next().write_bytes(signature_digest);
+                        }
+                    });
+                })
+            });
+
+            yasna::construct_der(|writer| {
+                writer.write_sequence(|writer| {
+                    writer.next().write_u32(300);
+                    writer.next().write_enum(self.security_level);
+                    writer.next().write_u32(300);
+                    writer.next().write_enum(self.security_level);
+                    writer.next().write_bytes(&self.attestation_challenge);
+                    writer.next().write_bytes(&[]);
+                    writer.next().write_sequence(|writer| {
+                        writer
+                            .next()
+                            .write_tagged(Tag::context(709), |writer| writer.write_bytes(&application_id));
+                    });
+                    writer.next().write_sequence(|writer| {
+                        writer.next().write_tagged(Tag::context(704), |writer| {
+                            writer.write_sequence(|writer| {
+                                writer.next().write_bytes(&[0; 32]);
+                                writer.next().write_bool(self.device_locked);
+                                writer.next().write_enum(0);
+                                writer.next().write_bytes(&[0; 32]);
+                            })
+                        });
+                    });
+                })
+            })
+        }
+    }
+
+    /// Generate an Android Key Attestation certificate chain for `hw_privkey`, ending with the CA certificate.
+    pub fn android_certificate_chain(
+        ca: &MockAttestationCa,
+        hw_privkey: &SigningKey,
+        key_description: &MockKeyDescription,
+    ) -> Vec<Vec<u8>> {
+        let key_pair = KeyPair::from_der(hw_privkey.to_pkcs8_der().unwrap().as_bytes()).unwrap();
+        let extension =
+            CustomExtension::from_oid_content(&[1, 3, 6, 1, 4, 1, 11129, 2, 1, 17], key_description.to_der());
+
+        vec![ca.issue(key_pair, extension), ca.certificate()]
+    }
+
+    /// The values of an Apple App Attest attestation object that are verified.
+    pub struct MockAppleAttestation {
+        pub client_data_hash: Vec<u8>,
+        pub app_id: String,
+        pub counter: u32,
+        pub aaguid: [u8; 16],
+    }
+
+    impl MockAppleAttestation {
+        pub fn new(challenge: &[u8], hw_pubkey: &VerifyingKey) -> Self {
+            let client_data_hash = Sha256::new()
+                .chain_update(challenge)
+                .chain_update(hw_pubkey.to_public_key_der().unwrap().as_bytes())
+                .finalize()
+                .to_vec();
+
+            MockAppleAttestation {
+                client_data_hash,
+                app_id: format!("{APPLE_TEAM_ID}.{APPLE_BUNDLE_ID}"),
+                counter: 0,
+                aaguid: *b"appattest\0\0\0\0\0\0\0",
+            }
+        }
+    }
+
+    /// Generate an Apple App Attest attestation object for a newly generated App Attest key.
+    pub fn apple_attestation_object(ca: &MockAttestationCa, attestation: &MockAppleAttestation) -> Vec<u8> {
+        let key_pair = KeyPair::generate(&PKCS_ECDSA_P256_SHA256).unwrap();
+        let credential_id = Sha256::digest(key_pair.public_key_raw());
+
+        let auth_data = [
+            Sha256::digest(&attestation.app_id).as_slice(),
+            &[0x40],
+            &attestation.counter.to_be_bytes(),
+            &attestation.aaguid,
+            &(credential_id.len() as u16).to_be_bytes(),
+            &credential_id,
+        ]
+        .concat();
+
+        let nonce = Sha256::new()
+            .chain_update(&auth_data)
+            .chain_update(&attestation.client_data_hash)
+            .finalize();
+        let extension = CustomExtension::from_oid_content(
+            &[1, 2, 840, 113635, 100, 8, 2],
+            yasna::construct_der(|writer| {
+                writer.write_sequence(|writer| {
+                    writer
+                        .next()
+                        .write_tagged(Tag::context(1), |writer| writer.write_bytes(&nonce));
+                })
+            }),
+        );
+        let credential_certificate = ca.issue(key_pair, extension);
+
+        let attestation_object = Value::Map(vec![
+            (
+                Value::Text("fmt".to_string()),
+                Value::Text("apple-appattest".to_string()),
+            ),
+            (
+                Value::Text("attStmt".to_string()),
+                Value::Map(vec![
+                    (
+                        Value::Text("x5c".to_string()),
+                        Value::Array(vec![Value::Bytes(credential_certificate)]),
+                    ),
+                    (Value::Text("receipt".to_string()), Value::Bytes(vec![])),
+                ]),
+            ),
+            (Value::Text("authData".to_string()), Value::Bytes(auth_data)),
+        ]);
+
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&attestation_object, &mut bytes).unwrap();
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use p256::ecdsa::SigningKey;
+    use rand::rngs::OsRng;
+
+    use wallet_provider_domain::generator::mock::MockGenerators;
+
+    use super::{mock::*, *};
+
+    /// Deserialize the verifier in the same way as the Wallet Provider settings, using the published root
+    /// certificates of Google and Apple.
+    fn published_roots_verifier() -> PlatformAttestationVerifier {
+        serde_json::from_value(serde_json::json!({
+            "required": true,
+            "android": {
+                "root_certificates": [GOOGLE_HARDWARE_ATTESTATION_ROOT],
+                "package_names": [ANDROID_PACKAGE_NAME],
+                "signing_certificate_digests": [hex::encode(ANDROID_SIGNING_CERTIFICATE_DIGEST)],
+            },
+            "apple": {
+                "root_certificates": [APPLE_APP_ATTESTATION_ROOT],
+                "team_id": APPLE_TEAM_ID,
+                "bundle_id": APPLE_BUNDLE_ID,
+            },
+        }))
+        .expect("published root certificates should be valid trust anchors")
+    }
+
+    #[test]
+    fn test_platform_attestation_verifier_missing() {
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+
+        let outcome = PlatformAttestationVerifier::default()
+            .verify(None, b"challenge", &hw_pubkey, &MockGenerators)
+            .expect("missing platform attestation should be accepted when not required");
+        assert!(outcome.is_none());
+
+        let verifier = PlatformAttestationVerifier {
+            required: true,
+            ..Default::default()
+        };
+        let error = verifier
+            .verify(None, b"challenge", &hw_pubkey, &MockGenerators)
+            .expect_err("missing platform attestation should be rejected when required");
+        assert_matches!(error, PlatformAttestationError::Missing);
+    }
+
+    #[test]
+    fn test_platform_attestation_verifier_not_configured() {
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let platform_attestation = PlatformAttestation::Apple {
+            attestation_object: vec![],
+        };
+
+        let error = PlatformAttestationVerifier::default()
+            .verify(Some(&platform_attestation), b"challenge", &hw_pubkey, &MockGenerators)
+            .expect_err("platform attestation for a platform that is not configured should be rejected");
+        assert_matches!(error, PlatformAttestationError::NotConfigured("Apple"));
+    }
+
+    #[test]
+    fn test_platform_attestation_verifier_published_roots_android() {
+        let hw_privkey = SigningKey::random(&mut OsRng);
+        let challenge = b"challenge";
+        let certificate_chain = android_certificate_chain(
+            &MockAttestationCa::generate(),
+            &hw_privkey,
+            &MockKeyDescription::new(challenge),
+        );
+        let platform_attestation = PlatformAttestation::Android { certificate_chain };
+
+        let error = published_roots_verifier()
+            .verify(
+                Some(&platform_attestation),
+                challenge,
+                hw_privkey.verifying_key(),
+                &MockGenerators,
+            )
+            .expect_err("Android key attestation not issued by Google should be rejected");
+        assert_matches!(
+            error,
+            PlatformAttestationError::Android(AndroidAttestationError::CertificateChain(webpki::Error::UnknownIssuer))
+        );
+    }
+
+    #[test]
+    fn test_platform_attestation_verifier_published_roots_apple() {
+        let hw_pubkey = *SigningKey::random(&mut OsRng).verifying_key();
+        let challenge = b"challenge";
+        let attestation_object = apple_attestation_object(
+            &MockAttestationCa::generate(),
+            &MockAppleAttestation::new(challenge, &hw_pubkey),
+        );
+        let platform_attestation = PlatformAttestation::Apple { attestation_object };
+
+        let error = published_roots_verifier()
+            .verify(Some(&platform_attestation), challenge, &hw_pubkey, &MockGenerators)
+            .expect_err("Apple app attestation not issued by Apple should be rejected");
+        assert_matches!(
+            error,
+            PlatformAttestationError::Apple(AppleAttestationError::CertificateChain(webpki::Error::UnknownIssuer))
+        );
+    }
+}
@@ -1,7 +1,9 @@
-use p256::ecdsa::SigningKey;
+use chrono::{DateTime, Local};
+use p256::ecdsa::{SigningKey, VerifyingKey};
 use rand::rngs::OsRng;
 use uuid::Uuid;
 
+use hsm::service::HsmError;
 use wallet_common::{
     account::messages::{
         auth::{Registration, WalletCertificate, WalletCertificateClaims},
@@ -12,14 +14,13 @@ use wallet_common::{
 };
 use wallet_provider_database_settings::Settings;
 use wallet_provider_domain::{
-    model::{hsm::mock::MockPkcs11Client, wallet_user::WalletUserQueryResult},
+    model::{hsm::mock::MockPkcs11Client, wallet_user::WalletUserQueryResult, FailingPinPolicy},
     repository::{PersistenceError, TransactionStarter, WalletUserRepository},
     EpochGenerator,
 };
 use wallet_provider_persistence::{database::Db, repositories::Repositories};
 use wallet_provider_service::{
     account_server::{mock, AccountServer},
-    hsm::HsmError,
     keys::CertificateSigningKey,
 };
 
@@ -30,6 +31,12 @@ impl Generator<Uuid> for UuidGenerator {
     }
 }
 
+impl Generator<DateTime<Local>> for UuidGenerator {
+    fn generate(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
 async fn db_from_env() -> Result<Db, PersistenceError> {
     let _ = tracing::subscriber::set_global_default(
         tracing_subscriber::fmt()
@@ -64,6 +71,7 @@ async fn do_registration(
             certificate_signing_key,
             &UuidGenerator,
             repos,
+            &FailingPinPolicy,
             hsm,
             registration_message,
         )
@@ -80,11 +88,15 @@ async fn do_registration(
 async fn assert_instruction_data(
     repos: &Repositories,
     wallet_id: &str,
+    hw_pubkey: &VerifyingKey,
     expected_sequence_number: u64,
     has_challenge: bool,
 ) {
     let tx = repos.begin_transaction().await.unwrap();
-    let user_result = repos.find_wallet_user_by_wallet_id(&tx, wallet_id).await.unwrap();
+    let user_result = repos
+        .find_wallet_user_by_wallet_id(&tx, wallet_id, hw_pubkey)
+        .await
+        .unwrap();
     match user_result {
         WalletUserQueryResult::Found(user_boxed) => {
             let user = *user_boxed;
@@ -133,7 +145,7 @@ async fn test_instruction_challenge() {
         .await
         .unwrap();
 
-    assert_instruction_data(&repos, &cert_data.wallet_id, 1, true).await;
+    assert_instruction_data(&repos, &cert_data.wallet_id, hw_privkey.verifying_key(), 1, true).await;
 
     let challenge2 = account_server
         .instruction_challenge(
@@ -150,7 +162,7 @@ async fn test_instruction_challenge() {
         .await
         .unwrap();
 
-    assert_instruction_data(&repos, &cert_data.wallet_id, 2, true).await;
+    assert_instruction_data(&repos, &cert_data.wallet_id, hw_privkey.verifying_key(), 2, true).await;
 
     assert_ne!(challenge1, challenge2);
 }
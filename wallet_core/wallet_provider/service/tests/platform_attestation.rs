@@ -0,0 +1,95 @@
+//! Verifies platform attestations that were recorded on real devices, as opposed to the unit tests that use
+//! attestations signed by a generated CA. Every fixture in `tests/fixtures/platform_attestation` is a JSON file
+//! containing:
+//!
+//! - `verifier`: the `platform_attestation` settings of the Wallet Provider, using the published root certificates;
+//! - `platform_attestation`, `challenge` and `hw_pubkey`: taken from a registration of the Wallet app on the device,
+//!   where `challenge` and the DER encoded `hw_pubkey` are base64 encoded;
+//! - `date_time`: the moment of the registration, at which the certificates were valid.
+//!
+//! At least one Android Key Attestation and one Apple App Attest fixture should be present.
+
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, Local};
+use p256::{ecdsa::VerifyingKey, pkcs8::DecodePublicKey};
+use serde::Deserialize;
+use serde_with::{base64::Base64, serde_as};
+
+use wallet_common::{account::messages::auth::PlatformAttestation, generator::Generator};
+use wallet_provider_service::platform_attestation::PlatformAttestationVerifier;
+
+#[serde_as]
+#[derive(Deserialize)]
+struct RecordedAttestation {
+    verifier: PlatformAttestationVerifier,
+    platform_attestation: PlatformAttestation,
+    #[serde_as(as = "Base64")]
+    challenge: Vec<u8>,
+    #[serde_as(as = "Base64")]
+    hw_pubkey: Vec<u8>,
+    date_time: DateTime<Local>,
+}
+
+impl Generator<DateTime<Local>> for RecordedAttestation {
+    fn generate(&self) -> DateTime<Local> {
+        self.date_time
+    }
+}
+
+fn recorded_attestations() -> Vec<(PathBuf, RecordedAttestation)> {
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/platform_attestation");
+
+    fs::read_dir(&fixtures)
+        .expect("could not read platform attestation fixtures")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+        .map(|path| {
+            let attestation = serde_json::from_slice(&fs::read(&path).unwrap())
+                .unwrap_or_else(|e| panic!("could not parse {}: {e}", path.display()));
+
+            (path, attestation)
+        })
+        .collect()
+}
+
+#[test]
+fn test_verify_recorded_platform_attestations() {
+    let attestations = recorded_attestations();
+
+    for platform in ["android", "apple"] {
+        assert!(
+            attestations.iter().any(|(_, attestation)| matches!(
+                (&attestation.platform_attestation, platform),
+                (PlatformAttestation::Android { .. }, "android") | (PlatformAttestation::Apple { .. }, "apple")
+            )),
+            "no recorded platform attestation for {platform}"
+        );
+    }
+
+    for (path, attestation) in attestations {
+        let hw_pubkey = VerifyingKey::from_public_key_der(&attestation.hw_pubkey).unwrap();
+
+        attestation
+            .verifier
+            .verify(
+                Some(&attestation.platform_attestation),
+                &attestation.challenge,
+                &hw_pubkey,
+                &attestation,
+            )
+            .unwrap_or_else(|e| panic!("recorded platform attestation {} should be valid: {e}", path.display()))
+            .expect("recorded platform attestation should have an outcome");
+
+        // The same attestation should not be accepted for another registration.
+        attestation
+            .verifier
+            .verify(
+                Some(&attestation.platform_attestation),
+                b"other_challenge",
+                &hw_pubkey,
+                &attestation,
+            )
+            .expect_err("recorded platform attestation should not be valid for another challenge");
+    }
+}
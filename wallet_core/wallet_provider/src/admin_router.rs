@@ -0,0 +1,191 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{Json, Response},
+    routing::{get, post, put},
+    Extension, Router,
+};
+use serde::Deserialize;
+use tower_http::trace::TraceLayer;
+use tracing::{info, warn};
+
+use wallet_provider_domain::model::{
+    admin_audit_log::{AdminActor, AdminAuditLogEntry},
+    instruction_audit_log::InstructionAuditLog,
+    wallet_user::{WalletUserDetails, WalletUserState},
+};
+use wallet_provider_service::admin;
+
+use crate::{errors::AdminApiError, router_state::RouterState, settings::OperatorApiKeys};
+
+/// All handlers of the admin API should return this result. This API is meant to be used by the operations team
+/// of the Wallet Provider only, which is why every route requires the API key of one of the configured operators as
+/// bearer token.
+type Result<T> = std::result::Result<T, AdminApiError>;
+
+pub fn admin_router(router_state: Arc<RouterState>, operator_api_keys: OperatorApiKeys) -> Router {
+    Router::new().route("/health", get(|| async {})).nest(
+        "/admin/v1",
+        Router::new()
+            .route("/wallet_users/:wallet_id", get(wallet_user))
+            .route("/wallet_users/:wallet_id/unblock", post(unblock_wallet_user))
+            .route("/wallet_users/:wallet_id/state", put(update_wallet_user_state))
+            .route(
+                "/wallet_users/:wallet_id/instruction_audit_log",
+                get(instruction_audit_log),
+            )
+            .route("/audit_log", get(audit_log))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(operator_api_keys),
+                authenticate_operator,
+            ))
+            .layer(TraceLayer::new_for_http())
+            .with_state(router_state),
+    )
+}
+
+/// The name of the operator whose API key was used for a request.
+#[derive(Clone)]
+struct Operator(String);
+
+async fn authenticate_operator(
+    State(operator_api_keys): State<Arc<OperatorApiKeys>>,
+    mut request: Request,
+    next: Next,
+) -> std::result::Result<Response, StatusCode> {
+    let operator = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| {
+            operator_api_keys
+                .as_ref()
+                .as_ref()
+                .iter()
+                .find(|(_, api_key)| api_key.as_str() == token)
+        })
+        .map(|(operator, _)| Operator(operator.clone()))
+        .ok_or_else(|| {
+            warn!("Rejected admin request without a valid operator API key");
+            StatusCode::UNAUTHORIZED
+        })?;
+
+    request.extensions_mut().insert(operator);
+
+    Ok(next.run(request).await)
+}
+
+/// The reason for an admin action, which the operator can optionally give as query parameter.
+#[derive(Deserialize)]
+struct ReasonQuery {
+    reason: Option<String>,
+}
+
+fn actor(Operator(operator): Operator, ReasonQuery { reason }: ReasonQuery) -> AdminActor {
+    AdminActor { operator, reason }
+}
+
+async fn wallet_user(
+    State(state): State<Arc<RouterState>>,
+    Extension(operator): Extension<Operator>,
+    Path(wallet_id): Path<String>,
+    Query(reason): Query<ReasonQuery>,
+) -> Result<(StatusCode, Json<WalletUserDetails>)> {
+    info!("Received admin request to look up wallet user");
+
+    let details = admin::wallet_user_details(
+        &state.repositories,
+        state.as_ref(),
+        &actor(operator, reason),
+        &wallet_id,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, details.into()))
+}
+
+async fn unblock_wallet_user(
+    State(state): State<Arc<RouterState>>,
+    Extension(operator): Extension<Operator>,
+    Path(wallet_id): Path<String>,
+    Query(reason): Query<ReasonQuery>,
+) -> Result<StatusCode> {
+    info!("Received admin request to unblock wallet user");
+
+    admin::unblock_wallet_user(
+        &state.repositories,
+        state.as_ref(),
+        &actor(operator, reason),
+        &wallet_id,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+struct UpdateWalletUserState {
+    state: WalletUserState,
+}
+
+async fn update_wallet_user_state(
+    State(state): State<Arc<RouterState>>,
+    Extension(operator): Extension<Operator>,
+    Path(wallet_id): Path<String>,
+    Query(reason): Query<ReasonQuery>,
+    Json(payload): Json<UpdateWalletUserState>,
+) -> Result<StatusCode> {
+    info!("Received admin request to update wallet user state");
+
+    admin::update_wallet_user_state(
+        &state.repositories,
+        state.as_ref(),
+        &actor(operator, reason),
+        &wallet_id,
+        payload.state,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn instruction_audit_log(
+    State(state): State<Arc<RouterState>>,
+    Extension(operator): Extension<Operator>,
+    Path(wallet_id): Path<String>,
+    Query(reason): Query<ReasonQuery>,
+) -> Result<(StatusCode, Json<InstructionAuditLog>)> {
+    info!("Received admin request for the instruction audit log of a wallet user");
+
+    let log = admin::instruction_audit_log(
+        &state.repositories,
+        state.as_ref(),
+        &actor(operator, reason),
+        &state.hsm,
+        state.account_server.instruction_audit_log_key_identifier(),
+        &wallet_id,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, log.into()))
+}
+
+#[derive(Deserialize)]
+struct AuditLogQuery {
+    wallet_id: Option<String>,
+}
+
+async fn audit_log(
+    State(state): State<Arc<RouterState>>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<(StatusCode, Json<Vec<AdminAuditLogEntry>>)> {
+    info!("Received admin request for the audit log");
+
+    let entries = admin::audit_log(&state.repositories, query.wallet_id.as_deref()).await?;
+
+    Ok((StatusCode::OK, entries.into()))
+}
@@ -2,13 +2,15 @@ use axum::response::{IntoResponse, Response};
 use http::StatusCode;
 use nutype::nutype;
 
+use hsm::service::HsmError;
 use wallet_common::{
     account::messages::errors::{AccountError, AccountErrorType},
     http_error::{HttpJsonError, HttpJsonErrorType},
 };
 use wallet_provider_service::{
-    account_server::{ChallengeError, InstructionError, RegistrationError, WalletCertificateError},
-    hsm::HsmError,
+    account_server::{ChallengeError, DeviceError, InstructionError, RegistrationError, WalletCertificateError},
+    admin::AdminError,
+    pin_recovery::PinRecoveryError,
 };
 
 // Make a newtype to circumvent the orphan rule.
@@ -36,7 +38,12 @@ impl HttpJsonErrorType for WalletProviderErrorType {
             AccountErrorType::IncorrectPin => "The PIN provided is incorrect",
             AccountErrorType::PinTimeout => "PIN checking is currently in timeout",
             AccountErrorType::AccountBlocked => "The requested account is blocked",
+            AccountErrorType::AccountSuspended => "The requested account is suspended",
+            AccountErrorType::AccountRevoked => "The requested account is revoked",
             AccountErrorType::InstructionValidation => "Could not validate instruction",
+            AccountErrorType::PinRecoveryValidation => "Could not verify PIN recovery",
+            AccountErrorType::DeviceNotLinked => "The device is not linked to the requested account",
+            AccountErrorType::DeviceLinkValidation => "Could not validate device link",
         };
 
         title.to_string()
@@ -50,7 +57,12 @@ impl HttpJsonErrorType for WalletProviderErrorType {
             AccountErrorType::IncorrectPin => StatusCode::FORBIDDEN,
             AccountErrorType::PinTimeout => StatusCode::FORBIDDEN,
             AccountErrorType::AccountBlocked => StatusCode::UNAUTHORIZED,
+            AccountErrorType::AccountSuspended => StatusCode::UNAUTHORIZED,
+            AccountErrorType::AccountRevoked => StatusCode::UNAUTHORIZED,
             AccountErrorType::InstructionValidation => StatusCode::FORBIDDEN,
+            AccountErrorType::PinRecoveryValidation => StatusCode::FORBIDDEN,
+            AccountErrorType::DeviceNotLinked => StatusCode::UNAUTHORIZED,
+            AccountErrorType::DeviceLinkValidation => StatusCode::FORBIDDEN,
         }
     }
 }
@@ -60,6 +72,9 @@ impl From<WalletProviderError> for AccountError {
         match value {
             WalletProviderError::Challenge(error) => match error {
                 ChallengeError::WalletCertificate(WalletCertificateError::UserBlocked) => Self::AccountBlocked,
+                ChallengeError::WalletCertificate(WalletCertificateError::UserSuspended) => Self::AccountSuspended,
+                ChallengeError::WalletCertificate(WalletCertificateError::UserRevoked) => Self::AccountRevoked,
+                ChallengeError::WalletCertificate(WalletCertificateError::DeviceNotLinked) => Self::DeviceNotLinked,
                 ChallengeError::WalletCertificate(_) => Self::ChallengeValidation,
                 _ => Self::ChallengeValidation,
             },
@@ -73,13 +88,39 @@ impl From<WalletProviderError> for AccountError {
                 RegistrationError::JwtSigning(_) => Self::Unexpected,
                 RegistrationError::CertificateStorage(_) => Self::Unexpected,
                 RegistrationError::WalletCertificate(_) => Self::Unexpected,
+                RegistrationError::RestoreWalletCertificate(WalletCertificateError::UserBlocked) => {
+                    Self::AccountBlocked
+                }
+                RegistrationError::RestoreWalletCertificate(WalletCertificateError::UserSuspended) => {
+                    Self::AccountSuspended
+                }
+                RegistrationError::RestoreWalletCertificate(WalletCertificateError::UserRevoked) => {
+                    Self::AccountRevoked
+                }
+                RegistrationError::RestoreWalletCertificate(_) => Self::RegistrationParsing,
+                RegistrationError::RestorePin(evaluation) => match InstructionError::from(evaluation) {
+                    InstructionError::IncorrectPin(data) => Self::IncorrectPin(data),
+                    InstructionError::PinTimeout(data) => Self::PinTimeout(data),
+                    _ => Self::AccountBlocked,
+                },
+                RegistrationError::PlatformAttestation(_) => Self::RegistrationParsing,
                 RegistrationError::HsmError(_) => Self::Unexpected,
             },
             WalletProviderError::Instruction(error) => match error {
                 InstructionError::IncorrectPin(data) => Self::IncorrectPin(data),
                 InstructionError::PinTimeout(data) => Self::PinTimeout(data),
                 InstructionError::AccountBlocked => Self::AccountBlocked,
+                InstructionError::WalletCertificate(WalletCertificateError::UserSuspended) => Self::AccountSuspended,
+                InstructionError::WalletCertificate(WalletCertificateError::UserRevoked) => Self::AccountRevoked,
+                InstructionError::WalletCertificate(WalletCertificateError::DeviceNotLinked) => Self::DeviceNotLinked,
                 InstructionError::Validation(_) => Self::InstructionValidation,
+                InstructionError::PinRecovery(PinRecoveryError::NotConfigured) => Self::Unexpected,
+                InstructionError::PinRecovery(_) => Self::PinRecoveryValidation,
+                InstructionError::Device(DeviceError::RemoveCurrentDevice | DeviceError::UnknownDevice(_)) => {
+                    Self::InstructionValidation
+                }
+                InstructionError::KeyNotFound(_) => Self::InstructionValidation,
+                InstructionError::Device(_) => Self::DeviceLinkValidation,
                 InstructionError::Signing(_)
                 | InstructionError::Storage(_)
                 | InstructionError::WalletCertificate(_)
@@ -108,3 +149,53 @@ impl IntoResponse for WalletProviderError {
         HttpJsonError::<WalletProviderErrorType>::from(self).into_response()
     }
 }
+
+/// The error types of the admin API, which are distinct from the [`AccountErrorType`] used by the wallet.
+#[derive(Debug, Clone, Copy, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum AdminErrorType {
+    Unexpected,
+    WalletUserNotFound,
+    WalletUserRevoked,
+}
+
+impl HttpJsonErrorType for AdminErrorType {
+    fn title(&self) -> String {
+        let title = match self {
+            AdminErrorType::Unexpected => "An unexpected error occurred",
+            AdminErrorType::WalletUserNotFound => "The requested wallet user does not exist",
+            AdminErrorType::WalletUserRevoked => "The requested wallet user is revoked",
+        };
+
+        title.to_string()
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AdminErrorType::Unexpected => StatusCode::INTERNAL_SERVER_ERROR,
+            AdminErrorType::WalletUserNotFound => StatusCode::NOT_FOUND,
+            AdminErrorType::WalletUserRevoked => StatusCode::CONFLICT,
+        }
+    }
+}
+
+impl From<AdminApiError> for AdminErrorType {
+    fn from(value: AdminApiError) -> Self {
+        match value.0 {
+            AdminError::WalletUserNotFound(_) => AdminErrorType::WalletUserNotFound,
+            AdminError::WalletUserRevoked(_) => AdminErrorType::WalletUserRevoked,
+            AdminError::Persistence(_) | AdminError::Hsm(_) => AdminErrorType::Unexpected,
+        }
+    }
+}
+
+// Make a newtype to circumvent the orphan rule.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub struct AdminApiError(#[from] pub AdminError);
+
+impl IntoResponse for AdminApiError {
+    fn into_response(self) -> Response {
+        HttpJsonError::<AdminErrorType>::from_error(self).into_response()
+    }
+}
@@ -1,3 +1,4 @@
+pub mod admin_router;
 pub mod errors;
 pub mod router;
 pub mod router_state;
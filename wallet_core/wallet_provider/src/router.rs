@@ -14,10 +14,12 @@ use tracing::info;
 use wallet_common::{
     account::{
         messages::{
-            auth::{Certificate, Challenge, Registration},
+            auth::{Certificate, Challenge, DeviceLink, Registration, WalletCertificate},
             instructions::{
-                CheckPin, GenerateKey, GenerateKeyResult, Instruction, InstructionChallengeRequestMessage,
-                InstructionEndpoint, InstructionResultMessage, Sign, SignResult,
+                CheckPin, DiffieHellman, DiffieHellmanResult, GenerateKey, GenerateKeyResult, GetDevices,
+                GetDevicesResult, GetInstructionAuditLog, GetInstructionAuditLogResult, Instruction,
+                InstructionChallengeRequestMessage, InstructionEndpoint, InstructionResultMessage, RecoverPin,
+                RemoveDevice, Sign, SignResult, StartDeviceLink, StartDeviceLinkResult,
             },
         },
         serialization::DerVerifyingKey,
@@ -41,8 +43,7 @@ use crate::{errors::WalletProviderError, router_state::RouterState};
 /// be able to handle these errors appropriately.
 type Result<T> = std::result::Result<T, WalletProviderError>;
 
-pub fn router(router_state: RouterState) -> Router {
-    let state = Arc::new(router_state);
+pub fn router(state: Arc<RouterState>) -> Router {
     Router::new()
         .nest("/", health_router())
         .nest(
@@ -50,10 +51,29 @@ pub fn router(router_state: RouterState) -> Router {
             Router::new()
                 .route("/enroll", post(enroll))
                 .route("/createwallet", post(create_wallet))
+                .route("/linkdevice", post(link_device))
                 .route("/instructions/challenge", post(instruction_challenge))
                 .route(&format!("/instructions/{}", CheckPin::ENDPOINT), post(check_pin))
                 .route(&format!("/instructions/{}", GenerateKey::ENDPOINT), post(generate_key))
                 .route(&format!("/instructions/{}", Sign::ENDPOINT), post(sign))
+                .route(
+                    &format!("/instructions/{}", DiffieHellman::ENDPOINT),
+                    post(diffie_hellman),
+                )
+                .route(
+                    &format!("/instructions/{}", GetInstructionAuditLog::ENDPOINT),
+                    post(get_instruction_audit_log),
+                )
+                .route(&format!("/instructions/{}", RecoverPin::ENDPOINT), post(recover_pin))
+                .route(
+                    &format!("/instructions/{}", StartDeviceLink::ENDPOINT),
+                    post(start_device_link),
+                )
+                .route(&format!("/instructions/{}", GetDevices::ENDPOINT), post(get_devices))
+                .route(
+                    &format!("/instructions/{}", RemoveDevice::ENDPOINT),
+                    post(remove_device),
+                )
                 .layer(TraceLayer::new_for_http())
                 .with_state(Arc::clone(&state)),
         )
@@ -97,6 +117,7 @@ async fn create_wallet(
             &state.certificate_signing_key,
             state.as_ref(),
             &state.repositories,
+            &state.pin_policy,
             &state.hsm,
             payload,
         )
@@ -109,6 +130,31 @@ async fn create_wallet(
     Ok((StatusCode::CREATED, body.into()))
 }
 
+async fn link_device(
+    State(state): State<Arc<RouterState>>,
+    Json(payload): Json<SignedDouble<DeviceLink>>,
+) -> Result<(StatusCode, Json<Certificate>)> {
+    info!("Received link device request, linking device with account server");
+
+    let cert = state
+        .account_server
+        .link_device(
+            &state.certificate_signing_key,
+            state.as_ref(),
+            &state.repositories,
+            &state.pin_policy,
+            &state.hsm,
+            payload,
+        )
+        .await?;
+
+    let body = Certificate { certificate: cert };
+
+    info!("Replying with the wallet certificate for the linked device");
+
+    Ok((StatusCode::CREATED, body.into()))
+}
+
 async fn instruction_challenge(
     State(state): State<Arc<RouterState>>,
     Json(payload): Json<InstructionChallengeRequestMessage>,
@@ -154,6 +200,60 @@ async fn sign(
     Ok((StatusCode::OK, body.into()))
 }
 
+async fn diffie_hellman(
+    State(state): State<Arc<RouterState>>,
+    Json(payload): Json<Instruction<DiffieHellman>>,
+) -> Result<(StatusCode, Json<InstructionResultMessage<DiffieHellmanResult>>)> {
+    info!("Received diffie hellman request, handling the DiffieHellman instruction");
+    let body = state.handle_instruction(payload).await?;
+    Ok((StatusCode::OK, body.into()))
+}
+
+async fn get_instruction_audit_log(
+    State(state): State<Arc<RouterState>>,
+    Json(payload): Json<Instruction<GetInstructionAuditLog>>,
+) -> Result<(StatusCode, Json<InstructionResultMessage<GetInstructionAuditLogResult>>)> {
+    info!("Received instruction audit log request, handling the GetInstructionAuditLog instruction");
+    let body = state.handle_instruction(payload).await?;
+    Ok((StatusCode::OK, body.into()))
+}
+
+async fn recover_pin(
+    State(state): State<Arc<RouterState>>,
+    Json(payload): Json<Instruction<RecoverPin>>,
+) -> Result<(StatusCode, Json<InstructionResultMessage<WalletCertificate>>)> {
+    info!("Received recover pin request, handling the RecoverPin instruction");
+    let body = state.recover_pin(payload).await?;
+    Ok((StatusCode::OK, body.into()))
+}
+
+async fn start_device_link(
+    State(state): State<Arc<RouterState>>,
+    Json(payload): Json<Instruction<StartDeviceLink>>,
+) -> Result<(StatusCode, Json<InstructionResultMessage<StartDeviceLinkResult>>)> {
+    info!("Received start device link request, handling the StartDeviceLink instruction");
+    let body = state.handle_instruction(payload).await?;
+    Ok((StatusCode::OK, body.into()))
+}
+
+async fn get_devices(
+    State(state): State<Arc<RouterState>>,
+    Json(payload): Json<Instruction<GetDevices>>,
+) -> Result<(StatusCode, Json<InstructionResultMessage<GetDevicesResult>>)> {
+    info!("Received get devices request, handling the GetDevices instruction");
+    let body = state.handle_instruction(payload).await?;
+    Ok((StatusCode::OK, body.into()))
+}
+
+async fn remove_device(
+    State(state): State<Arc<RouterState>>,
+    Json(payload): Json<Instruction<RemoveDevice>>,
+) -> Result<(StatusCode, Json<InstructionResultMessage<()>>)> {
+    info!("Received remove device request, handling the RemoveDevice instruction");
+    let body = state.handle_instruction(payload).await?;
+    Ok((StatusCode::OK, body.into()))
+}
+
 #[derive(Serialize)]
 struct PublicKeys {
     certificate_public_key: DerVerifyingKey,
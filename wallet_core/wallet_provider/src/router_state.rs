@@ -5,46 +5,47 @@ use serde::{de::DeserializeOwned, Serialize};
 use tracing::info;
 use uuid::Uuid;
 
+use hsm::{keys::HsmEcdsaKey, service::ConfiguredHsm};
 use wallet_common::{
-    account::messages::instructions::{Instruction, InstructionEndpoint, InstructionResultMessage},
+    account::messages::{
+        auth::WalletCertificate,
+        instructions::{Instruction, InstructionEndpoint, InstructionResultMessage, RecoverPin},
+    },
     generator::Generator,
     keys::EcdsaKey,
 };
 use wallet_provider_persistence::{database::Db, repositories::Repositories};
 use wallet_provider_service::{
-    account_server::AccountServer,
-    hsm::Pkcs11Hsm,
+    account_server::{AccountServer, AccountServerKeys},
     instructions::HandleInstruction,
-    keys::{CertificateSigning, InstructionResultSigning, WalletProviderEcdsaKey},
-    pin_policy::PinPolicy,
+    keys::{CertificateSigning, InstructionResultSigning},
+    pin_policy::{ConfiguredPinPolicy, DecayingPinPolicy, ExponentialBackoffPinPolicy, PinPolicy},
 };
 
-use crate::{errors::WalletProviderError, settings::Settings};
+use crate::{
+    errors::WalletProviderError,
+    settings::{PinPolicySettings, Settings},
+};
 
 pub struct RouterState {
     pub account_server: AccountServer,
-    pub pin_policy: PinPolicy,
+    pub pin_policy: ConfiguredPinPolicy,
     pub repositories: Repositories,
-    pub hsm: Pkcs11Hsm,
+    pub hsm: ConfiguredHsm,
     pub certificate_signing_key: CertificateSigning,
     pub instruction_result_signing_key: InstructionResultSigning,
 }
 
 impl RouterState {
     pub async fn new_from_settings(settings: Settings) -> Result<RouterState, Box<dyn Error>> {
-        let hsm = Pkcs11Hsm::new(
-            settings.hsm.library_path,
-            settings.hsm.user_pin,
-            settings.hsm.max_sessions,
-            settings.hsm.max_session_lifetime,
-            settings.attestation_wrapping_key_identifier,
-        )?;
-
-        let certificate_signing_key = CertificateSigning(WalletProviderEcdsaKey::new(
+        let hsm = settings.configured_hsm()?;
+        let pin_pubkey_encryption_key = settings.pin_pubkey_encryption_key();
+
+        let certificate_signing_key = CertificateSigning(HsmEcdsaKey::new(
             settings.certificate_signing_key_identifier,
             hsm.clone(),
         ));
-        let instruction_result_signing_key = InstructionResultSigning(WalletProviderEcdsaKey::new(
+        let instruction_result_signing_key = InstructionResultSigning(HsmEcdsaKey::new(
             settings.instruction_result_signing_key_identifier,
             hsm.clone(),
         ));
@@ -55,8 +56,14 @@ impl RouterState {
             settings.instruction_challenge_timeout,
             "account_server".into(),
             (&certificate_signing_pubkey).into(),
-            settings.pin_pubkey_encryption_key_identifier,
-            settings.pin_public_disclosure_protection_key_identifier,
+            AccountServerKeys {
+                pin_pubkey_encryption_key,
+                pin_public_disclosure_protection_key_identifier: settings
+                    .pin_public_disclosure_protection_key_identifier,
+                instruction_audit_log_key_identifier: settings.instruction_audit_log_key_identifier,
+            },
+            settings.platform_attestation,
+            settings.pin_recovery,
         )
         .await?;
 
@@ -66,16 +73,39 @@ impl RouterState {
         )
         .await?;
 
-        let pin_policy = PinPolicy::new(
-            settings.pin_policy.rounds,
-            settings.pin_policy.attempts_per_round,
-            settings
-                .pin_policy
-                .timeouts
-                .into_iter()
-                .map(Duration::from_std)
-                .collect::<Result<_, _>>()?,
-        );
+        let pin_policy = match settings.pin_policy {
+            PinPolicySettings::Rounds {
+                rounds,
+                attempts_per_round,
+                timeouts,
+            } => ConfiguredPinPolicy::Rounds(PinPolicy::new(
+                rounds,
+                attempts_per_round,
+                timeouts.into_iter().map(Duration::from_std).collect::<Result<_, _>>()?,
+            )),
+            PinPolicySettings::ExponentialBackoff {
+                attempts_before_timeout,
+                base_timeout,
+                max_timeout,
+                max_attempts,
+            } => ConfiguredPinPolicy::ExponentialBackoff(ExponentialBackoffPinPolicy::new(
+                attempts_before_timeout,
+                Duration::from_std(base_timeout)?,
+                Duration::from_std(max_timeout)?,
+                max_attempts,
+            )),
+            PinPolicySettings::Decaying {
+                attempts_before_timeout,
+                timeout,
+                max_attempts,
+                decay_interval,
+            } => ConfiguredPinPolicy::Decaying(DecayingPinPolicy::new(
+                attempts_before_timeout,
+                Duration::from_std(timeout)?,
+                max_attempts,
+                Duration::from_std(decay_interval)?,
+            )),
+        };
 
         let repositories = Repositories::new(db);
 
@@ -115,6 +145,27 @@ impl RouterState {
 
         Ok(InstructionResultMessage { result })
     }
+
+    pub async fn recover_pin(
+        &self,
+        instruction: Instruction<RecoverPin>,
+    ) -> Result<InstructionResultMessage<WalletCertificate>, WalletProviderError> {
+        let result = self
+            .account_server
+            .recover_pin(
+                instruction,
+                &self.certificate_signing_key,
+                &self.instruction_result_signing_key,
+                self,
+                &self.repositories,
+                &self.hsm,
+            )
+            .await?;
+
+        info!("Replying with the instruction result");
+
+        Ok(InstructionResultMessage { result })
+    }
 }
 
 impl Generator<uuid::Uuid> for RouterState {
@@ -1,19 +1,56 @@
-use std::error::Error;
+use std::{error::Error, future::IntoFuture, sync::Arc};
 
 use tokio::net::TcpListener;
-use tracing::debug;
+use tracing::{debug, error, info};
 
-use super::{router, router_state::RouterState, settings::Settings};
+use wallet_provider_service::key_rotation;
+
+use super::{admin_router, router, router_state::RouterState, settings::Settings};
 
 pub async fn serve(settings: Settings) -> Result<(), Box<dyn Error>> {
     let listener = TcpListener::bind((settings.webserver.ip, settings.webserver.port)).await?;
     debug!("listening on {}:{}", settings.webserver.ip, settings.webserver.port);
 
-    let router_state = RouterState::new_from_settings(settings).await?;
+    let admin_server = settings.admin_server.clone();
+    let pin_pubkey_encryption_key = settings.pin_pubkey_encryption_key();
+    let key_rotation_batch_size = settings.key_rotation.batch_size;
+    let router_state = Arc::new(RouterState::new_from_settings(settings).await?);
+
+    // Migrate any data that still uses a previous version of a rotated key in the background. As every batch is
+    // committed separately, this simply resumes where it left off after a restart.
+    tokio::spawn({
+        let router_state = Arc::clone(&router_state);
+        async move {
+            match key_rotation::rotate_keys(
+                &router_state.repositories,
+                &router_state.hsm,
+                &pin_pubkey_encryption_key,
+                key_rotation_batch_size,
+            )
+            .await
+            {
+                Ok(()) => info!("key rotation finished"),
+                Err(e) => error!("key rotation failed: {}", e),
+            }
+        }
+    });
+
+    let app = router::router(Arc::clone(&router_state));
+
+    match admin_server {
+        Some(admin_server) => {
+            let admin_listener = TcpListener::bind((admin_server.ip, admin_server.port)).await?;
+            debug!("listening for admin on {}:{}", admin_server.ip, admin_server.port);
 
-    let app = router::router(router_state);
+            let admin_app = admin_router::admin_router(router_state, admin_server.operator_api_keys);
 
-    axum::serve(listener, app).await?;
+            tokio::try_join!(
+                axum::serve(listener, app).into_future(),
+                axum::serve(admin_listener, admin_app).into_future()
+            )?;
+        }
+        None => axum::serve(listener, app).await?,
+    }
 
     Ok(())
 }
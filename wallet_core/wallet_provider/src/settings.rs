@@ -1,11 +1,23 @@
-use std::{env, net::IpAddr, path::PathBuf, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    net::IpAddr,
+    path::PathBuf,
+    time::Duration,
+};
 
 use config::{Config, ConfigError, Environment, File};
+use nutype::nutype;
 use serde::Deserialize;
 use serde_with::{serde_as, DurationMilliSeconds, DurationSeconds};
 
+use hsm::{
+    model::versioned_key::VersionedKey,
+    service::{ConfiguredHsm, HsmError, Pkcs11Hsm, SoftwareHsm},
+};
 use wallet_common::sentry::Sentry;
 use wallet_provider_database_settings::Database;
+use wallet_provider_service::{pin_recovery::PinRecoveryVerifier, platform_attestation::PlatformAttestationVerifier};
 
 #[serde_as]
 #[derive(Clone, Deserialize)]
@@ -15,10 +27,18 @@ pub struct Settings {
     pub attestation_wrapping_key_identifier: String,
     pub pin_pubkey_encryption_key_identifier: String,
     pub pin_public_disclosure_protection_key_identifier: String,
+    pub instruction_audit_log_key_identifier: String,
     pub database: Database,
     pub webserver: Webserver,
+    // The admin API is only served when configured. It SHOULD only be reachable by the operations team.
+    pub admin_server: Option<AdminServer>,
     pub hsm: Hsm,
+    pub key_rotation: KeyRotation,
     pub pin_policy: PinPolicySettings,
+    pub platform_attestation: PlatformAttestationVerifier,
+    // PIN recovery is only possible when the trust anchors of the PID issuer are configured.
+    #[serde(default)]
+    pub pin_recovery: PinRecoveryVerifier,
     pub structured_logging: bool,
     pub sentry: Option<Sentry>,
 
@@ -33,27 +53,110 @@ pub struct Webserver {
     pub port: u16,
 }
 
+#[derive(Clone, Deserialize)]
+pub struct AdminServer {
+    pub ip: IpAddr,
+    pub port: u16,
+    /// The API key of every operator that may use the admin API, by operator name. The audit log records the name of
+    /// the operator whose key was used for every admin action.
+    pub operator_api_keys: OperatorApiKeys,
+}
+
+/// At least one operator should be configured, none of the API keys may be empty and every operator should have their
+/// own API key, so that every admin action can be attributed to a single operator.
+#[nutype(
+    validate(predicate = |api_keys| are_valid_operator_api_keys(api_keys)),
+    derive(Clone, TryFrom, AsRef, Deserialize)
+)]
+pub struct OperatorApiKeys(HashMap<String, String>);
+
+fn are_valid_operator_api_keys(api_keys: &HashMap<String, String>) -> bool {
+    !api_keys.is_empty()
+        && api_keys.values().all(|api_key| !api_key.is_empty())
+        && api_keys.values().collect::<HashSet<_>>().len() == api_keys.len()
+}
+
+/// The versions of the keys in the HSM that are rotated periodically. Data that is wrapped or encrypted with a previous
+/// version is migrated to the current version in the background, after which that version can be retired.
+#[derive(Clone, Deserialize)]
+pub struct KeyRotation {
+    pub attestation_wrapping_key_version: u32,
+    pub retired_attestation_wrapping_key_versions: HashSet<u32>,
+    pub pin_pubkey_encryption_key_version: u32,
+    pub retired_pin_pubkey_encryption_key_versions: HashSet<u32>,
+    pub batch_size: u64,
+}
+
 #[serde_as]
 #[derive(Clone, Deserialize)]
-pub struct PinPolicySettings {
-    pub rounds: u8,
-    pub attempts_per_round: u8,
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PinPolicySettings {
+    /// A fixed number of rounds of attempts, with a timeout between every round.
+    Rounds {
+        rounds: u8,
+        attempts_per_round: u8,
+
+        #[serde(rename = "timeouts_in_ms")]
+        #[serde_as(as = "Vec<DurationMilliSeconds>")]
+        timeouts: Vec<Duration>,
+    },
+    /// A timeout that doubles with every attempt, after a number of attempts without timeout.
+    ExponentialBackoff {
+        attempts_before_timeout: u8,
+
+        #[serde(rename = "base_timeout_in_ms")]
+        #[serde_as(as = "DurationMilliSeconds")]
+        base_timeout: Duration,
+
+        #[serde(rename = "max_timeout_in_ms")]
+        #[serde_as(as = "DurationMilliSeconds")]
+        max_timeout: Duration,
+
+        max_attempts: u8,
+    },
+    /// A fixed timeout after a number of attempts, where the attempts counter decays over time.
+    Decaying {
+        attempts_before_timeout: u8,
+
+        #[serde(rename = "timeout_in_ms")]
+        #[serde_as(as = "DurationMilliSeconds")]
+        timeout: Duration,
+
+        max_attempts: u8,
 
-    #[serde(rename = "timeouts_in_ms")]
-    #[serde_as(as = "Vec<DurationMilliSeconds>")]
-    pub timeouts: Vec<Duration>,
+        #[serde(rename = "decay_interval_in_ms")]
+        #[serde_as(as = "DurationMilliSeconds")]
+        decay_interval: Duration,
+    },
 }
 
 #[serde_as]
 #[derive(Clone, Deserialize)]
-pub struct Hsm {
-    pub library_path: PathBuf,
-    pub user_pin: String,
-    pub max_sessions: u8,
-
-    #[serde(rename = "max_session_lifetime_in_sec")]
-    #[serde_as(as = "DurationSeconds")]
-    pub max_session_lifetime: Duration,
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Hsm {
+    Pkcs11 {
+        library_path: PathBuf,
+        user_pin: String,
+        max_sessions: u8,
+
+        #[serde(rename = "max_session_lifetime_in_sec")]
+        #[serde_as(as = "DurationSeconds")]
+        max_session_lifetime: Duration,
+
+        // Should be lower than `max_sessions`.
+        max_batch_sessions: u8,
+
+        #[serde(rename = "batch_checkout_timeout_in_ms")]
+        #[serde_as(as = "DurationMilliSeconds")]
+        batch_checkout_timeout: Duration,
+    },
+    /// Keys in an encrypted local key file, for development and testing without a PKCS#11 library.
+    Software {
+        key_file: PathBuf,
+        passphrase: String,
+        #[serde(default)]
+        allow_production: bool,
+    },
 }
 
 impl Settings {
@@ -74,15 +177,32 @@ impl Settings {
                 "pin_public_disclosure_protection_key_identifier",
                 "pin_public_disclosure_protection_key",
             )?
+            .set_default("instruction_audit_log_key_identifier", "instruction_audit_log_key")?
             .set_default("webserver.ip", "0.0.0.0")?
             .set_default("webserver.port", 3000)?
+            .set_default("pin_policy.type", "rounds")?
             .set_default("pin_policy.rounds", 4)?
             .set_default("pin_policy.attempts_per_round", 4)?
             .set_default("pin_policy.timeouts_in_ms", vec![60_000, 300_000, 3_600_000])?
+            .set_default("platform_attestation.required", false)?
             .set_default("structured_logging", false)?
             .set_default("instruction_challenge_timeout_in_ms", 15_000)?
+            .set_default("hsm.type", "pkcs11")?
             .set_default("hsm.max_sessions", 10)?
             .set_default("hsm.max_session_lifetime_in_sec", 900)?
+            .set_default("hsm.max_batch_sessions", 4)?
+            .set_default("hsm.batch_checkout_timeout_in_ms", 5_000)?
+            .set_default("key_rotation.attestation_wrapping_key_version", 0)?
+            .set_default(
+                "key_rotation.retired_attestation_wrapping_key_versions",
+                Vec::<u32>::new(),
+            )?
+            .set_default("key_rotation.pin_pubkey_encryption_key_version", 0)?
+            .set_default(
+                "key_rotation.retired_pin_pubkey_encryption_key_versions",
+                Vec::<u32>::new(),
+            )?
+            .set_default("key_rotation.batch_size", 100)?
             .add_source(File::from(config_path.join("wallet_provider.toml")).required(false))
             .add_source(
                 Environment::with_prefix("wallet_provider")
@@ -93,4 +213,65 @@ impl Settings {
             .build()?
             .try_deserialize()
     }
+
+    pub fn attestation_wrapping_key(&self) -> VersionedKey {
+        VersionedKey::new(
+            self.attestation_wrapping_key_identifier.clone(),
+            self.key_rotation.attestation_wrapping_key_version,
+            self.key_rotation.retired_attestation_wrapping_key_versions.clone(),
+        )
+    }
+
+    pub fn pin_pubkey_encryption_key(&self) -> VersionedKey {
+        VersionedKey::new(
+            self.pin_pubkey_encryption_key_identifier.clone(),
+            self.key_rotation.pin_pubkey_encryption_key_version,
+            self.key_rotation.retired_pin_pubkey_encryption_key_versions.clone(),
+        )
+    }
+
+    /// Create the configured HSM. The software HSM is provisioned with any key the Wallet Provider uses that is not
+    /// present yet, whereas these keys should already exist in a PKCS#11 HSM.
+    pub fn configured_hsm(&self) -> Result<ConfiguredHsm, HsmError> {
+        let hsm = match &self.hsm {
+            Hsm::Pkcs11 {
+                library_path,
+                user_pin,
+                max_sessions,
+                max_session_lifetime,
+                max_batch_sessions,
+                batch_checkout_timeout,
+            } => ConfiguredHsm::Pkcs11(Pkcs11Hsm::new(
+                library_path.clone(),
+                user_pin.clone(),
+                *max_sessions,
+                *max_session_lifetime,
+                *max_batch_sessions,
+                *batch_checkout_timeout,
+                self.attestation_wrapping_key(),
+            )?),
+            Hsm::Software {
+                key_file,
+                passphrase,
+                allow_production,
+            } => {
+                let hsm = SoftwareHsm::open(
+                    key_file.clone(),
+                    passphrase,
+                    self.attestation_wrapping_key(),
+                    *allow_production,
+                )?;
+
+                hsm.ensure_signing_key(&self.certificate_signing_key_identifier)?;
+                hsm.ensure_signing_key(&self.instruction_result_signing_key_identifier)?;
+                hsm.ensure_secret_key(&self.pin_pubkey_encryption_key().current_identifier())?;
+                hsm.ensure_secret_key(&self.pin_public_disclosure_protection_key_identifier)?;
+                hsm.ensure_secret_key(&self.instruction_audit_log_key_identifier)?;
+
+                ConfiguredHsm::Software(hsm)
+            }
+        };
+
+        Ok(hsm)
+    }
 }
@@ -1,30 +1,30 @@
 use std::sync::Arc;
 
-use p256::ecdsa::{signature::Verifier, SigningKey};
+use futures::future;
+use p256::{
+    ecdh,
+    ecdsa::{signature::Verifier, SigningKey},
+    SecretKey,
+};
 use rand_core::OsRng;
 use serial_test::serial;
 
+use hsm::{
+    model::{
+        encrypted::Encrypted,
+        encrypter::{Decrypter, Encrypter},
+        hsm::{Hsm, WalletUserHsm},
+    },
+    service::ConfiguredHsm,
+};
 use wallet_common::utils::{random_bytes, random_string};
 use wallet_provider::settings::Settings;
-use wallet_provider_domain::model::{
-    encrypted::Encrypted,
-    encrypter::{Decrypter, Encrypter},
-    hsm::{Hsm, WalletUserHsm},
-    wallet_user::WalletId,
-};
-use wallet_provider_service::hsm::Pkcs11Hsm;
+use wallet_provider_domain::model::wallet_user::WalletId;
 
-fn setup_hsm() -> (Pkcs11Hsm, Settings) {
+fn setup_hsm() -> (ConfiguredHsm, Settings) {
     let settings = Settings::new().unwrap();
-    let hsm = Pkcs11Hsm::new(
-        settings.hsm.library_path,
-        settings.hsm.user_pin,
-        settings.hsm.max_sessions,
-        settings.hsm.max_session_lifetime,
-        settings.attestation_wrapping_key_identifier,
-    )
-    .unwrap();
-    (hsm, Settings::new().unwrap())
+    let hsm = settings.configured_hsm().unwrap();
+    (hsm, settings)
 }
 
 #[tokio::test]
@@ -101,6 +101,27 @@ async fn wrap_key_and_sign() {
     public_key.verify(data.as_ref(), &signature).unwrap();
 }
 
+#[tokio::test]
+#[serial]
+async fn rewrap_multiple_and_sign() {
+    let (hsm, _) = setup_hsm();
+
+    let (public_key_1, wrapped_1) = hsm.generate_wrapped_key().await.unwrap();
+    let (public_key_2, wrapped_2) = hsm.generate_wrapped_key().await.unwrap();
+
+    let rewrapped = hsm.rewrap_multiple(vec![wrapped_1, wrapped_2]).await.unwrap();
+
+    let data = Arc::new(random_bytes(32));
+    for (public_key, wrapped) in [public_key_1, public_key_2].into_iter().zip(rewrapped) {
+        assert_eq!(wrapped.wrapping_key_version(), hsm.wrapping_key_version());
+
+        let signature = WalletUserHsm::sign_wrapped(&hsm, wrapped, Arc::clone(&data))
+            .await
+            .unwrap();
+        public_key.verify(data.as_ref(), &signature).unwrap();
+    }
+}
+
 #[tokio::test]
 #[serial]
 async fn encrypt_decrypt() {
@@ -137,3 +158,98 @@ async fn encrypt_decrypt_verifying_key() {
 
     assert_eq!(verifying_key, decrypted);
 }
+
+#[tokio::test]
+#[serial]
+async fn generate_wrapped_keys_and_sign_wrapped_multiple() {
+    let (hsm, _) = setup_hsm();
+
+    let keys = hsm.generate_wrapped_keys(&["key1", "key2", "key3"]).await.unwrap();
+    assert_eq!(
+        keys.iter()
+            .map(|(identifier, _, _)| identifier.as_str())
+            .collect::<Vec<_>>(),
+        vec!["key1", "key2", "key3"]
+    );
+
+    let data = Arc::new(random_bytes(32));
+    let signatures = hsm
+        .sign_wrapped_multiple(
+            keys.iter()
+                .map(|(_, _, wrapped)| (wrapped.clone(), Arc::clone(&data)))
+                .collect(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(signatures.len(), keys.len());
+    for ((_, public_key, _), signature) in keys.iter().zip(&signatures) {
+        public_key.verify(data.as_ref(), signature).unwrap();
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn generate_wrapped_keys_and_diffie_hellman_multiple() {
+    let (hsm, _) = setup_hsm();
+
+    let keys = hsm.generate_wrapped_keys(&["key1", "key2"]).await.unwrap();
+
+    let other_key = SecretKey::random(&mut OsRng);
+    let shared_secrets = hsm
+        .diffie_hellman_wrapped_multiple(
+            keys.iter().map(|(_, _, wrapped)| wrapped.clone()).collect(),
+            other_key.public_key().into(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(shared_secrets.len(), keys.len());
+    for ((_, public_key, _), shared_secret) in keys.iter().zip(&shared_secrets) {
+        let expected = ecdh::diffie_hellman(other_key.to_nonzero_scalar(), public_key.as_affine());
+        assert_eq!(shared_secret.as_slice(), expected.raw_secret_bytes().as_slice());
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn generate_keys_and_sign_multiple() {
+    let (hsm, _) = setup_hsm();
+
+    let wallet_id: WalletId = random_string(8);
+    let identifiers = [random_string(8), random_string(8)];
+    let identifiers = identifiers.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let keys = hsm.generate_keys(&wallet_id, &identifiers).await.unwrap();
+
+    let data = Arc::new(random_bytes(32));
+    let signatures = hsm
+        .sign_multiple(&wallet_id, &identifiers, Arc::clone(&data))
+        .await
+        .unwrap();
+
+    for ((key_identifier, public_key), (signature_identifier, signature)) in keys.iter().zip(&signatures) {
+        assert_eq!(key_identifier, signature_identifier);
+        public_key.verify(data.as_ref(), signature).unwrap();
+    }
+
+    for identifier in identifiers {
+        Hsm::delete_key(&hsm, &format!("{wallet_id}_{identifier}"))
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn concurrent_batches_wait_for_available_session() {
+    let (hsm, _) = setup_hsm();
+
+    // Start more batches than there are batch sessions, which should wait for each other instead of failing.
+    let batches = (0..12)
+        .map(|_| hsm.generate_wrapped_keys(&["key1", "key2"]))
+        .collect::<Vec<_>>();
+
+    let results = future::try_join_all(batches).await.unwrap();
+    assert!(results.iter().all(|keys| keys.len() == 2));
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt::Display, str::FromStr, sync::Arc};
+use std::{fmt::Display, str::FromStr, sync::Arc};
 
 use axum::{
     extract::State,
@@ -10,13 +10,9 @@ use axum_extra::{
     headers::{self, authorization::Credentials, Authorization, Header},
     TypedHeader,
 };
-use nutype::nutype;
 use serde::Serialize;
 
-use nl_wallet_mdoc::{
-    server_keys::{KeyPair, KeyRing},
-    server_state::SessionStore,
-};
+use nl_wallet_mdoc::{server_keys::KeyRing, server_state::SessionStore};
 use openid4vc::{
     credential::{CredentialRequest, CredentialRequests, CredentialResponse, CredentialResponses},
     dpop::{Dpop, DPOP_HEADER_NAME, DPOP_NONCE_HEADER_NAME},
@@ -37,38 +33,7 @@ struct ApplicationState<A, K, S> {
     issuer: Issuer<A, K, S>,
 }
 
-#[nutype(derive(From, AsRef))]
-pub struct IssuerKeyRing(HashMap<String, KeyPair>);
-
-impl KeyRing for IssuerKeyRing {
-    fn key_pair(&self, id: &str) -> Option<&KeyPair> {
-        self.as_ref().get(id)
-    }
-
-    fn contains_key_pair(&self, id: &str) -> bool {
-        self.as_ref().contains_key(id)
-    }
-}
-
-impl TryFrom<HashMap<String, settings::KeyPair>> for IssuerKeyRing {
-    type Error = p256::pkcs8::Error;
-
-    fn try_from(private_keys: HashMap<String, settings::KeyPair>) -> Result<Self, Self::Error> {
-        let key_ring = private_keys
-            .into_iter()
-            .map(|(doctype, key_pair)| {
-                let key_pair = (&key_pair).try_into()?;
-
-                Ok((doctype, key_pair))
-            })
-            .collect::<Result<HashMap<_, _>, Self::Error>>()?
-            .into();
-
-        Ok(key_ring)
-    }
-}
-
-pub fn create_issuance_router<A, S>(
+pub async fn create_issuance_router<A, S>(
     urls: &Urls,
     issuer: settings::Issuer,
     sessions: S,
@@ -82,7 +47,7 @@ where
         issuer: Issuer::new(
             sessions,
             attr_service,
-            IssuerKeyRing::try_from(issuer.private_keys)?,
+            issuer.key_ring().await?,
             &urls.public_url,
             issuer.wallet_client_ids,
         ),
@@ -115,8 +80,13 @@ where
     Ok(Json(metadata))
 }
 
-async fn metadata<A, K, S>(State(state): State<Arc<ApplicationState<A, K, S>>>) -> Json<IssuerMetadata> {
-    Json(state.issuer.metadata.clone())
+async fn metadata<A, K, S>(State(state): State<Arc<ApplicationState<A, K, S>>>) -> Json<IssuerMetadata>
+where
+    A: AttributeService,
+    K: KeyRing,
+    S: SessionStore<IssuanceData>,
+{
+    Json(state.issuer.metadata())
 }
 
 async fn token<A, K, S>(
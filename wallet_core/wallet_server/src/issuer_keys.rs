@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use futures::future::try_join_all;
+use indexmap::IndexMap;
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+
+use hsm::{keys::HsmEcdsaKey, service::HsmError};
+use nl_wallet_mdoc::{
+    server_keys::{KeyPair, KeyRing},
+    utils::x509::{Certificate, CertificateError},
+};
+use wallet_common::keys::EcdsaKey;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IssuerKeysError {
+    #[error("failed to parse DER-encoded private key: {0}")]
+    PrivateKeyParsing(#[from] p256::pkcs8::Error),
+    #[error("HSM error: {0}")]
+    Hsm(#[from] HsmError),
+    #[error("no HSM configured for key of doctype {0}")]
+    MissingHsm(String),
+    #[error("private key in configuration for doctype {0} is not allowed in production unless explicitly enabled")]
+    InsecurePrivateKeyNotAllowed(String),
+    #[error("error using private key: {0}")]
+    SigningKey(#[from] IssuerSigningKeyError),
+    #[error("certificate error: {0}")]
+    Certificate(#[from] CertificateError),
+    #[error("private key does not match certificate for doctype {0}")]
+    KeyMismatch(String),
+}
+
+/// The period during which an issuer key may be used to sign. Either end of the period may be left open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActivePeriod {
+    pub from: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ActivePeriod {
+    pub fn contains(&self, time: &DateTime<Utc>) -> bool {
+        self.from.map_or(true, |from| from <= *time) && self.until.map_or(true, |until| *time < until)
+    }
+
+    pub fn has_ended(&self, time: &DateTime<Utc>) -> bool {
+        self.until.is_some_and(|until| until <= *time)
+    }
+}
+
+/// A list of items, each with the period during which it is active.
+#[derive(Debug, Clone)]
+pub struct Schedule<T>(Vec<(ActivePeriod, T)>);
+
+impl<T> Schedule<T> {
+    pub fn new(items: Vec<(ActivePeriod, T)>) -> Self {
+        Self(items)
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().map(|(_, item)| item)
+    }
+
+    /// All items that are active at `time`.
+    pub fn active_at(&self, time: &DateTime<Utc>) -> impl Iterator<Item = &T> {
+        let time = *time;
+
+        self.0
+            .iter()
+            .filter(move |(period, _)| period.contains(&time))
+            .map(|(_, item)| item)
+    }
+
+    /// The item that should be used at `time`, which is the active item that became active last. This means that
+    /// during a rollover, the new item takes over as soon as its period starts.
+    pub fn current_at(&self, time: &DateTime<Utc>) -> Option<&T> {
+        self.0
+            .iter()
+            .filter(|(period, _)| period.contains(time))
+            .max_by_key(|(period, _)| period.from)
+            .map(|(_, item)| item)
+    }
+
+    /// All items that are active at `time` or will become active later, ordered by the start of their period.
+    pub fn upcoming_at(&self, time: &DateTime<Utc>) -> Vec<&T> {
+        let mut items = self
+            .0
+            .iter()
+            .filter(|(period, _)| !period.has_ended(time))
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(period, _)| period.from);
+
+        items.into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+/// The private key of an issuer, which is either stored in the configuration or resides in an HSM.
+pub enum IssuerSigningKey {
+    Software(SigningKey),
+    Hsm(HsmEcdsaKey),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IssuerSigningKeyError {
+    #[error("software key error: {0}")]
+    Software(#[from] p256::ecdsa::Error),
+    #[error("HSM key error: {0}")]
+    Hsm(#[from] HsmError),
+}
+
+impl EcdsaKey for IssuerSigningKey {
+    type Error = IssuerSigningKeyError;
+
+    async fn verifying_key(&self) -> Result<VerifyingKey, Self::Error> {
+        let verifying_key = match self {
+            Self::Software(key) => EcdsaKey::verifying_key(key).await?,
+            Self::Hsm(key) => key.verifying_key().await?,
+        };
+
+        Ok(verifying_key)
+    }
+
+    async fn try_sign(&self, msg: &[u8]) -> Result<Signature, Self::Error> {
+        let signature = match self {
+            Self::Software(key) => EcdsaKey::try_sign(key, msg).await?,
+            Self::Hsm(key) => key.try_sign(msg).await?,
+        };
+
+        Ok(signature)
+    }
+}
+
+/// The issuer key pairs per doctype, each of which is active during a particular period. This allows a new key pair
+/// to be rolled out while the previous one is still active.
+///
+/// Note that the certificate of the key pair used to sign an mdoc is announced to the wallet before the mdoc is
+/// actually signed. For issuance sessions to succeed during a rollover, the active periods of the old and new key
+/// pairs should therefore overlap for at least the expiration time of a session.
+pub struct IssuerKeyRing(HashMap<String, Schedule<KeyPair<IssuerSigningKey>>>);
+
+impl IssuerKeyRing {
+    /// Create a new key ring, after checking that the private key of every key pair matches its certificate.
+    pub async fn try_new(
+        key_pairs: HashMap<String, Schedule<KeyPair<IssuerSigningKey>>>,
+    ) -> Result<Self, IssuerKeysError> {
+        try_join_all(key_pairs.iter().flat_map(|(doctype, schedule)| {
+            schedule.items().map(move |key_pair| async move {
+                if key_pair.verifying_key().await? != key_pair.certificate().public_key()? {
+                    return Err(IssuerKeysError::KeyMismatch(doctype.clone()));
+                }
+
+                Ok(())
+            })
+        }))
+        .await?;
+
+        Ok(Self(key_pairs))
+    }
+}
+
+impl KeyRing for IssuerKeyRing {
+    type Key = IssuerSigningKey;
+
+    fn key_pair(&self, id: &str) -> Option<&KeyPair<IssuerSigningKey>> {
+        self.0.get(id).and_then(|schedule| schedule.current_at(&Utc::now()))
+    }
+
+    fn key_pair_for_certificate(&self, id: &str, certificate: &Certificate) -> Option<&KeyPair<IssuerSigningKey>> {
+        let now = Utc::now();
+
+        self.0.get(id).and_then(|schedule| {
+            schedule
+                .active_at(&now)
+                .find(|key_pair| key_pair.certificate() == certificate)
+        })
+    }
+
+    fn certificates(&self) -> IndexMap<String, Vec<Certificate>> {
+        let now = Utc::now();
+
+        let mut certificates = self
+            .0
+            .iter()
+            .map(|(doctype, schedule)| {
+                let doctype_certificates = schedule
+                    .upcoming_at(&now)
+                    .into_iter()
+                    .map(|key_pair| key_pair.certificate().clone())
+                    .collect();
+
+                (doctype.clone(), doctype_certificates)
+            })
+            .collect::<IndexMap<_, _>>();
+        certificates.sort_keys();
+
+        certificates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Duration;
+
+    use hsm::{
+        model::versioned_key::VersionedKey,
+        service::{ConfiguredHsm, SoftwareHsm},
+    };
+
+    use super::*;
+
+    const DOCTYPE: &str = "com.example.pid";
+
+    fn period(from: Option<i64>, until: Option<i64>) -> ActivePeriod {
+        let now = Utc::now();
+
+        ActivePeriod {
+            from: from.map(|days| now + Duration::days(days)),
+            until: until.map(|days| now + Duration::days(days)),
+        }
+    }
+
+    fn generate_key_pairs(count: usize) -> Vec<KeyPair<IssuerSigningKey>> {
+        let ca = KeyPair::generate_issuer_mock_ca().unwrap();
+
+        (0..count)
+            .map(|_| {
+                let key_pair = ca.generate_issuer_mock(None).unwrap();
+                let certificate = key_pair.certificate().clone();
+
+                KeyPair::new(IssuerSigningKey::Software(key_pair.private_key().clone()), certificate)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schedule_current_at() {
+        let now = Utc::now();
+
+        let schedule = Schedule::new(vec![
+            (period(None, Some(1)), "old"),
+            (period(Some(-1), None), "new"),
+            (period(Some(2), None), "future"),
+            (period(None, Some(-1)), "expired"),
+        ]);
+
+        assert_eq!(schedule.current_at(&now), Some(&"new"));
+        assert_eq!(schedule.active_at(&now).collect::<Vec<_>>(), vec![&"old", &"new"]);
+        assert_eq!(schedule.upcoming_at(&now), vec![&"old", &"new", &"future"]);
+
+        assert_eq!(schedule.current_at(&(now + Duration::days(3))), Some(&"future"));
+        assert_eq!(schedule.current_at(&(now - Duration::days(3))), Some(&"expired"));
+    }
+
+    #[tokio::test]
+    async fn test_issuer_key_ring_rollover() {
+        let mut key_pairs = generate_key_pairs(3).into_iter();
+        let (old, new, future) = (
+            key_pairs.next().unwrap(),
+            key_pairs.next().unwrap(),
+            key_pairs.next().unwrap(),
+        );
+        let (old_certificate, new_certificate, future_certificate) = (
+            old.certificate().clone(),
+            new.certificate().clone(),
+            future.certificate().clone(),
+        );
+
+        let key_ring = IssuerKeyRing::try_new(HashMap::from([(
+            DOCTYPE.to_string(),
+            Schedule::new(vec![
+                (period(None, Some(1)), old),
+                (period(Some(-1), Some(10)), new),
+                (period(Some(5), None), future),
+            ]),
+        )]))
+        .await
+        .expect("key pairs should match their certificates");
+
+        // The key pair that became active last should be used to sign new mdocs.
+        assert_eq!(key_ring.key_pair(DOCTYPE).unwrap().certificate(), &new_certificate);
+        assert!(key_ring.key_pair("com.example.unknown").is_none());
+
+        // The old key pair can still be used for a certificate that was announced earlier, unlike the future one.
+        assert_eq!(
+            key_ring
+                .key_pair_for_certificate(DOCTYPE, &old_certificate)
+                .unwrap()
+                .certificate(),
+            &old_certificate
+        );
+        assert!(key_ring
+            .key_pair_for_certificate(DOCTYPE, &future_certificate)
+            .is_none());
+
+        // All certificates that are or will be active should be published.
+        assert_eq!(
+            key_ring.certificates(),
+            IndexMap::from([(
+                DOCTYPE.to_string(),
+                vec![old_certificate, new_certificate, future_certificate]
+            )])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_issuer_key_ring_key_mismatch() {
+        // Combine the private key of the CA with the certificate of an issuer.
+        let ca = KeyPair::generate_issuer_mock_ca().unwrap();
+        let certificate = ca.generate_issuer_mock(None).unwrap().certificate().clone();
+        let key_pair = KeyPair::new(IssuerSigningKey::Software(ca.private_key().clone()), certificate);
+
+        let error = IssuerKeyRing::try_new(HashMap::from([(
+            DOCTYPE.to_string(),
+            Schedule::new(vec![(ActivePeriod::default(), key_pair)]),
+        )]))
+        .await
+        .map(|_| ())
+        .expect_err("creating key ring should fail");
+
+        assert_matches!(error, IssuerKeysError::KeyMismatch(doctype) if doctype == DOCTYPE);
+    }
+
+    #[tokio::test]
+    async fn test_issuer_key_ring_hsm_key_mismatch() {
+        let key_dir = tempfile::tempdir().unwrap();
+        let hsm = SoftwareHsm::open(
+            key_dir.path().join("keys.bin"),
+            "passphrase",
+            VersionedKey::from(String::from("wrapping_key")),
+            false,
+        )
+        .unwrap();
+        hsm.ensure_signing_key("issuer_key").unwrap();
+
+        let certificate = generate_key_pairs(1).pop().unwrap().certificate().clone();
+        let key_pair = KeyPair::new(
+            IssuerSigningKey::Hsm(HsmEcdsaKey::new(
+                String::from("issuer_key"),
+                ConfiguredHsm::Software(hsm),
+            )),
+            certificate,
+        );
+
+        let error = IssuerKeyRing::try_new(HashMap::from([(
+            DOCTYPE.to_string(),
+            Schedule::new(vec![(ActivePeriod::default(), key_pair)]),
+        )]))
+        .await
+        .map(|_| ())
+        .expect_err("creating key ring should fail");
+
+        assert_matches!(error, IssuerKeysError::KeyMismatch(doctype) if doctype == DOCTYPE);
+    }
+}
@@ -14,6 +14,7 @@ cfg_if::cfg_if! {
 cfg_if::cfg_if! {
     if #[cfg(feature = "issuance")] {
         pub mod issuer;
+        pub mod issuer_keys;
         pub mod pid;
     }
 }
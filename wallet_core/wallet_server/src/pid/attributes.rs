@@ -1,3 +1,4 @@
+use chrono::Utc;
 use indexmap::IndexMap;
 
 use nl_wallet_mdoc::{server_state::SessionState, unsigned::UnsignedMdoc, utils::x509::Certificate};
@@ -9,7 +10,10 @@ use openid4vc::{
 };
 use wallet_common::{config::wallet_config::BaseUrl, nonempty::NonEmpty};
 
-use crate::pid::brp::client::{BrpClient, BrpError, HttpBrpClient};
+use crate::{
+    issuer_keys::Schedule,
+    pid::brp::client::{BrpClient, BrpError, HttpBrpClient},
+};
 
 use super::digid::{self, OpenIdClient};
 
@@ -27,18 +31,21 @@ pub enum Error {
     UrlEncoding(#[from] serde_urlencoded::ser::Error),
     #[error("could not find attributes for BSN")]
     NoAttributesFound,
-    #[error("missing certificate for issuance of doctype {0}")]
+    #[error("no active certificate for issuance of doctype {0}")]
     MissingCertificate(String),
     #[error("error retrieving from BRP")]
     Brp(#[from] BrpError),
 }
 
+/// The issuer certificates per doctype, of which the currently active one is included in the attestation preview.
+/// The mdoc is then signed with the corresponding key, even if a rollover to a new key occurs in the meantime.
+#[derive(Debug, Clone)]
 pub struct AttributeCertificates {
-    certificates: IndexMap<String, Certificate>,
+    certificates: IndexMap<String, Schedule<Certificate>>,
 }
 
 impl AttributeCertificates {
-    pub fn new(certificates: IndexMap<String, Certificate>) -> Self {
+    pub fn new(certificates: IndexMap<String, Schedule<Certificate>>) -> Self {
         Self { certificates }
     }
 
@@ -47,6 +54,7 @@ impl AttributeCertificates {
             issuer: self
                 .certificates
                 .get(&unsigned.doc_type)
+                .and_then(|schedule| schedule.current_at(&Utc::now()))
                 .ok_or(Error::MissingCertificate(unsigned.doc_type.clone()))?
                 .clone(),
             unsigned_mdoc: unsigned,
@@ -67,12 +75,12 @@ impl BrpPidAttributeService {
         issuer_url: BaseUrl,
         bsn_privkey: String,
         trust_anchors: Vec<reqwest::Certificate>,
-        certificates: IndexMap<String, Certificate>,
+        certificates: AttributeCertificates,
     ) -> Result<Self, Error> {
         Ok(Self {
             brp_client,
             openid_client: OpenIdClient::new(issuer_url, bsn_privkey, trust_anchors)?,
-            certificates: AttributeCertificates::new(certificates),
+            certificates,
         })
     }
 }
@@ -14,7 +14,7 @@ where
     let log_requests = settings.log_requests;
 
     let wallet_issuance_router =
-        create_issuance_router(&settings.urls, settings.issuer, issuance_sessions, attr_service)?;
+        create_issuance_router(&settings.urls, settings.issuer, issuance_sessions, attr_service).await?;
 
     listen_wallet_only(
         settings.wallet_server,
@@ -20,7 +20,7 @@ where
     let log_requests = settings.log_requests;
 
     let wallet_issuance_router =
-        create_issuance_router(&settings.urls, settings.issuer, issuance_sessions, attr_service)?;
+        create_issuance_router(&settings.urls, settings.issuer, issuance_sessions, attr_service).await?;
     let (wallet_disclosure_router, requester_router) =
         verifier::create_routers(settings.urls, settings.verifier, disclosure_sessions)?;
 
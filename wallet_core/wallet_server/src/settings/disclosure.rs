@@ -1,11 +1,11 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, num::NonZeroU64, path::PathBuf, time::Duration};
 
 use nutype::nutype;
 use ring::hmac;
 use serde::Deserialize;
 use serde_with::{hex::Hex, serde_as};
 
-use nl_wallet_mdoc::verifier::SessionTypeReturnUrl;
+use nl_wallet_mdoc::verifier::{SessionTypeReturnUrl, ValidityPolicy};
 use openid4vc::verifier::{UseCase, UseCases};
 use wallet_common::trust_anchor::DerTrustAnchor;
 
@@ -18,6 +18,11 @@ const MIN_KEY_LENGTH_BYTES: usize = 16;
 pub struct Verifier {
     pub usecases: VerifierUseCases,
     pub trust_anchors: Vec<DerTrustAnchor>,
+    /// Files containing CRLs, or directories containing CRL files, against which the certificates of disclosed mdocs
+    /// are checked for revocation. These are read again after `crl_refresh_minutes`.
+    #[serde(default)]
+    pub crls: Vec<PathBuf>,
+    pub crl_refresh_minutes: NonZeroU64,
     #[serde_as(as = "Hex")]
     pub ephemeral_id_secret: EhpemeralIdSecret,
 }
@@ -32,6 +37,12 @@ pub struct EhpemeralIdSecret(Vec<u8>);
 pub struct VerifierUseCase {
     #[serde(default)]
     pub session_type_return_url: SessionTypeReturnUrl,
+    /// The endpoint of the Relying Party backend to which deletion requests from the wallet are forwarded.
+    /// If absent, the wallet server rejects deletion requests for this use case.
+    pub deletion_request_url: Option<Url>,
+    /// The policy for the validity of disclosed mdocs, which by default only requires them to be valid now.
+    #[serde(default)]
+    pub validity_policy: ValidityPolicy,
     #[serde(flatten)]
     pub key_pair: KeyPair,
 }
@@ -59,12 +70,22 @@ impl TryFrom<&VerifierUseCase> for UseCase {
     type Error = anyhow::Error;
 
     fn try_from(value: &VerifierUseCase) -> Result<Self, Self::Error> {
-        let use_case = UseCase::new((&value.key_pair).try_into()?, value.session_type_return_url)?;
+        let use_case = UseCase::new(
+            (&value.key_pair).try_into()?,
+            value.session_type_return_url,
+            value.validity_policy,
+        )?;
 
         Ok(use_case)
     }
 }
 
+impl Verifier {
+    pub fn crl_refresh_interval(&self) -> Duration {
+        Duration::from_secs(60 * self.crl_refresh_minutes.get())
+    }
+}
+
 impl From<&EhpemeralIdSecret> for hmac::Key {
     fn from(value: &EhpemeralIdSecret) -> Self {
         hmac::Key::new(hmac::HMAC_SHA256, value.as_ref())
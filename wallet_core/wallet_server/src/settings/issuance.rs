@@ -1,21 +1,43 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
+use chrono::{DateTime, Utc};
 use indexmap::IndexMap;
+use p256::{ecdsa::SigningKey, pkcs8::DecodePrivateKey};
 use serde::Deserialize;
+use serde_with::{base64::Base64, serde_as, DurationSeconds};
 
-use nl_wallet_mdoc::utils::x509::Certificate;
+use hsm::{
+    keys::HsmEcdsaKey,
+    model::versioned_key::VersionedKey,
+    service::{ConfiguredHsm, HsmError, Pkcs11Hsm, SoftwareHsm},
+};
+use nl_wallet_mdoc::{server_keys::KeyPair, utils::x509::Certificate};
 use wallet_common::{config::wallet_config::BaseUrl, reqwest::deserialize_certificates};
 
-use super::*;
-use crate::pid::{
-    attributes::{BrpPidAttributeService, Error as BrpError},
-    brp::client::HttpBrpClient,
+use crate::{
+    issuer_keys::{ActivePeriod, IssuerKeyRing, IssuerKeysError, IssuerSigningKey, Schedule},
+    pid::{
+        attributes::{AttributeCertificates, BrpPidAttributeService, Error as BrpError},
+        brp::client::HttpBrpClient,
+    },
 };
 
+/// The HSM abstraction requires a wrapping key, which the issuer does not use.
+const HSM_WRAPPING_KEY_IDENTIFIER: &str = "issuer_wrapping_key";
+
 #[derive(Clone, Deserialize)]
 pub struct Issuer {
-    // Issuer private keys index per doctype
-    pub private_keys: HashMap<String, KeyPair>,
+    /// Issuer keys per doctype. Multiple keys with (overlapping) active periods can be configured for a doctype,
+    /// in order to roll over from one key to the next.
+    pub keys: HashMap<String, Vec<IssuerKey>>,
+
+    /// The HSM containing the private keys that are referred to by `hsm_key_identifier`.
+    pub hsm: Option<Hsm>,
+
+    /// Whether keys with a `private_key` in the configuration may be used in a release build, which is regarded as
+    /// production use. This is meant for development and testing only.
+    #[serde(default)]
+    pub allow_insecure_private_key: bool,
 
     /// `client_id` values that this server accepts, identifying the wallet implementation (not individual instances,
     /// i.e., the `client_id` value of a wallet implementation will be constant across all wallets of that
@@ -28,6 +50,57 @@ pub struct Issuer {
     pub brp_server: BaseUrl,
 }
 
+#[serde_as]
+#[derive(Clone, Deserialize)]
+pub struct IssuerKey {
+    #[serde_as(as = "Base64")]
+    pub certificate: Vec<u8>,
+
+    #[serde(flatten)]
+    pub private_key: IssuerPrivateKey,
+
+    /// The moment from which this key is used to sign, which is immediately if not specified.
+    pub active_from: Option<DateTime<Utc>>,
+
+    /// The moment until which this key is used to sign, which is indefinitely if not specified. When rolling over to
+    /// a new key, this should be later than the `active_from` of the new key by at least the session expiration time.
+    pub active_until: Option<DateTime<Utc>>,
+}
+
+#[serde_as]
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssuerPrivateKey {
+    /// The identifier of the private key within the configured HSM.
+    HsmKeyIdentifier(String),
+    /// A DER-encoded private key, which should only be used for development and testing. This is refused in a
+    /// release build unless `allow_insecure_private_key` is set.
+    PrivateKey(#[serde_as(as = "Base64")] Vec<u8>),
+}
+
+#[serde_as]
+#[derive(Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Hsm {
+    Pkcs11 {
+        library_path: PathBuf,
+        user_pin: String,
+        // Should be at least 2, as one session is reserved for batches.
+        max_sessions: u8,
+
+        #[serde(rename = "max_session_lifetime_in_sec")]
+        #[serde_as(as = "DurationSeconds")]
+        max_session_lifetime: Duration,
+    },
+    /// Keys in an encrypted local key file, for development and testing without a PKCS#11 library.
+    Software {
+        key_file: PathBuf,
+        passphrase: String,
+        #[serde(default)]
+        allow_production: bool,
+    },
+}
+
 #[derive(Clone, Deserialize)]
 pub struct Digid {
     pub issuer_url: BaseUrl,
@@ -37,11 +110,107 @@ pub struct Digid {
 }
 
 impl Issuer {
-    pub fn certificates(&self) -> IndexMap<String, Certificate> {
-        self.private_keys
+    /// The certificates of the issuer keys per doctype, along with their active periods.
+    pub fn certificates(&self) -> AttributeCertificates {
+        let certificates = self
+            .keys
+            .iter()
+            .map(|(doctype, keys)| {
+                let schedule = keys
+                    .iter()
+                    .map(|key| (key.active_period(), Certificate::from(&key.certificate)))
+                    .collect();
+
+                (doctype.clone(), Schedule::new(schedule))
+            })
+            .collect::<IndexMap<_, _>>();
+
+        AttributeCertificates::new(certificates)
+    }
+
+    /// Load the issuer keys, checking that each private key matches its certificate.
+    pub async fn key_ring(&self) -> Result<IssuerKeyRing, IssuerKeysError> {
+        let hsm = self.hsm.as_ref().map(Hsm::configured_hsm).transpose()?;
+
+        let key_pairs = self
+            .keys
             .iter()
-            .map(|(doctype, privkey)| (doctype.clone(), privkey.certificate.clone().into()))
-            .collect()
+            .map(|(doctype, keys)| {
+                let schedule = keys
+                    .iter()
+                    .map(|key| {
+                        let private_key = match &key.private_key {
+                            IssuerPrivateKey::HsmKeyIdentifier(identifier) => {
+                                let hsm = hsm
+                                    .clone()
+                                    .ok_or_else(|| IssuerKeysError::MissingHsm(doctype.clone()))?;
+                                IssuerSigningKey::Hsm(HsmEcdsaKey::new(identifier.clone(), hsm))
+                            }
+                            IssuerPrivateKey::PrivateKey(der) => {
+                                if !cfg!(debug_assertions) && !self.allow_insecure_private_key {
+                                    return Err(IssuerKeysError::InsecurePrivateKeyNotAllowed(doctype.clone()));
+                                }
+                                IssuerSigningKey::Software(SigningKey::from_pkcs8_der(der)?)
+                            }
+                        };
+                        let key_pair = KeyPair::new(private_key, Certificate::from(&key.certificate));
+
+                        Ok((key.active_period(), key_pair))
+                    })
+                    .collect::<Result<_, IssuerKeysError>>()?;
+
+                Ok((doctype.clone(), Schedule::new(schedule)))
+            })
+            .collect::<Result<_, IssuerKeysError>>()?;
+
+        IssuerKeyRing::try_new(key_pairs).await
+    }
+}
+
+impl IssuerKey {
+    fn active_period(&self) -> ActivePeriod {
+        ActivePeriod {
+            from: self.active_from,
+            until: self.active_until,
+        }
+    }
+}
+
+impl Hsm {
+    /// Create the configured HSM. Contrary to the Wallet Provider, the issuer keys are never generated on startup, as
+    /// a certificate needs to be issued for each of them.
+    pub fn configured_hsm(&self) -> Result<ConfiguredHsm, HsmError> {
+        let wrapping_key = VersionedKey::from(String::from(HSM_WRAPPING_KEY_IDENTIFIER));
+
+        let hsm = match self {
+            Hsm::Pkcs11 {
+                library_path,
+                user_pin,
+                max_sessions,
+                max_session_lifetime,
+            } => ConfiguredHsm::Pkcs11(Pkcs11Hsm::new(
+                library_path.clone(),
+                user_pin.clone(),
+                *max_sessions,
+                *max_session_lifetime,
+                // Batch sessions are not used by the issuer, so reserve as few sessions as possible for them.
+                1,
+                Duration::ZERO,
+                wrapping_key,
+            )?),
+            Hsm::Software {
+                key_file,
+                passphrase,
+                allow_production,
+            } => ConfiguredHsm::Software(SoftwareHsm::open(
+                key_file.clone(),
+                passphrase,
+                wrapping_key,
+                *allow_production,
+            )?),
+        };
+
+        Ok(hsm)
     }
 }
 
@@ -58,3 +227,48 @@ impl TryFrom<&Issuer> for BrpPidAttributeService {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use config::{Config, File, FileFormat};
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_issuer_keys() {
+        let toml = r#"
+            [[keys."com.example.pid"]]
+            certificate = "AQID"
+            private_key = "BAUG"
+            active_until = "2024-02-01T00:00:00Z"
+
+            [[keys."com.example.pid"]]
+            certificate = "BwgJ"
+            hsm_key_identifier = "pid_issuer_key"
+            active_from = "2024-01-01T00:00:00Z"
+        "#;
+
+        let keys = Config::builder()
+            .add_source(File::from_str(toml, FileFormat::Toml))
+            .build()
+            .unwrap()
+            .get::<HashMap<String, Vec<IssuerKey>>>("keys")
+            .unwrap()
+            .remove("com.example.pid")
+            .unwrap();
+
+        assert_eq!(keys.len(), 2);
+        assert!(matches!(&keys[0].private_key, IssuerPrivateKey::PrivateKey(der) if der == &[4, 5, 6]));
+        assert_eq!(
+            keys[0].active_period(),
+            ActivePeriod {
+                from: None,
+                until: Some("2024-02-01T00:00:00Z".parse().unwrap()),
+            }
+        );
+        assert!(
+            matches!(&keys[1].private_key, IssuerPrivateKey::HsmKeyIdentifier(identifier) if identifier == "pid_issuer_key")
+        );
+        assert_eq!(keys[1].certificate, vec![7, 8, 9]);
+    }
+}
@@ -154,7 +154,9 @@ impl Settings {
             )?;
 
         #[cfg(feature = "disclosure")]
-        let config_builder = config_builder.set_default("universal_link_base_url", DEFAULT_UNIVERSAL_LINK_BASE)?;
+        let config_builder = config_builder
+            .set_default("universal_link_base_url", DEFAULT_UNIVERSAL_LINK_BASE)?
+            .set_default("verifier.crl_refresh_minutes", 60)?;
 
         #[cfg(feature = "issuance")]
         let config_builder = config_builder
@@ -176,7 +178,8 @@ impl Settings {
         #[cfg(feature = "disclosure")]
         let environment_parser = environment_parser
             .list_separator(",")
-            .with_list_parse_key("verifier.trust_anchors");
+            .with_list_parse_key("verifier.trust_anchors")
+            .with_list_parse_key("verifier.crls");
 
         let environment_parser = environment_parser.try_parsing(true);
 
@@ -1,26 +1,33 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use axum::{
     extract::{Path, Query, State},
     routing::{get, post},
     Form, Json, Router,
 };
-use http::{header, HeaderMap, HeaderValue, Method, Uri};
+use http::{header, HeaderMap, HeaderValue, Method, StatusCode, Uri};
 use serde::{Deserialize, Serialize};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
+use url::Url;
 
 use nl_wallet_mdoc::{
+    identifiers::AttributeIdentifier,
     server_state::{SessionStore, SessionToken},
+    utils::crl::CrlCache,
     verifier::{DisclosedAttributes, ItemsRequests, ReturnUrlTemplate, SessionType},
 };
 use openid4vc::{
+    deletion_request::{DeletionRequest, DeletionRequestError, DeletionRequestForm},
     disclosure_session::APPLICATION_OAUTH_AUTHZ_REQ_JWT,
     openid4vp::{VpResponse, WalletRequest},
-    verifier::{DisclosureData, StatusResponse, Verifier, WalletAuthResponse},
-    GetRequestErrorCode, PostAuthResponseErrorCode, VerificationErrorCode,
+    verifier::{DisclosureData, StatusResponse, UseCase, Verifier, WalletAuthResponse},
+    DeletionRequestErrorCode, GetRequestErrorCode, PostAuthResponseErrorCode, VerificationErrorCode,
+};
+use wallet_common::{
+    config::wallet_config::BaseUrl, generator::TimeGenerator, http_error::HttpJsonError,
+    reqwest::default_reqwest_client_builder,
 };
-use wallet_common::{config::wallet_config::BaseUrl, generator::TimeGenerator, http_error::HttpJsonError};
 
 use crate::{
     errors::ErrorResponse,
@@ -31,6 +38,9 @@ struct ApplicationState<S> {
     verifier: Verifier<S>,
     public_url: BaseUrl,
     universal_link_base_url: BaseUrl,
+    /// Maps the `client_id` of each use case to the Relying Party endpoint to forward deletion requests to, if any.
+    deletion_request_urls: HashMap<String, Option<Url>>,
+    http_client: reqwest::Client,
 }
 
 fn create_application_state<S>(
@@ -41,6 +51,18 @@ fn create_application_state<S>(
 where
     S: SessionStore<DisclosureData> + Send + Sync + 'static,
 {
+    let deletion_request_urls = verifier
+        .usecases
+        .as_ref()
+        .values()
+        .map(|use_case| {
+            let client_id = UseCase::try_from(use_case)?.client_id;
+            Ok((client_id, use_case.deletion_request_url.clone()))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    let crls = CrlCache::load(verifier.crls.clone(), verifier.crl_refresh_interval())?;
+
     let application_state = ApplicationState {
         verifier: Verifier::new(
             verifier.usecases.try_into()?,
@@ -50,10 +72,13 @@ where
                 .into_iter()
                 .map(|ta| ta.owned_trust_anchor)
                 .collect::<Vec<_>>(),
+            crls,
             (&verifier.ephemeral_id_secret).into(),
         ),
         public_url: urls.public_url,
         universal_link_base_url: urls.universal_link_base_url,
+        deletion_request_urls,
+        http_client: default_reqwest_client_builder().build()?,
     };
     Ok(application_state)
 }
@@ -68,6 +93,7 @@ where
     // Note that since `retrieve_request()` uses the `Form` extractor, it requires the
     // `Content-Type: application/x-www-form-urlencoded` header to be set on POST requests (but not GET requests).
     let wallet_router = Router::new()
+        .route("/deletion_request", post(deletion_request::<S>))
         .route("/:session_token/request_uri", get(retrieve_request::<S>))
         .route("/:session_token/request_uri", post(retrieve_request::<S>))
         .route("/:session_token/response_uri", post(post_response::<S>))
@@ -227,3 +253,55 @@ where
 
     Ok(Json(disclosed_attributes))
 }
+
+/// The request that the wallet server sends to the Relying Party backend after receiving a valid deletion request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelyingPartyDeletionRequest {
+    pub session_token: SessionToken,
+    pub attributes: Vec<AttributeIdentifier>,
+}
+
+async fn deletion_request<S>(
+    State(state): State<Arc<ApplicationState<S>>>,
+    Form(form): Form<DeletionRequestForm>,
+) -> Result<StatusCode, ErrorResponse<DeletionRequestErrorCode>>
+where
+    S: SessionStore<DisclosureData>,
+{
+    info!("process deletion request");
+
+    // This checks that the deletion request contains the deletion token the wallet received after disclosure,
+    // which proves that it took part in the session before anything is sent to the Relying Party.
+    let payload = state
+        .verifier
+        .verify_deletion_request(&DeletionRequest(form.deletion_request))
+        .map_err(ErrorResponse::new)?;
+
+    let url = state
+        .deletion_request_urls
+        .get(&payload.client_id)
+        .ok_or_else(|| DeletionRequestError::UnknownRelyingParty(payload.client_id.clone()))
+        .and_then(|url| {
+            url.as_ref()
+                .ok_or_else(|| DeletionRequestError::DeletionNotSupported(payload.client_id.clone()))
+        })
+        .map_err(ErrorResponse::new)?;
+
+    let request = RelyingPartyDeletionRequest {
+        session_token: payload.session_reference.into(),
+        attributes: payload.attributes,
+    };
+
+    state
+        .http_client
+        .post(url.clone())
+        .json(&request)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| ErrorResponse::new(DeletionRequestError::Forwarding(error)))?;
+
+    info!("deletion request forwarded to relying party successfully");
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -10,18 +10,21 @@ use assert_matches::assert_matches;
 use chrono::{DateTime, Utc};
 use http::StatusCode;
 use indexmap::IndexMap;
+use p256::{ecdsa::SigningKey, elliptic_curve::rand_core::OsRng};
 use parking_lot::RwLock;
 use reqwest::Response;
 use rstest::rstest;
 use tokio::time;
 
 use nl_wallet_mdoc::{
+    identifiers::AttributeIdentifier,
     server_state::{MemorySessionStore, SessionStore, SessionStoreTimeouts, CLEANUP_INTERVAL_SECONDS},
     utils::mock_time::MockTimeGenerator,
     verifier::{ReturnUrlTemplate, SessionType},
     ItemsRequest,
 };
 use openid4vc::{
+    deletion_request::{DeletionRequest, DeletionRequestForm},
     verifier::{DisclosureData, StatusResponse, VerifierUrlParameters},
     ErrorResponse,
 };
@@ -40,6 +43,7 @@ fn start_disclosure_request() -> StartDisclosureRequest {
         items_requests: vec![ItemsRequest {
             doc_type: "com.example.pid".to_owned(),
             request_info: None,
+            device_name_spaces: None,
             name_spaces: IndexMap::from([(
                 "com.example.pid".to_owned(),
                 IndexMap::from_iter(
@@ -503,3 +507,48 @@ async fn test_disclosure_expired_postgres() {
 
     test_disclosure_expired(settings, session_store, mock_time.as_ref(), true).await;
 }
+
+#[tokio::test]
+async fn test_deletion_request_error() {
+    let settings = wallet_server_settings();
+    let deletion_request_url = settings.urls.public_url.join("disclosure/deletion_request");
+    start_wallet_server(settings, MemorySessionStore::default()).await;
+
+    let client = default_reqwest_client_builder().build().unwrap();
+
+    // check that a malformed deletion request returns a 400
+    let response = client
+        .post(deletion_request_url.clone())
+        .form(&[("deletion_request", "not_a_jwt")])
+        .send()
+        .await
+        .unwrap();
+
+    test_error_response(response, StatusCode::BAD_REQUEST, "invalid_request").await;
+
+    // check that a deletion request with a deletion token that was not issued for the session returns a 403
+    let deletion_request = DeletionRequest::new(
+        &SigningKey::random(&mut OsRng),
+        "example.com".to_string(),
+        "session_token".to_string(),
+        "00".repeat(32),
+        vec![AttributeIdentifier {
+            doc_type: "com.example.pid".to_string(),
+            namespace: "com.example.pid".to_string(),
+            attribute: "given_name".to_string(),
+        }],
+    )
+    .await
+    .unwrap();
+
+    let response = client
+        .post(deletion_request_url)
+        .form(&DeletionRequestForm {
+            deletion_request: deletion_request.0,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    test_error_response(response, StatusCode::FORBIDDEN, "invalid_deletion_token").await;
+}